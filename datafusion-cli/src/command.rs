@@ -27,7 +27,8 @@ use datafusion::arrow::array::{ArrayRef, StringArray};
 use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::instant::Instant;
-use datafusion::common::{exec_datafusion_err, exec_err};
+use datafusion::common::{TableReference, exec_datafusion_err, exec_err};
+use datafusion::datasource::file_format::csv::CsvDialect;
 use datafusion::error::Result;
 use std::fs::File;
 use std::io::BufReader;
@@ -80,7 +81,8 @@ impl Command {
             }
             Self::DescribeTableStmt(name) => {
                 exec_and_print(ctx, print_options, format!("SHOW COLUMNS FROM {name}"))
-                    .await
+                    .await?;
+                print_csv_dialect(ctx, name, print_options).await
             }
             Self::Include(filename) => {
                 if let Some(filename) = filename {
@@ -204,6 +206,31 @@ fn all_commands_info() -> RecordBatch {
     .expect("This should not fail")
 }
 
+/// Prints the CSV dialect detected when the schema of table `name` was
+/// inferred, if any
+async fn print_csv_dialect(
+    ctx: &dyn CliSessionContext,
+    name: &str,
+    print_options: &PrintOptions,
+) -> Result<()> {
+    if print_options.quiet {
+        return Ok(());
+    }
+    let table_ref = TableReference::from(name);
+    let state = ctx.session_state();
+    let Some(table) = state
+        .schema_for_ref(table_ref.clone())?
+        .table(table_ref.table())
+        .await?
+    else {
+        return Ok(());
+    };
+    if let Some(dialect) = CsvDialect::from_metadata(table.schema().metadata()) {
+        println!("Detected CSV dialect: {dialect}");
+    }
+    Ok(())
+}
+
 impl FromStr for Command {
     type Err = ();
 
@@ -409,10 +409,12 @@ fn config_file_type_from_str(ext: &str) -> Option<ConfigFileType> {
 
 async fn create_plan(
     ctx: &dyn CliSessionContext,
-    statement: Statement,
+    mut statement: Statement,
     resolve_region: bool,
 ) -> Result<LogicalPlan, DataFusionError> {
-    let mut plan = ctx.session_state().statement_to_plan(statement).await?;
+    let state = ctx.session_state();
+    Box::pin(state.resolve_dynamic_pivots(&mut statement)).await?;
+    let mut plan = state.statement_to_plan(statement).await?;
 
     // Note that cmd is a mutable reference so that create_external_table function can remove all
     // datafusion-cli specific options before passing through to datafusion. Otherwise, datafusion
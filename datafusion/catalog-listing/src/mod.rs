@@ -28,6 +28,8 @@
 mod config;
 pub mod helpers;
 mod options;
+mod schema_evolution;
+pub mod statistics_sidecar;
 mod table;
 
 pub use config::{ListingTableConfig, SchemaSource};
@@ -17,6 +17,7 @@
 
 use arrow::datatypes::{DataType, SchemaRef};
 use datafusion_catalog::Session;
+use datafusion_common::file_options::bucketing::BucketSpec;
 use datafusion_common::plan_err;
 use datafusion_datasource::ListingTableUrl;
 use datafusion_datasource::file_format::FileFormat;
@@ -61,6 +62,9 @@ pub struct ListingOptions {
     ///       multiple equivalent orderings, the outer `Vec` will have a
     ///       single element.
     pub file_sort_order: Vec<Vec<SortExpr>>,
+    /// Optional hash bucketing of the files of the table.
+    /// See [Self::with_bucket_spec] for details
+    pub bucket_spec: Option<BucketSpec>,
 }
 
 impl ListingOptions {
@@ -78,6 +82,7 @@ impl ListingOptions {
             collect_stat: false,
             target_partitions: 1,
             file_sort_order: vec![],
+            bucket_spec: None,
         }
     }
 
@@ -260,6 +265,31 @@ impl ListingOptions {
         self
     }
 
+    /// Set the hash bucketing of the files on [`ListingOptions`] and returns self.
+    ///
+    /// Inserts write one file per bucket, and scans read each bucket as one
+    /// partition when every listed file is named by [`BucketSpec::file_name`],
+    /// letting joins of tables bucketed the same way and aggregates on the
+    /// bucket columns skip repartitioning.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use datafusion_catalog_listing::ListingOptions;
+    /// # use datafusion_common::file_options::bucketing::BucketSpec;
+    /// # use datafusion_datasource_parquet::file_format::ParquetFormat;
+    ///
+    /// let bucket_spec = BucketSpec::try_new(vec!["a".to_string()], 8).unwrap();
+    ///
+    /// let listing_options = ListingOptions::new(Arc::new(ParquetFormat::default()))
+    ///     .with_bucket_spec(Some(bucket_spec.clone()));
+    ///
+    /// assert_eq!(listing_options.bucket_spec, Some(bucket_spec));
+    /// ```
+    pub fn with_bucket_spec(mut self, bucket_spec: Option<BucketSpec>) -> Self {
+        self.bucket_spec = bucket_spec;
+        self
+    }
+
     /// Infer the schema of the files at the given path on the provided object store.
     ///
     /// If the table_path contains one or more files (i.e. it is a directory /
@@ -0,0 +1,601 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Reading files written before an `ALTER TABLE` on a [`ListingTable`]
+//!
+//! The changes are stored in the directory of the table, in an Arrow IPC
+//! file named [`SCHEMA_EVOLUTION_FILE`], so that they are not lost when the
+//! table is created again over the same files.
+//!
+//! [`ListingTable`]: crate::ListingTable
+
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, ListBuilder, StringArray, StringBuilder};
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::{
+    ColumnStatistics, Result, ScalarValue, Statistics, exec_datafusion_err,
+};
+use datafusion_physical_expr::expressions::{Column, Literal};
+use datafusion_physical_expr_adapter::{
+    DefaultPhysicalExprAdapterFactory, PhysicalExprAdapter, PhysicalExprAdapterFactory,
+};
+use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
+use object_store::path::Path;
+use object_store::{ObjectStore, ObjectStoreExt, PutPayload};
+
+/// Name of the file storing the [`SchemaEvolution`] of a [`ListingTable`] in
+/// its directory
+///
+/// [`ListingTable`]: crate::ListingTable
+pub const SCHEMA_EVOLUTION_FILE: &str = "_schema_evolution.arrow";
+
+/// Returns true if `path` is the file storing a [`SchemaEvolution`]
+pub(crate) fn is_schema_evolution_file(path: &Path) -> bool {
+    path.filename() == Some(SCHEMA_EVOLUTION_FILE)
+}
+
+/// Schema changes applied to a [`ListingTable`] after (some of) its files
+/// were written.
+///
+/// Each column is stored in files under a physical name, which is its own
+/// name unless that name was used by another column before: once a column
+/// is dropped or renamed, files written earlier still contain its data under
+/// the previous names, so these names are retired and never used for another
+/// column. A column that is added (or renamed) to a retired name is stored
+/// under a new physical name instead, e.g. `b#1`.
+///
+/// [`ListingTable`]: crate::ListingTable
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SchemaEvolution {
+    /// Current column name to the name it is stored under in files written
+    /// from now on, if different
+    physical_names: HashMap<String, String>,
+    /// Current column name to the names it was stored under in files written
+    /// before, most recent first
+    previous_names: HashMap<String, Vec<String>>,
+    /// Names under which files may contain data of another column, either
+    /// dropped or renamed since
+    retired_names: HashSet<String>,
+    /// Values for added columns that are missing from older files
+    column_defaults: HashMap<String, ScalarValue>,
+}
+
+impl SchemaEvolution {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.physical_names.is_empty()
+            && self.previous_names.is_empty()
+            && self.column_defaults.is_empty()
+    }
+
+    /// Returns the name `column` is stored under in files written from now on
+    pub(crate) fn physical_name<'a>(&'a self, column: &'a str) -> &'a str {
+        self.physical_names
+            .get(column)
+            .map(String::as_str)
+            .unwrap_or(column)
+    }
+
+    /// Returns a physical name for a new column named `name`, that no file
+    /// may contain data of another column under
+    fn unused_physical_name(&self, name: &str) -> Option<String> {
+        if !self.retired_names.contains(name) {
+            return None;
+        }
+        (1..)
+            .map(|generation| format!("{name}#{generation}"))
+            .find(|candidate| !self.retired_names.contains(candidate))
+    }
+
+    /// Retire every name `column` was stored under
+    fn retire(&mut self, column: &str) {
+        let physical_name = self.physical_name(column).to_string();
+        self.retired_names.insert(physical_name);
+        if let Some(previous) = self.previous_names.get(column) {
+            self.retired_names.extend(previous.iter().cloned());
+        }
+    }
+
+    pub(crate) fn add_column(&mut self, name: &str, default: Option<ScalarValue>) {
+        match self.unused_physical_name(name) {
+            Some(physical_name) => {
+                // Reserve the new name so that it is not handed out twice
+                self.retired_names.insert(physical_name.clone());
+                self.physical_names.insert(name.to_string(), physical_name)
+            }
+            None => self.physical_names.remove(name),
+        };
+        self.previous_names.remove(name);
+        match default {
+            Some(default) => self.column_defaults.insert(name.to_string(), default),
+            None => self.column_defaults.remove(name),
+        };
+    }
+
+    pub(crate) fn drop_column(&mut self, name: &str) {
+        self.retire(name);
+        self.physical_names.remove(name);
+        self.previous_names.remove(name);
+        self.column_defaults.remove(name);
+    }
+
+    pub(crate) fn rename_column(&mut self, old_name: &str, new_name: &str) {
+        let physical_name = self.physical_name(old_name).to_string();
+        let mut previous = self.previous_names.remove(old_name).unwrap_or_default();
+        self.physical_names.remove(old_name);
+        let new_name_retired = self.retired_names.contains(new_name);
+        self.retired_names.insert(physical_name.clone());
+        if new_name_retired {
+            // Keep writing the column under its current physical name
+            self.physical_names
+                .insert(new_name.to_string(), physical_name);
+        } else {
+            previous.insert(0, physical_name);
+        }
+        if !previous.is_empty() {
+            self.previous_names.insert(new_name.to_string(), previous);
+        }
+        if let Some(default) = self.column_defaults.remove(old_name) {
+            self.column_defaults.insert(new_name.to_string(), default);
+        }
+    }
+
+    /// Returns the names `column` may be stored under in a file, the current
+    /// physical name first
+    fn stored_names<'a>(&'a self, column: &'a str) -> impl Iterator<Item = &'a str> {
+        std::iter::once(self.physical_name(column)).chain(
+            self.previous_names
+                .get(column)
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        )
+    }
+
+    /// Forget the statistics of evolved columns: they were collected for the
+    /// column with that name in the file, which is missing or holds the data
+    /// of another column
+    pub(crate) fn adapt_statistics(
+        &self,
+        file_schema: &Schema,
+        mut statistics: Statistics,
+    ) -> Statistics {
+        for (field, column_statistics) in file_schema
+            .fields()
+            .iter()
+            .zip(statistics.column_statistics.iter_mut())
+        {
+            let name = field.name();
+            if self.physical_names.contains_key(name)
+                || self.previous_names.contains_key(name)
+                || self.column_defaults.contains_key(name)
+            {
+                *column_statistics = ColumnStatistics::new_unknown();
+            }
+        }
+        statistics
+    }
+
+    /// Writes this evolution to the file at `path`, replacing it if it exists
+    pub(crate) async fn write(&self, store: &dyn ObjectStore, path: &Path) -> Result<()> {
+        let batch = self.encode()?;
+        let mut buffer = vec![];
+        let mut writer = FileWriter::try_new(&mut buffer, batch.schema_ref())?;
+        writer.write(&batch)?;
+        writer.finish()?;
+        drop(writer);
+        store.put(path, PutPayload::from(buffer)).await?;
+        Ok(())
+    }
+
+    /// Reads the evolution written to the file at `path`, if it exists
+    pub(crate) async fn read(
+        store: &dyn ObjectStore,
+        path: &Path,
+    ) -> Result<Option<Self>> {
+        let bytes = match store.get(path).await {
+            Ok(result) => result.bytes().await?,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let reader = FileReader::try_new(Cursor::new(bytes), None)?;
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        match batches.as_slice() {
+            [batch] => Self::decode(batch).map(Some),
+            _ => Err(exec_datafusion_err!(
+                "Invalid schema evolution file {path}: expected a single batch"
+            )),
+        }
+    }
+
+    /// Encodes this evolution as a single row, with a `retired_names` column
+    /// and a `physical_name.<column>`, `previous_names.<column>` or
+    /// `default.<column>` column for each entry of the other maps
+    fn encode(&self) -> Result<RecordBatch> {
+        let names_array = |names: &mut dyn Iterator<Item = &String>| -> ArrayRef {
+            let mut builder = ListBuilder::new(StringBuilder::new());
+            for name in names {
+                builder.values().append_value(name);
+            }
+            builder.append(true);
+            Arc::new(builder.finish())
+        };
+
+        let mut retired_names = self.retired_names.iter().collect::<Vec<_>>();
+        retired_names.sort();
+        let retired_names = names_array(&mut retired_names.into_iter());
+        let mut fields = vec![Field::new(
+            "retired_names",
+            retired_names.data_type().clone(),
+            false,
+        )];
+        let mut columns = vec![retired_names];
+
+        let mut physical_names = self.physical_names.iter().collect::<Vec<_>>();
+        physical_names.sort();
+        for (column, physical_name) in physical_names {
+            let array: ArrayRef =
+                Arc::new(StringArray::from(vec![physical_name.as_str()]));
+            fields.push(Field::new(
+                format!("physical_name.{column}"),
+                array.data_type().clone(),
+                false,
+            ));
+            columns.push(array);
+        }
+        let mut previous_names = self.previous_names.iter().collect::<Vec<_>>();
+        previous_names.sort();
+        for (column, names) in previous_names {
+            let array = names_array(&mut names.iter());
+            fields.push(Field::new(
+                format!("previous_names.{column}"),
+                array.data_type().clone(),
+                false,
+            ));
+            columns.push(array);
+        }
+        let mut column_defaults = self.column_defaults.iter().collect::<Vec<_>>();
+        column_defaults.sort_by_key(|(column, _)| *column);
+        for (column, default) in column_defaults {
+            let array = default.to_array()?;
+            fields.push(Field::new(
+                format!("default.{column}"),
+                array.data_type().clone(),
+                true,
+            ));
+            columns.push(array);
+        }
+
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    /// Decodes an evolution encoded by [`Self::encode`]
+    fn decode(batch: &RecordBatch) -> Result<Self> {
+        let names = |array: &ArrayRef| -> Vec<String> {
+            array
+                .as_list::<i32>()
+                .value(0)
+                .as_string::<i32>()
+                .iter()
+                .flatten()
+                .map(str::to_string)
+                .collect()
+        };
+
+        let mut evolution = Self::default();
+        for (field, array) in batch.schema().fields().iter().zip(batch.columns()) {
+            match field.name().split_once('.') {
+                None if field.name() == "retired_names" => {
+                    evolution.retired_names.extend(names(array));
+                }
+                Some(("physical_name", column)) => {
+                    let physical_name = array.as_string::<i32>().value(0);
+                    evolution
+                        .physical_names
+                        .insert(column.to_string(), physical_name.to_string());
+                }
+                Some(("previous_names", column)) => {
+                    evolution
+                        .previous_names
+                        .insert(column.to_string(), names(array));
+                }
+                Some(("default", column)) => {
+                    evolution.column_defaults.insert(
+                        column.to_string(),
+                        ScalarValue::try_from_array(array, 0)?,
+                    );
+                }
+                _ => {
+                    return Err(exec_datafusion_err!(
+                        "Invalid column {} in a schema evolution file",
+                        field.name()
+                    ));
+                }
+            }
+        }
+        Ok(evolution)
+    }
+
+    /// Wrap `inner` (or the default factory) so files are read with the
+    /// evolved table schema
+    pub(crate) fn adapter_factory(
+        &self,
+        inner: Option<Arc<dyn PhysicalExprAdapterFactory>>,
+    ) -> Arc<dyn PhysicalExprAdapterFactory> {
+        Arc::new(SchemaEvolutionAdapterFactory {
+            inner: inner.unwrap_or_else(|| Arc::new(DefaultPhysicalExprAdapterFactory)),
+            evolution: self.clone(),
+        })
+    }
+}
+
+/// [`PhysicalExprAdapterFactory`] that resolves renamed columns to the name
+/// stored in each file and fills added columns with their default value,
+/// delegating everything else to an inner factory.
+#[derive(Debug)]
+struct SchemaEvolutionAdapterFactory {
+    inner: Arc<dyn PhysicalExprAdapterFactory>,
+    evolution: SchemaEvolution,
+}
+
+impl PhysicalExprAdapterFactory for SchemaEvolutionAdapterFactory {
+    fn create(
+        &self,
+        logical_file_schema: SchemaRef,
+        physical_file_schema: SchemaRef,
+    ) -> Result<Arc<dyn PhysicalExprAdapter>> {
+        let mut fields = logical_file_schema.fields().to_vec();
+        let mut renames = HashMap::new();
+        let mut defaults = HashMap::new();
+        for (index, field) in logical_file_schema.fields().iter().enumerate() {
+            let name = field.name();
+            let stored_name = self
+                .evolution
+                .stored_names(name)
+                .find(|stored_name| physical_file_schema.index_of(stored_name).is_ok());
+            match stored_name {
+                Some(stored_name) if stored_name == name.as_str() => {}
+                Some(stored_name) => {
+                    fields[index] =
+                        Arc::new(field.as_ref().clone().with_name(stored_name));
+                    renames.insert(name.clone(), Column::new(stored_name, index));
+                }
+                None => {
+                    // The file may still contain a dropped or renamed column
+                    // with this name, so never let the inner adapter read it
+                    let default = match self.evolution.column_defaults.get(name) {
+                        Some(default) => default.clone(),
+                        None => ScalarValue::try_from(field.data_type())?,
+                    };
+                    defaults.insert(name.clone(), default);
+                }
+            }
+        }
+
+        // The inner adapter sees the logical schema using the names of this file
+        let logical_file_schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            logical_file_schema.metadata().clone(),
+        ));
+        let inner = self
+            .inner
+            .create(logical_file_schema, physical_file_schema)?;
+        Ok(Arc::new(SchemaEvolutionAdapter {
+            inner,
+            renames,
+            defaults,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct SchemaEvolutionAdapter {
+    inner: Arc<dyn PhysicalExprAdapter>,
+    /// Logical column name to the column as named in the file
+    renames: HashMap<String, Column>,
+    /// Logical column name to the value to use as it is missing in the file
+    defaults: HashMap<String, ScalarValue>,
+}
+
+impl PhysicalExprAdapter for SchemaEvolutionAdapter {
+    fn rewrite(&self, expr: Arc<dyn PhysicalExpr>) -> Result<Arc<dyn PhysicalExpr>> {
+        let expr = expr
+            .transform(|expr| {
+                let Some(column) = expr.downcast_ref::<Column>() else {
+                    return Ok(Transformed::no(expr));
+                };
+                if let Some(renamed) = self.renames.get(column.name()) {
+                    return Ok(Transformed::yes(Arc::new(renamed.clone()) as _));
+                }
+                if let Some(default) = self.defaults.get(column.name()) {
+                    return Ok(Transformed::yes(
+                        Arc::new(Literal::new(default.clone())) as _
+                    ));
+                }
+                Ok(Transformed::no(expr))
+            })
+            .data()?;
+        self.inner.rewrite(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Int32Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field};
+    use datafusion_physical_expr::expressions::col;
+    use object_store::memory::InMemory;
+
+    fn evaluate(
+        evolution: &SchemaEvolution,
+        logical: &Schema,
+        batch: &RecordBatch,
+        column: &str,
+    ) -> Result<ArrayRef> {
+        let adapter = evolution
+            .adapter_factory(None)
+            .create(Arc::new(logical.clone()), batch.schema())?;
+        let expr = adapter.rewrite(col(column, logical)?)?;
+        expr.evaluate(batch)?.into_array(batch.num_rows())
+    }
+
+    #[test]
+    fn test_renamed_and_added_columns() -> Result<()> {
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            file_schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["x", "y"])),
+            ],
+        )?;
+
+        // ALTER TABLE RENAME COLUMN a TO c, then c TO d, then ADD COLUMN e DEFAULT 7
+        let mut evolution = SchemaEvolution::default();
+        evolution.rename_column("a", "c");
+        evolution.rename_column("c", "d");
+        evolution.add_column("e", Some(ScalarValue::Int32(Some(7))));
+        let logical = Schema::new(vec![
+            Field::new("d", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("e", DataType::Int32, true),
+        ]);
+
+        let d = evaluate(&evolution, &logical, &batch, "d")?;
+        assert_eq!(d.as_ref(), &Int32Array::from(vec![1, 2]));
+        let e = evaluate(&evolution, &logical, &batch, "e")?;
+        assert_eq!(e.as_ref(), &Int32Array::from(vec![7, 7]));
+
+        // dropping and re-adding `d` must not resurrect the old data
+        evolution.drop_column("d");
+        evolution.add_column("d", None);
+        let d = evaluate(&evolution, &logical, &batch, "d")?;
+        assert_eq!(d.as_ref(), &Int32Array::from(vec![None, None]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_shadowed_columns() -> Result<()> {
+        let old_file = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b", DataType::Utf8, true),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["x", "y"])),
+            ],
+        )?;
+
+        // ALTER TABLE DROP COLUMN b, then ADD COLUMN b INT
+        let mut evolution = SchemaEvolution::default();
+        evolution.drop_column("b");
+        evolution.add_column("b", None);
+        assert_eq!(evolution.physical_name("b"), "b#1");
+        let logical = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let b = evaluate(&evolution, &logical, &old_file, "b")?;
+        assert_eq!(b.as_ref(), &Int32Array::from(vec![None, None]));
+
+        // files written since store the new column as `b#1`
+        let new_file = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b#1", DataType::Int32, true),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![3])),
+                Arc::new(Int32Array::from(vec![30])),
+            ],
+        )?;
+        let b = evaluate(&evolution, &logical, &new_file, "b")?;
+        assert_eq!(b.as_ref(), &Int32Array::from(vec![30]));
+
+        // ALTER TABLE RENAME COLUMN a TO c, then ADD COLUMN a INT
+        evolution.rename_column("a", "c");
+        evolution.add_column("a", None);
+        assert_eq!(evolution.physical_name("c"), "c");
+        assert_eq!(evolution.physical_name("a"), "a#1");
+        let logical = Schema::new(vec![
+            Field::new("c", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+            Field::new("a", DataType::Int32, true),
+        ]);
+        let c = evaluate(&evolution, &logical, &old_file, "c")?;
+        assert_eq!(c.as_ref(), &Int32Array::from(vec![1, 2]));
+        let a = evaluate(&evolution, &logical, &old_file, "a")?;
+        assert_eq!(a.as_ref(), &Int32Array::from(vec![None, None]));
+
+        // renaming a column to a retired name keeps its physical name
+        evolution.rename_column("c", "d");
+        evolution.rename_column("b", "c");
+        assert_eq!(evolution.physical_name("c"), "b#1");
+        let logical = Schema::new(vec![
+            Field::new("d", DataType::Int32, true),
+            Field::new("c", DataType::Int32, true),
+            Field::new("a", DataType::Int32, true),
+        ]);
+        let c = evaluate(&evolution, &logical, &new_file, "c")?;
+        assert_eq!(c.as_ref(), &Int32Array::from(vec![30]));
+        let d = evaluate(&evolution, &logical, &new_file, "d")?;
+        assert_eq!(d.as_ref(), &Int32Array::from(vec![3]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_and_read() -> Result<()> {
+        let store = InMemory::new();
+        let path = Path::from("table").join(SCHEMA_EVOLUTION_FILE);
+        assert!(is_schema_evolution_file(&path));
+        assert_eq!(SchemaEvolution::read(&store, &path).await?, None);
+
+        // ALTER TABLE RENAME COLUMN a TO c, ADD COLUMN e DEFAULT 7, then
+        // DROP COLUMN b and ADD COLUMN b
+        let mut evolution = SchemaEvolution::default();
+        evolution.rename_column("a", "c");
+        evolution.add_column("e", Some(ScalarValue::Int32(Some(7))));
+        evolution.drop_column("b");
+        evolution.add_column("b", Some(ScalarValue::Utf8(None)));
+        evolution.write(&store, &path).await?;
+        assert_eq!(
+            SchemaEvolution::read(&store, &path).await?,
+            Some(evolution.clone())
+        );
+
+        // Writing again replaces the file
+        evolution.drop_column("c");
+        evolution.write(&store, &path).await?;
+        assert_eq!(SchemaEvolution::read(&store, &path).await?, Some(evolution));
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Sidecar files storing the statistics of the data files of a
+//! [`ListingTable`](crate::ListingTable)
+//!
+//! The statistics of a data file, as computed by `ANALYZE TABLE`, can be
+//! stored next to it, in an Arrow IPC file named after the data file with a
+//! [`STATISTICS_SIDECAR_SUFFIX`]. It holds a single row with the size and
+//! modification time of the data file, which must match for the statistics
+//! to be used, and the statistics of the file and of each of its columns.
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Int64Array, UInt64Array, new_null_array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::stats::Precision;
+use datafusion_common::{ColumnStatistics, Result, ScalarValue, Statistics};
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore, ObjectStoreExt, PutPayload};
+
+/// Suffix appended to the path of a data file to get the path of its
+/// statistics sidecar file
+pub const STATISTICS_SIDECAR_SUFFIX: &str = ".statistics.arrow";
+
+/// Returns the path of the statistics sidecar file of the data file `path`
+pub fn statistics_sidecar_path(path: &Path) -> Path {
+    Path::from(format!("{path}{STATISTICS_SIDECAR_SUFFIX}"))
+}
+
+/// Returns true if `path` is a statistics sidecar file
+pub fn is_statistics_sidecar(path: &Path) -> bool {
+    path.as_ref().ends_with(STATISTICS_SIDECAR_SUFFIX)
+}
+
+/// Writes the `statistics` of the data file `meta`, whose columns are those
+/// of `file_schema`, to its sidecar file.
+///
+/// Only the exact number of rows, null counts, minimums and maximums are
+/// stored, along with the (inexact) number of distinct values and total
+/// byte size.
+pub async fn write_statistics_sidecar(
+    store: &dyn ObjectStore,
+    meta: &ObjectMeta,
+    file_schema: &Schema,
+    statistics: &Statistics,
+) -> Result<()> {
+    let batch = encode(meta, file_schema, statistics)?;
+    let mut buffer = vec![];
+    let mut writer = FileWriter::try_new(&mut buffer, batch.schema_ref())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    drop(writer);
+    store
+        .put(
+            &statistics_sidecar_path(&meta.location),
+            PutPayload::from(buffer),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Reads the statistics of the data file `meta`, whose columns are those of
+/// `file_schema`, from its sidecar file.
+///
+/// Returns `None` if there is no sidecar file, or if it was written for
+/// another version of the data file or for other columns.
+pub async fn read_statistics_sidecar(
+    store: &dyn ObjectStore,
+    meta: &ObjectMeta,
+    file_schema: &Schema,
+) -> Result<Option<Statistics>> {
+    let bytes = match store.get(&statistics_sidecar_path(&meta.location)).await {
+        Ok(result) => result.bytes().await?,
+        Err(object_store::Error::NotFound { .. }) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let reader = FileReader::try_new(Cursor::new(bytes), None)?;
+    let batches = reader.collect::<Result<Vec<_>, _>>()?;
+    match batches.as_slice() {
+        [batch] => decode(batch, meta, file_schema),
+        _ => Ok(None),
+    }
+}
+
+/// Returns the schema of the sidecar file of a data file with `file_schema`
+fn sidecar_schema(file_schema: &Schema) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("size", DataType::UInt64, false),
+        Field::new("last_modified", DataType::Int64, false),
+        Field::new("num_rows", DataType::UInt64, true),
+        Field::new("total_byte_size", DataType::UInt64, true),
+    ];
+    for field in file_schema.fields() {
+        let name = field.name();
+        fields.extend([
+            Field::new(format!("{name}.null_count"), DataType::UInt64, true),
+            Field::new(format!("{name}.min"), field.data_type().clone(), true),
+            Field::new(format!("{name}.max"), field.data_type().clone(), true),
+            Field::new(format!("{name}.distinct_count"), DataType::UInt64, true),
+        ]);
+    }
+    Arc::new(Schema::new(fields))
+}
+
+fn encode(
+    meta: &ObjectMeta,
+    file_schema: &Schema,
+    statistics: &Statistics,
+) -> Result<RecordBatch> {
+    let count = |value: Option<&usize>| -> ArrayRef {
+        Arc::new(UInt64Array::from(vec![value.map(|v| *v as u64)]))
+    };
+    let mut columns = vec![
+        count(Some(&(meta.size as usize))),
+        Arc::new(Int64Array::from(vec![
+            meta.last_modified.timestamp_micros(),
+        ])) as ArrayRef,
+        count(exact(&statistics.num_rows)),
+        count(statistics.total_byte_size.get_value()),
+    ];
+    for (field, column) in file_schema
+        .fields()
+        .iter()
+        .zip(&statistics.column_statistics)
+    {
+        let value = |value: Option<&ScalarValue>| -> Result<ArrayRef> {
+            let Some(value) = value else {
+                return Ok(new_null_array(field.data_type(), 1));
+            };
+            let array = value.to_array()?;
+            if array.data_type() == field.data_type() {
+                Ok(array)
+            } else {
+                Ok(cast(&array, field.data_type())?)
+            }
+        };
+        columns.extend([
+            count(exact(&column.null_count)),
+            value(exact(&column.min_value))?,
+            value(exact(&column.max_value))?,
+            count(column.distinct_count.get_value()),
+        ]);
+    }
+    Ok(RecordBatch::try_new(sidecar_schema(file_schema), columns)?)
+}
+
+fn decode(
+    batch: &RecordBatch,
+    meta: &ObjectMeta,
+    file_schema: &Schema,
+) -> Result<Option<Statistics>> {
+    if batch.num_rows() != 1 || batch.schema() != sidecar_schema(file_schema) {
+        return Ok(None);
+    }
+    let value = |idx: usize| ScalarValue::try_from_array(batch.column(idx), 0);
+    let count = |idx: usize| -> Result<Option<usize>> {
+        Ok(match value(idx)? {
+            ScalarValue::UInt64(value) => value.map(|v| v as usize),
+            _ => None,
+        })
+    };
+    let last_modified = match value(1)? {
+        ScalarValue::Int64(value) => value,
+        _ => None,
+    };
+    if count(0)? != Some(meta.size as usize)
+        || last_modified != Some(meta.last_modified.timestamp_micros())
+    {
+        return Ok(None);
+    }
+
+    let column_statistics = (0..file_schema.fields().len())
+        .map(|idx| {
+            let base = 4 + idx * 4;
+            let non_null = |value: ScalarValue| {
+                (!value.is_null()).then_some(value).map(Precision::Exact)
+            };
+            Ok(ColumnStatistics {
+                null_count: count(base)?.map(Precision::Exact).unwrap_or_default(),
+                min_value: non_null(value(base + 1)?).unwrap_or_default(),
+                max_value: non_null(value(base + 2)?).unwrap_or_default(),
+                distinct_count: count(base + 3)?
+                    .map(Precision::Inexact)
+                    .unwrap_or_default(),
+                ..ColumnStatistics::new_unknown()
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(Statistics {
+        num_rows: count(2)?.map(Precision::Exact).unwrap_or_default(),
+        total_byte_size: count(3)?.map(Precision::Inexact).unwrap_or_default(),
+        column_statistics,
+    }))
+}
+
+fn exact<T: std::fmt::Debug + Clone + PartialEq + Eq + PartialOrd>(
+    precision: &Precision<T>,
+) -> Option<&T> {
+    match precision {
+        Precision::Exact(value) => Some(value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{TimeZone, Utc};
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn round_trip() -> Result<()> {
+        let store = InMemory::new();
+        let meta = ObjectMeta {
+            location: Path::from("t/data.csv"),
+            last_modified: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            size: 1024,
+            e_tag: None,
+            version: None,
+        };
+        let file_schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        let statistics = Statistics {
+            num_rows: Precision::Exact(3),
+            total_byte_size: Precision::Inexact(1024),
+            column_statistics: vec![
+                ColumnStatistics {
+                    null_count: Precision::Exact(1),
+                    min_value: Precision::Exact(ScalarValue::Int32(Some(1))),
+                    max_value: Precision::Exact(ScalarValue::Int32(Some(3))),
+                    distinct_count: Precision::Inexact(2),
+                    ..ColumnStatistics::new_unknown()
+                },
+                // Inexact values are not stored
+                ColumnStatistics {
+                    null_count: Precision::Inexact(0),
+                    min_value: Precision::Inexact(ScalarValue::from("x")),
+                    ..ColumnStatistics::new_unknown()
+                },
+            ],
+        };
+        write_statistics_sidecar(&store, &meta, &file_schema, &statistics).await?;
+
+        let read = read_statistics_sidecar(&store, &meta, &file_schema)
+            .await?
+            .unwrap();
+        assert_eq!(read.num_rows, Precision::Exact(3));
+        assert_eq!(read.total_byte_size, Precision::Inexact(1024));
+        assert_eq!(read.column_statistics[0], statistics.column_statistics[0]);
+        assert_eq!(read.column_statistics[1], ColumnStatistics::new_unknown());
+
+        // The statistics of other versions of the file are not used
+        let modified = ObjectMeta {
+            size: 2048,
+            ..meta.clone()
+        };
+        assert!(
+            read_statistics_sidecar(&store, &modified, &file_schema)
+                .await?
+                .is_none()
+        );
+        let other_schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        assert!(
+            read_statistics_sidecar(&store, &meta, &other_schema)
+                .await?
+                .is_none()
+        );
+        let other_file = ObjectMeta {
+            location: Path::from("t/other.csv"),
+            ..meta
+        };
+        assert!(
+            read_statistics_sidecar(&store, &other_file, &file_schema)
+                .await?
+                .is_none()
+        );
+        assert!(is_statistics_sidecar(&statistics_sidecar_path(
+            &other_file.location
+        )));
+        Ok(())
+    }
+}
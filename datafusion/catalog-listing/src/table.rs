@@ -17,13 +17,22 @@
 
 use crate::config::SchemaSource;
 use crate::helpers::{expr_applicable_for_cols, pruned_partition_list};
+use crate::schema_evolution::{
+    SCHEMA_EVOLUTION_FILE, SchemaEvolution, is_schema_evolution_file,
+};
+use crate::statistics_sidecar::{is_statistics_sidecar, read_statistics_sidecar};
 use crate::{ListingOptions, ListingTableConfig};
 use arrow::datatypes::{Field, Schema, SchemaBuilder, SchemaRef};
 use async_trait::async_trait;
-use datafusion_catalog::{ScanArgs, ScanResult, Session, TableProvider};
-use datafusion_common::stats::Precision;
+use datafusion_catalog::{
+    ScanArgs, ScanResult, Session, TableProvider, evaluate_column_default,
+};
+use datafusion_common::file_options::bucketing::BucketSpec;
+use datafusion_common::stats::{NdvFallback, Precision};
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
 use datafusion_common::{
-    Constraints, SchemaExt, Statistics, internal_datafusion_err, plan_err, project_schema,
+    ColumnStatistics, Constraints, SchemaExt, Statistics, internal_datafusion_err,
+    internal_err, plan_err, project_schema,
 };
 use datafusion_datasource::file::FileSource;
 use datafusion_datasource::file_groups::FileGroup;
@@ -35,17 +44,23 @@ use datafusion_datasource::{
     ListingTableUrl, PartitionedFile, TableSchemaBuilder, compute_all_files_statistics,
 };
 use datafusion_execution::cache::TableScopedPath;
-use datafusion_execution::cache::cache_manager::FileStatisticsCache;
+use datafusion_execution::cache::cache_manager::{FileStatisticsCache, ListFilesCache};
 use datafusion_expr::dml::InsertOp;
 use datafusion_expr::execution_props::ExecutionProps;
-use datafusion_expr::{Expr, TableProviderFilterPushDown, TableType};
+use datafusion_expr::{
+    AlterTableOperation, Expr, TableProviderFilterPushDown, TableType,
+};
 use datafusion_physical_expr::create_lex_ordering;
+use datafusion_physical_expr::expressions::Column as PhysicalColumn;
 use datafusion_physical_expr_adapter::PhysicalExprAdapterFactory;
-use datafusion_physical_expr_common::sort_expr::LexOrdering;
+use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
+use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
 use datafusion_physical_plan::ExecutionPlan;
 use datafusion_physical_plan::empty::EmptyExec;
+use datafusion_physical_plan::projection::ProjectionExec;
 use futures::{Stream, StreamExt, TryStreamExt, future, stream};
 use object_store::ObjectStore;
+use object_store::path::Path;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -58,6 +73,9 @@ pub struct ListFilesResult {
     pub statistics: Statistics,
     /// Whether files are grouped by partition values (enables Hash partitioning).
     pub grouped_by_partition: bool,
+    /// Whether files are grouped by hash bucket, one group per bucket
+    /// (enables Hash partitioning on the bucket columns).
+    pub grouped_by_bucket: bool,
 }
 
 /// Built in [`TableProvider`] that reads data from one or more files as a single table.
@@ -193,6 +211,11 @@ pub struct ListingTable {
     column_defaults: HashMap<String, Expr>,
     /// Optional [`PhysicalExprAdapterFactory`] for creating physical expression adapters
     expr_adapter_factory: Option<Arc<dyn PhysicalExprAdapterFactory>>,
+    /// Columns added or renamed by `ALTER TABLE` after files were written
+    schema_evolution: SchemaEvolution,
+    /// Cache for the listed files, whose cached statistics are returned by
+    /// [`TableProvider::statistics`]
+    list_files_cache: Option<Arc<dyn ListFilesCache>>,
 }
 
 impl ListingTable {
@@ -211,17 +234,7 @@ impl ListingTable {
             .options
             .ok_or_else(|| internal_datafusion_err!("No ListingOptions provided"))?;
 
-        // Add the partition columns to the file schema
-        let mut builder = SchemaBuilder::from(file_schema.as_ref().to_owned());
-        for (part_col_name, part_col_type) in &options.table_partition_cols {
-            builder.push(Field::new(part_col_name, part_col_type.clone(), false));
-        }
-
-        let table_schema = Arc::new(
-            builder
-                .finish()
-                .with_metadata(file_schema.metadata().clone()),
-        );
+        let table_schema = Self::table_schema(&file_schema, &options);
 
         let table = Self {
             table_paths: config.table_paths,
@@ -234,6 +247,8 @@ impl ListingTable {
             constraints: Constraints::default(),
             column_defaults: HashMap::new(),
             expr_adapter_factory: config.expr_adapter_factory,
+            schema_evolution: SchemaEvolution::default(),
+            list_files_cache: None,
         };
 
         Ok(table)
@@ -264,12 +279,65 @@ impl ListingTable {
         self
     }
 
+    /// Set the [`ListFilesCache`] of the runtime the table is listed with.
+    ///
+    /// The files it lists for this table are used to look up the statistics
+    /// returned by [`TableProvider::statistics`].
+    pub fn with_list_files_cache(
+        mut self,
+        cache: Option<Arc<dyn ListFilesCache>>,
+    ) -> Self {
+        self.list_files_cache = cache;
+        self
+    }
+
     /// Specify the SQL definition for this table, if any
     pub fn with_definition(mut self, definition: Option<String>) -> Self {
         self.definition = definition;
         self
     }
 
+    /// Reads the changes made by `ALTER TABLE` to the columns of this table,
+    /// which are stored in its directory, so that the files written before
+    /// are read as they were altered.
+    ///
+    /// The schema of this table must be the schema after these changes. See
+    /// [`Self::is_altered`] to check whether any changes were read.
+    pub async fn with_stored_schema_evolution(
+        mut self,
+        state: &dyn Session,
+    ) -> datafusion_common::Result<Self> {
+        let Some((table_path, path)) = self.schema_evolution_path() else {
+            return Ok(self);
+        };
+        let store = state.runtime_env().object_store(table_path)?;
+        if let Some(evolution) = SchemaEvolution::read(store.as_ref(), &path).await? {
+            self.schema_evolution = evolution;
+        }
+        Ok(self)
+    }
+
+    /// Returns true if files of this table must be read with the changes
+    /// made by `ALTER TABLE` to its columns, such as renamed columns
+    pub fn is_altered(&self) -> bool {
+        self.schema_evolution != SchemaEvolution::default()
+    }
+
+    /// Returns the path of the file storing the [`SchemaEvolution`] of this
+    /// table, in the directory of its single location, along with that
+    /// location
+    fn schema_evolution_path(&self) -> Option<(&ListingTableUrl, Path)> {
+        match self.table_paths.as_slice() {
+            [table_path]
+                if table_path.is_collection() || table_path.get_glob().is_some() =>
+            {
+                let path = table_path.prefix().clone().join(SCHEMA_EVOLUTION_FILE);
+                Some((table_path, path))
+            }
+            _ => None,
+        }
+    }
+
     /// Get paths ref
     pub fn table_paths(&self) -> &Vec<ListingTableUrl> {
         &self.table_paths
@@ -319,6 +387,109 @@ impl ListingTable {
         None
     }
 
+    /// Add the partition columns to the file schema
+    fn table_schema(file_schema: &SchemaRef, options: &ListingOptions) -> SchemaRef {
+        let mut builder = SchemaBuilder::from(file_schema.as_ref().to_owned());
+        for (part_col_name, part_col_type) in &options.table_partition_cols {
+            builder.push(Field::new(part_col_name, part_col_type.clone(), false));
+        }
+
+        Arc::new(
+            builder
+                .finish()
+                .with_metadata(file_schema.metadata().clone()),
+        )
+    }
+
+    /// Returns the [`PhysicalExprAdapterFactory`] used to read the files,
+    /// accounting for columns added or renamed by `ALTER TABLE`
+    fn expr_adapter_factory(&self) -> Option<Arc<dyn PhysicalExprAdapterFactory>> {
+        if self.schema_evolution.is_empty() {
+            self.expr_adapter_factory.clone()
+        } else {
+            Some(
+                self.schema_evolution
+                    .adapter_factory(self.expr_adapter_factory.clone()),
+            )
+        }
+    }
+
+    /// Renames the columns of `input`, which has the table schema, to the
+    /// names they are stored under in new files, see [`SchemaEvolution`].
+    ///
+    /// Returns the renamed input and its schema.
+    fn rename_to_physical_names(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> datafusion_common::Result<(Arc<dyn ExecutionPlan>, SchemaRef)> {
+        let table_schema = self.schema();
+        let physical_names = table_schema
+            .fields()
+            .iter()
+            .map(|field| self.schema_evolution.physical_name(field.name()))
+            .collect::<Vec<_>>();
+        if table_schema
+            .fields()
+            .iter()
+            .zip(&physical_names)
+            .all(|(field, name)| field.name().as_str() == *name)
+        {
+            return Ok((input, table_schema));
+        }
+
+        let input_schema = input.schema();
+        let exprs = input_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .zip(&physical_names)
+            .map(|((index, field), name)| {
+                let column = Arc::new(PhysicalColumn::new(field.name(), index));
+                (column as Arc<dyn PhysicalExpr>, name.to_string())
+            })
+            .collect::<Vec<_>>();
+        let input = Arc::new(ProjectionExec::try_new(exprs, input)?);
+        let fields = table_schema
+            .fields()
+            .iter()
+            .zip(&physical_names)
+            .map(|(field, name)| field.as_ref().clone().with_name(*name))
+            .collect::<Vec<_>>();
+        let output_schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            table_schema.metadata().clone(),
+        ));
+        Ok((input, output_schema))
+    }
+
+    /// Renames the columns of `ordering` to the names they are stored under
+    /// in new files
+    fn ordering_with_physical_names(
+        &self,
+        ordering: LexOrdering,
+    ) -> datafusion_common::Result<LexOrdering> {
+        let sort_exprs = ordering
+            .into_iter()
+            .map(|sort_expr| {
+                let expr = Arc::clone(&sort_expr.expr)
+                    .transform(|expr| {
+                        let Some(column) = expr.downcast_ref::<PhysicalColumn>() else {
+                            return Ok(Transformed::no(expr));
+                        };
+                        let name = self.schema_evolution.physical_name(column.name());
+                        Ok(Transformed::yes(Arc::new(PhysicalColumn::new(
+                            name,
+                            column.index(),
+                        )) as _))
+                    })
+                    .data()?;
+                Ok(PhysicalSortExpr::new(expr, sort_expr.options))
+            })
+            .collect::<datafusion_common::Result<Vec<_>>>()?;
+        LexOrdering::new(sort_exprs)
+            .ok_or_else(|| internal_datafusion_err!("Empty ordering after renaming"))
+    }
+
     /// Creates a file source for this table
     fn create_file_source(&self) -> Arc<dyn FileSource> {
         let table_schema = TableSchemaBuilder::from(&self.file_schema)
@@ -458,6 +629,65 @@ impl TableProvider for ListingTable {
         TableType::Base
     }
 
+    /// Returns the statistics of the files of this table cached in the
+    /// [`ListFilesCache`], as collected by previous scans or by
+    /// `ANALYZE TABLE` in the [`FileStatisticsCache`].
+    ///
+    /// They are inexact, as the files may have changed since they were
+    /// listed, and `None` unless the files of every location of the table,
+    /// which must be directories, and the statistics of each of these files
+    /// are cached. Writes to the table drop its files from the cache.
+    fn statistics(&self) -> Option<Statistics> {
+        let statistics_cache = self.collected_statistics.as_ref()?;
+        let list_files_cache = self.list_files_cache.as_ref()?;
+        let mut file_statistics = vec![];
+        for table_path in &self.table_paths {
+            if !table_path.is_collection() {
+                return None;
+            }
+            let table = table_path.get_table_ref().clone();
+            let listed = list_files_cache.get(&TableScopedPath {
+                table: table.clone(),
+                path: table_path.prefix().clone(),
+            })?;
+            // Subdirectories are ignored as with the default listing options
+            let files = listed.iter().filter(|meta| {
+                let location = &meta.location;
+                location.as_ref().ends_with(&self.options.file_extension)
+                    && table_path.contains(location, true)
+                    && !is_statistics_sidecar(location)
+                    && !is_schema_evolution_file(location)
+            });
+            for meta in files {
+                let path = TableScopedPath {
+                    table: table.clone(),
+                    path: meta.location.clone(),
+                };
+                let cached = statistics_cache
+                    .get(&path)
+                    .filter(|cached| cached.is_valid_for(meta))?;
+                file_statistics.push(cached.statistics);
+            }
+        }
+        if file_statistics.is_empty() {
+            return None;
+        }
+
+        let mut statistics = Statistics::try_merge_iter_with_ndv_fallback(
+            file_statistics.iter().map(|statistics| statistics.as_ref()),
+            &self.file_schema,
+            NdvFallback::Max,
+        )
+        .ok()?;
+        statistics.column_statistics.extend(
+            self.options
+                .table_partition_cols
+                .iter()
+                .map(|_| ColumnStatistics::new_unknown()),
+        );
+        Some(statistics.to_inexact())
+    }
+
     async fn scan(
         &self,
         state: &dyn Session,
@@ -508,7 +738,8 @@ impl TableProvider for ListingTable {
         let ListFilesResult {
             file_groups: mut partitioned_file_lists,
             statistics,
-            grouped_by_partition: partitioned_by_file_group,
+            grouped_by_partition,
+            grouped_by_bucket,
         } = self
             .list_files_for_scan(state, &partition_filters, statistic_file_limit)
             .await?;
@@ -523,10 +754,12 @@ impl TableProvider for ListingTable {
             state.execution_props(),
             &partitioned_file_lists,
         )?;
-        match state
+        // Regrouping the files of a bucketed scan would break its partitioning
+        match (state
             .config_options()
             .execution
             .split_file_groups_by_statistics
+            && !grouped_by_bucket)
             .then(|| {
                 output_ordering.first().map(|output_ordering| {
                     FileScanConfig::split_groups_by_statistics_with_target_partitions(
@@ -561,6 +794,10 @@ impl TableProvider for ListingTable {
         };
 
         let file_source = self.create_file_source();
+        let bucket_columns = match &self.options.bucket_spec {
+            Some(bucket_spec) if grouped_by_bucket => bucket_spec.columns.clone(),
+            _ => vec![],
+        };
 
         // create the execution plan
         let plan = self
@@ -575,8 +812,11 @@ impl TableProvider for ListingTable {
                     .with_projection_indices(projection)?
                     .with_limit(limit)
                     .with_output_ordering(output_ordering)
-                    .with_expr_adapter(self.expr_adapter_factory.clone())
-                    .with_partitioned_by_file_group(partitioned_by_file_group)
+                    .with_expr_adapter(self.expr_adapter_factory())
+                    .with_partitioned_by_file_group(
+                        grouped_by_partition || grouped_by_bucket,
+                    )
+                    .with_bucket_columns(bucket_columns)
                     .build(),
             )
             .await?;
@@ -656,24 +896,33 @@ impl TableProvider for ListingTable {
             let _ = lfc.remove(&key);
         }
 
+        // Columns are written under the names they are read back with
+        let (input, output_schema) = self.rename_to_physical_names(input)?;
+
         // Sink related option, apart from format
         let config = FileSinkConfig {
             original_url: String::default(),
             object_store_url: self.table_paths()[0].object_store(),
             table_paths: self.table_paths().clone(),
             file_group,
-            output_schema: self.schema(),
+            output_schema,
             table_partition_cols: self.options.table_partition_cols.clone(),
             insert_op,
             keep_partition_by_columns,
             file_extension: self.options().format.get_ext(),
             file_output_mode: FileOutputMode::Automatic,
+            bucket_spec: self.options.bucket_spec.clone(),
         };
 
         // For writes, we only use user-specified ordering (no file groups to derive from)
         let orderings = self.try_create_output_ordering(state.execution_props(), &[])?;
         // It is sufficient to pass only one of the equivalent orderings:
-        let order_requirements = orderings.into_iter().next().map(Into::into);
+        let order_requirements = orderings
+            .into_iter()
+            .next()
+            .map(|ordering| self.ordering_with_physical_names(ordering))
+            .transpose()?
+            .map(Into::into);
 
         self.options()
             .format
@@ -684,6 +933,140 @@ impl TableProvider for ListingTable {
     fn get_column_default(&self, column: &str) -> Option<&Expr> {
         self.column_defaults.get(column)
     }
+
+    /// Evolves the schema without rewriting any files: existing files are
+    /// read with the new schema through the table's [`PhysicalExprAdapterFactory`].
+    ///
+    /// Added columns are read as their default value (or `NULL`) from files
+    /// that do not contain them, and renamed columns are resolved to the
+    /// name used when each file was written. A column added under the name
+    /// of a dropped or renamed column is written to new files under another
+    /// name, e.g. `b#1`, so that the data of the old column is never read.
+    ///
+    /// These changes are stored in the directory of the table, see
+    /// [`ListingTable::with_stored_schema_evolution`]. Operations that
+    /// need them fail for tables that are not a single directory.
+    async fn alter_schema(
+        &self,
+        state: &dyn Session,
+        operation: &AlterTableOperation,
+    ) -> datafusion_common::Result<Arc<dyn TableProvider>> {
+        let is_partition_column = |name: &str| {
+            self.options
+                .table_partition_cols
+                .iter()
+                .any(|(col, _)| col == name)
+        };
+        let mut table = self.clone();
+        let mut fields = self.file_schema.fields().to_vec();
+        operation.apply_to_sort_order(&mut table.options.file_sort_order)?;
+        // partition columns follow the file columns in the table schema
+        table.constraints =
+            operation.apply_to_constraints(&self.table_schema, &self.constraints)?;
+
+        match operation {
+            AlterTableOperation::AddColumn {
+                field,
+                default,
+                if_not_exists,
+            } => {
+                if self.table_schema.field_with_name(field.name()).is_ok() {
+                    if *if_not_exists {
+                        return Ok(Arc::new(self.clone()));
+                    }
+                    return plan_err!(
+                        "ALTER TABLE failed: column '{}' already exists",
+                        field.name()
+                    );
+                }
+                let value = evaluate_column_default(state, field, default.as_ref())?;
+                let default_value = match default {
+                    Some(default) => {
+                        table
+                            .column_defaults
+                            .insert(field.name().clone(), default.clone());
+                        Some(value)
+                    }
+                    None => None,
+                };
+                fields.push(Arc::clone(field));
+                table
+                    .schema_evolution
+                    .add_column(field.name(), default_value);
+            }
+            AlterTableOperation::DropColumn { name, if_exists } => {
+                if is_partition_column(name) {
+                    return plan_err!(
+                        "ALTER TABLE failed: cannot drop partition column '{name}'"
+                    );
+                }
+                let Ok(index) = self.file_schema.index_of(name) else {
+                    if *if_exists {
+                        return Ok(Arc::new(self.clone()));
+                    }
+                    return plan_err!(
+                        "ALTER TABLE failed: column '{name}' does not exist"
+                    );
+                };
+                if fields.len() == 1 {
+                    return plan_err!(
+                        "ALTER TABLE failed: cannot drop the only column '{name}'"
+                    );
+                }
+                fields.remove(index);
+                table.column_defaults.remove(name);
+                table.schema_evolution.drop_column(name);
+            }
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                if is_partition_column(old_name) {
+                    return plan_err!(
+                        "ALTER TABLE failed: cannot rename partition column '{old_name}'"
+                    );
+                }
+                let Ok(index) = self.file_schema.index_of(old_name) else {
+                    return plan_err!(
+                        "ALTER TABLE failed: column '{old_name}' does not exist"
+                    );
+                };
+                if self.table_schema.field_with_name(new_name).is_ok() {
+                    return plan_err!(
+                        "ALTER TABLE failed: column '{new_name}' already exists"
+                    );
+                }
+                fields[index] =
+                    Arc::new(fields[index].as_ref().clone().with_name(new_name));
+                if let Some(default) = table.column_defaults.remove(old_name) {
+                    table.column_defaults.insert(new_name.clone(), default);
+                }
+                table.schema_evolution.rename_column(old_name, new_name);
+            }
+            AlterTableOperation::RenameTable { .. } => {
+                return internal_err!(
+                    "RenameTable must be handled by the SchemaProvider"
+                );
+            }
+        }
+
+        if table.schema_evolution != self.schema_evolution {
+            let Some((table_path, path)) = self.schema_evolution_path() else {
+                return plan_err!(
+                    "ALTER TABLE failed: the changes to the columns of a table must be stored in its directory, \
+                     but the table is not a single directory"
+                );
+            };
+            let store = state.runtime_env().object_store(table_path)?;
+            table.schema_evolution.write(store.as_ref(), &path).await?;
+        }
+
+        table.file_schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            self.file_schema.metadata().clone(),
+        ));
+        table.table_schema = Self::table_schema(&table.file_schema, &table.options);
+        // The file schema is now explicitly specified
+        table.schema_source = SchemaSource::Specified;
+        Ok(Arc::new(table))
+    }
 }
 
 impl ListingTable {
@@ -703,6 +1086,7 @@ impl ListingTable {
                 file_groups: vec![],
                 statistics: Statistics::new_unknown(&self.file_schema),
                 grouped_by_partition: false,
+                grouped_by_bucket: false,
             });
         };
         // list files (with partitions)
@@ -719,7 +1103,15 @@ impl ListingTable {
         .await?;
         let meta_fetch_concurrency =
             ctx.config_options().execution.meta_fetch_concurrency;
-        let file_list = stream::iter(file_list).flatten_unordered(meta_fetch_concurrency);
+        let file_list = stream::iter(file_list)
+            .flatten_unordered(meta_fetch_concurrency)
+            .try_filter(|part_file| {
+                let location = &part_file.object_meta.location;
+                future::ready(
+                    !is_statistics_sidecar(location)
+                        && !is_schema_evolution_file(location),
+                )
+            });
         // collect the statistics and ordering if required by the config
         let files = file_list
             .map(|part_file| async {
@@ -747,9 +1139,22 @@ impl ListingTable {
         // hash repartitioning for aggregates and joins on partition columns.
         let threshold = ctx.config_options().optimizer.preserve_file_partitions;
 
-        let (file_groups, grouped_by_partition) = if threshold > 0
-            && !self.options.table_partition_cols.is_empty()
+        // Files of bucketed tables are grouped by bucket instead, as long as
+        // every file was written to a single bucket
+        let bucket_groups = self
+            .options
+            .bucket_spec
+            .as_ref()
+            .map(|bucket_spec| group_files_by_bucket(&file_group, bucket_spec))
+            .transpose()?
+            .flatten();
+        let grouped_by_bucket = bucket_groups.is_some();
+
+        let (file_groups, grouped_by_partition) = if let Some(bucket_groups) =
+            bucket_groups
         {
+            (bucket_groups, false)
+        } else if threshold > 0 && !self.options.table_partition_cols.is_empty() {
             let grouped =
                 file_group.group_by_partition_values(self.options.target_partitions);
             if grouped.len() >= threshold {
@@ -784,6 +1189,7 @@ impl ListingTable {
             file_groups,
             statistics: stats,
             grouped_by_partition,
+            grouped_by_bucket,
         })
     }
 
@@ -822,7 +1228,20 @@ impl ListingTable {
             .infer_stats_and_ordering(ctx, store, Arc::clone(&self.file_schema), meta)
             .await?;
 
-        let statistics = Arc::new(file_meta.statistics);
+        // Statistics computed by `ANALYZE TABLE` take precedence over those
+        // found in the file, which are often unknown
+        let sidecar_statistics =
+            if ctx.config_options().execution.statistics_sidecar_files {
+                read_statistics_sidecar(store.as_ref(), meta, &self.file_schema).await?
+            } else {
+                None
+            };
+        let statistics = Arc::new(match sidecar_statistics {
+            Some(statistics) => statistics,
+            None => self
+                .schema_evolution
+                .adapt_statistics(&self.file_schema, file_meta.statistics),
+        });
 
         // Store in cache
         if let Some(cache) = &self.collected_statistics {
@@ -840,6 +1259,30 @@ impl ListingTable {
     }
 }
 
+/// Groups files into one group per bucket of `bucket_spec`, or returns `None`
+/// if there are no files or any file name has no bucket.
+///
+/// Fails if a file was written with another bucket hash than `bucket_spec`.
+fn group_files_by_bucket(
+    files: &FileGroup,
+    bucket_spec: &BucketSpec,
+) -> datafusion_common::Result<Option<Vec<FileGroup>>> {
+    if files.is_empty() {
+        return Ok(None);
+    }
+    let mut groups = vec![FileGroup::default(); bucket_spec.num_buckets];
+    for file in files.iter() {
+        let Some(file_name) = file.path().filename() else {
+            return Ok(None);
+        };
+        let Some(bucket) = bucket_spec.bucket_of_file(file_name)? else {
+            return Ok(None);
+        };
+        groups[bucket].push(file.clone());
+    }
+    Ok(Some(groups))
+}
+
 /// Processes a stream of partitioned files and returns a `FileGroup` containing the files.
 ///
 /// This function collects files from the provided stream until either:
@@ -20,7 +20,7 @@
 //! [Information Schema]: https://en.wikipedia.org/wiki/Information_schema
 
 use crate::streaming::StreamingTable;
-use crate::{CatalogProviderList, SchemaProvider, TableProvider};
+use crate::{CatalogProviderList, SchemaProvider, TableFunction, TableProvider};
 use arrow::array::builder::{BooleanBuilder, UInt8Builder};
 use arrow::{
     array::{StringBuilder, UInt64Builder},
@@ -55,6 +55,10 @@ pub(crate) const SCHEMATA: &str = "schemata";
 pub(crate) const ROUTINES: &str = "routines";
 pub(crate) const PARAMETERS: &str = "parameters";
 
+/// The `data_type` reported for table functions, which return a table rather
+/// than a value
+const TABLE_RETURN_TYPE: &str = "Table";
+
 /// All information schema tables
 pub const INFORMATION_SCHEMA_TABLES: &[&str] = &[
     TABLES,
@@ -81,14 +85,31 @@ impl InformationSchemaProvider {
     /// Creates a new [`InformationSchemaProvider`] for the provided `catalog_list`
     pub fn new(catalog_list: Arc<dyn CatalogProviderList>) -> Self {
         Self {
-            config: InformationSchemaConfig { catalog_list },
+            config: InformationSchemaConfig {
+                catalog_list,
+                table_functions: HashMap::new(),
+            },
         }
     }
+
+    /// Lists the provided table functions in `information_schema.routines`
+    /// and `information_schema.parameters`.
+    ///
+    /// Only table functions that report a
+    /// [`signature`](crate::TableFunctionImpl::signature) are listed.
+    pub fn with_table_functions(
+        mut self,
+        table_functions: HashMap<String, Arc<TableFunction>>,
+    ) -> Self {
+        self.config.table_functions = table_functions;
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
 struct InformationSchemaConfig {
     catalog_list: Arc<dyn CatalogProviderList>,
+    table_functions: HashMap<String, Arc<TableFunction>>,
 }
 
 impl InformationSchemaConfig {
@@ -301,6 +322,24 @@ impl InformationSchemaConfig {
                 )
             }
         }
+
+        for (name, udtf) in &self.table_functions {
+            let udtf = udtf.function();
+            let Some(signature) = udtf.signature() else {
+                continue;
+            };
+            builder.add_routine(
+                catalog_name,
+                schema_name,
+                name,
+                "FUNCTION",
+                Self::is_deterministic(signature),
+                Some(&TABLE_RETURN_TYPE),
+                "TABLE",
+                udtf.documentation().map(|d| d.description.to_string()),
+                udtf.documentation().map(|d| d.syntax_example.to_string()),
+            )
+        }
         Ok(())
     }
 
@@ -400,6 +439,25 @@ impl InformationSchemaConfig {
             }
         }
 
+        for (func_name, udtf) in &self.table_functions {
+            let udtf = udtf.function();
+            let Some(signature) = udtf.signature() else {
+                continue;
+            };
+            let args = udtf.documentation().and_then(|d| d.arguments.clone());
+            let combinations = get_udtf_arg_types(signature);
+            for (rid, arg_types) in combinations.into_iter().enumerate() {
+                add_parameters(
+                    func_name,
+                    args.as_ref(),
+                    arg_types,
+                    Some(TABLE_RETURN_TYPE.to_string()),
+                    Self::is_variadic(signature),
+                    rid as u8,
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -411,6 +469,23 @@ impl InformationSchemaConfig {
     }
 }
 
+/// get the argument types of a table function with the given signature
+fn get_udtf_arg_types(signature: &Signature) -> BTreeSet<Vec<String>> {
+    let arg_types = signature.type_signature.get_example_types();
+    if arg_types.is_empty() {
+        return BTreeSet::from([vec![]]);
+    }
+    arg_types
+        .into_iter()
+        .map(|arg_types| {
+            arg_types
+                .into_iter()
+                .map(|t| remove_native_type_prefix(&NativeType::from(t)))
+                .collect()
+        })
+        .collect()
+}
+
 /// get the arguments and return types of a UDF
 /// returns a tuple of (arg_types, return_type)
 fn get_udf_args_and_return_types(
@@ -1522,6 +1597,7 @@ mod tests {
     async fn make_tables_uses_table_type() {
         let config = InformationSchemaConfig {
             catalog_list: Arc::new(Fixture),
+            table_functions: HashMap::new(),
         };
         let mut builder = InformationSchemaTablesBuilder {
             catalog_names: StringBuilder::new(),
@@ -0,0 +1,153 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Materialized view data source which stores the result of a LogicalPlan.
+
+use std::sync::Arc;
+
+use crate::Session;
+use crate::{ScanArgs, ScanResult, TableProvider};
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+use datafusion_common::Statistics;
+use datafusion_common::error::Result;
+use datafusion_expr::{Expr, LogicalPlan, TableProviderFilterPushDown, TableType};
+use datafusion_physical_plan::ExecutionPlan;
+use parking_lot::RwLock;
+
+/// An implementation of `TableProvider` that scans the stored result of a
+/// logical plan.
+///
+/// Unlike a [`ViewTable`], the plan is not run when the view is scanned.
+/// Instead its result is computed once and kept in another `TableProvider`,
+/// such as a [`MemTable`], which is scanned in its place. The stored result
+/// is replaced with [`Self::replace_storage`] when the view is refreshed.
+///
+/// [`ViewTable`]: crate::view::ViewTable
+/// [`MemTable`]: crate::MemTable
+#[derive(Debug)]
+pub struct MaterializedView {
+    /// LogicalPlan the contents of the view are computed from
+    logical_plan: LogicalPlan,
+    /// Schema of the view, the schema of `logical_plan`
+    table_schema: SchemaRef,
+    /// SQL used to create the view, if available
+    definition: Option<String>,
+    /// Directory the contents are written to, if not kept in memory
+    location: Option<String>,
+    /// The stored result of `logical_plan`
+    storage: RwLock<Arc<dyn TableProvider>>,
+}
+
+impl MaterializedView {
+    /// Create a new materialized view of `logical_plan`, whose current
+    /// contents are stored in `storage`.
+    ///
+    /// Takes optionally the SQL text of the `CREATE` statement and the
+    /// directory `storage` reads its files from.
+    pub fn new(
+        logical_plan: LogicalPlan,
+        definition: Option<String>,
+        location: Option<String>,
+        storage: Arc<dyn TableProvider>,
+    ) -> Self {
+        let table_schema = Arc::clone(logical_plan.schema().inner());
+        Self {
+            logical_plan,
+            table_schema,
+            definition,
+            location,
+            storage: RwLock::new(storage),
+        }
+    }
+
+    /// Get definition ref
+    pub fn definition(&self) -> Option<&String> {
+        self.definition.as_ref()
+    }
+
+    /// Get logical_plan ref
+    pub fn logical_plan(&self) -> &LogicalPlan {
+        &self.logical_plan
+    }
+
+    /// The directory the contents of the view are written to, or `None`
+    /// if they are kept in memory
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// The `TableProvider` holding the current contents of the view
+    pub fn storage(&self) -> Arc<dyn TableProvider> {
+        Arc::clone(&self.storage.read())
+    }
+
+    /// Replace the contents of the view with `storage`, returning the
+    /// previous contents. Scans started before the call keep reading the
+    /// previous contents.
+    pub fn replace_storage(
+        &self,
+        storage: Arc<dyn TableProvider>,
+    ) -> Arc<dyn TableProvider> {
+        std::mem::replace(&mut *self.storage.write(), storage)
+    }
+}
+
+#[async_trait]
+impl TableProvider for MaterializedView {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.table_schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    fn get_table_definition(&self) -> Option<&str> {
+        self.definition.as_deref()
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        self.storage().supports_filters_pushdown(filters)
+    }
+
+    fn statistics(&self) -> Option<Statistics> {
+        self.storage().statistics()
+    }
+
+    async fn scan(
+        &self,
+        state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.storage().scan(state, projection, filters, limit).await
+    }
+
+    async fn scan_with_args<'a>(
+        &self,
+        state: &dyn Session,
+        args: ScanArgs<'a>,
+    ) -> Result<ScanResult> {
+        self.storage().scan_with_args(state, args).await
+    }
+}
@@ -19,39 +19,47 @@
 
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::ops::{Deref, DerefMut, Range};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use crate::TableProvider;
+use crate::transaction::{CommitClock, Timestamp, Transaction, TransactionParticipant};
+use crate::{TableProvider, evaluate_column_default};
 
 use arrow::array::{
-    Array, ArrayRef, BooleanArray, RecordBatch as ArrowRecordBatch, UInt64Array,
+    Array, ArrayRef, AsArray, BooleanArray, RecordBatch as ArrowRecordBatch, UInt64Array,
 };
 use arrow::compute::kernels::zip::zip;
-use arrow::compute::{and, filter_record_batch};
+use arrow::compute::{and, cast, filter_record_batch};
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use datafusion_common::error::Result;
-use datafusion_common::{Constraints, DFSchema, SchemaExt, not_impl_err, plan_err};
-use datafusion_datasource::memory::{MemSink, MemorySourceConfig};
-use datafusion_datasource::sink::DataSinkExec;
+use datafusion_common::{
+    Constraints, DFSchema, SchemaExt, exec_err, internal_datafusion_err, internal_err,
+    not_impl_err, plan_err,
+};
+use datafusion_datasource::memory::MemorySourceConfig;
+use datafusion_datasource::sink::{DataSink, DataSinkExec};
 use datafusion_datasource::source::DataSourceExec;
-use datafusion_expr::dml::InsertOp;
-use datafusion_expr::{Expr, SortExpr, TableType};
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_expr::dml::{InsertOp, MERGE_ACTION_COLUMN, MergeAction};
+use datafusion_expr::{AlterTableOperation, Expr, SortExpr, TableType};
 use datafusion_physical_expr::{
     LexOrdering, PhysicalExpr, create_physical_expr, create_physical_sort_exprs,
 };
 use datafusion_physical_plan::repartition::RepartitionExec;
 use datafusion_physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion_physical_plan::{
-    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties,
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties, collect,
     collect_partitioned,
 };
 use datafusion_session::Session;
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use log::debug;
 use parking_lot::Mutex;
-use tokio::sync::RwLock;
+use tokio::sync::{OwnedRwLockWriteGuard, RwLock, RwLockWriteGuard};
 
 // backward compatibility
 pub use datafusion_datasource::memory::PartitionData;
@@ -60,11 +68,25 @@ pub use datafusion_datasource::memory::PartitionData;
 /// data source that can be queried by DataFusion. This allows data to
 /// be pre-loaded into memory and then repeatedly queried without
 /// incurring additional file I/O overhead.
-#[derive(Debug)]
+///
+/// # Transactions
+///
+/// Statements that run in a [`Transaction`] read and write a private copy of
+/// the partitions, taken as of the snapshot of the transaction when it first
+/// accesses the table. The changes are written to `batches` when the
+/// transaction commits, and fail to commit if another change to the table was
+/// committed after the snapshot of the transaction. Previous versions of
+/// `batches` are kept while active transactions may still read them.
+///
+/// Changes to the schema of the table with `ALTER TABLE` are not
+/// transactional.
+#[derive(Debug, Clone)]
 pub struct MemTable {
     schema: SchemaRef,
     // batches used to be pub(crate), but it's needed to be public for the tests
     pub batches: Vec<PartitionData>,
+    /// The versions of `batches`, shared by the clones of this table
+    versions: Arc<Mutex<MemTableVersions>>,
     constraints: Constraints,
     column_defaults: HashMap<String, Expr>,
     /// Optional pre-known sort order(s). Must be `SortExpr`s.
@@ -100,6 +122,7 @@ impl MemTable {
                 .into_iter()
                 .map(|e| Arc::new(RwLock::new(e)))
                 .collect::<Vec<_>>(),
+            versions: Arc::default(),
             constraints: Constraints::default(),
             column_defaults: HashMap::new(),
             sort_order: Arc::new(Mutex::new(vec![])),
@@ -166,6 +189,305 @@ impl MemTable {
 
         MemTable::try_new(schema, data).map(|table| table.with_constraints(constraints))
     }
+
+    /// Returns the partitions read and written by the statements of `state`:
+    /// the partitions of its [`Transaction`] if it has one, otherwise the
+    /// committed partitions
+    async fn partitions(&self, state: &dyn Session) -> Result<MemPartitions> {
+        let clock = self
+            .versions
+            .lock()
+            .clock(&state.runtime_env().commit_clock);
+        let Some(txn) = Transaction::current(state) else {
+            return Ok(MemPartitions::Committed {
+                batches: self.batches.clone(),
+                versions: Arc::clone(&self.versions),
+                clock,
+            });
+        };
+        if !Arc::ptr_eq(&clock, txn.clock()) {
+            return exec_err!(
+                "Transaction {} cannot access a table whose commits are ordered by the clock of another runtime",
+                txn.id()
+            );
+        }
+        let key = Arc::as_ptr(&self.versions).addr();
+        let participant = match txn.participant(key) {
+            Some(participant) => participant,
+            None => {
+                let participant = MemTableTransaction::try_new(self, &txn).await?;
+                txn.enlist(key, participant).await?
+            }
+        };
+        Ok(MemPartitions::InTransaction(participant))
+    }
+}
+
+/// The versions of the partitions of a [`MemTable`], which let transactions
+/// read the partitions as of their snapshot
+#[derive(Debug, Default)]
+struct MemTableVersions {
+    /// The timestamp of the commit that created the current partitions
+    timestamp: Timestamp,
+    /// The previous versions of the partitions that may be read by active
+    /// transactions
+    history: Vec<MemTableVersion>,
+    /// The clock that orders the commits of the table, see [`Self::clock`]
+    clock: Option<Arc<CommitClock>>,
+}
+
+/// A previous version of the partitions of a [`MemTable`]
+#[derive(Debug)]
+struct MemTableVersion {
+    /// The snapshots that see this version
+    visible: Range<Timestamp>,
+    partitions: Vec<Vec<RecordBatch>>,
+}
+
+impl MemTableVersions {
+    /// Returns the clock that orders the commits of the table, which is the
+    /// commit clock of the runtime of the first session that accessed it
+    fn clock(&mut self, session_clock: &Arc<CommitClock>) -> Arc<CommitClock> {
+        Arc::clone(self.clock.get_or_insert_with(|| Arc::clone(session_clock)))
+    }
+
+    /// Records that the partitions `old` were replaced by a commit of
+    /// `clock` at `timestamp`, keeping them while active transactions may
+    /// read them
+    fn commit(
+        &mut self,
+        old: Vec<Vec<RecordBatch>>,
+        timestamp: Timestamp,
+        clock: &CommitClock,
+    ) {
+        self.history
+            .retain(|version| clock.has_active_snapshot(version.visible.clone()));
+        let visible = self.timestamp..timestamp;
+        if clock.has_active_snapshot(visible.clone()) {
+            self.history.push(MemTableVersion {
+                visible,
+                partitions: old,
+            });
+        }
+        self.timestamp = timestamp;
+    }
+
+    /// Returns the previous version of the partitions seen by `snapshot`
+    fn previous(&self, snapshot: Timestamp) -> Result<Vec<Vec<RecordBatch>>> {
+        self.history
+            .iter()
+            .find(|version| version.visible.contains(&snapshot))
+            .map(|version| version.partitions.clone())
+            .ok_or_else(|| {
+                internal_datafusion_err!("MemTable version at {snapshot} was dropped")
+            })
+    }
+}
+
+/// The partitions of a [`MemTable`] read and written by a statement, see
+/// [`MemTable::partitions`]
+#[derive(Debug, Clone)]
+enum MemPartitions {
+    /// The committed partitions, for statements that run outside of a
+    /// transaction
+    Committed {
+        batches: Vec<PartitionData>,
+        versions: Arc<Mutex<MemTableVersions>>,
+        clock: Arc<CommitClock>,
+    },
+    /// The partitions as seen by a transaction, with its changes
+    InTransaction(Arc<MemTableTransaction>),
+}
+
+impl MemPartitions {
+    fn batches(&self) -> &[PartitionData] {
+        match self {
+            Self::Committed { batches, .. } => batches,
+            Self::InTransaction(txn) => &txn.batches,
+        }
+    }
+
+    /// Returns the number of partitions
+    fn len(&self) -> usize {
+        self.batches().len()
+    }
+
+    /// Returns the content of the partitions
+    async fn read(&self) -> Vec<Vec<RecordBatch>> {
+        let mut partitions = Vec::with_capacity(self.len());
+        for partition in self.batches() {
+            partitions.push(partition.read().await.clone());
+        }
+        partitions
+    }
+
+    /// Returns the version of the partitions, which changes with every write.
+    ///
+    /// Outside of a transaction this is the timestamp of the latest commit of
+    /// the table, otherwise the number of writes of the transaction.
+    fn version(&self) -> u64 {
+        match self {
+            Self::Committed { versions, .. } => versions.lock().timestamp,
+            Self::InTransaction(txn) => txn.writes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Locks the partitions for writing, see [`MemPartitionsWriter`]
+    async fn write(&self) -> MemPartitionsWriter<'_> {
+        let mut guards = Vec::with_capacity(self.len());
+        for partition in self.batches() {
+            guards.push(partition.write().await);
+        }
+        let partitions = guards.iter().map(|guard| guard.to_vec()).collect();
+        MemPartitionsWriter {
+            target: self,
+            guards,
+            partitions,
+        }
+    }
+}
+
+/// Changes the content of [`MemPartitions`], which is locked for writing.
+///
+/// The changes are made on a copy of the content, which replaces the content
+/// of the partitions on [`Self::commit`]. Outside of a transaction, this
+/// commits a new version of the table.
+struct MemPartitionsWriter<'a> {
+    target: &'a MemPartitions,
+    guards: Vec<RwLockWriteGuard<'a, Vec<RecordBatch>>>,
+    partitions: Vec<Vec<RecordBatch>>,
+}
+
+impl MemPartitionsWriter<'_> {
+    /// Replaces the content of the partitions with the changed copy
+    fn commit(mut self) {
+        let old_partitions = self
+            .guards
+            .iter_mut()
+            .zip(self.partitions)
+            .map(|(guard, batches)| std::mem::replace(&mut **guard, batches))
+            .collect();
+        match self.target {
+            MemPartitions::Committed {
+                versions, clock, ..
+            } => {
+                let timestamp = clock.next_commit_timestamp();
+                versions.lock().commit(old_partitions, timestamp, clock);
+            }
+            MemPartitions::InTransaction(txn) => {
+                txn.writes.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl Deref for MemPartitionsWriter<'_> {
+    type Target = [Vec<RecordBatch>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.partitions
+    }
+}
+
+impl DerefMut for MemPartitionsWriter<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.partitions
+    }
+}
+
+/// The changes of a [`Transaction`] to a [`MemTable`]
+#[derive(Debug)]
+struct MemTableTransaction {
+    /// The committed partitions of the table
+    committed: Vec<PartitionData>,
+    /// The versions of `committed`
+    versions: Arc<Mutex<MemTableVersions>>,
+    /// The partitions as seen by the transaction, with its changes
+    batches: Vec<PartitionData>,
+    /// The number of times the transaction changed `batches`
+    writes: AtomicU64,
+    /// The write locks on `committed` and the partitions that replace them
+    /// on commit, held between prepare and commit
+    prepared: Mutex<Vec<PreparedPartition>>,
+}
+
+/// A write lock on a committed partition of a [`MemTableTransaction`], and
+/// the batches that replace its content on commit
+type PreparedPartition = (OwnedRwLockWriteGuard<Vec<RecordBatch>>, Vec<RecordBatch>);
+
+impl MemTableTransaction {
+    /// Copies the partitions of `table` as of the snapshot of `txn`
+    async fn try_new(table: &MemTable, txn: &Transaction) -> Result<Self> {
+        let mut guards = Vec::with_capacity(table.batches.len());
+        for partition in &table.batches {
+            guards.push(partition.read().await);
+        }
+        let partitions = {
+            // The guards prevent commits while the version is checked
+            let versions = table.versions.lock();
+            if versions.timestamp <= txn.snapshot() {
+                guards.iter().map(|guard| guard.to_vec()).collect()
+            } else {
+                versions.previous(txn.snapshot())?
+            }
+        };
+
+        Ok(Self {
+            committed: table.batches.clone(),
+            versions: Arc::clone(&table.versions),
+            batches: partitions
+                .into_iter()
+                .map(|batches| Arc::new(RwLock::new(batches)))
+                .collect(),
+            writes: AtomicU64::new(0),
+            prepared: Mutex::new(vec![]),
+        })
+    }
+}
+
+#[async_trait]
+impl TransactionParticipant for MemTableTransaction {
+    async fn prepare(&self, txn: &Transaction) -> Result<()> {
+        if self.writes.load(Ordering::Relaxed) == 0 {
+            return Ok(());
+        }
+
+        let mut locks = Vec::with_capacity(self.committed.len());
+        for partition in &self.committed {
+            locks.push(Arc::clone(partition).write_owned().await);
+        }
+        let timestamp = self.versions.lock().timestamp;
+        if timestamp > txn.snapshot() {
+            return exec_err!(
+                "Transaction {} conflicts with a concurrent change to a table it modified",
+                txn.id()
+            );
+        }
+        let mut prepared = Vec::with_capacity(locks.len());
+        for (lock, partition) in locks.into_iter().zip(&self.batches) {
+            prepared.push((lock, partition.read().await.clone()));
+        }
+        *self.prepared.lock() = prepared;
+        Ok(())
+    }
+
+    fn commit(&self, txn: &Transaction, timestamp: Timestamp) {
+        let prepared = std::mem::take(&mut *self.prepared.lock());
+        if prepared.is_empty() {
+            return;
+        }
+        let old_partitions = prepared
+            .into_iter()
+            .map(|(mut lock, batches)| std::mem::replace(&mut *lock, batches))
+            .collect();
+        self.versions
+            .lock()
+            .commit(old_partitions, timestamp, txn.clock());
+    }
+
+    fn abort(&self, _txn: &Transaction) {
+        self.prepared.lock().clear();
+    }
 }
 
 #[async_trait]
@@ -182,6 +504,10 @@ impl TableProvider for MemTable {
         TableType::Base
     }
 
+    fn supports_transactions(&self) -> bool {
+        true
+    }
+
     async fn scan(
         &self,
         state: &dyn Session,
@@ -189,11 +515,7 @@ impl TableProvider for MemTable {
         _filters: &[Expr],
         _limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let mut partitions = vec![];
-        for arc_inner_vec in self.batches.iter() {
-            let inner_vec = arc_inner_vec.read().await;
-            partitions.push(inner_vec.clone())
-        }
+        let partitions = self.partitions(state).await?.read().await;
 
         let mut source =
             MemorySourceConfig::try_new(&partitions, self.schema(), projection.cloned())?;
@@ -235,7 +557,7 @@ impl TableProvider for MemTable {
     /// [`SessionState`]: https://docs.rs/datafusion/latest/datafusion/execution/session_state/struct.SessionState.html
     async fn insert_into(
         &self,
-        _state: &dyn Session,
+        state: &dyn Session,
         input: Arc<dyn ExecutionPlan>,
         insert_op: InsertOp,
     ) -> Result<Arc<dyn ExecutionPlan>> {
@@ -250,7 +572,13 @@ impl TableProvider for MemTable {
         if insert_op != InsertOp::Append {
             return not_impl_err!("{insert_op} not implemented for MemoryTable yet");
         }
-        let sink = MemSink::try_new(self.batches.clone(), Arc::clone(&self.schema))?;
+        if self.batches.is_empty() {
+            return plan_err!("Cannot insert into MemTable with zero partitions");
+        }
+        let sink = MemTableSink {
+            partitions: self.partitions(state).await?,
+            schema: Arc::clone(&self.schema),
+        };
         Ok(Arc::new(DataSinkExec::new(input, Arc::new(sink), None)))
     }
 
@@ -273,8 +601,9 @@ impl TableProvider for MemTable {
         let mut total_deleted: u64 = 0;
         let df_schema = DFSchema::try_from(Arc::clone(&self.schema))?;
 
-        for partition_data in &self.batches {
-            let mut partition = partition_data.write().await;
+        let target = self.partitions(state).await?;
+        let mut partitions = target.write().await;
+        for partition in partitions.iter_mut() {
             let mut new_batches = Vec::with_capacity(partition.len());
 
             for batch in partition.iter() {
@@ -318,6 +647,7 @@ impl TableProvider for MemTable {
 
             *partition = new_batches;
         }
+        partitions.commit();
 
         Ok(Arc::new(DmlResultExec::new(total_deleted)))
     }
@@ -366,8 +696,9 @@ impl TableProvider for MemTable {
 
         let mut total_updated: u64 = 0;
 
-        for partition_data in &self.batches {
-            let mut partition = partition_data.write().await;
+        let target = self.partitions(state).await?;
+        let mut partitions = target.write().await;
+        for partition in partitions.iter_mut() {
             let mut new_batches = Vec::with_capacity(partition.len());
 
             for batch in partition.iter() {
@@ -449,9 +780,186 @@ impl TableProvider for MemTable {
 
             *partition = new_batches;
         }
+        partitions.commit();
 
         Ok(Arc::new(DmlResultExec::new(total_updated)))
     }
+
+    /// Returns an ExecutionPlan that applies the changes of a `MERGE` to this
+    /// [`MemTable`] when executed.
+    ///
+    /// The rows of `input` with a `delete` action are dropped and all other
+    /// rows replace the current content of the table, which is distributed
+    /// round-robin over the partitions.
+    ///
+    /// The MERGE fails when executed if the table was changed after it was
+    /// planned, as its input was computed from the previous content.
+    async fn merge_into(
+        &self,
+        state: &dyn Session,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if self.batches.is_empty() {
+            return internal_err!("MERGE into a MemTable without partitions");
+        }
+
+        // Check that the input has the columns of this table followed by the action
+        let input_schema = input.schema();
+        let num_columns = self.schema.fields().len();
+        if input_schema.fields().len() != num_columns + 1
+            || input_schema.field(num_columns).name() != MERGE_ACTION_COLUMN
+        {
+            return plan_err!(
+                "MERGE input must have the columns of the table followed by a '{MERGE_ACTION_COLUMN}' column"
+            );
+        }
+        self.schema().logically_equivalent_names_and_types(
+            &input_schema.project(&(0..num_columns).collect::<Vec<_>>())?,
+        )?;
+
+        let partitions = self.partitions(state).await?;
+        let version = partitions.version();
+        Ok(Arc::new(MemMergeExec::new(
+            input,
+            partitions,
+            version,
+            Arc::clone(&self.schema),
+            Arc::clone(&self.sort_order),
+        )))
+    }
+
+    async fn alter_schema(
+        &self,
+        state: &dyn Session,
+        operation: &AlterTableOperation,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let mut column_defaults = self.column_defaults.clone();
+        let constraints =
+            operation.apply_to_constraints(&self.schema, &self.constraints)?;
+        let mut sort_order = self.sort_order.lock().clone();
+        operation.apply_to_sort_order(&mut sort_order)?;
+
+        // Each operation maps an existing batch to a batch with the new schema
+        let (schema, alter_batch): (SchemaRef, Box<dyn Fn(&RecordBatch) -> _ + Send>) =
+            match operation {
+                AlterTableOperation::AddColumn {
+                    field,
+                    default,
+                    if_not_exists,
+                } => {
+                    if self.schema.field_with_name(field.name()).is_ok() {
+                        if *if_not_exists {
+                            return Ok(Arc::new(self.clone()));
+                        }
+                        return plan_err!(
+                            "ALTER TABLE failed: column '{}' already exists",
+                            field.name()
+                        );
+                    }
+                    let value = evaluate_column_default(state, field, default.as_ref())?;
+                    if let Some(default) = default {
+                        column_defaults.insert(field.name().clone(), default.clone());
+                    }
+                    let mut fields = self.schema.fields().to_vec();
+                    fields.push(Arc::clone(field));
+                    let schema = Arc::new(Schema::new_with_metadata(
+                        fields,
+                        self.schema.metadata().clone(),
+                    ));
+                    let batch_schema = Arc::clone(&schema);
+                    (
+                        schema,
+                        Box::new(move |batch: &RecordBatch| {
+                            let mut columns = batch.columns().to_vec();
+                            columns.push(value.to_array_of_size(batch.num_rows())?);
+                            Ok(RecordBatch::try_new(Arc::clone(&batch_schema), columns)?)
+                        }),
+                    )
+                }
+                AlterTableOperation::DropColumn { name, if_exists } => {
+                    let Ok(index) = self.schema.index_of(name) else {
+                        if *if_exists {
+                            return Ok(Arc::new(self.clone()));
+                        }
+                        return plan_err!(
+                            "ALTER TABLE failed: column '{name}' does not exist"
+                        );
+                    };
+                    if self.schema.fields().len() == 1 {
+                        return plan_err!(
+                            "ALTER TABLE failed: cannot drop the only column '{name}'"
+                        );
+                    }
+                    let kept = (0..self.schema.fields().len())
+                        .filter(|i| *i != index)
+                        .collect::<Vec<_>>();
+                    column_defaults.remove(name);
+
+                    let schema = Arc::new(self.schema.project(&kept)?);
+                    (
+                        schema,
+                        Box::new(move |batch: &RecordBatch| Ok(batch.project(&kept)?)),
+                    )
+                }
+                AlterTableOperation::RenameColumn { old_name, new_name } => {
+                    let Ok(index) = self.schema.index_of(old_name) else {
+                        return plan_err!(
+                            "ALTER TABLE failed: column '{old_name}' does not exist"
+                        );
+                    };
+                    if self.schema.field_with_name(new_name).is_ok() {
+                        return plan_err!(
+                            "ALTER TABLE failed: column '{new_name}' already exists"
+                        );
+                    }
+                    if let Some(default) = column_defaults.remove(old_name) {
+                        column_defaults.insert(new_name.clone(), default);
+                    }
+
+                    let mut fields = self.schema.fields().to_vec();
+                    fields[index] =
+                        Arc::new(fields[index].as_ref().clone().with_name(new_name));
+                    let schema = Arc::new(Schema::new_with_metadata(
+                        fields,
+                        self.schema.metadata().clone(),
+                    ));
+                    let batch_schema = Arc::clone(&schema);
+                    (
+                        schema,
+                        Box::new(move |batch: &RecordBatch| {
+                            Ok(RecordBatch::try_new(
+                                Arc::clone(&batch_schema),
+                                batch.columns().to_vec(),
+                            )?)
+                        }),
+                    )
+                }
+                AlterTableOperation::RenameTable { .. } => {
+                    return internal_err!(
+                        "RenameTable must be handled by the SchemaProvider"
+                    );
+                }
+            };
+
+        // Rewrite the data into new partitions so that scans already planned
+        // against this table keep seeing the old schema
+        let mut partitions = Vec::with_capacity(self.batches.len());
+        for partition in &self.batches {
+            let batches = partition.read().await;
+            partitions.push(
+                batches
+                    .iter()
+                    .map(&alter_batch)
+                    .collect::<Result<Vec<_>>>()?,
+            );
+        }
+
+        let table = MemTable::try_new(schema, partitions)?
+            .with_constraints(constraints)
+            .with_column_defaults(column_defaults)
+            .with_sort_order(sort_order);
+        Ok(Arc::new(table))
+    }
 }
 
 /// Evaluate filter expressions against a batch and return a combined boolean mask.
@@ -494,6 +1002,26 @@ fn evaluate_filters_to_mask(
     Ok(combined_mask)
 }
 
+/// Returns the schema of the single `count` row returned by DML operations
+fn make_count_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "count",
+        DataType::UInt64,
+        false,
+    )]))
+}
+
+/// Returns the properties of a plan that returns the single `count` row of
+/// a DML operation
+fn make_count_properties(schema: &SchemaRef) -> Arc<PlanProperties> {
+    Arc::new(PlanProperties::new(
+        datafusion_physical_expr::EquivalenceProperties::new(Arc::clone(schema)),
+        Partitioning::UnknownPartitioning(1),
+        datafusion_physical_plan::execution_plan::EmissionType::Final,
+        datafusion_physical_plan::execution_plan::Boundedness::Bounded,
+    ))
+}
+
 /// Returns a single row with the count of affected rows.
 #[derive(Debug)]
 struct DmlResultExec {
@@ -504,23 +1032,12 @@ struct DmlResultExec {
 
 impl DmlResultExec {
     fn new(rows_affected: u64) -> Self {
-        let schema = Arc::new(Schema::new(vec![Field::new(
-            "count",
-            DataType::UInt64,
-            false,
-        )]));
-
-        let properties = PlanProperties::new(
-            datafusion_physical_expr::EquivalenceProperties::new(Arc::clone(&schema)),
-            Partitioning::UnknownPartitioning(1),
-            datafusion_physical_plan::execution_plan::EmissionType::Final,
-            datafusion_physical_plan::execution_plan::Boundedness::Bounded,
-        );
-
+        let schema = make_count_schema();
+        let properties = make_count_properties(&schema);
         Self {
             rows_affected,
             schema,
-            properties: Arc::new(properties),
+            properties,
         }
     }
 }
@@ -568,8 +1085,8 @@ impl ExecutionPlan for DmlResultExec {
     fn execute(
         &self,
         _partition: usize,
-        _context: Arc<datafusion_execution::TaskContext>,
-    ) -> Result<datafusion_execution::SendableRecordBatchStream> {
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
         // Create a single batch with the count
         let count_array = UInt64Array::from(vec![self.rows_affected]);
         let batch = ArrowRecordBatch::try_new(
@@ -585,3 +1102,242 @@ impl ExecutionPlan for DmlResultExec {
         )))
     }
 }
+
+/// Appends the rows inserted into a [`MemTable`] to its partitions, see
+/// [`MemTable::insert_into`]
+#[derive(Debug)]
+struct MemTableSink {
+    partitions: MemPartitions,
+    schema: SchemaRef,
+}
+
+impl DisplayAs for MemTableSink {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "MemoryTable (partitions={})", self.partitions.len())
+            }
+            DisplayFormatType::TreeRender => {
+                // TODO: collect info
+                write!(f, "")
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DataSink for MemTableSink {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    async fn write_all(
+        &self,
+        mut data: SendableRecordBatchStream,
+        _context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        let num_partitions = self.partitions.len();
+
+        // buffer up the data round robin style into num_partitions
+        let mut new_batches = vec![vec![]; num_partitions];
+        let mut i = 0;
+        let mut row_count = 0;
+        while let Some(batch) = data.next().await.transpose()? {
+            row_count += batch.num_rows();
+            new_batches[i].push(batch);
+            i = (i + 1) % num_partitions;
+        }
+
+        // write the outputs into the batches
+        let mut partitions = self.partitions.write().await;
+        for (target, mut batches) in partitions.iter_mut().zip(new_batches) {
+            // Append all the new batches in one go to minimize locking overhead
+            target.append(&mut batches);
+        }
+        partitions.commit();
+
+        Ok(row_count as u64)
+    }
+}
+
+/// Applies the output of a `MERGE` plan to the partitions of a [`MemTable`],
+/// see [`MemTable::merge_into`]. Returns a single row with the count of
+/// inserted, updated and deleted rows.
+#[derive(Debug)]
+struct MemMergeExec {
+    input: Arc<dyn ExecutionPlan>,
+    partitions: MemPartitions,
+    /// The [version](MemPartitions::version) of the partitions read by
+    /// `input`
+    version: u64,
+    table_schema: SchemaRef,
+    /// The sort order of the table, which is reset once the rows are written
+    sort_order: Arc<Mutex<Vec<Vec<SortExpr>>>>,
+    schema: SchemaRef,
+    properties: Arc<PlanProperties>,
+}
+
+impl MemMergeExec {
+    fn new(
+        input: Arc<dyn ExecutionPlan>,
+        partitions: MemPartitions,
+        version: u64,
+        table_schema: SchemaRef,
+        sort_order: Arc<Mutex<Vec<Vec<SortExpr>>>>,
+    ) -> Self {
+        let schema = make_count_schema();
+        let properties = make_count_properties(&schema);
+        Self {
+            input,
+            partitions,
+            version,
+            table_schema,
+            sort_order,
+            schema,
+            properties,
+        }
+    }
+}
+
+impl DisplayAs for MemMergeExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default
+            | DisplayFormatType::Verbose
+            | DisplayFormatType::TreeRender => {
+                write!(f, "MemMergeExec: partitions={}", self.partitions.len())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for MemMergeExec {
+    fn name(&self) -> &str {
+        "MemMergeExec"
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let Ok([input]) = <[_; 1]>::try_from(children) else {
+            return internal_err!("MemMergeExec expects a single child");
+        };
+        Ok(Arc::new(Self::new(
+            input,
+            self.partitions.clone(),
+            self.version,
+            Arc::clone(&self.table_schema),
+            Arc::clone(&self.sort_order),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return internal_err!("MemMergeExec invalid partition {partition}");
+        }
+
+        let input = Arc::clone(&self.input);
+        let partitions = self.partitions.clone();
+        let version = self.version;
+        let table_schema = Arc::clone(&self.table_schema);
+        let sort_order = Arc::clone(&self.sort_order);
+        let schema = Arc::clone(&self.schema);
+        let stream = futures::stream::once(async move {
+            let mut rows_affected: u64 = 0;
+            let mut new_batches = vec![];
+            for batch in collect(input, context).await? {
+                let (batch, count) = apply_merge_actions(&table_schema, &batch)?;
+                rows_affected += count;
+                if batch.num_rows() > 0 {
+                    new_batches.push(batch);
+                }
+            }
+
+            // The new rows replace the whole table, so a change committed
+            // since the input was read would be lost
+            let mut writer = partitions.write().await;
+            if partitions.version() != version {
+                return exec_err!(
+                    "MERGE conflicts with a concurrent change to the table it modifies"
+                );
+            }
+            let num_partitions = writer.len();
+            writer.iter_mut().for_each(Vec::clear);
+            for (i, batch) in new_batches.into_iter().enumerate() {
+                writer[i % num_partitions].push(batch);
+            }
+            writer.commit();
+            // The rows are no longer sorted
+            *sort_order.lock() = vec![];
+
+            let count_array = UInt64Array::from(vec![rows_affected]);
+            Ok(ArrowRecordBatch::try_new(
+                schema,
+                vec![Arc::new(count_array) as ArrayRef],
+            )?)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.schema),
+            stream,
+        )))
+    }
+}
+
+/// Drops the deleted rows and the action column of a batch of the input of
+/// [`MemMergeExec`], returning the new rows of the table and the number of
+/// affected rows.
+fn apply_merge_actions(
+    table_schema: &SchemaRef,
+    batch: &RecordBatch,
+) -> Result<(RecordBatch, u64)> {
+    let num_columns = table_schema.fields().len();
+    let actions = cast(batch.column(num_columns), &DataType::Utf8)?;
+
+    let mut rows_affected = 0;
+    let keep = actions
+        .as_string::<i32>()
+        .iter()
+        .map(|action| {
+            let Some(action) = action else {
+                return Ok(Some(true));
+            };
+            rows_affected += 1;
+            match MergeAction::from_name(action) {
+                Some(MergeAction::Delete) => Ok(Some(false)),
+                Some(_) => Ok(Some(true)),
+                None => internal_err!("Invalid MERGE action: {action}"),
+            }
+        })
+        .collect::<Result<BooleanArray>>()?;
+
+    let batch = RecordBatch::try_new(
+        Arc::clone(table_schema),
+        batch.columns()[..num_columns].to_vec(),
+    )?;
+    Ok((filter_record_batch(&batch, &keep)?, rows_affected))
+}
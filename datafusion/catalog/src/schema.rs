@@ -82,6 +82,27 @@ pub trait SchemaProvider: Any + Debug + Sync + Send {
         exec_err!("schema provider does not support deregistering tables")
     }
 
+    /// If supported by the implementation, renames the `name` table to
+    /// `new_name` within this schema.
+    ///
+    /// The default implementation deregisters the table and registers it
+    /// again under the new name. Returns an error if `name` does not exist or
+    /// `new_name` is already taken.
+    async fn rename_table(&self, name: &str, new_name: String) -> Result<()> {
+        if self.table_exist(&new_name) {
+            return exec_err!("The table {new_name} already exists");
+        }
+        let Some(table) = self.deregister_table(name)? else {
+            return exec_err!("The table {name} does not exist");
+        };
+        if let Err(e) = self.register_table(new_name, Arc::clone(&table)) {
+            // restore the original table so a failed rename is not a drop
+            self.register_table(name.to_string(), table)?;
+            return Err(e);
+        }
+        Ok(())
+    }
+
     /// Returns true if table exist in the schema provider, false otherwise.
     fn table_exist(&self, name: &str) -> bool;
 }
@@ -21,16 +21,20 @@ use std::fmt::Debug;
 use std::sync::Arc;
 
 use crate::session::Session;
-use arrow::datatypes::SchemaRef;
+use arrow::array::{RecordBatch, RecordBatchOptions};
+use arrow::datatypes::{Field, Schema, SchemaRef};
 use async_trait::async_trait;
-use datafusion_common::{Constraints, Statistics, not_impl_err};
+use datafusion_common::{
+    Constraints, DFSchema, ScalarValue, Statistics, not_impl_err, plan_err,
+};
 use datafusion_common::{Result, internal_err};
 use datafusion_expr::Expr;
 use datafusion_expr::statistics::StatisticsRequest;
 
 use datafusion_expr::dml::InsertOp;
 use datafusion_expr::{
-    CreateExternalTable, LogicalPlan, TableProviderFilterPushDown, TableType,
+    AlterTableOperation, CreateExternalTable, Documentation, LogicalPlan, Signature,
+    TableProviderFilterPushDown, TableType,
 };
 use datafusion_physical_plan::ExecutionPlan;
 
@@ -318,6 +322,18 @@ pub trait TableProvider: Any + Debug + Sync + Send {
         None
     }
 
+    /// Whether the changes to this table made by the statements of a
+    /// [`Transaction`] are private to the transaction until it commits, and
+    /// discarded if it rolls back.
+    ///
+    /// Statements that change tables that do not support transactions fail
+    /// in a transaction, as their changes could not be rolled back.
+    ///
+    /// [`Transaction`]: crate::transaction::Transaction
+    fn supports_transactions(&self) -> bool {
+        false
+    }
+
     /// Return an [`ExecutionPlan`] to insert data into this table, if
     /// supported.
     ///
@@ -372,6 +388,28 @@ pub trait TableProvider: Any + Debug + Sync + Send {
         not_impl_err!("UPDATE not supported for {} table", self.table_type())
     }
 
+    /// Apply the changes of a `MERGE INTO` statement.
+    ///
+    /// `input` produces one row per row of the table joined with the
+    /// `MERGE` source: the new values of every column of the table, followed
+    /// by a Utf8 [`MERGE_ACTION_COLUMN`] holding the [`MergeAction`] of the
+    /// row. Unmodified rows of the table have a `NULL` action and their
+    /// current values, so that copy-on-write tables can rewrite their data
+    /// from `input` alone.
+    ///
+    /// Returns an [`ExecutionPlan`] producing a single row with `count` (UInt64),
+    /// the number of rows inserted, updated or deleted.
+    ///
+    /// [`MERGE_ACTION_COLUMN`]: datafusion_expr::dml::MERGE_ACTION_COLUMN
+    /// [`MergeAction`]: datafusion_expr::dml::MergeAction
+    async fn merge_into(
+        &self,
+        _state: &dyn Session,
+        _input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        not_impl_err!("MERGE not supported for {} table", self.table_type())
+    }
+
     /// Remove all rows from the table.
     ///
     /// Should return an [ExecutionPlan] producing a single row with count (UInt64),
@@ -379,6 +417,56 @@ pub trait TableProvider: Any + Debug + Sync + Send {
     async fn truncate(&self, _state: &dyn Session) -> Result<Arc<dyn ExecutionPlan>> {
         not_impl_err!("TRUNCATE not supported for {} table", self.table_type())
     }
+
+    /// Apply a column level `ALTER TABLE` operation (add, drop or rename a
+    /// column) to this table.
+    ///
+    /// Returns a provider with the new schema, which replaces this one in
+    /// its [`SchemaProvider`]. Existing data must remain readable through the
+    /// returned provider, e.g. added columns are backfilled with their
+    /// default value (or `NULL`).
+    ///
+    /// [`AlterTableOperation::RenameTable`] is handled by
+    /// [`SchemaProvider::rename_table`] and is never passed to this method.
+    ///
+    /// [`SchemaProvider`]: crate::SchemaProvider
+    /// [`SchemaProvider::rename_table`]: crate::SchemaProvider::rename_table
+    async fn alter_schema(
+        &self,
+        _state: &dyn Session,
+        _operation: &AlterTableOperation,
+    ) -> Result<Arc<dyn TableProvider>> {
+        not_impl_err!("ALTER TABLE not supported for {} table", self.table_type())
+    }
+}
+
+/// Evaluates the constant `DEFAULT` expression of a column added to a table by
+/// [`TableProvider::alter_schema`], cast to the type of `field`.
+///
+/// Returns an error if a NOT NULL column has no default.
+pub fn evaluate_column_default(
+    state: &dyn Session,
+    field: &Field,
+    default: Option<&Expr>,
+) -> Result<ScalarValue> {
+    let Some(default) = default else {
+        if !field.is_nullable() {
+            return plan_err!(
+                "ALTER TABLE failed: NOT NULL column '{}' requires a DEFAULT value",
+                field.name()
+            );
+        }
+        return ScalarValue::try_from(field.data_type());
+    };
+    let physical_expr =
+        state.create_physical_expr(default.clone(), &DFSchema::empty())?;
+    let batch = RecordBatch::try_new_with_options(
+        Arc::new(Schema::empty()),
+        vec![],
+        &RecordBatchOptions::new().with_row_count(Some(1)),
+    )?;
+    let array = physical_expr.evaluate(&batch)?.into_array(1)?;
+    ScalarValue::try_from_array(&array, 0)?.cast_to(field.data_type())
 }
 
 impl dyn TableProvider {
@@ -593,6 +681,23 @@ pub trait TableFunctionImpl: Debug + Sync + Send + Any {
         #[expect(deprecated)]
         self.call(args.exprs)
     }
+
+    /// Returns the [`Signature`] of the arguments this function accepts, if
+    /// known.
+    ///
+    /// Table functions with a signature are listed in
+    /// `information_schema.routines`, `information_schema.parameters` and the
+    /// output of `SHOW FUNCTIONS`.
+    fn signature(&self) -> Option<&Signature> {
+        None
+    }
+
+    /// Returns the documentation for this table function.
+    ///
+    /// See [`Self::signature`] for where the documentation is shown.
+    fn documentation(&self) -> Option<&Documentation> {
+        None
+    }
 }
 
 /// A table that uses a function to generate data
@@ -0,0 +1,450 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`Transaction`]s with snapshot isolation
+
+use std::any::Any;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion_common::{Result, exec_err};
+pub use datafusion_execution::transaction::{CommitClock, Timestamp};
+use datafusion_expr::logical_plan::{TransactionAccessMode, TransactionIsolationLevel};
+use datafusion_session::Session;
+use parking_lot::Mutex;
+
+/// A participant in a [`Transaction`], such as a table changed by the
+/// transaction.
+///
+/// Table providers that support transactions keep the changes made by the
+/// statements of a transaction private, and [enlist](Transaction::enlist) a
+/// participant that makes these changes visible when the transaction
+/// commits, or discards them when it aborts.
+///
+/// A transaction that changes several participants commits in two phases:
+/// all participants are [prepared](Self::prepare) first, and committed only
+/// if none of them fails. Anything that may fail must be done while
+/// preparing, as [`Self::commit`] cannot fail, so that either all or none of
+/// the changes become visible.
+///
+/// See [`MemTable`](crate::MemTable) for an implementation with snapshot
+/// isolation.
+#[async_trait]
+pub trait TransactionParticipant: Any + Debug + Send + Sync {
+    /// Called when the participant is [enlisted](Transaction::enlist) in
+    /// `txn`
+    async fn begin(&self, _txn: &Transaction) -> Result<()> {
+        Ok(())
+    }
+
+    /// Checks that the changes of `txn` can be committed, for example that
+    /// they do not conflict with changes committed after the snapshot of
+    /// `txn`, and prevents conflicting changes until [`Self::commit`] or
+    /// [`Self::abort`] is called.
+    async fn prepare(&self, _txn: &Transaction) -> Result<()> {
+        Ok(())
+    }
+
+    /// Makes the changes of `txn` visible to the transactions whose snapshot
+    /// is at or after `timestamp`.
+    ///
+    /// Called after all participants of `txn` were prepared successfully.
+    fn commit(&self, txn: &Transaction, timestamp: Timestamp);
+
+    /// Discards the changes of `txn`, and releases anything acquired by
+    /// [`Self::prepare`].
+    ///
+    /// Also called if committing `txn` is cancelled or fails before the
+    /// participant is committed.
+    fn abort(&self, txn: &Transaction);
+}
+
+/// The status of a [`Transaction`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// The transaction runs statements
+    Active,
+    /// The transaction was committed
+    Committed,
+    /// The transaction was rolled back or failed to commit
+    Aborted,
+}
+
+/// A participant enlisted in a transaction
+#[derive(Debug)]
+struct Enlisted {
+    /// The key of the participant, see [`Transaction::enlist`]
+    key: usize,
+    participant: Arc<dyn TransactionParticipant>,
+    /// The same participant, used to downcast it to its concrete type
+    any: Arc<dyn Any + Send + Sync>,
+}
+
+/// The participants of a transaction and its status
+#[derive(Debug)]
+struct TransactionState {
+    status: TransactionStatus,
+    participants: Vec<Enlisted>,
+}
+
+/// A transaction started by `BEGIN` or `START TRANSACTION`.
+///
+/// The statements of a transaction see the data as of its
+/// [snapshot](Self::snapshot) together with their own changes, and their
+/// changes become visible to other sessions only when the transaction
+/// commits. Concurrent transactions that change the same data conflict, and
+/// the transaction that commits last fails (first committer wins).
+///
+/// The active transaction of a session is stored as an extension of its
+/// [`SessionConfig`], see [`Transaction::current`]. Transactions are ordered
+/// by the [`CommitClock`] of the [`RuntimeEnv`] of their session, so
+/// sessions only see each other's transactions if they share a runtime or
+/// its clock.
+///
+/// All isolation levels run with snapshot isolation. Note that snapshot
+/// isolation does not prevent all anomalies of concurrent transactions
+/// (e.g. write skew), so `SERIALIZABLE` transactions are not truly
+/// serializable.
+///
+/// [`SessionConfig`]: datafusion_execution::config::SessionConfig
+/// [`RuntimeEnv`]: datafusion_execution::runtime_env::RuntimeEnv
+#[derive(Debug)]
+pub struct Transaction {
+    /// Unique id of this transaction within its clock
+    id: u64,
+    /// The timestamp of the commits visible to this transaction
+    snapshot: Timestamp,
+    /// The clock that orders the commits of this transaction
+    clock: Arc<CommitClock>,
+    access_mode: TransactionAccessMode,
+    isolation_level: TransactionIsolationLevel,
+    state: Mutex<TransactionState>,
+}
+
+impl Transaction {
+    /// Starts a new transaction that sees the latest data committed with
+    /// `clock`
+    pub fn begin(
+        clock: Arc<CommitClock>,
+        access_mode: TransactionAccessMode,
+        isolation_level: TransactionIsolationLevel,
+    ) -> Self {
+        let (id, snapshot) = clock.begin();
+        Self {
+            id,
+            snapshot,
+            clock,
+            access_mode,
+            isolation_level,
+            state: Mutex::new(TransactionState {
+                status: TransactionStatus::Active,
+                participants: vec![],
+            }),
+        }
+    }
+
+    /// Returns the transaction of the session, if any
+    pub fn current(session: &dyn Session) -> Option<Arc<Self>> {
+        session.config().get_extension::<Self>()
+    }
+
+    /// Returns the unique id of this transaction
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the timestamp of the latest commit visible to this transaction
+    pub fn snapshot(&self) -> Timestamp {
+        self.snapshot
+    }
+
+    /// Returns the clock that orders the commits of this transaction
+    pub fn clock(&self) -> &Arc<CommitClock> {
+        &self.clock
+    }
+
+    /// Returns whether this transaction may change data
+    pub fn access_mode(&self) -> &TransactionAccessMode {
+        &self.access_mode
+    }
+
+    /// Returns the isolation level requested for this transaction
+    pub fn isolation_level(&self) -> &TransactionIsolationLevel {
+        &self.isolation_level
+    }
+
+    /// Returns the status of this transaction
+    pub fn status(&self) -> TransactionStatus {
+        self.state.lock().status
+    }
+
+    /// Returns the participant enlisted with `key`, if it is of type `T`
+    pub fn participant<T: TransactionParticipant>(&self, key: usize) -> Option<Arc<T>> {
+        let state = self.state.lock();
+        let enlisted = state.participants.iter().find(|e| e.key == key)?;
+        Arc::clone(&enlisted.any).downcast().ok()
+    }
+
+    /// Enlists `participant` in this transaction, so that it is committed or
+    /// aborted with the transaction, and calls its
+    /// [`begin`](TransactionParticipant::begin).
+    ///
+    /// `key` identifies the participant within this transaction, for example
+    /// the address of the state of the table it belongs to. If a participant
+    /// was already enlisted with `key`, it is returned instead.
+    pub async fn enlist<T: TransactionParticipant>(
+        &self,
+        key: usize,
+        participant: T,
+    ) -> Result<Arc<T>> {
+        let participant = {
+            let mut state = self.state.lock();
+            if state.status != TransactionStatus::Active {
+                return exec_err!("Transaction {} is no longer active", self.id);
+            }
+            if let Some(enlisted) = state.participants.iter().find(|e| e.key == key) {
+                return match Arc::clone(&enlisted.any).downcast() {
+                    Ok(existing) => Ok(existing),
+                    Err(_) => exec_err!(
+                        "Transaction {} has a participant of another type with key {key}",
+                        self.id
+                    ),
+                };
+            }
+            let participant = Arc::new(participant);
+            state.participants.push(Enlisted {
+                key,
+                participant: Arc::clone(&participant) as _,
+                any: Arc::clone(&participant) as _,
+            });
+            participant
+        };
+        participant.begin(self).await?;
+        Ok(participant)
+    }
+
+    /// Commits this transaction, making its changes visible to other
+    /// transactions.
+    ///
+    /// If a participant fails to prepare, for example because of a conflict
+    /// with a concurrent transaction, the transaction is aborted and the error
+    /// is returned. The participants are committed together without yielding,
+    /// so a commit that is cancelled also aborts the transaction and never
+    /// leaves some of its changes visible.
+    pub async fn commit(&self) -> Result<()> {
+        // The transaction counts as aborted until all participants are
+        // committed
+        let mut participants = self.finish(TransactionStatus::Aborted)?;
+        if participants.participants.is_empty() {
+            self.state.lock().status = TransactionStatus::Committed;
+            return Ok(());
+        }
+
+        let _commits = self.clock.lock_commits().await;
+        for participant in &participants.participants {
+            participant.prepare(self).await?;
+        }
+
+        let timestamp = self.clock.next_commit_timestamp();
+        for participant in std::mem::take(&mut participants.participants) {
+            participant.commit(self, timestamp);
+        }
+        self.state.lock().status = TransactionStatus::Committed;
+        Ok(())
+    }
+
+    /// Rolls back this transaction, discarding its changes
+    pub fn rollback(&self) -> Result<()> {
+        // The participants are aborted when dropped
+        self.finish(TransactionStatus::Aborted)?;
+        Ok(())
+    }
+
+    /// Ends this transaction with `status`, returning its participants
+    fn finish(&self, status: TransactionStatus) -> Result<AbortOnDrop<'_>> {
+        let participants = {
+            let mut state = self.state.lock();
+            if state.status != TransactionStatus::Active {
+                return exec_err!("Transaction {} is no longer active", self.id);
+            }
+            state.status = status;
+            std::mem::take(&mut state.participants)
+        };
+        self.clock.release(self.snapshot);
+        Ok(AbortOnDrop {
+            txn: self,
+            participants: participants.into_iter().map(|e| e.participant).collect(),
+        })
+    }
+}
+
+/// The participants of a finished [`Transaction`], which are aborted when
+/// dropped unless they were committed
+struct AbortOnDrop<'a> {
+    txn: &'a Transaction,
+    participants: Vec<Arc<dyn TransactionParticipant>>,
+}
+
+impl Drop for AbortOnDrop<'_> {
+    fn drop(&mut self) {
+        for participant in &self.participants {
+            participant.abort(self.txn);
+        }
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        // A transaction dropped without commit or rollback is aborted
+        let state = self.state.get_mut();
+        if state.status == TransactionStatus::Active {
+            state.status = TransactionStatus::Aborted;
+            let participants = std::mem::take(&mut state.participants);
+            self.clock.release(self.snapshot);
+            for enlisted in participants {
+                enlisted.participant.abort(self);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CountingParticipant {
+        fail_prepare: bool,
+        block_prepare: bool,
+        prepared: AtomicUsize,
+        committed: AtomicUsize,
+        aborted: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TransactionParticipant for CountingParticipant {
+        async fn prepare(&self, _txn: &Transaction) -> Result<()> {
+            if self.fail_prepare {
+                return exec_err!("conflict");
+            }
+            if self.block_prepare {
+                std::future::pending::<()>().await;
+            }
+            self.prepared.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn commit(&self, _txn: &Transaction, _timestamp: Timestamp) {
+            self.committed.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn abort(&self, _txn: &Transaction) {
+            self.aborted.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn begin(clock: &Arc<CommitClock>) -> Transaction {
+        Transaction::begin(
+            Arc::clone(clock),
+            TransactionAccessMode::ReadWrite,
+            TransactionIsolationLevel::Snapshot,
+        )
+    }
+
+    #[tokio::test]
+    async fn commit_and_rollback() -> Result<()> {
+        let clock = Arc::new(CommitClock::default());
+        let txn = begin(&clock);
+        assert!(clock.has_active_snapshot(txn.snapshot()..txn.snapshot() + 1));
+        let first = txn.enlist(1, CountingParticipant::default()).await?;
+        let again = txn.enlist(1, CountingParticipant::default()).await?;
+        assert!(Arc::ptr_eq(&first, &again));
+        assert!(txn.participant::<CountingParticipant>(1).is_some());
+        assert!(txn.participant::<CountingParticipant>(2).is_none());
+
+        txn.commit().await?;
+        assert_eq!(txn.status(), TransactionStatus::Committed);
+        assert!(!clock.has_active_snapshot(txn.snapshot()..txn.snapshot() + 1));
+        assert_eq!(first.prepared.load(Ordering::Relaxed), 1);
+        assert_eq!(first.committed.load(Ordering::Relaxed), 1);
+        assert_eq!(first.aborted.load(Ordering::Relaxed), 0);
+        assert!(txn.commit().await.is_err());
+        assert!(txn.enlist(2, CountingParticipant::default()).await.is_err());
+
+        let txn = begin(&clock);
+        let participant = txn.enlist(1, CountingParticipant::default()).await?;
+        txn.rollback()?;
+        assert_eq!(txn.status(), TransactionStatus::Aborted);
+        assert_eq!(participant.committed.load(Ordering::Relaxed), 0);
+        assert_eq!(participant.aborted.load(Ordering::Relaxed), 1);
+
+        let txn = begin(&clock);
+        let participant = txn.enlist(1, CountingParticipant::default()).await?;
+        drop(txn);
+        assert_eq!(participant.aborted.load(Ordering::Relaxed), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failed_prepare_aborts_all_participants() -> Result<()> {
+        let clock = Arc::new(CommitClock::default());
+        let txn = begin(&clock);
+        let ok = txn.enlist(1, CountingParticipant::default()).await?;
+        let failing = CountingParticipant {
+            fail_prepare: true,
+            ..Default::default()
+        };
+        let failing = txn.enlist(2, failing).await?;
+
+        let err = txn.commit().await.unwrap_err();
+        assert!(err.to_string().contains("conflict"), "{err}");
+        assert_eq!(txn.status(), TransactionStatus::Aborted);
+        assert_eq!(ok.committed.load(Ordering::Relaxed), 0);
+        assert_eq!(ok.aborted.load(Ordering::Relaxed), 1);
+        assert_eq!(failing.aborted.load(Ordering::Relaxed), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancelled_commit_aborts_all_participants() -> Result<()> {
+        let clock = Arc::new(CommitClock::default());
+        let txn = begin(&clock);
+        let ok = txn.enlist(1, CountingParticipant::default()).await?;
+        let blocking = CountingParticipant {
+            block_prepare: true,
+            ..Default::default()
+        };
+        let blocking = txn.enlist(2, blocking).await?;
+
+        // The commit is dropped while `blocking` is being prepared
+        assert!(txn.commit().now_or_never().is_none());
+        assert_eq!(txn.status(), TransactionStatus::Aborted);
+        assert_eq!(ok.prepared.load(Ordering::Relaxed), 1);
+        assert_eq!(ok.committed.load(Ordering::Relaxed), 0);
+        assert_eq!(ok.aborted.load(Ordering::Relaxed), 1);
+        assert_eq!(blocking.aborted.load(Ordering::Relaxed), 1);
+
+        // Other transactions of the clock can still commit
+        let txn = begin(&clock);
+        txn.enlist(1, CountingParticipant::default()).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,356 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! String collations, see [`Collation`]
+
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, GenericStringArray, GenericStringBuilder, OffsetSizeTrait,
+    StringViewBuilder,
+};
+use arrow::datatypes::{DataType, Field};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::Result;
+use crate::error::{_exec_err, _plan_datafusion_err, _plan_err};
+
+/// Key of the Arrow field metadata that holds the name of the [`Collation`]
+/// of a string field. Fields without this key use [`Collation::Binary`].
+pub const COLLATION_METADATA_KEY: &str = "datafusion.collation";
+
+/// Separates the levels of a collation key. It sorts before every other
+/// character, so that a key whose first level is a prefix of another key's
+/// first level sorts first.
+const LEVEL_SEPARATOR: char = '\0';
+
+/// How strings are compared for equality and ordering.
+///
+/// A collation is attached to a string field using the
+/// [`COLLATION_METADATA_KEY`] field metadata, for example by the SQL
+/// `COLLATE` clause. Comparisons, sorting, grouping and joins on collated
+/// fields compare the [collation keys](Self::append_key) of the values, which
+/// are strings whose binary order is the order of the collation.
+///
+/// # Names
+///
+/// | Name                                   | Collation                                |
+/// |----------------------------------------|------------------------------------------|
+/// | `binary`, `C`, `POSIX`, `ucs_basic`    | [`Collation::Binary`]                    |
+/// | `nocase`, `case_insensitive`           | [`Collation::CaseInsensitive`]           |
+/// | `und`, `root`, `unicode`, `<language>` | Unicode, [`CollationStrength::Tertiary`]  |
+/// | `<language>-u-ks-level1`               | Unicode, [`CollationStrength::Primary`]   |
+/// | `<language>-u-ks-level2`               | Unicode, [`CollationStrength::Secondary`] |
+/// | `<language>-u-ks-level3`               | Unicode, [`CollationStrength::Tertiary`]  |
+///
+/// Unicode collations are named by BCP 47 language tags such as `en-US` or
+/// `und-u-ks-level2`. Language specific tailorings are not supported, so all
+/// languages use the root collation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Collation {
+    /// Compare strings by their Unicode code points
+    #[default]
+    Binary,
+    /// Compare the lowercase strings by their Unicode code points
+    CaseInsensitive,
+    /// Compare strings alphabetically by their base letters, then by their
+    /// accents and then by case, up to the given strength
+    Unicode(CollationStrength),
+}
+
+/// The differences between strings that are significant for a
+/// [`Collation::Unicode`] collation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CollationStrength {
+    /// Only compare base letters, ignoring accents and case (`ks-level1`)
+    Primary,
+    /// Compare base letters and accents, ignoring case (`ks-level2`)
+    Secondary,
+    /// Compare base letters, accents and case, with uppercase letters first
+    /// (`ks-level3`)
+    Tertiary,
+}
+
+impl Collation {
+    /// Parse a collation name, see [`Collation`] for the supported names
+    pub fn try_new(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "binary" | "c" | "posix" | "ucs_basic" => Ok(Self::Binary),
+            "nocase" | "case_insensitive" => Ok(Self::CaseInsensitive),
+            "root" | "unicode" => Ok(Self::Unicode(CollationStrength::Tertiary)),
+            tag => Self::try_from_language_tag(tag)
+                .map(Self::Unicode)
+                .ok_or_else(|| _plan_datafusion_err!("Unsupported collation '{name}'")),
+        }
+    }
+
+    /// Parse a lowercase BCP 47 language tag such as `en-us-u-ks-level2`
+    fn try_from_language_tag(tag: &str) -> Option<CollationStrength> {
+        let (language, extension) = match tag.split_once("-u-") {
+            Some((language, extension)) => (language, Some(extension)),
+            None => (tag, None),
+        };
+        let mut subtags = language.split('-');
+        let primary = subtags.next()?;
+        if !(2..=3).contains(&primary.len())
+            || !primary.chars().all(|c| c.is_ascii_alphabetic())
+            || !subtags
+                .all(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric()))
+        {
+            return None;
+        }
+        match extension {
+            None => Some(CollationStrength::Tertiary),
+            Some("ks-level1") => Some(CollationStrength::Primary),
+            Some("ks-level2") => Some(CollationStrength::Secondary),
+            Some("ks-level3") => Some(CollationStrength::Tertiary),
+            Some(_) => None,
+        }
+    }
+
+    /// The canonical name of this collation
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::CaseInsensitive => "nocase",
+            Self::Unicode(CollationStrength::Primary) => "und-u-ks-level1",
+            Self::Unicode(CollationStrength::Secondary) => "und-u-ks-level2",
+            Self::Unicode(CollationStrength::Tertiary) => "und",
+        }
+    }
+
+    /// Returns true if this collation compares strings by their code points
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Self::Binary)
+    }
+
+    /// Returns the collation of `field`, or [`Collation::Binary`] if it has
+    /// none
+    pub fn from_field(field: &Field) -> Result<Self> {
+        field
+            .metadata()
+            .get(COLLATION_METADATA_KEY)
+            .map_or(Ok(Self::Binary), |name| Self::try_new(name))
+    }
+
+    /// Returns `field` with this collation, or without a collation if this is
+    /// [`Collation::Binary`].
+    ///
+    /// Returns an error if `field` is not a string field.
+    pub fn apply_to_field(&self, field: Field) -> Result<Field> {
+        if !matches!(
+            field.data_type(),
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View | DataType::Null
+        ) {
+            return _plan_err!(
+                "COLLATE requires a string argument, got {}",
+                field.data_type()
+            );
+        }
+        let mut metadata = field.metadata().clone();
+        if self.is_binary() {
+            metadata.remove(COLLATION_METADATA_KEY);
+        } else {
+            metadata.insert(COLLATION_METADATA_KEY.to_string(), self.name().to_string());
+        }
+        Ok(field.with_metadata(metadata))
+    }
+
+    /// Appends the collation key of `value` to `key`.
+    ///
+    /// Two strings are equal under this collation if and only if their keys
+    /// are equal, and the binary order of the keys is the order of the
+    /// strings under this collation.
+    pub fn append_key(&self, value: &str, key: &mut String) {
+        match self {
+            Self::Binary => key.push_str(value),
+            Self::CaseInsensitive => {
+                key.extend(value.chars().flat_map(char::to_lowercase))
+            }
+            Self::Unicode(strength) => {
+                // Decompose accented characters so that the base letters and
+                // accents can be compared separately
+                let lowercase = value.to_lowercase();
+                key.extend(lowercase.nfd().filter(|c| !is_combining_mark(*c)));
+                if *strength >= CollationStrength::Secondary {
+                    key.push(LEVEL_SEPARATOR);
+                    key.extend(lowercase.nfd());
+                }
+                if *strength >= CollationStrength::Tertiary {
+                    key.push(LEVEL_SEPARATOR);
+                    key.extend(value.nfd());
+                }
+            }
+        }
+    }
+
+    /// Returns the collation keys of the values of a string array, see
+    /// [`Self::append_key`]. Null values have a null key.
+    pub fn keys(&self, array: &ArrayRef) -> Result<ArrayRef> {
+        if self.is_binary() {
+            return Ok(Arc::clone(array));
+        }
+        match array.data_type() {
+            DataType::Utf8 => Ok(self.generic_keys(array.as_string::<i32>())),
+            DataType::LargeUtf8 => Ok(self.generic_keys(array.as_string::<i64>())),
+            DataType::Utf8View => {
+                let array = array.as_string_view();
+                let mut builder = StringViewBuilder::with_capacity(array.len());
+                let mut key = String::new();
+                for value in array.iter() {
+                    match value {
+                        Some(value) => {
+                            key.clear();
+                            self.append_key(value, &mut key);
+                            builder.append_value(&key);
+                        }
+                        None => builder.append_null(),
+                    }
+                }
+                Ok(Arc::new(builder.finish()))
+            }
+            DataType::Null => Ok(Arc::clone(array)),
+            other => _exec_err!("Collation {self} is not supported for {other}"),
+        }
+    }
+
+    fn generic_keys<O: OffsetSizeTrait>(
+        &self,
+        array: &GenericStringArray<O>,
+    ) -> ArrayRef {
+        let mut builder = GenericStringBuilder::<O>::with_capacity(
+            array.len(),
+            array.value_data().len(),
+        );
+        let mut key = String::new();
+        for value in array.iter() {
+            match value {
+                Some(value) => {
+                    key.clear();
+                    self.append_key(value, &mut key);
+                    builder.append_value(&key);
+                }
+                None => builder.append_null(),
+            }
+        }
+        Arc::new(builder.finish())
+    }
+}
+
+impl Display for Collation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+
+    fn key(collation: Collation, value: &str) -> String {
+        let mut key = String::new();
+        collation.append_key(value, &mut key);
+        key
+    }
+
+    #[test]
+    fn parse_names() -> Result<()> {
+        assert_eq!(Collation::try_new("binary")?, Collation::Binary);
+        assert_eq!(Collation::try_new("C")?, Collation::Binary);
+        assert_eq!(Collation::try_new("NOCASE")?, Collation::CaseInsensitive);
+        assert_eq!(
+            Collation::try_new("und")?,
+            Collation::Unicode(CollationStrength::Tertiary)
+        );
+        assert_eq!(
+            Collation::try_new("en-u-ks-level2")?,
+            Collation::Unicode(CollationStrength::Secondary)
+        );
+        assert_eq!(
+            Collation::try_new("de-DE-u-ks-level1")?,
+            Collation::Unicode(CollationStrength::Primary)
+        );
+        for name in ["", "english", "en-u-kc-true", "en--u-ks-level2", "e1"] {
+            assert!(Collation::try_new(name).is_err(), "{name}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn keys_compare_like_the_collation() {
+        let case_insensitive = Collation::CaseInsensitive;
+        assert_eq!(key(case_insensitive, "ABC"), key(case_insensitive, "abc"));
+        assert_ne!(key(case_insensitive, "abc"), key(case_insensitive, "äbc"));
+
+        let primary = Collation::Unicode(CollationStrength::Primary);
+        assert_eq!(key(primary, "Résumé"), key(primary, "resume"));
+
+        let secondary = Collation::Unicode(CollationStrength::Secondary);
+        assert_eq!(key(secondary, "Résumé"), key(secondary, "rÉsumÉ"));
+        assert_ne!(key(secondary, "Résumé"), key(secondary, "resume"));
+
+        let tertiary = Collation::Unicode(CollationStrength::Tertiary);
+        assert_ne!(key(tertiary, "a"), key(tertiary, "A"));
+        // Precomposed and decomposed accents are equal
+        assert_eq!(key(tertiary, "\u{e9}"), key(tertiary, "e\u{301}"));
+
+        // Alphabetical order, rather than code point order
+        let mut words = vec!["b", "Á", "a", "B", "ab", "á"];
+        words.sort_by_key(|word| key(tertiary, word));
+        assert_eq!(words, vec!["a", "Á", "á", "ab", "B", "b"]);
+    }
+
+    #[test]
+    fn array_keys() -> Result<()> {
+        let array: ArrayRef = Arc::new(StringArray::from(vec![Some("AbC"), None]));
+        let keys = Collation::CaseInsensitive.keys(&array)?;
+        assert_eq!(
+            keys.as_string::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some("abc"), None]
+        );
+        assert!(Arc::ptr_eq(&Collation::Binary.keys(&array)?, &array));
+        Ok(())
+    }
+
+    #[test]
+    fn field_collation() -> Result<()> {
+        let field = Collation::try_new("en-u-ks-level2")?.apply_to_field(Field::new(
+            "a",
+            DataType::Utf8,
+            true,
+        ))?;
+        assert_eq!(
+            field.metadata().get(COLLATION_METADATA_KEY).unwrap(),
+            "und-u-ks-level2"
+        );
+        assert_eq!(
+            Collation::from_field(&field)?,
+            Collation::Unicode(CollationStrength::Secondary)
+        );
+        let field = Collation::Binary.apply_to_field(field)?;
+        assert!(field.metadata().is_empty());
+        assert!(
+            Collation::CaseInsensitive
+                .apply_to_field(Field::new("a", DataType::Int32, true))
+                .is_err()
+        );
+        Ok(())
+    }
+}
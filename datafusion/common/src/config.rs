@@ -681,6 +681,13 @@ config_namespace! {
         /// `ListingTableProvider` in DataFusion. Defaults to true.
         pub collect_statistics: bool, default = true
 
+        /// When set to true, `ANALYZE TABLE` writes the statistics of each file
+        /// of a listing table to a sidecar file next to it, named after the
+        /// file with a `.statistics.arrow` suffix, and listing tables read the
+        /// statistics of a file from its sidecar file, if it is up to date,
+        /// instead of inferring them from the file.
+        pub statistics_sidecar_files: bool, default = false
+
         /// Number of partitions for query execution. Increasing partitions can increase
         /// concurrency.
         ///
@@ -1345,6 +1352,34 @@ config_namespace! {
         /// predicate push down.
         pub filter_null_join_keys: bool, default = false
 
+        /// When set to true, the optimizer infers predicates from the
+        /// equalities of inner joins: a comparison of a join key to literals,
+        /// an `IN` list or `IS NOT NULL` on a join key is copied to the keys
+        /// it is equal to, including through trees of joins, so that it can
+        /// be pushed down to all the joined tables.
+        pub enable_predicate_inference: bool, default = false
+
+        /// When set to true, the optimizer pre-aggregates the input of an
+        /// inner join below the join when all the aggregates above the join
+        /// are `sum`, `count`, `min`, `max` or `avg` of columns of that input,
+        /// and re-aggregates the partial results above the join. This is only
+        /// done when the table statistics estimate that the pre-aggregation
+        /// reduces the number of rows enough, see
+        /// `eager_aggregation_max_group_ratio`.
+        pub enable_eager_aggregation: bool, default = false
+
+        /// The maximum ratio of the estimated number of groups of an eager
+        /// pre-aggregation to the estimated number of its input rows for the
+        /// pre-aggregation to be added below a join
+        pub eager_aggregation_max_group_ratio: f64, default = 0.5
+
+        /// When set to true, the optimizer decorrelates the correlated
+        /// subqueries and lateral joins that the other decorrelation rules do
+        /// not support, such as correlations under aggregates with `LIMIT`,
+        /// inside window functions or across several levels of subqueries, by
+        /// joining them with the distinct values of the correlated columns
+        pub enable_dependent_join_decorrelation: bool, default = false
+
         /// Should DataFusion repartition data using the aggregate keys to execute aggregates
         /// in parallel using the provided `target_partitions` level
         pub repartition_aggregations: bool, default = true
@@ -1475,6 +1510,18 @@ config_namespace! {
         /// query is used.
         pub join_reordering: bool, default = true
 
+        /// When set to true, the physical plan optimizer reorders trees of
+        /// inner joins based on statistics to reduce the number of rows of
+        /// intermediate results. Outer, semi and anti joins are not
+        /// reordered: the trees of inner joins below and above them are
+        /// reordered separately.
+        pub enable_join_reorder: bool, default = false
+
+        /// The maximum number of relations of a tree of inner joins for which
+        /// `enable_join_reorder` searches for the best order with dynamic
+        /// programming. Larger trees are ordered greedily.
+        pub join_reorder_dp_threshold: usize, default = 10
+
         /// When set to true, the physical plan optimizer uses the pluggable
         /// `StatisticsRegistry` for statistics propagation across operators.
         /// This enables more accurate cardinality estimates compared to each
@@ -1498,6 +1545,27 @@ config_namespace! {
         /// will be collected into a single partition
         pub hash_join_single_partition_threshold_rows: usize, default = 1024 * 128
 
+        /// When set to true, the physical optimizer re-plans partitioned hash joins during
+        /// execution, once the actual size of their build side is known. A build side that
+        /// turns out to be below `hash_join_single_partition_threshold` and
+        /// `hash_join_single_partition_threshold_rows` is collected into a single partition,
+        /// and its probe side is no longer repartitioned. Otherwise, the partitions of the join
+        /// may be coalesced or split, see `adaptive_execution_partition_size`.
+        pub enable_adaptive_execution: bool, default = false
+
+        /// The target size in bytes of the build side of a partition of a partitioned hash
+        /// join, when `enable_adaptive_execution` is set. If the build side turns out to be
+        /// smaller, the partitions of the join are coalesced into fewer output partitions. A
+        /// partition larger than this size and `adaptive_execution_skew_factor` times the
+        /// median partition is skewed, and its probe side is split into several output
+        /// partitions. Set to 0 to neither coalesce nor split partitions.
+        pub adaptive_execution_partition_size: usize, default = 64 * 1024 * 1024
+
+        /// How many times larger than the median partition of a partitioned hash join a
+        /// partition must be to be skewed, when `enable_adaptive_execution` is set. Set to 0
+        /// to not split skewed partitions.
+        pub adaptive_execution_skew_factor: usize, default = 5
+
         /// Maximum size in bytes for the build side of a hash join to be pushed down as an InList expression for dynamic filtering.
         /// Build sides larger than this will use hash table lookups instead.
         /// Set to 0 to always use hash table lookups.
@@ -1528,6 +1596,25 @@ config_namespace! {
         /// See: <https://trino.io/docs/current/admin/dynamic-filtering.html#dynamic-filter-collection-thresholds>
         pub hash_join_inlist_pushdown_max_distinct_values: usize, default = 150
 
+        /// When set to true, hash joins whose build side is too large to be pushed down as an
+        /// InList expression push down a Bloom filter of the build side join keys instead of a
+        /// hash table lookup for dynamic filtering.
+        ///
+        /// A Bloom filter is smaller and faster to probe than the hash table, but lets through
+        /// some probe rows without a match (see `hash_join_bloom_filter_fpp`).
+        pub hash_join_bloom_filter_pushdown: bool, default = false
+
+        /// The false positive probability of the Bloom filters pushed down by hash joins when
+        /// `hash_join_bloom_filter_pushdown` is enabled.
+        pub hash_join_bloom_filter_fpp: f64, default = 0.01
+
+        /// Maximum number of distinct build side join keys of a single column hash join pushing
+        /// down a Bloom filter to also be pushed down as an InList expression, which lets Parquet
+        /// scans probe the Bloom filters of their row groups with them. The distinct keys must
+        /// also fit in `hash_join_inlist_pushdown_max_size` bytes. Set to 0 to only push down
+        /// the Bloom filter.
+        pub hash_join_bloom_filter_max_probe_values: usize, default = 1000
+
         /// The default filter selectivity used by Filter Statistics
         /// when an exact selectivity cannot be determined. Valid values are
         /// between 0 (no selectivity) and 100 (all rows are selected).
@@ -1568,6 +1655,12 @@ config_namespace! {
         /// branches share the same source and compatible wrapper nodes such as identical
         /// projections or aliases.
         pub enable_unions_to_filter: bool, default = false
+
+        /// When set to true, the logical optimizer will rewrite queries that compute
+        /// the same result as a materialized view, or a roll-up of its aggregates,
+        /// to scan the stored contents of the view instead. Such queries return the
+        /// contents as of the last refresh of the view, even if its inputs changed.
+        pub enable_materialized_view_rewrite: bool, default = false
     }
 }
 
@@ -2474,6 +2567,8 @@ pub enum ConfigFileType {
     #[cfg(feature = "parquet")]
     PARQUET,
     JSON,
+    AVRO,
+    FWF,
 }
 
 /// Represents the configuration options available for handling different table formats within a data processing application.
@@ -2492,6 +2587,12 @@ pub struct TableOptions {
     /// Configuration options for JSON file handling.
     pub json: JsonOptions,
 
+    /// Configuration options for Avro file handling.
+    pub avro: AvroOptions,
+
+    /// Configuration options for fixed-width text file handling.
+    pub fixed_width: FixedWidthOptions,
+
     /// The current file format that the table operations should assume. This option allows
     /// for dynamic switching between the supported file types (e.g., CSV, Parquet, JSON).
     pub current_format: Option<ConfigFileType>,
@@ -2515,11 +2616,15 @@ impl ConfigField for TableOptions {
                 ConfigFileType::PARQUET => self.parquet.visit(v, "format", ""),
                 ConfigFileType::CSV => self.csv.visit(v, "format", ""),
                 ConfigFileType::JSON => self.json.visit(v, "format", ""),
+                ConfigFileType::AVRO => self.avro.visit(v, "format", ""),
+                ConfigFileType::FWF => self.fixed_width.visit(v, "format", ""),
             }
         } else {
             self.csv.visit(v, "csv", "");
             self.parquet.visit(v, "parquet", "");
             self.json.visit(v, "json", "");
+            self.avro.visit(v, "avro", "");
+            self.fixed_width.visit(v, "fwf", "");
         }
     }
 
@@ -2551,6 +2656,8 @@ impl ConfigField for TableOptions {
                     ConfigFileType::PARQUET => self.parquet.set(rem, value),
                     ConfigFileType::CSV => self.csv.set(rem, value),
                     ConfigFileType::JSON => self.json.set(rem, value),
+                    ConfigFileType::AVRO => self.avro.set(rem, value),
+                    ConfigFileType::FWF => self.fixed_width.set(rem, value),
                 }
             }
             _ => _config_err!("Config value \"{key}\" not found on TableOptions"),
@@ -3462,6 +3569,28 @@ config_namespace! {
         /// of columns, it creates a union schema containing all columns found across the files, and will
         /// pad any files missing columns with null values for their rows.
         pub truncated_rows: Option<bool>, default = None
+        /// Whether to detect the dialect of the files when inferring the schema.
+        ///
+        /// When set to true, the delimiter, quote, escape, header, comment lines,
+        /// null tokens and date/timestamp formats are detected from a sample of the
+        /// first file. The detected delimiter and quote replace the configured ones,
+        /// while the other detected settings only apply to options that are not set.
+        /// The detected dialect is recorded in the schema metadata and shown by `DESCRIBE`.
+        pub sniff_dialect: bool, default = false
+        /// The number of bytes sampled from the start of the first file to detect
+        /// the dialect. Defaults to 64 KiB when `None`.
+        pub sniff_sample_size: Option<usize>, default = None
+        /// A delimiter of one or more bytes, such as `||`, replacing `delimiter`
+        /// when reading. Bytes may be given as `\xNN` escapes, and `\t`, `\r`,
+        /// `\n` and `\\` are also recognized, e.g. `\x01\x02`.
+        ///
+        /// Files with a multi-byte delimiter or terminator are not split across
+        /// partitions, and cannot be written.
+        pub delimiter_sequence: Option<String>, default = None
+        /// A record terminator of one or more bytes, such as `\r\n` or `~~`,
+        /// replacing `terminator` when reading. Uses the same escapes as
+        /// `delimiter_sequence`.
+        pub terminator_sequence: Option<String>, default = None
     }
 }
 
@@ -3595,6 +3724,36 @@ impl CsvOptions {
         self
     }
 
+    /// Set true to detect the dialect of the files when inferring the schema.
+    /// - default to false
+    pub fn with_sniff_dialect(mut self, sniff_dialect: bool) -> Self {
+        self.sniff_dialect = sniff_dialect;
+        self
+    }
+
+    /// Set the number of bytes sampled to detect the dialect.
+    /// - default to 64 KiB
+    pub fn with_sniff_sample_size(mut self, sample_size: usize) -> Self {
+        self.sniff_sample_size = Some(sample_size);
+        self
+    }
+
+    /// The sequence of bytes separating values within a row, replacing the
+    /// single byte delimiter when reading.
+    /// - default is None
+    pub fn with_delimiter_sequence(mut self, delimiter: Option<String>) -> Self {
+        self.delimiter_sequence = delimiter;
+        self
+    }
+
+    /// The sequence of bytes that terminates a row, replacing the single
+    /// byte terminator when reading.
+    /// - default is None
+    pub fn with_terminator_sequence(mut self, terminator: Option<String>) -> Self {
+        self.terminator_sequence = terminator;
+        self
+    }
+
     /// The delimiter character.
     pub fn delimiter(&self) -> u8 {
         self.delimiter
@@ -3648,10 +3807,42 @@ config_namespace! {
     }
 }
 
+config_namespace! {
+    /// Options controlling Avro format
+    pub struct AvroOptions {
+        /// Compression codec of the data blocks of written Avro files. Supported
+        /// values are "null" (no compression), "deflate", "snappy", "zstd", "bzip2"
+        /// and "xz". If not specified, data blocks are not compressed.
+        pub compression: Option<String>, default = None
+    }
+}
+
+config_namespace! {
+    /// Options controlling fixed-width text format
+    pub struct FixedWidthOptions {
+        /// The columns of each record, as comma separated `name:start:length`
+        /// specs, where `start` is the 0 based byte offset of the column in the
+        /// record and `length` is its width in bytes, e.g. `id:0:6,name:6:20`.
+        /// Required to infer the schema; when the table is created with a
+        /// schema, every column of the schema must have a spec.
+        pub columns: Option<String>, default = None
+        /// Whether the first record is a header, which is skipped
+        pub has_header: bool, default = false
+        /// Whether spaces around values are trimmed. Values that are empty after
+        /// trimming are read as null.
+        pub trim: bool, default = true
+        /// The length in bytes of each record for files whose records are not
+        /// terminated, such as mainframe extracts. When `None`, records are
+        /// terminated by `\n` or `\r\n`.
+        pub record_length: Option<usize>, default = None
+        pub compression: CompressionTypeVariant, default = CompressionTypeVariant::UNCOMPRESSED
+    }
+}
+
 pub trait OutputFormatExt: Display {}
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "parquet", expect(clippy::large_enum_variant))]
+#[expect(clippy::large_enum_variant)]
 pub enum OutputFormat {
     CSV(CsvOptions),
     JSON(JsonOptions),
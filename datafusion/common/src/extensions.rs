@@ -130,6 +130,13 @@ impl Extensions {
         self.inner.contains_key(&TypeId::of::<T>())
     }
 
+    /// Remove the extension of type `T`, returning it if it was set.
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<Arc<T>> {
+        self.inner
+            .remove(&TypeId::of::<T>())
+            .map(|p| Arc::downcast::<T>(p).expect("TypeId matches T"))
+    }
+
     /// Merge entries from `other` into `self`. Entries in `other` take
     /// precedence over existing entries with the same type.
     pub fn merge(&mut self, other: &Extensions) {
@@ -186,6 +193,11 @@ mod tests {
         let prev = ext.insert(A(2));
         assert_eq!(prev.as_deref(), Some(&A(1)));
         assert_eq!(ext.get::<A>(), Some(&A(2)));
+
+        let removed = ext.remove::<A>();
+        assert_eq!(removed.as_deref(), Some(&A(2)));
+        assert!(!ext.contains::<A>());
+        assert!(ext.remove::<A>().is_none());
     }
 
     #[test]
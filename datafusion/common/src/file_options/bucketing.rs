@@ -0,0 +1,508 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Hash bucketing of the files of a table
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use arrow::array::{Array, ArrayRef, ArrowPrimitiveType, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::*;
+
+use crate::Result;
+use crate::error::{_not_impl_err, _plan_datafusion_err, _plan_err, DataFusionError};
+
+/// Hash bucketing of the files of a table, declared with
+/// `CLUSTERED BY (<columns>) INTO <num_buckets> BUCKETS`.
+///
+/// Each file of a bucketed table only contains the rows of a single bucket,
+/// computed with the [`BucketHash`] of the table. The hash is recorded in the
+/// name of each file, see [`Self::file_name`], and files written with another
+/// hash are rejected when the table is read. Scans of the table read each
+/// bucket as one partition, which lets joins of tables bucketed the same way
+/// skip repartitioning.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd)]
+pub struct BucketSpec {
+    /// The columns hashed to compute the bucket of a row
+    pub columns: Vec<String>,
+    /// The number of buckets
+    pub num_buckets: usize,
+    /// The hash computing the bucket of a row
+    pub hash: BucketHash,
+}
+
+impl BucketSpec {
+    /// Create a bucketing on `columns` into `num_buckets` buckets with the
+    /// default [`BucketHash`], failing if there are no columns or buckets
+    pub fn try_new(columns: Vec<String>, num_buckets: usize) -> Result<Self> {
+        if columns.is_empty() {
+            return _plan_err!("Bucketing requires at least one column");
+        }
+        if num_buckets == 0 {
+            return _plan_err!("The number of buckets must be greater than 0");
+        }
+        Ok(Self {
+            columns,
+            num_buckets,
+            hash: BucketHash::default(),
+        })
+    }
+
+    /// Set the hash computing the bucket of a row
+    pub fn with_hash(mut self, hash: BucketHash) -> Self {
+        self.hash = hash;
+        self
+    }
+
+    /// Computes the bucket of each row from the values of the bucket columns
+    pub fn bucket_ids(&self, arrays: &[ArrayRef], num_rows: usize) -> Result<Vec<usize>> {
+        bucket_ids(self.hash, arrays, num_rows, self.num_buckets)
+    }
+
+    /// The name of the file of `bucket` written by the write with `write_id`,
+    /// which records the hash of the bucketing
+    pub fn file_name(
+        &self,
+        write_id: &str,
+        bucket: usize,
+        file_extension: &str,
+    ) -> String {
+        format!(
+            "{write_id}_{}_bucket_{bucket:05}.{file_extension}",
+            self.hash
+        )
+    }
+
+    /// The bucket of a file named by [`Self::file_name`], or `None` if the
+    /// name has no bucket or it is not a bucket of this bucketing.
+    ///
+    /// Fails if the file was not written with the hash of this bucketing, as
+    /// its rows may then belong to other buckets.
+    pub fn bucket_of_file(&self, file_name: &str) -> Result<Option<usize>> {
+        let Some((prefix, suffix)) = file_name.rsplit_once("_bucket_") else {
+            return Ok(None);
+        };
+        let (bucket, _) = suffix.split_once('.').unwrap_or((suffix, ""));
+        let Some(bucket) = bucket
+            .parse::<usize>()
+            .ok()
+            .filter(|bucket| *bucket < self.num_buckets)
+        else {
+            return Ok(None);
+        };
+        match prefix.rsplit_once('_') {
+            Some((_, hash)) if hash == self.hash.to_string() => Ok(Some(bucket)),
+            _ => _plan_err!(
+                "File {file_name} was not written with the {} bucket hash of the table",
+                self.hash
+            ),
+        }
+    }
+}
+
+impl Display for BucketSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CLUSTERED BY ({}) INTO {} BUCKETS",
+            self.columns.join(", "),
+            self.num_buckets
+        )
+    }
+}
+
+/// The hash computing the bucket of a row of a bucketed table.
+///
+/// Unlike the hashes used for hash repartitioning, which may change between
+/// releases, a bucket hash is fully specified, as it decides the files the
+/// rows of a table are written to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd)]
+pub enum BucketHash {
+    /// The 32 bit x86 variant of MurmurHash3, seeded with 42 like the
+    /// bucketing of Spark.
+    ///
+    /// The columns of a row are hashed in order, each seeded with the hash of
+    /// the previous column, and null values leave the hash unchanged:
+    ///
+    /// - booleans (as 0 or 1), integers of up to 32 bits, `Date32` and
+    ///   `Time32` values are hashed as 4 byte integers
+    /// - 64 bit integers, `Date64`, `Time64`, `Timestamp` and `Duration`
+    ///   values are hashed as 8 byte integers
+    /// - floats are hashed as the bits of their value, with `-0.0` hashed as
+    ///   `0.0`
+    /// - `Decimal128` values are hashed as the 16 little endian bytes of
+    ///   their unscaled value
+    /// - strings and binaries are hashed as their bytes
+    /// - dictionaries are hashed as their values
+    ///
+    /// The bucket of a row is the hash, as a signed 32 bit integer, modulo
+    /// the number of buckets, made non negative.
+    #[default]
+    Murmur3,
+}
+
+impl Display for BucketHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Murmur3 => write!(f, "murmur3"),
+        }
+    }
+}
+
+impl FromStr for BucketHash {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "murmur3" => Ok(Self::Murmur3),
+            _ => Err(_plan_datafusion_err!("Unknown bucket hash {s}")),
+        }
+    }
+}
+
+const MURMUR3_SEED: u32 = 42;
+
+fn bucket_ids(
+    hash: BucketHash,
+    arrays: &[ArrayRef],
+    num_rows: usize,
+    num_buckets: usize,
+) -> Result<Vec<usize>> {
+    let mut hashes = match hash {
+        BucketHash::Murmur3 => vec![MURMUR3_SEED; num_rows],
+    };
+    for array in arrays {
+        murmur3_update(array, &mut hashes)?;
+    }
+    let num_buckets = num_buckets as i64;
+    Ok(hashes
+        .into_iter()
+        .map(|hash| i64::from(hash as i32).rem_euclid(num_buckets) as usize)
+        .collect())
+}
+
+/// Updates the Murmur3 `hashes` of the rows with the values of `array`
+fn murmur3_update(array: &ArrayRef, hashes: &mut [u32]) -> Result<()> {
+    fn update_primitive<T: ArrowPrimitiveType>(
+        array: &dyn Array,
+        hashes: &mut [u32],
+        hash: impl Fn(T::Native, u32) -> u32,
+    ) {
+        let array = array.as_primitive::<T>();
+        for (i, h) in hashes.iter_mut().enumerate() {
+            if array.is_valid(i) {
+                *h = hash(array.value(i), *h);
+            }
+        }
+    }
+
+    fn update_bytes<'a>(
+        values: impl Iterator<Item = Option<&'a [u8]>>,
+        hashes: &mut [u32],
+    ) {
+        for (value, h) in values.zip(hashes.iter_mut()) {
+            if let Some(value) = value {
+                *h = murmur3::hash_bytes(value, *h);
+            }
+        }
+    }
+
+    let int = |v: i32, h| murmur3::hash_int(v, h);
+    let long = |v: i64, h| murmur3::hash_long(v, h);
+    let array = array.as_ref();
+    match array.data_type() {
+        DataType::Boolean => {
+            let array = array.as_boolean();
+            for (i, h) in hashes.iter_mut().enumerate() {
+                if array.is_valid(i) {
+                    *h = int(array.value(i) as i32, *h);
+                }
+            }
+        }
+        DataType::Int8 => {
+            update_primitive::<Int8Type>(array, hashes, |v, h| int(v.into(), h))
+        }
+        DataType::Int16 => {
+            update_primitive::<Int16Type>(array, hashes, |v, h| int(v.into(), h))
+        }
+        DataType::Int32 => update_primitive::<Int32Type>(array, hashes, int),
+        DataType::UInt8 => {
+            update_primitive::<UInt8Type>(array, hashes, |v, h| int(v.into(), h))
+        }
+        DataType::UInt16 => {
+            update_primitive::<UInt16Type>(array, hashes, |v, h| int(v.into(), h))
+        }
+        DataType::UInt32 => {
+            update_primitive::<UInt32Type>(array, hashes, |v, h| int(v as i32, h))
+        }
+        DataType::Date32 => update_primitive::<Date32Type>(array, hashes, int),
+        DataType::Time32(TimeUnit::Second) => {
+            update_primitive::<Time32SecondType>(array, hashes, int)
+        }
+        DataType::Time32(_) => {
+            update_primitive::<Time32MillisecondType>(array, hashes, int)
+        }
+        DataType::Int64 => update_primitive::<Int64Type>(array, hashes, long),
+        DataType::UInt64 => {
+            update_primitive::<UInt64Type>(array, hashes, |v, h| long(v as i64, h))
+        }
+        DataType::Date64 => update_primitive::<Date64Type>(array, hashes, long),
+        DataType::Time64(TimeUnit::Microsecond) => {
+            update_primitive::<Time64MicrosecondType>(array, hashes, long)
+        }
+        DataType::Time64(_) => {
+            update_primitive::<Time64NanosecondType>(array, hashes, long)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            update_primitive::<TimestampSecondType>(array, hashes, long)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            update_primitive::<TimestampMillisecondType>(array, hashes, long)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            update_primitive::<TimestampMicrosecondType>(array, hashes, long)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            update_primitive::<TimestampNanosecondType>(array, hashes, long)
+        }
+        DataType::Duration(TimeUnit::Second) => {
+            update_primitive::<DurationSecondType>(array, hashes, long)
+        }
+        DataType::Duration(TimeUnit::Millisecond) => {
+            update_primitive::<DurationMillisecondType>(array, hashes, long)
+        }
+        DataType::Duration(TimeUnit::Microsecond) => {
+            update_primitive::<DurationMicrosecondType>(array, hashes, long)
+        }
+        DataType::Duration(TimeUnit::Nanosecond) => {
+            update_primitive::<DurationNanosecondType>(array, hashes, long)
+        }
+        DataType::Float32 => update_primitive::<Float32Type>(array, hashes, |v, h| {
+            let v = if v == 0.0 { 0.0 } else { v };
+            int(v.to_bits() as i32, h)
+        }),
+        DataType::Float64 => update_primitive::<Float64Type>(array, hashes, |v, h| {
+            let v = if v == 0.0 { 0.0 } else { v };
+            long(v.to_bits() as i64, h)
+        }),
+        DataType::Decimal128(_, _) => {
+            update_primitive::<Decimal128Type>(array, hashes, |v, h| {
+                murmur3::hash_bytes(&v.to_le_bytes(), h)
+            })
+        }
+        DataType::Utf8 => update_bytes(
+            array
+                .as_string::<i32>()
+                .iter()
+                .map(|v| v.map(str::as_bytes)),
+            hashes,
+        ),
+        DataType::LargeUtf8 => update_bytes(
+            array
+                .as_string::<i64>()
+                .iter()
+                .map(|v| v.map(str::as_bytes)),
+            hashes,
+        ),
+        DataType::Utf8View => update_bytes(
+            array.as_string_view().iter().map(|v| v.map(str::as_bytes)),
+            hashes,
+        ),
+        DataType::Binary => update_bytes(array.as_binary::<i32>().iter(), hashes),
+        DataType::LargeBinary => update_bytes(array.as_binary::<i64>().iter(), hashes),
+        DataType::BinaryView => update_bytes(array.as_binary_view().iter(), hashes),
+        DataType::FixedSizeBinary(_) => {
+            update_bytes(array.as_fixed_size_binary().iter(), hashes)
+        }
+        DataType::Dictionary(_, value_type) => {
+            murmur3_update(&cast(array, value_type)?, hashes)?
+        }
+        data_type => {
+            return _not_impl_err!("Bucketing by {data_type} columns is not supported");
+        }
+    }
+    Ok(())
+}
+
+/// The 32 bit x86 variant of MurmurHash3
+mod murmur3 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    fn mix_k1(k1: u32) -> u32 {
+        k1.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2)
+    }
+
+    fn mix_h1(h1: u32, k1: u32) -> u32 {
+        (h1 ^ k1)
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64)
+    }
+
+    fn fmix(mut h1: u32, length: u32) -> u32 {
+        h1 ^= length;
+        h1 ^= h1 >> 16;
+        h1 = h1.wrapping_mul(0x85eb_ca6b);
+        h1 ^= h1 >> 13;
+        h1 = h1.wrapping_mul(0xc2b2_ae35);
+        h1 ^ (h1 >> 16)
+    }
+
+    pub(super) fn hash_int(value: i32, seed: u32) -> u32 {
+        fmix(mix_h1(seed, mix_k1(value as u32)), 4)
+    }
+
+    pub(super) fn hash_long(value: i64, seed: u32) -> u32 {
+        let h1 = mix_h1(seed, mix_k1(value as u32));
+        let h1 = mix_h1(h1, mix_k1((value >> 32) as u32));
+        fmix(h1, 8)
+    }
+
+    pub(super) fn hash_bytes(bytes: &[u8], seed: u32) -> u32 {
+        let chunks = bytes.chunks_exact(4);
+        let tail = chunks.remainder();
+        let mut h1 = seed;
+        for chunk in chunks {
+            let k1 = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            h1 = mix_h1(h1, mix_k1(k1));
+        }
+        if !tail.is_empty() {
+            let k1 = tail
+                .iter()
+                .rev()
+                .fold(0, |k1, byte| (k1 << 8) | u32::from(*byte));
+            h1 ^= mix_k1(k1);
+        }
+        fmix(h1, bytes.len() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{
+        DictionaryArray, Float64Array, Int32Array, NullArray, StringArray,
+        StringViewArray,
+    };
+
+    use super::*;
+
+    #[test]
+    fn bucket_file_names() -> Result<()> {
+        let spec = BucketSpec::try_new(vec!["a".to_string()], 4)?;
+        let name = spec.file_name("abc", 3, "parquet");
+        assert_eq!(name, "abc_murmur3_bucket_00003.parquet");
+        assert_eq!(spec.bucket_of_file(&name)?, Some(3));
+        assert_eq!(
+            spec.bucket_of_file("abc_murmur3_bucket_00003.csv.gz")?,
+            Some(3)
+        );
+        assert_eq!(
+            spec.bucket_of_file("abc_murmur3_bucket_00004.parquet")?,
+            None
+        );
+        assert_eq!(spec.bucket_of_file("abc_3.parquet")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn bucket_files_of_other_hashes_are_rejected() {
+        let spec = BucketSpec::try_new(vec!["a".to_string()], 4).unwrap();
+        let err = spec
+            .bucket_of_file("abc_bucket_00003.parquet")
+            .unwrap_err()
+            .strip_backtrace();
+        assert_eq!(
+            err,
+            "Error during planning: File abc_bucket_00003.parquet was not written with the murmur3 bucket hash of the table"
+        );
+        assert!(
+            spec.bucket_of_file("abc_xxhash_bucket_00003.parquet")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn murmur3_hashes() {
+        // Reference values of the 32 bit x86 variant of MurmurHash3
+        assert_eq!(murmur3::hash_bytes(b"", 0), 0);
+        assert_eq!(murmur3::hash_bytes(b"", 1), 0x514e_28b7);
+        assert_eq!(murmur3::hash_bytes(b"test", 0), 0xba6b_d213);
+        assert_eq!(murmur3::hash_bytes(b"Hello, world!", 1234), 0xfaf6_cdb3);
+        assert_eq!(
+            murmur3::hash_bytes(b"The quick brown fox jumps over the lazy dog", 0),
+            0x2e4f_f723
+        );
+        // Integers are hashed as their little endian bytes
+        assert_eq!(
+            murmur3::hash_int(42, MURMUR3_SEED),
+            murmur3::hash_bytes(&42i32.to_le_bytes(), MURMUR3_SEED)
+        );
+        assert_eq!(
+            murmur3::hash_long(-42, MURMUR3_SEED),
+            murmur3::hash_bytes(&(-42i64).to_le_bytes(), MURMUR3_SEED)
+        );
+    }
+
+    #[test]
+    fn bucket_ids_are_stable() -> Result<()> {
+        let spec = BucketSpec::try_new(vec!["a".to_string(), "b".to_string()], 4)?;
+        let a: ArrayRef =
+            Arc::new(Int32Array::from(vec![Some(1), Some(2), None, Some(4)]));
+        let b: ArrayRef = Arc::new(StringArray::from(vec!["x", "y", "z", "w"]));
+        // The buckets of rows must not change between releases
+        assert_eq!(spec.bucket_ids(&[a, b], 4)?, vec![3, 1, 0, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn bucket_ids_of_equal_values() -> Result<()> {
+        let spec = BucketSpec::try_new(vec!["a".to_string()], 8)?;
+        let utf8: ArrayRef = Arc::new(StringArray::from(vec!["x", "yy", "zzzzz"]));
+        let utf8_view: ArrayRef =
+            Arc::new(StringViewArray::from(vec!["x", "yy", "zzzzz"]));
+        let dictionary: ArrayRef = Arc::new(
+            vec!["x", "yy", "zzzzz"]
+                .into_iter()
+                .collect::<DictionaryArray<Int8Type>>(),
+        );
+        let ids = spec.bucket_ids(&[utf8], 3)?;
+        assert_eq!(ids, spec.bucket_ids(&[utf8_view], 3)?);
+        assert_eq!(ids, spec.bucket_ids(&[dictionary], 3)?);
+
+        let zeros: ArrayRef = Arc::new(Float64Array::from(vec![0.0, -0.0]));
+        let ids = spec.bucket_ids(&[zeros], 2)?;
+        assert_eq!(ids[0], ids[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_bucket_column() {
+        let spec = BucketSpec::try_new(vec!["a".to_string()], 4).unwrap();
+        let array: ArrayRef = Arc::new(NullArray::new(1));
+        assert!(spec.bucket_ids(&[array], 1).is_err());
+    }
+
+    #[test]
+    fn invalid_bucket_spec() {
+        assert!(BucketSpec::try_new(vec![], 4).is_err());
+        assert!(BucketSpec::try_new(vec!["a".to_string()], 0).is_err());
+    }
+}
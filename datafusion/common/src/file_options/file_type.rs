@@ -26,8 +26,12 @@ pub const DEFAULT_ARROW_EXTENSION: &str = ".arrow";
 pub const DEFAULT_AVRO_EXTENSION: &str = ".avro";
 /// The default file extension of csv files
 pub const DEFAULT_CSV_EXTENSION: &str = ".csv";
+/// The default file extension of fixed-width text files
+pub const DEFAULT_FWF_EXTENSION: &str = ".fwf";
 /// The default file extension of json files
 pub const DEFAULT_JSON_EXTENSION: &str = ".json";
+/// The default file extension of orc files
+pub const DEFAULT_ORC_EXTENSION: &str = ".orc";
 /// The default file extension of parquet files
 pub const DEFAULT_PARQUET_EXTENSION: &str = ".parquet";
 
@@ -19,6 +19,7 @@
 
 pub mod arrow_writer;
 pub mod avro_writer;
+pub mod bucketing;
 pub mod csv_writer;
 pub mod file_type;
 pub mod json_writer;
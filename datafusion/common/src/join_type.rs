@@ -72,6 +72,14 @@ pub enum JoinType {
     /// Same logic as the LeftMark Join above, however it returns a record for each record from the
     /// right input.
     RightMark,
+    /// As-of Join
+    ///
+    /// Returns one record for each record from the left input, joined with the single closest
+    /// record from the right input that has equal join keys and satisfies the inequality match
+    /// condition stored in the join filter (for example `left.ts >= right.ts` picks the latest
+    /// right row at or before each left row). If there is no such right row, NULL values are
+    /// returned for the columns from the right table.
+    AsOf,
 }
 
 impl JoinType {
@@ -81,9 +89,20 @@ impl JoinType {
 
     /// Returns the `JoinType` if the (2) inputs were swapped
     ///
-    /// Panics if [`Self::supports_swap`] returns false
+    /// Panics if [`Self::supports_swap`] returns false, see [`Self::try_swap`]
+    /// for a fallible version
     pub fn swap(&self) -> JoinType {
-        match self {
+        match self.try_swap() {
+            Ok(join_type) => join_type,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Returns the `JoinType` if the (2) inputs were swapped
+    ///
+    /// Returns an error if [`Self::supports_swap`] returns false
+    pub fn try_swap(&self) -> Result<JoinType> {
+        Ok(match self {
             JoinType::Inner => JoinType::Inner,
             JoinType::Full => JoinType::Full,
             JoinType::Left => JoinType::Right,
@@ -94,7 +113,10 @@ impl JoinType {
             JoinType::RightAnti => JoinType::LeftAnti,
             JoinType::LeftMark => JoinType::RightMark,
             JoinType::RightMark => JoinType::LeftMark,
-        }
+            JoinType::AsOf => {
+                return _not_impl_err!("As-of joins do not support swapping inputs");
+            }
+        })
     }
 
     /// Whether each side of the join is preserved for ON-clause filter pushdown.
@@ -123,6 +145,9 @@ impl JoinType {
             JoinType::RightAnti => (true, false),
             JoinType::LeftMark => (false, true),
             JoinType::RightMark => (true, false),
+            // The match condition selects a single right row, so filtering
+            // either input before the join changes which row is picked.
+            JoinType::AsOf => (false, false),
         }
     }
 
@@ -154,6 +179,7 @@ impl JoinType {
                 | JoinType::LeftAnti
                 | JoinType::LeftMark
                 | JoinType::RightSemi
+                | JoinType::AsOf
         )
     }
 }
@@ -171,6 +197,7 @@ impl Display for JoinType {
             JoinType::RightAnti => "RightAnti",
             JoinType::LeftMark => "LeftMark",
             JoinType::RightMark => "RightMark",
+            JoinType::AsOf => "AsOf",
         };
         write!(f, "{join_type}")
     }
@@ -192,6 +219,7 @@ impl FromStr for JoinType {
             "RIGHTANTI" => Ok(JoinType::RightAnti),
             "LEFTMARK" => Ok(JoinType::LeftMark),
             "RIGHTMARK" => Ok(JoinType::RightMark),
+            "ASOF" => Ok(JoinType::AsOf),
             _ => _not_impl_err!("The join type {s} does not exist or is not implemented"),
         }
     }
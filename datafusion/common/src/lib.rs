@@ -37,6 +37,7 @@ mod unnest;
 
 pub mod alias;
 pub mod cast;
+pub mod collation;
 pub mod config;
 pub mod cse;
 pub mod datatype;
@@ -81,7 +82,8 @@ pub use error::{
 };
 pub use file_options::file_type::{
     DEFAULT_ARROW_EXTENSION, DEFAULT_AVRO_EXTENSION, DEFAULT_CSV_EXTENSION,
-    DEFAULT_JSON_EXTENSION, DEFAULT_PARQUET_EXTENSION, GetExt,
+    DEFAULT_FWF_EXTENSION, DEFAULT_JSON_EXTENSION, DEFAULT_ORC_EXTENSION,
+    DEFAULT_PARQUET_EXTENSION, GetExt,
 };
 pub use functional_dependencies::{
     Constraint, Constraints, Dependency, FunctionalDependence, FunctionalDependencies,
@@ -58,8 +58,8 @@ use datafusion_common::{
 };
 use datafusion_expr::select_expr::SelectExpr;
 use datafusion_expr::{
-    ExplainOption, ScalarUDF, SortExpr, TableProviderFilterPushDown, UNNAMED_TABLE, case,
-    dml::InsertOp, is_null, lit, utils::COUNT_STAR_EXPANSION,
+    ExplainOption, SampleMethod, ScalarUDF, SortExpr, TableProviderFilterPushDown,
+    UNNAMED_TABLE, case, dml::InsertOp, is_null, lit, utils::COUNT_STAR_EXPANSION,
 };
 use datafusion_functions::core::coalesce;
 use datafusion_functions::math::nanvl;
@@ -690,6 +690,107 @@ impl DataFrame {
         })
     }
 
+    /// Return a new `DataFrame` with one column per value in `pivot_values`
+    /// of `pivot_column`, holding the result of the aggregates in `aggr_expr`
+    /// for the rows with that value. The remaining columns are used as
+    /// grouping keys.
+    ///
+    /// This is the equivalent of `PIVOT (<aggr_expr> FOR <pivot_column> IN (<pivot_values>))`,
+    /// see [`LogicalPlanBuilder::pivot`] for how the columns are named.
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::functions_aggregate::expr_fn::sum;
+    /// # use datafusion_common::assert_batches_sorted_eq;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx
+    ///     .read_csv("tests/data/example_long.csv", CsvReadOptions::new())
+    ///     .await?;
+    /// // The following use is the equivalent of
+    /// // "SELECT * FROM (SELECT a, b FROM t) PIVOT (sum(b) FOR a IN (1, 4))"
+    /// let df = df
+    ///     .select_columns(&["a", "b"])?
+    ///     .pivot(vec![sum(col("b"))], col("a"), vec![lit(1), lit(4)])?;
+    /// let expected = vec![
+    ///     "+---+---+",
+    ///     "| 1 | 4 |",
+    ///     "+---+---+",
+    ///     "| 2 | 5 |",
+    ///     "+---+---+",
+    /// ];
+    /// # assert_batches_sorted_eq!(expected, &df.collect().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pivot(
+        self,
+        aggr_expr: Vec<Expr>,
+        pivot_column: Expr,
+        pivot_values: Vec<Expr>,
+    ) -> Result<DataFrame> {
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .pivot(aggr_expr, pivot_column, pivot_values)?
+            .build()?;
+        Ok(DataFrame {
+            session_state: self.session_state,
+            plan,
+            projection_requires_validation: true,
+        })
+    }
+
+    /// Return a new `DataFrame` with one row per input row and column in
+    /// `columns`, holding the name of the column in `name_column` and its
+    /// value in `value_column`. The other columns are kept unchanged.
+    ///
+    /// Rows where the value is NULL are removed unless `include_nulls` is
+    /// true. This is the equivalent of
+    /// `UNPIVOT [INCLUDE NULLS] (<value_column> FOR <name_column> IN (<columns>))`.
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion_common::assert_batches_sorted_eq;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx
+    ///     .read_csv("tests/data/example.csv", CsvReadOptions::new())
+    ///     .await?;
+    /// let df = df.unpivot("value", "name", vec![col("b"), col("c")], false)?;
+    /// let expected = vec![
+    ///     "+---+------+-------+",
+    ///     "| a | name | value |",
+    ///     "+---+------+-------+",
+    ///     "| 1 | b    | 2     |",
+    ///     "| 1 | c    | 3     |",
+    ///     "+---+------+-------+",
+    /// ];
+    /// # assert_batches_sorted_eq!(expected, &df.collect().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unpivot(
+        self,
+        value_column: &str,
+        name_column: &str,
+        columns: Vec<Expr>,
+        include_nulls: bool,
+    ) -> Result<DataFrame> {
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .unpivot(value_column, name_column, columns, include_nulls)?
+            .build()?;
+        Ok(DataFrame {
+            session_state: self.session_state,
+            plan,
+            projection_requires_validation: true,
+        })
+    }
+
     /// Returns a new `DataFrame` with a limited number of rows.
     ///
     /// # Arguments
@@ -731,6 +832,45 @@ impl DataFrame {
         })
     }
 
+    /// Returns a new `DataFrame` with a random sample of the rows, where each
+    /// row is included independently with probability `fraction`.
+    ///
+    /// This is equivalent to SQL `TABLESAMPLE BERNOULLI (fraction * 100)`.
+    ///
+    /// # Arguments
+    /// `fraction` - Probability of including each row, between 0 and 1.
+    /// `seed` - Seed used to make the sample repeatable. If `None`, a
+    /// different sample is returned each time the `DataFrame` is executed.
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx
+    ///     .read_csv("tests/data/example_long.csv", CsvReadOptions::new())
+    ///     .await?;
+    /// let sample = df.clone().sample(0.5, Some(42))?;
+    /// let num_rows = sample.clone().count().await?;
+    /// assert!(num_rows <= df.count().await?);
+    /// // The same seed returns the same sample
+    /// assert_eq!(sample.count().await?, num_rows);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sample(self, fraction: f64, seed: Option<u64>) -> Result<DataFrame> {
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .sample(SampleMethod::Bernoulli(fraction), seed)?
+            .build()?;
+        Ok(DataFrame {
+            session_state: self.session_state,
+            plan,
+            projection_requires_validation: self.projection_requires_validation,
+        })
+    }
+
     /// Calculate the union of two [`DataFrame`]s, preserving duplicate rows.
     ///
     /// The two [`DataFrame`]s must have exactly the same schema
@@ -1379,6 +1519,74 @@ impl DataFrame {
         })
     }
 
+    /// Join this `DataFrame` with another `DataFrame` using an as-of join.
+    ///
+    /// Each row of this `DataFrame` is joined with the single row of `right`
+    /// that has equal `left_cols` / `right_cols` values and is closest to it
+    /// according to `match_condition`, or with NULLs if there is no such row.
+    /// The `match_condition` must compare a column of this `DataFrame` with a
+    /// column of `right` using `<`, `<=`, `>` or `>=`. For example
+    /// `col("trade_ts").gt_eq(col("quote_ts"))` picks the latest quote at or
+    /// before each trade.
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion_common::assert_batches_sorted_eq;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let left = ctx
+    ///     .read_csv("tests/data/example.csv", CsvReadOptions::new())
+    ///     .await?;
+    /// let right = ctx
+    ///     .read_csv("tests/data/example.csv", CsvReadOptions::new())
+    ///     .await?
+    ///     .select(vec![
+    ///         col("a").alias("a2"),
+    ///         col("b").alias("b2"),
+    ///         col("c").alias("c2"),
+    ///     ])?;
+    /// // Perform the equivalent of
+    /// // `left ASOF JOIN right MATCH_CONDITION (b >= b2) ON a = a2`
+    /// let join = left.join_asof(right, &["a"], &["a2"], col("b").gt_eq(col("b2")))?;
+    /// let expected = vec![
+    ///     "+---+---+---+----+----+----+",
+    ///     "| a | b | c | a2 | b2 | c2 |",
+    ///     "+---+---+---+----+----+----+",
+    ///     "| 1 | 2 | 3 | 1  | 2  | 3  |",
+    ///     "+---+---+---+----+----+----+",
+    /// ];
+    /// assert_batches_sorted_eq!(expected, &join.collect().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn join_asof(
+        self,
+        right: DataFrame,
+        left_cols: &[&str],
+        right_cols: &[&str],
+        match_condition: Expr,
+    ) -> Result<DataFrame> {
+        let left_keys: Vec<Expr> = left_cols
+            .iter()
+            .map(|c| Expr::Column(Column::from(*c)))
+            .collect();
+        let right_keys: Vec<Expr> = right_cols
+            .iter()
+            .map(|c| Expr::Column(Column::from(*c)))
+            .collect();
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .join_asof(right.plan, (left_keys, right_keys), match_condition)?
+            .build()?;
+        Ok(DataFrame {
+            session_state: self.session_state,
+            plan,
+            projection_requires_validation: true,
+        })
+    }
+
     /// Repartition a DataFrame based on a logical partitioning scheme.
     ///
     /// # Example
@@ -15,8 +15,10 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Re-exports the [`datafusion_datasource_csv::file_format`] module, and contains tests for it.
+//! Re-exports the [`datafusion_datasource_csv::file_format`] and
+//! [`datafusion_datasource_csv::sniff`] modules, and contains tests for them.
 pub use datafusion_datasource_csv::file_format::*;
+pub use datafusion_datasource_csv::sniff::*;
 
 #[cfg(test)]
 mod tests {
@@ -20,11 +20,15 @@
 
 pub mod arrow;
 pub mod csv;
+pub mod fixed_width;
 pub mod json;
 
 #[cfg(feature = "avro")]
 pub mod avro;
 
+#[cfg(feature = "orc")]
+pub mod orc;
+
 #[cfg(feature = "parquet")]
 pub mod parquet;
 
@@ -96,6 +96,17 @@ pub struct CsvReadOptions<'a> {
     /// When set to true then it will allow records with less than the expected number of columns and fill the missing columns with nulls.
     /// If the record’s schema is not nullable, then it will still return an error.
     pub truncated_rows: bool,
+    /// Whether to detect the dialect of the files when inferring the schema.
+    /// When enabled, the detected header setting replaces `has_header` and the
+    /// detected delimiter and quote replace `delimiter` and `quote`.
+    /// Defaults to false.
+    pub sniff_dialect: bool,
+    /// An optional delimiter of one or more bytes, replacing `delimiter`.
+    /// Defaults to None.
+    pub delimiter_sequence: Option<String>,
+    /// An optional terminator of one or more bytes, replacing `terminator`.
+    /// Defaults to None.
+    pub terminator_sequence: Option<String>,
 }
 
 impl Default for CsvReadOptions<'_> {
@@ -123,6 +134,9 @@ impl<'a> CsvReadOptions<'a> {
             comment: None,
             null_regex: None,
             truncated_rows: false,
+            sniff_dialect: false,
+            delimiter_sequence: None,
+            terminator_sequence: None,
         }
     }
 
@@ -156,6 +170,22 @@ impl<'a> CsvReadOptions<'a> {
         self
     }
 
+    /// Specify a delimiter of one or more bytes, such as `||`, to use for
+    /// CSV read. See [`CsvOptions::delimiter_sequence`] for the escapes that
+    /// are recognized.
+    ///
+    /// [`CsvOptions::delimiter_sequence`]: datafusion_common::config::CsvOptions::delimiter_sequence
+    pub fn delimiter_sequence(mut self, delimiter: impl Into<String>) -> Self {
+        self.delimiter_sequence = Some(delimiter.into());
+        self
+    }
+
+    /// Specify a terminator of one or more bytes to use for CSV read
+    pub fn terminator_sequence(mut self, terminator: impl Into<String>) -> Self {
+        self.terminator_sequence = Some(terminator.into());
+        self
+    }
+
     /// Specify delimiter to use for CSV read
     pub fn escape(mut self, escape: u8) -> Self {
         self.escape = Some(escape);
@@ -238,6 +268,13 @@ impl<'a> CsvReadOptions<'a> {
         self.truncated_rows = truncated_rows;
         self
     }
+
+    /// Configure whether to detect the dialect of the files when inferring
+    /// the schema
+    pub fn sniff_dialect(mut self, sniff_dialect: bool) -> Self {
+        self.sniff_dialect = sniff_dialect;
+        self
+    }
 }
 
 /// Options that control the reading of Parquet files.
@@ -623,9 +660,12 @@ impl ReadOptions<'_> for CsvReadOptions<'_> {
         config: &SessionConfig,
         table_options: TableOptions,
     ) -> ListingOptions {
-        let file_format = CsvFormat::default()
-            .with_options(table_options.csv)
-            .with_has_header(self.has_header)
+        let mut file_format = CsvFormat::default().with_options(table_options.csv);
+        // Leave the header unset so that the detected one applies
+        if !self.sniff_dialect {
+            file_format = file_format.with_has_header(self.has_header);
+        }
+        let file_format = file_format
             .with_comment(self.comment)
             .with_delimiter(self.delimiter)
             .with_quote(self.quote)
@@ -635,7 +675,10 @@ impl ReadOptions<'_> for CsvReadOptions<'_> {
             .with_schema_infer_max_rec(self.schema_infer_max_records)
             .with_file_compression_type(self.file_compression_type.to_owned())
             .with_null_regex(self.null_regex.clone())
-            .with_truncated_rows(self.truncated_rows);
+            .with_truncated_rows(self.truncated_rows)
+            .with_sniff_dialect(self.sniff_dialect)
+            .with_delimiter_sequence(self.delimiter_sequence.clone())
+            .with_terminator_sequence(self.terminator_sequence.clone());
 
         ListingOptions::new(Arc::new(file_format))
             .with_file_extension(self.file_extension)
@@ -738,7 +781,7 @@ impl ReadOptions<'_> for AvroReadOptions<'_> {
         config: &SessionConfig,
         _table_options: TableOptions,
     ) -> ListingOptions {
-        let file_format = AvroFormat;
+        let file_format = AvroFormat::default();
 
         ListingOptions::new(Arc::new(file_format))
             .with_file_extension(self.file_extension)
@@ -1546,6 +1546,7 @@ mod tests {
             keep_partition_by_columns: false,
             file_extension: "parquet".into(),
             file_output_mode: FileOutputMode::Automatic,
+            bucket_spec: None,
         };
         let parquet_sink = Arc::new(ParquetSink::new(
             file_sink_config,
@@ -1638,6 +1639,7 @@ mod tests {
             keep_partition_by_columns: false,
             file_extension: "parquet".into(),
             file_output_mode: FileOutputMode::Automatic,
+            bucket_spec: None,
         };
         let parquet_sink = Arc::new(ParquetSink::new(
             file_sink_config,
@@ -1729,6 +1731,7 @@ mod tests {
                 keep_partition_by_columns: false,
                 file_extension: "parquet".into(),
                 file_output_mode: FileOutputMode::Automatic,
+                bucket_spec: None,
             };
             let parquet_sink = Arc::new(ParquetSink::new(
                 file_sink_config,
@@ -190,15 +190,43 @@ impl TableProviderFactory for ListingTableFactory {
             }
             Some(s) => s,
         };
+        if let Some(bucket_spec) = &cmd.bucket_spec {
+            for column in &bucket_spec.columns {
+                if resolved_schema.field_with_name(column).is_err()
+                    && !options
+                        .table_partition_cols
+                        .iter()
+                        .any(|(c, _)| c == column)
+                {
+                    return plan_err!("Bucket column {column} is not in schema");
+                }
+            }
+        }
         let config = ListingTableConfig::new(table_path)
-            .with_listing_options(options.with_file_sort_order(cmd.order_exprs.clone()))
+            .with_listing_options(
+                options
+                    .with_file_sort_order(cmd.order_exprs.clone())
+                    .with_bucket_spec(cmd.bucket_spec.clone()),
+            )
             .with_schema(resolved_schema);
+        let cache_manager = &state.runtime_env().cache_manager;
         let provider = ListingTable::try_new(config)?
-            .with_cache(state.runtime_env().cache_manager.get_file_statistic_cache());
+            .with_cache(cache_manager.get_file_statistic_cache())
+            .with_list_files_cache(cache_manager.get_list_files_cache());
         let table = provider
             .with_definition(cmd.definition.clone())
             .with_constraints(cmd.constraints.clone())
-            .with_column_defaults(cmd.column_defaults.clone());
+            .with_column_defaults(cmd.column_defaults.clone())
+            .with_stored_schema_evolution(state)
+            .await?;
+        // The files of an altered table store columns under other names than
+        // those of the table, so its schema cannot be inferred from them
+        if cmd.schema.fields().is_empty() && table.is_altered() {
+            return plan_err!(
+                "Table {} was altered, so its columns must be specified",
+                cmd.location
+            );
+        }
 
         // Pre-warm statistics cache if collect_statistics is enabled
         if session_state.config().collect_statistics() {
@@ -21,16 +21,18 @@ mod tests {
     use crate::datasource::MemTable;
     use crate::datasource::{DefaultTableSource, provider_as_source};
     use crate::physical_plan::collect;
-    use crate::prelude::SessionContext;
+    use crate::prelude::{SessionConfig, SessionContext};
     use arrow::array::{AsArray, Int32Array};
-    use arrow::datatypes::{DataType, Field, Schema, UInt64Type};
+    use arrow::datatypes::{DataType, Field, Int64Type, Schema, UInt64Type};
     use arrow::error::ArrowError;
     use arrow::record_batch::RecordBatch;
     use arrow_schema::SchemaRef;
     use datafusion_catalog::TableProvider;
-    use datafusion_common::{Constraint, Constraints, DataFusionError, Result};
-    use datafusion_expr::LogicalPlanBuilder;
+    use datafusion_common::{
+        Constraint, Constraints, DataFusionError, Result, assert_contains,
+    };
     use datafusion_expr::dml::InsertOp;
+    use datafusion_expr::{LogicalPlanBuilder, col};
     use futures::StreamExt;
     use std::collections::HashMap;
     use std::sync::Arc;
@@ -501,4 +503,197 @@ mod tests {
         );
         Ok(())
     }
+
+    /// Returns two sessions with the same runtime that share the table `t`
+    /// with the values 1, 2 and 3
+    fn sessions_sharing_table() -> Result<(SessionContext, SessionContext)> {
+        let first = SessionContext::new();
+        let second =
+            SessionContext::new_with_config_rt(SessionConfig::new(), first.runtime_env());
+        share_table(&first, &second)?;
+        Ok((first, second))
+    }
+
+    /// Registers the table `t` with the values 1, 2 and 3 in both sessions
+    fn share_table(first: &SessionContext, second: &SessionContext) -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let table = Arc::new(MemTable::try_new(schema, vec![vec![batch]])?);
+        first.register_table("t", table.clone())?;
+        second.register_table("t", table)?;
+        Ok(())
+    }
+
+    async fn execute(ctx: &SessionContext, sql: &str) -> Result<()> {
+        ctx.sql(sql).await?.collect().await?;
+        Ok(())
+    }
+
+    /// Returns the sum of the values of `t` seen by `ctx`
+    async fn sum(ctx: &SessionContext) -> Result<i64> {
+        let batches = ctx.sql("SELECT sum(a) FROM t").await?.collect().await?;
+        Ok(batches[0].column(0).as_primitive::<Int64Type>().value(0))
+    }
+
+    #[tokio::test]
+    async fn test_transaction_changes_invisible_until_commit() -> Result<()> {
+        let (first, second) = sessions_sharing_table()?;
+
+        execute(&first, "BEGIN").await?;
+        execute(&first, "INSERT INTO t VALUES (4)").await?;
+        execute(&first, "DELETE FROM t WHERE a = 1").await?;
+        execute(&first, "UPDATE t SET a = 20 WHERE a = 2").await?;
+        assert_eq!(sum(&first).await?, 27);
+        assert_eq!(sum(&second).await?, 6);
+
+        execute(&first, "COMMIT").await?;
+        assert!(first.transaction().is_none());
+        assert_eq!(sum(&first).await?, 27);
+        assert_eq!(sum(&second).await?, 27);
+
+        execute(&first, "BEGIN").await?;
+        execute(&first, "DELETE FROM t").await?;
+        assert_eq!(sum(&second).await?, 27);
+        execute(&first, "ROLLBACK").await?;
+        assert_eq!(sum(&first).await?, 27);
+        assert_eq!(sum(&second).await?, 27);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_reads_snapshot() -> Result<()> {
+        let (first, second) = sessions_sharing_table()?;
+
+        // The first transaction has not read the table before the change of
+        // the second session, so it reads a previous version of the table
+        execute(&first, "BEGIN").await?;
+        execute(&second, "INSERT INTO t VALUES (10)").await?;
+        assert_eq!(sum(&first).await?, 6);
+
+        execute(&second, "DELETE FROM t WHERE a = 1").await?;
+        assert_eq!(sum(&first).await?, 6);
+        assert_eq!(sum(&second).await?, 15);
+
+        execute(&first, "COMMIT").await?;
+        assert_eq!(sum(&first).await?, 15);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_conflict() -> Result<()> {
+        let (first, second) = sessions_sharing_table()?;
+
+        execute(&first, "BEGIN").await?;
+        execute(&second, "BEGIN").await?;
+        execute(&first, "UPDATE t SET a = a * 10").await?;
+        execute(&second, "DELETE FROM t WHERE a = 3").await?;
+
+        // First committer wins
+        execute(&first, "COMMIT").await?;
+        let err = execute(&second, "COMMIT").await.unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "conflicts with a concurrent change to a table it modified"
+        );
+        assert!(second.transaction().is_none());
+        assert_eq!(sum(&second).await?, 60);
+
+        // Transactions that only read the table do not conflict
+        execute(&first, "BEGIN").await?;
+        assert_eq!(sum(&first).await?, 60);
+        execute(&second, "INSERT INTO t VALUES (1)").await?;
+        execute(&first, "COMMIT").await?;
+        assert_eq!(sum(&first).await?, 61);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_of_another_runtime() -> Result<()> {
+        let first = SessionContext::new();
+        let second = SessionContext::new();
+        share_table(&first, &second)?;
+
+        // Statements outside of transactions can change the table from any
+        // runtime
+        execute(&first, "INSERT INTO t VALUES (4)").await?;
+        execute(&second, "INSERT INTO t VALUES (5)").await?;
+        assert_eq!(sum(&first).await?, 15);
+
+        // The commits of the table are ordered by the clock of the runtime
+        // of the session that accessed it first
+        execute(&second, "BEGIN").await?;
+        let err = sum(&second).await.unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "cannot access a table whose commits are ordered by the clock of another runtime"
+        );
+        execute(&second, "ROLLBACK").await?;
+
+        execute(&first, "BEGIN").await?;
+        execute(&first, "DELETE FROM t WHERE a = 5").await?;
+        execute(&first, "COMMIT").await?;
+        assert_eq!(sum(&second).await?, 10);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_resets_sort_order_when_executed() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let table = Arc::new(
+            MemTable::try_new(schema, vec![vec![batch]])?
+                .with_sort_order(vec![vec![col("a").sort(true, false)]]),
+        );
+        let ctx = SessionContext::new();
+        ctx.register_table("t", table.clone())?;
+        let merge = "MERGE INTO t USING (VALUES (4)) AS s (a) ON t.a = s.a \
+                     WHEN NOT MATCHED THEN INSERT VALUES (s.a)";
+
+        // Planning the MERGE does not change the table
+        execute(&ctx, &format!("EXPLAIN {merge}")).await?;
+        assert_eq!(table.sort_order.lock().len(), 1);
+
+        execute(&ctx, merge).await?;
+        assert!(table.sort_order.lock().is_empty());
+        assert_eq!(sum(&ctx).await?, 10);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_conflicts_with_concurrent_insert() -> Result<()> {
+        let (ctx, _) = sessions_sharing_table()?;
+        let merge = "MERGE INTO t USING (VALUES (4)) AS s (a) ON t.a = s.a \
+                     WHEN NOT MATCHED THEN INSERT VALUES (s.a)";
+
+        // The MERGE replaces the table with rows computed before the insert
+        let plan = ctx.sql(merge).await?.create_physical_plan().await?;
+        execute(&ctx, "INSERT INTO t VALUES (5)").await?;
+        let err = collect(plan, ctx.task_ctx()).await.unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "MERGE conflicts with a concurrent change to the table it modifies"
+        );
+        assert_eq!(sum(&ctx).await?, 11);
+
+        // Whichever runs first, the inserted row is never lost
+        let (inserted, merged) = futures::join!(
+            execute(&ctx, "INSERT INTO t VALUES (6)"),
+            execute(&ctx, merge)
+        );
+        inserted?;
+        match merged {
+            Ok(()) => assert_eq!(sum(&ctx).await?, 21),
+            Err(err) => {
+                assert_contains!(err.to_string(), "MERGE conflicts");
+                assert_eq!(sum(&ctx).await?, 17);
+            }
+        }
+        Ok(())
+    }
 }
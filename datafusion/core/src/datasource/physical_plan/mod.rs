@@ -19,6 +19,7 @@
 
 pub mod arrow;
 pub mod csv;
+pub mod fixed_width;
 pub mod json;
 
 #[cfg(feature = "parquet")]
@@ -30,6 +31,12 @@ pub mod avro;
 #[cfg(feature = "avro")]
 pub use avro::AvroSource;
 
+#[cfg(feature = "orc")]
+pub mod orc;
+
+#[cfg(feature = "orc")]
+pub use orc::OrcSource;
+
 #[cfg(feature = "parquet")]
 pub use datafusion_datasource_parquet::source::ParquetSource;
 #[cfg(feature = "parquet")]
@@ -47,6 +54,7 @@ pub use datafusion_datasource::file_scan_config::{
     wrap_partition_value_in_dict,
 };
 pub use datafusion_datasource::file_sink_config::*;
+pub use fixed_width::{FixedWidthOpener, FixedWidthSource};
 
 pub use datafusion_datasource::file_stream::{
     FileOpenFuture, FileOpener, FileStream, FileStreamBuilder, OnError,
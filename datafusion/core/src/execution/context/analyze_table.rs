@@ -0,0 +1,365 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `ANALYZE TABLE` support for [`SessionContext`]
+
+use std::sync::Arc;
+
+use super::{DataFrame, Result, SessionContext, TableProvider};
+use crate::datasource::listing::{ListingTable, PartitionedFile};
+use crate::datasource::physical_plan::{FileGroup, FileScanConfigBuilder};
+use crate::functions_aggregate::approx_distinct::approx_distinct_udaf;
+use crate::functions_aggregate::count::count_udaf;
+use crate::functions_aggregate::min_max::{max_udaf, min_udaf};
+use crate::physical_plan::aggregates::{AggregateExec, AggregateMode, PhysicalGroupBy};
+use crate::physical_plan::collect_partitioned;
+
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion_catalog_listing::statistics_sidecar::write_statistics_sidecar;
+use datafusion_common::stats::Precision;
+use datafusion_common::{
+    ScalarValue, Statistics, exec_err, internal_datafusion_err, not_impl_err, plan_err,
+};
+use datafusion_datasource::TableSchema;
+use datafusion_execution::cache::TableScopedPath;
+use datafusion_execution::cache::cache_manager::CachedFileMetadata;
+use datafusion_expr::{AggregateUDF, AnalyzeTable};
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::aggregate::{AggregateExprBuilder, AggregateFunctionExpr};
+use datafusion_physical_expr::expressions::{col, lit};
+
+/// Positions of the statistics of a column in the result of the query
+/// computing them
+struct ColumnAggregates {
+    /// Index of the column in the file schema
+    index: usize,
+    /// Number of non-null values
+    non_null: usize,
+    /// Minimum and maximum values, if supported by the column type
+    min_max: Option<usize>,
+    /// Approximate number of distinct values, if supported by the column type
+    distinct: Option<usize>,
+}
+
+impl SessionContext {
+    /// Computes the statistics of each file of the listing table `cmd.name`
+    /// and stores them in the file statistics cache, where later scans of
+    /// the table find them, and in statistics sidecar files if
+    /// `datafusion.execution.statistics_sidecar_files` is enabled.
+    pub(super) async fn analyze_table(&self, cmd: AnalyzeTable) -> Result<DataFrame> {
+        let AnalyzeTable { name, columns, .. } = cmd;
+        let provider = self.table_provider(name.clone()).await?;
+        let Some(table) = provider.downcast_ref::<ListingTable>() else {
+            return not_impl_err!(
+                "ANALYZE TABLE is only supported for listing tables, '{name}' is not one"
+            );
+        };
+        let Some(cache) = self.runtime_env().cache_manager.get_file_statistic_cache()
+        else {
+            return exec_err!("ANALYZE TABLE requires a file statistics cache");
+        };
+        let Some(table_path) = table.table_paths().first() else {
+            return exec_err!("Listing table has no paths");
+        };
+
+        let file_schema = file_schema(table)?;
+        let indices = if columns.is_empty() {
+            (0..file_schema.fields().len()).collect::<Vec<_>>()
+        } else {
+            let mut indices = Vec::with_capacity(columns.len());
+            for column in &columns {
+                if let Ok(index) = file_schema.index_of(column) {
+                    indices.push(index);
+                } else if table
+                    .options()
+                    .table_partition_cols
+                    .iter()
+                    .all(|(partition_col, _)| partition_col != column)
+                {
+                    return plan_err!("Column '{column}' not found in table '{name}'");
+                }
+                // Partition columns are not analyzed as their statistics are
+                // derived from the file paths
+            }
+            indices
+        };
+
+        let state = self.state();
+        let files = table
+            .list_files_for_scan(&state, &[], None)
+            .await?
+            .file_groups
+            .into_iter()
+            .flat_map(|group| group.into_inner())
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return self.return_empty_dataframe();
+        }
+
+        // Every file is scanned as its own partition, which yields a single
+        // row with the statistics of the file
+        let file_groups = files
+            .iter()
+            .map(|file| {
+                let mut file = file.clone();
+                file.partition_values.clear();
+                file.statistics = None;
+                FileGroup::new(vec![file])
+            })
+            .collect::<Vec<_>>();
+        let file_source = table
+            .options()
+            .format
+            .file_source(TableSchema::from(Arc::clone(&file_schema)));
+        let scan = table
+            .options()
+            .format
+            .create_physical_plan(
+                &state,
+                FileScanConfigBuilder::new(table_path.object_store(), file_source)
+                    .with_file_groups(file_groups)
+                    .build(),
+            )
+            .await?;
+        let (aggregates, columns) = aggregates(&file_schema, &indices)?;
+        let filters = vec![None; aggregates.len()];
+        let aggregate = Arc::new(AggregateExec::try_new(
+            AggregateMode::Single,
+            PhysicalGroupBy::default(),
+            aggregates,
+            filters,
+            scan,
+            Arc::clone(&file_schema),
+        )?);
+        let results = collect_partitioned(aggregate, self.task_ctx()).await?;
+
+        let write_sidecars = state.config_options().execution.statistics_sidecar_files;
+        let store = self.runtime_env().object_store(table_path)?;
+        for (file, batches) in files.iter().zip(results) {
+            let path = TableScopedPath {
+                table: file.table_reference.clone(),
+                path: file.object_meta.location.clone(),
+            };
+            let cached = cache
+                .get(&path)
+                .filter(|cached| cached.is_valid_for(&file.object_meta));
+            let previous = cached.as_ref().map(|cached| cached.statistics.as_ref());
+            let statistics =
+                file_statistics(&file_schema, file, &batches, &columns, previous)?;
+            if write_sidecars {
+                write_statistics_sidecar(
+                    store.as_ref(),
+                    &file.object_meta,
+                    &file_schema,
+                    &statistics,
+                )
+                .await?;
+            }
+            cache.put(
+                &path,
+                CachedFileMetadata::new(
+                    file.object_meta.clone(),
+                    Arc::new(statistics),
+                    cached.and_then(|cached| cached.ordering),
+                ),
+            );
+        }
+        self.return_empty_dataframe()
+    }
+}
+
+/// Returns the aggregates computing the statistics of the columns of
+/// `file_schema` at `indices`, along with their positions in the result
+fn aggregates(
+    file_schema: &SchemaRef,
+    indices: &[usize],
+) -> Result<(Vec<Arc<AggregateFunctionExpr>>, Vec<ColumnAggregates>)> {
+    let aggregate =
+        |udaf: Arc<AggregateUDF>, expr: Arc<dyn PhysicalExpr>, alias: String| {
+            AggregateExprBuilder::new(udaf, vec![expr])
+                .schema(Arc::clone(file_schema))
+                .alias(alias)
+                .build()
+                .map(Arc::new)
+        };
+    let mut aggregates = vec![aggregate(
+        count_udaf(),
+        lit(ScalarValue::Int64(Some(1))),
+        "num_rows".to_string(),
+    )?];
+    let mut columns = Vec::with_capacity(indices.len());
+    for &index in indices {
+        let field = file_schema.field(index);
+        let column = col(field.name(), file_schema)?;
+        let mut next = |udaf, name: &str| -> Result<usize> {
+            let alias = format!("{}_{name}_{index}", aggregates.len());
+            aggregates.push(aggregate(udaf, Arc::clone(&column), alias)?);
+            Ok(aggregates.len() - 1)
+        };
+        let non_null = next(count_udaf(), "non_null")?;
+        let min_max = if supports_min_max(field.data_type()) {
+            let idx = next(min_udaf(), "min")?;
+            next(max_udaf(), "max")?;
+            Some(idx)
+        } else {
+            None
+        };
+        let distinct = if supports_approx_distinct(field.data_type()) {
+            Some(next(approx_distinct_udaf(), "distinct")?)
+        } else {
+            None
+        };
+        columns.push(ColumnAggregates {
+            index,
+            non_null,
+            min_max,
+            distinct,
+        });
+    }
+    Ok((aggregates, columns))
+}
+
+/// Returns the statistics of `file` computed by the `aggregates` in
+/// `batches`. The statistics of the columns that were not analyzed are
+/// taken from `previous`, if any.
+fn file_statistics(
+    file_schema: &SchemaRef,
+    file: &PartitionedFile,
+    batches: &[RecordBatch],
+    columns: &[ColumnAggregates],
+    previous: Option<&Statistics>,
+) -> Result<Statistics> {
+    let [batch] = batches else {
+        return exec_err!("Expected a single batch of statistics");
+    };
+    if batch.num_rows() != 1 {
+        return exec_err!("Expected a single row of statistics");
+    }
+    let value = |idx: usize| ScalarValue::try_from_array(batch.column(idx), 0);
+    let as_usize = |idx: usize| -> Result<usize> {
+        match value(idx)?.cast_to(&DataType::UInt64)? {
+            ScalarValue::UInt64(Some(v)) => Ok(v as usize),
+            v => Err(internal_datafusion_err!("Unexpected count {v}")),
+        }
+    };
+
+    let num_rows = as_usize(0)?;
+    let mut statistics = match previous {
+        Some(previous)
+            if previous.column_statistics.len() == file_schema.fields().len() =>
+        {
+            previous.clone()
+        }
+        _ => Statistics::new_unknown(file_schema),
+    };
+    statistics.num_rows = Precision::Exact(num_rows);
+    if statistics.total_byte_size == Precision::Absent {
+        statistics.total_byte_size = Precision::Inexact(file.object_meta.size as usize);
+    }
+    for column in columns {
+        let column_statistics = &mut statistics.column_statistics[column.index];
+        column_statistics.null_count =
+            Precision::Exact(num_rows - as_usize(column.non_null)?);
+        if let Some(idx) = column.min_max {
+            let (min, max) = (value(idx)?, value(idx + 1)?);
+            column_statistics.min_value = exact_non_null(min);
+            column_statistics.max_value = exact_non_null(max);
+        }
+        if let Some(idx) = column.distinct {
+            // The number of distinct values is estimated by HyperLogLog
+            column_statistics.distinct_count = Precision::Inexact(as_usize(idx)?);
+        }
+    }
+    Ok(statistics)
+}
+
+/// Returns the schema of the columns stored in the files of `table`
+fn file_schema(table: &ListingTable) -> Result<SchemaRef> {
+    let schema = table.schema();
+    let num_file_columns =
+        schema.fields().len() - table.options().table_partition_cols.len();
+    let indices = (0..num_file_columns).collect::<Vec<_>>();
+    Ok(Arc::new(schema.project(&indices)?))
+}
+
+fn exact_non_null(value: ScalarValue) -> Precision<ScalarValue> {
+    if value.is_null() {
+        Precision::Absent
+    } else {
+        Precision::Exact(value)
+    }
+}
+
+/// Returns true if `min` and `max` support columns of `data_type`
+fn supports_min_max(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float16
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Decimal128(_, _)
+            | DataType::Decimal256(_, _)
+            | DataType::Date32
+            | DataType::Date64
+            | DataType::Time32(_)
+            | DataType::Time64(_)
+            | DataType::Timestamp(_, _)
+            | DataType::Duration(_)
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Utf8View
+            | DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+    )
+}
+
+/// Returns true if `approx_distinct` supports columns of `data_type`
+fn supports_approx_distinct(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Date32
+            | DataType::Date64
+            | DataType::Time32(_)
+            | DataType::Time64(_)
+            | DataType::Timestamp(_, _)
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Utf8View
+            | DataType::Binary
+            | DataType::LargeBinary
+    )
+}
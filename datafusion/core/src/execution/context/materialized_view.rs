@@ -0,0 +1,216 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! `CREATE MATERIALIZED VIEW` and `REFRESH MATERIALIZED VIEW` support for
+//! [`SessionContext`]
+
+use std::sync::Arc;
+
+use super::{DataFrame, LogicalPlan, Result, SessionContext, TableProvider, TableType};
+use crate::datasource::MemTable;
+use crate::datasource::listing::ListingTableUrl;
+use crate::datasource::provider_as_source;
+
+use datafusion_catalog::materialized_view::MaterializedView;
+use datafusion_common::{TableReference, exec_datafusion_err, exec_err};
+use datafusion_expr::RefreshMaterializedView;
+use futures::TryStreamExt;
+use object_store::ObjectStoreExt;
+
+impl SessionContext {
+    /// Computes the contents of a new materialized view of `input` and
+    /// returns the provider to register it under `name`.
+    ///
+    /// A `location` must be empty unless it is the location of `replaced`,
+    /// the materialized view the new view replaces.
+    pub(super) async fn create_materialized_view(
+        &self,
+        name: &TableReference,
+        input: LogicalPlan,
+        definition: Option<String>,
+        location: Option<String>,
+        replaced: Option<&MaterializedView>,
+    ) -> Result<Arc<MaterializedView>> {
+        // store the contents as a directory of files
+        let location = location.map(|location| {
+            if location.ends_with('/') {
+                location
+            } else {
+                format!("{location}/")
+            }
+        });
+        if let Some(location) = &location
+            && replaced.and_then(|view| view.location()) != Some(location.as_str())
+            && !self.is_empty_location(location).await?
+        {
+            return exec_err!(
+                "Location '{location}' of materialized view '{name}' is not empty"
+            );
+        }
+        let (storage, directory) =
+            self.materialize(name, &input, location.as_deref()).await?;
+        if let (Some(location), Some(directory)) = (&location, &directory) {
+            self.remove_previous_contents(name, location, directory)
+                .await?;
+        }
+        Ok(Arc::new(MaterializedView::new(
+            input, definition, location, storage,
+        )))
+    }
+
+    /// Makes the registered materialized view `view` available to the
+    /// optimizer for answering queries
+    pub(super) fn register_materialized_view(
+        &self,
+        name: TableReference,
+        view: Arc<MaterializedView>,
+    ) -> Result<()> {
+        let definition = view.logical_plan().clone();
+        self.state.write().register_materialized_view(
+            name,
+            definition,
+            provider_as_source(view),
+        )
+    }
+
+    /// Recomputes the contents of the materialized view `cmd.name`
+    pub(super) async fn refresh_materialized_view(
+        &self,
+        cmd: RefreshMaterializedView,
+    ) -> Result<DataFrame> {
+        let RefreshMaterializedView { name, .. } = cmd;
+        let table = self.table_provider(name.clone()).await.map_err(|_| {
+            exec_datafusion_err!("Materialized view '{name}' doesn't exist")
+        })?;
+        let Some(view) = table.downcast_ref::<MaterializedView>() else {
+            return exec_err!("'{name}' is not a materialized view");
+        };
+
+        let (storage, directory) = self
+            .materialize(&name, view.logical_plan(), view.location())
+            .await?;
+        // Scans planned from now on read the new contents
+        view.replace_storage(storage);
+        if let (Some(location), Some(directory)) = (view.location(), &directory) {
+            self.remove_previous_contents(&name, location, directory)
+                .await?;
+        }
+        self.return_empty_dataframe()
+    }
+
+    /// Runs `plan` and stores its result, in memory or as Parquet files in a
+    /// new directory inside `location`. Returns a table reading the result,
+    /// along with the directory it was written to.
+    async fn materialize(
+        &self,
+        name: &TableReference,
+        plan: &LogicalPlan,
+        location: Option<&str>,
+    ) -> Result<(Arc<dyn TableProvider>, Option<String>)> {
+        // The contents of a view must not be computed from materialized views,
+        // which may be out of date or the view itself
+        let mut state = self.state();
+        state
+            .config_mut()
+            .options_mut()
+            .optimizer
+            .enable_materialized_view_rewrite = false;
+        let schema = Arc::clone(plan.schema().inner());
+        let df = DataFrame::new(state, plan.clone());
+
+        match location {
+            None => {
+                let batches = df.collect_partitioned().await?;
+                Ok((Arc::new(MemTable::try_new(schema, batches)?), None))
+            }
+            #[cfg(feature = "parquet")]
+            Some(location) => {
+                // The previous contents stay in place until the new ones are
+                // complete and have replaced them
+                let directory = format!("{location}{}/", uuid::Uuid::new_v4());
+                let storage = self
+                    .materialize_parquet(name, df, schema, &directory)
+                    .await?;
+                Ok((storage, Some(directory)))
+            }
+            #[cfg(not(feature = "parquet"))]
+            Some(_) => {
+                let _ = name;
+                datafusion_common::not_impl_err!(
+                    "Materialized views stored in a location require the parquet feature"
+                )
+            }
+        }
+    }
+
+    /// Writes the result of `df` as Parquet files to `directory`, and returns
+    /// a table reading them
+    #[cfg(feature = "parquet")]
+    async fn materialize_parquet(
+        &self,
+        name: &TableReference,
+        df: DataFrame,
+        schema: arrow::datatypes::SchemaRef,
+        directory: &str,
+    ) -> Result<Arc<dyn TableProvider>> {
+        use crate::dataframe::DataFrameWriteOptions;
+        use crate::datasource::listing::{ListingTable, ListingTableConfig};
+        use crate::execution::options::{ParquetReadOptions, ReadOptions};
+
+        df.write_parquet(directory, DataFrameWriteOptions::new(), None)
+            .await?;
+
+        let url = ListingTableUrl::parse(directory)?.with_table_ref(name.clone());
+        let options = ParquetReadOptions::default()
+            .to_listing_options(&self.copied_config(), self.copied_table_options());
+        let config = ListingTableConfig::new(url)
+            .with_listing_options(options)
+            .with_schema(schema);
+        Ok(Arc::new(ListingTable::try_new(config)?))
+    }
+
+    /// Returns whether there are no files in `location`
+    async fn is_empty_location(&self, location: &str) -> Result<bool> {
+        let url = ListingTableUrl::parse(location)?;
+        let store = self.runtime_env().object_store(&url)?;
+        Ok(store.list(Some(url.prefix())).try_next().await?.is_none())
+    }
+
+    /// Removes the files in `location` of the materialized view `name` that
+    /// are not in `directory`, which holds its current contents
+    async fn remove_previous_contents(
+        &self,
+        name: &TableReference,
+        location: &str,
+        directory: &str,
+    ) -> Result<()> {
+        let url = ListingTableUrl::parse(location)?;
+        let current = ListingTableUrl::parse(directory)?;
+        let store = self.runtime_env().object_store(&url)?;
+        let previous: Vec<_> = store
+            .list(Some(url.prefix()))
+            .try_filter(|meta| {
+                futures::future::ready(!meta.location.prefix_matches(current.prefix()))
+            })
+            .try_collect()
+            .await?;
+        for meta in previous {
+            store.delete(&meta.location).await?;
+        }
+        self.invalidate_caches(&Some(name.clone()), TableType::Base)
+    }
+}
@@ -45,10 +45,12 @@ use crate::{
     logical_expr::AggregateUDF,
     logical_expr::ScalarUDF,
     logical_expr::{
-        CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateFunction,
-        CreateMemoryTable, CreateView, DropCatalogSchema, DropFunction, DropTable,
-        DropView, Execute, LogicalPlan, LogicalPlanBuilder, Prepare, ResetVariable,
-        SetVariable, TableType, UNNAMED_TABLE,
+        AlterTable, AlterTableOperation, CreateCatalog, CreateCatalogSchema,
+        CreateExternalTable, CreateFunction, CreateMemoryTable, CreateView,
+        DropCatalogSchema, DropFunction, DropTable, DropView, Execute, LogicalPlan,
+        LogicalPlanBuilder, Prepare, ResetVariable, SetVariable, TableType,
+        TransactionAccessMode, TransactionConclusion, TransactionEnd,
+        TransactionIsolationLevel, TransactionStart, UNNAMED_TABLE,
     },
     physical_expr::PhysicalExpr,
     physical_plan::ExecutionPlan,
@@ -61,14 +63,16 @@ pub use crate::execution::session_state::SessionState;
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use datafusion_catalog::MemoryCatalogProvider;
+use datafusion_catalog::materialized_view::MaterializedView;
 use datafusion_catalog::memory::MemorySchemaProvider;
+use datafusion_catalog::transaction::Transaction;
 use datafusion_catalog::{
     DynamicFileCatalog, TableFunction, TableFunctionImpl, UrlTableFactory,
 };
 use datafusion_common::config::{ConfigField, ConfigOptions};
 use datafusion_common::metadata::ScalarAndMetadata;
 use datafusion_common::{
-    DFSchema, DataFusionError, ParamValues, SchemaError, SchemaReference, TableReference,
+    DFSchema, ParamValues, SchemaError, SchemaReference, TableReference,
     config::{ConfigExtension, TableOptions},
     exec_datafusion_err, exec_err, internal_datafusion_err, not_impl_err,
     plan_datafusion_err, plan_err, schema_err,
@@ -100,6 +104,7 @@ use datafusion_optimizer::simplify_expressions::ExprSimplifier;
 use datafusion_optimizer::{Analyzer, OptimizerContext};
 use datafusion_optimizer::{AnalyzerRule, OptimizerRule};
 use datafusion_session::SessionStore;
+pub use sql_function::SqlFunctionFactory;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -108,10 +113,13 @@ use object_store::ObjectStore;
 use parking_lot::RwLock;
 use url::Url;
 
+mod analyze_table;
 mod csv;
 mod json;
+mod materialized_view;
 #[cfg(feature = "parquet")]
 mod parquet;
+mod sql_function;
 
 #[cfg(feature = "avro")]
 mod avro;
@@ -472,7 +480,8 @@ impl SessionContext {
         self.session_start_time
     }
 
-    /// Registers a [`FunctionFactory`] to handle `CREATE FUNCTION` statements
+    /// Registers a [`FunctionFactory`] to handle `CREATE FUNCTION` statements,
+    /// instead of the default [`SqlFunctionFactory`]
     pub fn with_function_factory(
         self,
         function_factory: Arc<dyn FunctionFactory>,
@@ -686,6 +695,13 @@ impl SessionContext {
     /// [`SQLOptions::verify_plan`].
     pub async fn execute_logical_plan(&self, plan: LogicalPlan) -> Result<DataFrame> {
         match plan {
+            LogicalPlan::Ddl(ddl)
+                if self.transaction().is_some_and(|txn| {
+                    txn.access_mode() == &TransactionAccessMode::ReadOnly
+                }) =>
+            {
+                plan_err!("Cannot execute {} in a read-only transaction", ddl.name())
+            }
             LogicalPlan::Ddl(ddl) => {
                 // Box::pin avoids allocating the stack space within this function's frame
                 // for every one of these individual async functions, decreasing the risk of
@@ -719,10 +735,42 @@ impl SessionContext {
                     DdlStatement::DropFunction(cmd) => {
                         Box::pin(self.drop_function(cmd)).await
                     }
+                    DdlStatement::AlterTable(cmd) => {
+                        Box::pin(self.alter_table(cmd)).await
+                    }
+                    DdlStatement::RefreshMaterializedView(cmd) => {
+                        Box::pin(self.refresh_materialized_view(cmd)).await
+                    }
+                    DdlStatement::AnalyzeTable(cmd) => {
+                        Box::pin(self.analyze_table(cmd)).await
+                    }
                     ddl => Ok(DataFrame::new(self.state(), LogicalPlan::Ddl(ddl))),
                 }
             }
-            // TODO what about the other statements (like TransactionStart and TransactionEnd)
+            LogicalPlan::Statement(Statement::TransactionStart(TransactionStart {
+                access_mode,
+                isolation_level,
+            })) => {
+                self.begin_transaction(access_mode, isolation_level)?;
+                self.return_empty_dataframe()
+            }
+            LogicalPlan::Statement(Statement::TransactionEnd(TransactionEnd {
+                conclusion,
+                chain,
+            })) => {
+                let txn = self.take_transaction()?;
+                match conclusion {
+                    TransactionConclusion::Commit => txn.commit().await?,
+                    TransactionConclusion::Rollback => txn.rollback()?,
+                }
+                if chain {
+                    self.begin_transaction(
+                        txn.access_mode().clone(),
+                        txn.isolation_level().clone(),
+                    )?;
+                }
+                self.return_empty_dataframe()
+            }
             LogicalPlan::Statement(Statement::SetVariable(stmt)) => {
                 self.set_variable(stmt).await?;
                 self.return_empty_dataframe()
@@ -810,6 +858,83 @@ impl SessionContext {
         self.state.read().create_physical_expr(expr, df_schema)
     }
 
+    /// Returns the active [`Transaction`] of this session, if any
+    pub fn transaction(&self) -> Option<Arc<Transaction>> {
+        self.state.read().config().get_extension::<Transaction>()
+    }
+
+    /// Starts a [`Transaction`], like `BEGIN` or `START TRANSACTION`.
+    ///
+    /// The statements of the session run in the transaction until it is
+    /// ended with [`Self::commit_transaction`] or
+    /// [`Self::rollback_transaction`]. Their changes to tables that support
+    /// transactions, such as [`MemTable`], are not visible to other sessions
+    /// before the transaction commits. Changes to other tables and `COPY` fail
+    /// in a transaction, and DDL statements take effect immediately and are
+    /// not rolled back.
+    ///
+    /// Transactions are ordered by the [`CommitClock`] of the [`RuntimeEnv`]
+    /// of the session, so sessions that change the same tables must share it.
+    ///
+    /// [`CommitClock`]: datafusion_catalog::transaction::CommitClock
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// ctx.sql("CREATE TABLE t (x INT)").await?.collect().await?;
+    /// ctx.sql("BEGIN").await?.collect().await?;
+    /// ctx.sql("INSERT INTO t VALUES (1)").await?.collect().await?;
+    /// ctx.sql("ROLLBACK").await?.collect().await?;
+    /// let count = ctx.table("t").await?.count().await?;
+    /// assert_eq!(count, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn begin_transaction(
+        &self,
+        access_mode: TransactionAccessMode,
+        isolation_level: TransactionIsolationLevel,
+    ) -> Result<()> {
+        let mut state = self.state.write();
+        if state.config().get_extension::<Transaction>().is_some() {
+            return exec_err!("There is already a transaction in progress");
+        }
+        let txn = Transaction::begin(
+            Arc::clone(&state.runtime_env().commit_clock),
+            access_mode,
+            isolation_level,
+        );
+        state.config_mut().set_extension(Arc::new(txn));
+        Ok(())
+    }
+
+    /// Commits the active [`Transaction`] of this session, like `COMMIT`.
+    ///
+    /// If the transaction fails to commit, for example because it conflicts
+    /// with a concurrent transaction, it is rolled back and an error is
+    /// returned.
+    pub async fn commit_transaction(&self) -> Result<()> {
+        self.take_transaction()?.commit().await
+    }
+
+    /// Rolls back the active [`Transaction`] of this session, like
+    /// `ROLLBACK`, discarding its changes
+    pub fn rollback_transaction(&self) -> Result<()> {
+        self.take_transaction()?.rollback()
+    }
+
+    /// Removes the active [`Transaction`] from this session
+    fn take_transaction(&self) -> Result<Arc<Transaction>> {
+        let mut state = self.state.write();
+        match state.config_mut().remove_extension::<Transaction>() {
+            Some(txn) => Ok(txn),
+            None => exec_err!("There is no transaction in progress"),
+        }
+    }
+
     // return an empty dataframe
     fn return_empty_dataframe(&self) -> Result<DataFrame> {
         let plan = LogicalPlanBuilder::empty(false).build()?;
@@ -944,6 +1069,8 @@ impl SessionContext {
             or_replace,
             definition,
             temporary,
+            materialized,
+            location,
         } = cmd;
 
         let view = self.table(name.clone()).await;
@@ -952,24 +1079,36 @@ impl SessionContext {
             return not_impl_err!("Temporary views not supported");
         }
 
-        match (or_replace, view) {
-            (true, Ok(_)) => {
-                let input = Self::apply_type_coercion(Arc::unwrap_or_clone(input))?;
-                Self::ensure_unique_column_names(input.schema())?;
+        if let (false, Ok(_)) = (or_replace, &view) {
+            return exec_err!("Table '{name}' already exists");
+        }
+
+        let input = Self::apply_type_coercion(Arc::unwrap_or_clone(input))?;
+        Self::ensure_unique_column_names(input.schema())?;
+        if materialized {
+            let replaced = match &view {
+                Ok(_) => self.table_provider(name.clone()).await.ok(),
+                Err(_) => None,
+            };
+            let replaced = replaced
+                .as_ref()
+                .and_then(|table| table.downcast_ref::<MaterializedView>());
+            let table = self
+                .create_materialized_view(&name, input, definition, location, replaced)
+                .await?;
+            if view.is_ok() {
                 self.deregister_table(name.clone())?;
-                let table = Arc::new(ViewTable::new(input, definition));
-                self.register_table(name, table)?;
-                self.return_empty_dataframe()
             }
-            (_, Err(_)) => {
-                let input = Self::apply_type_coercion(Arc::unwrap_or_clone(input))?;
-                Self::ensure_unique_column_names(input.schema())?;
-                let table = Arc::new(ViewTable::new(input, definition));
-                self.register_table(name, table)?;
-                self.return_empty_dataframe()
+            self.register_table(name.clone(), Arc::clone(&table) as _)?;
+            self.register_materialized_view(name, table)?;
+        } else {
+            if view.is_ok() {
+                self.deregister_table(name.clone())?;
             }
-            (false, Ok(_)) => exec_err!("Table '{name}' already exists"),
+            let table = Arc::new(ViewTable::new(input, definition));
+            self.register_table(name, table)?;
         }
+        self.return_empty_dataframe()
     }
 
     fn ensure_unique_column_names(schema: &DFSchema) -> Result<()> {
@@ -1078,6 +1217,90 @@ impl SessionContext {
         }
     }
 
+    async fn alter_table(&self, cmd: AlterTable) -> Result<DataFrame> {
+        let AlterTable {
+            name,
+            if_exists,
+            operations,
+            ..
+        } = cmd;
+        let (resolved, maybe_schema) = {
+            let state = self.state.read();
+            let resolved = state.resolve_table_ref(name.clone());
+            let schema = state
+                .catalog_list()
+                .catalog(&resolved.catalog)
+                .and_then(|c| c.schema(&resolved.schema));
+            (resolved, schema)
+        };
+        let Some(schema) = maybe_schema else {
+            return if if_exists {
+                self.return_empty_dataframe()
+            } else {
+                exec_err!("Table '{name}' doesn't exist.")
+            };
+        };
+        let mut table_name = resolved.table.to_string();
+        let Some(mut table) = schema.table(&table_name).await? else {
+            return if if_exists {
+                self.return_empty_dataframe()
+            } else {
+                exec_err!("Table '{name}' doesn't exist.")
+            };
+        };
+        if table.table_type() != TableType::Base {
+            return plan_err!(
+                "ALTER TABLE is not supported for {} '{name}'",
+                table.table_type()
+            );
+        }
+
+        // Apply all operations before changing the catalog, so that the
+        // statement has no effect if any of them fails
+        let state = self.state();
+        let original = Arc::clone(&table);
+        let mut new_table_name = None;
+        for operation in &operations {
+            match operation {
+                AlterTableOperation::RenameTable { new_name } => {
+                    let new_resolved = state.resolve_table_ref(new_name.clone());
+                    if new_resolved.catalog != resolved.catalog
+                        || new_resolved.schema != resolved.schema
+                    {
+                        return not_impl_err!(
+                            "ALTER TABLE RENAME TO a different schema is not supported"
+                        );
+                    }
+                    new_table_name = Some(new_resolved.table.to_string());
+                }
+                operation => {
+                    table = table.alter_schema(&state, operation).await?;
+                }
+            }
+        }
+
+        self.invalidate_caches(&Some(name.clone()), TableType::Base)?;
+        let original_name = resolved.table.to_string();
+        if let Some(new_table_name) = new_table_name.filter(|n| *n != table_name) {
+            schema
+                .rename_table(&table_name, new_table_name.clone())
+                .await?;
+            table_name = new_table_name;
+        }
+        if !Arc::ptr_eq(&table, &original) {
+            schema.deregister_table(&table_name)?;
+            if let Err(e) = schema.register_table(table_name.clone(), table) {
+                // Restore the table as it was before the statement
+                schema.register_table(table_name.clone(), original)?;
+                if table_name != original_name {
+                    schema.rename_table(&table_name, original_name).await?;
+                }
+                return Err(e);
+            }
+        }
+        self.return_empty_dataframe()
+    }
+
     async fn drop_schema(&self, cmd: DropCatalogSchema) -> Result<DataFrame> {
         let DropCatalogSchema {
             name,
@@ -1469,6 +1692,14 @@ impl SessionContext {
                 fsc.drop_table_entries(table_ref)?;
             }
         }
+        if table_type == TableType::View
+            && let Some(table_ref) = table_ref
+        {
+            // a dropped materialized view can no longer answer queries
+            self.state
+                .write()
+                .deregister_materialized_view(table_ref.clone());
+        }
         Ok(())
     }
 
@@ -1479,11 +1710,7 @@ impl SessionContext {
 
             match function_factory {
                 Some(f) => f.create(&state, stmt).await?,
-                _ => {
-                    return Err(DataFusionError::Configuration(
-                        "Function factory has not been configured".to_string(),
-                    ));
-                }
+                None => SqlFunctionFactory::default().create(&state, stmt).await?,
             }
         };
 
@@ -1732,8 +1959,10 @@ impl SessionContext {
         let config = ListingTableConfig::new_with_multi_paths(table_paths)
             .with_listing_options(listing_options)
             .with_schema(resolved_schema);
+        let cache_manager = &self.runtime_env().cache_manager;
         let provider = ListingTable::try_new(config)?
-            .with_cache(self.runtime_env().cache_manager.get_file_statistic_cache());
+            .with_cache(cache_manager.get_file_statistic_cache())
+            .with_list_files_cache(cache_manager.get_list_files_cache());
         self.read_table(Arc::new(provider))
     }
 
@@ -1830,9 +2059,11 @@ impl SessionContext {
         let config = ListingTableConfig::new(table_path)
             .with_listing_options(options)
             .with_schema(resolved_schema);
+        let cache_manager = &self.runtime_env().cache_manager;
         let table = ListingTable::try_new(config)?
             .with_definition(sql_definition)
-            .with_cache(self.runtime_env().cache_manager.get_file_statistic_cache());
+            .with_cache(cache_manager.get_file_statistic_cache())
+            .with_list_files_cache(cache_manager.get_list_files_cache());
         self.register_table(table_ref, Arc::new(table))?;
         Ok(())
     }
@@ -2174,9 +2405,11 @@ pub trait QueryPlanner: Any + Debug {
 /// DataFusion will parse `CREATE FUNCTION` statements into [`CreateFunction`]
 /// structs and pass them to the [`create`](Self::create) method.
 ///
-/// Note there is no default implementation of this trait provided in DataFusion,
-/// because the implementation and requirements vary widely. Please see
-/// [function_factory example] for a reference implementation.
+/// If no factory is configured, [`SqlFunctionFactory`] is used, which supports
+/// functions whose body is a SQL expression or query. Functions defined in
+/// other languages require a custom implementation, as the requirements vary
+/// widely. Please see [function_factory example] for a reference
+/// implementation.
 ///
 /// [function_factory example]: https://github.com/apache/datafusion/blob/main/datafusion-examples/examples/builtin_functions/function_factory.rs
 ///
@@ -2456,6 +2689,62 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn analyze_table() -> Result<()> {
+        use datafusion_common::stats::Precision;
+        use datafusion_common::{ScalarValue, assert_contains};
+
+        let tmp_dir = TempDir::new()?;
+        std::fs::write(tmp_dir.path().join("data.csv"), "a,b\n1,x\n,y\n3,y\n")?;
+        let ctx = SessionContext::new();
+        ctx.sql(&format!(
+            "CREATE EXTERNAL TABLE t (a INT, b VARCHAR) STORED AS CSV LOCATION '{}/' \
+             OPTIONS ('format.has_header' 'true')",
+            tmp_dir.path().display()
+        ))
+        .await?;
+
+        ctx.sql("ANALYZE TABLE t COMPUTE STATISTICS").await?;
+
+        let statistics = ctx.table_provider("t").await?.statistics().unwrap();
+        assert_eq!(statistics.num_rows, Precision::Inexact(3));
+        let a = &statistics.column_statistics[0];
+        assert_eq!(a.null_count, Precision::Inexact(1));
+        assert_eq!(a.min_value, Precision::Inexact(ScalarValue::Int32(Some(1))));
+        assert_eq!(a.max_value, Precision::Inexact(ScalarValue::Int32(Some(3))));
+        assert_eq!(a.distinct_count, Precision::Inexact(2));
+        let b = &statistics.column_statistics[1];
+        assert_eq!(b.null_count, Precision::Inexact(0));
+        assert_eq!(b.distinct_count, Precision::Inexact(2));
+
+        // Scans of the table use the computed statistics
+        let plan = ctx
+            .sql("SELECT * FROM t")
+            .await?
+            .create_physical_plan()
+            .await?;
+        let statistics = plan.partition_statistics(None)?;
+        assert_eq!(statistics.num_rows, Precision::Exact(3));
+        assert_eq!(
+            statistics.column_statistics[0].null_count,
+            Precision::Exact(1)
+        );
+
+        ctx.sql("CREATE TABLE m AS VALUES (1)").await?;
+        let err = ctx
+            .sql("ANALYZE TABLE m COMPUTE STATISTICS")
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "only supported for listing tables");
+        let err = ctx
+            .sql("ANALYZE TABLE t COMPUTE STATISTICS FOR COLUMNS c")
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "Column 'c' not found in table 't'");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn send_context_to_threads() -> Result<()> {
         // ensure SessionContexts can be used in a multi-threaded
@@ -0,0 +1,585 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SqlFunctionFactory`]: creates functions from `CREATE FUNCTION`
+//! statements whose body is written in SQL
+
+use std::sync::Arc;
+
+use super::{FunctionFactory, RegisterFunction, SessionState};
+use crate::catalog::{TableFunctionArgs, TableFunctionImpl, TableProvider};
+use crate::datasource::ViewTable;
+
+use arrow::datatypes::{DataType, Field, FieldRef, Schema};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use async_trait::async_trait;
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::{
+    Column, DFSchema, Result, ScalarValue, exec_err, not_impl_err, plan_err,
+};
+use datafusion_expr::execution_props::ExecutionProps;
+use datafusion_expr::simplify::{ExprSimplifyResult, SimplifyContext};
+use datafusion_expr::{
+    ColumnarValue, CreateFunction, DocSection, Documentation, Expr, ExprSchemable,
+    LogicalPlan, OperateFunctionArg, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl,
+    Signature, TypeSignature, Volatility,
+};
+use datafusion_optimizer::analyzer::type_coercion::TypeCoercionRewriter;
+use datafusion_physical_expr::create_physical_expr;
+
+const DOC_SECTION_SQL_FUNCTIONS: DocSection = DocSection {
+    include: false,
+    label: "SQL Functions",
+    description: Some("Functions created with `CREATE FUNCTION`"),
+};
+
+/// The [`FunctionFactory`] used by [`SessionContext`] when no other factory
+/// has been configured.
+///
+/// It creates "SQL macros": functions whose body is SQL that is inlined into
+/// the calling query, so the optimizer sees (and can optimize) the body
+/// rather than an opaque function call.
+///
+/// * A body that is an expression creates a scalar function. Calls are
+///   replaced with the body, with the arguments substituted for the
+///   parameters.
+/// * A body that is a query, given as a string, creates a table function.
+///   Calls are replaced with the query, with the (constant) arguments
+///   substituted for the parameters.
+///
+/// Parameters are referenced by name, or positionally as `$1`, `$2`, etc.
+/// Parameters of table functions can only be referenced as placeholders
+/// (`$name` or `$1`).
+///
+/// ```sql
+/// CREATE FUNCTION clean_phone(s TEXT)
+///   RETURNS TEXT
+///   RETURN regexp_replace(s, '[^0-9]', '', 'g');
+///
+/// CREATE FUNCTION add_one(BIGINT) RETURNS BIGINT AS '$1 + 1';
+///
+/// CREATE FUNCTION orders_since(since DATE)
+///   AS 'SELECT * FROM orders WHERE order_date >= $since';
+///
+/// SELECT clean_phone(phone) FROM customers;
+/// SELECT * FROM orders_since('2024-01-01');
+/// ```
+///
+/// [`SessionContext`]: super::SessionContext
+#[derive(Debug, Default)]
+pub struct SqlFunctionFactory {}
+
+#[async_trait]
+impl FunctionFactory for SqlFunctionFactory {
+    async fn create(
+        &self,
+        state: &SessionState,
+        statement: CreateFunction,
+    ) -> Result<RegisterFunction> {
+        let CreateFunction {
+            or_replace,
+            name,
+            args,
+            return_type,
+            params,
+            ..
+        } = statement;
+
+        if let Some(language) = &params.language
+            && !language.value.eq_ignore_ascii_case("sql")
+        {
+            return not_impl_err!(
+                "Unsupported language '{}' for function '{name}': only SQL functions are supported without a custom FunctionFactory",
+                language.value
+            );
+        }
+        let Some(body) = params.function_body else {
+            return plan_err!("Function '{name}' has no body");
+        };
+        if !or_replace && function_exists(state, &name) {
+            return exec_err!("Function '{name}' already exists");
+        }
+
+        let normalize = state.config_options().sql_parser.enable_ident_normalization;
+        let parameters =
+            SqlFunctionParameters::try_new(args.unwrap_or_default(), normalize)?;
+        let volatility = params.behavior.unwrap_or(Volatility::Volatile);
+
+        let sql = match &body {
+            Expr::Literal(value, _) => value.try_as_str().flatten().map(str::to_string),
+            _ => None,
+        };
+        let body = match sql {
+            Some(sql) => plan_sql_body(state, &sql, &parameters).await?,
+            None => SqlFunctionBody::Expr(body),
+        };
+
+        match body {
+            SqlFunctionBody::Expr(body) => {
+                let function = SqlScalarFunction::try_new(
+                    name,
+                    parameters,
+                    body,
+                    return_type,
+                    volatility,
+                )?;
+                Ok(RegisterFunction::Scalar(Arc::new(ScalarUDF::from(
+                    function,
+                ))))
+            }
+            SqlFunctionBody::Query(plan, sql) => {
+                if return_type.is_some() {
+                    return plan_err!(
+                        "Table function '{name}' cannot declare RETURNS: its columns are those of its query"
+                    );
+                }
+                let function = SqlTableFunction::new(
+                    name.clone(),
+                    parameters,
+                    plan,
+                    sql,
+                    volatility,
+                );
+                Ok(RegisterFunction::Table(name, Arc::new(function)))
+            }
+        }
+    }
+}
+
+fn function_exists(state: &SessionState, name: &str) -> bool {
+    state.scalar_functions().contains_key(name)
+        || state.aggregate_functions().contains_key(name)
+        || state.window_functions().contains_key(name)
+        || state.table_functions().contains_key(name)
+}
+
+/// The planned body of a SQL function
+enum SqlFunctionBody {
+    /// The body of a scalar function
+    Expr(Expr),
+    /// The body of a table function, and its SQL text
+    Query(LogicalPlan, String),
+}
+
+/// Plans a body given as a string: a query creates a table function, anything
+/// else is parsed as the expression of a scalar function
+#[cfg(feature = "sql")]
+async fn plan_sql_body(
+    state: &SessionState,
+    sql: &str,
+    parameters: &SqlFunctionParameters,
+) -> Result<SqlFunctionBody> {
+    use datafusion_sql::parser::Statement;
+    use datafusion_sql::planner::PlannerContext;
+    use sqlparser::ast::Statement as SQLStatement;
+
+    let dialect = state.config_options().sql_parser.dialect;
+    let mut planner_context = PlannerContext::new()
+        .with_prepare_param_data_types(parameters.placeholder_fields());
+
+    match state.sql_to_statement(sql, &dialect) {
+        Ok(Statement::Statement(statement))
+            if matches!(statement.as_ref(), SQLStatement::Query(_)) =>
+        {
+            let plan = state
+                .statement_to_plan_with_context(
+                    Statement::Statement(statement),
+                    &mut planner_context,
+                )
+                .await?;
+            Ok(SqlFunctionBody::Query(plan, sql.to_string()))
+        }
+        _ => {
+            let sql_expr = state.sql_to_expr_with_alias(sql, &dialect)?;
+            let expr = state.create_logical_expr_with_context(
+                sql_expr,
+                &parameters.schema()?,
+                &mut planner_context,
+            )?;
+            Ok(SqlFunctionBody::Expr(expr))
+        }
+    }
+}
+
+#[cfg(not(feature = "sql"))]
+async fn plan_sql_body(
+    _state: &SessionState,
+    _sql: &str,
+    _parameters: &SqlFunctionParameters,
+) -> Result<SqlFunctionBody> {
+    not_impl_err!("Function bodies given as strings require the `sql` feature")
+}
+
+/// The parameters of a SQL function
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct SqlFunctionParameters {
+    /// The parameters as they are referenced from the body: by their
+    /// (normalized) name, or as `$1`, `$2`, ... if they are unnamed
+    fields: Vec<FieldRef>,
+    /// The parameters as declared, used to resolve placeholders such as
+    /// `$name` while planning the body
+    placeholder_fields: Vec<FieldRef>,
+    /// The default value of each parameter, cast to its type
+    defaults: Vec<Option<Expr>>,
+}
+
+impl SqlFunctionParameters {
+    fn try_new(args: Vec<OperateFunctionArg>, normalize: bool) -> Result<Self> {
+        let mut fields = Vec::with_capacity(args.len());
+        let mut placeholder_fields = Vec::with_capacity(args.len());
+        let mut defaults = Vec::with_capacity(args.len());
+        for (i, arg) in args.into_iter().enumerate() {
+            let (name, declared_name) = match arg.name {
+                Some(ident) if normalize && ident.quote_style.is_none() => {
+                    (ident.value.to_ascii_lowercase(), ident.value)
+                }
+                Some(ident) => (ident.value.clone(), ident.value),
+                None => (format!("${}", i + 1), String::new()),
+            };
+            let default = arg
+                .default_expr
+                .map(|expr| match expr {
+                    Expr::Literal(value, metadata) => {
+                        Ok(Expr::Literal(value.cast_to(&arg.data_type)?, metadata))
+                    }
+                    expr => expr.cast_to(&arg.data_type, &DFSchema::empty()),
+                })
+                .transpose()?;
+            fields.push(Arc::new(Field::new(name, arg.data_type.clone(), true)));
+            placeholder_fields.push(Arc::new(Field::new(
+                declared_name,
+                arg.data_type,
+                true,
+            )));
+            defaults.push(default);
+        }
+        Ok(Self {
+            fields,
+            placeholder_fields,
+            defaults,
+        })
+    }
+
+    fn schema(&self) -> Result<DFSchema> {
+        DFSchema::from_unqualified_fields(self.fields.clone().into(), Default::default())
+    }
+
+    fn placeholder_fields(&self) -> Vec<Option<FieldRef>> {
+        self.placeholder_fields.iter().cloned().map(Some).collect()
+    }
+
+    /// The number of parameters without a default value
+    fn num_required(&self) -> usize {
+        self.defaults.iter().take_while(|d| d.is_none()).count()
+    }
+
+    /// The signature accepting every valid number of arguments
+    fn signature(&self, volatility: Volatility) -> Signature {
+        let types = self
+            .fields
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect::<Vec<_>>();
+        let num_required = self.num_required();
+        if num_required == types.len() {
+            return Signature::exact(types, volatility);
+        }
+        let type_signatures = (num_required..=types.len())
+            .map(|n| match n {
+                0 => TypeSignature::Nullary,
+                n => TypeSignature::Exact(types[..n].to_vec()),
+            })
+            .collect();
+        Signature::one_of(type_signatures, volatility)
+    }
+
+    /// Replaces placeholders (`$1`, ...) in `body` with the parameter columns
+    fn bind(&self, body: Expr) -> Result<Expr> {
+        body.transform(|expr| match expr {
+            Expr::Placeholder(placeholder) => {
+                let field = placeholder
+                    .id
+                    .strip_prefix('$')
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .and_then(|index| self.fields.get(index.checked_sub(1)?));
+                match field {
+                    Some(field) => Ok(Transformed::yes(Expr::Column(
+                        Column::new_unqualified(field.name()),
+                    ))),
+                    None => plan_err!("Unknown parameter {}", placeholder.id),
+                }
+            }
+            _ => Ok(Transformed::no(expr)),
+        })
+        .data()
+    }
+
+    /// Appends the default values of parameters that are not in `args`
+    fn with_defaults(&self, name: &str, mut args: Vec<Expr>) -> Result<Vec<Expr>> {
+        if args.len() > self.fields.len() {
+            return plan_err!(
+                "Function '{name}' expects at most {} arguments, got {}",
+                self.fields.len(),
+                args.len()
+            );
+        }
+        for (field, default) in self.fields.iter().zip(&self.defaults).skip(args.len()) {
+            match default {
+                Some(default) => args.push(default.clone()),
+                None => {
+                    return plan_err!(
+                        "Function '{name}' requires a value for parameter {}",
+                        field.name()
+                    );
+                }
+            }
+        }
+        Ok(args)
+    }
+
+    /// Replaces the parameter columns in `body` with `args`
+    fn substitute(&self, body: Expr, args: &[Expr]) -> Result<Expr> {
+        body.transform(|expr| match expr {
+            Expr::Column(column) if column.relation.is_none() => {
+                match self.fields.iter().position(|f| f.name() == &column.name) {
+                    Some(index) => Ok(Transformed::yes(args[index].clone())),
+                    None => Ok(Transformed::no(Expr::Column(column))),
+                }
+            }
+            _ => Ok(Transformed::no(expr)),
+        })
+        .data()
+    }
+
+    /// Documentation listing the parameters of the function
+    fn documentation(&self, name: &str, description: String) -> Documentation {
+        let syntax_example = format!(
+            "{name}({})",
+            self.fields
+                .iter()
+                .map(|f| f.name().as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        self.fields
+            .iter()
+            .fold(
+                Documentation::builder(
+                    DOC_SECTION_SQL_FUNCTIONS,
+                    description,
+                    syntax_example,
+                ),
+                |builder, field| {
+                    builder.with_argument(field.name(), field.data_type().to_string())
+                },
+            )
+            .build()
+    }
+}
+
+/// A scalar function created from an expression, see [`SqlFunctionFactory`]
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct SqlScalarFunction {
+    name: String,
+    parameters: SqlFunctionParameters,
+    /// The body, referencing the parameters as columns
+    body: Expr,
+    return_type: DataType,
+    signature: Signature,
+    documentation: Documentation,
+}
+
+impl SqlScalarFunction {
+    fn try_new(
+        name: String,
+        parameters: SqlFunctionParameters,
+        body: Expr,
+        return_type: Option<DataType>,
+        volatility: Volatility,
+    ) -> Result<Self> {
+        let body = parameters.bind(body)?;
+        if body.exists(|expr| {
+            Ok(matches!(
+                expr,
+                Expr::ScalarSubquery(_) | Expr::InSubquery(_) | Expr::Exists(_)
+            ))
+        })? {
+            return not_impl_err!(
+                "Subqueries are not supported in the body of scalar function '{name}', use AS '<query>' to create a table function"
+            );
+        }
+        if body.exists(|expr| {
+            Ok(matches!(
+                expr,
+                Expr::AggregateFunction(_) | Expr::WindowFunction(_)
+            ))
+        })? {
+            return plan_err!(
+                "Aggregate and window functions are not allowed in the body of scalar function '{name}'"
+            );
+        }
+
+        // Type check the body against the declared parameter types, which
+        // the arguments are coerced to by the signature
+        let schema = parameters.schema()?;
+        let body = body
+            .rewrite(&mut TypeCoercionRewriter::new(&schema))
+            .data()?;
+        let body = match return_type {
+            Some(return_type) => body.cast_to(&return_type, &schema)?,
+            None => body,
+        };
+        let return_type = body.get_type(&schema)?;
+
+        let documentation = parameters.documentation(
+            &name,
+            format!("SQL function returning {}", body.human_display()),
+        );
+        Ok(Self {
+            signature: parameters.signature(volatility),
+            name,
+            parameters,
+            body,
+            return_type,
+            documentation,
+        })
+    }
+}
+
+impl ScalarUDFImpl for SqlScalarFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.return_type.clone())
+    }
+
+    /// Evaluates the body directly. Calls are normally inlined by
+    /// [`Self::simplify`], but may be evaluated before that, e.g. when
+    /// constant folding an immutable function.
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let fields = &self.parameters.fields[..args.args.len()];
+        let columns = args
+            .args
+            .iter()
+            .map(|arg| arg.to_array(args.number_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let schema = Arc::new(Schema::new(fields.to_vec()));
+        let batch = RecordBatch::try_new_with_options(
+            Arc::clone(&schema),
+            columns,
+            &RecordBatchOptions::new().with_row_count(Some(args.number_rows)),
+        )?;
+
+        let columns = fields
+            .iter()
+            .map(|f| Expr::Column(Column::new_unqualified(f.name())))
+            .collect();
+        let columns = self.parameters.with_defaults(&self.name, columns)?;
+        let body = self.parameters.substitute(self.body.clone(), &columns)?;
+        let body = create_physical_expr(
+            &body,
+            &DFSchema::try_from(schema)?,
+            &ExecutionProps::new(),
+        )?;
+        body.evaluate(&batch)
+    }
+
+    fn simplify(
+        &self,
+        args: Vec<Expr>,
+        _info: &SimplifyContext,
+    ) -> Result<ExprSimplifyResult> {
+        let args = self.parameters.with_defaults(&self.name, args)?;
+        let body = self.parameters.substitute(self.body.clone(), &args)?;
+        Ok(ExprSimplifyResult::Simplified(body))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        Some(&self.documentation)
+    }
+}
+
+/// A table function created from a query, see [`SqlFunctionFactory`]
+#[derive(Debug)]
+struct SqlTableFunction {
+    name: String,
+    parameters: SqlFunctionParameters,
+    /// The query, referencing the parameters as placeholders
+    plan: LogicalPlan,
+    sql: String,
+    signature: Signature,
+    documentation: Documentation,
+}
+
+impl SqlTableFunction {
+    fn new(
+        name: String,
+        parameters: SqlFunctionParameters,
+        plan: LogicalPlan,
+        sql: String,
+        volatility: Volatility,
+    ) -> Self {
+        let documentation = parameters.documentation(
+            &name,
+            format!("SQL table function returning the results of `{sql}`"),
+        );
+        Self {
+            signature: parameters.signature(volatility),
+            name,
+            parameters,
+            plan,
+            sql,
+            documentation,
+        }
+    }
+}
+
+impl TableFunctionImpl for SqlTableFunction {
+    fn call_with_args(&self, args: TableFunctionArgs) -> Result<Arc<dyn TableProvider>> {
+        let args = self
+            .parameters
+            .with_defaults(&self.name, args.exprs().to_vec())?;
+        let values = args
+            .into_iter()
+            .zip(&self.parameters.fields)
+            .map(|(arg, field)| match arg {
+                Expr::Literal(value, _) => value.cast_to(field.data_type()),
+                arg => plan_err!(
+                    "Arguments of table function '{}' must be literals, got {arg}",
+                    self.name
+                ),
+            })
+            .collect::<Result<Vec<ScalarValue>>>()?;
+
+        let plan = self.plan.clone().with_param_values(values)?;
+        Ok(Arc::new(ViewTable::new(plan, Some(self.sql.clone()))))
+    }
+
+    fn signature(&self) -> Option<&Signature> {
+        Some(&self.signature)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        Some(&self.documentation)
+    }
+}
@@ -66,6 +66,7 @@ use datafusion_expr::simplify::SimplifyContext;
 use datafusion_expr::{
     AggregateUDF, Explain, Expr, HigherOrderUDF, LogicalPlan, ScalarUDF, WindowUDF,
 };
+use datafusion_optimizer::materialized_view_rewrite::MaterializedViewCandidate;
 use datafusion_optimizer::simplify_expressions::ExprSimplifier;
 use datafusion_optimizer::{
     Analyzer, AnalyzerRule, Optimizer, OptimizerConfig, OptimizerRule,
@@ -82,6 +83,8 @@ use datafusion_session::Session;
 use datafusion_sql::{
     parser::{DFParserBuilder, Statement},
     planner::{ContextProvider, ParserOptions, PlannerContext, SqlToRel},
+    resolve::{dynamic_pivot_queries, resolve_dynamic_pivots},
+    unparser::Unparser,
 };
 
 use async_trait::async_trait;
@@ -91,7 +94,10 @@ use log::{debug, info};
 use object_store::ObjectStore;
 #[cfg(feature = "sql")]
 use sqlparser::{
-    ast::{Expr as SQLExpr, ExprWithAlias as SQLExprWithAlias},
+    ast::{
+        Expr as SQLExpr, ExprWithAlias as SQLExprWithAlias, Ident,
+        Statement as SQLStatement,
+    },
     dialect::dialect_from_str,
 };
 use url::Url;
@@ -208,6 +214,8 @@ pub struct SessionState {
     /// Cache logical plans of prepared statements for later execution.
     /// Key is the prepared statement name.
     prepared_plans: HashMap<String, Arc<PreparedPlan>>,
+    /// Materialized views the optimizer may use to answer queries
+    materialized_views: Vec<MaterializedViewCandidate>,
 }
 
 impl PhysicalOptimizerContext for SessionState {
@@ -255,6 +263,7 @@ impl Debug for SessionState {
             .field("aggregate_functions", &self.aggregate_functions)
             .field("window_functions", &self.window_functions)
             .field("prepared_plans", &self.prepared_plans)
+            .field("materialized_views", &self.materialized_views)
             .finish()
     }
 }
@@ -349,9 +358,10 @@ impl SessionState {
         let resolved_ref = self.resolve_table_ref(table_ref);
         if self.config.information_schema() && *resolved_ref.schema == *INFORMATION_SCHEMA
         {
-            return Ok(Arc::new(InformationSchemaProvider::new(Arc::clone(
-                &self.catalog_list,
-            ))));
+            return Ok(Arc::new(
+                InformationSchemaProvider::new(Arc::clone(&self.catalog_list))
+                    .with_table_functions(self.table_functions.clone()),
+            ));
         }
 
         self.catalog_list
@@ -522,10 +532,25 @@ impl SessionState {
     }
 
     /// Convert an AST Statement into a LogicalPlan
+    ///
+    /// This does not run any query, so the dynamic pivots of the statement
+    /// must have been resolved with [`Self::resolve_dynamic_pivots`].
     #[cfg(feature = "sql")]
     pub async fn statement_to_plan(
         &self,
         statement: Statement,
+    ) -> datafusion_common::Result<LogicalPlan> {
+        self.statement_to_plan_with_context(statement, &mut PlannerContext::new())
+            .await
+    }
+
+    /// Convert an AST Statement into a LogicalPlan, planning it with the
+    /// provided [`PlannerContext`] (e.g. to supply the types of placeholders)
+    #[cfg(feature = "sql")]
+    pub(crate) async fn statement_to_plan_with_context(
+        &self,
+        statement: Statement,
+        planner_context: &mut PlannerContext,
     ) -> datafusion_common::Result<LogicalPlan> {
         let references = self.resolve_table_references(&statement)?;
 
@@ -547,7 +572,87 @@ impl SessionState {
         }
 
         let query = SqlToRel::new_with_options(&provider, self.get_parser_options());
-        query.statement_to_plan(statement)
+        match statement {
+            Statement::Statement(statement) => {
+                query.sql_statement_to_plan_with_context(*statement, planner_context)
+            }
+            statement => query.statement_to_plan(statement),
+        }
+    }
+
+    /// Resolve the values of the dynamic pivots in the SQL statement, i.e.
+    /// `PIVOT ... IN (ANY)` and `PIVOT ... IN (<subquery>)`, by running the
+    /// queries returning their values.
+    ///
+    /// The output columns of a dynamic pivot depend on the data, so this step
+    /// runs queries, and must be called before [`Self::statement_to_plan`] to
+    /// plan such a statement. [`Self::create_logical_plan`] calls it. The
+    /// dynamic pivots of an `EXPLAIN` statement are not resolved, as it does
+    /// not run queries, unless it is an `EXPLAIN ANALYZE`.
+    ///
+    /// See [`datafusion_sql::resolve::dynamic_pivot_queries`] for more information.
+    #[cfg(feature = "sql")]
+    pub async fn resolve_dynamic_pivots(
+        &self,
+        statement: &mut Statement,
+    ) -> datafusion_common::Result<()> {
+        let queries = {
+            let dialect = self.config.options().sql_parser.dialect;
+            let dialect = dialect_from_str(dialect).ok_or_else(|| {
+                plan_datafusion_err!(
+                    "Unsupported SQL dialect: {dialect}. Available dialects: {}.",
+                    Dialect::available()
+                )
+            })?;
+            dynamic_pivot_queries(statement, dialect.as_ref())?
+        };
+        if queries.is_empty() {
+            return Ok(());
+        }
+
+        let mut values = Vec::with_capacity(queries.len());
+        for query in queries {
+            let mut query =
+                Statement::Statement(Box::new(SQLStatement::Query(Box::new(query))));
+            Box::pin(self.resolve_dynamic_pivots(&mut query)).await?;
+            let plan = self.statement_to_plan(query).await?;
+            if plan.schema().fields().len() != 1 {
+                return datafusion_common::plan_err!(
+                    "PIVOT values query must return a single column"
+                );
+            }
+            let plan = self.create_physical_plan(&plan).await?;
+            let batches =
+                datafusion_physical_plan::collect(plan, self.task_ctx()).await?;
+
+            // NULL never matches a pivot value, and duplicates would produce
+            // duplicate columns
+            let unparser = Unparser::default();
+            let mut seen = HashSet::new();
+            let mut pivot_values = vec![];
+            for batch in &batches {
+                let column = batch.column(0);
+                for i in 0..column.len() {
+                    let value =
+                        datafusion_common::ScalarValue::try_from_array(column, i)?;
+                    if value.is_null() || !seen.insert(value.clone()) {
+                        continue;
+                    }
+                    let name = match &value {
+                        datafusion_common::ScalarValue::Utf8(Some(s))
+                        | datafusion_common::ScalarValue::Utf8View(Some(s))
+                        | datafusion_common::ScalarValue::LargeUtf8(Some(s)) => s.clone(),
+                        value => value.to_string(),
+                    };
+                    pivot_values.push(SQLExprWithAlias {
+                        expr: unparser.expr_to_sql(&datafusion_expr::lit(value))?,
+                        alias: Some(Ident::with_quote('"', name)),
+                    });
+                }
+            }
+            values.push(pivot_values);
+        }
+        resolve_dynamic_pivots(statement, values)
     }
 
     #[cfg(feature = "sql")]
@@ -587,7 +692,8 @@ impl SessionState {
         sql: &str,
     ) -> datafusion_common::Result<LogicalPlan> {
         let dialect = self.config.options().sql_parser.dialect;
-        let statement = self.sql_to_statement(sql, &dialect)?;
+        let mut statement = self.sql_to_statement(sql, &dialect)?;
+        self.resolve_dynamic_pivots(&mut statement).await?;
         let plan = self.statement_to_plan(statement).await?;
         Ok(plan)
     }
@@ -614,6 +720,22 @@ impl SessionState {
         &self,
         sql_expr: SQLExprWithAlias,
         df_schema: &DFSchema,
+    ) -> datafusion_common::Result<Expr> {
+        self.create_logical_expr_with_context(
+            sql_expr,
+            df_schema,
+            &mut PlannerContext::new(),
+        )
+    }
+
+    /// Creates a datafusion style AST [`Expr`] from a SQL expression, planning
+    /// it with the provided [`PlannerContext`]
+    #[cfg(feature = "sql")]
+    pub(crate) fn create_logical_expr_with_context(
+        &self,
+        sql_expr: SQLExprWithAlias,
+        df_schema: &DFSchema,
+        planner_context: &mut PlannerContext,
     ) -> datafusion_common::Result<Expr> {
         let provider = SessionContextProvider {
             state: self,
@@ -621,7 +743,7 @@ impl SessionState {
         };
 
         let query = SqlToRel::new_with_options(&provider, self.get_parser_options());
-        query.sql_to_expr_with_alias(sql_expr, df_schema, &mut PlannerContext::new())
+        query.sql_to_expr_with_alias(sql_expr, df_schema, planner_context)
     }
 
     /// Returns the [`Analyzer`] for this session
@@ -1015,6 +1137,42 @@ impl SessionState {
             None => exec_err!("Prepared statement '{}' does not exist", name),
         }
     }
+
+    /// Make the materialized view `name`, whose contents are the result of
+    /// `definition` and are read from `source`, available to the optimizer
+    /// for answering queries. Replaces any view registered under the same
+    /// name.
+    ///
+    /// `definition` is analyzed before it is registered.
+    pub fn register_materialized_view(
+        &mut self,
+        name: impl Into<TableReference>,
+        definition: LogicalPlan,
+        source: Arc<dyn TableSource>,
+    ) -> datafusion_common::Result<()> {
+        let name = TableReference::from(self.resolve_table_ref(name));
+        let definition = self.analyzer.execute_and_check(
+            definition,
+            self.config_options(),
+            |_, _| {},
+        )?;
+        self.deregister_materialized_view(name.clone());
+        self.materialized_views
+            .push(MaterializedViewCandidate::new(name, definition, source));
+        Ok(())
+    }
+
+    /// Stop using the materialized view `name` to answer queries. Returns
+    /// `true` if it was registered.
+    pub fn deregister_materialized_view(
+        &mut self,
+        name: impl Into<TableReference>,
+    ) -> bool {
+        let name = TableReference::from(self.resolve_table_ref(name));
+        let len = self.materialized_views.len();
+        self.materialized_views.retain(|view| view.name() != &name);
+        self.materialized_views.len() < len
+    }
 }
 
 /// A builder to be used for building [`SessionState`]'s. Defaults will
@@ -1050,6 +1208,7 @@ pub struct SessionStateBuilder {
     function_factory: Option<Arc<dyn FunctionFactory>>,
     cache_factory: Option<Arc<dyn CacheFactory>>,
     statistics_registry: Option<StatisticsRegistry>,
+    materialized_views: Option<Vec<MaterializedViewCandidate>>,
     // fields to support convenience functions
     analyzer_rules: Option<Vec<Arc<dyn AnalyzerRule + Send + Sync>>>,
     optimizer_rules: Option<Vec<Arc<dyn OptimizerRule + Send + Sync>>>,
@@ -1093,6 +1252,7 @@ impl SessionStateBuilder {
             function_factory: None,
             cache_factory: None,
             statistics_registry: None,
+            materialized_views: None,
             // fields to support convenience functions
             analyzer_rules: None,
             optimizer_rules: None,
@@ -1153,6 +1313,7 @@ impl SessionStateBuilder {
             function_factory: existing.function_factory,
             cache_factory: existing.cache_factory,
             statistics_registry: existing.statistics_registry,
+            materialized_views: Some(existing.materialized_views),
             // fields to support convenience functions
             analyzer_rules: None,
             optimizer_rules: None,
@@ -1491,7 +1652,8 @@ impl SessionStateBuilder {
     ///
     /// The registry allows physical optimizer rules to access enhanced statistics
     /// (e.g., NDV overrides, histograms) beyond what is available from
-    /// `ExecutionPlan::partition_statistics()`.
+    /// `ExecutionPlan::partition_statistics()`. Defaults to
+    /// [`SessionStateDefaults::default_statistics_registry`].
     pub fn with_statistics_registry(mut self, registry: StatisticsRegistry) -> Self {
         self.statistics_registry = Some(registry);
         self
@@ -1566,6 +1728,7 @@ impl SessionStateBuilder {
             function_factory,
             cache_factory,
             statistics_registry,
+            materialized_views,
             analyzer_rules,
             optimizer_rules,
             physical_optimizer_rules,
@@ -1573,6 +1736,9 @@ impl SessionStateBuilder {
 
         let config = config.unwrap_or_default();
         let runtime_env = runtime_env.unwrap_or_else(|| Arc::new(RuntimeEnv::default()));
+        let statistics_registry = statistics_registry.unwrap_or_else(|| {
+            SessionStateDefaults::default_statistics_registry(&runtime_env)
+        });
 
         let mut state = SessionState {
             session_id: session_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
@@ -1607,8 +1773,9 @@ impl SessionStateBuilder {
             runtime_env,
             function_factory,
             cache_factory,
-            statistics_registry,
+            statistics_registry: Some(statistics_registry),
             prepared_plans: HashMap::new(),
+            materialized_views: materialized_views.unwrap_or_default(),
         };
 
         if let Some(file_formats) = file_formats {
@@ -1908,6 +2075,7 @@ impl Debug for SessionStateBuilder {
             .field("higher_order_functions", &self.higher_order_functions)
             .field("aggregate_functions", &self.aggregate_functions)
             .field("window_functions", &self.window_functions)
+            .field("materialized_views", &self.materialized_views)
             .finish()
     }
 }
@@ -2282,6 +2450,10 @@ impl OptimizerConfig for SessionState {
     fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
         Some(self)
     }
+
+    fn materialized_views(&self) -> &[MaterializedViewCandidate] {
+        &self.materialized_views
+    }
 }
 
 /// Create a new task context instance from SessionState
@@ -2414,6 +2586,32 @@ mod tests {
         assert!(sql_to_expr(&state).is_err())
     }
 
+    #[test]
+    fn test_default_statistics_registry() {
+        use datafusion_datasource::histogram::SampledHistogramStatisticsProvider;
+        use datafusion_physical_plan::operator_statistics::StatisticsRegistry;
+
+        let state = SessionStateBuilder::new().build();
+        let registry = state.statistics_registry().unwrap();
+        assert!(
+            registry
+                .provider::<SampledHistogramStatisticsProvider>()
+                .is_some()
+        );
+        #[cfg(feature = "parquet")]
+        assert!(
+            registry
+                .provider::<crate::datasource::physical_plan::parquet::ParquetPageIndexStatisticsProvider>()
+                .is_some()
+        );
+
+        // A registry set on the builder replaces the default one
+        let state = SessionStateBuilder::new()
+            .with_statistics_registry(StatisticsRegistry::new())
+            .build();
+        assert!(state.statistics_registry().unwrap().providers().is_empty());
+    }
+
     #[test]
     #[cfg(feature = "sql")]
     fn test_create_logical_expr_from_sql_expr() {
@@ -22,9 +22,14 @@ use crate::datasource::file_format::arrow::ArrowFormatFactory;
 #[cfg(feature = "avro")]
 use crate::datasource::file_format::avro::AvroFormatFactory;
 use crate::datasource::file_format::csv::CsvFormatFactory;
+use crate::datasource::file_format::fixed_width::FixedWidthFormatFactory;
 use crate::datasource::file_format::json::JsonFormatFactory;
+#[cfg(feature = "orc")]
+use crate::datasource::file_format::orc::OrcFormatFactory;
 #[cfg(feature = "parquet")]
 use crate::datasource::file_format::parquet::ParquetFormatFactory;
+#[cfg(feature = "parquet")]
+use crate::datasource::physical_plan::parquet::ParquetPageIndexStatisticsProvider;
 use crate::datasource::provider::DefaultTableFactory;
 use crate::execution::context::SessionState;
 #[cfg(feature = "nested_expressions")]
@@ -32,12 +37,14 @@ use crate::functions_nested;
 use crate::{functions, functions_aggregate, functions_table, functions_window};
 use datafusion_catalog::TableFunction;
 use datafusion_catalog::{MemoryCatalogProvider, MemorySchemaProvider};
+use datafusion_datasource::histogram::SampledHistogramStatisticsProvider;
 use datafusion_execution::config::SessionConfig;
 use datafusion_execution::object_store::ObjectStoreUrl;
 use datafusion_execution::runtime_env::RuntimeEnv;
 use datafusion_expr::planner::ExprPlanner;
 use datafusion_expr::registry::ExtensionTypeRegistrationRef;
 use datafusion_expr::{AggregateUDF, HigherOrderUDF, ScalarUDF, WindowUDF};
+use datafusion_physical_plan::operator_statistics::StatisticsRegistry;
 use std::collections::HashMap;
 use std::sync::Arc;
 use url::Url;
@@ -58,6 +65,8 @@ impl SessionStateDefaults {
         table_factories.insert("NDJSON".into(), Arc::new(DefaultTableFactory::new()));
         table_factories.insert("AVRO".into(), Arc::new(DefaultTableFactory::new()));
         table_factories.insert("ARROW".into(), Arc::new(DefaultTableFactory::new()));
+        table_factories.insert("ORC".into(), Arc::new(DefaultTableFactory::new()));
+        table_factories.insert("FWF".into(), Arc::new(DefaultTableFactory::new()));
 
         table_factories
     }
@@ -152,13 +161,31 @@ impl SessionStateDefaults {
             Arc::new(JsonFormatFactory::new()),
             Arc::new(CsvFormatFactory::new()),
             Arc::new(ArrowFormatFactory::new()),
+            Arc::new(FixedWidthFormatFactory::new()),
             #[cfg(feature = "avro")]
             Arc::new(AvroFormatFactory::new()),
+            #[cfg(feature = "orc")]
+            Arc::new(OrcFormatFactory::new()),
         ];
 
         file_formats
     }
 
+    /// returns the default [`StatisticsRegistry`]: the built-in providers,
+    /// preceded by the providers of histograms of the files sampled with
+    /// [`SampledHistogramStatisticsProvider::sample`] and of Parquet files
+    /// whose page indexes are cached in `runtime_env`
+    #[cfg_attr(not(feature = "parquet"), expect(unused_variables))]
+    pub fn default_statistics_registry(runtime_env: &RuntimeEnv) -> StatisticsRegistry {
+        let mut registry = StatisticsRegistry::default_with_builtin_providers();
+        registry.register(Arc::new(SampledHistogramStatisticsProvider::default()));
+        #[cfg(feature = "parquet")]
+        registry.register(Arc::new(ParquetPageIndexStatisticsProvider::new(
+            runtime_env.cache_manager.get_file_metadata_cache(),
+        )));
+        registry
+    }
+
     /// registers all builtin functions - scalar, array and aggregate
     pub fn register_builtin_functions(state: &mut SessionState) {
         Self::register_scalar_functions(state);
@@ -787,6 +787,9 @@ pub use parquet;
 #[cfg(feature = "avro")]
 pub use datafusion_datasource_avro::arrow_avro;
 
+#[cfg(feature = "orc")]
+pub use datafusion_datasource_orc::orc_rust;
+
 #[cfg(test)]
 mod optimizer_rule_reference;
 
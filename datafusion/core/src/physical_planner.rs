@@ -21,6 +21,7 @@ use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::datasource::file_format::csv::CSV_DIALECT_METADATA_PREFIX;
 use crate::datasource::file_format::file_type_to_format;
 use crate::datasource::listing::ListingTableUrl;
 use crate::datasource::physical_plan::{FileOutputMode, FileSinkConfig};
@@ -42,7 +43,8 @@ use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::filter::FilterExecBuilder;
 use crate::physical_plan::joins::utils as join_utils;
 use crate::physical_plan::joins::{
-    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode, SortMergeJoinExec,
+    AsOfJoinExec, CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode,
+    SortMergeJoinExec,
 };
 use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
 use crate::physical_plan::projection::{ProjectionExec, ProjectionExpr};
@@ -50,7 +52,10 @@ use crate::physical_plan::repartition::RepartitionExec;
 use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::union::UnionExec;
 use crate::physical_plan::unnest::UnnestExec;
-use crate::physical_plan::windows::{BoundedWindowAggExec, WindowAggExec};
+use crate::physical_plan::windows::{
+    BoundedWindowAggExec, MatchRecognizeExec, MatchRecognizeMeasure, MeasureFunction,
+    WindowAggExec,
+};
 use crate::physical_plan::{
     ExecutionPlan, ExecutionPlanProperties, InputOrderMode, Partitioning, PhysicalExpr,
     WindowExpr, displayable, windows,
@@ -62,6 +67,7 @@ use arrow::compute::SortOptions;
 use arrow::datatypes::Schema;
 use arrow_schema::Field;
 use datafusion_catalog::ScanArgs;
+use datafusion_catalog::transaction::Transaction;
 use datafusion_common::Column;
 use datafusion_common::HashMap as DFHashMap;
 use datafusion_common::display::ToStringifiedPlan;
@@ -78,6 +84,7 @@ use datafusion_common::{
 };
 use datafusion_datasource::file_groups::FileGroup;
 use datafusion_datasource::memory::MemorySourceConfig;
+use datafusion_datasource::source::DataSourceExec;
 use datafusion_expr::dml::{CopyTo, InsertOp};
 use datafusion_expr::execution_props::{ScalarSubqueryResults, SubqueryIndex};
 use datafusion_expr::expr::{
@@ -85,18 +92,21 @@ use datafusion_expr::expr::{
     physical_name,
 };
 use datafusion_expr::expr_rewriter::unnormalize_cols;
-use datafusion_expr::logical_plan::Subquery;
 use datafusion_expr::logical_plan::builder::wrap_projection_for_join_if_necessary;
-use datafusion_expr::utils::{expr_to_columns, split_conjunction};
+use datafusion_expr::logical_plan::{Subquery, TransactionAccessMode};
+use datafusion_expr::utils::{
+    expr_to_columns, find_asof_match_condition, split_conjunction,
+};
 use datafusion_expr::{
     Analyze, BinaryExpr, DescribeTable, DmlStatement, Explain, ExplainFormat, Extension,
-    FetchType, Filter, JoinType, Operator, RecursiveQuery, SkipType, StringifiedPlan,
-    WindowFrame, WindowFrameBound, WriteOp,
+    FetchType, Filter, JoinType, MatchRecognize, Operator, RecursiveQuery,
+    RowPatternFunction, Sample, SampleMethod, SkipType, StringifiedPlan, WindowFrame,
+    WindowFrameBound, WriteOp,
 };
 use datafusion_physical_expr::aggregate::{
     AggregateFunctionExpr, LoweredAggregate, LoweredAggregateBuilder,
 };
-use datafusion_physical_expr::expressions::Literal;
+use datafusion_physical_expr::expressions::{Literal, collation_keys};
 use datafusion_physical_expr::{
     LexOrdering, PhysicalSortExpr, RangePartitioning, create_physical_sort_exprs,
 };
@@ -106,6 +116,7 @@ use datafusion_physical_plan::execution_plan::InvariantLevel;
 use datafusion_physical_plan::joins::PiecewiseMergeJoinExec;
 use datafusion_physical_plan::placeholder_row::PlaceholderRowExec;
 use datafusion_physical_plan::recursive_query::RecursiveQueryExec;
+use datafusion_physical_plan::sample::SampleExec;
 use datafusion_physical_plan::scalar_subquery::{ScalarSubqueryExec, ScalarSubqueryLink};
 use datafusion_physical_plan::unnest::ListUnnest;
 
@@ -651,6 +662,9 @@ impl DefaultPhysicalPlanner {
         children: ChildrenContainer,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let execution_props = session_state.execution_props();
+        if let Some(txn) = Transaction::current(session_state) {
+            check_write_in_transaction(node, &txn)?;
+        }
         let exec_node: Arc<dyn ExecutionPlan> = match node {
             // Leaves (no children)
             LogicalPlan::TableScan(scan) => {
@@ -824,6 +838,7 @@ impl DefaultPhysicalPlanner {
                     keep_partition_by_columns,
                     file_extension,
                     file_output_mode,
+                    bucket_spec: None,
                 };
 
                 let ordering = input_exec.properties().output_ordering().cloned();
@@ -924,6 +939,27 @@ impl DefaultPhysicalPlanner {
                     );
                 }
             }
+            LogicalPlan::Dml(DmlStatement {
+                table_name,
+                target,
+                op: WriteOp::Merge,
+                ..
+            }) => {
+                if let Some(provider) = target.downcast_ref::<DefaultTableSource>() {
+                    let input_exec = children.one()?;
+                    provider
+                        .table_provider
+                        .merge_into(session_state, input_exec)
+                        .await
+                        .map_err(|e| {
+                            e.context(format!("MERGE operation on table '{table_name}'"))
+                        })?
+                } else {
+                    return exec_err!(
+                        "Table source can't be downcasted to DefaultTableSource"
+                    );
+                }
+            }
             LogicalPlan::Window(Window { window_expr, .. }) => {
                 assert_or_internal_err!(
                     !window_expr.is_empty(),
@@ -995,6 +1031,100 @@ impl DefaultPhysicalPlanner {
                     )?)
                 }
             }
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                input,
+                partition_by,
+                order_by,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                pattern,
+                defines,
+                ..
+            }) => {
+                let input_exec = children.one()?;
+                let physical_input_schema = input_exec.schema();
+                let input_dfschema = input.schema();
+
+                let partition_by =
+                    create_physical_exprs(partition_by, input_dfschema, execution_props)?;
+                let order_by = create_physical_sort_exprs(
+                    order_by,
+                    input_dfschema,
+                    execution_props,
+                )?;
+                let defines = defines
+                    .iter()
+                    .map(|define| {
+                        let condition = create_physical_expr(
+                            &define.condition,
+                            input_dfschema,
+                            execution_props,
+                        )?;
+                        Ok((define.variable.clone(), condition))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let measures = measures
+                    .iter()
+                    .map(|measure| {
+                        let function = match &measure.function {
+                            RowPatternFunction::First(expr) => MeasureFunction::First(
+                                create_physical_expr(
+                                    expr,
+                                    input_dfschema,
+                                    execution_props,
+                                )?,
+                            ),
+                            RowPatternFunction::Last(expr) => MeasureFunction::Last(
+                                create_physical_expr(
+                                    expr,
+                                    input_dfschema,
+                                    execution_props,
+                                )?,
+                            ),
+                            RowPatternFunction::Aggregate(expr) => {
+                                let lowered = LoweredAggregateBuilder::new(
+                                    expr,
+                                    input_dfschema,
+                                    &physical_input_schema,
+                                    execution_props,
+                                )
+                                .build()?;
+                                if lowered.filter.is_some()
+                                    || !lowered.order_bys.is_empty()
+                                {
+                                    return not_impl_err!(
+                                        "FILTER and ORDER BY are not supported in MATCH_RECOGNIZE measure {}",
+                                        measure.name
+                                    );
+                                }
+                                MeasureFunction::Aggregate(lowered.aggregate)
+                            }
+                            RowPatternFunction::Classifier => MeasureFunction::Classifier,
+                            RowPatternFunction::MatchNumber => {
+                                MeasureFunction::MatchNumber
+                            }
+                        };
+                        Ok(MatchRecognizeMeasure {
+                            name: measure.name.clone(),
+                            variable: measure.variable.clone(),
+                            function,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Arc::new(MatchRecognizeExec::try_new(
+                    input_exec,
+                    partition_by,
+                    order_by,
+                    measures,
+                    *rows_per_match,
+                    after_match_skip.clone(),
+                    pattern.as_ref().clone(),
+                    defines,
+                    Arc::clone(node.schema().inner()),
+                )?)
+            }
             LogicalPlan::Aggregate(Aggregate {
                 input,
                 group_expr,
@@ -1345,6 +1475,21 @@ impl DefaultPhysicalPlanner {
 
                 Arc::new(GlobalLimitExec::new(input, skip, fetch))
             }
+            LogicalPlan::Sample(Sample { method, seed, .. }) => {
+                let input = children.one()?;
+                // Block sampling can skip reading the unsampled blocks if it is
+                // pushed into the data source
+                let pushed_down = match (method, input.downcast_ref::<DataSourceExec>()) {
+                    (SampleMethod::System(fraction), Some(source)) => {
+                        source.try_pushdown_sample(*fraction, *seed)?
+                    }
+                    _ => None,
+                };
+                match pushed_down {
+                    Some(source) => Arc::new(source),
+                    None => Arc::new(SampleExec::new(input, *method, *seed)),
+                }
+            }
             LogicalPlan::Unnest(Unnest {
                 list_type_columns,
                 struct_type_columns,
@@ -1509,7 +1654,14 @@ impl DefaultPhysicalPlanner {
                         let l = create_physical_expr(l, left_df_schema, execution_props)?;
                         let r =
                             create_physical_expr(r, right_df_schema, execution_props)?;
-                        Ok((l, r))
+                        // Keys with a non binary collation are joined on
+                        // their collation keys
+                        collation_keys(
+                            l,
+                            left_df_schema.as_arrow(),
+                            r,
+                            right_df_schema.as_arrow(),
+                        )
                     })
                     .collect::<Result<join_utils::JoinOn>>()?;
 
@@ -1628,7 +1780,27 @@ impl DefaultPhysicalPlanner {
                     session_state.config_options().optimizer.prefer_hash_join;
 
                 // TODO: Allow PWMJ to deal with residual equijoin conditions
-                let join: Arc<dyn ExecutionPlan> = if join_on.is_empty() {
+                let join: Arc<dyn ExecutionPlan> = if *join_type == JoinType::AsOf {
+                    let Some(match_condition) = filter else {
+                        return internal_err!("As-of join requires a match condition");
+                    };
+                    let (l, op, r) = find_asof_match_condition(
+                        match_condition,
+                        left_df_schema,
+                        right_df_schema,
+                    )?;
+                    let match_on = (
+                        create_physical_expr(&l, left_df_schema, execution_props)?,
+                        create_physical_expr(&r, right_df_schema, execution_props)?,
+                    );
+                    Arc::new(AsOfJoinExec::try_new(
+                        physical_left,
+                        physical_right,
+                        join_on,
+                        match_on,
+                        op,
+                    )?)
+                } else if join_on.is_empty() {
                     if join_filter.is_none() && *join_type == JoinType::Inner {
                         // cross join if there is no join conditions and no join filter set
                         Arc::new(CrossJoinExec::new(physical_left, physical_right))
@@ -2194,6 +2366,34 @@ fn get_physical_expr_pair(
     Ok((physical_expr, physical_name))
 }
 
+/// Checks that `node` can run in `txn`: read-only transactions cannot change
+/// data, and other transactions can only change tables that support
+/// transactions, as other changes could not be rolled back.
+fn check_write_in_transaction(node: &LogicalPlan, txn: &Transaction) -> Result<()> {
+    match node {
+        LogicalPlan::Dml(dml)
+            if txn.access_mode() == &TransactionAccessMode::ReadOnly =>
+        {
+            plan_err!("Cannot execute {} in a read-only transaction", dml.op)
+        }
+        LogicalPlan::Dml(dml)
+            if !source_as_provider(&dml.target)?.supports_transactions() =>
+        {
+            plan_err!(
+                "Cannot execute {} in a transaction, table {} does not support transactions",
+                dml.op,
+                dml.table_name
+            )
+        }
+        LogicalPlan::Copy(_) => {
+            plan_err!(
+                "Cannot execute COPY in a transaction, its files are not transactional"
+            )
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Extract filter predicates from a DML input plan (DELETE/UPDATE).
 ///
 /// Walks the logical plan tree and collects Filter predicates and any filters
@@ -2279,12 +2479,14 @@ fn extract_dml_filters(
             LogicalPlan::Projection(_)
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Limit(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Sort(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::Join(_)
             | LogicalPlan::Repartition(_)
             | LogicalPlan::Aggregate(_)
             | LogicalPlan::Window(_)
+            | LogicalPlan::MatchRecognize(_)
             | LogicalPlan::Subquery(_) => {
                 // Filter information may appear in child nodes; continue traversal
                 // to extract filters from Filter/TableScan nodes deeper in the plan
@@ -2931,6 +3133,19 @@ impl DefaultPhysicalPlanner {
             is_nullables.append_value(nullable_str);
         }
 
+        // The CSV dialect detected when the schema was inferred follows the columns
+        let mut dialect: Vec<_> = table_schema
+            .metadata()
+            .iter()
+            .filter(|(key, _)| key.starts_with(CSV_DIALECT_METADATA_PREFIX))
+            .collect();
+        dialect.sort();
+        for (key, value) in dialect {
+            column_names.append_value(key);
+            data_types.append_value(value);
+            is_nullables.append_value("");
+        }
+
         let record_batch = RecordBatch::try_new(
             output_schema,
             vec![
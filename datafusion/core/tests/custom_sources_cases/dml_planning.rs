@@ -15,7 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Tests for DELETE, UPDATE, and TRUNCATE planning to verify filter and assignment extraction.
+//! Tests for DELETE, UPDATE, TRUNCATE, and MERGE planning to verify filter and assignment extraction.
 
 use std::sync::{Arc, Mutex};
 
@@ -24,6 +24,7 @@ use async_trait::async_trait;
 use datafusion::datasource::{TableProvider, TableType};
 use datafusion::error::Result;
 use datafusion::execution::context::{SessionConfig, SessionContext};
+use datafusion::logical_expr::dml::MERGE_ACTION_COLUMN;
 use datafusion::logical_expr::{
     Expr, LogicalPlan, TableProviderFilterPushDown, TableScan,
 };
@@ -287,6 +288,65 @@ impl TableProvider for CaptureTruncateProvider {
     }
 }
 
+/// A TableProvider that captures the input schema passed to merge_into().
+struct CaptureMergeProvider {
+    schema: SchemaRef,
+    received_input_schema: Arc<Mutex<Option<SchemaRef>>>,
+}
+
+impl CaptureMergeProvider {
+    fn new(schema: SchemaRef) -> Self {
+        Self {
+            schema,
+            received_input_schema: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn captured_input_schema(&self) -> Option<SchemaRef> {
+        self.received_input_schema.lock().unwrap().clone()
+    }
+}
+
+impl std::fmt::Debug for CaptureMergeProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaptureMergeProvider")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl TableProvider for CaptureMergeProvider {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(EmptyExec::new(Arc::clone(&self.schema))))
+    }
+
+    async fn merge_into(
+        &self,
+        _state: &dyn Session,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        *self.received_input_schema.lock().unwrap() = Some(input.schema());
+        Ok(Arc::new(EmptyExec::new(Arc::new(Schema::new(vec![
+            Field::new("count", DataType::UInt64, false),
+        ])))))
+    }
+}
+
 fn test_schema() -> SchemaRef {
     Arc::new(Schema::new(vec![
         Field::new("id", DataType::Int32, false),
@@ -804,3 +864,58 @@ async fn test_unsupported_table_truncate() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_merge_input_schema() -> Result<()> {
+    let provider = Arc::new(CaptureMergeProvider::new(test_schema()));
+    let ctx = SessionContext::new();
+    ctx.register_table("t", Arc::clone(&provider) as Arc<dyn TableProvider>)?;
+    ctx.sql("CREATE TABLE s (id INT, value INT) AS VALUES (1, 10)")
+        .await?
+        .collect()
+        .await?;
+
+    ctx.sql(
+        "MERGE INTO t USING s ON t.id = s.id \
+         WHEN MATCHED THEN UPDATE SET value = s.value \
+         WHEN NOT MATCHED THEN INSERT (id, value) VALUES (s.id, s.value)",
+    )
+    .await?
+    .collect()
+    .await?;
+
+    let input_schema = provider
+        .captured_input_schema()
+        .expect("input schema should be captured");
+    let names: Vec<_> = input_schema
+        .fields()
+        .iter()
+        .map(|f| f.name().as_str())
+        .collect();
+    assert_eq!(names, ["id", "status", "value", MERGE_ACTION_COLUMN]);
+    assert_eq!(input_schema.field(3).data_type(), &DataType::Utf8);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unsupported_table_merge() -> Result<()> {
+    let schema = test_schema();
+    let ctx = SessionContext::new();
+
+    let empty_table = datafusion::datasource::empty::EmptyTable::new(schema);
+    ctx.register_table("empty_t", Arc::new(empty_table))?;
+
+    let err = ctx
+        .sql("MERGE INTO empty_t USING empty_t AS s ON empty_t.id = s.id WHEN MATCHED THEN DELETE")
+        .await?
+        .collect()
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.strip_backtrace(),
+        "MERGE operation on table 'empty_t'\ncaused by\nThis feature is not implemented: MERGE not supported for Base table"
+    );
+
+    Ok(())
+}
@@ -1619,6 +1619,28 @@ async fn limit() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn sample() -> Result<()> {
+    let df = create_test_table("test").await?;
+
+    assert_eq!(df.clone().sample(1.0, None)?.count().await?, 4);
+    assert_eq!(df.clone().sample(0.0, None)?.count().await?, 0);
+
+    // The same seed returns the same sample
+    let sampled = df.clone().sample(0.5, Some(7))?;
+    assert_batches_eq!(
+        pretty_format_batches(&sampled.clone().collect().await?)?
+            .to_string()
+            .lines()
+            .collect::<Vec<_>>(),
+        &sampled.collect().await?
+    );
+
+    let err = df.sample(1.5, None).unwrap_err();
+    assert_snapshot!(err.strip_backtrace(), @"Error during planning: Sample fraction must be between 0 and 1, got 1.5");
+    Ok(())
+}
+
 #[tokio::test]
 async fn df_count() -> Result<()> {
     let count = test_table().await?.count().await?;
@@ -2600,7 +2622,8 @@ async fn verify_join_output_partitioning() -> Result<()> {
             JoinType::Left
             | JoinType::LeftSemi
             | JoinType::LeftAnti
-            | JoinType::LeftMark => {
+            | JoinType::LeftMark
+            | JoinType::AsOf => {
                 let left_exprs: Vec<Arc<dyn PhysicalExpr>> = vec![
                     Arc::new(Column::new_with_schema("c1", &join_schema)?),
                     Arc::new(Column::new_with_schema("c2", &join_schema)?),
@@ -7043,6 +7066,8 @@ async fn execute_logical_plan_rejects_duplicate_unqualified_names_in_create_view
         or_replace: false,
         definition: None,
         temporary: false,
+        materialized: false,
+        location: None,
     }));
 
     let err = ctx.execute_logical_plan(plan).await.unwrap_err();
@@ -7071,6 +7096,8 @@ async fn execute_logical_plan_rejects_duplicate_unqualified_names_in_replace_vie
         or_replace: true,
         definition: None,
         temporary: false,
+        materialized: false,
+        location: None,
     }));
 
     let err = ctx.execute_logical_plan(plan).await.unwrap_err();
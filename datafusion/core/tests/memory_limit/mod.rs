@@ -579,6 +579,7 @@ async fn setup_context(
         parquet_encryption_factory_registry: runtime
             .parquet_encryption_factory_registry
             .clone(),
+        commit_clock: runtime.commit_clock.clone(),
     });
 
     let config = SessionConfig::new()
@@ -0,0 +1,371 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::array::{AsArray, Int32Array};
+use arrow::datatypes::{DataType, Field, Int32Type, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion::datasource::memory::MemorySourceConfig;
+use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion_common::{JoinType, Result};
+use datafusion_physical_expr::Partitioning;
+use datafusion_physical_expr::expressions::col;
+use datafusion_physical_expr_common::sort_expr::LexOrdering;
+use datafusion_physical_optimizer::PhysicalOptimizerRule;
+use datafusion_physical_optimizer::adaptive_execution::{
+    AdaptiveExec, AdaptiveExecution, AdaptivePartitionsExec, AdaptiveStageExec,
+    JoinPartitions,
+};
+use datafusion_physical_plan::execution_plan::reset_plan_states;
+use datafusion_physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion_physical_plan::repartition::RepartitionExec;
+use datafusion_physical_plan::{
+    ExecutionPlan, ExecutionPlanProperties, collect_partitioned, displayable,
+};
+
+use crate::physical_optimizer::test_utils::{
+    hash_join_exec, sort_exec_with_preserve_partitioning, sort_expr,
+};
+
+fn int_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]))
+}
+
+/// A scan of `values` split in two partitions, hash repartitioned on `a`
+fn partitioned_scan(values: Range<i32>) -> Result<Arc<dyn ExecutionPlan>> {
+    partitioned_scan_of(&values.collect::<Vec<_>>())
+}
+
+/// A scan of `values` split in two partitions, hash repartitioned on `a`
+fn partitioned_scan_of(values: &[i32]) -> Result<Arc<dyn ExecutionPlan>> {
+    let schema = int_schema();
+    let (first, second) = values.split_at(values.len() / 2);
+    let partitions = [first, second]
+        .into_iter()
+        .map(|values| {
+            let array = Arc::new(Int32Array::from(values.to_vec()));
+            Ok(vec![RecordBatch::try_new(
+                Arc::clone(&schema),
+                vec![array],
+            )?])
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let scan = MemorySourceConfig::try_new_exec(&partitions, Arc::clone(&schema), None)?;
+    Ok(Arc::new(RepartitionExec::try_new(
+        scan,
+        Partitioning::Hash(vec![col("a", &schema)?], 4),
+    )?))
+}
+
+/// A partitioned join of the build side `build` values with the probe side
+/// `probe` values
+fn partitioned_join(
+    join_type: JoinType,
+    build: Range<i32>,
+    probe: Range<i32>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let schema = int_schema();
+    let on = vec![(col("a", &schema)?, col("a", &schema)?)];
+    hash_join_exec(
+        partitioned_scan(build)?,
+        partitioned_scan(probe)?,
+        on,
+        None,
+        &join_type,
+    )
+}
+
+fn session_config(threshold_rows: usize) -> SessionConfig {
+    let mut config = SessionConfig::new().with_target_partitions(4);
+    config.options_mut().optimizer.enable_adaptive_execution = true;
+    config
+        .options_mut()
+        .optimizer
+        .hash_join_single_partition_threshold_rows = threshold_rows;
+    config
+}
+
+fn with_partition_size(mut config: SessionConfig, size: usize) -> SessionConfig {
+    config
+        .options_mut()
+        .optimizer
+        .adaptive_execution_partition_size = size;
+    config
+}
+
+/// Runs the [`AdaptiveExecution`] rule and `plan`, and returns the output
+/// partitions and the re-planned plan
+async fn run_adaptive(
+    config: SessionConfig,
+    plan: Arc<dyn ExecutionPlan>,
+) -> Result<(Vec<Vec<RecordBatch>>, Arc<dyn ExecutionPlan>)> {
+    let ctx = SessionContext::new_with_config(config);
+    let plan = AdaptiveExecution::new().optimize(plan, ctx.state().config_options())?;
+    let Some(adaptive) = plan.downcast_ref::<AdaptiveExec>() else {
+        panic!("expected an AdaptiveExec, got {}", plan.name());
+    };
+    let partitions = collect_partitioned(Arc::clone(&plan), ctx.task_ctx()).await?;
+    assert_eq!(partitions.len(), 4);
+    let adapted = Arc::clone(adaptive.adapted_plan().unwrap());
+    // The re-planned plan is shown once executed
+    assert!(Arc::ptr_eq(plan.children()[0], &adapted));
+    Ok((partitions, adapted))
+}
+
+fn num_rows(partitions: &[Vec<RecordBatch>]) -> usize {
+    partitions.iter().flatten().map(RecordBatch::num_rows).sum()
+}
+
+/// Returns the join of a re-planned plan
+fn find_join(plan: &Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    let mut join = None;
+    plan.apply(|plan| {
+        if plan.is::<HashJoinExec>() {
+            join = Some(Arc::clone(plan));
+            return Ok(TreeNodeRecursion::Stop);
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    Ok(join.expect("expected a HashJoinExec"))
+}
+
+#[tokio::test]
+async fn adaptive_execution_collects_small_build_side() -> Result<()> {
+    let plan = partitioned_join(JoinType::Inner, 0..10, 0..100)?;
+    let (partitions, adapted) = run_adaptive(session_config(1000), plan).await?;
+    assert_eq!(num_rows(&partitions), 10);
+
+    let join = find_join(&adapted)?;
+    let join = join.downcast_ref::<HashJoinExec>().unwrap();
+    assert_eq!(*join.partition_mode(), PartitionMode::CollectLeft);
+    let Some(stage) = join.left().downcast_ref::<AdaptiveStageExec>() else {
+        panic!("expected an AdaptiveStageExec, got {}", join.left().name());
+    };
+    assert!(stage.is_complete());
+    assert_eq!(
+        stage.properties().output_partitioning().partition_count(),
+        1
+    );
+    // The probe side is not hash repartitioned anymore
+    let right = join.right();
+    assert!(
+        !matches!(right.output_partitioning(), Partitioning::Hash(..)),
+        "{}",
+        displayable(right.as_ref()).indent(true)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn adaptive_execution_collects_outer_and_anti_joins() -> Result<()> {
+    // The build side values 0..5 have no match, and the probe side values
+    // 10..100 have no match
+    for (join_type, expected_rows) in [
+        (JoinType::Left, 10),
+        (JoinType::Right, 95),
+        (JoinType::Full, 100),
+        (JoinType::LeftSemi, 5),
+        (JoinType::LeftAnti, 5),
+        (JoinType::RightAnti, 90),
+    ] {
+        let plan = partitioned_join(join_type, 0..10, 5..100)?;
+        let (partitions, adapted) = run_adaptive(session_config(1000), plan).await?;
+        assert_eq!(num_rows(&partitions), expected_rows, "{join_type}");
+
+        let join = find_join(&adapted)?;
+        let join = join.downcast_ref::<HashJoinExec>().unwrap();
+        assert_eq!(
+            *join.partition_mode(),
+            PartitionMode::CollectLeft,
+            "{join_type}"
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn adaptive_execution_keeps_ordering() -> Result<()> {
+    let join = partitioned_join(JoinType::Inner, 0..10, 0..100)?;
+    let ordering = LexOrdering::new([sort_expr("a", &join.schema())]).unwrap();
+    let plan = sort_exec_with_preserve_partitioning(ordering.clone(), join);
+    let (partitions, adapted) = run_adaptive(session_config(1000), plan).await?;
+    assert_eq!(num_rows(&partitions), 10);
+
+    assert_eq!(adapted.output_ordering(), Some(&ordering));
+    for partition in &partitions {
+        let values = partition
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert!(values.is_sorted(), "{values:?}");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn adaptive_execution_keeps_large_build_side_partitioned() -> Result<()> {
+    let plan = partitioned_join(JoinType::Inner, 0..10, 0..100)?;
+    let config = with_partition_size(session_config(1), 0);
+    let (partitions, adapted) = run_adaptive(config, plan).await?;
+    assert_eq!(num_rows(&partitions), 10);
+
+    let join = find_join(&adapted)?;
+    let join = join.downcast_ref::<HashJoinExec>().unwrap();
+    assert_eq!(*join.partition_mode(), PartitionMode::Partitioned);
+    let Some(stage) = join.left().downcast_ref::<AdaptiveStageExec>() else {
+        panic!("expected an AdaptiveStageExec, got {}", join.left().name());
+    };
+    assert!(!stage.is_complete());
+    assert_eq!(
+        stage.properties().output_partitioning().partition_count(),
+        4
+    );
+    Ok(())
+}
+
+/// Returns the [`AdaptivePartitionsExec`] of a re-planned plan
+fn find_partitions(plan: &Arc<dyn ExecutionPlan>) -> Result<JoinPartitions> {
+    let mut partitions = None;
+    plan.apply(|plan| {
+        if let Some(plan) = plan.downcast_ref::<AdaptivePartitionsExec>() {
+            partitions = Some(plan.partitions().clone());
+            return Ok(TreeNodeRecursion::Stop);
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    Ok(partitions.unwrap_or_else(|| {
+        panic!(
+            "expected an AdaptivePartitionsExec in {}",
+            displayable(plan.as_ref()).indent(true)
+        )
+    }))
+}
+
+#[tokio::test]
+async fn adaptive_execution_coalesces_small_partitions() -> Result<()> {
+    // The build side values 0..5 have no match, and the probe side values
+    // 10..100 have no match
+    for (join_type, expected_rows) in [
+        (JoinType::Inner, 5),
+        (JoinType::Left, 10),
+        (JoinType::Full, 100),
+        (JoinType::RightAnti, 90),
+    ] {
+        let plan = partitioned_join(join_type, 0..10, 5..100)?;
+        // The build side is above the single partition threshold, but below
+        // the partition size
+        let (partitions, adapted) = run_adaptive(session_config(1), plan).await?;
+        assert_eq!(num_rows(&partitions), expected_rows, "{join_type}");
+
+        assert_eq!(
+            find_partitions(&adapted)?,
+            JoinPartitions::Coalesced(1),
+            "{join_type}"
+        );
+        let join = find_join(&adapted)?;
+        let join = join.downcast_ref::<HashJoinExec>().unwrap();
+        assert_eq!(*join.partition_mode(), PartitionMode::Partitioned);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn adaptive_execution_splits_skewed_partitions() -> Result<()> {
+    // The build side has the value 7 100000 more times, and the probe side
+    // values 0..5 have no match
+    let build = (0..10)
+        .chain(std::iter::repeat_n(7, 100_000))
+        .collect::<Vec<_>>();
+    for (join_type, expected_rows) in [
+        (JoinType::Inner, 100_005),
+        (JoinType::Left, 100_010),
+        (JoinType::Right, 100_095),
+        (JoinType::Full, 100_100),
+        (JoinType::LeftSemi, 100_005),
+        (JoinType::LeftAnti, 5),
+        (JoinType::RightSemi, 5),
+        (JoinType::RightAnti, 90),
+    ] {
+        let schema = int_schema();
+        let plan = hash_join_exec(
+            partitioned_scan_of(&build)?,
+            partitioned_scan(5..100)?,
+            vec![(col("a", &schema)?, col("a", &schema)?)],
+            None,
+            &join_type,
+        )?;
+        // Only the partition of the value 7 is above the partition size
+        let config = with_partition_size(session_config(1), 200_000);
+        let (partitions, adapted) = run_adaptive(config, plan).await?;
+        assert_eq!(num_rows(&partitions), expected_rows, "{join_type}");
+
+        let JoinPartitions::Split(splits) = find_partitions(&adapted)? else {
+            panic!("expected split partitions for {join_type}");
+        };
+        assert_eq!(
+            splits.iter().filter(|splits| **splits > 1).count(),
+            1,
+            "{join_type}: {splits:?}"
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn adaptive_execution_executes_again() -> Result<()> {
+    for config in [
+        // Collected, coalesced and kept partitioned build sides
+        session_config(1000),
+        session_config(1),
+        with_partition_size(session_config(1), 0),
+    ] {
+        let ctx = SessionContext::new_with_config(config);
+        let plan = partitioned_join(JoinType::Inner, 0..10, 0..100)?;
+        let plan =
+            AdaptiveExecution::new().optimize(plan, ctx.state().config_options())?;
+        for _ in 0..2 {
+            let partitions =
+                collect_partitioned(Arc::clone(&plan), ctx.task_ctx()).await?;
+            assert_eq!(num_rows(&partitions), 10);
+        }
+
+        // The re-planned plan reads its stages again once reset
+        let plan = reset_plan_states(plan)?;
+        let partitions = collect_partitioned(plan, ctx.task_ctx()).await?;
+        assert_eq!(num_rows(&partitions), 10);
+    }
+    Ok(())
+}
+
+#[test]
+fn adaptive_execution_disabled() -> Result<()> {
+    let plan = partitioned_join(JoinType::Inner, 0..10, 0..100)?;
+    let optimized =
+        AdaptiveExecution::new().optimize(Arc::clone(&plan), &ConfigOptions::new())?;
+    assert!(Arc::ptr_eq(&plan, &optimized));
+    Ok(())
+}
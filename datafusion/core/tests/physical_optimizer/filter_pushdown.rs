@@ -2755,6 +2755,107 @@ async fn test_hashjoin_hash_table_pushdown_collect_left() {
     );
 }
 
+#[tokio::test]
+async fn test_hashjoin_bloom_filter_pushdown_collect_left() {
+    use datafusion_common::JoinType;
+    use datafusion_physical_plan::joins::{HashJoinExec, PartitionMode};
+
+    let build_batches =
+        vec![record_batch!(("a", Int32, [1, 3]), ("c", Float64, [1.0, 3.0])).unwrap()];
+    let build_side_schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("c", DataType::Float64, false),
+    ]));
+    let build_scan = TestScanBuilder::new(Arc::clone(&build_side_schema))
+        .with_support(true)
+        .with_batches(build_batches)
+        .build();
+
+    let probe_batches = vec![
+        record_batch!(
+            ("a", Int32, [1, 2, 3, 4]),
+            ("e", Float64, [1.0, 2.0, 3.0, 4.0])
+        )
+        .unwrap(),
+    ];
+    let probe_side_schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("e", DataType::Float64, false),
+    ]));
+    let probe_scan = TestScanBuilder::new(Arc::clone(&probe_side_schema))
+        .with_support(true)
+        .with_batches(probe_batches)
+        .build();
+
+    let on = vec![(
+        col("a", &build_side_schema).unwrap(),
+        col("a", &probe_side_schema).unwrap(),
+    )];
+    let plan = Arc::new(
+        HashJoinExec::try_new(
+            build_scan,
+            Arc::clone(&probe_scan),
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::CollectLeft,
+            datafusion_common::NullEquality::NullEqualsNothing,
+            false,
+        )
+        .unwrap(),
+    ) as Arc<dyn ExecutionPlan>;
+
+    // Force the Bloom filter strategy over the InList strategy
+    let session_config = SessionConfig::default()
+        .with_batch_size(10)
+        .set_usize("datafusion.optimizer.hash_join_inlist_pushdown_max_size", 1)
+        .set_bool("datafusion.optimizer.hash_join_bloom_filter_pushdown", true)
+        .set_bool("datafusion.optimizer.enable_dynamic_filter_pushdown", true);
+    let plan = FilterPushdown::new_post_optimization()
+        .optimize(plan, session_config.options())
+        .unwrap();
+    let session_ctx = SessionContext::new_with_config(session_config);
+    session_ctx.register_object_store(
+        ObjectStoreUrl::parse("test://").unwrap().as_ref(),
+        Arc::new(InMemory::new()),
+    );
+    let task_ctx = session_ctx.state().task_ctx();
+    let batches = collect(Arc::clone(&plan), task_ctx).await.unwrap();
+
+    let plan_str = format_plan_for_test(&plan).to_string();
+    assert!(
+        plan_str.contains("bloom_filter"),
+        "Expected bloom_filter in plan but got: {plan_str}"
+    );
+    assert!(
+        !plan_str.contains("hash_lookup"),
+        "Expected no hash_lookup in plan but got: {plan_str}"
+    );
+    // The few distinct keys are also pushed down for the scan to probe its
+    // Bloom filters with
+    assert!(
+        plan_str.contains("bloom_filter AND a@0 IN (SET) ([1, 3])"),
+        "Expected the build side keys in plan but got: {plan_str}"
+    );
+
+    // 2 is within the bounds of the build side, but not in its Bloom filter
+    assert_eq!(probe_scan.metrics().unwrap().output_rows().unwrap(), 2);
+
+    let result = format!("{}", pretty_format_batches(&batches).unwrap());
+    insta::assert_snapshot!(
+        result,
+        @r"
+    +---+-----+---+-----+
+    | a | c   | a | e   |
+    +---+-----+---+-----+
+    | 1 | 1.0 | 1 | 1.0 |
+    | 3 | 3.0 | 3 | 3.0 |
+    +---+-----+---+-----+
+    ",
+    );
+}
+
 // Not portable to sqllogictest: asserts on `HashJoinExec::dynamic_filter_for_test().is_used()`
 // which is a debug-only API. The observable behavior (probe-side scan
 // receiving the dynamic filter when the data source supports it) is
@@ -0,0 +1,277 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Int32Array, RecordBatch};
+use arrow::datatypes::{DataType, Field, Schema};
+use datafusion::datasource::memory::MemorySourceConfig;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::stats::Precision;
+use datafusion_common::test_util::batches_to_sort_string;
+use datafusion_common::{
+    ColumnStatistics, JoinSide, JoinType, NullEquality, Result, Statistics,
+};
+use datafusion_execution::TaskContext;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column, col, lit};
+use datafusion_physical_optimizer::PhysicalOptimizerRule;
+use datafusion_physical_optimizer::join_reorder::JoinReorder;
+use datafusion_physical_plan::joins::utils::{ColumnIndex, JoinFilter};
+use datafusion_physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion_physical_plan::projection::ProjectionExec;
+use datafusion_physical_plan::{ExecutionPlan, collect, displayable};
+
+use super::join_selection::StatisticsExec;
+
+/// A scan of `num_rows` rows with a column per name of `columns`, with their
+/// number of distinct values
+fn scan(
+    num_rows: Option<usize>,
+    columns: &[(&str, Option<usize>)],
+) -> Arc<dyn ExecutionPlan> {
+    let schema = Schema::new(
+        columns
+            .iter()
+            .map(|(name, _)| Field::new(*name, DataType::Int32, false))
+            .collect::<Vec<_>>(),
+    );
+    let statistics = Statistics {
+        num_rows: num_rows.map_or(Precision::Absent, Precision::Inexact),
+        total_byte_size: Precision::Absent,
+        column_statistics: columns
+            .iter()
+            .map(|(_, distinct)| {
+                ColumnStatistics::new_unknown().with_distinct_count(
+                    distinct.map_or(Precision::Absent, Precision::Inexact),
+                )
+            })
+            .collect(),
+    };
+    Arc::new(StatisticsExec::new(statistics, schema))
+}
+
+fn hash_join(
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on: (&str, &str),
+    join_type: JoinType,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let on = vec![(col(on.0, &left.schema())?, col(on.1, &right.schema())?)];
+    Ok(Arc::new(HashJoinExec::try_new(
+        left,
+        right,
+        on,
+        None,
+        &join_type,
+        None,
+        PartitionMode::Partitioned,
+        NullEquality::NullEqualsNothing,
+        false,
+    )?))
+}
+
+/// `(big1 ⋈ big2) ⋈ small`, where `big2 ⋈ small` is much smaller than
+/// `big1 ⋈ big2`
+fn badly_ordered_joins(big1_rows: Option<usize>) -> Result<Arc<dyn ExecutionPlan>> {
+    let big1 = scan(big1_rows, &[("big1_x", Some(10))]);
+    let big2 = scan(Some(1_000_000), &[("big2_x", Some(10)), ("big2_y", None)]);
+    let small = scan(Some(10), &[("small_y", None)]);
+    let join = hash_join(big1, big2, ("big1_x", "big2_x"), JoinType::Inner)?;
+    hash_join(join, small, ("big2_y", "small_y"), JoinType::Inner)
+}
+
+/// The order of the joins of `plan`, with the relations named after the
+/// prefix of their first column
+fn join_order(plan: &Arc<dyn ExecutionPlan>) -> String {
+    if let Some(join) = plan.downcast_ref::<HashJoinExec>() {
+        format!(
+            "({}, {})",
+            join_order(join.left()),
+            join_order(join.right())
+        )
+    } else if let Some(projection) = plan.downcast_ref::<ProjectionExec>() {
+        join_order(projection.input())
+    } else {
+        let schema = plan.schema();
+        let name = schema.field(0).name();
+        name.split('_').next().unwrap_or(name).to_string()
+    }
+}
+
+fn reorder_config() -> ConfigOptions {
+    let mut config = ConfigOptions::new();
+    config.optimizer.enable_join_reorder = true;
+    config
+}
+
+#[test]
+fn test_join_reorder() -> Result<()> {
+    let plan = badly_ordered_joins(Some(1_000_000))?;
+    let optimized = JoinReorder::new().optimize(Arc::clone(&plan), &reorder_config())?;
+
+    assert_eq!(join_order(&plan), "((big1, big2), small)");
+    assert_eq!(join_order(&optimized), "((small, big2), big1)");
+    // The columns are projected back to their original order
+    assert!(optimized.downcast_ref::<ProjectionExec>().is_some());
+    assert_eq!(optimized.schema(), plan.schema());
+    Ok(())
+}
+
+#[test]
+fn test_join_reorder_greedy() -> Result<()> {
+    let plan = badly_ordered_joins(Some(1_000_000))?;
+    let mut config = reorder_config();
+    config.optimizer.join_reorder_dp_threshold = 2;
+    let optimized = JoinReorder::new().optimize(plan, &config)?;
+
+    assert_eq!(join_order(&optimized), "((small, big2), big1)");
+    Ok(())
+}
+
+#[test]
+fn test_join_reorder_disabled() -> Result<()> {
+    let plan = badly_ordered_joins(Some(1_000_000))?;
+    let optimized =
+        JoinReorder::new().optimize(Arc::clone(&plan), &ConfigOptions::new())?;
+
+    assert!(Arc::ptr_eq(&plan, &optimized));
+    Ok(())
+}
+
+#[test]
+fn test_join_reorder_unknown_statistics() -> Result<()> {
+    let plan = badly_ordered_joins(None)?;
+    let optimized = JoinReorder::new().optimize(Arc::clone(&plan), &reorder_config())?;
+
+    assert_eq!(
+        displayable(optimized.as_ref()).indent(true).to_string(),
+        displayable(plan.as_ref()).indent(true).to_string()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_join_reorder_below_outer_join() -> Result<()> {
+    let other = scan(Some(100), &[("other_z", None)]);
+    let plan = hash_join(
+        badly_ordered_joins(Some(1_000_000))?,
+        other,
+        ("big1_x", "other_z"),
+        JoinType::Left,
+    )?;
+    let optimized = JoinReorder::new().optimize(Arc::clone(&plan), &reorder_config())?;
+
+    // The left join is kept, the inner joins of its left input are reordered
+    let join = optimized.downcast_ref::<HashJoinExec>().unwrap();
+    assert_eq!(join.join_type(), &JoinType::Left);
+    assert_eq!(join_order(&optimized), "(((small, big2), big1), other)");
+    assert_eq!(optimized.schema(), plan.schema());
+    Ok(())
+}
+
+/// Computes the value of a column from the row number
+type ColumnValue = fn(i32) -> i32;
+
+/// A scan of `num_rows` rows with a column per name of `columns`, whose
+/// values are a function of the row number
+fn memory_scan(
+    num_rows: i32,
+    columns: &[(&str, ColumnValue)],
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|(name, _)| Field::new(*name, DataType::Int32, false))
+            .collect::<Vec<_>>(),
+    ));
+    let arrays = columns
+        .iter()
+        .map(|(_, value)| {
+            Arc::new(Int32Array::from_iter_values((0..num_rows).map(value))) as ArrayRef
+        })
+        .collect();
+    let batch = RecordBatch::try_new(Arc::clone(&schema), arrays)?;
+    let scan: Arc<dyn ExecutionPlan> =
+        MemorySourceConfig::try_new_exec(&[vec![batch]], schema, None)?;
+    Ok(scan)
+}
+
+#[tokio::test]
+async fn test_join_reorder_results() -> Result<()> {
+    let big1 = memory_scan(200, &[("big1_x", |i| i % 10)])?;
+    let big2 = memory_scan(200, &[("big2_x", |i| i % 10), ("big2_y", |i| i)])?;
+    let small = memory_scan(5, &[("small_y", |i| i * 7), ("small_z", |i| i)])?;
+    let join = hash_join(big1, big2, ("big1_x", "big2_x"), JoinType::Inner)?;
+
+    // `big1_x + small_z > 5` needs the first and last relations, which are
+    // not joined directly once reordered
+    let filter_schema = Schema::new(vec![
+        Field::new("big1_x", DataType::Int32, false),
+        Field::new("small_z", DataType::Int32, false),
+    ]);
+    let expression = Arc::new(BinaryExpr::new(
+        Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("big1_x", 0)),
+            Operator::Plus,
+            Arc::new(Column::new("small_z", 1)),
+        )),
+        Operator::Gt,
+        lit(5),
+    ));
+    let column_indices = vec![
+        ColumnIndex {
+            index: 0,
+            side: JoinSide::Left,
+        },
+        ColumnIndex {
+            index: 1,
+            side: JoinSide::Right,
+        },
+    ];
+    let filter = JoinFilter::new(expression, column_indices, Arc::new(filter_schema));
+    let on = vec![(
+        col("big2_y", &join.schema())?,
+        col("small_y", &small.schema())?,
+    )];
+    let plan: Arc<dyn ExecutionPlan> = Arc::new(HashJoinExec::try_new(
+        join,
+        small,
+        on,
+        Some(filter),
+        &JoinType::Inner,
+        None,
+        PartitionMode::Partitioned,
+        NullEquality::NullEqualsNothing,
+        false,
+    )?);
+
+    let optimized = JoinReorder::new().optimize(Arc::clone(&plan), &reorder_config())?;
+    assert_eq!(join_order(&optimized), "((small, big2), big1)");
+    assert_eq!(optimized.schema(), plan.schema());
+
+    let task_ctx = Arc::new(TaskContext::default());
+    let expected = collect(plan, Arc::clone(&task_ctx)).await?;
+    let actual = collect(optimized, task_ctx).await?;
+    // 20 rows of `big1` for each of the 3 rows of `small` passing the filter
+    assert_eq!(actual.iter().map(|b| b.num_rows()).sum::<usize>(), 60);
+    assert_eq!(
+        batches_to_sort_string(&actual),
+        batches_to_sort_string(&expected)
+    );
+    Ok(())
+}
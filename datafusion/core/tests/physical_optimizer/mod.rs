@@ -17,6 +17,7 @@
 
 //! Physical Optimizer integration tests
 
+mod adaptive_execution;
 #[expect(clippy::needless_pass_by_value)]
 mod aggregate_statistics;
 mod combine_partial_final_agg;
@@ -26,6 +27,7 @@ mod enforce_sorting;
 mod enforce_sorting_monotonicity;
 mod ensure_requirements;
 mod filter_pushdown;
+mod join_reorder;
 mod join_selection;
 #[expect(clippy::needless_pass_by_value)]
 mod limit_pushdown;
@@ -1386,6 +1386,38 @@ async fn create_scalar_function_from_sql_statement_postgres_syntax() -> Result<(
     Ok(())
 }
 
+#[tokio::test]
+async fn create_sql_function_without_function_factory() -> Result<()> {
+    let ctx = SessionContext::new();
+    ctx.sql("CREATE TABLE t(a BIGINT) AS VALUES (1), (2), (3)")
+        .await?
+        .collect()
+        .await?;
+    ctx.sql("CREATE FUNCTION add_one(x BIGINT) RETURNS BIGINT RETURN x + 1")
+        .await?;
+
+    // the call is replaced by the body of the function
+    let df = ctx.sql("SELECT a FROM t WHERE add_one(a) > 2").await?;
+    let plan = df.clone().into_optimized_plan()?;
+    assert!(
+        !plan.display_indent().to_string().contains("add_one"),
+        "{}",
+        plan.display_indent()
+    );
+
+    let result = df.collect().await?;
+    insta::assert_snapshot!(batches_to_string(&result), @r"
+    +---+
+    | a |
+    +---+
+    | 2 |
+    | 3 |
+    +---+
+    ");
+
+    Ok(())
+}
+
 #[derive(Debug)]
 struct MyRegexUdf {
     signature: Signature,
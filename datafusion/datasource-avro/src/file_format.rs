@@ -23,52 +23,104 @@ use std::sync::Arc;
 use crate::read_avro_schema_from_reader;
 use crate::source::AvroSource;
 
-use arrow::datatypes::Schema;
-use arrow::datatypes::SchemaRef;
-use datafusion_common::DEFAULT_AVRO_EXTENSION;
-use datafusion_common::GetExt;
-use datafusion_common::internal_err;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use arrow_avro::compression::CompressionCodec;
+use arrow_avro::writer::{AvroWriter, WriterBuilder};
+use datafusion_common::config::{AvroOptions, ConfigField, ConfigFileType};
 use datafusion_common::parsers::CompressionTypeVariant;
-use datafusion_common::{Result, Statistics};
+use datafusion_common::{
+    DEFAULT_AVRO_EXTENSION, DataFusionError, GetExt, Result, Statistics, config_err,
+    internal_err, not_impl_err,
+};
+use datafusion_common_runtime::{JoinSet, SpawnedTask};
+use datafusion_datasource::display::FileGroupDisplay;
 use datafusion_datasource::file::FileSource;
 use datafusion_datasource::file_compression_type::FileCompressionType;
 use datafusion_datasource::file_format::{FileFormat, FileFormatFactory};
 use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::file_sink_config::{FileSink, FileSinkConfig};
+use datafusion_datasource::sink::{DataSink, DataSinkExec};
 use datafusion_datasource::source::DataSourceExec;
-use datafusion_physical_plan::ExecutionPlan;
+use datafusion_datasource::write::demux::DemuxedStreamReceiver;
+use datafusion_datasource::write::{
+    ObjectWriterBuilder, SharedBuffer, get_writer_schema,
+};
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_expr::dml::InsertOp;
+use datafusion_physical_expr_common::sort_expr::LexRequirement;
+use datafusion_physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan};
 use datafusion_session::Session;
 
 use async_trait::async_trait;
 use object_store::{GetResultPayload, ObjectMeta, ObjectStore, ObjectStoreExt};
+use tokio::io::AsyncWriteExt;
+
+/// Initial writing buffer size. Note this is just a size hint for efficiency. It
+/// will grow beyond the set value if needed.
+const INITIAL_BUFFER_BYTES: usize = 1048576;
+
+/// If the buffered Avro data exceeds this size, it is flushed to object store
+const BUFFER_FLUSH_BYTES: usize = 1024000;
 
 #[derive(Default)]
 /// Factory struct used to create [`AvroFormat`]
-pub struct AvroFormatFactory;
+pub struct AvroFormatFactory {
+    /// the options carried by format factory
+    pub options: Option<AvroOptions>,
+}
 
 impl AvroFormatFactory {
     /// Creates an instance of [`AvroFormatFactory`]
     pub fn new() -> Self {
-        Self {}
+        Self { options: None }
+    }
+
+    /// Creates an instance of [`AvroFormatFactory`] with customized default options
+    pub fn new_with_options(options: AvroOptions) -> Self {
+        Self {
+            options: Some(options),
+        }
     }
 }
 
 impl FileFormatFactory for AvroFormatFactory {
     fn create(
         &self,
-        _state: &dyn Session,
-        _format_options: &HashMap<String, String>,
+        state: &dyn Session,
+        format_options: &HashMap<String, String>,
     ) -> Result<Arc<dyn FileFormat>> {
-        Ok(Arc::new(AvroFormat))
+        let avro_options = match &self.options {
+            None => {
+                let mut table_options = state.default_table_options();
+                table_options.set_config_format(ConfigFileType::AVRO);
+                table_options.alter_with_string_hash_map(format_options)?;
+                table_options.avro
+            }
+            Some(avro_options) => {
+                let mut avro_options = avro_options.clone();
+                for (k, v) in format_options {
+                    avro_options.set(k, v)?;
+                }
+                avro_options
+            }
+        };
+
+        Ok(Arc::new(AvroFormat::default().with_options(avro_options)))
     }
 
     fn default(&self) -> Arc<dyn FileFormat> {
-        Arc::new(AvroFormat)
+        Arc::new(AvroFormat::default())
     }
 }
 
 impl fmt::Debug for AvroFormatFactory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("AvroFormatFactory").finish()
+        f.debug_struct("AvroFormatFactory")
+            .field("options", &self.options)
+            .finish()
     }
 }
 
@@ -81,7 +133,29 @@ impl GetExt for AvroFormatFactory {
 
 /// Avro [`FileFormat`] implementation.
 #[derive(Default, Debug)]
-pub struct AvroFormat;
+pub struct AvroFormat {
+    options: AvroOptions,
+}
+
+impl AvroFormat {
+    /// Set Avro options
+    pub fn with_options(mut self, options: AvroOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Retrieve Avro options
+    pub fn options(&self) -> &AvroOptions {
+        &self.options
+    }
+
+    /// Set the compression codec of the data blocks of written files, see
+    /// [`AvroOptions::compression`]
+    pub fn with_compression(mut self, compression: Option<String>) -> Self {
+        self.options.compression = compression;
+        self
+    }
+}
 
 #[async_trait]
 impl FileFormat for AvroFormat {
@@ -147,6 +221,22 @@ impl FileFormat for AvroFormat {
         Ok(DataSourceExec::from_data_source(conf))
     }
 
+    async fn create_writer_physical_plan(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        _state: &dyn Session,
+        conf: FileSinkConfig,
+        order_requirements: Option<LexRequirement>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if conf.insert_op != InsertOp::Append {
+            return not_impl_err!("Overwrites are not implemented yet for Avro format");
+        }
+
+        let sink = Arc::new(AvroSink::try_new(conf, self.options.clone())?);
+
+        Ok(Arc::new(DataSinkExec::new(input, sink, order_requirements)) as _)
+    }
+
     fn file_source(
         &self,
         table_schema: datafusion_datasource::TableSchema,
@@ -154,3 +244,229 @@ impl FileFormat for AvroFormat {
         Arc::new(AvroSource::new(table_schema))
     }
 }
+
+/// Parses the name of an Avro compression codec, where `None` means that data
+/// blocks are not compressed
+fn parse_compression_codec(codec: Option<&str>) -> Result<Option<CompressionCodec>> {
+    let Some(codec) = codec else {
+        return Ok(None);
+    };
+    match codec.to_lowercase().as_str() {
+        "null" | "uncompressed" => Ok(None),
+        "deflate" => Ok(Some(CompressionCodec::Deflate)),
+        "snappy" => Ok(Some(CompressionCodec::Snappy)),
+        "zstd" => Ok(Some(CompressionCodec::ZStandard)),
+        "bzip2" => Ok(Some(CompressionCodec::Bzip2)),
+        "xz" => Ok(Some(CompressionCodec::Xz)),
+        _ => config_err!(
+            "Unknown or unsupported Avro compression codec: {codec}. Valid values are: null, deflate, snappy, zstd, bzip2 and xz."
+        ),
+    }
+}
+
+/// Returns the type written to Avro files for `data_type`.
+///
+/// Avro has no view types, so string and binary views are written as
+/// strings and bytes. Other types, including nested and nullable types,
+/// decimals and timestamps, are mapped to Avro types and logical types by
+/// the [`arrow_avro`] writer.
+fn avro_data_type(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::Utf8View => DataType::Utf8,
+        DataType::BinaryView => DataType::Binary,
+        DataType::Struct(fields) => DataType::Struct(avro_fields(fields)),
+        DataType::List(field) | DataType::ListView(field) => {
+            DataType::List(Arc::new(avro_field(field)))
+        }
+        DataType::LargeList(field) | DataType::LargeListView(field) => {
+            DataType::LargeList(Arc::new(avro_field(field)))
+        }
+        DataType::FixedSizeList(field, size) => {
+            DataType::FixedSizeList(Arc::new(avro_field(field)), *size)
+        }
+        DataType::Map(field, sorted) => {
+            DataType::Map(Arc::new(avro_field(field)), *sorted)
+        }
+        _ => data_type.clone(),
+    }
+}
+
+fn avro_field(field: &Field) -> Field {
+    field
+        .clone()
+        .with_data_type(avro_data_type(field.data_type()))
+}
+
+fn avro_fields(fields: &Fields) -> Fields {
+    fields.iter().map(|f| avro_field(f)).collect()
+}
+
+/// Returns the schema written to Avro files for `schema`
+fn avro_schema(schema: &Schema) -> Schema {
+    Schema::new_with_metadata(avro_fields(schema.fields()), schema.metadata().clone())
+}
+
+/// Casts the columns of `batch` to the types of the Avro `schema`
+fn cast_to_avro_schema(batch: &RecordBatch, schema: &SchemaRef) -> Result<RecordBatch> {
+    if batch.schema_ref().fields() == schema.fields() {
+        return Ok(batch.clone());
+    }
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(column, field)| cast(column, field.data_type()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(RecordBatch::try_new(Arc::clone(schema), columns)?)
+}
+
+/// Implements [`FileSink`] for writing to Avro object container files
+pub struct AvroSink {
+    /// Config options for writing data
+    config: FileSinkConfig,
+    /// Avro options for the written files
+    options: AvroOptions,
+    /// Compression codec of the data blocks, parsed from `options`
+    codec: Option<CompressionCodec>,
+}
+
+impl AvroSink {
+    /// Create from config, failing if the compression codec of `options` is
+    /// not supported
+    pub fn try_new(config: FileSinkConfig, options: AvroOptions) -> Result<Self> {
+        let codec = parse_compression_codec(options.compression.as_deref())?;
+        Ok(Self {
+            config,
+            options,
+            codec,
+        })
+    }
+
+    /// Retrieve the Avro options
+    pub fn options(&self) -> &AvroOptions {
+        &self.options
+    }
+}
+
+impl fmt::Debug for AvroSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AvroSink").finish()
+    }
+}
+
+impl DisplayAs for AvroSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "AvroSink(file_groups=",)?;
+                FileGroupDisplay(&self.config.file_group).fmt_as(t, f)?;
+                write!(f, ")")
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "format: avro")?;
+                write!(f, "file={}", &self.config.original_url)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl FileSink for AvroSink {
+    fn config(&self) -> &FileSinkConfig {
+        &self.config
+    }
+
+    async fn spawn_writer_tasks_and_join(
+        &self,
+        context: &Arc<TaskContext>,
+        demux_task: SpawnedTask<Result<()>>,
+        mut file_stream_rx: DemuxedStreamReceiver,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<u64> {
+        let mut file_write_tasks: JoinSet<std::result::Result<usize, DataFusionError>> =
+            JoinSet::new();
+
+        let schema = Arc::new(avro_schema(&get_writer_schema(&self.config)));
+        while let Some((path, mut rx)) = file_stream_rx.recv().await {
+            let shared_buffer = SharedBuffer::new(INITIAL_BUFFER_BYTES);
+            let mut avro_writer: AvroWriter<SharedBuffer> =
+                WriterBuilder::new(schema.as_ref().clone())
+                    .with_compression(self.codec)
+                    .build(shared_buffer.clone())
+                    .map_err(ArrowError::from)?;
+            let mut object_store_writer = ObjectWriterBuilder::new(
+                FileCompressionType::UNCOMPRESSED,
+                &path,
+                Arc::clone(&object_store),
+            )
+            .with_buffer_size(Some(
+                context
+                    .session_config()
+                    .options()
+                    .execution
+                    .objectstore_writer_buffer_size,
+            ))
+            .build()?;
+            let schema = Arc::clone(&schema);
+            file_write_tasks.spawn(async move {
+                let mut row_count = 0;
+                while let Some(batch) = rx.recv().await {
+                    row_count += batch.num_rows();
+                    avro_writer
+                        .write(&cast_to_avro_schema(&batch, &schema)?)
+                        .map_err(ArrowError::from)?;
+                    let mut buff_to_flush = shared_buffer.buffer.try_lock().unwrap();
+                    if buff_to_flush.len() > BUFFER_FLUSH_BYTES {
+                        object_store_writer
+                            .write_all(buff_to_flush.as_slice())
+                            .await?;
+                        buff_to_flush.clear();
+                    }
+                }
+                avro_writer.finish().map_err(ArrowError::from)?;
+                let final_buff = shared_buffer.buffer.try_lock().unwrap();
+
+                object_store_writer.write_all(final_buff.as_slice()).await?;
+                object_store_writer.shutdown().await?;
+                Ok(row_count)
+            });
+        }
+
+        let mut row_count = 0;
+        while let Some(result) = file_write_tasks.join_next().await {
+            match result {
+                Ok(r) => {
+                    row_count += r?;
+                }
+                Err(e) => {
+                    if e.is_panic() {
+                        std::panic::resume_unwind(e.into_panic());
+                    } else {
+                        unreachable!();
+                    }
+                }
+            }
+        }
+
+        demux_task
+            .join_unwind()
+            .await
+            .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))??;
+        Ok(row_count as u64)
+    }
+}
+
+#[async_trait]
+impl DataSink for AvroSink {
+    fn schema(&self) -> &SchemaRef {
+        self.config.output_schema()
+    }
+
+    async fn write_all(
+        &self,
+        data: SendableRecordBatchStream,
+        context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        FileSink::write_all(self, data, context).await
+    }
+}
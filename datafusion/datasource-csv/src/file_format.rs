@@ -21,6 +21,10 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug};
 use std::sync::Arc;
 
+use crate::multi_byte::{
+    MultiByteTranscoder, is_multi_byte, transcode_stream, transcoded_options,
+};
+use crate::sniff::{CsvDialect, DEFAULT_SNIFF_SAMPLE_SIZE};
 use crate::source::CsvSource;
 
 use arrow::array::RecordBatch;
@@ -170,12 +174,22 @@ impl CsvFormat {
 
     /// Convert a stream of bytes into a stream of of [`Bytes`] containing newline
     /// delimited CSV records, while accounting for `\` and `"`.
+    ///
+    /// If a delimiter or terminator sequence is configured, the records are
+    /// transcoded to comma separated CSV, see [`crate::multi_byte`].
     pub async fn read_to_delimited_chunks_from_stream<'a>(
         &self,
         stream: BoxStream<'a, Result<Bytes>>,
     ) -> BoxStream<'a, Result<Bytes>> {
         let file_compression_type: FileCompressionType = self.options.compression.into();
-        let decoder = file_compression_type.convert_stream(stream);
+        let decoder = file_compression_type
+            .convert_stream(stream)
+            .and_then(|stream| {
+                Ok(match MultiByteTranscoder::try_new(&self.options)? {
+                    Some(transcoder) => transcode_stream(stream, transcoder),
+                    None => stream,
+                })
+            });
         let stream = match decoder {
             Ok(decoded_stream) => {
                 newline_delimited_stream(decoded_stream.map_err(|e| match e {
@@ -306,6 +320,38 @@ impl CsvFormat {
         self
     }
 
+    /// Set true to detect the dialect of the files when inferring the schema.
+    /// See [`CsvDialect`] for what is detected.
+    /// - defaults to false
+    pub fn with_sniff_dialect(mut self, sniff_dialect: bool) -> Self {
+        self.options.sniff_dialect = sniff_dialect;
+        self
+    }
+
+    /// Set the number of bytes sampled from the start of the first file to
+    /// detect the dialect
+    /// - defaults to [`DEFAULT_SNIFF_SAMPLE_SIZE`]
+    pub fn with_sniff_sample_size(mut self, sample_size: usize) -> Self {
+        self.options.sniff_sample_size = Some(sample_size);
+        self
+    }
+
+    /// The sequence of bytes separating values within a row, such as `||`,
+    /// replacing the single byte delimiter when reading.
+    /// - default is None
+    pub fn with_delimiter_sequence(mut self, delimiter: Option<String>) -> Self {
+        self.options.delimiter_sequence = delimiter;
+        self
+    }
+
+    /// The sequence of bytes that terminates a row, replacing the single
+    /// byte terminator when reading.
+    /// - default is None
+    pub fn with_terminator_sequence(mut self, terminator: Option<String>) -> Self {
+        self.options.terminator_sequence = terminator;
+        self
+    }
+
     /// The delimiter character.
     pub fn delimiter(&self) -> u8 {
         self.options.delimiter
@@ -320,6 +366,94 @@ impl CsvFormat {
     pub fn escape(&self) -> Option<u8> {
         self.options.escape
     }
+
+    /// Returns the options used to read and write files of `file_schema`: when
+    /// sniffing is enabled, the dialect recorded in the schema metadata during
+    /// schema inference is applied to the configured options.
+    fn options_for_schema(&self, file_schema: &Schema) -> CsvOptions {
+        let dialect = self
+            .options
+            .sniff_dialect
+            .then(|| CsvDialect::from_metadata(file_schema.metadata()))
+            .flatten();
+        match dialect {
+            Some(dialect) => dialect.apply_to(self.options.clone()),
+            None => self.options.clone(),
+        }
+    }
+
+    /// Detects the dialect from a sample of whole lines from the start of the
+    /// first non empty object, or returns `None` if all objects are empty
+    async fn sniff_dialect(
+        &self,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> Result<Option<CsvDialect>> {
+        let Some(object) = objects.iter().find(|object| object.size > 0) else {
+            return Ok(None);
+        };
+        let sample_size = self
+            .options
+            .sniff_sample_size
+            .unwrap_or(DEFAULT_SNIFF_SAMPLE_SIZE);
+
+        let mut stream = self.read_to_delimited_chunks(store, object).await;
+        let mut sample = Vec::with_capacity(sample_size);
+        while sample.len() < sample_size
+            && let Some(chunk) = stream.next().await.transpose()?
+        {
+            sample.extend_from_slice(&chunk);
+        }
+        // Chunks end at line ends, so only cut the sample at the last line end
+        // within the sample size
+        if sample.len() > sample_size
+            && let Some(end) = sample[..sample_size].iter().rposition(|b| *b == b'\n')
+        {
+            sample.truncate(end + 1);
+        }
+        Ok(Some(CsvDialect::sniff(&sample)))
+    }
+
+    /// Infers the schema of `objects` with the configured options
+    async fn infer_schema_with_options(
+        &self,
+        state: &dyn Session,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> Result<Schema> {
+        let mut schemas = vec![];
+
+        let mut records_to_read = self
+            .options
+            .schema_infer_max_rec
+            .unwrap_or(DEFAULT_SCHEMA_INFER_MAX_RECORD);
+
+        // Records of multi-byte delimited files are transcoded to comma
+        // separated CSV when read
+        let transcoded = is_multi_byte(&self.options)
+            .then(|| Self::default().with_options(transcoded_options(&self.options)));
+        let infer_format = transcoded.as_ref().unwrap_or(self);
+
+        for object in objects {
+            let stream = self.read_to_delimited_chunks(store, object).await;
+            let (schema, records_read) = infer_format
+                .infer_schema_from_stream(state, records_to_read, stream)
+                .await
+                .map_err(|err| {
+                    DataFusionError::Context(
+                        format!("Error when processing CSV file {}", &object.location),
+                        Box::new(err),
+                    )
+                })?;
+            records_to_read -= records_read;
+            schemas.push(schema);
+            if records_to_read == 0 {
+                break;
+            }
+        }
+
+        Schema::try_merge(schemas).map_err(Into::into)
+    }
 }
 
 #[derive(Debug)]
@@ -379,33 +513,27 @@ impl FileFormat for CsvFormat {
         store: &Arc<dyn ObjectStore>,
         objects: &[ObjectMeta],
     ) -> Result<SchemaRef> {
-        let mut schemas = vec![];
-
-        let mut records_to_read = self
-            .options
-            .schema_infer_max_rec
-            .unwrap_or(DEFAULT_SCHEMA_INFER_MAX_RECORD);
-
-        for object in objects {
-            let stream = self.read_to_delimited_chunks(store, object).await;
-            let (schema, records_read) = self
-                .infer_schema_from_stream(state, records_to_read, stream)
-                .await
-                .map_err(|err| {
-                    DataFusionError::Context(
-                        format!("Error when processing CSV file {}", &object.location),
-                        Box::new(err),
-                    )
-                })?;
-            records_to_read -= records_read;
-            schemas.push(schema);
-            if records_to_read == 0 {
-                break;
-            }
-        }
+        let dialect = if self.options.sniff_dialect && !is_multi_byte(&self.options) {
+            self.sniff_dialect(store, objects).await?
+        } else {
+            None
+        };
+        let Some(dialect) = dialect else {
+            let schema = self
+                .infer_schema_with_options(state, store, objects)
+                .await?;
+            return Ok(Arc::new(schema));
+        };
 
-        let merged_schema = Schema::try_merge(schemas)?;
-        Ok(Arc::new(merged_schema))
+        // Infer the schema with the detected dialect, and record the dialect in
+        // the schema so that scans and writes use it too
+        let format = Self::default().with_options(dialect.apply_to(self.options.clone()));
+        let schema = format
+            .infer_schema_with_options(state, store, objects)
+            .await?;
+        let mut metadata = schema.metadata().clone();
+        metadata.extend(dialect.to_metadata());
+        Ok(Arc::new(schema.with_metadata(metadata)))
     }
 
     async fn infer_stats(
@@ -423,17 +551,16 @@ impl FileFormat for CsvFormat {
         state: &dyn Session,
         conf: FileScanConfig,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        let mut csv_options = self.options_for_schema(conf.file_schema());
+
         // Consult configuration options for default values
-        let has_header = self
-            .options
+        let has_header = csv_options
             .has_header
             .unwrap_or_else(|| state.config_options().catalog.has_header);
-        let newlines_in_values = self
-            .options
+        let newlines_in_values = csv_options
             .newlines_in_values
             .unwrap_or_else(|| state.config_options().catalog.newlines_in_values);
 
-        let mut csv_options = self.options.clone();
         csv_options.has_header = Some(has_header);
         csv_options.newlines_in_values = Some(newlines_in_values);
 
@@ -463,23 +590,25 @@ impl FileFormat for CsvFormat {
         if conf.insert_op != InsertOp::Append {
             return not_impl_err!("Overwrites are not implemented yet for CSV");
         }
+        if is_multi_byte(&self.options) {
+            return not_impl_err!(
+                "Writing CSV with a delimiter or terminator sequence is not supported"
+            );
+        }
 
         // `has_header` and `newlines_in_values` fields of CsvOptions may inherit
         // their values from session from configuration settings. To support
         // this logic, writer options are built from the copy of `self.options`
         // with updated values of these special fields.
-        let has_header = self
-            .options()
+        let options = self.options_for_schema(conf.output_schema());
+        let has_header = options
             .has_header
             .unwrap_or_else(|| state.config_options().catalog.has_header);
-        let newlines_in_values = self
-            .options()
+        let newlines_in_values = options
             .newlines_in_values
             .unwrap_or_else(|| state.config_options().catalog.newlines_in_values);
 
-        let options = self
-            .options()
-            .clone()
+        let options = options
             .with_has_header(has_header)
             .with_newlines_in_values(newlines_in_values);
 
@@ -491,7 +620,7 @@ impl FileFormat for CsvFormat {
     }
 
     fn file_source(&self, table_schema: TableSchema) -> Arc<dyn FileSource> {
-        let mut csv_options = self.options.clone();
+        let mut csv_options = self.options_for_schema(table_schema.file_schema());
         if csv_options.has_header.is_none() {
             csv_options.has_header = Some(true);
         }
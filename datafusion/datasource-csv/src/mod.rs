@@ -21,6 +21,8 @@
 #![cfg_attr(not(test), deny(clippy::clone_on_ref_ptr))]
 
 pub mod file_format;
+pub mod multi_byte;
+pub mod sniff;
 pub mod source;
 
 use std::sync::Arc;
@@ -0,0 +1,437 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Reading CSV files with delimiters and terminators of more than one byte
+//!
+//! The arrow CSV reader only supports single byte delimiters and terminators,
+//! so files configured with [`CsvOptions::delimiter_sequence`] or
+//! [`CsvOptions::terminator_sequence`] are first transcoded by
+//! [`MultiByteTranscoder`] into comma separated, newline terminated CSV, which
+//! is then read with the options returned by [`transcoded_options`].
+
+use bytes::Bytes;
+use datafusion_common::config::CsvOptions;
+use datafusion_common::{Result, config_err};
+use futures::stream::BoxStream;
+use futures::{StreamExt, stream};
+
+/// Returns true if `options` configure a delimiter or terminator sequence
+pub fn is_multi_byte(options: &CsvOptions) -> bool {
+    options.delimiter_sequence.is_some() || options.terminator_sequence.is_some()
+}
+
+/// Parses a delimiter or terminator sequence such as `||` or `\x01\x02`.
+///
+/// Bytes may be given as `\xNN` hex escapes, and `\t`, `\r`, `\n` and `\\` are
+/// also recognized. Any other character stands for its UTF-8 bytes.
+pub fn parse_byte_sequence(value: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('t') => bytes.push(b'\t'),
+            Some('r') => bytes.push(b'\r'),
+            Some('n') => bytes.push(b'\n'),
+            Some('\\') => bytes.push(b'\\'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return config_err!(
+                        "Invalid hex escape \\x{hex} in byte sequence {value}"
+                    );
+                }
+                bytes.push(u8::from_str_radix(&hex, 16).expect("valid hex"));
+            }
+            Some(other) => {
+                return config_err!("Invalid escape \\{other} in byte sequence {value}");
+            }
+            None => return config_err!("Trailing \\ in byte sequence {value}"),
+        }
+    }
+    if bytes.is_empty() {
+        return config_err!("Byte sequence must not be empty");
+    }
+    Ok(bytes)
+}
+
+/// Returns the options to read the output of a [`MultiByteTranscoder`] created
+/// from `options`
+pub fn transcoded_options(options: &CsvOptions) -> CsvOptions {
+    CsvOptions {
+        delimiter: b',',
+        quote: b'"',
+        terminator: None,
+        escape: None,
+        double_quote: None,
+        comment: None,
+        delimiter_sequence: None,
+        terminator_sequence: None,
+        ..options.clone()
+    }
+}
+
+/// What a [`MultiByteTranscoder`] is in the middle of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// At the start of a record
+    RecordStart,
+    /// At the start of a field
+    FieldStart,
+    /// In a field that is not quoted, or after the closing quote
+    Unquoted,
+    /// In a quoted field
+    Quoted,
+    /// In a comment line
+    Comment,
+}
+
+/// The separator found at a position of the input
+enum Separator {
+    Delimiter(usize),
+    Terminator(usize),
+    /// The input ends with a prefix of a separator
+    Incomplete,
+    None,
+}
+
+/// Transcodes CSV data with multi-byte delimiters or terminators into comma
+/// separated, newline terminated CSV.
+///
+/// Quoted values, escapes and comment lines are interpreted with the
+/// configured quote, escape and comment bytes, so separators within quoted
+/// values are kept. Values are quoted in the output where needed. Input can be
+/// provided in chunks of any size; separators split across chunks are
+/// recognized.
+#[derive(Debug)]
+pub struct MultiByteTranscoder {
+    delimiter: Vec<u8>,
+    /// Record terminators, longest first
+    terminators: Vec<Vec<u8>>,
+    quote: u8,
+    escape: Option<u8>,
+    double_quote: bool,
+    comment: Option<u8>,
+    state: State,
+    /// The unprocessed end of the input
+    pending: Vec<u8>,
+    /// The value of the current field
+    field: Vec<u8>,
+    /// Whether the current field was quoted
+    quoted: bool,
+}
+
+impl MultiByteTranscoder {
+    /// Returns a transcoder for `options`, or `None` if they configure neither
+    /// a delimiter nor a terminator sequence
+    pub fn try_new(options: &CsvOptions) -> Result<Option<Self>> {
+        if !is_multi_byte(options) {
+            return Ok(None);
+        }
+        let delimiter = match &options.delimiter_sequence {
+            Some(delimiter) => parse_byte_sequence(delimiter)?,
+            None => vec![options.delimiter],
+        };
+        let mut terminators = match (&options.terminator_sequence, options.terminator) {
+            (Some(terminator), _) => vec![parse_byte_sequence(terminator)?],
+            (None, Some(terminator)) => vec![vec![terminator]],
+            // Like the arrow CSV reader, default to any of CRLF, LF and CR
+            (None, None) => vec![b"\r\n".to_vec(), b"\n".to_vec(), b"\r".to_vec()],
+        };
+        if terminators.contains(&delimiter) {
+            return config_err!("CSV delimiter and terminator must be different");
+        }
+        terminators.sort_by_key(|t| std::cmp::Reverse(t.len()));
+
+        Ok(Some(Self {
+            delimiter,
+            terminators,
+            quote: options.quote,
+            escape: options.escape,
+            double_quote: options.double_quote.unwrap_or(true),
+            comment: options.comment,
+            state: State::RecordStart,
+            pending: vec![],
+            field: vec![],
+            quoted: false,
+        }))
+    }
+
+    /// Transcodes `input`, appending the output to `output`. The end of the
+    /// input may be kept until the next call if it could start a separator.
+    pub fn transcode(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        self.pending.extend_from_slice(input);
+        self.process(output, false);
+    }
+
+    /// Transcodes the rest of the input, terminating the last record
+    pub fn finish(&mut self, output: &mut Vec<u8>) {
+        self.process(output, true);
+        if !matches!(self.state, State::RecordStart | State::Comment) {
+            self.end_field(output);
+            output.push(b'\n');
+        }
+        self.state = State::RecordStart;
+    }
+
+    fn process(&mut self, output: &mut Vec<u8>, eof: bool) {
+        let pending = std::mem::take(&mut self.pending);
+        let mut pos = 0;
+        while pos < pending.len() {
+            let byte = pending[pos];
+            match self.state {
+                State::RecordStart => {
+                    if Some(byte) == self.comment {
+                        self.state = State::Comment;
+                        pos += 1;
+                        continue;
+                    }
+                    match self.separator_at(&pending[pos..], eof) {
+                        // Skip empty lines
+                        Separator::Terminator(len) => pos += len,
+                        Separator::Incomplete => break,
+                        _ => self.state = State::FieldStart,
+                    }
+                }
+                State::Comment => match self.separator_at(&pending[pos..], eof) {
+                    Separator::Terminator(len) => {
+                        self.state = State::RecordStart;
+                        pos += len;
+                    }
+                    Separator::Incomplete => break,
+                    _ => pos += 1,
+                },
+                State::FieldStart if byte == self.quote => {
+                    self.quoted = true;
+                    self.state = State::Quoted;
+                    pos += 1;
+                }
+                State::FieldStart | State::Unquoted => {
+                    match self.separator_at(&pending[pos..], eof) {
+                        Separator::Delimiter(len) => {
+                            self.end_field(output);
+                            output.push(b',');
+                            self.state = State::FieldStart;
+                            pos += len;
+                        }
+                        Separator::Terminator(len) => {
+                            self.end_field(output);
+                            output.push(b'\n');
+                            self.state = State::RecordStart;
+                            pos += len;
+                        }
+                        Separator::Incomplete => break,
+                        Separator::None => {
+                            self.field.push(byte);
+                            self.state = State::Unquoted;
+                            pos += 1;
+                        }
+                    }
+                }
+                State::Quoted => {
+                    let next = pending.get(pos + 1).copied();
+                    if next.is_none()
+                        && !eof
+                        && (self.is_escape(byte) || byte == self.quote)
+                    {
+                        // The meaning depends on the next byte
+                        break;
+                    }
+                    if self.is_escape(byte)
+                        && let Some(next) = next
+                    {
+                        self.field.push(next);
+                        pos += 2;
+                    } else if byte == self.quote {
+                        if self.double_quote && next == Some(self.quote) {
+                            self.field.push(self.quote);
+                            pos += 2;
+                        } else {
+                            self.state = State::Unquoted;
+                            pos += 1;
+                        }
+                    } else {
+                        self.field.push(byte);
+                        pos += 1;
+                    }
+                }
+            }
+        }
+        self.pending = pending[pos..].to_vec();
+    }
+
+    fn is_escape(&self, byte: u8) -> bool {
+        self.escape
+            .is_some_and(|escape| escape == byte && escape != self.quote)
+    }
+
+    /// Returns the separator at the start of `input`, preferring the longest
+    fn separator_at(&self, input: &[u8], eof: bool) -> Separator {
+        let mut separators = self
+            .terminators
+            .iter()
+            .map(|t| (t, true))
+            .chain(std::iter::once((&self.delimiter, false)))
+            .collect::<Vec<_>>();
+        separators.sort_by_key(|(s, _)| std::cmp::Reverse(s.len()));
+
+        for (separator, is_terminator) in separators {
+            if input.starts_with(separator) {
+                return if is_terminator {
+                    Separator::Terminator(separator.len())
+                } else {
+                    Separator::Delimiter(separator.len())
+                };
+            }
+            if !eof && input.len() < separator.len() && separator.starts_with(input) {
+                return Separator::Incomplete;
+            }
+        }
+        Separator::None
+    }
+
+    /// Writes the current field to `output`, quoted if needed
+    fn end_field(&mut self, output: &mut Vec<u8>) {
+        let needs_quotes = self.quoted
+            || self
+                .field
+                .iter()
+                .any(|b| matches!(b, b',' | b'"' | b'\n' | b'\r'));
+        if needs_quotes {
+            output.push(b'"');
+            for &byte in &self.field {
+                if byte == b'"' {
+                    output.push(b'"');
+                }
+                output.push(byte);
+            }
+            output.push(b'"');
+        } else {
+            output.extend_from_slice(&self.field);
+        }
+        self.field.clear();
+        self.quoted = false;
+    }
+}
+
+/// Transcodes a stream of CSV data with `transcoder`
+pub fn transcode_stream<'a>(
+    input: BoxStream<'a, Result<Bytes>>,
+    transcoder: MultiByteTranscoder,
+) -> BoxStream<'a, Result<Bytes>> {
+    stream::unfold(Some((input, transcoder)), |state| async move {
+        let (mut input, mut transcoder) = state?;
+        let mut output = vec![];
+        loop {
+            match input.next().await {
+                Some(Ok(bytes)) => {
+                    transcoder.transcode(&bytes, &mut output);
+                    if !output.is_empty() {
+                        return Some((Ok(output.into()), Some((input, transcoder))));
+                    }
+                }
+                Some(Err(e)) => return Some((Err(e), None)),
+                None => {
+                    transcoder.finish(&mut output);
+                    return (!output.is_empty()).then(|| (Ok(output.into()), None));
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(delimiter: Option<&str>, terminator: Option<&str>) -> CsvOptions {
+        CsvOptions::default()
+            .with_delimiter_sequence(delimiter.map(String::from))
+            .with_terminator_sequence(terminator.map(String::from))
+    }
+
+    /// Transcodes `input` split into chunks of `chunk_size` bytes
+    fn transcode(options: &CsvOptions, input: &[u8], chunk_size: usize) -> String {
+        let mut transcoder = MultiByteTranscoder::try_new(options).unwrap().unwrap();
+        let mut output = vec![];
+        for chunk in input.chunks(chunk_size) {
+            transcoder.transcode(chunk, &mut output);
+        }
+        transcoder.finish(&mut output);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn parse_sequences() {
+        assert_eq!(parse_byte_sequence("||").unwrap(), b"||");
+        assert_eq!(parse_byte_sequence("\\x01\\x02").unwrap(), [1, 2]);
+        assert_eq!(parse_byte_sequence("\\t\\r\\n\\\\").unwrap(), b"\t\r\n\\");
+        assert_eq!(parse_byte_sequence("§").unwrap(), "§".as_bytes());
+        assert!(parse_byte_sequence("").is_err());
+        assert!(parse_byte_sequence("\\x1").is_err());
+        assert!(parse_byte_sequence("\\xzz").is_err());
+        assert!(parse_byte_sequence("\\q").is_err());
+        assert!(parse_byte_sequence("a\\").is_err());
+    }
+
+    #[test]
+    fn single_byte_options() {
+        assert!(
+            MultiByteTranscoder::try_new(&CsvOptions::default())
+                .unwrap()
+                .is_none()
+        );
+        assert!(MultiByteTranscoder::try_new(&options(Some("|"), Some("|"))).is_err());
+    }
+
+    #[test]
+    fn transcode_delimiter() {
+        let options = options(Some("||"), None);
+        let input = b"a||b||c\n1||x,y||\r\n2|||3\n";
+        let expected = "a,b,c\n1,\"x,y\",\n2,|3\n";
+        for chunk_size in 1..=input.len() {
+            assert_eq!(transcode(&options, input, chunk_size), expected);
+        }
+    }
+
+    #[test]
+    fn transcode_delimiter_and_terminator() {
+        let options = options(Some("\\x01\\x02"), Some("~~"));
+        let input = b"a\x01\x02b~~1\x01\x02line\none~~~~2\x01\x02\"q\"";
+        let expected = "a,b\n1,\"line\none\"\n2,\"q\"\n";
+        for chunk_size in 1..=input.len() {
+            assert_eq!(transcode(&options, input, chunk_size), expected);
+        }
+    }
+
+    #[test]
+    fn transcode_quotes_and_escapes() {
+        let mut options = options(Some("::"), None);
+        options.escape = Some(b'\\');
+        options.comment = Some(b'#');
+        let input = b"#a::comment\n\"a::b\"::\"say \"\"hi\"\"\"::\"x\\\"y\"\n";
+        let expected = "\"a::b\",\"say \"\"hi\"\"\",\"x\"\"y\"\n";
+        for chunk_size in 1..=input.len() {
+            assert_eq!(transcode(&options, input, chunk_size), expected);
+        }
+    }
+}
@@ -0,0 +1,561 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CsvDialect`] detection ("sniffing") from a sample of a CSV file
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use datafusion_common::config::CsvOptions;
+
+/// The number of bytes sampled from the start of a file to detect its dialect
+/// when `sniff_sample_size` is not set
+pub const DEFAULT_SNIFF_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// Prefix of the schema metadata keys recording a detected [`CsvDialect`]
+pub const CSV_DIALECT_METADATA_PREFIX: &str = "csv.dialect.";
+
+/// Candidate delimiters, in order of preference on ties
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Candidate quote characters, in order of preference on ties
+const QUOTES: [u8; 2] = [b'"', b'\''];
+
+/// Unquoted values commonly used by exporting tools to represent nulls
+const NULL_TOKENS: [&str; 7] = ["NULL", "null", "NA", "N/A", "\\N", "None", "nil"];
+
+/// Candidate date formats. Day first formats are tried before month first
+/// ones, so dates such as `03/04/2024` that are valid in both are read day
+/// first.
+const DATE_FORMATS: [&str; 6] = [
+    "%Y-%m-%d", "%d/%m/%Y", "%m/%d/%Y", "%d.%m.%Y", "%Y/%m/%d", "%d-%m-%Y",
+];
+
+/// Candidate timestamp formats, in the same order as [`DATE_FORMATS`]
+const TIMESTAMP_FORMATS: [&str; 12] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+    "%d-%m-%Y %H:%M:%S",
+    "%d-%m-%Y %H:%M",
+];
+
+/// The dialect of a CSV file: how its values are separated, quoted and
+/// formatted.
+///
+/// A dialect is detected with [`CsvDialect::sniff`] from a sample of a file
+/// when the `sniff_dialect` CSV option is set, and recorded in the metadata
+/// of the inferred schema under [`CSV_DIALECT_METADATA_PREFIX`] so that scans
+/// and writes of the table, as well as `DESCRIBE`, use the same dialect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvDialect {
+    /// The character separating values within a row
+    pub delimiter: u8,
+    /// The character quoting values
+    pub quote: u8,
+    /// The character escaping quotes within quoted values, if any
+    pub escape: Option<u8>,
+    /// Whether the first row is a header
+    pub has_header: bool,
+    /// Lines starting with this character are comments
+    pub comment: Option<u8>,
+    /// A regex matching the values read as nulls, if the file uses null
+    /// tokens other than empty values
+    pub null_regex: Option<String>,
+    /// The format of date values, if any column holds dates
+    pub date_format: Option<String>,
+    /// The format of timestamp values, if any column holds timestamps
+    pub timestamp_format: Option<String>,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            has_header: true,
+            comment: None,
+            null_regex: None,
+            date_format: None,
+            timestamp_format: None,
+        }
+    }
+}
+
+impl CsvDialect {
+    /// Detects the dialect of a CSV file from a sample of whole lines from its
+    /// start.
+    ///
+    /// Every combination of the candidate delimiters and quotes is used to
+    /// split the sampled rows, and the one splitting the most rows into the
+    /// same number of fields is picked. The first row is a header if its
+    /// values do not have the types of the values below them. Returns the
+    /// default dialect if the sample has no rows.
+    pub fn sniff(sample: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(sample);
+        let lines: Vec<&str> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+
+        let comment = (lines.iter().any(|line| line.starts_with('#'))
+            && lines.iter().any(|line| !line.starts_with('#')))
+        .then_some(b'#');
+        let lines: Vec<&str> = lines
+            .into_iter()
+            .filter(|line| comment.is_none() || !line.starts_with('#'))
+            .collect();
+        if lines.is_empty() {
+            return Self::default();
+        }
+
+        let (delimiter, quote, escape) = sniff_separators(&text, &lines);
+        let rows: Vec<Vec<Value>> = lines
+            .iter()
+            .map(|line| split_fields(line, delimiter, quote, escape))
+            .collect();
+
+        // The first row is never a null token, even when it is not a header
+        let null_tokens: Vec<&str> = NULL_TOKENS
+            .into_iter()
+            .filter(|token| {
+                rows.iter()
+                    .skip(1)
+                    .flatten()
+                    .any(|value| !value.quoted && value.text == *token)
+            })
+            .collect();
+        let is_null = |value: &Value| {
+            value.text.is_empty()
+                || (!value.quoted && null_tokens.contains(&value.text.as_str()))
+        };
+
+        let has_header = sniff_header(&rows, &is_null);
+        let data_rows = &rows[usize::from(has_header)..];
+        let date_format = sniff_format(data_rows, &DATE_FORMATS, &is_null, |v, f| {
+            NaiveDate::parse_from_str(v, f).is_ok()
+        });
+        let timestamp_format =
+            sniff_format(data_rows, &TIMESTAMP_FORMATS, &is_null, |v, f| {
+                NaiveDateTime::parse_from_str(v, f).is_ok()
+            });
+
+        let null_regex = (!null_tokens.is_empty()).then(|| {
+            let tokens: Vec<String> = null_tokens
+                .iter()
+                .map(|token| regex::escape(token))
+                .collect();
+            format!("^(?:{})?$", tokens.join("|"))
+        });
+
+        Self {
+            delimiter,
+            quote,
+            escape,
+            has_header,
+            comment,
+            null_regex,
+            date_format: date_format.map(str::to_string),
+            timestamp_format: timestamp_format.map(str::to_string),
+        }
+    }
+
+    /// Applies the dialect to `options`.
+    ///
+    /// The delimiter and quote always replace the configured ones, while the
+    /// other settings only fill options that are not set.
+    pub fn apply_to(&self, mut options: CsvOptions) -> CsvOptions {
+        options.delimiter = self.delimiter;
+        options.quote = self.quote;
+        options.escape = options.escape.or(self.escape);
+        options.has_header = options.has_header.or(Some(self.has_header));
+        options.comment = options.comment.or(self.comment);
+        options.null_regex = options.null_regex.or_else(|| self.null_regex.clone());
+        options.date_format = options.date_format.or_else(|| self.date_format.clone());
+        options.timestamp_format = options
+            .timestamp_format
+            .or_else(|| self.timestamp_format.clone());
+        options
+    }
+
+    /// Returns the schema metadata entries recording this dialect
+    pub fn to_metadata(&self) -> HashMap<String, String> {
+        let entries = [
+            ("delimiter", Some(byte_to_string(self.delimiter))),
+            ("quote", Some(byte_to_string(self.quote))),
+            ("escape", self.escape.map(byte_to_string)),
+            ("has_header", Some(self.has_header.to_string())),
+            ("comment", self.comment.map(byte_to_string)),
+            ("null_regex", self.null_regex.clone()),
+            ("date_format", self.date_format.clone()),
+            ("timestamp_format", self.timestamp_format.clone()),
+        ];
+        entries
+            .into_iter()
+            .filter_map(|(key, value)| {
+                value.map(|value| (format!("{CSV_DIALECT_METADATA_PREFIX}{key}"), value))
+            })
+            .collect()
+    }
+
+    /// Reads a dialect recorded by [`Self::to_metadata`], or returns `None` if
+    /// `metadata` has no dialect
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Option<Self> {
+        let get =
+            |key: &str| metadata.get(&format!("{CSV_DIALECT_METADATA_PREFIX}{key}"));
+        Some(Self {
+            delimiter: get("delimiter").and_then(|v| string_to_byte(v))?,
+            quote: get("quote").and_then(|v| string_to_byte(v))?,
+            escape: get("escape").and_then(|v| string_to_byte(v)),
+            has_header: get("has_header")?.parse().ok()?,
+            comment: get("comment").and_then(|v| string_to_byte(v)),
+            null_regex: get("null_regex").cloned(),
+            date_format: get("date_format").cloned(),
+            timestamp_format: get("timestamp_format").cloned(),
+        })
+    }
+}
+
+impl Display for CsvDialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "none".into());
+        write!(
+            f,
+            "delimiter='{}', quote='{}', escape={}, has_header={}, comment={}, \
+             null_regex={}, date_format={}, timestamp_format={}",
+            byte_to_string(self.delimiter),
+            byte_to_string(self.quote),
+            optional(self.escape.map(byte_to_string)),
+            self.has_header,
+            optional(self.comment.map(byte_to_string)),
+            optional(self.null_regex.clone()),
+            optional(self.date_format.clone()),
+            optional(self.timestamp_format.clone()),
+        )
+    }
+}
+
+/// A value split from a row
+#[derive(Debug)]
+struct Value {
+    text: String,
+    quoted: bool,
+}
+
+/// Picks the delimiter, quote and escape splitting the most `lines` into the
+/// same number of fields
+fn sniff_separators(text: &str, lines: &[&str]) -> (u8, u8, Option<u8>) {
+    let mut best = (b',', b'"', None);
+    // (rows with the most common number of fields, that number, quoted fields)
+    let mut best_score = (0, 1, 0);
+    for delimiter in DELIMITERS {
+        for quote in QUOTES {
+            let escape = text
+                .contains(&format!("\\{}", quote as char))
+                .then_some(b'\\');
+            let rows: Vec<Vec<Value>> = lines
+                .iter()
+                .map(|line| split_fields(line, delimiter, quote, escape))
+                .collect();
+
+            let mut counts = HashMap::<usize, usize>::new();
+            for row in &rows {
+                *counts.entry(row.len()).or_default() += 1;
+            }
+            let (num_fields, num_rows) = counts
+                .into_iter()
+                .max_by_key(|(num_fields, num_rows)| (*num_rows, *num_fields))
+                .unwrap_or((1, 0));
+            let quoted = rows.iter().flatten().filter(|value| value.quoted).count();
+
+            let score = (num_rows, num_fields, quoted);
+            if num_fields > 1 && score > best_score {
+                best = (delimiter, quote, escape);
+                best_score = score;
+            }
+        }
+    }
+    best
+}
+
+/// Splits a line into its values, removing quotes around quoted values
+fn split_fields(line: &str, delimiter: u8, quote: u8, escape: Option<u8>) -> Vec<Value> {
+    let (delimiter, quote) = (delimiter as char, quote as char);
+    let escape = escape.map(char::from);
+    let mut values = vec![];
+    let mut text = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if Some(c) == escape {
+                text.extend(chars.next());
+            } else if c == quote {
+                if chars.peek() == Some(&quote) {
+                    text.push(quote);
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                text.push(c);
+            }
+        } else if c == delimiter {
+            values.push(Value {
+                text: std::mem::take(&mut text),
+                quoted,
+            });
+            quoted = false;
+        } else if c == quote && text.is_empty() {
+            in_quotes = true;
+            quoted = true;
+        } else {
+            text.push(c);
+        }
+    }
+    values.push(Value { text, quoted });
+    values
+}
+
+/// The kind of value of a field, used to tell headers from data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    Timestamp,
+    Text,
+}
+
+impl Kind {
+    fn of(value: &str) -> Self {
+        if value.parse::<i64>().is_ok() {
+            Self::Integer
+        } else if value.parse::<f64>().is_ok() {
+            Self::Float
+        } else if value.eq_ignore_ascii_case("true")
+            || value.eq_ignore_ascii_case("false")
+        {
+            Self::Boolean
+        } else if DATE_FORMATS
+            .iter()
+            .any(|format| NaiveDate::parse_from_str(value, format).is_ok())
+        {
+            Self::Date
+        } else if TIMESTAMP_FORMATS
+            .iter()
+            .any(|format| NaiveDateTime::parse_from_str(value, format).is_ok())
+        {
+            Self::Timestamp
+        } else {
+            Self::Text
+        }
+    }
+}
+
+/// Whether the first row is a header: each column whose values below the first
+/// row all have the same kind votes for a header if the first value has a
+/// different kind, and against it otherwise. Text columns vote for a header if
+/// all their values below the first row have the same length, which differs
+/// from the length of the first value. Ties are read as a header.
+fn sniff_header(rows: &[Vec<Value>], is_null: &impl Fn(&Value) -> bool) -> bool {
+    let Some((first, rest)) = rows.split_first() else {
+        return true;
+    };
+    if rest.is_empty() {
+        return first
+            .iter()
+            .all(|value| value.quoted || Kind::of(&value.text) == Kind::Text);
+    }
+
+    let mut votes = 0_i64;
+    for (column, header) in first.iter().enumerate() {
+        let values: Vec<&Value> = rest
+            .iter()
+            .filter_map(|row| row.get(column))
+            .filter(|value| !is_null(value))
+            .collect();
+        let Some(kind) = values.first().map(|value| Kind::of(&value.text)) else {
+            continue;
+        };
+        if values.iter().any(|value| Kind::of(&value.text) != kind) {
+            continue;
+        }
+        if kind != Kind::Text {
+            votes += if Kind::of(&header.text) == kind {
+                -1
+            } else {
+                1
+            };
+        } else {
+            let len = values[0].text.chars().count();
+            if values.iter().all(|value| value.text.chars().count() == len)
+                && header.text.chars().count() != len
+            {
+                votes += 1;
+            }
+        }
+    }
+    votes >= 0
+}
+
+/// Returns the first of `formats` that parses every non null value of a
+/// column of `rows`, preferring the format of the leftmost such column
+fn sniff_format(
+    rows: &[Vec<Value>],
+    formats: &[&'static str],
+    is_null: &impl Fn(&Value) -> bool,
+    parses: impl Fn(&str, &str) -> bool,
+) -> Option<&'static str> {
+    let num_columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    (0..num_columns).find_map(|column| {
+        let values: Vec<&str> = rows
+            .iter()
+            .filter_map(|row| row.get(column))
+            .filter(|value| !is_null(value))
+            .map(|value| value.text.as_str())
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        formats
+            .iter()
+            .copied()
+            .find(|&format| values.iter().all(|&value| parses(value, format)))
+    })
+}
+
+fn byte_to_string(byte: u8) -> String {
+    match byte {
+        b'\t' => "\\t".to_string(),
+        byte => (byte as char).to_string(),
+    }
+}
+
+fn string_to_byte(value: &str) -> Option<u8> {
+    match value.as_bytes() {
+        b"\\t" => Some(b'\t'),
+        [byte] => Some(*byte),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_semicolon_delimited_day_first_dates() {
+        let sample = b"id;name;amount;born\n\
+            1;\"Dupont; Jean\";1,5;31/12/1980\n\
+            2;Marie;2,25;01/02/1975\n\
+            3;NULL;3;NULL\n";
+        let dialect = CsvDialect::sniff(sample);
+        assert_eq!(dialect.delimiter, b';');
+        assert_eq!(dialect.quote, b'"');
+        assert_eq!(dialect.escape, None);
+        assert!(dialect.has_header);
+        assert_eq!(dialect.null_regex.as_deref(), Some("^(?:NULL)?$"));
+        assert_eq!(dialect.date_format.as_deref(), Some("%d/%m/%Y"));
+        assert_eq!(dialect.timestamp_format, None);
+    }
+
+    #[test]
+    fn sniff_headerless_tab_delimited() {
+        let sample = b"1\t2024-01-02 10:00:00\ttrue\n\
+            2\t2024-01-03 11:30:00\tfalse\n\
+            3\t2024-01-04 12:45:00\ttrue\n";
+        let dialect = CsvDialect::sniff(sample);
+        assert_eq!(dialect.delimiter, b'\t');
+        assert!(!dialect.has_header);
+        assert_eq!(dialect.date_format, None);
+        assert_eq!(
+            dialect.timestamp_format.as_deref(),
+            Some("%Y-%m-%d %H:%M:%S")
+        );
+    }
+
+    #[test]
+    fn sniff_comments_quotes_and_escapes() {
+        let sample = b"# exported by tool\n\
+            'a'|'b'\n\
+            'x \\' y'|'1'\n\
+            'z'|'2'\n";
+        let dialect = CsvDialect::sniff(sample);
+        assert_eq!(dialect.delimiter, b'|');
+        assert_eq!(dialect.quote, b'\'');
+        assert_eq!(dialect.escape, Some(b'\\'));
+        assert_eq!(dialect.comment, Some(b'#'));
+        assert!(dialect.has_header);
+    }
+
+    #[test]
+    fn sniff_month_first_dates() {
+        let sample = b"d,v\n12/31/2024,1\n01/02/2024,2\n";
+        let dialect = CsvDialect::sniff(sample);
+        assert_eq!(dialect.delimiter, b',');
+        assert_eq!(dialect.date_format.as_deref(), Some("%m/%d/%Y"));
+    }
+
+    #[test]
+    fn sniff_empty_sample() {
+        assert_eq!(CsvDialect::sniff(b""), CsvDialect::default());
+    }
+
+    #[test]
+    fn dialect_metadata_roundtrip() {
+        let dialect = CsvDialect {
+            delimiter: b'\t',
+            escape: Some(b'\\'),
+            has_header: false,
+            null_regex: Some("^(?:NA)?$".to_string()),
+            date_format: Some("%d.%m.%Y".to_string()),
+            ..Default::default()
+        };
+        let metadata = dialect.to_metadata();
+        assert_eq!(metadata["csv.dialect.delimiter"], "\\t");
+        assert_eq!(CsvDialect::from_metadata(&metadata), Some(dialect));
+        assert_eq!(CsvDialect::from_metadata(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn apply_keeps_explicit_options() {
+        let dialect = CsvDialect {
+            delimiter: b';',
+            has_header: false,
+            null_regex: Some("^(?:NULL)?$".to_string()),
+            ..Default::default()
+        };
+        let mut options = CsvOptions::default().with_has_header(true);
+        options.null_regex = Some("^$".to_string());
+        let options = dialect.apply_to(options);
+        assert_eq!(options.delimiter, b';');
+        assert_eq!(options.has_header, Some(true));
+        assert_eq!(options.null_regex.as_deref(), Some("^$"));
+    }
+}
@@ -34,7 +34,7 @@ use datafusion_datasource::{
 
 use arrow::csv;
 use datafusion_common::config::CsvOptions;
-use datafusion_common::{DataFusionError, Result};
+use datafusion_common::{DataFusionError, Result, exec_datafusion_err};
 use datafusion_common_runtime::JoinSet;
 use datafusion_datasource::file::FileSource;
 use datafusion_datasource::file_scan_config::FileScanConfig;
@@ -45,9 +45,13 @@ use datafusion_physical_plan::{
 };
 
 use crate::file_format::CsvDecoder;
+use crate::multi_byte::{
+    MultiByteTranscoder, is_multi_byte, transcode_stream, transcoded_options,
+};
 use futures::{StreamExt, TryStreamExt};
 use object_store::buffered::BufWriter;
 use object_store::{GetOptions, GetResultPayload, ObjectStore};
+use regex::Regex;
 use tokio::io::AsyncWriteExt;
 
 /// A Config for [`CsvOpener`]
@@ -181,10 +185,10 @@ impl CsvSource {
 
 impl CsvSource {
     fn open<R: Read>(&self, reader: R) -> Result<csv::Reader<R>> {
-        Ok(self.builder().build(reader)?)
+        Ok(self.builder()?.build(reader)?)
     }
 
-    fn builder(&self) -> csv::ReaderBuilder {
+    fn builder(&self) -> Result<csv::ReaderBuilder> {
         let mut builder =
             csv::ReaderBuilder::new(Arc::clone(self.table_schema.file_schema()))
                 .with_delimiter(self.delimiter())
@@ -205,8 +209,14 @@ impl CsvSource {
         if let Some(comment) = self.comment() {
             builder = builder.with_comment(comment);
         }
+        if let Some(null_regex) = &self.options.null_regex {
+            let regex = Regex::new(null_regex).map_err(|e| {
+                exec_datafusion_err!("Invalid CSV null regex {null_regex}: {e}")
+            })?;
+            builder = builder.with_null_regex(regex);
+        }
 
-        builder
+        Ok(builder)
     }
 }
 
@@ -297,8 +307,10 @@ impl FileSource for CsvSource {
 
     fn supports_repartitioning(&self) -> bool {
         // Cannot repartition if values may contain newlines, as record
-        // boundaries cannot be determined by byte offset alone
-        !self.options.newlines_in_values.unwrap_or(false)
+        // boundaries cannot be determined by byte offset alone. Ranges are
+        // aligned on single byte terminators, so neither if a delimiter or
+        // terminator sequence is used.
+        !self.options.newlines_in_values.unwrap_or(false) && !is_multi_byte(&self.options)
     }
 
     fn fmt_extra(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
@@ -350,6 +362,13 @@ impl FileOpener for CsvOpener {
         config.options.has_header = Some(csv_has_header);
         config.options.truncated_rows = Some(config.truncate_rows());
 
+        // Files with a delimiter or terminator sequence are transcoded to comma
+        // separated CSV before decoding
+        let transcoder = MultiByteTranscoder::try_new(&config.options)?;
+        if transcoder.is_some() {
+            config.options = transcoded_options(&config.options);
+        }
+
         let file_compression_type = self.file_compression_type.to_owned();
 
         if partitioned_file.range.is_some() {
@@ -390,6 +409,19 @@ impl FileOpener for CsvOpener {
                 .get_opts(&partitioned_file.object_meta.location, options)
                 .await?;
 
+            if let Some(transcoder) = transcoder {
+                let decoder = config.builder()?.build_decoder();
+                let s = result.into_stream().map_err(DataFusionError::from);
+                let input = file_compression_type.convert_stream(s.boxed())?;
+                let input = transcode_stream(input, transcoder).fuse();
+
+                let stream = deserialize_stream(
+                    input,
+                    DecoderDeserializer::new(CsvDecoder::new(decoder)),
+                );
+                return Ok(stream.map_err(Into::into).boxed());
+            }
+
             match result.payload {
                 #[cfg(not(target_arch = "wasm32"))]
                 GetResultPayload::File(mut file, _) => {
@@ -419,7 +451,7 @@ impl FileOpener for CsvOpener {
                         .boxed())
                 }
                 GetResultPayload::Stream(s) => {
-                    let decoder = config.builder().build_decoder();
+                    let decoder = config.builder()?.build_decoder();
                     let s = s.map_err(DataFusionError::from);
                     let input = file_compression_type.convert_stream(s.boxed())?.fuse();
 
@@ -0,0 +1,191 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Column specs of fixed-width records
+
+use std::collections::HashSet;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use datafusion_common::config::FixedWidthOptions;
+use datafusion_common::{Result, config_datafusion_err, config_err};
+
+/// A column of fixed-width records, found at the same byte offset of each
+/// record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedWidthColumn {
+    /// The name of the column
+    pub name: String,
+    /// The 0 based byte offset of the column in the record
+    pub start: usize,
+    /// The width of the column in bytes
+    pub length: usize,
+}
+
+impl FixedWidthColumn {
+    /// Returns the bytes of this column in `record`, which are cut short or
+    /// empty if the record ends before the column does
+    pub fn value<'a>(&self, record: &'a [u8]) -> &'a [u8] {
+        let start = self.start.min(record.len());
+        let end = (self.start + self.length).min(record.len());
+        &record[start..end]
+    }
+}
+
+/// Parses comma separated `name:start:length` column specs, such as
+/// `id:0:6,name:6:20`
+pub fn parse_columns(specs: &str) -> Result<Vec<FixedWidthColumn>> {
+    let mut names = HashSet::new();
+    specs
+        .split(',')
+        .map(|spec| {
+            let spec = spec.trim();
+            // Split from the end so that names may contain `:`
+            let mut parts = spec.rsplitn(3, ':');
+            let (Some(length), Some(start), Some(name)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                return config_err!(
+                    "Invalid fixed-width column {spec}, expected name:start:length"
+                );
+            };
+            let parse = |value: &str| {
+                value.trim().parse::<usize>().map_err(|e| {
+                    config_datafusion_err!(
+                        "Invalid offset {value} of fixed-width column {spec}: {e}"
+                    )
+                })
+            };
+            let column = FixedWidthColumn {
+                name: name.trim().to_string(),
+                start: parse(start)?,
+                length: parse(length)?,
+            };
+            if column.name.is_empty() || column.length == 0 {
+                return config_err!(
+                    "Fixed-width column {spec} must have a name and a length greater than 0"
+                );
+            }
+            if !names.insert(column.name.clone()) {
+                return config_err!("Duplicate fixed-width column {}", column.name);
+            }
+            Ok(column)
+        })
+        .collect()
+}
+
+/// Returns the columns configured by `options`
+pub fn configured_columns(options: &FixedWidthOptions) -> Result<Vec<FixedWidthColumn>> {
+    match &options.columns {
+        Some(columns) => parse_columns(columns),
+        None => config_err!(
+            "Fixed-width files require the columns option, as name:start:length specs"
+        ),
+    }
+}
+
+/// Returns a schema of nullable string fields for `columns`
+pub fn columns_schema(columns: &[FixedWidthColumn]) -> Schema {
+    Schema::new(
+        columns
+            .iter()
+            .map(|column| Field::new(&column.name, DataType::Utf8, true))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Returns the columns configured by `options` for each field of `schema`,
+/// matched by name
+pub fn columns_for_schema(
+    options: &FixedWidthOptions,
+    schema: &Schema,
+) -> Result<Vec<FixedWidthColumn>> {
+    let columns = configured_columns(options)?;
+    schema
+        .fields()
+        .iter()
+        .map(|field| {
+            columns
+                .iter()
+                .find(|column| &column.name == field.name())
+                .cloned()
+                .ok_or_else(|| {
+                    config_datafusion_err!(
+                        "No fixed-width column spec for field {}",
+                        field.name()
+                    )
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, start: usize, length: usize) -> FixedWidthColumn {
+        FixedWidthColumn {
+            name: name.to_string(),
+            start,
+            length,
+        }
+    }
+
+    #[test]
+    fn parse_specs() {
+        assert_eq!(
+            parse_columns("id:0:6, name:6:20,a:b:26:2").unwrap(),
+            vec![
+                column("id", 0, 6),
+                column("name", 6, 20),
+                column("a:b", 26, 2)
+            ]
+        );
+        assert!(parse_columns("id:0").is_err());
+        assert!(parse_columns("id:x:6").is_err());
+        assert!(parse_columns("id:0:0").is_err());
+        assert!(parse_columns(":0:6").is_err());
+        assert!(parse_columns("id:0:6,id:6:2").is_err());
+    }
+
+    #[test]
+    fn column_values() {
+        let id = column("id", 2, 3);
+        assert_eq!(id.value(b"abcdefg"), b"cde");
+        assert_eq!(id.value(b"abcd"), b"cd");
+        assert_eq!(id.value(b"a"), b"");
+    }
+
+    #[test]
+    fn schema_columns() {
+        let options = FixedWidthOptions {
+            columns: Some("id:0:6,name:6:20".to_string()),
+            ..Default::default()
+        };
+        let schema = Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("id", DataType::Int64, true),
+        ]);
+        assert_eq!(
+            columns_for_schema(&options, &schema).unwrap(),
+            vec![column("name", 6, 20), column("id", 0, 6)]
+        );
+
+        let schema = Schema::new(vec![Field::new("other", DataType::Utf8, true)]);
+        assert!(columns_for_schema(&options, &schema).is_err());
+        assert!(configured_columns(&FixedWidthOptions::default()).is_err());
+    }
+}
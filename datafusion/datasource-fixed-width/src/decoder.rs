@@ -0,0 +1,307 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Decoding of fixed-width records into [`RecordBatch`]es
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::columns::FixedWidthColumn;
+
+use arrow::array::{ArrayRef, RecordBatch, RecordBatchOptions, StringBuilder};
+use arrow::compute::{CastOptions, cast_with_options};
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::error::ArrowError;
+use datafusion_datasource::decoder::Decoder;
+
+/// Decodes fixed-width records into batches of `schema`.
+///
+/// Values are read as strings and cast to the types of `schema`. Records are
+/// either terminated by `\n` or `\r\n`, skipping empty lines, or have a fixed
+/// length in bytes when `record_length` is set.
+pub(crate) struct FixedWidthDecoder {
+    /// The columns to decode, one for each field of `schema`
+    columns: Vec<FixedWidthColumn>,
+    schema: SchemaRef,
+    batch_size: usize,
+    trim: bool,
+    record_length: Option<usize>,
+    /// Whether the next record is a header to skip
+    skip_header: bool,
+    builders: Vec<StringBuilder>,
+    num_rows: usize,
+    /// The start of a record whose end has not been decoded yet
+    partial: Vec<u8>,
+}
+
+impl FixedWidthDecoder {
+    pub(crate) fn new(
+        columns: Vec<FixedWidthColumn>,
+        schema: SchemaRef,
+        batch_size: usize,
+        trim: bool,
+        record_length: Option<usize>,
+        skip_header: bool,
+    ) -> Self {
+        let builders = columns.iter().map(|_| StringBuilder::new()).collect();
+        Self {
+            columns,
+            schema,
+            batch_size,
+            trim,
+            record_length,
+            skip_header,
+            builders,
+            num_rows: 0,
+            partial: vec![],
+        }
+    }
+
+    fn push_record(&mut self, record: &[u8]) -> Result<(), ArrowError> {
+        let record = match self.record_length {
+            Some(_) => record,
+            None => {
+                let record = record.strip_suffix(b"\r").unwrap_or(record);
+                if record.is_empty() {
+                    return Ok(());
+                }
+                record
+            }
+        };
+        if self.skip_header {
+            self.skip_header = false;
+            return Ok(());
+        }
+
+        for (column, builder) in self.columns.iter().zip(&mut self.builders) {
+            let mut value = column.value(record);
+            if self.trim {
+                value = value.trim_ascii();
+            }
+            if value.is_empty() {
+                builder.append_null();
+                continue;
+            }
+            let value = std::str::from_utf8(value).map_err(|e| {
+                ArrowError::ParseError(format!(
+                    "Invalid UTF-8 in fixed-width column {}: {e}",
+                    column.name
+                ))
+            })?;
+            builder.append_value(value);
+        }
+        self.num_rows += 1;
+        Ok(())
+    }
+}
+
+impl Decoder for FixedWidthDecoder {
+    fn decode(&mut self, buf: &[u8]) -> Result<usize, ArrowError> {
+        if buf.is_empty() {
+            // The end of the input, whose last record may not be terminated
+            if !self.partial.is_empty() {
+                let record = std::mem::take(&mut self.partial);
+                self.push_record(&record)?;
+            }
+            return Ok(0);
+        }
+
+        let mut consumed = 0;
+        while self.num_rows < self.batch_size && consumed < buf.len() {
+            let rest = &buf[consumed..];
+            // The end of the record in `rest`, and the start of the next one
+            let end = match self.record_length {
+                Some(length) => {
+                    let needed = length - self.partial.len();
+                    (needed <= rest.len()).then_some((needed, needed))
+                }
+                None => rest
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .map(|pos| (pos, pos + 1)),
+            };
+            let Some((end, next)) = end else {
+                self.partial.extend_from_slice(rest);
+                consumed = buf.len();
+                break;
+            };
+
+            if self.partial.is_empty() {
+                self.push_record(&rest[..end])?;
+            } else {
+                let mut record = std::mem::take(&mut self.partial);
+                record.extend_from_slice(&rest[..end]);
+                self.push_record(&record)?;
+                record.clear();
+                self.partial = record;
+            }
+            consumed += next;
+        }
+        Ok(consumed)
+    }
+
+    fn flush(&mut self) -> Result<Option<RecordBatch>, ArrowError> {
+        if self.num_rows == 0 {
+            return Ok(None);
+        }
+        let cast_options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        let columns = self
+            .builders
+            .iter_mut()
+            .zip(self.schema.fields())
+            .map(|(builder, field)| {
+                let array = Arc::new(builder.finish()) as ArrayRef;
+                match field.data_type() {
+                    DataType::Utf8 => Ok(array),
+                    data_type => cast_with_options(&array, data_type, &cast_options),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(self.num_rows));
+        self.num_rows = 0;
+        RecordBatch::try_new_with_options(Arc::clone(&self.schema), columns, &options)
+            .map(Some)
+    }
+
+    fn can_flush_early(&self) -> bool {
+        self.num_rows >= self.batch_size
+    }
+}
+
+impl fmt::Debug for FixedWidthDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedWidthDecoder")
+            .field("columns", &self.columns)
+            .field("record_length", &self.record_length)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::{Field, Int64Type, Schema};
+
+    fn decoder(
+        batch_size: usize,
+        record_length: Option<usize>,
+        skip_header: bool,
+    ) -> FixedWidthDecoder {
+        let columns = vec![
+            FixedWidthColumn {
+                name: "id".to_string(),
+                start: 0,
+                length: 3,
+            },
+            FixedWidthColumn {
+                name: "name".to_string(),
+                start: 3,
+                length: 5,
+            },
+        ];
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        FixedWidthDecoder::new(
+            columns,
+            schema,
+            batch_size,
+            true,
+            record_length,
+            skip_header,
+        )
+    }
+
+    /// Decodes `input` in chunks of `chunk_size` bytes, returning the values
+    /// of the decoded batches
+    fn decode(
+        decoder: &mut FixedWidthDecoder,
+        input: &[u8],
+        chunk_size: usize,
+    ) -> Vec<(Option<i64>, Option<String>)> {
+        let mut batches = vec![];
+        let mut chunks = input.chunks(chunk_size).chain(std::iter::once(&[][..]));
+        let mut chunk = chunks.next();
+        while let Some(buf) = chunk {
+            let consumed = decoder.decode(buf).unwrap();
+            if consumed == 0 || decoder.can_flush_early() {
+                batches.extend(decoder.flush().unwrap());
+            }
+            chunk = if consumed == buf.len() {
+                chunks.next()
+            } else {
+                Some(&buf[consumed..])
+            };
+        }
+        batches
+            .iter()
+            .flat_map(|batch| {
+                let ids = batch.column(0).as_primitive::<Int64Type>();
+                let names = batch.column(1).as_string::<i32>();
+                (0..batch.num_rows()).map(|i| {
+                    (
+                        ids.is_valid(i).then(|| ids.value(i)),
+                        names.is_valid(i).then(|| names.value(i).to_string()),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_lines() {
+        let input = b"ID NAME \n  1alice\r\n\n 22 bob\n333";
+        let expected = vec![
+            (Some(1), Some("alice".to_string())),
+            (Some(22), Some("bob".to_string())),
+            (Some(333), None),
+        ];
+        for chunk_size in 1..=input.len() {
+            for batch_size in 1..=3 {
+                let mut decoder = decoder(batch_size, None, true);
+                assert_eq!(decode(&mut decoder, input, chunk_size), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_fixed_length_records() {
+        let input = b"  1alice 22 bob   ";
+        let expected = vec![
+            (Some(1), Some("alice".to_string())),
+            (Some(22), Some("bob".to_string())),
+        ];
+        for chunk_size in 1..=input.len() {
+            let mut decoder = decoder(8192, Some(9), false);
+            assert_eq!(decode(&mut decoder, input, chunk_size), expected);
+        }
+    }
+
+    #[test]
+    fn decode_invalid_value() {
+        let mut decoder = decoder(8192, None, false);
+        decoder.decode(b"abc\n").unwrap();
+        decoder.decode(b"").unwrap();
+        assert!(decoder.flush().is_err());
+    }
+}
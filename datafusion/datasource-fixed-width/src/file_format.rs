@@ -0,0 +1,234 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`FixedWidthFormat`], fixed-width text [`FileFormat`] abstractions
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use crate::columns::{columns_for_schema, columns_schema, configured_columns};
+use crate::source::FixedWidthSource;
+
+use arrow::datatypes::SchemaRef;
+use datafusion_common::config::{ConfigField, ConfigFileType, FixedWidthOptions};
+use datafusion_common::{DEFAULT_FWF_EXTENSION, GetExt, Result, Statistics};
+use datafusion_datasource::TableSchema;
+use datafusion_datasource::file::FileSource;
+use datafusion_datasource::file_compression_type::FileCompressionType;
+use datafusion_datasource::file_format::{FileFormat, FileFormatFactory};
+use datafusion_datasource::file_scan_config::{FileScanConfig, FileScanConfigBuilder};
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_physical_plan::ExecutionPlan;
+use datafusion_session::Session;
+
+use async_trait::async_trait;
+use object_store::{ObjectMeta, ObjectStore};
+
+#[derive(Default)]
+/// Factory used to create [`FixedWidthFormat`]
+pub struct FixedWidthFormatFactory {
+    /// the options for fixed-width file read
+    pub options: Option<FixedWidthOptions>,
+}
+
+impl FixedWidthFormatFactory {
+    /// Creates an instance of [`FixedWidthFormatFactory`]
+    pub fn new() -> Self {
+        Self { options: None }
+    }
+
+    /// Creates an instance of [`FixedWidthFormatFactory`] with customized default options
+    pub fn new_with_options(options: FixedWidthOptions) -> Self {
+        Self {
+            options: Some(options),
+        }
+    }
+}
+
+impl Debug for FixedWidthFormatFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedWidthFormatFactory")
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl FileFormatFactory for FixedWidthFormatFactory {
+    fn create(
+        &self,
+        state: &dyn Session,
+        format_options: &HashMap<String, String>,
+    ) -> Result<Arc<dyn FileFormat>> {
+        let options = match &self.options {
+            None => {
+                let mut table_options = state.default_table_options();
+                table_options.set_config_format(ConfigFileType::FWF);
+                table_options.alter_with_string_hash_map(format_options)?;
+                table_options.fixed_width
+            }
+            Some(options) => {
+                let mut options = options.clone();
+                for (k, v) in format_options {
+                    options.set(k, v)?;
+                }
+                options
+            }
+        };
+
+        Ok(Arc::new(FixedWidthFormat::default().with_options(options)))
+    }
+
+    fn default(&self) -> Arc<dyn FileFormat> {
+        Arc::new(FixedWidthFormat::default())
+    }
+}
+
+impl GetExt for FixedWidthFormatFactory {
+    fn get_ext(&self) -> String {
+        // Removes the dot, i.e. ".fwf" -> "fwf"
+        DEFAULT_FWF_EXTENSION[1..].to_string()
+    }
+}
+
+/// Fixed-width text [`FileFormat`] implementation.
+///
+/// Each column is found at the same byte offset of every record, as configured
+/// by the `columns` option, e.g. `id:0:6,name:6:20`:
+///
+/// ```text
+///      1alice
+///     22bob
+/// ```
+///
+/// The inferred schema has a nullable string field for each column. When the
+/// table has a schema, values are cast to its types. Files may be split
+/// across partitions, see [`FixedWidthSource`].
+#[derive(Debug, Default)]
+pub struct FixedWidthFormat {
+    options: FixedWidthOptions,
+}
+
+impl FixedWidthFormat {
+    /// Set the fixed-width options
+    pub fn with_options(mut self, options: FixedWidthOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Retrieve the fixed-width options
+    pub fn options(&self) -> &FixedWidthOptions {
+        &self.options
+    }
+
+    /// Set the columns of each record, as comma separated
+    /// `name:start:length` specs
+    pub fn with_columns(mut self, columns: impl Into<String>) -> Self {
+        self.options.columns = Some(columns.into());
+        self
+    }
+
+    /// Set true to indicate that the first record is a header.
+    /// - default to false
+    pub fn with_has_header(mut self, has_header: bool) -> Self {
+        self.options.has_header = has_header;
+        self
+    }
+
+    /// Set true to trim whitespace around values.
+    /// - default to true
+    pub fn with_trim(mut self, trim: bool) -> Self {
+        self.options.trim = trim;
+        self
+    }
+
+    /// Set the length in bytes of each record, for files whose records are
+    /// not terminated.
+    /// - default to None (records are terminated by `\n` or `\r\n`)
+    pub fn with_record_length(mut self, record_length: Option<usize>) -> Self {
+        self.options.record_length = record_length;
+        self
+    }
+
+    /// Set a `FileCompressionType` of fixed-width files
+    /// - defaults to `FileCompressionType::UNCOMPRESSED`
+    pub fn with_file_compression_type(
+        mut self,
+        file_compression_type: FileCompressionType,
+    ) -> Self {
+        self.options.compression = file_compression_type.into();
+        self
+    }
+}
+
+#[async_trait]
+impl FileFormat for FixedWidthFormat {
+    fn get_ext(&self) -> String {
+        FixedWidthFormatFactory::new().get_ext()
+    }
+
+    fn get_ext_with_compression(
+        &self,
+        file_compression_type: &FileCompressionType,
+    ) -> Result<String> {
+        let ext = self.get_ext();
+        Ok(format!("{}{}", ext, file_compression_type.get_ext()))
+    }
+
+    fn compression_type(&self) -> Option<FileCompressionType> {
+        Some(self.options.compression.into())
+    }
+
+    async fn infer_schema(
+        &self,
+        _state: &dyn Session,
+        _store: &Arc<dyn ObjectStore>,
+        _objects: &[ObjectMeta],
+    ) -> Result<SchemaRef> {
+        let columns = configured_columns(&self.options)?;
+        Ok(Arc::new(columns_schema(&columns)))
+    }
+
+    async fn infer_stats(
+        &self,
+        _state: &dyn Session,
+        _store: &Arc<dyn ObjectStore>,
+        table_schema: SchemaRef,
+        _object: &ObjectMeta,
+    ) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&table_schema))
+    }
+
+    async fn create_physical_plan(
+        &self,
+        _state: &dyn Session,
+        conf: FileScanConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        // Check that every field has a column spec when planning, rather than
+        // when the files are opened
+        columns_for_schema(&self.options, conf.file_schema())?;
+
+        let conf = FileScanConfigBuilder::from(conf)
+            .with_file_compression_type(self.options.compression.into())
+            .build();
+        Ok(DataSourceExec::from_data_source(conf))
+    }
+
+    fn file_source(&self, table_schema: TableSchema) -> Arc<dyn FileSource> {
+        Arc::new(FixedWidthSource::new(table_schema).with_options(self.options.clone()))
+    }
+}
@@ -0,0 +1,294 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan for reading fixed-width text files
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::columns::{FixedWidthColumn, columns_for_schema};
+use crate::decoder::FixedWidthDecoder;
+
+use arrow::datatypes::SchemaRef;
+use datafusion_common::config::FixedWidthOptions;
+use datafusion_common::{DataFusionError, Result, config_err};
+use datafusion_datasource::decoder::{DecoderDeserializer, deserialize_stream};
+use datafusion_datasource::file::FileSource;
+use datafusion_datasource::file_compression_type::FileCompressionType;
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::file_stream::{FileOpenFuture, FileOpener};
+use datafusion_datasource::projection::{ProjectionOpener, SplitProjection};
+use datafusion_datasource::{
+    FileRange, PartitionedFile, RangeCalculation, TableSchema, as_file_source,
+    calculate_range,
+};
+use datafusion_physical_plan::DisplayFormatType;
+use datafusion_physical_plan::metrics::ExecutionPlanMetricsSet;
+use datafusion_physical_plan::projection::ProjectionExprs;
+
+use futures::{StreamExt, TryStreamExt};
+use object_store::{GetOptions, ObjectStore};
+
+/// A [`FileSource`] for fixed-width text files, see
+/// [`FixedWidthFormat`](crate::FixedWidthFormat).
+///
+/// Files are split across partitions by byte range: the range of each
+/// partition is aligned on record boundaries, by finding the next line end,
+/// or by rounding up to a multiple of `record_length`.
+#[derive(Debug, Clone)]
+pub struct FixedWidthSource {
+    options: FixedWidthOptions,
+    batch_size: Option<usize>,
+    table_schema: TableSchema,
+    projection: SplitProjection,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl FixedWidthSource {
+    /// Returns a [`FixedWidthSource`]
+    pub fn new(table_schema: impl Into<TableSchema>) -> Self {
+        let table_schema = table_schema.into();
+        Self {
+            options: FixedWidthOptions::default(),
+            projection: SplitProjection::unprojected(&table_schema),
+            table_schema,
+            batch_size: None,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// Sets the fixed-width options
+    pub fn with_options(mut self, options: FixedWidthOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Retrieve the fixed-width options
+    pub fn options(&self) -> &FixedWidthOptions {
+        &self.options
+    }
+}
+
+impl From<FixedWidthSource> for Arc<dyn FileSource> {
+    fn from(source: FixedWidthSource) -> Self {
+        as_file_source(source)
+    }
+}
+
+impl FileSource for FixedWidthSource {
+    fn create_file_opener(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        base_config: &FileScanConfig,
+        _partition: usize,
+    ) -> Result<Arc<dyn FileOpener>> {
+        if self.options.record_length == Some(0) {
+            return config_err!("Fixed-width record length must be greater than 0");
+        }
+        let file_schema = self.table_schema.file_schema();
+        let file_indices = &self.projection.file_indices;
+        let columns = columns_for_schema(&self.options, file_schema)?;
+
+        let opener = Arc::new(FixedWidthOpener {
+            options: self.options.clone(),
+            columns: file_indices.iter().map(|i| columns[*i].clone()).collect(),
+            schema: Arc::new(file_schema.project(file_indices)?),
+            batch_size: self
+                .batch_size
+                .expect("Batch size must be set before creating opener"),
+            file_compression_type: base_config.file_compression_type,
+            object_store,
+        }) as Arc<dyn FileOpener>;
+        ProjectionOpener::try_new(self.projection.clone(), opener, file_schema)
+    }
+
+    fn table_schema(&self) -> &TableSchema {
+        &self.table_schema
+    }
+
+    fn with_batch_size(&self, batch_size: usize) -> Arc<dyn FileSource> {
+        let mut conf = self.clone();
+        conf.batch_size = Some(batch_size);
+        Arc::new(conf)
+    }
+
+    fn try_pushdown_projection(
+        &self,
+        projection: &ProjectionExprs,
+    ) -> Result<Option<Arc<dyn FileSource>>> {
+        let mut source = self.clone();
+        let new_projection = self.projection.source.try_merge(projection)?;
+        source.projection =
+            SplitProjection::new(self.table_schema.file_schema(), &new_projection);
+        Ok(Some(Arc::new(source)))
+    }
+
+    fn projection(&self) -> Option<&ProjectionExprs> {
+        Some(&self.projection.source)
+    }
+
+    fn metrics(&self) -> &ExecutionPlanMetricsSet {
+        &self.metrics
+    }
+
+    fn file_type(&self) -> &str {
+        "fixed_width"
+    }
+
+    fn fmt_extra(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                if let Some(record_length) = self.options.record_length {
+                    write!(f, ", record_length={record_length}")?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => Ok(()),
+        }
+    }
+}
+
+/// A [`FileOpener`] that opens a fixed-width file and yields a
+/// [`FileOpenFuture`]
+pub struct FixedWidthOpener {
+    options: FixedWidthOptions,
+    /// The columns to read, one for each field of `schema`
+    columns: Vec<FixedWidthColumn>,
+    schema: SchemaRef,
+    batch_size: usize,
+    file_compression_type: FileCompressionType,
+    object_store: Arc<dyn ObjectStore>,
+}
+
+/// Returns the byte range of the records of `file` that start within its
+/// range, for records of `record_length` bytes
+fn record_range(file: &PartitionedFile, record_length: usize) -> RangeCalculation {
+    let Some(FileRange { start, end }) = &file.range else {
+        return RangeCalculation::Range(None);
+    };
+    let record_length = record_length as u64;
+    let align =
+        |offset: i64| (offset.max(0) as u64).div_ceil(record_length) * record_length;
+    let (start, end) = (align(*start), align(*end).min(file.object_meta.size));
+    if start >= end {
+        RangeCalculation::TerminateEarly
+    } else {
+        RangeCalculation::Range(Some(start..end))
+    }
+}
+
+impl FileOpener for FixedWidthOpener {
+    /// Open a partitioned fixed-width file.
+    ///
+    /// If `file_meta.range` is `Some(FileRange {start, end})`, the partition
+    /// reads the records that start within the byte range [start, end), like
+    /// [`CsvOpener`] does for lines.
+    ///
+    /// [`CsvOpener`]: https://docs.rs/datafusion/latest/datafusion/datasource/physical_plan/struct.CsvOpener.html
+    fn open(&self, partitioned_file: PartitionedFile) -> Result<FileOpenFuture> {
+        if partitioned_file.range.is_some() && self.file_compression_type.is_compressed()
+        {
+            return config_err!(
+                "Reading compressed fixed-width files in parallel is not supported"
+            );
+        }
+
+        let store = Arc::clone(&self.object_store);
+        let options = self.options.clone();
+        let columns = self.columns.clone();
+        let schema = Arc::clone(&self.schema);
+        let batch_size = self.batch_size;
+        let file_compression_type = self.file_compression_type;
+
+        Ok(Box::pin(async move {
+            let calculated_range = match options.record_length {
+                Some(record_length) => record_range(&partitioned_file, record_length),
+                None => calculate_range(&partitioned_file, &store, None).await?,
+            };
+            let range = match calculated_range {
+                RangeCalculation::Range(range) => range,
+                RangeCalculation::TerminateEarly => {
+                    return Ok(futures::stream::empty().boxed());
+                }
+            };
+
+            // Only the partition at the start of the file reads the header
+            let skip_header =
+                options.has_header && range.as_ref().is_none_or(|r| r.start == 0);
+            let decoder = FixedWidthDecoder::new(
+                columns,
+                schema,
+                batch_size,
+                options.trim,
+                options.record_length,
+                skip_header,
+            );
+
+            let get_options = GetOptions {
+                range: range.map(Into::into),
+                ..Default::default()
+            };
+            let result = store
+                .get_opts(&partitioned_file.object_meta.location, get_options)
+                .await?;
+            let input = result.into_stream().map_err(DataFusionError::from);
+            let input = file_compression_type.convert_stream(input.boxed())?.fuse();
+
+            let stream = deserialize_stream(input, DecoderDeserializer::new(decoder));
+            Ok(stream.map_err(Into::into).boxed())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(size: u64, range: Option<(i64, i64)>) -> PartitionedFile {
+        match range {
+            Some((start, end)) => {
+                PartitionedFile::new_with_range("file.fwf".to_string(), size, start, end)
+            }
+            None => PartitionedFile::new("file.fwf", size),
+        }
+    }
+
+    fn range(calculation: RangeCalculation) -> Option<Option<(u64, u64)>> {
+        match calculation {
+            RangeCalculation::Range(range) => Some(range.map(|r| (r.start, r.end))),
+            RangeCalculation::TerminateEarly => None,
+        }
+    }
+
+    #[test]
+    fn record_ranges() {
+        assert_eq!(range(record_range(&file(100, None), 10)), Some(None));
+        assert_eq!(
+            range(record_range(&file(100, Some((0, 35))), 10)),
+            Some(Some((0, 40)))
+        );
+        assert_eq!(
+            range(record_range(&file(100, Some((35, 70))), 10)),
+            Some(Some((40, 70)))
+        );
+        assert_eq!(
+            range(record_range(&file(95, Some((70, 95))), 10)),
+            Some(Some((70, 95)))
+        );
+        assert_eq!(range(record_range(&file(100, Some((41, 49))), 10)), None);
+    }
+}
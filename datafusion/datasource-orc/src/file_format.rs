@@ -0,0 +1,380 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Apache ORC [`FileFormat`] abstractions
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::reader::ObjectStoreReader;
+use crate::source::OrcSource;
+
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::parsers::CompressionTypeVariant;
+use datafusion_common::stats::Precision;
+use datafusion_common::{
+    DEFAULT_ORC_EXTENSION, DataFusionError, GetExt, Result, Statistics, internal_err,
+    not_impl_err,
+};
+use datafusion_common_runtime::{JoinSet, SpawnedTask};
+use datafusion_datasource::TableSchema;
+use datafusion_datasource::display::FileGroupDisplay;
+use datafusion_datasource::file::FileSource;
+use datafusion_datasource::file_compression_type::FileCompressionType;
+use datafusion_datasource::file_format::{FileFormat, FileFormatFactory};
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::file_sink_config::{FileSink, FileSinkConfig};
+use datafusion_datasource::sink::{DataSink, DataSinkExec};
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_datasource::write::demux::DemuxedStreamReceiver;
+use datafusion_datasource::write::{
+    ObjectWriterBuilder, SharedBuffer, get_writer_schema,
+};
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_expr::dml::InsertOp;
+use datafusion_physical_expr_common::sort_expr::LexRequirement;
+use datafusion_physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan};
+use datafusion_session::Session;
+
+use async_trait::async_trait;
+use object_store::{ObjectMeta, ObjectStore};
+use orc_rust::{ArrowReaderBuilder, ArrowWriterBuilder};
+use tokio::io::AsyncWriteExt;
+
+/// Initial writing buffer size. Note this is just a size hint for efficiency. It
+/// will grow beyond the set value if needed.
+const INITIAL_BUFFER_BYTES: usize = 1048576;
+
+/// If the buffered ORC data exceeds this size, it is flushed to object store
+const BUFFER_FLUSH_BYTES: usize = 1024000;
+
+/// Factory struct used to create [`OrcFormat`]
+#[derive(Default, Debug)]
+pub struct OrcFormatFactory;
+
+impl OrcFormatFactory {
+    /// Creates an instance of [`OrcFormatFactory`]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl FileFormatFactory for OrcFormatFactory {
+    fn create(
+        &self,
+        _state: &dyn Session,
+        _format_options: &HashMap<String, String>,
+    ) -> Result<Arc<dyn FileFormat>> {
+        Ok(Arc::new(OrcFormat))
+    }
+
+    fn default(&self) -> Arc<dyn FileFormat> {
+        Arc::new(OrcFormat)
+    }
+}
+
+impl GetExt for OrcFormatFactory {
+    fn get_ext(&self) -> String {
+        // Removes the dot, i.e. ".orc" -> "orc"
+        DEFAULT_ORC_EXTENSION[1..].to_string()
+    }
+}
+
+/// ORC [`FileFormat`] implementation.
+///
+/// Files are read stripe by stripe: a file may be split across partitions at
+/// stripe boundaries, and filters are used to skip the stripes whose
+/// statistics show they cannot match.
+#[derive(Default, Debug)]
+pub struct OrcFormat;
+
+/// Converts an error of the ORC reader or writer
+fn orc_err(e: orc_rust::error::OrcError) -> DataFusionError {
+    DataFusionError::External(Box::new(e))
+}
+
+#[async_trait]
+impl FileFormat for OrcFormat {
+    fn get_ext(&self) -> String {
+        OrcFormatFactory::new().get_ext()
+    }
+
+    fn get_ext_with_compression(
+        &self,
+        file_compression_type: &FileCompressionType,
+    ) -> Result<String> {
+        let ext = self.get_ext();
+        match file_compression_type.get_variant() {
+            CompressionTypeVariant::UNCOMPRESSED => Ok(ext),
+            _ => internal_err!("ORC FileFormat does not support compression."),
+        }
+    }
+
+    fn compression_type(&self) -> Option<FileCompressionType> {
+        None
+    }
+
+    async fn infer_schema(
+        &self,
+        _state: &dyn Session,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> Result<SchemaRef> {
+        let mut schemas = vec![];
+        for object in objects {
+            let reader = ObjectStoreReader::new(Arc::clone(store), object.clone());
+            let builder = ArrowReaderBuilder::try_new_async(reader)
+                .await
+                .map_err(orc_err)?;
+            schemas.push(builder.schema().as_ref().clone());
+        }
+        let merged_schema = Schema::try_merge(schemas)?;
+        Ok(Arc::new(merged_schema))
+    }
+
+    async fn infer_stats(
+        &self,
+        _state: &dyn Session,
+        store: &Arc<dyn ObjectStore>,
+        table_schema: SchemaRef,
+        object: &ObjectMeta,
+    ) -> Result<Statistics> {
+        let reader = ObjectStoreReader::new(Arc::clone(store), object.clone());
+        let builder = ArrowReaderBuilder::try_new_async(reader)
+            .await
+            .map_err(orc_err)?;
+        let num_rows = builder.file_metadata().number_of_rows() as usize;
+        Ok(Statistics::new_unknown(&table_schema)
+            .with_num_rows(Precision::Exact(num_rows))
+            .with_total_byte_size(Precision::Inexact(object.size as usize)))
+    }
+
+    async fn create_physical_plan(
+        &self,
+        _state: &dyn Session,
+        conf: FileScanConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(DataSourceExec::from_data_source(conf))
+    }
+
+    async fn create_writer_physical_plan(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        _state: &dyn Session,
+        conf: FileSinkConfig,
+        order_requirements: Option<LexRequirement>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if conf.insert_op != InsertOp::Append {
+            return not_impl_err!("Overwrites are not implemented yet for ORC format");
+        }
+
+        let sink = Arc::new(OrcSink::new(conf));
+
+        Ok(Arc::new(DataSinkExec::new(input, sink, order_requirements)) as _)
+    }
+
+    fn file_source(&self, table_schema: TableSchema) -> Arc<dyn FileSource> {
+        Arc::new(OrcSource::new(table_schema))
+    }
+}
+
+/// Returns the schema written to ORC files for `schema`.
+///
+/// ORC has no view types, so string and binary views are written as strings
+/// and binaries.
+fn orc_schema(schema: &Schema) -> Schema {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let data_type = match field.data_type() {
+                DataType::Utf8View => DataType::Utf8,
+                DataType::BinaryView => DataType::Binary,
+                data_type => data_type.clone(),
+            };
+            field.as_ref().clone().with_data_type(data_type)
+        })
+        .collect::<Vec<_>>();
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
+/// Casts the columns of `batch` to the types of the ORC `schema`
+fn cast_to_orc_schema(batch: &RecordBatch, schema: &SchemaRef) -> Result<RecordBatch> {
+    if batch.schema_ref().fields() == schema.fields() {
+        return Ok(batch.clone());
+    }
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(column, field)| cast(column, field.data_type()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(RecordBatch::try_new(Arc::clone(schema), columns)?)
+}
+
+/// Implements [`FileSink`] for writing to ORC files
+pub struct OrcSink {
+    /// Config options for writing data
+    config: FileSinkConfig,
+}
+
+impl OrcSink {
+    /// Create from config.
+    pub fn new(config: FileSinkConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl fmt::Debug for OrcSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrcSink").finish()
+    }
+}
+
+impl DisplayAs for OrcSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "OrcSink(file_groups=",)?;
+                FileGroupDisplay(&self.config.file_group).fmt_as(t, f)?;
+                write!(f, ")")
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "format: orc")?;
+                write!(f, "file={}", &self.config.original_url)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl FileSink for OrcSink {
+    fn config(&self) -> &FileSinkConfig {
+        &self.config
+    }
+
+    async fn spawn_writer_tasks_and_join(
+        &self,
+        context: &Arc<TaskContext>,
+        demux_task: SpawnedTask<Result<()>>,
+        mut file_stream_rx: DemuxedStreamReceiver,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<u64> {
+        let mut file_write_tasks: JoinSet<std::result::Result<usize, DataFusionError>> =
+            JoinSet::new();
+
+        let schema = Arc::new(orc_schema(&get_writer_schema(&self.config)));
+        while let Some((path, mut rx)) = file_stream_rx.recv().await {
+            let mut object_store_writer = ObjectWriterBuilder::new(
+                FileCompressionType::UNCOMPRESSED,
+                &path,
+                Arc::clone(&object_store),
+            )
+            .with_buffer_size(Some(
+                context
+                    .session_config()
+                    .options()
+                    .execution
+                    .objectstore_writer_buffer_size,
+            ))
+            .build()?;
+            let schema = Arc::clone(&schema);
+            // The ORC writer is not `Send`, so the batches are encoded on a
+            // blocking thread which sends the encoded bytes to be written
+            let (bytes_tx, mut bytes_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(1);
+            let encode_task = SpawnedTask::spawn_blocking(move || {
+                let shared_buffer = SharedBuffer::new(INITIAL_BUFFER_BYTES);
+                let mut orc_writer =
+                    ArrowWriterBuilder::new(shared_buffer.clone(), Arc::clone(&schema))
+                        .try_build()
+                        .map_err(orc_err)?;
+                let mut row_count = 0;
+                while let Some(batch) = rx.blocking_recv() {
+                    row_count += batch.num_rows();
+                    orc_writer
+                        .write(&cast_to_orc_schema(&batch, &schema)?)
+                        .map_err(orc_err)?;
+                    let mut buff_to_flush = shared_buffer.buffer.try_lock().unwrap();
+                    if buff_to_flush.len() > BUFFER_FLUSH_BYTES
+                        && bytes_tx
+                            .blocking_send(std::mem::take(&mut *buff_to_flush))
+                            .is_err()
+                    {
+                        // The write task failed and reports the error
+                        return Ok(row_count);
+                    }
+                }
+                orc_writer.close().map_err(orc_err)?;
+                let final_buff =
+                    std::mem::take(&mut *shared_buffer.buffer.try_lock().unwrap());
+                // An error means the write task failed and reports the error
+                let _ = bytes_tx.blocking_send(final_buff);
+                Ok::<_, DataFusionError>(row_count)
+            });
+            file_write_tasks.spawn(async move {
+                while let Some(bytes) = bytes_rx.recv().await {
+                    object_store_writer.write_all(bytes.as_slice()).await?;
+                }
+                let row_count = encode_task
+                    .join_unwind()
+                    .await
+                    .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))??;
+                object_store_writer.shutdown().await?;
+                Ok(row_count)
+            });
+        }
+
+        let mut row_count = 0;
+        while let Some(result) = file_write_tasks.join_next().await {
+            match result {
+                Ok(r) => {
+                    row_count += r?;
+                }
+                Err(e) => {
+                    if e.is_panic() {
+                        std::panic::resume_unwind(e.into_panic());
+                    } else {
+                        unreachable!();
+                    }
+                }
+            }
+        }
+
+        demux_task
+            .join_unwind()
+            .await
+            .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))??;
+        Ok(row_count as u64)
+    }
+}
+
+#[async_trait]
+impl DataSink for OrcSink {
+    fn schema(&self) -> &SchemaRef {
+        self.config.output_schema()
+    }
+
+    async fn write_all(
+        &self,
+        data: SendableRecordBatchStream,
+        context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        FileSink::write_all(self, data, context).await
+    }
+}
@@ -0,0 +1,450 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan for reading ORC files
+
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::error::Result;
+use datafusion_datasource::TableSchema;
+use datafusion_datasource::file::FileSource;
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::file_stream::FileOpener;
+use datafusion_datasource::projection::{ProjectionOpener, SplitProjection};
+use datafusion_physical_expr::conjunction;
+use datafusion_physical_expr_common::physical_expr::{PhysicalExpr, fmt_sql};
+use datafusion_physical_plan::DisplayFormatType;
+use datafusion_physical_plan::filter_pushdown::{FilterPushdownPropagation, PushedDown};
+use datafusion_physical_plan::metrics::ExecutionPlanMetricsSet;
+use datafusion_physical_plan::projection::ProjectionExprs;
+
+use object_store::ObjectStore;
+
+/// OrcSource holds the extra configuration that is necessary for opening ORC files
+///
+/// Filters pushed down into the scan are used to skip the stripes whose
+/// statistics show they cannot match, but are still evaluated by the plan
+/// above the scan.
+#[derive(Clone)]
+pub struct OrcSource {
+    table_schema: TableSchema,
+    batch_size: Option<usize>,
+    projection: SplitProjection,
+    /// Predicate used to prune stripes
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl OrcSource {
+    /// Initialize an OrcSource with the provided schema
+    pub fn new(table_schema: impl Into<TableSchema>) -> Self {
+        let table_schema = table_schema.into();
+        Self {
+            projection: SplitProjection::unprojected(&table_schema),
+            table_schema,
+            batch_size: None,
+            predicate: None,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// Set the predicate used to prune stripes
+    pub fn with_predicate(&self, predicate: Arc<dyn PhysicalExpr>) -> Self {
+        let mut conf = self.clone();
+        conf.predicate = Some(predicate);
+        conf
+    }
+
+    fn projected_file_schema(&self) -> SchemaRef {
+        let file_schema = self.table_schema.file_schema();
+        if self.projection.file_indices.is_empty() {
+            return Arc::clone(file_schema);
+        }
+
+        Arc::new(Schema::new(
+            self.projection
+                .file_indices
+                .iter()
+                .map(|idx| file_schema.field(*idx).clone())
+                .collect::<Vec<_>>(),
+        ))
+    }
+}
+
+impl FileSource for OrcSource {
+    fn create_file_opener(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        _base_config: &FileScanConfig,
+        partition: usize,
+    ) -> Result<Arc<dyn FileOpener>> {
+        let mut opener = Arc::new(private::OrcOpener {
+            config: Arc::new(self.clone()),
+            object_store,
+            partition,
+        }) as Arc<dyn FileOpener>;
+        opener = ProjectionOpener::try_new(
+            self.projection.clone(),
+            Arc::clone(&opener),
+            self.table_schema.file_schema(),
+        )?;
+        Ok(opener)
+    }
+
+    fn table_schema(&self) -> &TableSchema {
+        &self.table_schema
+    }
+
+    fn with_batch_size(&self, batch_size: usize) -> Arc<dyn FileSource> {
+        let mut conf = self.clone();
+        conf.batch_size = Some(batch_size);
+        Arc::new(conf)
+    }
+
+    fn filter(&self) -> Option<Arc<dyn PhysicalExpr>> {
+        self.predicate.clone()
+    }
+
+    fn try_pushdown_projection(
+        &self,
+        projection: &ProjectionExprs,
+    ) -> Result<Option<Arc<dyn FileSource>>> {
+        let mut source = self.clone();
+        let new_projection = self.projection.source.try_merge(projection)?;
+        let split_projection =
+            SplitProjection::new(self.table_schema.file_schema(), &new_projection);
+        source.projection = split_projection;
+        Ok(Some(Arc::new(source)))
+    }
+
+    fn projection(&self) -> Option<&ProjectionExprs> {
+        Some(&self.projection.source)
+    }
+
+    fn metrics(&self) -> &ExecutionPlanMetricsSet {
+        &self.metrics
+    }
+
+    fn file_type(&self) -> &str {
+        "orc"
+    }
+
+    fn fmt_extra(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                if let Some(predicate) = &self.predicate {
+                    write!(f, ", predicate={predicate}")?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                if let Some(predicate) = &self.predicate {
+                    writeln!(f, "predicate={}", fmt_sql(predicate.as_ref()))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn try_pushdown_filters(
+        &self,
+        filters: Vec<Arc<dyn PhysicalExpr>>,
+        _config: &ConfigOptions,
+    ) -> Result<FilterPushdownPropagation<Arc<dyn FileSource>>> {
+        if filters.is_empty() {
+            return Ok(FilterPushdownPropagation::with_parent_pushdown_result(
+                vec![],
+            ));
+        }
+        // The filters are only used to prune stripes, so the parent still has
+        // to evaluate all of them
+        let parent_pushdown_result = vec![PushedDown::No; filters.len()];
+        let predicate = match &self.predicate {
+            Some(predicate) => {
+                conjunction(std::iter::once(Arc::clone(predicate)).chain(filters))
+            }
+            None => conjunction(filters),
+        };
+        let source = Arc::new(self.with_predicate(predicate)) as Arc<dyn FileSource>;
+        Ok(
+            FilterPushdownPropagation::with_parent_pushdown_result(
+                parent_pushdown_result,
+            )
+            .with_updated_node(source),
+        )
+    }
+}
+
+mod private {
+    use super::*;
+    use std::collections::HashMap;
+    use std::ops::Range;
+
+    use datafusion_common::{DataFusionError, ScalarValue};
+    use datafusion_datasource::{PartitionedFile, file_stream::FileOpenFuture};
+    use datafusion_physical_expr::simplifier::PhysicalExprSimplifier;
+    use datafusion_physical_expr_adapter::{
+        BatchAdapterFactory, DefaultPhysicalExprAdapterFactory,
+        PhysicalExprAdapterFactory, replace_columns_with_literals,
+    };
+    use datafusion_physical_plan::metrics::{
+        Count, Label, MetricBuilder, MetricType, PruningMetrics,
+    };
+    use datafusion_pruning::build_pruning_predicate;
+    use futures::{StreamExt, TryStreamExt};
+    use log::debug;
+    use orc_rust::ArrowReaderBuilder;
+    use orc_rust::projection::ProjectionMask;
+    use orc_rust::reader::metadata::FileMetadata;
+
+    use crate::reader::ObjectStoreReader;
+    use crate::statistics::StripePruningStatistics;
+
+    pub struct OrcOpener {
+        pub config: Arc<OrcSource>,
+        pub object_store: Arc<dyn ObjectStore>,
+        pub partition: usize,
+    }
+
+    /// Metrics of the stripes of a file pruned by the predicate
+    struct OrcFileMetrics {
+        /// Number of stripes pruned and matched by statistics
+        stripes_pruned_statistics: PruningMetrics,
+        /// Number of times the pruning predicate could not be created
+        predicate_creation_errors: Count,
+        /// Number of times the pruning predicate could not be evaluated
+        predicate_evaluation_errors: Count,
+    }
+
+    impl OrcFileMetrics {
+        fn new(
+            partition: usize,
+            filename: &str,
+            metrics: &ExecutionPlanMetricsSet,
+        ) -> Self {
+            let filename_label = Label::new("filename", Arc::<str>::from(filename));
+            let builder = MetricBuilder::new(metrics).with_label(filename_label);
+            let stripes_pruned_statistics = builder
+                .clone()
+                .with_type(MetricType::Summary)
+                .pruning_metrics("stripes_pruned_statistics", partition);
+            let predicate_creation_errors = builder
+                .clone()
+                .with_type(MetricType::Dev)
+                .counter("num_predicate_creation_errors", partition);
+            let predicate_evaluation_errors = builder
+                .with_type(MetricType::Dev)
+                .counter("predicate_evaluation_errors", partition);
+            Self {
+                stripes_pruned_statistics,
+                predicate_creation_errors,
+                predicate_evaluation_errors,
+            }
+        }
+    }
+
+    fn orc_err(e: impl std::error::Error + Send + Sync + 'static) -> DataFusionError {
+        DataFusionError::External(Box::new(e))
+    }
+
+    impl FileOpener for OrcOpener {
+        fn open(&self, partitioned_file: PartitionedFile) -> Result<FileOpenFuture> {
+            let config = Arc::clone(&self.config);
+            let projected_file_schema = config.projected_file_schema();
+            let batch_size = config.batch_size.expect("Batch size must set before open");
+            let metrics = OrcFileMetrics::new(
+                self.partition,
+                partitioned_file.object_meta.location.as_ref(),
+                &config.metrics,
+            );
+            // Partition columns are constant for the file
+            let literal_columns: HashMap<String, ScalarValue> = config
+                .table_schema
+                .table_partition_cols()
+                .iter()
+                .zip(partitioned_file.partition_values.iter())
+                .map(|(field, value)| (field.name().clone(), value.clone()))
+                .collect();
+            let predicate = config
+                .predicate
+                .clone()
+                .map(|p| replace_columns_with_literals(p, &literal_columns))
+                .transpose()?;
+            let reader = ObjectStoreReader::new(
+                Arc::clone(&self.object_store),
+                partitioned_file.object_meta.clone(),
+            );
+            let range = partitioned_file.range;
+
+            Ok(Box::pin(async move {
+                let builder = ArrowReaderBuilder::try_new_async(reader.clone())
+                    .await
+                    .map_err(orc_err)?;
+                let file_metadata = builder.file_metadata();
+                let file_schema = builder.schema();
+
+                // Only read the stripes starting in the range of this partition
+                let stripes = file_metadata
+                    .stripe_metadatas()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, stripe)| {
+                        range
+                            .as_ref()
+                            .is_none_or(|range| range.contains(stripe.offset() as i64))
+                    })
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+                let stripes = match predicate {
+                    Some(predicate) => prune_stripes(
+                        predicate,
+                        config.table_schema.file_schema(),
+                        &file_schema,
+                        file_metadata,
+                        stripes,
+                        &metrics,
+                    )?,
+                    None => stripes,
+                };
+                let byte_ranges = stripe_byte_ranges(file_metadata, &stripes);
+
+                // The reader keeps the columns in the order of the file
+                let indices = file_schema
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, field)| {
+                        projected_file_schema.field_with_name(field.name()).is_ok()
+                    })
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+                let reader_schema = Arc::new(file_schema.project(&indices)?);
+                let names = reader_schema
+                    .fields()
+                    .iter()
+                    .map(|field| field.name().as_str())
+                    .collect::<Vec<_>>();
+                let projection =
+                    ProjectionMask::named_roots(file_metadata.root_data_type(), &names);
+                let batch_adapter =
+                    BatchAdapterFactory::new(Arc::clone(&projected_file_schema))
+                        .make_adapter(&reader_schema)?;
+
+                // Read each run of adjacent stripes with a single reader, reusing
+                // the metadata already read for the first one
+                let mut builder = Some(builder);
+                Ok(futures::stream::iter(byte_ranges)
+                    .then(move |byte_range| {
+                        let builder = builder.take();
+                        let reader = reader.clone();
+                        let projection = projection.clone();
+                        async move {
+                            let builder = match builder {
+                                Some(builder) => builder,
+                                None => ArrowReaderBuilder::try_new_async(reader)
+                                    .await
+                                    .map_err(orc_err)?,
+                            };
+                            let stream = builder
+                                .with_file_byte_range(byte_range)
+                                .with_projection(projection)
+                                .with_batch_size(batch_size)
+                                .build_async()
+                                .map_err(orc_err);
+                            Ok::<_, DataFusionError>(stream)
+                        }
+                    })
+                    .try_flatten()
+                    .map(move |r| r.and_then(|batch| batch_adapter.adapt_batch(&batch)))
+                    .boxed())
+            }))
+        }
+    }
+
+    /// Returns the `stripes` that may match `predicate` according to their
+    /// statistics
+    fn prune_stripes(
+        predicate: Arc<dyn PhysicalExpr>,
+        logical_file_schema: &SchemaRef,
+        physical_file_schema: &SchemaRef,
+        file_metadata: &FileMetadata,
+        stripes: Vec<usize>,
+        metrics: &OrcFileMetrics,
+    ) -> Result<Vec<usize>> {
+        let rewriter = DefaultPhysicalExprAdapterFactory.create(
+            Arc::clone(logical_file_schema),
+            Arc::clone(physical_file_schema),
+        )?;
+        let simplifier = PhysicalExprSimplifier::new(physical_file_schema);
+        let predicate = simplifier.simplify(rewriter.rewrite(predicate)?)?;
+        let Some(pruning_predicate) = build_pruning_predicate(
+            predicate,
+            physical_file_schema,
+            &metrics.predicate_creation_errors,
+        ) else {
+            return Ok(stripes);
+        };
+
+        let statistics = StripePruningStatistics::new(file_metadata, &stripes);
+        match pruning_predicate.prune(&statistics) {
+            Ok(keep) => {
+                let kept = stripes
+                    .iter()
+                    .zip(keep)
+                    .filter_map(|(stripe, keep)| keep.then_some(*stripe))
+                    .collect::<Vec<_>>();
+                metrics
+                    .stripes_pruned_statistics
+                    .add_pruned(stripes.len() - kept.len());
+                metrics.stripes_pruned_statistics.add_matched(kept.len());
+                Ok(kept)
+            }
+            Err(e) => {
+                debug!("Error evaluating stripe predicate values {e}");
+                metrics.predicate_evaluation_errors.add(1);
+                Ok(stripes)
+            }
+        }
+    }
+
+    /// The byte ranges of the runs of adjacent `stripes`
+    fn stripe_byte_ranges(
+        file_metadata: &FileMetadata,
+        stripes: &[usize],
+    ) -> Vec<Range<usize>> {
+        let stripe_metadatas = file_metadata.stripe_metadatas();
+        let mut ranges: Vec<(usize, Range<usize>)> = vec![];
+        for &index in stripes {
+            let stripe = &stripe_metadatas[index];
+            let start = stripe.offset() as usize;
+            let end = start
+                + (stripe.index_length() + stripe.data_length() + stripe.footer_length())
+                    as usize;
+            match ranges.last_mut() {
+                Some((last, range)) if *last + 1 == index => {
+                    *last = index;
+                    range.end = end;
+                }
+                _ => ranges.push((index, start..end)),
+            }
+        }
+        ranges.into_iter().map(|(_, range)| range).collect()
+    }
+}
@@ -0,0 +1,187 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`PruningStatistics`] of the stripes of an ORC file
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BooleanArray, Date32Array, Float64Array, Int64Array, StringArray,
+    UInt64Array,
+};
+use datafusion_common::pruning::PruningStatistics;
+use datafusion_common::{Column, ScalarValue};
+use orc_rust::reader::metadata::FileMetadata;
+use orc_rust::statistics::{ColumnStatistics, TypeStatistics};
+use orc_rust::stripe::StripeMetadata;
+
+/// Statistics of the stripes of an ORC file, from its metadata section.
+///
+/// Only the top level columns with integer, floating point, string and date
+/// statistics have minimum and maximum values.
+pub(crate) struct StripePruningStatistics<'a> {
+    file_metadata: &'a FileMetadata,
+    /// The stripes to prune, by index in the file
+    stripes: &'a [usize],
+}
+
+impl<'a> StripePruningStatistics<'a> {
+    pub(crate) fn new(file_metadata: &'a FileMetadata, stripes: &'a [usize]) -> Self {
+        Self {
+            file_metadata,
+            stripes,
+        }
+    }
+
+    fn stripe_metadatas(&self) -> impl Iterator<Item = &'a StripeMetadata> + '_ {
+        let stripes = self.file_metadata.stripe_metadatas();
+        self.stripes.iter().map(move |index| &stripes[*index])
+    }
+
+    /// The statistics of `column` in each stripe, if it is a top level column
+    /// of the file
+    fn column_statistics(
+        &self,
+        column: &Column,
+    ) -> Option<Vec<Option<&'a ColumnStatistics>>> {
+        let column_index = self
+            .file_metadata
+            .root_data_type()
+            .children()
+            .iter()
+            .find(|child| child.name() == column.name)?
+            .data_type()
+            .column_index();
+        Some(
+            self.stripe_metadatas()
+                .map(|stripe| stripe.column_statistics().get(column_index))
+                .collect(),
+        )
+    }
+
+    /// The minimum (or maximum if `max` is set) values of `column` in each
+    /// stripe
+    fn min_max_values(&self, column: &Column, max: bool) -> Option<ArrayRef> {
+        let statistics = self
+            .column_statistics(column)?
+            .into_iter()
+            .map(|statistics| statistics.and_then(ColumnStatistics::type_statistics))
+            .collect::<Vec<_>>();
+        let array: ArrayRef = match statistics.iter().flatten().next()? {
+            TypeStatistics::Integer { .. } => Arc::new(
+                statistics
+                    .iter()
+                    .map(|statistics| match statistics {
+                        Some(TypeStatistics::Integer {
+                            min, max: maximum, ..
+                        }) => Some(if max { *maximum } else { *min }),
+                        _ => None,
+                    })
+                    .collect::<Int64Array>(),
+            ),
+            TypeStatistics::Double { .. } => Arc::new(
+                statistics
+                    .iter()
+                    .map(|statistics| match statistics {
+                        Some(TypeStatistics::Double {
+                            min, max: maximum, ..
+                        }) => Some(if max { *maximum } else { *min }),
+                        _ => None,
+                    })
+                    .collect::<Float64Array>(),
+            ),
+            TypeStatistics::String { .. } => Arc::new(
+                statistics
+                    .iter()
+                    .map(|statistics| match statistics {
+                        Some(TypeStatistics::String {
+                            lower_bound,
+                            upper_bound,
+                            ..
+                        }) => Some(if max {
+                            upper_bound.as_str()
+                        } else {
+                            lower_bound.as_str()
+                        }),
+                        _ => None,
+                    })
+                    .collect::<StringArray>(),
+            ),
+            TypeStatistics::Date { .. } => Arc::new(
+                statistics
+                    .iter()
+                    .map(|statistics| match statistics {
+                        Some(TypeStatistics::Date { min, max: maximum }) => {
+                            Some(if max { *maximum } else { *min })
+                        }
+                        _ => None,
+                    })
+                    .collect::<Date32Array>(),
+            ),
+            _ => return None,
+        };
+        Some(array)
+    }
+}
+
+impl PruningStatistics for StripePruningStatistics<'_> {
+    fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.min_max_values(column, false)
+    }
+
+    fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.min_max_values(column, true)
+    }
+
+    fn num_containers(&self) -> usize {
+        self.stripes.len()
+    }
+
+    fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
+        // ORC statistics count the non null values of a column
+        let counts: UInt64Array = self
+            .column_statistics(column)?
+            .into_iter()
+            .zip(self.stripe_metadatas())
+            .map(|(statistics, stripe)| {
+                statistics.map(|statistics| {
+                    stripe
+                        .number_of_rows()
+                        .saturating_sub(statistics.number_of_values())
+                })
+            })
+            .collect();
+        Some(Arc::new(counts) as ArrayRef)
+    }
+
+    fn row_counts(&self) -> Option<ArrayRef> {
+        let counts: UInt64Array = self
+            .stripe_metadatas()
+            .map(|stripe| Some(stripe.number_of_rows()))
+            .collect();
+        Some(Arc::new(counts) as ArrayRef)
+    }
+
+    fn contained(
+        &self,
+        _column: &Column,
+        _values: &HashSet<ScalarValue>,
+    ) -> Option<BooleanArray> {
+        None
+    }
+}
@@ -0,0 +1,308 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ParquetPageIndexStatisticsProvider`] building histograms of the columns
+//! of Parquet files from their page indexes.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::metadata::CachedParquetMetaData;
+use crate::source::ParquetSource;
+
+use arrow::array::Array;
+use arrow::compute::cast;
+use arrow::datatypes::{Field, Schema};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_execution::cache::cache_manager::FileMetadataCache;
+use datafusion_physical_plan::ExecutionPlan;
+use datafusion_physical_plan::operator_statistics::{
+    ColumnHistogram, ColumnHistograms, ExtendedStatistics, HistogramBucket,
+    HistogramOptions, StatisticsProvider, StatisticsResult,
+};
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::parquet_to_arrow_schema;
+use parquet::file::metadata::ParquetMetaData;
+
+/// A [`StatisticsProvider`] attaching [`ColumnHistograms`] built from the
+/// page indexes of Parquet files to scans of them.
+///
+/// Each data page is bounded by the minimum and maximum values of the page,
+/// and adjacent pages are merged into equi-depth buckets of about the same
+/// number of rows, so the histograms are most precise for sorted or
+/// clustered columns. Page indexes are read from the file metadata cache,
+/// which holds them once a file has been scanned: scans of files that are not
+/// all cached are delegated to the next provider.
+///
+/// It is part of the default `StatisticsRegistry` of a session.
+#[derive(Debug)]
+pub struct ParquetPageIndexStatisticsProvider {
+    metadata_cache: Arc<dyn FileMetadataCache>,
+    options: HistogramOptions,
+}
+
+impl ParquetPageIndexStatisticsProvider {
+    /// Create a new provider reading page indexes from `metadata_cache`,
+    /// usually the one of the `RuntimeEnv`
+    pub fn new(metadata_cache: Arc<dyn FileMetadataCache>) -> Self {
+        Self {
+            metadata_cache,
+            options: HistogramOptions::default(),
+        }
+    }
+
+    /// Set the options limiting the number of buckets of the histograms
+    pub fn with_options(mut self, options: HistogramOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl StatisticsProvider for ParquetPageIndexStatisticsProvider {
+    fn compute_statistics(
+        &self,
+        plan: &dyn ExecutionPlan,
+        _child_stats: &[ExtendedStatistics],
+    ) -> Result<StatisticsResult> {
+        let Some((config, _)) = plan
+            .downcast_ref::<DataSourceExec>()
+            .and_then(|scan| scan.downcast_to_file_source::<ParquetSource>())
+        else {
+            return Ok(StatisticsResult::Delegate);
+        };
+
+        // Files split in ranges appear several times
+        let mut seen = HashSet::new();
+        let mut files = vec![];
+        for file in config.file_groups.iter().flat_map(|group| group.iter()) {
+            if !seen.insert(&file.object_meta.location) {
+                continue;
+            }
+            let Some(metadata) = self
+                .metadata_cache
+                .get(&file.object_meta.location)
+                .filter(|cached| cached.is_valid_for(&file.object_meta))
+                .and_then(|cached| {
+                    let metadata = cached
+                        .file_metadata
+                        .as_any()
+                        .downcast_ref::<CachedParquetMetaData>()?;
+                    Some(Arc::clone(metadata.parquet_metadata()))
+                })
+            else {
+                return Ok(StatisticsResult::Delegate);
+            };
+            let file_metadata = metadata.file_metadata();
+            let arrow_schema = parquet_to_arrow_schema(
+                file_metadata.schema_descr(),
+                file_metadata.key_value_metadata(),
+            )?;
+            files.push((metadata, arrow_schema));
+        }
+
+        let base = plan.partition_statistics(None)?;
+        let schema = config.projected_schema()?;
+        let columns = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let distinct_count = base
+                    .column_statistics
+                    .get(index)
+                    .and_then(|c| c.distinct_count.get_value().copied());
+                let mut merged: Option<ColumnHistogram> = None;
+                for (metadata, arrow_schema) in &files {
+                    let histogram = page_index_histogram(
+                        metadata,
+                        arrow_schema,
+                        field,
+                        distinct_count,
+                    )?;
+                    merged = Some(match merged {
+                        Some(merged) => merged.merge(&histogram),
+                        None => histogram,
+                    });
+                }
+                merged.map(|h| Arc::new(h.compact(self.options.num_buckets)))
+            })
+            .collect();
+        let histograms = ColumnHistograms::new(columns);
+        if !histograms.has_histograms() {
+            return Ok(StatisticsResult::Delegate);
+        }
+        Ok(StatisticsResult::Computed(
+            histograms.into_statistics(&base),
+        ))
+    }
+}
+
+/// Builds the histogram of the column `field` of a Parquet file from its page
+/// index, with a bucket per data page.
+///
+/// `arrow_schema` is the schema of the file and `distinct_count` the number
+/// of distinct values of the column, if known.
+fn page_index_histogram(
+    metadata: &ParquetMetaData,
+    arrow_schema: &Schema,
+    field: &Field,
+    distinct_count: Option<usize>,
+) -> Option<ColumnHistogram> {
+    let column_index = metadata.column_index()?;
+    let offset_index = metadata.offset_index()?;
+    let converter = StatisticsConverter::try_new(
+        field.name(),
+        arrow_schema,
+        metadata.file_metadata().schema_descr(),
+    )
+    .ok()?;
+
+    let row_groups = (0..metadata.num_row_groups()).collect::<Vec<_>>();
+    let mins = converter
+        .data_page_mins(column_index, offset_index, &row_groups)
+        .ok()?;
+    let maxes = converter
+        .data_page_maxes(column_index, offset_index, &row_groups)
+        .ok()?;
+    let null_counts = converter
+        .data_page_null_counts(column_index, offset_index, &row_groups)
+        .ok()?;
+    let row_counts = converter
+        .data_page_row_counts(offset_index, metadata.row_groups(), &row_groups)
+        .ok()??;
+    // The file may store the column with another type than the table
+    let mins = cast(&mins, field.data_type()).ok()?;
+    let maxes = cast(&maxes, field.data_type()).ok()?;
+
+    let mut buckets = vec![];
+    let mut null_count = 0.0;
+    for page in 0..row_counts.len() {
+        if row_counts.is_null(page) || null_counts.is_null(page) {
+            return None;
+        }
+        let nulls = null_counts.value(page) as f64;
+        let count = row_counts.value(page) as f64 - nulls;
+        null_count += nulls;
+        if count <= 0.0 {
+            continue;
+        }
+        let lower = ScalarValue::try_from_array(&mins, page).ok()?;
+        let upper = ScalarValue::try_from_array(&maxes, page).ok()?;
+        if lower.is_null() || upper.is_null() {
+            return None;
+        }
+
+        // A page has at most one distinct value per row, per integer in its
+        // range and per distinct value of the column
+        let mut distinct = count;
+        if lower.data_type().is_integer()
+            && let Some(distance) = lower.distance(&upper)
+        {
+            distinct = distinct.min(distance as f64 + 1.0);
+        }
+        if let Some(distinct_count) = distinct_count {
+            distinct = distinct.min(distinct_count as f64);
+        }
+        buckets.push(HistogramBucket {
+            lower,
+            upper,
+            count,
+            distinct_count: distinct.max(1.0),
+        });
+    }
+    Some(ColumnHistogram::new(buckets, vec![], null_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::DataType;
+    use bytes::Bytes;
+    use parquet::arrow::ArrowWriter;
+    use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+    use parquet::file::metadata::PageIndexPolicy;
+    use parquet::file::properties::{EnabledStatistics, WriterProperties};
+
+    /// Writes the values `0..100` to pages of 25 rows, every tenth value
+    /// being null, and reads back the metadata with the page index
+    fn metadata() -> Result<(Arc<ParquetMetaData>, Arc<Schema>)> {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int32, true)]));
+        let values = Int32Array::from_iter((0..100).map(|i| (i % 10 != 0).then_some(i)));
+        let batch = RecordBatch::try_new(Arc::clone(&schema), vec![Arc::new(values)])?;
+        let properties = WriterProperties::builder()
+            .set_statistics_enabled(EnabledStatistics::Page)
+            .set_data_page_row_count_limit(25)
+            .set_write_batch_size(25)
+            .build();
+        let mut buffer = vec![];
+        let mut writer =
+            ArrowWriter::try_new(&mut buffer, Arc::clone(&schema), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let options =
+            ArrowReaderOptions::new().with_page_index_policy(PageIndexPolicy::Required);
+        let reader_metadata = ArrowReaderMetadata::load(&Bytes::from(buffer), options)?;
+        Ok((
+            Arc::clone(reader_metadata.metadata()),
+            Arc::clone(reader_metadata.schema()),
+        ))
+    }
+
+    #[test]
+    fn test_page_index_histogram() -> Result<()> {
+        let (metadata, schema) = metadata()?;
+        let field = Field::new("v", DataType::Int32, true);
+        let bucket = |lower: i32, upper: i32, count: f64| HistogramBucket {
+            lower: ScalarValue::Int32(Some(lower)),
+            upper: ScalarValue::Int32(Some(upper)),
+            count,
+            distinct_count: count,
+        };
+
+        // A bucket per page, without the nulls
+        let histogram = page_index_histogram(&metadata, &schema, &field, None).unwrap();
+        assert_eq!(
+            histogram.buckets(),
+            &[
+                bucket(1, 24, 22.0),
+                bucket(25, 49, 23.0),
+                bucket(51, 74, 22.0),
+                bucket(75, 99, 23.0)
+            ]
+        );
+        assert_eq!(histogram.null_count(), 10.0);
+
+        // The number of distinct values of a page is at most that of the column
+        let histogram =
+            page_index_histogram(&metadata, &schema, &field, Some(5)).unwrap();
+        assert!(histogram.buckets().iter().all(|b| b.distinct_count == 5.0));
+
+        // The values are cast to the type of the table
+        let field = Field::new("v", DataType::Int64, true);
+        let histogram = page_index_histogram(&metadata, &schema, &field, None).unwrap();
+        assert_eq!(histogram.buckets()[0].lower, ScalarValue::Int64(Some(1)));
+
+        // Columns that are not in the file have no histogram
+        let field = Field::new("w", DataType::Int32, true);
+        assert!(page_index_histogram(&metadata, &schema, &field, None).is_none());
+        Ok(())
+    }
+}
@@ -28,6 +28,7 @@ pub mod access_plan;
 mod bloom_filter;
 mod decoder_projection;
 pub mod file_format;
+mod histogram;
 pub mod metadata;
 mod metrics;
 mod opener;
@@ -48,6 +49,7 @@ mod writer;
 
 pub use access_plan::{ParquetAccessPlan, RowGroupAccess};
 pub use file_format::*;
+pub use histogram::ParquetPageIndexStatisticsProvider;
 pub use metrics::ParquetFileMetrics;
 pub use page_filter::PagePruningAccessPlanFilter;
 pub use reader::*; // Expose so downstream crates can use it
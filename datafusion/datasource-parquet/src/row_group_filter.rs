@@ -31,6 +31,7 @@ use datafusion_expr::Operator;
 use datafusion_physical_expr::expressions::{BinaryExpr, IsNullExpr, NotExpr};
 use datafusion_physical_expr::utils::collect_columns;
 use datafusion_physical_expr::{PhysicalExpr, PhysicalExprSimplifier};
+use datafusion_physical_plan::sample::sample_block;
 use datafusion_pruning::PruningPredicate;
 use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
 use parquet::file::metadata::RowGroupMetaData;
@@ -245,6 +246,20 @@ impl RowGroupAccessPlanFilter {
             }
         }
     }
+    /// Prune remaining row groups that are not part of a random sample of
+    /// `fraction` of the row groups.
+    ///
+    /// Whether a row group is sampled only depends on `seed`, `file_name` and
+    /// its index, so the same row groups are read every time the file is
+    /// scanned with the same seed.
+    pub fn prune_by_sample(&mut self, fraction: f64, seed: u64, file_name: &str) {
+        for idx in self.access_plan.row_group_indexes() {
+            if !sample_block(fraction, seed, (file_name, idx)) {
+                self.access_plan.skip(idx);
+            }
+        }
+    }
+
     /// Prune remaining row groups using min/max/null_count statistics and
     /// the [`PruningPredicate`] to determine if the predicate can not be true.
     ///
@@ -597,6 +612,31 @@ mod tests {
         assert_eq!(filter.remaining_row_group_count(), 2);
     }
 
+    #[test]
+    fn prune_by_sample() {
+        let sample = |fraction, seed, file_name| {
+            let mut filter =
+                RowGroupAccessPlanFilter::new(ParquetAccessPlan::new_all(100));
+            filter.access_plan.skip(0);
+            filter.prune_by_sample(fraction, seed, file_name);
+            filter.access_plan.row_group_indexes()
+        };
+
+        let sampled = sample(0.3, 1, "file.parquet");
+        assert!(
+            sampled.len() > 15 && sampled.len() < 45,
+            "{}",
+            sampled.len()
+        );
+        assert!(!sampled.contains(&0));
+        assert_eq!(sampled, sample(0.3, 1, "file.parquet"));
+        assert_ne!(sampled, sample(0.3, 2, "file.parquet"));
+        assert_ne!(sampled, sample(0.3, 1, "other.parquet"));
+
+        assert_eq!(sample(1.0, 1, "file.parquet").len(), 99);
+        assert!(sample(0.0, 1, "file.parquet").is_empty());
+    }
+
     #[test]
     fn row_group_pruning_predicate_simple_expr() {
         use datafusion_expr::{col, lit};
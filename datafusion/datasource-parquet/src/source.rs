@@ -298,6 +298,9 @@ pub struct ParquetSource {
     /// Sort order driving `PreparedAccessPlan::reorder_by_statistics`
     /// in the opener.
     sort_order_for_reorder: Option<LexOrdering>,
+    /// Optional `(fraction, seed)` to only read a random sample of the row
+    /// groups (`TABLESAMPLE SYSTEM`)
+    sample: Option<(f64, u64)>,
 }
 
 impl ParquetSource {
@@ -324,6 +327,7 @@ impl ParquetSource {
             encryption_factory: None,
             reverse_row_groups: false,
             sort_order_for_reorder: None,
+            sample: None,
         }
     }
 
@@ -634,6 +638,7 @@ impl FileSource for ParquetSource {
             max_predicate_cache_size: self.max_predicate_cache_size(),
             reverse_row_groups: self.reverse_row_groups,
             sort_order_for_reorder: self.sort_order_for_reorder.clone(),
+            sample: self.sample,
             virtual_state,
         }))
     }
@@ -664,6 +669,16 @@ impl FileSource for ParquetSource {
         Arc::new(conf)
     }
 
+    fn try_pushdown_sample(
+        &self,
+        fraction: f64,
+        seed: u64,
+    ) -> datafusion_common::Result<Option<Arc<dyn FileSource>>> {
+        let mut source = self.clone();
+        source.sample = Some((fraction, seed));
+        Ok(Some(Arc::new(source)))
+    }
+
     fn try_pushdown_projection(
         &self,
         projection: &ProjectionExprs,
@@ -703,6 +718,9 @@ impl FileSource for ParquetSource {
                 if self.reverse_row_groups {
                     write!(f, ", reverse_row_groups=true")?;
                 }
+                if let Some((fraction, _)) = self.sample {
+                    write!(f, ", sample_fraction={fraction}")?;
+                }
 
                 // Try to build the pruning predicates.
                 // These are only generated here because it's useful to have *some*
@@ -292,6 +292,23 @@ pub trait FileSource: Any + Send + Sync {
         files
     }
 
+    /// Try to create a new FileSource that only reads a random sample of about
+    /// `fraction` of the data in each file, for example by skipping row
+    /// groups.
+    ///
+    /// The same data must be read each time a file is scanned with the same
+    /// `seed`. If this returns `None` (the default), [`FileScanConfig`]
+    /// samples whole files instead.
+    ///
+    /// [`FileScanConfig`]: crate::file_scan_config::FileScanConfig
+    fn try_pushdown_sample(
+        &self,
+        _fraction: f64,
+        _seed: u64,
+    ) -> Result<Option<Arc<dyn FileSource>>> {
+        Ok(None)
+    }
+
     /// Try to push down a projection into this FileSource.
     ///
     /// `FileSource` implementations that support projection pushdown should
@@ -49,6 +49,7 @@ use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
 use datafusion_physical_plan::SortOrderPushdownResult;
 use datafusion_physical_plan::coop::cooperative;
 use datafusion_physical_plan::execution_plan::SchedulingType;
+use datafusion_physical_plan::sample::sample_block;
 use datafusion_physical_plan::{
     DisplayAs, DisplayFormatType,
     display::{ProjectSchemaDisplay, display_orderings},
@@ -206,6 +207,11 @@ pub struct FileScanConfig {
     /// If the number of file partitions > target_partitions, the file partitions will be grouped
     /// in a round-robin fashion such that number of file partitions = target_partitions.
     pub partitioned_by_file_group: bool,
+    /// When non-empty and `partitioned_by_file_group` is true, file_groups are
+    /// the hash buckets of a bucketed table on these columns, and
+    /// output_partitioning will return Bucket partitioning on them instead of
+    /// the Hash partitioning on the partition columns.
+    pub bucket_columns: Vec<String>,
 }
 
 /// A builder for [`FileScanConfig`]'s.
@@ -278,6 +284,7 @@ pub struct FileScanConfigBuilder {
     batch_size: Option<usize>,
     expr_adapter_factory: Option<Arc<dyn PhysicalExprAdapterFactory>>,
     partitioned_by_file_group: bool,
+    bucket_columns: Vec<String>,
 }
 
 impl FileScanConfigBuilder {
@@ -304,6 +311,7 @@ impl FileScanConfigBuilder {
             batch_size: None,
             expr_adapter_factory: None,
             partitioned_by_file_group: false,
+            bucket_columns: vec![],
         }
     }
 
@@ -504,6 +512,17 @@ impl FileScanConfigBuilder {
         self
     }
 
+    /// Set the columns the file groups are hash bucketed on.
+    ///
+    /// When set together with [`Self::with_partitioned_by_file_group`], each
+    /// file group must contain the files of one bucket, in bucket order, and
+    /// the output partitioning will be declared as Bucket partitioning on these
+    /// columns.
+    pub fn with_bucket_columns(mut self, bucket_columns: Vec<String>) -> Self {
+        self.bucket_columns = bucket_columns;
+        self
+    }
+
     /// Build the final [`FileScanConfig`] with all the configured settings.
     ///
     /// This method takes ownership of the builder and returns the constructed `FileScanConfig`.
@@ -525,6 +544,7 @@ impl FileScanConfigBuilder {
             batch_size,
             expr_adapter_factory: expr_adapter,
             partitioned_by_file_group,
+            bucket_columns,
         } = self;
 
         let constraints = constraints.unwrap_or_default();
@@ -550,6 +570,7 @@ impl FileScanConfigBuilder {
             expr_adapter_factory: expr_adapter,
             statistics,
             partitioned_by_file_group,
+            bucket_columns,
         }
     }
 }
@@ -569,6 +590,7 @@ impl From<FileScanConfig> for FileScanConfigBuilder {
             batch_size: config.batch_size,
             expr_adapter_factory: config.expr_adapter_factory,
             partitioned_by_file_group: config.partitioned_by_file_group,
+            bucket_columns: config.bucket_columns,
         }
     }
 }
@@ -704,7 +726,8 @@ impl DataSource for FileScanConfig {
     ///
     /// When `partitioned_by_file_group` is true, this returns `Partitioning::Hash` on
     /// the Hive partition columns, allowing the optimizer to skip hash repartitioning
-    /// for aggregates and joins on those columns.
+    /// for aggregates and joins on those columns. If `bucket_columns` is set, this
+    /// returns `Partitioning::Bucket` on the bucket columns instead.
     ///
     /// Tradeoffs
     /// - Benefit: Eliminates `RepartitionExec` and `SortExec` for queries with
@@ -718,7 +741,14 @@ impl DataSource for FileScanConfig {
     ///   preserving I/O parallelism while maintaining partition semantics.
     fn output_partitioning(&self) -> Partitioning {
         if self.partitioned_by_file_group {
-            let partition_cols = self.table_partition_cols();
+            let partition_cols: Vec<&str> = if self.bucket_columns.is_empty() {
+                self.table_partition_cols()
+                    .iter()
+                    .map(|f| f.name().as_str())
+                    .collect()
+            } else {
+                self.bucket_columns.iter().map(String::as_str).collect()
+            };
             if !partition_cols.is_empty() {
                 let projected_schema = match self.projected_schema() {
                     Ok(schema) => schema,
@@ -733,19 +763,23 @@ impl DataSource for FileScanConfig {
                 // Build Column expressions for partition columns based on their
                 // position in the projected schema
                 let mut exprs: Vec<Arc<dyn PhysicalExpr>> = Vec::new();
-                for partition_col in partition_cols {
+                for &partition_col in &partition_cols {
                     if let Some((idx, _)) = projected_schema
                         .fields()
                         .iter()
                         .enumerate()
-                        .find(|(_, f)| f.name() == partition_col.name())
+                        .find(|(_, f)| f.name() == partition_col)
                     {
-                        exprs.push(Arc::new(Column::new(partition_col.name(), idx)));
+                        exprs.push(Arc::new(Column::new(partition_col, idx)));
                     }
                 }
 
                 if exprs.len() == partition_cols.len() {
-                    return Partitioning::Hash(exprs, self.file_groups.len());
+                    return if self.bucket_columns.is_empty() {
+                        Partitioning::Hash(exprs, self.file_groups.len())
+                    } else {
+                        Partitioning::Bucket(exprs, self.file_groups.len())
+                    };
                 }
             }
         }
@@ -915,6 +949,64 @@ impl DataSource for FileScanConfig {
         }
     }
 
+    /// Only read a sample of the data, either by pushing the sample into the
+    /// [`FileSource`] (e.g. Parquet skips row groups), or otherwise by
+    /// skipping whole files.
+    fn try_pushdown_sample(
+        &self,
+        fraction: f64,
+        seed: u64,
+    ) -> Result<Option<Arc<dyn DataSource>>> {
+        let mut new_config = self.clone();
+        match self.file_source.try_pushdown_sample(fraction, seed)? {
+            Some(new_source) => {
+                new_config.file_source = new_source;
+            }
+            None => {
+                for file_group in &mut new_config.file_groups {
+                    let files = file_group
+                        .iter()
+                        .filter(|file| {
+                            let range = file.range.as_ref().map(|range| range.start);
+                            sample_block(
+                                fraction,
+                                seed,
+                                (file.object_meta.location.as_ref(), range),
+                            )
+                        })
+                        .cloned()
+                        .collect();
+                    let mut sampled_group = FileGroup::new(files);
+                    if let Some(statistics) = file_group.file_statistics(None) {
+                        sampled_group =
+                            sampled_group.with_statistics(Arc::new(statistics.clone()));
+                    }
+                    *file_group = sampled_group;
+                }
+            }
+        }
+
+        // The statistics are now only an estimate of the sampled data
+        let sample_statistics = |statistics: Statistics| {
+            let num_rows = statistics.num_rows.with_estimated_selectivity(fraction);
+            let total_byte_size = statistics
+                .total_byte_size
+                .with_estimated_selectivity(fraction);
+            Statistics {
+                num_rows,
+                total_byte_size,
+                ..statistics.to_inexact()
+            }
+        };
+        new_config.statistics = sample_statistics(new_config.statistics);
+        for file_group in &mut new_config.file_groups {
+            if let Some(statistics) = file_group.statistics_mut() {
+                *statistics = sample_statistics(statistics.clone());
+            }
+        }
+        Ok(Some(Arc::new(new_config)))
+    }
+
     /// Push sort requirements into file-based data sources.
     ///
     /// # Sort Pushdown Architecture
@@ -2539,6 +2631,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_output_partitioning_with_bucket_columns() {
+        let file_schema = aggr_test_schema();
+        let partition_col =
+            Field::new("date", wrap_partition_type_in_dict(DataType::Utf8), false);
+
+        let mut config = config_for_projection(
+            Arc::clone(&file_schema),
+            None,
+            Statistics::new_unknown(&file_schema),
+            vec![partition_col],
+        );
+        config.partitioned_by_file_group = true;
+        config.bucket_columns = vec!["c2".to_string(), "c1".to_string()];
+        config.file_groups = vec![FileGroup::default(); 4];
+
+        // Bucket columns take precedence over the partition columns
+        match config.output_partitioning() {
+            Partitioning::Bucket(exprs, num_partitions) => {
+                assert_eq!(num_partitions, 4);
+                let col_names: Vec<_> = exprs
+                    .iter()
+                    .map(|e| e.downcast_ref::<Column>().unwrap().name())
+                    .collect();
+                assert_eq!(col_names, vec!["c2", "c1"]);
+            }
+            _ => panic!("Expected Bucket partitioning"),
+        }
+
+        // Projecting out a bucket column loses the partitioning
+        let mut config = config_for_projection(
+            Arc::clone(&file_schema),
+            Some(vec![0]),
+            Statistics::new_unknown(&file_schema),
+            vec![],
+        );
+        config.partitioned_by_file_group = true;
+        config.bucket_columns = vec!["c2".to_string()];
+        assert!(matches!(
+            config.output_partitioning(),
+            Partitioning::UnknownPartitioning(_)
+        ));
+    }
+
     #[test]
     fn try_pushdown_sort_reverses_file_groups_only_when_requested_is_reverse()
     -> Result<()> {
@@ -24,6 +24,7 @@ use crate::write::demux::{DemuxedStreamReceiver, start_demuxer_task};
 
 use arrow::datatypes::{DataType, SchemaRef};
 use datafusion_common::Result;
+use datafusion_common::file_options::bucketing::BucketSpec;
 use datafusion_common_runtime::SpawnedTask;
 use datafusion_execution::object_store::ObjectStoreUrl;
 use datafusion_execution::{SendableRecordBatchStream, TaskContext};
@@ -160,6 +161,8 @@ pub struct FileSinkConfig {
     pub file_extension: String,
     /// Determines how the output path is interpreted.
     pub file_output_mode: FileOutputMode,
+    /// Hash bucketing of the written files, if the table is bucketed
+    pub bucket_spec: Option<BucketSpec>,
 }
 
 impl FileSinkConfig {
@@ -0,0 +1,309 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SampledHistogramStatisticsProvider`] attaching histograms built by
+//! sampling files to scans of them.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::PartitionedFile;
+use crate::file_groups::FileGroup;
+use crate::file_scan_config::{FileScanConfig, FileScanConfigBuilder};
+use crate::source::DataSourceExec;
+
+use datafusion_common::{Result, Statistics, not_impl_err};
+use datafusion_execution::TaskContext;
+use datafusion_physical_plan::ExecutionPlan;
+use datafusion_physical_plan::operator_statistics::{
+    ColumnHistogram, ColumnHistograms, ExtendedStatistics, HistogramOptions,
+    StatisticsProvider, StatisticsResult, sample_histograms,
+};
+use object_store::path::Path;
+use parking_lot::RwLock;
+
+/// A [`StatisticsProvider`] attaching [`ColumnHistograms`] to scans of files
+/// previously sampled with [`Self::sample`].
+///
+/// Histograms are kept per file and column name, and merged into equi-depth
+/// buckets for scans of several files. Scans of files that were not all
+/// sampled are delegated to the next provider.
+///
+/// It is part of the default [`StatisticsRegistry`] of a session, from which
+/// it is found with [`StatisticsRegistry::provider`] to sample files.
+///
+/// [`StatisticsRegistry`]: datafusion_physical_plan::operator_statistics::StatisticsRegistry
+/// [`StatisticsRegistry::provider`]: datafusion_physical_plan::operator_statistics::StatisticsRegistry::provider
+#[derive(Debug, Default)]
+pub struct SampledHistogramStatisticsProvider {
+    options: HistogramOptions,
+    /// Histogram of each sampled column of each file
+    histograms: RwLock<HashMap<Path, HashMap<String, Arc<ColumnHistogram>>>>,
+}
+
+impl SampledHistogramStatisticsProvider {
+    /// Create a new provider building histograms with `options`
+    pub fn new(options: HistogramOptions) -> Self {
+        Self {
+            options,
+            histograms: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Sample each file scanned by `scan` and build the histograms of the
+    /// columns it outputs, replacing those of previous samples.
+    ///
+    /// Files are sampled by their [`FileSource`] if it supports it, e.g. by
+    /// skipping row groups, and otherwise by reading their first rows.
+    ///
+    /// [`FileSource`]: crate::file::FileSource
+    pub async fn sample(
+        &self,
+        scan: &DataSourceExec,
+        context: Arc<TaskContext>,
+    ) -> Result<()> {
+        let Some(config) = scan.data_source().downcast_ref::<FileScanConfig>() else {
+            return not_impl_err!("Only scans of files can be sampled");
+        };
+        let schema = config.projected_schema()?;
+        for file in unique_files(config) {
+            let plan = self.sample_plan(config, file)?;
+            let histograms =
+                sample_histograms(plan, Arc::clone(&context), &self.options).await?;
+
+            let mut sampled = self.histograms.write();
+            let columns = sampled
+                .entry(file.object_meta.location.clone())
+                .or_default();
+            for (field, histogram) in schema.fields().iter().zip(histograms.columns()) {
+                match histogram {
+                    Some(histogram) => {
+                        columns.insert(field.name().clone(), Arc::clone(histogram));
+                    }
+                    None => {
+                        columns.remove(field.name());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the histograms of `file`
+    pub fn remove(&self, file: &Path) {
+        self.histograms.write().remove(file);
+    }
+
+    /// Returns a plan scanning a sample of `file` with `config`
+    fn sample_plan(
+        &self,
+        config: &FileScanConfig,
+        file: &PartitionedFile,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let table_schema = config.file_source().table_schema().table_schema();
+        let mut statistics = Statistics::new_unknown(table_schema);
+        if let Some(file_statistics) = &file.statistics {
+            statistics.num_rows = file_statistics.num_rows;
+        }
+
+        let mut source = Arc::clone(config.file_source());
+        if let Some(&num_rows) = statistics.num_rows.get_value()
+            && num_rows > self.options.sample_size
+        {
+            let fraction = self.options.sample_size as f64 / num_rows as f64;
+            if let Some(sampled) = source.try_pushdown_sample(fraction, 0)? {
+                source = sampled;
+            }
+        }
+
+        let mut file = file.clone();
+        file.range = None;
+        let config = FileScanConfigBuilder::from(config.clone())
+            .with_source(source)
+            .with_file_groups(vec![FileGroup::new(vec![file])])
+            .with_limit(None)
+            .with_statistics(statistics)
+            .build();
+        Ok(DataSourceExec::from_data_source(config))
+    }
+}
+
+/// The files scanned by `config`, once each even if they are split in ranges
+fn unique_files(config: &FileScanConfig) -> Vec<&PartitionedFile> {
+    let mut seen = HashSet::new();
+    config
+        .file_groups
+        .iter()
+        .flat_map(|group| group.iter())
+        .filter(|file| seen.insert(&file.object_meta.location))
+        .collect()
+}
+
+impl StatisticsProvider for SampledHistogramStatisticsProvider {
+    fn compute_statistics(
+        &self,
+        plan: &dyn ExecutionPlan,
+        _child_stats: &[ExtendedStatistics],
+    ) -> Result<StatisticsResult> {
+        let Some(config) = plan
+            .downcast_ref::<DataSourceExec>()
+            .and_then(|scan| scan.data_source().downcast_ref::<FileScanConfig>())
+        else {
+            return Ok(StatisticsResult::Delegate);
+        };
+
+        let files = unique_files(config);
+        let schema = config.projected_schema()?;
+        let histograms = {
+            let sampled = self.histograms.read();
+            let columns = schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    let mut merged: Option<ColumnHistogram> = None;
+                    for file in &files {
+                        let histogram =
+                            sampled.get(&file.object_meta.location)?.get(field.name())?;
+                        merged = Some(match merged {
+                            Some(merged) => merged.merge(histogram),
+                            None => histogram.as_ref().clone(),
+                        });
+                    }
+                    merged.map(|h| Arc::new(h.compact(self.options.num_buckets)))
+                })
+                .collect();
+            ColumnHistograms::new(columns)
+        };
+        if !histograms.has_histograms() {
+            return Ok(StatisticsResult::Delegate);
+        }
+
+        let base = plan.partition_statistics(None)?;
+        Ok(StatisticsResult::Computed(
+            histograms.into_statistics(&base),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_stream::{FileOpenFuture, FileOpener};
+    use crate::test_util::MockSource;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_common::ScalarValue;
+    use datafusion_execution::object_store::ObjectStoreUrl;
+    use datafusion_physical_plan::operator_statistics::HistogramBucket;
+    use futures::{FutureExt, StreamExt};
+
+    /// Opens the file named `i` as the values `i * 100..(i + 1) * 100`
+    struct RangeOpener {
+        schema: SchemaRef,
+    }
+
+    impl FileOpener for RangeOpener {
+        fn open(&self, file: PartitionedFile) -> Result<FileOpenFuture> {
+            let i = file
+                .object_meta
+                .location
+                .filename()
+                .and_then(|name| name.parse::<i32>().ok())
+                .unwrap();
+            let values = Int32Array::from_iter_values(i * 100..(i + 1) * 100);
+            let batch =
+                RecordBatch::try_new(Arc::clone(&self.schema), vec![Arc::new(values)])?;
+            let stream = futures::stream::once(async move { Ok(batch) }).boxed();
+            Ok(futures::future::ready(Ok(stream)).boxed())
+        }
+    }
+
+    fn scan(files: &[&str]) -> Arc<DataSourceExec> {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int32, false)]));
+        let source = MockSource::new(Arc::clone(&schema)).with_file_opener(Arc::new(
+            RangeOpener {
+                schema: Arc::clone(&schema),
+            },
+        ));
+        let files = files
+            .iter()
+            .map(|name| PartitionedFile::new(*name, 100))
+            .collect();
+        let config = FileScanConfigBuilder::new(
+            ObjectStoreUrl::local_filesystem(),
+            Arc::new(source),
+        )
+        .with_file_groups(vec![FileGroup::new(files)])
+        .build();
+        DataSourceExec::from_data_source(config)
+    }
+
+    fn histogram(
+        provider: &SampledHistogramStatisticsProvider,
+        scan: &DataSourceExec,
+    ) -> Result<Option<Arc<ColumnHistogram>>> {
+        Ok(match provider.compute_statistics(scan, &[])? {
+            StatisticsResult::Computed(statistics) => statistics
+                .get_extension::<ColumnHistograms>()
+                .and_then(|histograms| histograms.column(0).cloned()),
+            StatisticsResult::Delegate => None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sampled_histograms() -> Result<()> {
+        let provider = SampledHistogramStatisticsProvider::new(HistogramOptions {
+            num_buckets: 4,
+            num_most_common_values: 0,
+            ..Default::default()
+        });
+        let scan_01 = scan(&["0", "1"]);
+        assert!(histogram(&provider, &scan_01)?.is_none());
+
+        provider
+            .sample(&scan_01, Arc::new(TaskContext::default()))
+            .await?;
+
+        // The histograms of the files are merged into equi-depth buckets
+        let histogram_01 = histogram(&provider, &scan_01)?.unwrap();
+        let bucket = |lower: i32, upper: i32| HistogramBucket {
+            lower: ScalarValue::Int32(Some(lower)),
+            upper: ScalarValue::Int32(Some(upper)),
+            count: 50.0,
+            distinct_count: 50.0,
+        };
+        assert_eq!(
+            histogram_01.buckets(),
+            &[
+                bucket(0, 49),
+                bucket(50, 99),
+                bucket(100, 149),
+                bucket(150, 199)
+            ]
+        );
+        assert_eq!(histogram_01.null_count(), 0.0);
+
+        // Scans of files that were not all sampled are delegated
+        assert!(histogram(&provider, &scan(&["0", "1", "2"]))?.is_none());
+        assert!(histogram(&provider, &scan(&["1"]))?.is_some());
+        provider.remove(&Path::from("0"));
+        assert!(histogram(&provider, &scan_01)?.is_none());
+        assert!(histogram(&provider, &scan(&["1"]))?.is_some());
+        Ok(())
+    }
+}
@@ -219,6 +219,23 @@ pub trait DataSource: Any + Send + Sync + Debug {
         Ok(SortOrderPushdownResult::Unsupported)
     }
 
+    /// Try to create a new DataSource that only reads a random sample of
+    /// about `fraction` of its data, skipping whole blocks of rows such as
+    /// files or row groups (`TABLESAMPLE SYSTEM`).
+    ///
+    /// The same blocks must be read each time the source is scanned with the
+    /// same `seed`.
+    ///
+    /// Default implementation returns `None`, meaning the sample must be
+    /// taken after the data is read.
+    fn try_pushdown_sample(
+        &self,
+        _fraction: f64,
+        _seed: u64,
+    ) -> Result<Option<Arc<dyn DataSource>>> {
+        Ok(None)
+    }
+
     /// Returns a variant of this `DataSource` that is aware of order-sensitivity.
     fn with_preserve_order(&self, _preserve_order: bool) -> Option<Arc<dyn DataSource>> {
         None
@@ -567,6 +584,22 @@ impl DataSourceExec {
         self
     }
 
+    /// Try to only read a random sample of about `fraction` of the data, see
+    /// [`DataSource::try_pushdown_sample`].
+    ///
+    /// If `seed` is `None`, a random seed is used.
+    pub fn try_pushdown_sample(
+        &self,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Option<Self>> {
+        let seed = seed.unwrap_or_else(rand::random);
+        Ok(self
+            .data_source
+            .try_pushdown_sample(fraction, seed)?
+            .map(|data_source| self.clone().with_data_source(data_source)))
+    }
+
     /// Assign constraints
     pub fn with_constraints(mut self, constraints: Constraints) -> Self {
         Arc::make_mut(&mut self.cache).set_constraints(constraints);
@@ -25,6 +25,7 @@ use std::sync::Arc;
 use crate::url::ListingTableUrl;
 use crate::write::FileSinkConfig;
 use datafusion_common::error::Result;
+use datafusion_common::file_options::bucketing::BucketSpec;
 use datafusion_physical_plan::SendableRecordBatchStream;
 
 use arrow::array::{
@@ -49,6 +50,8 @@ use rand::distr::SampleString;
 use tokio::sync::mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender};
 
 type RecordBatchReceiver = Receiver<RecordBatch>;
+/// The hive partition values and, if the output is bucketed, the bucket of a file
+type PartitionKey = (Vec<String>, Option<usize>);
 pub type DemuxedStreamReceiver = UnboundedReceiver<(Path, RecordBatchReceiver)>;
 
 /// Splits a single [SendableRecordBatchStream] into a dynamically determined
@@ -83,6 +86,10 @@ pub type DemuxedStreamReceiver = UnboundedReceiver<(Path, RecordBatchReceiver)>;
 /// unique values of a specific column, see
 /// <https://github.com/apache/datafusion/issues/7744>
 ///
+/// The `bucket_spec` parameter will additionally split the input by the hash
+/// bucket of each row, writing one file per bucket named by
+/// [`BucketSpec::file_name`].
+///
 /// ```text
 ///                                                                              ┌───────────┐               ┌────────────┐    ┌─────────────┐
 ///                                                                     ┌──────▶ │  batch 1  ├────▶...──────▶│   Batch a  │    │ Output File1│
@@ -105,7 +112,7 @@ pub(crate) fn start_demuxer_task(
     let context = Arc::clone(context);
     let file_extension = config.file_extension.clone();
     let base_output_path = config.table_paths[0].clone();
-    let task = if config.table_partition_cols.is_empty() {
+    let task = if config.table_partition_cols.is_empty() && config.bucket_spec.is_none() {
         let single_file_output = config
             .file_output_mode
             .single_file_output(&base_output_path);
@@ -124,6 +131,7 @@ pub(crate) fn start_demuxer_task(
         // There could be an arbitrarily large number of parallel hive style partitions being written to, so we cannot
         // bound this channel without risking a deadlock.
         let partition_by = config.table_partition_cols.clone();
+        let bucket_spec = config.bucket_spec.clone();
         let keep_partition_by_columns = config.keep_partition_by_columns;
         SpawnedTask::spawn(async move {
             hive_style_partitions_demuxer(
@@ -131,6 +139,7 @@ pub(crate) fn start_demuxer_task(
                 data,
                 context,
                 partition_by,
+                bucket_spec,
                 base_output_path,
                 file_extension,
                 keep_partition_by_columns,
@@ -291,13 +300,16 @@ fn create_new_file_stream(
 }
 
 /// Splits an input stream based on the distinct values of a set of columns
+/// and, if the output is bucketed, the hash bucket of each row
 /// Assumes standard hive style partition paths such as
 /// /col1=val1/col2=val2/outputfile.parquet
+#[expect(clippy::too_many_arguments)]
 async fn hive_style_partitions_demuxer(
     tx: UnboundedSender<(Path, Receiver<RecordBatch>)>,
     mut input: SendableRecordBatchStream,
     context: Arc<TaskContext>,
     partition_by: Vec<(String, DataType)>,
+    bucket_spec: Option<BucketSpec>,
     base_output_path: ListingTableUrl,
     file_extension: String,
     keep_partition_by_columns: bool,
@@ -308,14 +320,19 @@ async fn hive_style_partitions_demuxer(
     let max_buffered_recordbatches = exec_options.max_buffered_batches_per_output_file;
 
     // To support non string partition col types, cast the type to &str first
-    let mut value_map: HashMap<Vec<String>, Sender<RecordBatch>> = HashMap::new();
+    let mut value_map: HashMap<PartitionKey, Sender<RecordBatch>> = HashMap::new();
 
     while let Some(rb) = input.next().await.transpose()? {
         // First compute partition key for each row of batch, e.g. (col1=val1, col2=val2, ...)
         let all_partition_values = compute_partition_keys_by_row(&rb, &partition_by)?;
+        let bucket_ids = bucket_spec
+            .as_ref()
+            .map(|spec| compute_bucket_ids_by_row(&rb, spec))
+            .transpose()?;
 
         // Next compute how the batch should be split up to take each distinct key to its own batch
-        let take_map = compute_take_arrays(&rb, &all_partition_values);
+        let take_map =
+            compute_take_arrays(&rb, &all_partition_values, bucket_ids.as_deref());
 
         // Divide up the batch into distinct partition key batches and send each batch
         for (part_key, mut builder) in take_map.into_iter() {
@@ -335,7 +352,8 @@ async fn hive_style_partitions_demuxer(
                     let (part_tx, part_rx) =
                         mpsc::channel::<RecordBatch>(max_buffered_recordbatches);
                     let file_path = compute_hive_style_file_path(
-                        &part_key,
+                        &part_key.0,
+                        bucket_spec.as_ref().zip(part_key.1),
                         &partition_by,
                         &write_id,
                         &file_extension,
@@ -539,17 +557,35 @@ fn compute_partition_keys_by_row<'a>(
     Ok(all_partition_values)
 }
 
+fn compute_bucket_ids_by_row(rb: &RecordBatch, spec: &BucketSpec) -> Result<Vec<usize>> {
+    let schema = rb.schema();
+    let arrays = spec
+        .columns
+        .iter()
+        .map(|col| {
+            rb.column_by_name(col).cloned().ok_or(exec_datafusion_err!(
+                "Bucket column {col} does not exist in source data! Got schema {schema}."
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    spec.bucket_ids(&arrays, rb.num_rows())
+}
+
 fn compute_take_arrays(
     rb: &RecordBatch,
     all_partition_values: &[Vec<Cow<str>>],
-) -> HashMap<Vec<String>, UInt64Builder> {
+    bucket_ids: Option<&[usize]>,
+) -> HashMap<PartitionKey, UInt64Builder> {
     let mut take_map = HashMap::new();
     for i in 0..rb.num_rows() {
         let mut part_key = vec![];
         for vals in all_partition_values.iter() {
             part_key.push(vals[i].clone().into());
         }
-        let builder = take_map.entry(part_key).or_insert_with(UInt64Builder::new);
+        let bucket = bucket_ids.map(|ids| ids[i]);
+        let builder = take_map
+            .entry((part_key, bucket))
+            .or_insert_with(UInt64Builder::new);
         builder.append_value(i as u64);
     }
     take_map
@@ -582,6 +618,7 @@ fn remove_partition_by_columns(
 
 fn compute_hive_style_file_path(
     part_key: &[String],
+    bucket: Option<(&BucketSpec, usize)>,
     partition_by: &[(String, DataType)],
     write_id: &str,
     file_extension: &str,
@@ -592,5 +629,10 @@ fn compute_hive_style_file_path(
         file_path = file_path.join(format!("{}={}", partition_by[j].0, part_key[j]));
     }
 
-    file_path.join(format!("{write_id}.{file_extension}"))
+    match bucket {
+        Some((bucket_spec, bucket)) => {
+            file_path.join(bucket_spec.file_name(write_id, bucket, file_extension))
+        }
+        None => file_path.join(format!("{write_id}.{file_extension}")),
+    }
 }
@@ -608,6 +608,17 @@ impl SessionConfig {
     {
         self.extensions.get_arc::<T>()
     }
+
+    /// Remove extension, if any for the specified type `T` exists, and
+    /// return it.
+    ///
+    /// See [`with_extension`](Self::with_extension) on how to add attach extensions.
+    pub fn remove_extension<T>(&mut self) -> Option<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.extensions.remove::<T>()
+    }
 }
 
 impl From<ConfigOptions> for SessionConfig {
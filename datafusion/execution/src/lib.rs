@@ -37,6 +37,7 @@ pub mod parquet_encryption;
 pub mod runtime_env;
 mod stream;
 mod task;
+pub mod transaction;
 
 pub mod registry {
     pub use datafusion_expr::registry::{
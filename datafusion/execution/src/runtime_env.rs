@@ -31,6 +31,7 @@ use crate::{
 use crate::cache::cache_manager::{CacheManager, CacheManagerConfig};
 #[cfg(feature = "parquet_encryption")]
 use crate::parquet_encryption::{EncryptionFactory, EncryptionFactoryRegistry};
+use crate::transaction::CommitClock;
 use datafusion_common::{Result, config::ConfigEntry};
 use object_store::ObjectStore;
 use std::sync::Arc;
@@ -52,6 +53,7 @@ use url::Url;
 /// * [`DiskManager`]: Manage temporary files on local disk
 /// * [`CacheManager`]: Manage temporary cache data during the session lifetime
 /// * [`ObjectStoreRegistry`]: Manage mapping URLs to object store instances
+/// * [`CommitClock`]: Order the commits of transactions
 ///
 /// # Example: Create default `RuntimeEnv`
 /// ```
@@ -83,6 +85,8 @@ pub struct RuntimeEnv {
     /// Parquet encryption factory registry
     #[cfg(feature = "parquet_encryption")]
     pub parquet_encryption_factory_registry: Arc<EncryptionFactoryRegistry>,
+    /// Commit clock of the transactions of the sessions using this runtime
+    pub commit_clock: Arc<CommitClock>,
 }
 
 impl Debug for RuntimeEnv {
@@ -349,6 +353,8 @@ pub struct RuntimeEnvBuilder {
     /// Parquet encryption factory registry
     #[cfg(feature = "parquet_encryption")]
     pub parquet_encryption_factory_registry: Arc<EncryptionFactoryRegistry>,
+    /// Commit clock of the transactions of the sessions using the runtime
+    pub commit_clock: Arc<CommitClock>,
 }
 
 impl Default for RuntimeEnvBuilder {
@@ -368,6 +374,7 @@ impl RuntimeEnvBuilder {
             object_store_registry: Arc::new(DefaultObjectStoreRegistry::default()),
             #[cfg(feature = "parquet_encryption")]
             parquet_encryption_factory_registry: Default::default(),
+            commit_clock: Default::default(),
         }
     }
 
@@ -397,6 +404,13 @@ impl RuntimeEnvBuilder {
         self
     }
 
+    /// Customize the commit clock, to order the transactions of sessions
+    /// using different runtimes with the same clock
+    pub fn with_commit_clock(mut self, commit_clock: Arc<CommitClock>) -> Self {
+        self.commit_clock = commit_clock;
+        self
+    }
+
     /// Customize object store registry
     pub fn with_object_store_registry(
         mut self,
@@ -468,6 +482,7 @@ impl RuntimeEnvBuilder {
             object_store_registry,
             #[cfg(feature = "parquet_encryption")]
             parquet_encryption_factory_registry,
+            commit_clock,
         } = self;
         let memory_pool =
             memory_pool.unwrap_or_else(|| Arc::new(UnboundedMemoryPool::default()));
@@ -484,6 +499,7 @@ impl RuntimeEnvBuilder {
             object_store_registry,
             #[cfg(feature = "parquet_encryption")]
             parquet_encryption_factory_registry,
+            commit_clock,
         })
     }
 
@@ -523,6 +539,7 @@ impl RuntimeEnvBuilder {
             parquet_encryption_factory_registry: Arc::clone(
                 &runtime_env.parquet_encryption_factory_registry,
             ),
+            commit_clock: Arc::clone(&runtime_env.commit_clock),
         }
     }
 
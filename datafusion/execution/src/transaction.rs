@@ -0,0 +1,133 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CommitClock`] that orders the commits of the transactions of a
+//! [`RuntimeEnv`](crate::runtime_env::RuntimeEnv)
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use futures::lock::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
+use parking_lot::Mutex;
+
+/// A point in time of a [`CommitClock`].
+///
+/// Each commit, of a transaction or of a statement that runs outside of a
+/// transaction, gets a new timestamp. A transaction sees the changes of the
+/// commits with a timestamp lower than or equal to its snapshot.
+pub type Timestamp = u64;
+
+/// The commit clock shared by the transactions of the sessions of a
+/// [`RuntimeEnv`](crate::runtime_env::RuntimeEnv).
+///
+/// Tracks the timestamp of the latest commit and the snapshots of the active
+/// transactions, so that table providers know which versions of their data
+/// may still be read.
+#[derive(Debug, Default)]
+pub struct CommitClock {
+    state: Mutex<ClockState>,
+    /// Serializes the commits of transactions, so that the participants of
+    /// different transactions are never prepared concurrently
+    commits: AsyncMutex<()>,
+}
+
+#[derive(Debug, Default)]
+struct ClockState {
+    /// The timestamp of the latest commit
+    now: Timestamp,
+    /// The id of the latest transaction
+    last_id: u64,
+    /// The number of active transactions for each snapshot timestamp
+    active: BTreeMap<Timestamp, usize>,
+}
+
+impl CommitClock {
+    /// Registers a new transaction, returning its id and its snapshot, the
+    /// timestamp of the latest commit.
+    ///
+    /// The snapshot is active until it is [released](Self::release).
+    pub fn begin(&self) -> (u64, Timestamp) {
+        let mut state = self.state.lock();
+        state.last_id += 1;
+        let snapshot = state.now;
+        *state.active.entry(snapshot).or_default() += 1;
+        (state.last_id, snapshot)
+    }
+
+    /// Releases a snapshot returned by [`Self::begin`]
+    pub fn release(&self, snapshot: Timestamp) {
+        let mut state = self.state.lock();
+        if let Some(count) = state.active.get_mut(&snapshot) {
+            *count -= 1;
+            if *count == 0 {
+                state.active.remove(&snapshot);
+            }
+        }
+    }
+
+    /// Returns the timestamp of a new commit, which is after the snapshots
+    /// of all active transactions.
+    ///
+    /// Transactions that start after this call see the commit, so the
+    /// committed data must be locked against reads until the commit is
+    /// complete.
+    pub fn next_commit_timestamp(&self) -> Timestamp {
+        let mut state = self.state.lock();
+        state.now += 1;
+        state.now
+    }
+
+    /// Returns whether an active transaction has its snapshot in `range`.
+    ///
+    /// A version of the data committed at `range.start` and replaced at
+    /// `range.end` can be dropped if this returns `false`.
+    pub fn has_active_snapshot(&self, range: Range<Timestamp>) -> bool {
+        self.state.lock().active.range(range).next().is_some()
+    }
+
+    /// Waits until no other transaction of this clock is committing, and
+    /// returns a guard that keeps others from committing until dropped
+    pub async fn lock_commits(&self) -> AsyncMutexGuard<'_, ()> {
+        self.commits.lock().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commits_are_after_active_snapshots() {
+        let clock = CommitClock::default();
+        let (first, snapshot) = clock.begin();
+        let timestamp = clock.next_commit_timestamp();
+        assert!(timestamp > snapshot);
+        assert!(clock.has_active_snapshot(snapshot..timestamp));
+
+        let (second, later) = clock.begin();
+        assert!(second > first);
+        assert!(later >= timestamp);
+
+        clock.release(snapshot);
+        assert!(!clock.has_active_snapshot(snapshot..timestamp));
+        assert!(clock.has_active_snapshot(later..later + 1));
+
+        // Clocks of different runtimes are independent
+        let other = CommitClock::default();
+        assert!(!other.has_active_snapshot(later..later + 1));
+    }
+}
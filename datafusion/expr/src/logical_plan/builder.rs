@@ -33,14 +33,14 @@ use crate::expr_rewriter::{
 use crate::logical_plan::{
     Aggregate, Analyze, Distinct, DistinctOn, EmptyRelation, Explain, Filter, Join,
     JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Prepare,
-    Projection, Repartition, Sort, SubqueryAlias, TableScanBuilder, Union, Unnest,
-    Values, Window,
+    Projection, Repartition, Sample, SampleMethod, Sort, SubqueryAlias, TableScanBuilder,
+    Union, Unnest, Values, Window,
 };
 use crate::select_expr::SelectExpr;
 use crate::utils::{
     can_hash, columnize_expr, compare_sort_expr, expand_qualified_wildcard,
-    expand_wildcard, expr_to_columns, find_valid_equijoin_key_pair,
-    group_window_expr_by_sort_keys,
+    expand_wildcard, expr_to_columns, find_asof_match_condition,
+    find_valid_equijoin_key_pair, group_window_expr_by_sort_keys,
 };
 use crate::{
     DmlStatement, ExplainOption, Expr, ExprSchemable, Operator, RecursiveQuery,
@@ -683,6 +683,16 @@ impl LogicalPlanBuilder {
         })))
     }
 
+    /// Return a random sample of the rows using `method`.
+    ///
+    /// If `seed` is `None`, a different sample is returned each time the plan
+    /// is executed.
+    pub fn sample(self, method: SampleMethod, seed: Option<u64>) -> Result<Self> {
+        Sample::try_new(self.plan, method, seed)
+            .map(LogicalPlan::Sample)
+            .map(Self::new)
+    }
+
     /// Apply an alias
     pub fn alias(self, alias: impl Into<TableReference>) -> Result<Self> {
         subquery_alias(Arc::unwrap_or_clone(self.plan), alias).map(Self::new)
@@ -1311,6 +1321,177 @@ impl LogicalPlanBuilder {
             .map(Self::new)
     }
 
+    /// Apply a pivot: turn the `pivot_values` of `pivot_column` into columns.
+    ///
+    /// For each aggregate in `aggr_expr` and each value in `pivot_values`, an
+    /// output column is computed by the aggregate, filtered to the rows where
+    /// `pivot_column` equals the value. The input columns that are not
+    /// referenced by `pivot_column` or by any aggregate are used as the
+    /// grouping keys.
+    ///
+    /// Output columns are named after the value, or its alias if the value
+    /// is aliased. When there is more than one aggregate, or the aggregate is
+    /// aliased, the name is prefixed with the alias or function name of the
+    /// aggregate, e.g. `sum_Q1`.
+    ///
+    /// ```text
+    /// SELECT * FROM sales PIVOT (sum(amount) FOR quarter IN ('Q1', 'Q2'))
+    /// ```
+    /// is planned as
+    /// ```text
+    /// Aggregate: groupBy=[[sales.region]], aggr=[[
+    ///   sum(sales.amount) FILTER (WHERE sales.quarter = Utf8("Q1")) AS Q1,
+    ///   sum(sales.amount) FILTER (WHERE sales.quarter = Utf8("Q2")) AS Q2
+    /// ]]
+    /// ```
+    pub fn pivot(
+        self,
+        aggr_expr: impl IntoIterator<Item = impl Into<Expr>>,
+        pivot_column: impl Into<Expr>,
+        pivot_values: impl IntoIterator<Item = impl Into<Expr>>,
+    ) -> Result<Self> {
+        let aggr_expr = normalize_cols(aggr_expr, &self.plan)?;
+        let pivot_column = normalize_col(pivot_column.into(), &self.plan)?;
+        let pivot_values = pivot_values
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<Expr>>();
+        if aggr_expr.is_empty() {
+            return plan_err!("PIVOT requires at least one aggregate expression");
+        }
+        if pivot_values.is_empty() {
+            return plan_err!("PIVOT requires at least one pivot value");
+        }
+
+        let mut pivot_refs = pivot_column.column_refs();
+        aggr_expr
+            .iter()
+            .for_each(|expr| pivot_refs.extend(expr.column_refs()));
+        let group_expr = self
+            .plan
+            .schema()
+            .columns()
+            .into_iter()
+            .filter(|column| !pivot_refs.contains(column))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+
+        let mut pivot_exprs = Vec::with_capacity(aggr_expr.len() * pivot_values.len());
+        for aggr in &aggr_expr {
+            let (aggr, aggr_alias) = match aggr {
+                Expr::Alias(Alias { expr, name, .. }) => (expr.as_ref(), Some(name)),
+                aggr => (aggr, None),
+            };
+            let Expr::AggregateFunction(func) = aggr else {
+                return plan_err!("PIVOT expects an aggregate function, got {aggr}");
+            };
+            let prefix = (aggr_expr.len() > 1 || aggr_alias.is_some()).then(|| {
+                aggr_alias
+                    .cloned()
+                    .unwrap_or_else(|| func.func.name().to_string())
+            });
+            for value in &pivot_values {
+                let (value, value_name) = match value {
+                    Expr::Alias(Alias { expr, name, .. }) => {
+                        (expr.as_ref().clone(), name.clone())
+                    }
+                    Expr::Literal(ScalarValue::Utf8(Some(s)), _)
+                    | Expr::Literal(ScalarValue::Utf8View(Some(s)), _)
+                    | Expr::Literal(ScalarValue::LargeUtf8(Some(s)), _) => {
+                        (value.clone(), s.clone())
+                    }
+                    Expr::Literal(scalar, _) => (value.clone(), scalar.to_string()),
+                    value => (value.clone(), value.schema_name().to_string()),
+                };
+                let predicate = pivot_column.clone().eq(value);
+                let mut func = func.clone();
+                func.params.filter = Some(Box::new(match func.params.filter.take() {
+                    Some(filter) => filter.and(predicate),
+                    None => predicate,
+                }));
+                let name = match &prefix {
+                    Some(prefix) => format!("{prefix}_{value_name}"),
+                    None => value_name,
+                };
+                pivot_exprs.push(Expr::AggregateFunction(func).alias(name));
+            }
+        }
+
+        self.aggregate(group_expr, pivot_exprs)
+    }
+
+    /// Apply an unpivot: turn the `columns` into rows.
+    ///
+    /// Each input row produces one output row per entry of `columns`, holding
+    /// the entry's label in a column named `name_column` and the value of the
+    /// entry in a column named `value_column`. The label is the alias of the
+    /// entry if it is aliased, or the name of the column otherwise. All other
+    /// input columns are passed through unchanged.
+    ///
+    /// Rows where the value is NULL are removed unless `include_nulls` is true.
+    ///
+    /// The unpivot is planned as a `UNION ALL` of one projection per entry.
+    pub fn unpivot(
+        self,
+        value_column: impl Into<String>,
+        name_column: impl Into<String>,
+        columns: impl IntoIterator<Item = impl Into<Expr>>,
+        include_nulls: bool,
+    ) -> Result<Self> {
+        let value_column = value_column.into();
+        let name_column = name_column.into();
+        let columns = normalize_cols(columns, &self.plan)?;
+        if columns.is_empty() {
+            return plan_err!("UNPIVOT requires at least one column");
+        }
+
+        let mut unpivot_refs = HashSet::new();
+        let mut entries = Vec::with_capacity(columns.len());
+        for column in &columns {
+            let (expr, label) = match column {
+                Expr::Alias(Alias { expr, name, .. }) => (expr.as_ref(), name.clone()),
+                Expr::Column(c) => (column, c.name.clone()),
+                expr => (expr, expr.schema_name().to_string()),
+            };
+            unpivot_refs.extend(expr.column_refs());
+            entries.push((expr.clone(), label));
+        }
+        let passthrough = self
+            .plan
+            .schema()
+            .columns()
+            .into_iter()
+            .filter(|column| !unpivot_refs.contains(column))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+
+        let inputs = entries
+            .into_iter()
+            .map(|(expr, label)| {
+                let branch = Self::new_from_arc(Arc::clone(&self.plan));
+                let branch = if include_nulls {
+                    branch
+                } else {
+                    branch.filter(expr.clone().is_not_null())?
+                };
+                branch
+                    .project(passthrough.iter().cloned().chain([
+                        lit(label).alias(&name_column),
+                        expr.alias(&value_column),
+                    ]))?
+                    .build()
+                    .map(Arc::new)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let plan = if inputs.len() == 1 {
+            Arc::unwrap_or_clone(inputs.into_iter().next().unwrap())
+        } else {
+            LogicalPlan::Union(Union::try_new_with_loose_types(inputs)?)
+        };
+        Ok(Self::new(plan))
+    }
+
     /// Create an expression to represent the explanation of the plan
     ///
     /// if `analyze` is true, runs the actual plan and produces
@@ -1527,6 +1708,38 @@ impl LogicalPlanBuilder {
         Ok(Self::new(LogicalPlan::Join(join)))
     }
 
+    /// Apply an as-of join with the given equi-join key expressions and
+    /// inequality match condition.
+    ///
+    /// For each row of the current plan, the as-of join picks the single row
+    /// of `right` with equal join keys that is closest to it according to
+    /// `match_condition`, or NULLs when there is no such row. For example
+    /// `col("trades.ts").gt_eq(col("quotes.ts"))` joins each trade with the
+    /// latest quote at or before it.
+    ///
+    /// `match_condition` must compare an expression of the left input with
+    /// an expression of the right input using `<`, `<=`, `>` or `>=`.
+    pub fn join_asof(
+        self,
+        right: LogicalPlan,
+        equi_exprs: (Vec<impl Into<Expr>>, Vec<impl Into<Expr>>),
+        match_condition: Expr,
+    ) -> Result<Self> {
+        let match_condition = normalize_col_with_schemas_and_ambiguity_check(
+            match_condition,
+            &[&[self.plan.schema(), right.schema()]],
+            &[],
+        )?;
+        let (left_expr, op, right_expr) = find_asof_match_condition(
+            &match_condition,
+            self.plan.schema(),
+            right.schema(),
+        )?;
+        let match_condition = binary_expr(left_expr, op, right_expr);
+
+        self.join_with_expr_keys(right, JoinType::AsOf, equi_exprs, Some(match_condition))
+    }
+
     /// Unnest the given column.
     pub fn unnest_column(self, column: impl Into<Column>) -> Result<Self> {
         unnest(Arc::unwrap_or_clone(self.plan), vec![column.into()]).map(Self::new)
@@ -1703,7 +1916,7 @@ pub fn build_join_schema(
                 .collect::<Vec<_>>();
             left_fields.into_iter().chain(right_fields).collect()
         }
-        JoinType::Left => {
+        JoinType::Left | JoinType::AsOf => {
             // left then right, right set to nullable in case of not matched scenario
             let left_fields = left_fields
                 .map(|(q, f)| (q.cloned(), Arc::clone(f)))
@@ -2405,6 +2618,56 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_builder_pivot() -> Result<()> {
+        let plan = table_scan(
+            Some("employee_csv"),
+            &employee_schema(),
+            Some(vec![0, 3, 4]),
+        )?
+        .pivot(
+            vec![sum(col("salary"))],
+            col("state"),
+            vec![lit("CO"), lit("CA").alias("california")],
+        )?
+        .build()?;
+
+        assert_snapshot!(plan, @r#"
+        Aggregate: groupBy=[[employee_csv.id]], aggr=[[sum(employee_csv.salary) FILTER (WHERE employee_csv.state = Utf8("CO")) AS CO, sum(employee_csv.salary) FILTER (WHERE employee_csv.state = Utf8("CA")) AS california]]
+          TableScan: employee_csv projection=[id, state, salary]
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_unpivot() -> Result<()> {
+        let plan = table_scan(
+            Some("employee_csv"),
+            &employee_schema(),
+            Some(vec![0, 1, 2]),
+        )?
+        .unpivot(
+            "name",
+            "kind",
+            vec![col("first_name").alias("first"), col("last_name")],
+            false,
+        )?
+        .build()?;
+
+        assert_snapshot!(plan, @r#"
+        Union
+          Projection: employee_csv.id, Utf8("first") AS kind, employee_csv.first_name AS name
+            Filter: employee_csv.first_name IS NOT NULL
+              TableScan: employee_csv projection=[id, first_name, last_name]
+          Projection: employee_csv.id, Utf8("last_name") AS kind, employee_csv.last_name AS name
+            Filter: employee_csv.last_name IS NOT NULL
+              TableScan: employee_csv projection=[id, first_name, last_name]
+        "#);
+
+        Ok(())
+    }
+
     #[test]
     fn plan_builder_union_distinct() -> Result<()> {
         let plan =
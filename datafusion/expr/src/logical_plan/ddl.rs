@@ -27,10 +27,14 @@ use std::{
 use crate::expr::Sort;
 #[cfg(not(feature = "sql"))]
 use crate::sql::Ident;
-use arrow::datatypes::DataType;
-use datafusion_common::tree_node::{Transformed, TreeNodeContainer, TreeNodeRecursion};
+use arrow::datatypes::{DataType, FieldRef, Schema};
+use datafusion_common::file_options::bucketing::BucketSpec;
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeContainer, TreeNodeRecursion,
+};
 use datafusion_common::{
-    Constraints, DFSchemaRef, Result, SchemaReference, TableReference,
+    Column, Constraint, Constraints, DFSchemaRef, Result, SchemaReference,
+    TableReference, plan_err,
 };
 #[cfg(feature = "sql")]
 use sqlparser::ast::Ident;
@@ -56,6 +60,8 @@ pub enum DdlStatement {
     DropTable(DropTable),
     /// Drops a view.
     DropView(DropView),
+    /// Recomputes the contents of a materialized view.
+    RefreshMaterializedView(RefreshMaterializedView),
     /// Drops a catalog schema
     DropCatalogSchema(DropCatalogSchema),
     /// Create function statement. Boxed for the same reason as
@@ -63,6 +69,10 @@ pub enum DdlStatement {
     CreateFunction(Box<CreateFunction>),
     /// Drop function statement
     DropFunction(DropFunction),
+    /// Alters the columns or the name of an existing table.
+    AlterTable(AlterTable),
+    /// Computes and stores the statistics of a table.
+    AnalyzeTable(AnalyzeTable),
 }
 
 impl DdlStatement {
@@ -79,9 +89,15 @@ impl DdlStatement {
             DdlStatement::CreateIndex(CreateIndex { schema, .. }) => schema,
             DdlStatement::DropTable(DropTable { schema, .. }) => schema,
             DdlStatement::DropView(DropView { schema, .. }) => schema,
+            DdlStatement::RefreshMaterializedView(RefreshMaterializedView {
+                schema,
+                ..
+            }) => schema,
             DdlStatement::DropCatalogSchema(DropCatalogSchema { schema, .. }) => schema,
             DdlStatement::CreateFunction(cf) => &cf.schema,
             DdlStatement::DropFunction(DropFunction { schema, .. }) => schema,
+            DdlStatement::AlterTable(AlterTable { schema, .. }) => schema,
+            DdlStatement::AnalyzeTable(AnalyzeTable { schema, .. }) => schema,
         }
     }
 
@@ -97,9 +113,12 @@ impl DdlStatement {
             DdlStatement::CreateIndex(_) => "CreateIndex",
             DdlStatement::DropTable(_) => "DropTable",
             DdlStatement::DropView(_) => "DropView",
+            DdlStatement::RefreshMaterializedView(_) => "RefreshMaterializedView",
             DdlStatement::DropCatalogSchema(_) => "DropCatalogSchema",
             DdlStatement::CreateFunction(_) => "CreateFunction",
             DdlStatement::DropFunction(_) => "DropFunction",
+            DdlStatement::AlterTable(_) => "AlterTable",
+            DdlStatement::AnalyzeTable(_) => "AnalyzeTable",
         }
     }
 
@@ -116,9 +135,12 @@ impl DdlStatement {
             DdlStatement::CreateIndex(_) => vec![],
             DdlStatement::DropTable(_) => vec![],
             DdlStatement::DropView(_) => vec![],
+            DdlStatement::RefreshMaterializedView(_) => vec![],
             DdlStatement::DropCatalogSchema(_) => vec![],
             DdlStatement::CreateFunction(_) => vec![],
             DdlStatement::DropFunction(_) => vec![],
+            DdlStatement::AlterTable(_) => vec![],
+            DdlStatement::AnalyzeTable(_) => vec![],
         }
     }
 
@@ -152,8 +174,14 @@ impl DdlStatement {
                             write!(f, "CreateMemoryTable: {name:?} {constraints}")
                         }
                     }
-                    DdlStatement::CreateView(CreateView { name, .. }) => {
-                        write!(f, "CreateView: {name:?}")
+                    DdlStatement::CreateView(CreateView {
+                        name, materialized, ..
+                    }) => {
+                        if *materialized {
+                            write!(f, "CreateMaterializedView: {name:?}")
+                        } else {
+                            write!(f, "CreateView: {name:?}")
+                        }
                     }
                     DdlStatement::CreateCatalogSchema(CreateCatalogSchema {
                         schema_name,
@@ -179,6 +207,12 @@ impl DdlStatement {
                     }) => {
                         write!(f, "DropView: {name:?} if not exist:={if_exists}")
                     }
+                    DdlStatement::RefreshMaterializedView(RefreshMaterializedView {
+                        name,
+                        ..
+                    }) => {
+                        write!(f, "RefreshMaterializedView: {name:?}")
+                    }
                     DdlStatement::DropCatalogSchema(DropCatalogSchema {
                         name,
                         if_exists,
@@ -197,6 +231,27 @@ impl DdlStatement {
                     DdlStatement::DropFunction(DropFunction { name, .. }) => {
                         write!(f, "DropFunction: name {name:?}")
                     }
+                    DdlStatement::AlterTable(AlterTable {
+                        name,
+                        if_exists,
+                        operations,
+                        ..
+                    }) => {
+                        write!(f, "AlterTable: {name:?} if exists:={if_exists}")?;
+                        for operation in operations {
+                            write!(f, " {operation}")?;
+                        }
+                        Ok(())
+                    }
+                    DdlStatement::AnalyzeTable(AnalyzeTable {
+                        name, columns, ..
+                    }) => {
+                        write!(f, "AnalyzeTable: {name:?}")?;
+                        if !columns.is_empty() {
+                            write!(f, " columns=[{}]", columns.join(", "))?;
+                        }
+                        Ok(())
+                    }
                 }
             }
         }
@@ -217,6 +272,8 @@ pub struct CreateExternalTable {
     pub file_type: String,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// Hash bucketing of the files
+    pub bucket_spec: Option<BucketSpec>,
     /// Option to not error if table already exists
     pub if_not_exists: bool,
     /// Option to replace table content if table already exists
@@ -270,6 +327,7 @@ impl CreateExternalTable {
             file_type: file_type.into(),
             schema,
             table_partition_cols: vec![],
+            bucket_spec: None,
             if_not_exists: false,
             or_replace: false,
             temporary: false,
@@ -293,6 +351,7 @@ pub struct CreateExternalTableBuilder {
     file_type: String,
     schema: DFSchemaRef,
     table_partition_cols: Vec<String>,
+    bucket_spec: Option<BucketSpec>,
     if_not_exists: bool,
     or_replace: bool,
     temporary: bool,
@@ -311,6 +370,12 @@ impl CreateExternalTableBuilder {
         self
     }
 
+    /// Set the hash bucketing of the files
+    pub fn with_bucket_spec(mut self, bucket_spec: Option<BucketSpec>) -> Self {
+        self.bucket_spec = bucket_spec;
+        self
+    }
+
     /// Set the if_not_exists flag
     pub fn with_if_not_exists(mut self, if_not_exists: bool) -> Self {
         self.if_not_exists = if_not_exists;
@@ -376,6 +441,7 @@ impl CreateExternalTableBuilder {
             location: self.location,
             file_type: self.file_type,
             table_partition_cols: self.table_partition_cols,
+            bucket_spec: self.bucket_spec,
             if_not_exists: self.if_not_exists,
             or_replace: self.or_replace,
             temporary: self.temporary,
@@ -397,6 +463,7 @@ impl Hash for CreateExternalTable {
         self.location.hash(state);
         self.file_type.hash(state);
         self.table_partition_cols.hash(state);
+        self.bucket_spec.hash(state);
         self.if_not_exists.hash(state);
         self.definition.hash(state);
         self.order_exprs.hash(state);
@@ -419,6 +486,8 @@ impl PartialOrd for CreateExternalTable {
             pub file_type: &'a String,
             /// Partition Columns
             pub table_partition_cols: &'a Vec<String>,
+            /// Hash bucketing of the files
+            pub bucket_spec: &'a Option<BucketSpec>,
             /// Option to not error if table already exists
             pub if_not_exists: &'a bool,
             /// SQL used to create the table, if available
@@ -435,6 +504,7 @@ impl PartialOrd for CreateExternalTable {
             location: &self.location,
             file_type: &self.file_type,
             table_partition_cols: &self.table_partition_cols,
+            bucket_spec: &self.bucket_spec,
             if_not_exists: &self.if_not_exists,
             definition: &self.definition,
             order_exprs: &self.order_exprs,
@@ -446,6 +516,7 @@ impl PartialOrd for CreateExternalTable {
             location: &other.location,
             file_type: &other.file_type,
             table_partition_cols: &other.table_partition_cols,
+            bucket_spec: &other.bucket_spec,
             if_not_exists: &other.if_not_exists,
             definition: &other.definition,
             order_exprs: &other.order_exprs,
@@ -491,6 +562,12 @@ pub struct CreateView {
     pub definition: Option<String>,
     /// Whether the view is ephemeral
     pub temporary: bool,
+    /// Whether the result of `input` is computed once and stored, rather
+    /// than recomputed every time the view is scanned
+    pub materialized: bool,
+    /// Directory the contents of a materialized view are written to as
+    /// Parquet files. When `None` the contents are kept in memory.
+    pub location: Option<String>,
 }
 
 /// Creates a catalog (aka "Database").
@@ -585,6 +662,26 @@ impl PartialOrd for DropView {
     }
 }
 
+/// Recomputes the contents of a materialized view from its definition,
+/// replacing the previously stored result.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RefreshMaterializedView {
+    /// The materialized view name
+    pub name: TableReference,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for RefreshMaterializedView {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.name
+            .partial_cmp(&other.name)
+            // TODO (https://github.com/apache/datafusion/issues/17477) avoid recomparing all fields
+            .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
 /// Drops a schema
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DropCatalogSchema {
@@ -805,6 +902,216 @@ impl PartialOrd for CreateIndex {
     }
 }
 
+/// Alters an existing table, e.g. `ALTER TABLE t ADD COLUMN c INT`.
+///
+/// Column operations are applied by the table's [`TableProvider`] while
+/// renaming the table is handled by the [`SchemaProvider`] it is registered in.
+///
+/// [`TableProvider`]: https://docs.rs/datafusion/latest/datafusion/catalog/trait.TableProvider.html
+/// [`SchemaProvider`]: https://docs.rs/datafusion/latest/datafusion/catalog/trait.SchemaProvider.html
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AlterTable {
+    /// The table name
+    pub name: TableReference,
+    /// Do nothing if the table does not exist
+    pub if_exists: bool,
+    /// The operations to apply, in order
+    pub operations: Vec<AlterTableOperation>,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for AlterTable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.name.partial_cmp(&other.name) {
+            Some(Ordering::Equal) => match self.if_exists.partial_cmp(&other.if_exists) {
+                Some(Ordering::Equal) => self.operations.partial_cmp(&other.operations),
+                cmp => cmp,
+            },
+            cmp => cmp,
+        }
+        // TODO (https://github.com/apache/datafusion/issues/17477) avoid recomparing all fields
+        .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
+/// Scans a table to compute its statistics, such as the number of rows and
+/// the number of distinct values, minimum and maximum of each column, and
+/// stores them to be used for planning later queries.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AnalyzeTable {
+    /// The table name
+    pub name: TableReference,
+    /// The columns to compute statistics for, all columns if empty
+    pub columns: Vec<String>,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for AnalyzeTable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.name.partial_cmp(&other.name) {
+            Some(Ordering::Equal) => self.columns.partial_cmp(&other.columns),
+            cmp => cmp,
+        }
+        // TODO (https://github.com/apache/datafusion/issues/17477) avoid recomparing all fields
+        .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
+/// A single operation of an `ALTER TABLE` statement
+///
+/// See [`AlterTable`] for details
+#[derive(Clone, PartialEq, Eq, PartialOrd, Hash, Debug)]
+pub enum AlterTableOperation {
+    /// `ADD COLUMN [IF NOT EXISTS] <column_def>`
+    AddColumn {
+        /// The new column. It is appended after the existing columns.
+        field: FieldRef,
+        /// Value for existing rows and the default for future inserts
+        default: Option<Expr>,
+        /// Do nothing if a column with the same name already exists
+        if_not_exists: bool,
+    },
+    /// `DROP COLUMN [IF EXISTS] <name>`
+    DropColumn {
+        /// The column to remove
+        name: String,
+        /// Do nothing if the column does not exist
+        if_exists: bool,
+    },
+    /// `RENAME COLUMN <old_name> TO <new_name>`
+    RenameColumn {
+        /// The existing column name
+        old_name: String,
+        /// The new column name
+        new_name: String,
+    },
+    /// `RENAME TO <new_name>`
+    RenameTable {
+        /// The new table name
+        new_name: TableReference,
+    },
+}
+
+impl AlterTableOperation {
+    /// Update a table's known sort orders for this operation.
+    ///
+    /// Renamed columns are renamed in the sort expressions. Each ordering is
+    /// truncated before the first expression referencing a dropped column and
+    /// orderings that become empty are removed.
+    pub fn apply_to_sort_order(&self, sort_order: &mut Vec<Vec<SortExpr>>) -> Result<()> {
+        match self {
+            AlterTableOperation::DropColumn { name, .. } => {
+                for ordering in sort_order.iter_mut() {
+                    if let Some(pos) = ordering.iter().position(|sort| {
+                        sort.expr.column_refs().iter().any(|c| &c.name == name)
+                    }) {
+                        ordering.truncate(pos);
+                    }
+                }
+                sort_order.retain(|ordering| !ordering.is_empty());
+            }
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                for sort in sort_order.iter_mut().flatten() {
+                    sort.expr = std::mem::take(&mut sort.expr)
+                        .transform(|expr| match expr {
+                            Expr::Column(c) if &c.name == old_name => {
+                                Ok(Transformed::yes(Expr::Column(Column::new(
+                                    c.relation, new_name,
+                                ))))
+                            }
+                            _ => Ok(Transformed::no(expr)),
+                        })
+                        .data()?;
+                }
+            }
+            AlterTableOperation::AddColumn { .. }
+            | AlterTableOperation::RenameTable { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Update a table's constraints, whose indices refer to the fields of
+    /// `schema`, for this operation.
+    ///
+    /// Returns an error when dropping a column that is part of a constraint.
+    /// Added columns are appended to the schema and renames keep the field
+    /// indices, so only dropped columns change the constraints.
+    pub fn apply_to_constraints(
+        &self,
+        schema: &Schema,
+        constraints: &Constraints,
+    ) -> Result<Constraints> {
+        let AlterTableOperation::DropColumn { name, .. } = self else {
+            return Ok(constraints.clone());
+        };
+        let Ok(index) = schema.index_of(name) else {
+            return Ok(constraints.clone());
+        };
+        let shift = |indices: &[usize]| {
+            indices
+                .iter()
+                .map(|i| if *i > index { i - 1 } else { *i })
+                .collect::<Vec<_>>()
+        };
+        let mut altered = Vec::with_capacity(constraints.len());
+        for constraint in constraints.iter() {
+            let (kind, indices) = match constraint {
+                Constraint::PrimaryKey(indices) => ("PRIMARY KEY", indices),
+                Constraint::Unique(indices) => ("UNIQUE", indices),
+            };
+            if indices.contains(&index) {
+                return plan_err!(
+                    "ALTER TABLE failed: cannot drop column '{name}' used by a {kind} constraint"
+                );
+            }
+            altered.push(match constraint {
+                Constraint::PrimaryKey(_) => Constraint::PrimaryKey(shift(indices)),
+                Constraint::Unique(_) => Constraint::Unique(shift(indices)),
+            });
+        }
+        Ok(Constraints::new_unverified(altered))
+    }
+}
+
+impl Display for AlterTableOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlterTableOperation::AddColumn {
+                field,
+                default,
+                if_not_exists,
+            } => {
+                write!(f, "ADD COLUMN ")?;
+                if *if_not_exists {
+                    write!(f, "IF NOT EXISTS ")?;
+                }
+                write!(f, "{} {}", field.name(), field.data_type())?;
+                if let Some(default) = default {
+                    write!(f, " DEFAULT {default}")?;
+                }
+                Ok(())
+            }
+            AlterTableOperation::DropColumn { name, if_exists } => {
+                if *if_exists {
+                    write!(f, "DROP COLUMN IF EXISTS {name}")
+                } else {
+                    write!(f, "DROP COLUMN {name}")
+                }
+            }
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                write!(f, "RENAME COLUMN {old_name} TO {new_name}")
+            }
+            AlterTableOperation::RenameTable { new_name } => {
+                write!(f, "RENAME TO {new_name}")
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{CreateCatalog, DdlStatement, DropView};
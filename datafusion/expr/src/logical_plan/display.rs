@@ -22,9 +22,9 @@ use std::fmt;
 
 use crate::{
     Aggregate, DescribeTable, Distinct, DistinctOn, DmlStatement, Expr, Filter, Join,
-    Limit, LogicalPlan, Partitioning, Projection, RecursiveQuery, Repartition, Sort,
-    Subquery, SubqueryAlias, TableProviderFilterPushDown, TableScan, Unnest, Values,
-    Window, expr_vec_fmt,
+    Limit, LogicalPlan, MatchRecognize, Partitioning, Projection, RecursiveQuery,
+    Repartition, Sample, Sort, Subquery, SubqueryAlias, TableProviderFilterPushDown,
+    TableScan, Unnest, Values, Window, expr_vec_fmt,
 };
 
 use crate::dml::CopyTo;
@@ -556,6 +556,16 @@ impl<'a, 'b> PgJsonVisitor<'a, 'b> {
                 };
                 object
             }
+            LogicalPlan::Sample(Sample { method, seed, .. }) => {
+                let mut object = json!({
+                    "Node Type": "Sample",
+                    "Method": method.to_string(),
+                });
+                if let Some(seed) = seed {
+                    object["Seed"] = seed.to_string().into()
+                };
+                object
+            }
             LogicalPlan::Subquery(Subquery { .. }) => {
                 json!({
                     "Node Type": "Subquery"
@@ -651,6 +661,27 @@ impl<'a, 'b> PgJsonVisitor<'a, 'b> {
                     "StructColumn": expr_vec_fmt!(struct_type_columns),
                 })
             }
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                partition_by,
+                order_by,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                pattern,
+                defines,
+                ..
+            }) => {
+                json!({
+                    "Node Type": "MatchRecognize",
+                    "Partition By": expr_vec_fmt!(partition_by),
+                    "Order By": expr_vec_fmt!(order_by),
+                    "Measures": expr_vec_fmt!(measures),
+                    "Rows Per Match": rows_per_match.to_string(),
+                    "After Match Skip": after_match_skip.to_string(),
+                    "Pattern": pattern.to_string(),
+                    "Define": expr_vec_fmt!(defines),
+                })
+            }
         }
     }
 }
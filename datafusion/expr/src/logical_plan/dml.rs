@@ -117,7 +117,7 @@ impl CopyTo {
 /// Modifies the content of a database
 ///
 /// This operator is used to perform DML operations such as INSERT, DELETE,
-/// UPDATE, and CTAS (CREATE TABLE AS SELECT).
+/// UPDATE, MERGE, and CTAS (CREATE TABLE AS SELECT).
 ///
 /// * `INSERT` - Appends new rows to the existing table. Calls
 ///   [`TableProvider::insert_into`]
@@ -126,6 +126,9 @@ impl CopyTo {
 ///
 /// * `UPDATE` - Modifies existing rows in the table. Calls [`TableProvider::update`]
 ///
+/// * `MERGE` - Inserts, updates and deletes rows of the table based on a join
+///   with a source relation. Calls [`TableProvider::merge_into`]
+///
 /// * `CREATE TABLE AS SELECT` - Creates a new table and populates it with data
 ///   from a query. This is similar to the `INSERT` operation, but it creates a new
 ///   table instead of modifying an existing one.
@@ -136,6 +139,7 @@ impl CopyTo {
 /// [`TableProvider::insert_into`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.insert_into
 /// [`TableProvider::delete_from`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.delete_from
 /// [`TableProvider::update`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.update
+/// [`TableProvider::merge_into`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.merge_into
 #[derive(Clone)]
 pub struct DmlStatement {
     /// The table name
@@ -239,6 +243,13 @@ pub enum WriteOp {
     Ctas,
     /// `TRUNCATE` operation
     Truncate,
+    /// `MERGE INTO` operation
+    ///
+    /// The input has one column per column of the target table, holding the
+    /// new values of the row, followed by a [`MERGE_ACTION_COLUMN`] with the
+    /// [`MergeAction`] to apply. Rows of the target table that are not
+    /// modified are included with a `NULL` action and their current values.
+    Merge,
 }
 
 impl WriteOp {
@@ -250,6 +261,7 @@ impl WriteOp {
             WriteOp::Update => "Update",
             WriteOp::Ctas => "Ctas",
             WriteOp::Truncate => "Truncate",
+            WriteOp::Merge => "Merge",
         }
     }
 }
@@ -260,6 +272,49 @@ impl Display for WriteOp {
     }
 }
 
+/// The name of the column of the input of a [`WriteOp::Merge`] holding the
+/// [`MergeAction`] of each row
+pub const MERGE_ACTION_COLUMN: &str = "__merge_action";
+
+/// The action applied to a row by a `MERGE` statement, stored as a string in
+/// the [`MERGE_ACTION_COLUMN`] of the input of a [`WriteOp::Merge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub enum MergeAction {
+    /// The row is inserted into the target table (`WHEN NOT MATCHED THEN INSERT`)
+    Insert,
+    /// The row of the target table is replaced (`WHEN MATCHED THEN UPDATE`)
+    Update,
+    /// The row of the target table is removed (`WHEN MATCHED THEN DELETE`)
+    Delete,
+}
+
+impl MergeAction {
+    /// Return the value of this [`MergeAction`] in the [`MERGE_ACTION_COLUMN`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            MergeAction::Insert => "insert",
+            MergeAction::Update => "update",
+            MergeAction::Delete => "delete",
+        }
+    }
+
+    /// Parse a value of the [`MERGE_ACTION_COLUMN`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "insert" => Some(MergeAction::Insert),
+            "update" => Some(MergeAction::Update),
+            "delete" => Some(MergeAction::Delete),
+            _ => None,
+        }
+    }
+}
+
+impl Display for MergeAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
 pub enum InsertOp {
     /// Appends new rows to the existing table without modifying any
@@ -324,7 +324,8 @@ fn check_inner_plan(inner_plan: &LogicalPlan) -> Result<()> {
             JoinType::Left
             | JoinType::LeftSemi
             | JoinType::LeftAnti
-            | JoinType::LeftMark => {
+            | JoinType::LeftMark
+            | JoinType::AsOf => {
                 check_inner_plan(left)?;
                 check_no_outer_references(right)
             }
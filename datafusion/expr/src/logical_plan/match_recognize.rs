@@ -0,0 +1,677 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Row pattern recognition (SQL `MATCH_RECOGNIZE`), see [`MatchRecognize`]
+
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::tree_node::{Transformed, TreeNodeContainer, TreeNodeRecursion};
+use datafusion_common::{
+    DFSchema, DFSchemaRef, Result, internal_datafusion_err, plan_err,
+};
+
+use crate::expr::Sort as SortExpr;
+use crate::expr_schema::ExprSchemable;
+use crate::utils::exprlist_to_fields;
+use crate::{Expr, LogicalPlan};
+
+/// Finds the sequences of rows of each partition of its input that match a
+/// regular expression of pattern variables (SQL `MATCH_RECOGNIZE`).
+///
+/// The rows of each partition, as defined by `partition_by`, are considered
+/// in the order of `order_by`. A row can be mapped to a pattern variable if
+/// the variable's condition in `defines` is true for the row; variables
+/// without a condition match every row. Matches are searched for starting at
+/// the first row of each partition, preferring the first alternative and the
+/// longest repetition of the pattern, and resume after each match as
+/// specified by `after_match_skip`. Empty matches are never returned.
+///
+/// The output contains one row per match with the `partition_by` columns
+/// followed by the `measures`, or, with [`RowsPerMatch::All`], every row of
+/// each match with the input columns followed by the `measures`.
+///
+/// For example, the following query finds V-shaped price patterns:
+///
+/// ```sql
+/// SELECT * FROM ticks MATCH_RECOGNIZE (
+///   PARTITION BY symbol
+///   ORDER BY ts
+///   MEASURES FIRST(DOWN.price) AS top, LAST(UP.price) AS bottom
+///   PATTERN (DOWN+ UP+)
+///   DEFINE DOWN AS price < PREV(price), UP AS price > PREV(price)
+/// )
+/// ```
+///
+/// The SQL planner computes `PREV` and `NEXT` as window functions of the
+/// input and the measures that combine several [`RowPatternFunction`]s in a
+/// projection of the output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchRecognize {
+    /// The incoming logical plan
+    pub input: Arc<LogicalPlan>,
+    /// The expressions that divide the input into partitions
+    pub partition_by: Vec<Expr>,
+    /// The order of the rows within each partition
+    pub order_by: Vec<SortExpr>,
+    /// The values computed for each match, or each row of a match
+    pub measures: Vec<RowPatternMeasure>,
+    /// Whether one row is produced per match or per matched row
+    pub rows_per_match: RowsPerMatch,
+    /// Where the search for the next match resumes after a match
+    pub after_match_skip: AfterMatchSkip,
+    /// The pattern to match, boxed to keep [`LogicalPlan`] small
+    pub pattern: Box<RowPattern>,
+    /// The conditions of the pattern variables, evaluated on the input rows
+    pub defines: Vec<RowPatternDefinition>,
+    /// The schema description of the output
+    pub schema: DFSchemaRef,
+}
+
+impl MatchRecognize {
+    /// Create a new `MatchRecognize` operator
+    #[expect(clippy::too_many_arguments)]
+    pub fn try_new(
+        input: Arc<LogicalPlan>,
+        partition_by: Vec<Expr>,
+        order_by: Vec<SortExpr>,
+        measures: Vec<RowPatternMeasure>,
+        rows_per_match: RowsPerMatch,
+        after_match_skip: AfterMatchSkip,
+        pattern: RowPattern,
+        defines: Vec<RowPatternDefinition>,
+    ) -> Result<Self> {
+        let variables = pattern.variables();
+        let check_variable = |variable: &str, context: &str| {
+            if variables.contains(variable) {
+                Ok(())
+            } else {
+                plan_err!(
+                    "Pattern variable {variable} in {context} is not defined in PATTERN {pattern}"
+                )
+            }
+        };
+        for (i, definition) in defines.iter().enumerate() {
+            check_variable(&definition.variable, "DEFINE")?;
+            if defines[..i]
+                .iter()
+                .any(|other| other.variable == definition.variable)
+            {
+                return plan_err!(
+                    "Pattern variable {} is defined more than once",
+                    definition.variable
+                );
+            }
+        }
+        for variable in measures.iter().filter_map(|m| m.variable.as_deref()) {
+            check_variable(variable, "MEASURES")?;
+        }
+        if let AfterMatchSkip::ToFirst(variable) | AfterMatchSkip::ToLast(variable) =
+            &after_match_skip
+        {
+            check_variable(variable, "AFTER MATCH SKIP")?;
+        }
+
+        let mut fields = match rows_per_match {
+            RowsPerMatch::One => exprlist_to_fields(&partition_by, &input)?,
+            RowsPerMatch::All => input
+                .schema()
+                .iter()
+                .map(|(qualifier, field)| (qualifier.cloned(), Arc::clone(field)))
+                .collect(),
+        };
+        for measure in &measures {
+            fields.push((None, Arc::new(measure.to_field(input.schema())?)));
+        }
+        let schema = Arc::new(DFSchema::new_with_metadata(
+            fields,
+            input.schema().metadata().clone(),
+        )?);
+
+        Ok(Self {
+            input,
+            partition_by,
+            order_by,
+            measures,
+            rows_per_match,
+            after_match_skip,
+            pattern: Box::new(pattern),
+            defines,
+            schema,
+        })
+    }
+
+    /// Returns a copy of this node with new expressions, in the order of
+    /// [`LogicalPlan::expressions`], and a new input.
+    pub fn with_new_exprs(&self, exprs: Vec<Expr>, input: LogicalPlan) -> Result<Self> {
+        let mut exprs = exprs.into_iter();
+        let mut next = || {
+            exprs.next().ok_or_else(|| {
+                internal_datafusion_err!("Too few expressions for MatchRecognize")
+            })
+        };
+        let partition_by = self
+            .partition_by
+            .iter()
+            .map(|_| next())
+            .collect::<Result<_>>()?;
+        let order_by = self
+            .order_by
+            .iter()
+            .map(|sort| Ok(sort.with_expr(next()?)))
+            .collect::<Result<_>>()?;
+        let defines = self
+            .defines
+            .iter()
+            .map(|definition| {
+                Ok(RowPatternDefinition::new(
+                    definition.variable.clone(),
+                    next()?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        let measures = self
+            .measures
+            .iter()
+            .map(|measure| {
+                let function = match &measure.function {
+                    RowPatternFunction::First(_) => RowPatternFunction::First(next()?),
+                    RowPatternFunction::Last(_) => RowPatternFunction::Last(next()?),
+                    RowPatternFunction::Aggregate(_) => {
+                        RowPatternFunction::Aggregate(next()?)
+                    }
+                    function @ (RowPatternFunction::Classifier
+                    | RowPatternFunction::MatchNumber) => function.clone(),
+                };
+                Ok(RowPatternMeasure {
+                    function,
+                    ..measure.clone()
+                })
+            })
+            .collect::<Result<_>>()?;
+        Self::try_new(
+            Arc::new(input),
+            partition_by,
+            order_by,
+            measures,
+            self.rows_per_match,
+            self.after_match_skip.clone(),
+            self.pattern.as_ref().clone(),
+            defines,
+        )
+    }
+}
+
+impl<'a> TreeNodeContainer<'a, Expr> for MatchRecognize {
+    fn apply_elements<F: FnMut(&'a Expr) -> Result<TreeNodeRecursion>>(
+        &'a self,
+        mut f: F,
+    ) -> Result<TreeNodeRecursion> {
+        self.partition_by
+            .apply_elements(&mut f)?
+            .visit_sibling(|| self.order_by.apply_elements(&mut f))?
+            .visit_sibling(|| self.defines.apply_elements(&mut f))?
+            .visit_sibling(|| self.measures.apply_elements(&mut f))
+    }
+
+    fn map_elements<F: FnMut(Expr) -> Result<Transformed<Expr>>>(
+        self,
+        f: F,
+    ) -> Result<Transformed<Self>> {
+        let Self {
+            input,
+            partition_by,
+            order_by,
+            measures,
+            rows_per_match,
+            after_match_skip,
+            pattern,
+            defines,
+            schema,
+        } = self;
+        Ok((partition_by, order_by, (defines, measures))
+            .map_elements(f)?
+            .update_data(|(partition_by, order_by, (defines, measures))| Self {
+                input,
+                partition_by,
+                order_by,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                pattern,
+                defines,
+                schema,
+            }))
+    }
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for MatchRecognize {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.input.partial_cmp(&other.input)? {
+            Ordering::Equal => {} // continue
+            not_equal => return Some(not_equal),
+        }
+        match self.partition_by.partial_cmp(&other.partition_by)? {
+            Ordering::Equal => {} // continue
+            not_equal => return Some(not_equal),
+        }
+        match self.order_by.partial_cmp(&other.order_by)? {
+            Ordering::Equal => {} // continue
+            not_equal => return Some(not_equal),
+        }
+
+        // Contract for PartialOrd and PartialEq consistency requires that
+        // a == b if and only if partial_cmp(a, b) == Some(Equal).
+        if self == other {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+/// The condition of a pattern variable in `DEFINE`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct RowPatternDefinition {
+    /// The name of the pattern variable
+    pub variable: String,
+    /// A boolean expression over the input rows
+    pub condition: Expr,
+}
+
+impl RowPatternDefinition {
+    /// Create a new pattern variable definition
+    pub fn new(variable: impl Into<String>, condition: Expr) -> Self {
+        Self {
+            variable: variable.into(),
+            condition,
+        }
+    }
+}
+
+impl Display for RowPatternDefinition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} AS {}", self.variable, self.condition)
+    }
+}
+
+impl<'a> TreeNodeContainer<'a, Expr> for RowPatternDefinition {
+    fn apply_elements<F: FnMut(&'a Expr) -> Result<TreeNodeRecursion>>(
+        &'a self,
+        f: F,
+    ) -> Result<TreeNodeRecursion> {
+        self.condition.apply_elements(f)
+    }
+
+    fn map_elements<F: FnMut(Expr) -> Result<Transformed<Expr>>>(
+        self,
+        f: F,
+    ) -> Result<Transformed<Self>> {
+        self.condition
+            .map_elements(f)?
+            .map_data(|condition| Ok(Self { condition, ..self }))
+    }
+}
+
+/// A value computed for each match, or each row of a match, in `MEASURES`
+///
+/// With [`RowsPerMatch::All`] measures use running semantics: the value for a
+/// row is computed over the rows of the match up to and including that row.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct RowPatternMeasure {
+    /// The name of the output column
+    pub name: String,
+    /// Restricts the rows of the match to the rows mapped to this pattern
+    /// variable, or all rows of the match if `None`
+    pub variable: Option<String>,
+    /// The function computed over the rows of the match
+    pub function: RowPatternFunction,
+}
+
+impl RowPatternMeasure {
+    /// Create a new measure
+    pub fn new(
+        name: impl Into<String>,
+        variable: Option<String>,
+        function: RowPatternFunction,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            variable,
+            function,
+        }
+    }
+
+    /// Returns the output field of this measure
+    pub fn to_field(&self, input_schema: &DFSchema) -> Result<Field> {
+        let (data_type, nullable) = match &self.function {
+            // NULL if no row of the match is mapped to the variable
+            RowPatternFunction::First(expr) | RowPatternFunction::Last(expr) => {
+                (expr.get_type(input_schema)?, true)
+            }
+            RowPatternFunction::Aggregate(expr) => {
+                let (_, field) = expr.to_field(input_schema)?;
+                (field.data_type().clone(), field.is_nullable())
+            }
+            RowPatternFunction::Classifier => (DataType::Utf8, true),
+            RowPatternFunction::MatchNumber => (DataType::UInt64, false),
+        };
+        Ok(Field::new(&self.name, data_type, nullable))
+    }
+}
+
+impl Display for RowPatternMeasure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let variable = self.variable.as_deref();
+        match &self.function {
+            RowPatternFunction::First(expr) => {
+                write!(f, "FIRST({}{expr})", VariablePrefix(variable))?
+            }
+            RowPatternFunction::Last(expr) => {
+                write!(f, "LAST({}{expr})", VariablePrefix(variable))?
+            }
+            RowPatternFunction::Aggregate(expr) => match variable {
+                Some(variable) => write!(f, "{expr} FILTER (WHERE {variable})")?,
+                None => write!(f, "{expr}")?,
+            },
+            RowPatternFunction::Classifier => write!(f, "CLASSIFIER()")?,
+            RowPatternFunction::MatchNumber => write!(f, "MATCH_NUMBER()")?,
+        }
+        write!(f, " AS {}", self.name)
+    }
+}
+
+struct VariablePrefix<'a>(Option<&'a str>);
+
+impl Display for VariablePrefix<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(variable) => write!(f, "{variable}: "),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> TreeNodeContainer<'a, Expr> for RowPatternMeasure {
+    fn apply_elements<F: FnMut(&'a Expr) -> Result<TreeNodeRecursion>>(
+        &'a self,
+        f: F,
+    ) -> Result<TreeNodeRecursion> {
+        self.function.apply_elements(f)
+    }
+
+    fn map_elements<F: FnMut(Expr) -> Result<Transformed<Expr>>>(
+        self,
+        f: F,
+    ) -> Result<Transformed<Self>> {
+        self.function
+            .map_elements(f)?
+            .map_data(|function| Ok(Self { function, ..self }))
+    }
+}
+
+/// A function computed over the rows of a match, see [`RowPatternMeasure`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub enum RowPatternFunction {
+    /// `FIRST(expr)`: the value of `expr` for the first row
+    First(Expr),
+    /// `LAST(expr)`: the value of `expr` for the last row
+    Last(Expr),
+    /// An aggregate function ([`Expr::AggregateFunction`]) over the rows
+    Aggregate(Expr),
+    /// `CLASSIFIER()`: the name of the pattern variable of the last row
+    Classifier,
+    /// `MATCH_NUMBER()`: the sequential number of the match within its
+    /// partition, starting at 1
+    MatchNumber,
+}
+
+impl RowPatternFunction {
+    /// Returns the expression evaluated by this function, if any
+    pub fn expr(&self) -> Option<&Expr> {
+        match self {
+            Self::First(expr) | Self::Last(expr) | Self::Aggregate(expr) => Some(expr),
+            Self::Classifier | Self::MatchNumber => None,
+        }
+    }
+}
+
+impl<'a> TreeNodeContainer<'a, Expr> for RowPatternFunction {
+    fn apply_elements<F: FnMut(&'a Expr) -> Result<TreeNodeRecursion>>(
+        &'a self,
+        f: F,
+    ) -> Result<TreeNodeRecursion> {
+        match self.expr() {
+            Some(expr) => expr.apply_elements(f),
+            None => Ok(TreeNodeRecursion::Continue),
+        }
+    }
+
+    fn map_elements<F: FnMut(Expr) -> Result<Transformed<Expr>>>(
+        self,
+        f: F,
+    ) -> Result<Transformed<Self>> {
+        Ok(match self {
+            Self::First(expr) => expr.map_elements(f)?.update_data(Self::First),
+            Self::Last(expr) => expr.map_elements(f)?.update_data(Self::Last),
+            Self::Aggregate(expr) => expr.map_elements(f)?.update_data(Self::Aggregate),
+            Self::Classifier | Self::MatchNumber => Transformed::no(self),
+        })
+    }
+}
+
+/// Whether [`MatchRecognize`] produces one row per match or per matched row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub enum RowsPerMatch {
+    /// `ONE ROW PER MATCH`: a summary row per match (the default)
+    One,
+    /// `ALL ROWS PER MATCH`: each row of the match, except for the rows
+    /// mapped to excluded pattern variables
+    All,
+}
+
+impl Display for RowsPerMatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RowsPerMatch::One => write!(f, "ONE ROW PER MATCH"),
+            RowsPerMatch::All => write!(f, "ALL ROWS PER MATCH"),
+        }
+    }
+}
+
+/// Where [`MatchRecognize`] resumes the search for a match after a match
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub enum AfterMatchSkip {
+    /// `PAST LAST ROW`: at the row after the last row of the match (the
+    /// default)
+    PastLastRow,
+    /// `TO NEXT ROW`: at the row after the first row of the match
+    ToNextRow,
+    /// `TO FIRST <variable>`: at the first row mapped to the variable
+    ToFirst(String),
+    /// `TO LAST <variable>`: at the last row mapped to the variable
+    ToLast(String),
+}
+
+impl Display for AfterMatchSkip {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AfterMatchSkip::PastLastRow => write!(f, "PAST LAST ROW"),
+            AfterMatchSkip::ToNextRow => write!(f, "TO NEXT ROW"),
+            AfterMatchSkip::ToFirst(variable) => write!(f, "TO FIRST {variable}"),
+            AfterMatchSkip::ToLast(variable) => write!(f, "TO LAST {variable}"),
+        }
+    }
+}
+
+/// A regular expression over pattern variables, see [`MatchRecognize`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub enum RowPattern {
+    /// A row mapped to the pattern variable
+    Variable(String),
+    /// `^`: the start of the partition
+    PartitionStart,
+    /// `$`: the end of the partition
+    PartitionEnd,
+    /// `{- pattern -}`: rows that are not returned by `ALL ROWS PER MATCH`
+    Exclude(Box<RowPattern>),
+    /// `PERMUTE(pattern, ...)`: all patterns in any order, preferring the
+    /// order in which they are listed
+    Permute(Vec<RowPattern>),
+    /// `pattern pattern ...`
+    Concat(Vec<RowPattern>),
+    /// `pattern | pattern | ...`: preferring the first matching alternative
+    Alternation(Vec<RowPattern>),
+    /// `pattern{min, max}`: greedy repetition, unbounded if `max` is `None`
+    Repetition {
+        /// The repeated pattern
+        pattern: Box<RowPattern>,
+        /// The minimum number of repetitions
+        min: u32,
+        /// The maximum number of repetitions
+        max: Option<u32>,
+    },
+}
+
+impl RowPattern {
+    /// Returns the names of the pattern variables, in lexical order
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut variables = BTreeSet::new();
+        self.collect_variables(&mut variables);
+        variables
+    }
+
+    fn collect_variables<'a>(&'a self, variables: &mut BTreeSet<&'a str>) {
+        match self {
+            RowPattern::Variable(name) => {
+                variables.insert(name);
+            }
+            RowPattern::PartitionStart | RowPattern::PartitionEnd => {}
+            RowPattern::Exclude(pattern) | RowPattern::Repetition { pattern, .. } => {
+                pattern.collect_variables(variables)
+            }
+            RowPattern::Permute(patterns)
+            | RowPattern::Concat(patterns)
+            | RowPattern::Alternation(patterns) => patterns
+                .iter()
+                .for_each(|pattern| pattern.collect_variables(variables)),
+        }
+    }
+}
+
+impl Display for RowPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Parenthesize nested patterns that bind less tightly
+        let fmt_nested = |f: &mut Formatter<'_>, pattern: &RowPattern| match pattern {
+            RowPattern::Concat(_) | RowPattern::Alternation(_) => {
+                write!(f, "({pattern})")
+            }
+            _ => write!(f, "{pattern}"),
+        };
+        match self {
+            RowPattern::Variable(name) => write!(f, "{name}"),
+            RowPattern::PartitionStart => write!(f, "^"),
+            RowPattern::PartitionEnd => write!(f, "$"),
+            RowPattern::Exclude(pattern) => write!(f, "{{- {pattern} -}}"),
+            RowPattern::Permute(patterns) => {
+                write!(f, "PERMUTE(")?;
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{pattern}")?;
+                }
+                write!(f, ")")
+            }
+            RowPattern::Concat(patterns) => {
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    match pattern {
+                        RowPattern::Alternation(_) => write!(f, "({pattern})")?,
+                        _ => write!(f, "{pattern}")?,
+                    }
+                }
+                Ok(())
+            }
+            RowPattern::Alternation(patterns) => {
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{pattern}")?;
+                }
+                Ok(())
+            }
+            RowPattern::Repetition { pattern, min, max } => {
+                fmt_nested(f, pattern)?;
+                match (min, max) {
+                    (0, None) => write!(f, "*"),
+                    (1, None) => write!(f, "+"),
+                    (0, Some(1)) => write!(f, "?"),
+                    (min, None) => write!(f, "{{{min},}}"),
+                    (min, Some(max)) if min == max => write!(f, "{{{min}}}"),
+                    (min, Some(max)) => write!(f, "{{{min},{max}}}"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str) -> RowPattern {
+        RowPattern::Variable(name.to_string())
+    }
+
+    fn repeat(pattern: RowPattern, min: u32, max: Option<u32>) -> RowPattern {
+        RowPattern::Repetition {
+            pattern: Box::new(pattern),
+            min,
+            max,
+        }
+    }
+
+    #[test]
+    fn display_row_pattern() {
+        let pattern = RowPattern::Concat(vec![
+            RowPattern::PartitionStart,
+            var("A"),
+            repeat(RowPattern::Alternation(vec![var("B"), var("C")]), 1, None),
+            RowPattern::Exclude(Box::new(var("D"))),
+            repeat(RowPattern::Concat(vec![var("E"), var("F")]), 2, Some(3)),
+            repeat(var("G"), 0, Some(1)),
+            RowPattern::Permute(vec![var("X"), var("Y")]),
+            RowPattern::PartitionEnd,
+        ]);
+        assert_eq!(
+            pattern.to_string(),
+            "^ A (B | C)+ {- D -} (E F){2,3} G? PERMUTE(X, Y) $"
+        );
+        assert_eq!(
+            pattern.variables().into_iter().collect::<Vec<_>>(),
+            vec!["A", "B", "C", "D", "E", "F", "G", "X", "Y"]
+        );
+    }
+}
@@ -21,6 +21,7 @@ pub mod display;
 pub mod dml;
 mod extension;
 pub(crate) mod invariants;
+mod match_recognize;
 pub use invariants::{InvariantLevel, assert_expected_schema, check_subquery_expr};
 mod plan;
 mod statement;
@@ -32,18 +33,23 @@ pub use builder::{
     wrap_projection_for_join_if_necessary,
 };
 pub use ddl::{
-    CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateFunction,
-    CreateFunctionBody, CreateIndex, CreateMemoryTable, CreateView, DdlStatement,
-    DropCatalogSchema, DropFunction, DropTable, DropView, OperateFunctionArg,
+    AlterTable, AlterTableOperation, AnalyzeTable, CreateCatalog, CreateCatalogSchema,
+    CreateExternalTable, CreateFunction, CreateFunctionBody, CreateIndex,
+    CreateMemoryTable, CreateView, DdlStatement, DropCatalogSchema, DropFunction,
+    DropTable, DropView, OperateFunctionArg, RefreshMaterializedView,
+};
+pub use dml::{DmlStatement, MERGE_ACTION_COLUMN, MergeAction, WriteOp};
+pub use match_recognize::{
+    AfterMatchSkip, MatchRecognize, RowPattern, RowPatternDefinition, RowPatternFunction,
+    RowPatternMeasure, RowsPerMatch,
 };
-pub use dml::{DmlStatement, WriteOp};
 pub use plan::{
     Aggregate, Analyze, ColumnUnnestList, DescribeTable, Distinct, DistinctOn,
     EmptyRelation, Explain, ExplainOption, Extension, FetchType, Filter, Join,
     JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Projection,
-    RangePartitioning, RecursiveQuery, Repartition, SkipType, Sort, StringifiedPlan,
-    Subquery, SubqueryAlias, TableScan, TableScanBuilder, ToStringifiedPlan, Union,
-    Unnest, Values, Window, projection_schema,
+    RangePartitioning, RecursiveQuery, Repartition, Sample, SampleMethod, SkipType, Sort,
+    StringifiedPlan, Subquery, SubqueryAlias, TableScan, TableScanBuilder,
+    ToStringifiedPlan, Union, Unnest, Values, Window, projection_schema,
 };
 pub use statement::{
     Deallocate, Execute, Prepare, ResetVariable, SetVariable, Statement,
@@ -39,7 +39,7 @@ use crate::expr_rewriter::{
 };
 use crate::logical_plan::display::{GraphvizVisitor, IndentVisitor};
 use crate::logical_plan::extension::UserDefinedLogicalNode;
-use crate::logical_plan::{DmlStatement, Statement};
+use crate::logical_plan::{DmlStatement, MatchRecognize, Statement};
 use crate::utils::{
     enumerate_grouping_sets, exprlist_to_fields, find_out_reference_exprs,
     grouping_set_expr_count, grouping_set_to_exprlist, merge_schema, split_conjunction,
@@ -260,6 +260,11 @@ pub enum LogicalPlan {
     SubqueryAlias(SubqueryAlias),
     /// Skip some number of rows, and then fetch some number of rows.
     Limit(Limit),
+    /// Returns a random sample of the input rows. This is used to implement
+    /// SQL `TABLESAMPLE`.
+    ///
+    /// See [`Sample`] for more details
+    Sample(Sample),
     /// A DataFusion [`Statement`] such as `SET VARIABLE` or `START TRANSACTION`
     Statement(Statement),
     /// Values expression. See
@@ -294,6 +299,11 @@ pub enum LogicalPlan {
     Unnest(Unnest),
     /// A variadic query (e.g. "Recursive CTEs")
     RecursiveQuery(RecursiveQuery),
+    /// Finds sequences of rows that match a pattern. This is used to
+    /// implement SQL `MATCH_RECOGNIZE`.
+    ///
+    /// See [`MatchRecognize`] for more details
+    MatchRecognize(MatchRecognize),
 }
 
 impl Default for LogicalPlan {
@@ -343,6 +353,7 @@ impl LogicalPlan {
             LogicalPlan::Join(Join { schema, .. }) => schema,
             LogicalPlan::Repartition(Repartition { input, .. }) => input.schema(),
             LogicalPlan::Limit(Limit { input, .. }) => input.schema(),
+            LogicalPlan::Sample(Sample { input, .. }) => input.schema(),
             LogicalPlan::Statement(statement) => statement.schema(),
             LogicalPlan::Subquery(Subquery { subquery, .. }) => subquery.schema(),
             LogicalPlan::SubqueryAlias(SubqueryAlias { schema, .. }) => schema,
@@ -358,6 +369,7 @@ impl LogicalPlan {
             LogicalPlan::Ddl(ddl) => ddl.schema(),
             LogicalPlan::Unnest(Unnest { schema, .. }) => schema,
             LogicalPlan::RecursiveQuery(RecursiveQuery { schema, .. }) => schema,
+            LogicalPlan::MatchRecognize(MatchRecognize { schema, .. }) => schema,
         }
     }
 
@@ -369,6 +381,7 @@ impl LogicalPlan {
             | LogicalPlan::Projection(_)
             | LogicalPlan::Aggregate(_)
             | LogicalPlan::Unnest(_)
+            | LogicalPlan::MatchRecognize(_)
             | LogicalPlan::Join(_) => self
                 .inputs()
                 .iter()
@@ -460,6 +473,7 @@ impl LogicalPlan {
             LogicalPlan::Sort(Sort { input, .. }) => vec![input],
             LogicalPlan::Join(Join { left, right, .. }) => vec![left, right],
             LogicalPlan::Limit(Limit { input, .. }) => vec![input],
+            LogicalPlan::Sample(Sample { input, .. }) => vec![input],
             LogicalPlan::Subquery(Subquery { subquery, .. }) => vec![subquery],
             LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => vec![input],
             LogicalPlan::Extension(extension) => extension.node.inputs(),
@@ -475,6 +489,7 @@ impl LogicalPlan {
             LogicalPlan::Copy(copy) => vec![&copy.input],
             LogicalPlan::Ddl(ddl) => ddl.inputs(),
             LogicalPlan::Unnest(Unnest { input, .. }) => vec![input],
+            LogicalPlan::MatchRecognize(MatchRecognize { input, .. }) => vec![input],
             LogicalPlan::RecursiveQuery(RecursiveQuery {
                 static_term,
                 recursive_term,
@@ -545,6 +560,7 @@ impl LogicalPlan {
             | LogicalPlan::Distinct(Distinct::All(input))
             | LogicalPlan::Sort(Sort { input, .. })
             | LogicalPlan::Limit(Limit { input, .. })
+            | LogicalPlan::Sample(Sample { input, .. })
             | LogicalPlan::Repartition(Repartition { input, .. })
             | LogicalPlan::Window(Window { input, .. }) => input.head_output_expr(),
             LogicalPlan::Join(Join {
@@ -560,9 +576,10 @@ impl LogicalPlan {
                         left.head_output_expr()
                     }
                 }
-                JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-                    left.head_output_expr()
-                }
+                JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+                | JoinType::AsOf => left.head_output_expr(),
                 JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
                     right.head_output_expr()
                 }
@@ -598,7 +615,8 @@ impl LogicalPlan {
             | LogicalPlan::Copy(_)
             | LogicalPlan::Ddl(_)
             | LogicalPlan::DescribeTable(_)
-            | LogicalPlan::Unnest(_) => Ok(None),
+            | LogicalPlan::Unnest(_)
+            | LogicalPlan::MatchRecognize(_) => Ok(None),
         }
     }
 
@@ -697,6 +715,7 @@ impl LogicalPlan {
                 schema: _,
             }) => SubqueryAlias::try_new(input, alias).map(LogicalPlan::SubqueryAlias),
             LogicalPlan::Limit(_) => Ok(self),
+            LogicalPlan::Sample(_) => Ok(self),
             LogicalPlan::Ddl(_) => Ok(self),
             LogicalPlan::Extension(Extension { node }) => {
                 // todo make an API that does not require cloning
@@ -764,6 +783,27 @@ impl LogicalPlan {
                 // Update schema with unnested column type.
                 unnest_with_options(Arc::unwrap_or_clone(input), exec_columns, options)
             }
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                input,
+                partition_by,
+                order_by,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                pattern,
+                defines,
+                schema: _,
+            }) => MatchRecognize::try_new(
+                input,
+                partition_by,
+                order_by,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                *pattern,
+                defines,
+            )
+            .map(LogicalPlan::MatchRecognize),
         }
     }
 
@@ -1024,6 +1064,15 @@ impl LogicalPlan {
                     input: Arc::new(input),
                 }))
             }
+            LogicalPlan::Sample(Sample { method, seed, .. }) => {
+                self.assert_no_expressions(expr)?;
+                let input = self.only_input(inputs)?;
+                Ok(LogicalPlan::Sample(Sample {
+                    method: *method,
+                    seed: *seed,
+                    input: Arc::new(input),
+                }))
+            }
             LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(CreateMemoryTable {
                 name,
                 if_not_exists,
@@ -1051,6 +1100,8 @@ impl LogicalPlan {
                 or_replace,
                 definition,
                 temporary,
+                materialized,
+                location,
                 ..
             })) => {
                 self.assert_no_expressions(expr)?;
@@ -1061,6 +1112,8 @@ impl LogicalPlan {
                     or_replace: *or_replace,
                     temporary: *temporary,
                     definition: definition.clone(),
+                    materialized: *materialized,
+                    location: location.clone(),
                 })))
             }
             LogicalPlan::Extension(e) => Ok(LogicalPlan::Extension(Extension {
@@ -1193,6 +1246,12 @@ impl LogicalPlan {
                     unnest_with_options(input, columns.clone(), options.clone())?;
                 Ok(new_plan)
             }
+            LogicalPlan::MatchRecognize(match_recognize) => {
+                let input = self.only_input(inputs)?;
+                match_recognize
+                    .with_new_exprs(expr, input)
+                    .map(LogicalPlan::MatchRecognize)
+            }
         }
     }
 
@@ -1395,9 +1454,10 @@ impl LogicalPlan {
                         (left_max, right_max, _) => Some(left_max * right_max),
                     }
                 }
-                JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-                    left.max_rows()
-                }
+                JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+                | JoinType::AsOf => left.max_rows(),
                 JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
                     right.max_rows()
                 }
@@ -1418,11 +1478,17 @@ impl LogicalPlan {
                 Ok(FetchType::Literal(s)) => s,
                 _ => None,
             },
+            LogicalPlan::Sample(Sample { method, input, .. }) => match method {
+                SampleMethod::Rows(n) => {
+                    Some(input.max_rows().map_or(*n, |max| max.min(*n)))
+                }
+                SampleMethod::Bernoulli(_) | SampleMethod::System(_) => input.max_rows(),
+            },
             LogicalPlan::Distinct(
                 Distinct::All(input) | Distinct::On(DistinctOn { input, .. }),
             ) => input.max_rows(),
             LogicalPlan::Values(v) => Some(v.values.len()),
-            LogicalPlan::Unnest(_) => None,
+            LogicalPlan::Unnest(_) | LogicalPlan::MatchRecognize(_) => None,
             LogicalPlan::Ddl(_)
             | LogicalPlan::Explain(_)
             | LogicalPlan::Analyze(_)
@@ -1469,6 +1535,8 @@ impl LogicalPlan {
             LogicalPlan::DescribeTable(_) => Ok(None),
             LogicalPlan::Unnest(_) => Ok(None),
             LogicalPlan::RecursiveQuery(_) => Ok(None),
+            LogicalPlan::MatchRecognize(_) => Ok(None),
+            LogicalPlan::Sample(_) => Ok(None),
         }
     }
 
@@ -1507,6 +1575,8 @@ impl LogicalPlan {
             LogicalPlan::DescribeTable(_) => Ok(None),
             LogicalPlan::Unnest(_) => Ok(None),
             LogicalPlan::RecursiveQuery(_) => Ok(None),
+            LogicalPlan::MatchRecognize(_) => Ok(None),
+            LogicalPlan::Sample(_) => Ok(None),
         }
     }
 
@@ -2162,6 +2232,13 @@ impl LogicalPlan {
                         };
                         write!(f, "Limit: skip={skip_str}, fetch={fetch_str}",)
                     }
+                    LogicalPlan::Sample(Sample { method, seed, .. }) => {
+                        write!(f, "Sample: method={method}")?;
+                        if let Some(seed) = seed {
+                            write!(f, ", seed={seed}")?;
+                        }
+                        Ok(())
+                    }
                     LogicalPlan::Subquery(Subquery { .. }) => {
                         write!(f, "Subquery:")
                     }
@@ -2226,6 +2303,25 @@ impl LogicalPlan {
                             expr_vec_fmt!(struct_type_columns)
                         )
                     }
+                    LogicalPlan::MatchRecognize(MatchRecognize {
+                        partition_by,
+                        order_by,
+                        measures,
+                        rows_per_match,
+                        after_match_skip,
+                        pattern,
+                        defines,
+                        ..
+                    }) => {
+                        write!(
+                            f,
+                            "MatchRecognize: partitionBy=[{}], orderBy=[{}], measures=[{}], {rows_per_match}, AFTER MATCH SKIP {after_match_skip}, pattern=[{pattern}], define=[{}]",
+                            expr_vec_fmt!(partition_by),
+                            expr_vec_fmt!(order_by),
+                            expr_vec_fmt!(measures),
+                            expr_vec_fmt!(defines)
+                        )
+                    }
                 }
             }
         }
@@ -3428,6 +3524,9 @@ impl PartialOrd for Union {
 /// | parent_span_id     | Utf8                        | YES         |
 /// +--------------------+-----------------------------+-------------+
 /// ```
+///
+/// For CSV tables whose dialect was detected while inferring the schema, the
+/// columns are followed by one `csv.dialect.*` row per detected setting.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DescribeTable {
     /// Table schema
@@ -3718,6 +3817,121 @@ impl Limit {
     }
 }
 
+/// Returns a random sample of the rows of its input. This is used to
+/// implement SQL `TABLESAMPLE`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct Sample {
+    /// How rows are sampled
+    pub method: SampleMethod,
+    /// Seed for the random number generator. If `None`, a random seed is
+    /// chosen each time the plan is executed
+    pub seed: Option<u64>,
+    /// The logical plan
+    pub input: Arc<LogicalPlan>,
+}
+
+impl Sample {
+    /// Create a new [`Sample`] node, validating the sampling parameters
+    pub fn try_new(
+        input: Arc<LogicalPlan>,
+        method: SampleMethod,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        match method {
+            SampleMethod::Bernoulli(fraction) | SampleMethod::System(fraction)
+                if !(0.0..=1.0).contains(&fraction) =>
+            {
+                plan_err!("Sample fraction must be between 0 and 1, got {fraction}")
+            }
+            _ => Ok(Self {
+                method,
+                seed,
+                input,
+            }),
+        }
+    }
+}
+
+/// The sampling method used by a [`Sample`] plan
+#[derive(Debug, Clone, Copy)]
+pub enum SampleMethod {
+    /// Each row is included independently with the given probability
+    /// (`TABLESAMPLE BERNOULLI`)
+    Bernoulli(f64),
+    /// Each block of rows (a file, Parquet row group or batch) is included
+    /// independently with the given probability (`TABLESAMPLE SYSTEM`).
+    ///
+    /// This is cheaper than [`Self::Bernoulli`] as blocks that are not
+    /// sampled do not have to be read, but the sample is less random.
+    System(f64),
+    /// A uniformly random sample of exactly the given number of rows, or all
+    /// rows if the input has fewer (`TABLESAMPLE (n ROWS)`)
+    Rows(usize),
+}
+
+impl SampleMethod {
+    fn discriminant(&self) -> u8 {
+        match self {
+            SampleMethod::Bernoulli(_) => 0,
+            SampleMethod::System(_) => 1,
+            SampleMethod::Rows(_) => 2,
+        }
+    }
+
+    /// The value of this method as bits, so that it can be hashed and compared
+    fn value_bits(&self) -> u64 {
+        match self {
+            SampleMethod::Bernoulli(fraction) | SampleMethod::System(fraction) => {
+                fraction.to_bits()
+            }
+            SampleMethod::Rows(n) => *n as u64,
+        }
+    }
+}
+
+impl PartialEq for SampleMethod {
+    fn eq(&self, other: &Self) -> bool {
+        self.discriminant() == other.discriminant()
+            && self.value_bits() == other.value_bits()
+    }
+}
+
+impl Eq for SampleMethod {}
+
+impl Hash for SampleMethod {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.discriminant().hash(state);
+        self.value_bits().hash(state);
+    }
+}
+
+impl PartialOrd for SampleMethod {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let ordering = self.discriminant().cmp(&other.discriminant());
+        if ordering != Ordering::Equal {
+            return Some(ordering);
+        }
+        match (self, other) {
+            (SampleMethod::Rows(a), SampleMethod::Rows(b)) => Some(a.cmp(b)),
+            (
+                SampleMethod::Bernoulli(a) | SampleMethod::System(a),
+                SampleMethod::Bernoulli(b) | SampleMethod::System(b),
+            ) => Some(a.total_cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SampleMethod {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SampleMethod::Bernoulli(fraction) => write!(f, "bernoulli({fraction})"),
+            SampleMethod::System(fraction) => write!(f, "system({fraction})"),
+            SampleMethod::Rows(n) => write!(f, "rows({n})"),
+        }
+    }
+}
+
 /// Removes duplicate rows from the input
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum Distinct {
@@ -4295,6 +4509,7 @@ impl Join {
             || original_join.join_type == JoinType::Left
             || original_join.join_type == JoinType::Right
             || original_join.join_type == JoinType::Full
+            || original_join.join_type == JoinType::AsOf
         {
             (left_sch, right_sch, requalified) =
                 requalify_sides_if_needed(left_sch.clone(), right_sch.clone())?;
@@ -28,9 +28,10 @@ use crate::{Expr, LogicalPlan, expr_vec_fmt};
 ///
 /// # Transactions:
 ///
-/// While DataFusion does not offer support transactions, it provides
-/// [`LogicalPlan`] support to assist building database systems
-/// using DataFusion
+/// `SessionContext` runs [`TransactionStart`] and [`TransactionEnd`] with
+/// snapshot isolation for the tables that support transactions, such as
+/// `MemTable`. Systems built on DataFusion can also plan these statements and
+/// handle them themselves.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum Statement {
     // Begin a transaction
@@ -41,9 +41,9 @@ use crate::logical_plan::plan::RangePartitioning;
 use crate::{
     Aggregate, Analyze, CreateMemoryTable, CreateView, DdlStatement, Distinct,
     DistinctOn, DmlStatement, Execute, Explain, Expr, Extension, Filter, Join, Limit,
-    LogicalPlan, Partitioning, Prepare, Projection, RecursiveQuery, Repartition, Sort,
-    Statement, Subquery, SubqueryAlias, TableScan, Union, Unnest, UserDefinedLogicalNode,
-    Values, Window, dml::CopyTo,
+    LogicalPlan, MatchRecognize, Partitioning, Prepare, Projection, RecursiveQuery,
+    Repartition, Sample, Sort, Statement, Subquery, SubqueryAlias, TableScan, Union,
+    Unnest, UserDefinedLogicalNode, Values, Window, dml::CopyTo,
 };
 use datafusion_common::tree_node::TreeNodeRefContainer;
 
@@ -151,6 +151,17 @@ impl TreeNode for LogicalPlan {
             LogicalPlan::Limit(Limit { skip, fetch, input }) => input
                 .map_elements(f)?
                 .update_data(|input| LogicalPlan::Limit(Limit { skip, fetch, input })),
+            LogicalPlan::Sample(Sample {
+                method,
+                seed,
+                input,
+            }) => input.map_elements(f)?.update_data(|input| {
+                LogicalPlan::Sample(Sample {
+                    method,
+                    seed,
+                    input,
+                })
+            }),
             LogicalPlan::Subquery(Subquery {
                 subquery,
                 outer_ref_columns,
@@ -292,6 +303,8 @@ impl TreeNode for LogicalPlan {
                         or_replace,
                         definition,
                         temporary,
+                        materialized,
+                        location,
                     }) => input.map_elements(f)?.update_data(|input| {
                         DdlStatement::CreateView(CreateView {
                             name,
@@ -299,6 +312,8 @@ impl TreeNode for LogicalPlan {
                             or_replace,
                             definition,
                             temporary,
+                            materialized,
+                            location,
                         })
                     }),
                     // no inputs in these statements
@@ -308,9 +323,12 @@ impl TreeNode for LogicalPlan {
                     | DdlStatement::CreateIndex(_)
                     | DdlStatement::DropTable(_)
                     | DdlStatement::DropView(_)
+                    | DdlStatement::RefreshMaterializedView(_)
                     | DdlStatement::DropCatalogSchema(_)
                     | DdlStatement::CreateFunction(_)
-                    | DdlStatement::DropFunction(_) => Transformed::no(ddl),
+                    | DdlStatement::DropFunction(_)
+                    | DdlStatement::AlterTable(_)
+                    | DdlStatement::AnalyzeTable(_) => Transformed::no(ddl),
                 }
                 .update_data(LogicalPlan::Ddl)
             }
@@ -361,6 +379,29 @@ impl TreeNode for LogicalPlan {
                 _ => Transformed::no(stmt),
             }
             .update_data(LogicalPlan::Statement),
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                input,
+                partition_by,
+                order_by,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                pattern,
+                defines,
+                schema,
+            }) => input.map_elements(f)?.update_data(|input| {
+                LogicalPlan::MatchRecognize(MatchRecognize {
+                    input,
+                    partition_by,
+                    order_by,
+                    measures,
+                    rows_per_match,
+                    after_match_skip,
+                    pattern,
+                    defines,
+                    schema,
+                })
+            }),
             // plans without inputs
             LogicalPlan::TableScan { .. }
             | LogicalPlan::EmptyRelation { .. }
@@ -478,6 +519,9 @@ impl LogicalPlan {
                 }
                 _ => Ok(TreeNodeRecursion::Continue),
             },
+            LogicalPlan::MatchRecognize(match_recognize) => {
+                match_recognize.apply_elements(f)
+            }
             // plans without expressions
             LogicalPlan::EmptyRelation(_)
             | LogicalPlan::RecursiveQuery(_)
@@ -487,6 +531,7 @@ impl LogicalPlan {
             | LogicalPlan::Explain(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::Distinct(Distinct::All(_))
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Dml(_)
             | LogicalPlan::Ddl(_)
             | LogicalPlan::Copy(_)
@@ -686,6 +731,9 @@ impl LogicalPlan {
                 _ => Transformed::no(stmt),
             }
             .update_data(LogicalPlan::Statement),
+            LogicalPlan::MatchRecognize(match_recognize) => match_recognize
+                .map_elements(f)?
+                .update_data(LogicalPlan::MatchRecognize),
             // plans without expressions
             LogicalPlan::EmptyRelation(_)
             | LogicalPlan::Unnest(_)
@@ -696,6 +744,7 @@ impl LogicalPlan {
             | LogicalPlan::Explain(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::Distinct(Distinct::All(_))
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Dml(_)
             | LogicalPlan::Ddl(_)
             | LogicalPlan::Copy(_)
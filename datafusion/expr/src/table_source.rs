@@ -20,7 +20,7 @@
 use crate::{Expr, LogicalPlan};
 
 use arrow::datatypes::SchemaRef;
-use datafusion_common::{Constraints, Result};
+use datafusion_common::{Constraints, Result, Statistics};
 
 use std::{any::Any, borrow::Cow};
 
@@ -116,6 +116,13 @@ pub trait TableSource: Any + Sync + Send {
             .collect())
     }
 
+    /// Get statistics for this table, if available.
+    ///
+    /// Used by cost-based optimizer rules such as eager aggregation.
+    fn statistics(&self) -> Option<Statistics> {
+        None
+    }
+
     /// Get the Logical plan of this table provider, if available.
     ///
     /// For example, a view may have a logical plan, but a CSV file does not.
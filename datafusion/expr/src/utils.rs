@@ -959,6 +959,40 @@ pub fn find_valid_equijoin_key_pair(
     Ok(None)
 }
 
+/// Splits the match condition of an as-of join into `(left, operator, right)`.
+///
+/// A valid match condition is a single `<`, `<=`, `>` or `>=` comparison
+/// between an expression of the left input and an expression of the right
+/// input. The comparison is flipped when needed so that the returned `left`
+/// expression always refers to the left input.
+pub fn find_asof_match_condition(
+    match_condition: &Expr,
+    left_schema: &DFSchema,
+    right_schema: &DFSchema,
+) -> Result<(Expr, Operator, Expr)> {
+    if let Expr::BinaryExpr(BinaryExpr { left, op, right }) = match_condition
+        && matches!(
+            op,
+            Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+        )
+        && let Some((left_key, right_key)) =
+            find_valid_equijoin_key_pair(left, right, left_schema, right_schema)?
+    {
+        let op = if &left_key == left.as_ref() {
+            *op
+        } else {
+            // The operator is one of the comparisons above, which can all be swapped
+            op.swap().unwrap()
+        };
+        return Ok((left_key, op, right_key));
+    }
+
+    plan_err!(
+        "As-of join match condition must compare an expression of the left input \
+         with an expression of the right input using <, <=, > or >=, got {match_condition}"
+    )
+}
+
 /// Creates a detailed error message for a function with wrong signature.
 ///
 /// For example, a query like `select round(3.14, 1.1);` would yield:
@@ -50,6 +50,9 @@ impl From<&Partitioning> for FFI_Partitioning {
             Partitioning::Range(range) => {
                 Self::UnknownPartitioning(range.partition_count())
             }
+            // FFI does not expose bucket partitioning, whose partitions must
+            // not be mistaken for hash partitions
+            Partitioning::Bucket(_, size) => Self::UnknownPartitioning(*size),
             Partitioning::UnknownPartitioning(size) => Self::UnknownPartitioning(*size),
         }
     }
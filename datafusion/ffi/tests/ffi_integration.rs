@@ -103,6 +103,7 @@ mod tests {
             location: "test".to_string(),
             file_type: "test".to_string(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             if_not_exists: false,
             or_replace: false,
             temporary: false,
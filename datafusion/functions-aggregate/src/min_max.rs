@@ -19,17 +19,19 @@
 //! [`Min`] and [`MinAccumulator`] accumulator for the `min` function
 
 mod min_max_bytes;
+mod min_max_collated;
 mod min_max_struct;
 
 use arrow::array::ArrayRef;
 use arrow::datatypes::{
     DataType, Decimal32Type, Decimal64Type, Decimal128Type, Decimal256Type,
     DurationMicrosecondType, DurationMillisecondType, DurationNanosecondType,
-    DurationSecondType, Float16Type, Float32Type, Float64Type, Int8Type, Int16Type,
-    Int32Type, Int64Type, UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+    DurationSecondType, Field, FieldRef, Float16Type, Float32Type, Float64Type, Int8Type,
+    Int16Type, Int32Type, Int64Type, UInt8Type, UInt16Type, UInt32Type, UInt64Type,
 };
+use datafusion_common::collation::Collation;
 use datafusion_common::stats::Precision;
-use datafusion_common::{ColumnStatistics, Result, exec_err, internal_err};
+use datafusion_common::{ColumnStatistics, Result, exec_err, internal_err, not_impl_err};
 use datafusion_functions_aggregate_common::aggregate::groups_accumulator::prim_op::PrimitiveGroupsAccumulator;
 use datafusion_physical_expr::expressions;
 use std::cmp::Ordering;
@@ -43,6 +45,7 @@ use arrow::datatypes::{
 };
 
 use crate::min_max::min_max_bytes::MinMaxBytesAccumulator;
+use crate::min_max::min_max_collated::MinMaxCollatedAccumulator;
 use crate::min_max::min_max_struct::MinMaxStructAccumulator;
 use datafusion_common::ScalarValue;
 use datafusion_expr::{
@@ -54,6 +57,7 @@ use datafusion_macros::user_doc;
 use half::f16;
 use std::mem::size_of_val;
 use std::ops::Deref;
+use std::sync::Arc;
 
 fn get_min_max_result_type(input_types: &[DataType]) -> Result<Vec<DataType>> {
     // make sure that the input types only has one element.
@@ -76,6 +80,23 @@ fn get_min_max_result_type(input_types: &[DataType]) -> Result<Vec<DataType>> {
     }
 }
 
+/// Returns the return field of `min` and `max`, which keeps the collation of
+/// the argument
+fn min_max_return_field(name: &str, arg_fields: &[FieldRef]) -> Result<FieldRef> {
+    let field = Field::new(name, arg_fields[0].data_type().clone(), true);
+    let collation = Collation::from_field(&arg_fields[0])?;
+    if collation.is_binary() {
+        return Ok(Arc::new(field));
+    }
+    Ok(Arc::new(collation.apply_to_field(field)?))
+}
+
+/// Returns the non binary collation of the return field of `min` and `max`
+fn non_binary_collation(args: &AccumulatorArgs) -> Result<Option<Collation>> {
+    let collation = Collation::from_field(&args.return_field)?;
+    Ok((!collation.is_binary()).then_some(collation))
+}
+
 #[user_doc(
     doc_section(label = "General Functions"),
     description = "Returns the maximum value in the specified column.",
@@ -214,14 +235,25 @@ impl AggregateUDFImpl for Max {
         Ok(arg_types[0].to_owned())
     }
 
+    fn return_field(&self, arg_fields: &[FieldRef]) -> Result<FieldRef> {
+        min_max_return_field(self.name(), arg_fields)
+    }
+
     fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
-        Ok(Box::new(MaxAccumulator::try_new(
-            acc_args.return_field.data_type(),
-        )?))
+        let data_type = acc_args.return_field.data_type();
+        if let Some(collation) = non_binary_collation(&acc_args)? {
+            return Ok(Box::new(MinMaxCollatedAccumulator::try_new(
+                data_type, collation, false,
+            )?));
+        }
+        Ok(Box::new(MaxAccumulator::try_new(data_type)?))
     }
 
     fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
         use DataType::*;
+        if !matches!(non_binary_collation(&args), Ok(None)) {
+            return false;
+        }
         matches!(
             args.return_field.data_type(),
             Int8 | Int16
@@ -344,6 +376,11 @@ impl AggregateUDFImpl for Max {
         &self,
         args: AccumulatorArgs,
     ) -> Result<Box<dyn Accumulator>> {
+        if let Some(collation) = non_binary_collation(&args)? {
+            return not_impl_err!(
+                "max over a sliding window frame does not support collation {collation}"
+            );
+        }
         Ok(Box::new(SlidingMaxAccumulator::try_new(
             args.return_field.data_type(),
         )?))
@@ -497,14 +534,25 @@ impl AggregateUDFImpl for Min {
         Ok(arg_types[0].to_owned())
     }
 
+    fn return_field(&self, arg_fields: &[FieldRef]) -> Result<FieldRef> {
+        min_max_return_field(self.name(), arg_fields)
+    }
+
     fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
-        Ok(Box::new(MinAccumulator::try_new(
-            acc_args.return_field.data_type(),
-        )?))
+        let data_type = acc_args.return_field.data_type();
+        if let Some(collation) = non_binary_collation(&acc_args)? {
+            return Ok(Box::new(MinMaxCollatedAccumulator::try_new(
+                data_type, collation, true,
+            )?));
+        }
+        Ok(Box::new(MinAccumulator::try_new(data_type)?))
     }
 
     fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
         use DataType::*;
+        if !matches!(non_binary_collation(&args), Ok(None)) {
+            return false;
+        }
         matches!(
             args.return_field.data_type(),
             Int8 | Int16
@@ -627,6 +675,11 @@ impl AggregateUDFImpl for Min {
         &self,
         args: AccumulatorArgs,
     ) -> Result<Box<dyn Accumulator>> {
+        if let Some(collation) = non_binary_collation(&args)? {
+            return not_impl_err!(
+                "min over a sliding window frame does not support collation {collation}"
+            );
+        }
         Ok(Box::new(SlidingMinAccumulator::try_new(
             args.return_field.data_type(),
         )?))
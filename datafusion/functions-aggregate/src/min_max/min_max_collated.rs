@@ -0,0 +1,173 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::mem::size_of_val;
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::datatypes::DataType;
+use datafusion_common::collation::Collation;
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::Accumulator;
+
+/// Accumulator for MIN/MAX operations on strings with a non binary
+/// [`Collation`].
+///
+/// Values are compared by their collation keys, and the original value of the
+/// minimum or maximum key is returned. If several values have the same key
+/// (for example `'a'` and `'A'` with a case-insensitive collation), the first
+/// one seen is returned.
+#[derive(Debug)]
+pub(super) struct MinMaxCollatedAccumulator {
+    /// The collation used to compare values
+    collation: Collation,
+    /// Whether this accumulator computes the minimum or the maximum
+    is_min: bool,
+    /// The current minimum or maximum value
+    value: ScalarValue,
+    /// The collation key of `value`, `None` if no value has been seen
+    key: Option<String>,
+}
+
+impl MinMaxCollatedAccumulator {
+    pub(super) fn try_new(
+        data_type: &DataType,
+        collation: Collation,
+        is_min: bool,
+    ) -> Result<Self> {
+        Ok(Self {
+            collation,
+            is_min,
+            value: ScalarValue::try_from(data_type)?,
+            key: None,
+        })
+    }
+}
+
+/// Returns the value of the string array `keys` at `row`
+fn key_value(keys: &ArrayRef, row: usize) -> &str {
+    match keys.data_type() {
+        DataType::Utf8 => keys.as_string::<i32>().value(row),
+        DataType::LargeUtf8 => keys.as_string::<i64>().value(row),
+        _ => keys.as_string_view().value(row),
+    }
+}
+
+impl Accumulator for MinMaxCollatedAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        let keys = self.collation.keys(values)?;
+        for row in (0..keys.len()).filter(|row| keys.is_valid(*row)) {
+            let key = key_value(&keys, row);
+            let replace = match &self.key {
+                None => true,
+                Some(current) if self.is_min => key < current.as_str(),
+                Some(current) => key > current.as_str(),
+            };
+            if replace {
+                self.key = Some(key.to_string());
+                self.value = ScalarValue::try_from_array(values, row)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(self.value.clone())
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) - size_of_val(&self.value)
+            + self.value.size()
+            + self.key.as_ref().map(String::capacity).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+    use datafusion_common::collation::CollationStrength;
+    use std::sync::Arc;
+
+    fn min_max(
+        collation: Collation,
+        is_min: bool,
+        values: &[&str],
+    ) -> Result<ScalarValue> {
+        let mut acc =
+            MinMaxCollatedAccumulator::try_new(&DataType::Utf8, collation, is_min)?;
+        let (first, second) = values.split_at(values.len() / 2);
+        acc.update_batch(&[Arc::new(StringArray::from(first.to_vec()))])?;
+        let mut other =
+            MinMaxCollatedAccumulator::try_new(&DataType::Utf8, collation, is_min)?;
+        other.update_batch(&[Arc::new(StringArray::from(second.to_vec()))])?;
+        acc.merge_batch(&[other.state()?[0].to_array()?])?;
+        acc.evaluate()
+    }
+
+    #[test]
+    fn min_max_case_insensitive() -> Result<()> {
+        let values = ["b", "C", "a", "B", "A", "c"];
+        assert_eq!(
+            min_max(Collation::CaseInsensitive, true, &values)?,
+            ScalarValue::from("a")
+        );
+        assert_eq!(
+            min_max(Collation::CaseInsensitive, false, &values)?,
+            ScalarValue::from("C")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn min_max_accent_insensitive() -> Result<()> {
+        let collation = Collation::Unicode(CollationStrength::Primary);
+        let values = ["zebra", "Émile", "apple"];
+        assert_eq!(
+            min_max(collation, true, &values)?,
+            ScalarValue::from("apple")
+        );
+        assert_eq!(
+            min_max(collation, false, &values)?,
+            ScalarValue::from("zebra")
+        );
+
+        let values = ["ab", "Éz", "eb"];
+        assert_eq!(min_max(collation, false, &values)?, ScalarValue::from("Éz"));
+        Ok(())
+    }
+
+    #[test]
+    fn min_max_of_nulls() -> Result<()> {
+        let mut acc = MinMaxCollatedAccumulator::try_new(
+            &DataType::Utf8,
+            Collation::CaseInsensitive,
+            true,
+        )?;
+        acc.update_batch(&[Arc::new(StringArray::from(vec![None::<&str>, None]))])?;
+        assert_eq!(acc.evaluate()?, ScalarValue::Utf8(None));
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::collation::Collation;
+use datafusion_common::{Result, exec_err, internal_err, plan_datafusion_err};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl,
+    Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+
+#[user_doc(
+    doc_section(label = "Other Functions"),
+    description = "Sets the collation of a string expression, which is used to compare, sort and group its values. This is the function form of the SQL `expression COLLATE collation` syntax. Supported collations are `binary` (the default), `nocase` for case-insensitive comparison and Unicode collations named by language tags such as `und`, `en-u-ks-level2` (ignores case) or `und-u-ks-level1` (ignores case and accents).",
+    syntax_example = "collate(expression, collation)",
+    sql_example = r#"```sql
+> select column1 from (values ('b'), ('A'), ('a')) order by collate(column1, 'nocase'), column1;
++---------+
+| column1 |
++---------+
+| A       |
+| a       |
+| b       |
++---------+
+```"#,
+    argument(
+        name = "expression",
+        description = "String expression to set the collation of. Values flow through unchanged."
+    ),
+    argument(
+        name = "collation",
+        description = "Name of the collation. Must be a constant string literal."
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollateFunc {
+    signature: Signature,
+}
+
+impl Default for CollateFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CollateFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for CollateFunc {
+    fn name(&self) -> &str {
+        "collate"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("collate: return_type called instead of return_field_from_args")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        if args.arg_fields.len() != 2 {
+            return exec_err!(
+                "collate requires 2 arguments, got {}",
+                args.arg_fields.len()
+            );
+        }
+        let name = args.scalar_arguments[1]
+            .and_then(|sv| sv.try_as_str().flatten())
+            .ok_or_else(|| {
+                plan_datafusion_err!(
+                    "collate requires argument 1 (collation) to be a constant string"
+                )
+            })?;
+        let collation = Collation::try_new(name)?;
+
+        let input_field = &args.arg_fields[0];
+        let field = Field::new(
+            input_field.name(),
+            input_field.data_type().clone(),
+            input_field.is_nullable(),
+        )
+        .with_metadata(input_field.metadata().clone());
+        Ok(collation.apply_to_field(field)?.into())
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        // The collation is attached to the return field during planning, the
+        // values are unchanged
+        Ok(args.args[0].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion_common::ScalarValue;
+    use datafusion_common::collation::COLLATION_METADATA_KEY;
+    use std::sync::Arc;
+
+    fn return_field(input: DataType, collation: &str) -> Result<FieldRef> {
+        let fields = [
+            Arc::new(Field::new("name", input, true)),
+            Arc::new(Field::new("", DataType::Utf8, false)),
+        ];
+        let collation = ScalarValue::from(collation);
+        CollateFunc::new().return_field_from_args(ReturnFieldArgs {
+            arg_fields: &fields,
+            scalar_arguments: &[None, Some(&collation)],
+        })
+    }
+
+    #[test]
+    fn attaches_collation() -> Result<()> {
+        let field = return_field(DataType::Utf8View, "NOCASE")?;
+        assert_eq!(field.name(), "name");
+        assert_eq!(field.data_type(), &DataType::Utf8View);
+        assert_eq!(
+            field
+                .metadata()
+                .get(COLLATION_METADATA_KEY)
+                .map(String::as_str),
+            Some("nocase")
+        );
+
+        let field = return_field(DataType::Utf8, "binary")?;
+        assert!(field.metadata().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let err = return_field(DataType::Utf8, "klingon-u-xx").unwrap_err();
+        assert!(err.to_string().contains("Unsupported collation"), "{err}");
+
+        let err = return_field(DataType::Int32, "nocase").unwrap_err();
+        assert!(
+            err.to_string().contains("COLLATE requires a string"),
+            "{err}"
+        );
+    }
+}
@@ -27,6 +27,7 @@ pub mod arrow_try_cast;
 pub mod arrowtypeof;
 pub mod cast_to_type;
 pub mod coalesce;
+pub mod collate;
 pub mod expr_ext;
 pub mod getfield;
 pub mod greatest;
@@ -66,6 +67,7 @@ make_udf_function!(union_tag::UnionTagFunc, union_tag);
 make_udf_function!(version::VersionFunc, version);
 make_udf_function!(arrow_metadata::ArrowMetadataFunc, arrow_metadata);
 make_udf_function!(with_metadata::WithMetadataFunc, with_metadata);
+make_udf_function!(collate::CollateFunc, collate);
 make_udf_function!(arrow_field::ArrowFieldFunc, arrow_field);
 
 pub mod expr_fn {
@@ -119,6 +121,10 @@ pub mod expr_fn {
         with_metadata,
         "Attaches Arrow field metadata (key/value pairs) to the input expression",
         args,
+    ),(
+        collate,
+        "Sets the collation of a string expression, which is used to compare, sort and group its values",
+        arg1 arg2
     ),(
         r#struct,
         "Returns a struct with the given arguments",
@@ -176,6 +182,7 @@ pub fn functions() -> Vec<Arc<ScalarUDF>> {
         try_cast_to_type(),
         arrow_metadata(),
         with_metadata(),
+        collate(),
         nvl(),
         nvl2(),
         overlay(),
@@ -574,6 +574,7 @@ impl OptimizerRule for CommonSubexprEliminate {
             | LogicalPlan::Subquery(_)
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Limit(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Ddl(_)
             | LogicalPlan::Explain(_)
             | LogicalPlan::Analyze(_)
@@ -584,7 +585,8 @@ impl OptimizerRule for CommonSubexprEliminate {
             | LogicalPlan::Dml(_)
             | LogicalPlan::Copy(_)
             | LogicalPlan::Unnest(_)
-            | LogicalPlan::RecursiveQuery(_) => {
+            | LogicalPlan::RecursiveQuery(_)
+            | LogicalPlan::MatchRecognize(_) => {
                 // This rule handles recursion itself in a `ApplyOrder::TopDown` like
                 // manner. Process uncorrelated subqueries in expressions
                 // (e.g., Expr::ScalarSubquery), then direct children.
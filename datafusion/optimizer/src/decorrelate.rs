@@ -503,7 +503,7 @@ fn remove_duplicated_filter(
         .collect::<Vec<_>>())
 }
 
-fn agg_exprs_evaluation_result_on_empty_batch(
+pub(crate) fn agg_exprs_evaluation_result_on_empty_batch(
     agg_expr: &[Expr],
     schema: &DFSchemaRef,
     expr_result_map_for_count_bug: &mut ExprResultMap,
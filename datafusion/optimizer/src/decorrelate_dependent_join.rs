@@ -0,0 +1,1435 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DecorrelateDependentJoin`] decorrelates arbitrary correlated subqueries
+//! and lateral joins by pushing the dependent join down the subquery plan.
+
+use std::sync::Arc;
+
+use crate::decorrelate::{ExprResultMap, agg_exprs_evaluation_result_on_empty_batch};
+use crate::optimizer::ApplyOrder;
+use crate::utils::evaluates_to_null;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::alias::AliasGenerator;
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
+use datafusion_common::{
+    Column, DFSchema, NullEquality, Result, TableReference, internal_err,
+};
+use datafusion_expr::expr::{
+    Exists, InSubquery, SetComparison, WindowFunction, WindowFunctionParams,
+};
+use datafusion_expr::expr_rewriter::NamePreserver;
+use datafusion_expr::logical_plan::{
+    Aggregate, Distinct, FetchType, Filter, Join, JoinType, Limit, Projection, SkipType,
+    Subquery, SubqueryAlias, Union, Window,
+};
+use datafusion_expr::utils::{conjunction, split_conjunction_owned};
+use datafusion_expr::{
+    BinaryExpr, Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder, Operator, SortExpr,
+    WindowFrame, WindowFunctionDefinition, WindowUDF, binary_expr, lit, when,
+};
+
+/// Optimizer rule decorrelating the correlated subqueries and lateral joins
+/// left by [`DecorrelatePredicateSubquery`], [`ScalarSubqueryToJoin`] and
+/// [`DecorrelateLateralJoin`], such as subqueries with non-equality
+/// correlations, `LIMIT`s, window functions or several levels of nesting.
+///
+/// A correlated subquery is a dependent join of its outer plan with the
+/// subquery plan, evaluated once per outer row. The dependent join is
+/// rewritten to a regular join with the subquery evaluated once per distinct
+/// value of the correlated outer columns, the *domain*, following
+/// ["Unnesting Arbitrary Queries"] by Neumann and Kemper:
+///
+/// 1. The domain is the distinct values of the correlated outer columns.
+/// 2. The domain is pushed down the subquery plan until the correlated
+///    expressions are evaluated against it, e.g. an aggregate also groups by
+///    the domain and a `LIMIT` is evaluated per domain value with
+///    `row_number`. A filter equating inner expressions with all the
+///    correlated columns computes the domain from those expressions instead
+///    of joining with it (domain elimination).
+/// 3. The outer plan is joined with the decorrelated subquery plan on
+///    `outer column IS NOT DISTINCT FROM domain column`.
+///
+/// Outer references of nested subqueries to the correlated columns are
+/// rewritten to outer references to the domain columns, so that they are
+/// decorrelated by a later pass of the rule.
+///
+/// Enabled by [`enable_dependent_join_decorrelation`].
+///
+/// ["Unnesting Arbitrary Queries"]: https://cs.emis.de/LNI/Proceedings/Proceedings241/383.pdf
+/// [`DecorrelatePredicateSubquery`]: crate::decorrelate_predicate_subquery::DecorrelatePredicateSubquery
+/// [`ScalarSubqueryToJoin`]: crate::scalar_subquery_to_join::ScalarSubqueryToJoin
+/// [`DecorrelateLateralJoin`]: crate::decorrelate_lateral_join::DecorrelateLateralJoin
+/// [`enable_dependent_join_decorrelation`]: datafusion_common::config::OptimizerOptions::enable_dependent_join_decorrelation
+#[derive(Default, Debug)]
+pub struct DecorrelateDependentJoin {}
+
+impl DecorrelateDependentJoin {
+    #[expect(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OptimizerRule for DecorrelateDependentJoin {
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        if !config
+            .options()
+            .optimizer
+            .enable_dependent_join_decorrelation
+        {
+            return Ok(Transformed::no(plan));
+        }
+
+        match plan {
+            LogicalPlan::Filter(filter) => rewrite_filter(filter, config),
+            LogicalPlan::Projection(projection) => rewrite_projection(projection, config),
+            LogicalPlan::Join(join) => rewrite_lateral_join(join, config),
+            plan => Ok(Transformed::no(plan)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "decorrelate_dependent_join"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        // Nested subqueries are decorrelated before the subqueries containing
+        // them
+        Some(ApplyOrder::BottomUp)
+    }
+}
+
+/// Rewrites the correlated `EXISTS` and `IN` conjuncts of `filter` to semi
+/// and anti joins, and its other correlated subqueries to left and mark joins
+fn rewrite_filter(
+    filter: Filter,
+    config: &dyn OptimizerConfig,
+) -> Result<Transformed<LogicalPlan>> {
+    if !contains_subquery(&filter.predicate) {
+        return Ok(Transformed::no(LogicalPlan::Filter(filter)));
+    }
+
+    let mut joiner = SubqueryJoiner::new(&filter.input, config);
+    let mut predicates = vec![];
+    for conjunct in split_conjunction_owned(filter.predicate.clone()) {
+        let joined = match &conjunct {
+            Expr::Exists(Exists { subquery, negated }) => {
+                joiner.semi_join(subquery, None, *negated)?
+            }
+            Expr::InSubquery(InSubquery {
+                expr,
+                subquery,
+                negated,
+            }) if !contains_subquery(expr) => {
+                joiner.semi_join(subquery, Some(expr.as_ref()), *negated)?
+            }
+            _ => false,
+        };
+        if !joined {
+            predicates.push(joiner.rewrite_expr(conjunct)?);
+        }
+    }
+    if !joiner.transformed {
+        return Ok(Transformed::no(LogicalPlan::Filter(filter)));
+    }
+
+    let mut builder = LogicalPlanBuilder::from(joiner.plan);
+    if let Some(predicate) = conjunction(predicates) {
+        builder = builder.filter(predicate)?;
+    }
+    let plan = builder
+        .project(
+            filter
+                .input
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column),
+        )?
+        .build()?;
+    Ok(Transformed::yes(plan))
+}
+
+/// Rewrites the correlated scalar and `EXISTS` subqueries of `projection` to
+/// left and mark joins
+fn rewrite_projection(
+    projection: Projection,
+    config: &dyn OptimizerConfig,
+) -> Result<Transformed<LogicalPlan>> {
+    if !projection.expr.iter().any(contains_subquery) {
+        return Ok(Transformed::no(LogicalPlan::Projection(projection)));
+    }
+
+    let mut joiner = SubqueryJoiner::new(&projection.input, config);
+    let preserver = NamePreserver::new_for_projection();
+    let exprs = projection
+        .expr
+        .iter()
+        .map(|expr| {
+            let saved = preserver.save(expr);
+            Ok(saved.restore(joiner.rewrite_expr(expr.clone())?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if !joiner.transformed {
+        return Ok(Transformed::no(LogicalPlan::Projection(projection)));
+    }
+
+    let projection = Projection::try_new(exprs, Arc::new(joiner.plan))?;
+    Ok(Transformed::yes(LogicalPlan::Projection(projection)))
+}
+
+/// Rewrites an inner or left lateral join to a join with the decorrelated
+/// lateral subquery
+fn rewrite_lateral_join(
+    join: Join,
+    config: &dyn OptimizerConfig,
+) -> Result<Transformed<LogicalPlan>> {
+    if !matches!(join.join_type, JoinType::Inner | JoinType::Left) {
+        return Ok(Transformed::no(LogicalPlan::Join(join)));
+    }
+    let (subquery, alias) = match join.right.as_ref() {
+        LogicalPlan::Subquery(subquery) => (subquery.clone(), None),
+        LogicalPlan::SubqueryAlias(SubqueryAlias { input, alias, .. }) => {
+            match input.as_ref() {
+                LogicalPlan::Subquery(subquery) => {
+                    (subquery.clone(), Some(alias.clone()))
+                }
+                _ => return Ok(Transformed::no(LogicalPlan::Join(join))),
+            }
+        }
+        _ => return Ok(Transformed::no(LogicalPlan::Join(join))),
+    };
+    let Some(decorrelated) =
+        Decorrelated::try_new(&join.left, &subquery.subquery, config)?
+    else {
+        return Ok(Transformed::no(LogicalPlan::Join(join)));
+    };
+
+    let (right, mut on) =
+        decorrelated.into_plan(subquery.subquery.schema().columns(), alias)?;
+    on.extend(join_conditions(&join)?);
+    let plan = LogicalPlanBuilder::from(join.left.as_ref().clone())
+        .join_on(right, join.join_type, on)?
+        .build()?;
+    let columns = join
+        .schema
+        .columns()
+        .into_iter()
+        .map(Expr::Column)
+        .collect();
+    let projection = Projection::try_new(columns, Arc::new(plan))?;
+    Ok(Transformed::yes(LogicalPlan::Projection(projection)))
+}
+
+/// The conditions of `join` as expressions
+fn join_conditions(join: &Join) -> Result<Vec<Expr>> {
+    let operator = match join.null_equality {
+        NullEquality::NullEqualsNothing => Operator::Eq,
+        NullEquality::NullEqualsNull => Operator::IsNotDistinctFrom,
+    };
+    Ok(join
+        .on
+        .iter()
+        .map(|(left, right)| binary_expr(left.clone(), operator, right.clone()))
+        .chain(join.filter.clone())
+        .collect())
+}
+
+/// Returns true if `expr` contains a subquery
+fn contains_subquery(expr: &Expr) -> bool {
+    expr.exists(|expr| {
+        Ok(matches!(
+            expr,
+            Expr::Exists(_)
+                | Expr::InSubquery(_)
+                | Expr::SetComparison(_)
+                | Expr::ScalarSubquery(_)
+        ))
+    })
+    .expect("exists closure is infallible")
+}
+
+/// Returns true if `plan` returns at most one row per row of its outer plan
+fn at_most_one_row(plan: &LogicalPlan) -> bool {
+    if plan.max_rows().is_some_and(|max_rows| max_rows <= 1) {
+        return true;
+    }
+    match plan {
+        LogicalPlan::Projection(Projection { input, .. })
+        | LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. })
+        | LogicalPlan::Filter(Filter { input, .. }) => at_most_one_row(input),
+        LogicalPlan::Aggregate(aggregate) => aggregate.group_expr.is_empty(),
+        _ => false,
+    }
+}
+
+/// `left IS NOT DISTINCT FROM right`
+fn not_distinct(left: Column, right: Column) -> Expr {
+    binary_expr(
+        Expr::Column(left),
+        Operator::IsNotDistinctFrom,
+        Expr::Column(right),
+    )
+}
+
+/// Joins an outer plan with the decorrelated plans of its correlated
+/// subqueries
+struct SubqueryJoiner<'a> {
+    /// The outer plan of the subqueries
+    outer: Arc<LogicalPlan>,
+    /// The outer plan joined with the subqueries decorrelated so far
+    plan: LogicalPlan,
+    config: &'a dyn OptimizerConfig,
+    /// Whether a subquery was decorrelated
+    transformed: bool,
+}
+
+impl<'a> SubqueryJoiner<'a> {
+    fn new(outer: &Arc<LogicalPlan>, config: &'a dyn OptimizerConfig) -> Self {
+        Self {
+            outer: Arc::clone(outer),
+            plan: outer.as_ref().clone(),
+            config,
+            transformed: false,
+        }
+    }
+
+    /// Decorrelates `subquery`, returning its plan projected to `columns` and
+    /// the domain columns under a new alias, the conditions joining it with
+    /// the outer plan, and the alias
+    fn decorrelate(
+        &self,
+        subquery: &Subquery,
+        columns: Vec<Column>,
+    ) -> Result<Option<(LogicalPlan, Vec<Expr>, TableReference)>> {
+        let Some(decorrelated) =
+            Decorrelated::try_new(&self.outer, &subquery.subquery, self.config)?
+        else {
+            return Ok(None);
+        };
+        let alias =
+            TableReference::bare(self.config.alias_generator().next("__dependent_sq"));
+        let (plan, on) = decorrelated.into_plan(columns, Some(alias.clone()))?;
+        Ok(Some((plan, on, alias)))
+    }
+
+    fn join(
+        &mut self,
+        right: LogicalPlan,
+        join_type: JoinType,
+        on: Vec<Expr>,
+    ) -> Result<()> {
+        self.plan = LogicalPlanBuilder::from(std::mem::take(&mut self.plan))
+            .join_on(right, join_type, on)?
+            .build()?;
+        self.transformed = true;
+        Ok(())
+    }
+
+    /// Rewrites the conjunct `[NOT] EXISTS (subquery)` or `expr [NOT] IN
+    /// (subquery)` to a semi or anti join, returning false if the subquery
+    /// can not be decorrelated
+    fn semi_join(
+        &mut self,
+        subquery: &Subquery,
+        expr: Option<&Expr>,
+        negated: bool,
+    ) -> Result<bool> {
+        let columns = match expr {
+            Some(_) => vec![subquery.subquery.schema().columns().swap_remove(0)],
+            None => vec![],
+        };
+        let Some((right, mut on, _)) = self.decorrelate(subquery, columns)? else {
+            return Ok(false);
+        };
+        if let Some(expr) = expr {
+            let value = right.schema().qualified_field(0);
+            let equal = expr.clone().eq(Expr::Column(Column::from(value)));
+            // `expr NOT IN (subquery)` is false if `expr` is equal to or
+            // compared with NULL to any of its values
+            on.push(if negated { equal.is_not_false() } else { equal });
+        }
+        let join_type = if negated {
+            JoinType::LeftAnti
+        } else {
+            JoinType::LeftSemi
+        };
+        self.join(right, join_type, on)?;
+        Ok(true)
+    }
+
+    /// Rewrites the correlated scalar and `EXISTS` subqueries of `expr` to
+    /// left and mark joins
+    fn rewrite_expr(&mut self, expr: Expr) -> Result<Expr> {
+        expr.transform_down(|expr| match expr {
+            Expr::ScalarSubquery(subquery) => {
+                if !at_most_one_row(&subquery.subquery) {
+                    return Ok(Transformed::no(Expr::ScalarSubquery(subquery)));
+                }
+                let value = subquery.subquery.schema().columns().swap_remove(0);
+                let Some((right, on, alias)) =
+                    self.decorrelate(&subquery, vec![value.clone()])?
+                else {
+                    return Ok(Transformed::no(Expr::ScalarSubquery(subquery)));
+                };
+                self.join(right, JoinType::Left, on)?;
+                let value = Expr::Column(Column::new(Some(alias), value.name));
+                Ok(Transformed::new(value, true, TreeNodeRecursion::Jump))
+            }
+            Expr::Exists(Exists { subquery, negated }) => {
+                let Some((right, on, alias)) = self.decorrelate(&subquery, vec![])?
+                else {
+                    return Ok(Transformed::no(Expr::Exists(Exists {
+                        subquery,
+                        negated,
+                    })));
+                };
+                self.join(right, JoinType::LeftMark, on)?;
+                let mark = Expr::Column(Column::new(Some(alias), "mark"));
+                let value = if negated { !mark } else { mark };
+                Ok(Transformed::new(value, true, TreeNodeRecursion::Jump))
+            }
+            expr => Ok(Transformed::no(expr)),
+        })
+        .data()
+    }
+}
+
+/// A correlated subquery plan decorrelated against its outer plan
+struct Decorrelated {
+    /// The subquery plan, also outputting the domain columns
+    plan: LogicalPlan,
+    /// The outer columns the subquery is correlated with
+    correlated: Vec<Column>,
+    /// The domain columns of `plan`, one per correlated column
+    domain: Vec<Column>,
+}
+
+impl Decorrelated {
+    /// Decorrelates `subquery` against `outer`, returning `None` if it is not
+    /// correlated with `outer` or can not be decorrelated
+    fn try_new(
+        outer: &LogicalPlan,
+        subquery: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<Self>> {
+        let outer_schema = outer.schema();
+        let mut correlated: Vec<Column> = vec![];
+        subquery.apply_with_subqueries(|plan| {
+            plan.apply_expressions(|expr| {
+                expr.apply(|expr| {
+                    if let Expr::OuterReferenceColumn(_, column) = expr
+                        && outer_schema.has_column(column)
+                        && !correlated.contains(column)
+                    {
+                        correlated.push(column.clone());
+                    }
+                    Ok(TreeNodeRecursion::Continue)
+                })
+            })
+        })?;
+        if correlated.is_empty() {
+            return Ok(None);
+        }
+
+        // The subquery plan itself must not reference columns of other outer
+        // plans, which would be left in its decorrelated plan, and must not
+        // shadow the correlated columns for its nested subqueries
+        let mut unsupported = false;
+        subquery.apply(|plan| {
+            plan.apply_expressions(|expr| {
+                expr.apply(|expr| {
+                    if let Expr::OuterReferenceColumn(_, column) = expr
+                        && !outer_schema.has_column(column)
+                    {
+                        unsupported = true;
+                        return Ok(TreeNodeRecursion::Stop);
+                    }
+                    Ok(TreeNodeRecursion::Continue)
+                })
+            })
+        })?;
+        subquery.apply_with_subqueries(|plan| {
+            if correlated
+                .iter()
+                .any(|column| plan.schema().has_column(column))
+            {
+                unsupported = true;
+                return Ok(TreeNodeRecursion::Stop);
+            }
+            Ok(TreeNodeRecursion::Continue)
+        })?;
+        if unsupported {
+            return Ok(None);
+        }
+
+        let alias_generator = config.alias_generator();
+        let group_expr = correlated
+            .iter()
+            .map(|column| {
+                Expr::Column(column.clone()).alias(alias_generator.next("__domain"))
+            })
+            .collect::<Vec<_>>();
+        let domain = LogicalPlanBuilder::from(outer.clone())
+            .aggregate(group_expr, Vec::<Expr>::new())?
+            .build()?;
+        let row_number = config
+            .function_registry()
+            .and_then(|registry| registry.udwf("row_number").ok());
+        let unnester = Unnester {
+            correlated,
+            domain,
+            alias_generator,
+            row_number,
+        };
+
+        Ok(unnester.push(subquery)?.map(|(plan, domain)| Self {
+            plan,
+            correlated: unnester.correlated,
+            domain,
+        }))
+    }
+
+    /// Returns the decorrelated plan projected to `columns` of the subquery
+    /// and the domain columns, optionally under `alias`, with the conditions
+    /// joining it with the outer plan
+    fn into_plan(
+        self,
+        columns: Vec<Column>,
+        alias: Option<TableReference>,
+    ) -> Result<(LogicalPlan, Vec<Expr>)> {
+        let exprs = columns
+            .into_iter()
+            .chain(self.domain.iter().cloned())
+            .map(Expr::Column)
+            .collect();
+        let plan =
+            LogicalPlan::Projection(Projection::try_new(exprs, Arc::new(self.plan))?);
+        let (plan, domain) = match alias {
+            Some(alias) => {
+                let domain = self
+                    .domain
+                    .iter()
+                    .map(|column| Column::new(Some(alias.clone()), &column.name))
+                    .collect();
+                let plan = SubqueryAlias::try_new(Arc::new(plan), alias)?;
+                (LogicalPlan::SubqueryAlias(plan), domain)
+            }
+            None => (plan, self.domain),
+        };
+        let on = self
+            .correlated
+            .into_iter()
+            .zip(domain)
+            .map(|(column, domain)| not_distinct(column, domain))
+            .collect();
+        Ok((plan, on))
+    }
+}
+
+/// Pushes the domain of correlated columns down a subquery plan
+struct Unnester<'a> {
+    /// The outer columns the subquery is correlated with
+    correlated: Vec<Column>,
+    /// The distinct values of the correlated columns
+    domain: LogicalPlan,
+    alias_generator: &'a Arc<AliasGenerator>,
+    row_number: Option<Arc<WindowUDF>>,
+}
+
+impl Unnester<'_> {
+    /// Returns `plan` evaluated for each value of the domain, also outputting
+    /// the domain columns, or `None` if it can not be decorrelated
+    fn push(&self, plan: &LogicalPlan) -> Result<Option<(LogicalPlan, Vec<Column>)>> {
+        if !self.is_correlated(plan)? {
+            let plan = LogicalPlanBuilder::from(plan.clone())
+                .cross_join(self.domain.clone())?
+                .build()?;
+            return Ok(Some((plan, self.domain.schema().columns())));
+        }
+
+        match plan {
+            LogicalPlan::Filter(filter) => {
+                if let Some(eliminated) = self.eliminate_domain(filter)? {
+                    return Ok(Some(eliminated));
+                }
+                let Some((input, domain)) = self.push(&filter.input)? else {
+                    return Ok(None);
+                };
+                let predicate = self.replace(filter.predicate.clone(), &domain)?;
+                let plan = plan.with_new_exprs(vec![predicate], vec![input])?;
+                Ok(Some((plan, domain)))
+            }
+            LogicalPlan::Projection(projection) => {
+                let Some((input, domain)) = self.push(&projection.input)? else {
+                    return Ok(None);
+                };
+                let exprs = self
+                    .replace_all(plan, projection.expr.clone(), &domain)?
+                    .into_iter()
+                    .chain(domain.iter().cloned().map(Expr::Column))
+                    .collect();
+                let projection = Projection::try_new(exprs, Arc::new(input))?;
+                Ok(Some((LogicalPlan::Projection(projection), domain)))
+            }
+            LogicalPlan::Aggregate(aggregate) => self.push_aggregate(plan, aggregate),
+            LogicalPlan::Window(window) => {
+                let Some((input, domain)) = self.push(&window.input)? else {
+                    return Ok(None);
+                };
+                // The window functions are also partitioned by the domain
+                let preserver = NamePreserver::new(plan);
+                let window_expr = window
+                    .window_expr
+                    .iter()
+                    .map(|expr| {
+                        let saved = preserver.save(expr);
+                        let expr = self.replace(expr.clone(), &domain)?;
+                        Ok(saved.restore(partition_by_domain(expr, &domain)?))
+                    })
+                    .collect::<Result<_>>()?;
+                let window = Window::try_new(window_expr, Arc::new(input))?;
+                Ok(Some((LogicalPlan::Window(window), domain)))
+            }
+            LogicalPlan::Limit(limit) => self.push_limit(limit),
+            LogicalPlan::Sort(sort) => {
+                let Some((input, domain)) = self.push(&sort.input)? else {
+                    return Ok(None);
+                };
+                let order_by = self.replace_sort(sort.expr.clone(), &domain)?;
+                match sort.fetch {
+                    Some(fetch) => {
+                        self.limit_per_domain(input, domain, order_by, 0, Some(fetch))
+                    }
+                    None => {
+                        let plan =
+                            LogicalPlanBuilder::from(input).sort(order_by)?.build()?;
+                        Ok(Some((plan, domain)))
+                    }
+                }
+            }
+            LogicalPlan::Join(join) => self.push_join(join),
+            LogicalPlan::SubqueryAlias(subquery_alias) => {
+                let Some((input, domain)) = self.push(&subquery_alias.input)? else {
+                    return Ok(None);
+                };
+                let alias = subquery_alias.alias.clone();
+                let domain = domain
+                    .iter()
+                    .map(|column| Column::new(Some(alias.clone()), &column.name))
+                    .collect();
+                let plan = SubqueryAlias::try_new(Arc::new(input), alias)?;
+                Ok(Some((LogicalPlan::SubqueryAlias(plan), domain)))
+            }
+            LogicalPlan::Union(union) => self.push_union(union),
+            LogicalPlan::Distinct(Distinct::All(input)) => {
+                let Some((input, domain)) = self.push(input)? else {
+                    return Ok(None);
+                };
+                let plan = LogicalPlan::Distinct(Distinct::All(Arc::new(input)));
+                Ok(Some((plan, domain)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Evaluates a filter equating inner expressions with all the correlated
+    /// columns on those expressions instead of the domain, e.g. rewrites
+    /// `t2.a = outer_ref(t1.a) AND t2.b > outer_ref(t1.a)` to `t2.a IS NOT
+    /// NULL AND t2.b > t2.a`, with `t2.a` as domain column
+    fn eliminate_domain(
+        &self,
+        filter: &Filter,
+    ) -> Result<Option<(LogicalPlan, Vec<Column>)>> {
+        if self.is_correlated(&filter.input)? {
+            return Ok(None);
+        }
+
+        let schema = filter.input.schema();
+        let mut equal = vec![None; self.correlated.len()];
+        let mut remaining = vec![];
+        for conjunct in split_conjunction_owned(filter.predicate.clone()) {
+            if let Some((i, expr)) = self.correlated_equality(&conjunct, schema)?
+                && equal[i].is_none()
+            {
+                equal[i] = Some(expr);
+            } else if contains_subquery(&conjunct) {
+                return Ok(None);
+            } else {
+                remaining.push(conjunct);
+            }
+        }
+        let Some(equal) = equal.into_iter().collect::<Option<Vec<_>>>() else {
+            return Ok(None);
+        };
+
+        let predicates = remaining
+            .into_iter()
+            .map(|expr| self.substitute(expr, &equal))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            // The correlated columns are never equal to NULL
+            .chain(equal.iter().map(|expr| expr.clone().is_not_null()))
+            .collect::<Vec<_>>();
+        let names = equal
+            .iter()
+            .map(|_| self.alias_generator.next("__domain"))
+            .collect::<Vec<_>>();
+        let exprs = schema.columns().into_iter().map(Expr::Column).chain(
+            equal
+                .into_iter()
+                .zip(&names)
+                .map(|(expr, name)| expr.alias(name)),
+        );
+        let plan = LogicalPlanBuilder::from(filter.input.as_ref().clone())
+            .filter(conjunction(predicates).unwrap_or_else(|| lit(true)))?
+            .project(exprs)?
+            .build()?;
+        Ok(Some((
+            plan,
+            names.into_iter().map(Column::from_name).collect(),
+        )))
+    }
+
+    /// Returns the index of the correlated column and the inner expression
+    /// of `expr` if it is `inner = outer_ref(column)`
+    fn correlated_equality(
+        &self,
+        expr: &Expr,
+        schema: &DFSchema,
+    ) -> Result<Option<(usize, Expr)>> {
+        let Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        }) = expr
+        else {
+            return Ok(None);
+        };
+        for (outer, inner) in [(left, right), (right, left)] {
+            if let Expr::OuterReferenceColumn(field, column) = outer.as_ref()
+                && let Some(i) = self.position(column)
+                && !inner.contains_outer()
+                && !contains_subquery(inner)
+                && !inner.is_volatile()
+                && inner.get_type(schema)? == *field.data_type()
+            {
+                return Ok(Some((i, inner.as_ref().clone())));
+            }
+        }
+        Ok(None)
+    }
+
+    fn push_aggregate(
+        &self,
+        plan: &LogicalPlan,
+        aggregate: &Aggregate,
+    ) -> Result<Option<(LogicalPlan, Vec<Column>)>> {
+        if matches!(aggregate.group_expr.as_slice(), [Expr::GroupingSet(_)]) {
+            return Ok(None);
+        }
+        let Some((input, domain)) = self.push(&aggregate.input)? else {
+            return Ok(None);
+        };
+        let group_expr = self.replace_all(plan, aggregate.group_expr.clone(), &domain)?;
+        let aggr_expr = self.replace_all(plan, aggregate.aggr_expr.clone(), &domain)?;
+
+        if !group_expr.is_empty() {
+            // Groups are computed per domain value
+            let group_expr = group_expr
+                .into_iter()
+                .chain(domain.iter().cloned().map(Expr::Column))
+                .collect();
+            let aggregate = Aggregate::try_new(Arc::new(input), group_expr, aggr_expr)?;
+            return Ok(Some((LogicalPlan::Aggregate(aggregate), domain)));
+        }
+
+        // An aggregate without grouping returns a row even for the domain
+        // values without input rows: group by the domain, and left join the
+        // domain with the groups, replacing the missing aggregates with their
+        // value on empty input (e.g. 0 for `count`)
+        let keys = domain
+            .iter()
+            .map(|_| self.alias_generator.next("__domain"))
+            .collect::<Vec<_>>();
+        let group_expr = domain
+            .iter()
+            .zip(&keys)
+            .map(|(column, key)| Expr::Column(column.clone()).alias(key))
+            .collect();
+        let aggregated = Aggregate::try_new(Arc::new(input), group_expr, aggr_expr)?;
+        let marker = self.alias_generator.next("__always_true");
+        let exprs = aggregated
+            .schema
+            .columns()
+            .into_iter()
+            .map(Expr::Column)
+            .chain([lit(true).alias(&marker)]);
+        let aggregated = LogicalPlanBuilder::from(LogicalPlan::Aggregate(aggregated))
+            .project(exprs)?
+            .build()?;
+        let on = self
+            .domain
+            .schema()
+            .columns()
+            .into_iter()
+            .zip(keys)
+            .map(|(column, key)| not_distinct(column, Column::from_name(key)))
+            .collect::<Vec<_>>();
+        let joined = LogicalPlanBuilder::from(self.domain.clone())
+            .join_on(aggregated, JoinType::Left, on)?
+            .build()?;
+
+        let mut empty_results = ExprResultMap::new();
+        agg_exprs_evaluation_result_on_empty_batch(
+            &aggregate.aggr_expr,
+            aggregate.input.schema(),
+            &mut empty_results,
+        )?;
+        let exprs = plan
+            .schema()
+            .iter()
+            .map(|(qualifier, field)| {
+                let column = Expr::Column(Column::new(qualifier.cloned(), field.name()));
+                let expr = match empty_results.get(field.name()) {
+                    Some(result)
+                        if !evaluates_to_null(result.clone(), result.column_refs())? =>
+                    {
+                        when(
+                            Expr::Column(Column::from_name(&marker)).is_null(),
+                            result.clone(),
+                        )
+                        .otherwise(column)?
+                        .alias_qualified(qualifier.cloned(), field.name())
+                    }
+                    _ => column,
+                };
+                Ok(expr)
+            })
+            .chain(
+                self.domain
+                    .schema()
+                    .columns()
+                    .into_iter()
+                    .map(|column| Ok(Expr::Column(column))),
+            )
+            .collect::<Result<Vec<_>>>()?;
+        let plan = LogicalPlan::Projection(Projection::try_new(exprs, Arc::new(joined))?);
+        Ok(Some((plan, self.domain.schema().columns())))
+    }
+
+    fn push_limit(&self, limit: &Limit) -> Result<Option<(LogicalPlan, Vec<Column>)>> {
+        let (SkipType::Literal(skip), FetchType::Literal(fetch)) =
+            (limit.get_skip_type()?, limit.get_fetch_type()?)
+        else {
+            return Ok(None);
+        };
+        // The ordering of the limited rows
+        let (input, order_by) = match limit.input.as_ref() {
+            LogicalPlan::Sort(sort) if sort.fetch.is_none() => {
+                (sort.input.as_ref(), sort.expr.clone())
+            }
+            input => (input, vec![]),
+        };
+        let Some((input, domain)) = self.push(input)? else {
+            return Ok(None);
+        };
+        let order_by = self.replace_sort(order_by, &domain)?;
+        self.limit_per_domain(input, domain, order_by, skip, fetch)
+    }
+
+    /// Limits `input` to `fetch` rows after `skip` rows per domain value,
+    /// numbering its rows with `row_number`
+    fn limit_per_domain(
+        &self,
+        input: LogicalPlan,
+        domain: Vec<Column>,
+        order_by: Vec<SortExpr>,
+        skip: usize,
+        fetch: Option<usize>,
+    ) -> Result<Option<(LogicalPlan, Vec<Column>)>> {
+        let Some(row_number) = &self.row_number else {
+            return Ok(None);
+        };
+        let mut predicates = vec![];
+        let row_number_column = self.alias_generator.next("__row_number");
+        let row_number_expr = Expr::Column(Column::from_name(&row_number_column));
+        if skip > 0 {
+            predicates.push(row_number_expr.clone().gt(lit(skip as u64)));
+        }
+        if let Some(fetch) = fetch {
+            predicates.push(row_number_expr.lt_eq(lit((skip + fetch) as u64)));
+        }
+        let Some(predicate) = conjunction(predicates) else {
+            return Ok(Some((input, domain)));
+        };
+
+        let window_frame = WindowFrame::new((!order_by.is_empty()).then_some(false));
+        let window = Expr::from(WindowFunction {
+            fun: WindowFunctionDefinition::WindowUDF(Arc::clone(row_number)),
+            params: WindowFunctionParams {
+                args: vec![],
+                partition_by: domain.iter().cloned().map(Expr::Column).collect(),
+                order_by,
+                window_frame,
+                filter: None,
+                null_treatment: None,
+                distinct: false,
+            },
+        })
+        .alias(&row_number_column);
+        let columns = input.schema().columns().into_iter().map(Expr::Column);
+        let plan = LogicalPlanBuilder::from(input)
+            .window(vec![window])?
+            .filter(predicate)?
+            .project(columns)?
+            .build()?;
+        Ok(Some((plan, domain)))
+    }
+
+    fn push_join(&self, join: &Join) -> Result<Option<(LogicalPlan, Vec<Column>)>> {
+        if join.null_aware {
+            return Ok(None);
+        }
+        let left_correlated = self.is_correlated(&join.left)?;
+        let right_correlated = self.is_correlated(&join.right)?;
+        // The domain is pushed to the correlated inputs, or to the left input
+        // if only the join conditions are correlated. The non-preserved input
+        // of an outer join can not be correlated
+        let (push_left, push_right) = match join.join_type {
+            JoinType::Inner => (left_correlated || !right_correlated, right_correlated),
+            JoinType::Left
+            | JoinType::LeftSemi
+            | JoinType::LeftAnti
+            | JoinType::LeftMark
+                if !right_correlated =>
+            {
+                (true, false)
+            }
+            JoinType::Right
+            | JoinType::RightSemi
+            | JoinType::RightAnti
+            | JoinType::RightMark
+                if !left_correlated =>
+            {
+                (false, true)
+            }
+            _ => return Ok(None),
+        };
+
+        let (left, left_domain) = if push_left {
+            let Some(pushed) = self.push(&join.left)? else {
+                return Ok(None);
+            };
+            pushed
+        } else {
+            (join.left.as_ref().clone(), vec![])
+        };
+        let (right, right_domain) = if push_right {
+            let Some(pushed) = self.push(&join.right)? else {
+                return Ok(None);
+            };
+            pushed
+        } else {
+            (join.right.as_ref().clone(), vec![])
+        };
+
+        let mut on = vec![];
+        let (right, domain) = match (push_left, push_right) {
+            (true, true) => {
+                // The domain columns of the right input are renamed, and
+                // joined with the ones of the left input
+                let mut exprs = right
+                    .schema()
+                    .columns()
+                    .into_iter()
+                    .filter(|column| !right_domain.contains(column))
+                    .map(Expr::Column)
+                    .collect::<Vec<_>>();
+                for (left, right) in left_domain.iter().zip(right_domain) {
+                    let name = self.alias_generator.next("__domain");
+                    exprs.push(Expr::Column(right).alias(&name));
+                    on.push(not_distinct(left.clone(), Column::from_name(name)));
+                }
+                let right = LogicalPlanBuilder::from(right).project(exprs)?.build()?;
+                (right, left_domain)
+            }
+            (true, false) => (right, left_domain),
+            _ => (right, right_domain),
+        };
+        for condition in join_conditions(join)? {
+            on.push(self.replace(condition, &domain)?);
+        }
+        let plan = LogicalPlanBuilder::from(left)
+            .join_on(right, join.join_type, on)?
+            .build()?;
+        Ok(Some((plan, domain)))
+    }
+
+    fn push_union(&self, union: &Union) -> Result<Option<(LogicalPlan, Vec<Column>)>> {
+        let mut inputs = Vec::with_capacity(union.inputs.len());
+        for input in &union.inputs {
+            let Some((pushed, domain)) = self.push(input)? else {
+                return Ok(None);
+            };
+            // The columns of the inputs of a union are matched by position
+            let exprs = input
+                .schema()
+                .columns()
+                .into_iter()
+                .chain(domain)
+                .map(Expr::Column)
+                .collect();
+            let projection = Projection::try_new(exprs, Arc::new(pushed))?;
+            inputs.push(Arc::new(LogicalPlan::Projection(projection)));
+        }
+        let union = Union::try_new(inputs)?;
+        let domain = union
+            .schema
+            .columns()
+            .split_off(union.schema.fields().len() - self.correlated.len());
+        Ok(Some((LogicalPlan::Union(union), domain)))
+    }
+
+    /// Returns true if `plan` or its subqueries reference the correlated
+    /// columns
+    fn is_correlated(&self, plan: &LogicalPlan) -> Result<bool> {
+        let mut correlated = false;
+        plan.apply_with_subqueries(|plan| {
+            plan.apply_expressions(|expr| {
+                expr.apply(|expr| {
+                    if let Expr::OuterReferenceColumn(_, column) = expr
+                        && self.position(column).is_some()
+                    {
+                        correlated = true;
+                        return Ok(TreeNodeRecursion::Stop);
+                    }
+                    Ok(TreeNodeRecursion::Continue)
+                })
+            })
+        })?;
+        Ok(correlated)
+    }
+
+    fn position(&self, column: &Column) -> Option<usize> {
+        self.correlated.iter().position(|c| c == column)
+    }
+
+    /// Rewrites the expressions of `plan` with [`Self::replace`], preserving
+    /// their names
+    fn replace_all(
+        &self,
+        plan: &LogicalPlan,
+        exprs: Vec<Expr>,
+        domain: &[Column],
+    ) -> Result<Vec<Expr>> {
+        let preserver = NamePreserver::new(plan);
+        exprs
+            .into_iter()
+            .map(|expr| {
+                let saved = preserver.save(&expr);
+                Ok(saved.restore(self.replace(expr, domain)?))
+            })
+            .collect()
+    }
+
+    fn replace_sort(
+        &self,
+        exprs: Vec<SortExpr>,
+        domain: &[Column],
+    ) -> Result<Vec<SortExpr>> {
+        exprs
+            .into_iter()
+            .map(|sort| Ok(sort.with_expr(self.replace(sort.expr.clone(), domain)?)))
+            .collect()
+    }
+
+    /// Rewrites the references to the correlated columns in `expr` to the
+    /// `domain` columns, which its subqueries reference as outer columns
+    fn replace(&self, expr: Expr, domain: &[Column]) -> Result<Expr> {
+        expr.transform_up(|expr| match expr {
+            Expr::OuterReferenceColumn(field, column) => {
+                Ok(match self.position(&column) {
+                    Some(i) => Transformed::yes(Expr::Column(domain[i].clone())),
+                    None => Transformed::no(Expr::OuterReferenceColumn(field, column)),
+                })
+            }
+            Expr::ScalarSubquery(subquery) => Ok(Transformed::yes(Expr::ScalarSubquery(
+                self.replace_in_subquery(subquery, domain)?,
+            ))),
+            Expr::Exists(Exists { subquery, negated }) => {
+                let subquery = self.replace_in_subquery(subquery, domain)?;
+                Ok(Transformed::yes(Expr::Exists(Exists { subquery, negated })))
+            }
+            Expr::InSubquery(InSubquery {
+                expr,
+                subquery,
+                negated,
+            }) => {
+                let subquery = self.replace_in_subquery(subquery, domain)?;
+                Ok(Transformed::yes(Expr::InSubquery(InSubquery {
+                    expr,
+                    subquery,
+                    negated,
+                })))
+            }
+            Expr::SetComparison(SetComparison {
+                expr,
+                subquery,
+                op,
+                quantifier,
+            }) => {
+                let subquery = self.replace_in_subquery(subquery, domain)?;
+                Ok(Transformed::yes(Expr::SetComparison(SetComparison {
+                    expr,
+                    subquery,
+                    op,
+                    quantifier,
+                })))
+            }
+            expr => Ok(Transformed::no(expr)),
+        })
+        .data()
+    }
+
+    /// Rewrites the outer references to the correlated columns in `subquery`
+    /// and its nested subqueries to outer references to the `domain` columns
+    fn replace_in_subquery(
+        &self,
+        subquery: Subquery,
+        domain: &[Column],
+    ) -> Result<Subquery> {
+        let rename = |expr: Expr| {
+            expr.transform(|expr| match expr {
+                Expr::OuterReferenceColumn(field, column) => {
+                    Ok(match self.position(&column) {
+                        Some(i) => {
+                            let field = field.as_ref().clone().with_name(&domain[i].name);
+                            Transformed::yes(Expr::OuterReferenceColumn(
+                                Arc::new(field),
+                                domain[i].clone(),
+                            ))
+                        }
+                        None => {
+                            Transformed::no(Expr::OuterReferenceColumn(field, column))
+                        }
+                    })
+                }
+                expr => Ok(Transformed::no(expr)),
+            })
+        };
+        let plan = LogicalPlan::Subquery(subquery)
+            .transform_up_with_subqueries(|plan| match plan {
+                LogicalPlan::Subquery(mut subquery) => {
+                    subquery.outer_ref_columns = subquery
+                        .outer_ref_columns
+                        .into_iter()
+                        .map(|expr| rename(expr).data())
+                        .collect::<Result<_>>()?;
+                    Ok(Transformed::yes(LogicalPlan::Subquery(subquery)))
+                }
+                plan => {
+                    let preserver = NamePreserver::new(&plan);
+                    plan.map_expressions(|expr| {
+                        let saved = preserver.save(&expr);
+                        Ok(rename(expr)?.update_data(|expr| saved.restore(expr)))
+                    })
+                }
+            })?
+            .data;
+        let LogicalPlan::Subquery(subquery) = plan else {
+            return internal_err!("Rewriting a subquery should return a subquery");
+        };
+        Ok(subquery)
+    }
+
+    /// Replaces the outer references to the correlated columns in `expr` with
+    /// the expressions `equal` to them
+    fn substitute(&self, expr: Expr, equal: &[Expr]) -> Result<Expr> {
+        expr.transform(|expr| match expr {
+            Expr::OuterReferenceColumn(field, column) => {
+                Ok(match self.position(&column) {
+                    Some(i) => Transformed::yes(equal[i].clone()),
+                    None => Transformed::no(Expr::OuterReferenceColumn(field, column)),
+                })
+            }
+            expr => Ok(Transformed::no(expr)),
+        })
+        .data()
+    }
+}
+
+/// Adds the `domain` columns to the partitioning of the window functions of
+/// `expr`
+fn partition_by_domain(expr: Expr, domain: &[Column]) -> Result<Expr> {
+    expr.transform(|expr| match expr {
+        Expr::WindowFunction(mut window) => {
+            window
+                .params
+                .partition_by
+                .extend(domain.iter().cloned().map(Expr::Column));
+            Ok(Transformed::yes(Expr::WindowFunction(window)))
+        }
+        expr => Ok(Transformed::no(expr)),
+    })
+    .data()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test::test_table_scan_with_name;
+    use crate::{Optimizer, OptimizerContext, assert_optimized_plan_eq_snapshot};
+
+    use arrow::datatypes::DataType;
+    use datafusion_common::Spans;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_expr::registry::{FunctionRegistry, MemoryFunctionRegistry};
+    use datafusion_expr::test::function_stub::sum;
+    use datafusion_expr::{col, exists, out_ref_col, scalar_subquery};
+    use datafusion_functions_window::row_number::row_number_udwf;
+
+    /// An [`OptimizerConfig`] enabling the rule, with the `row_number`
+    /// function
+    struct TestConfig {
+        inner: OptimizerContext,
+        registry: MemoryFunctionRegistry,
+    }
+
+    impl TestConfig {
+        fn new(enabled: bool) -> Result<Self> {
+            let mut options = ConfigOptions::default();
+            options.optimizer.enable_dependent_join_decorrelation = enabled;
+            let mut registry = MemoryFunctionRegistry::new();
+            registry.register_udwf(row_number_udwf())?;
+            Ok(Self {
+                inner: OptimizerContext::new_with_config_options(Arc::new(options)),
+                registry,
+            })
+        }
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+            self.inner.query_execution_start_time()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            self.inner.alias_generator()
+        }
+
+        fn options(&self) -> Arc<ConfigOptions> {
+            self.inner.options()
+        }
+
+        fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
+            Some(&self.registry)
+        }
+    }
+
+    fn optimize(plan: LogicalPlan, enabled: bool) -> Result<LogicalPlan> {
+        let optimizer =
+            Optimizer::with_rules(vec![Arc::new(DecorrelateDependentJoin::new())]);
+        optimizer.optimize(plan, &TestConfig::new(enabled)?, |_, _| {})
+    }
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let rules: Vec<Arc<dyn crate::OptimizerRule + Send + Sync>> =
+                vec![Arc::new(DecorrelateDependentJoin::new())];
+            assert_optimized_plan_eq_snapshot!(
+                TestConfig::new(true)?,
+                rules,
+                $plan,
+                @ $expected,
+            )
+        }};
+    }
+
+    #[test]
+    fn scalar_subquery_with_non_equal_correlation() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+            .filter(col("t2.a").lt(out_ref_col(DataType::UInt32, "t1.a")))?
+            .aggregate(Vec::<Expr>::new(), vec![sum(col("t2.b"))])?
+            .project(vec![sum(col("t2.b"))])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .project(vec![col("t1.a"), scalar_subquery(Arc::new(subquery))])?
+            .build()?;
+
+        // The outer plan is left joined with the sum per domain value, which
+        // is left joined with the domain for the values without rows
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: t1.a, __dependent_sq_4.sum(t2.b) AS sum(t2.b)
+          Left Join:  Filter: t1.a IS NOT DISTINCT FROM __dependent_sq_4.__domain_1
+            TableScan: t1
+            SubqueryAlias: __dependent_sq_4
+              Projection: sum(t2.b), __domain_1
+                Projection: sum(t2.b), __domain_1
+                  Projection: sum(t2.b), __domain_1
+                    Left Join:  Filter: __domain_1 IS NOT DISTINCT FROM __domain_2
+                      Aggregate: groupBy=[[t1.a AS __domain_1]], aggr=[[]]
+                        TableScan: t1
+                      Projection: __domain_2, sum(t2.b), Boolean(true) AS __always_true_3
+                        Aggregate: groupBy=[[__domain_1 AS __domain_2]], aggr=[[sum(t2.b)]]
+                          Filter: t2.a < __domain_1
+                            Cross Join:
+                              TableScan: t2
+                              Aggregate: groupBy=[[t1.a AS __domain_1]], aggr=[[]]
+                                TableScan: t1
+        ")
+    }
+
+    #[test]
+    fn exists_with_limit() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+            .filter(col("t2.a").eq(out_ref_col(DataType::UInt32, "t1.a")))?
+            .sort(vec![col("t2.b").sort(true, false)])?
+            .limit(1, Some(1))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .filter(exists(Arc::new(subquery)))?
+            .build()?;
+
+        // The domain is eliminated by the equality with `t2.a`, and the rows
+        // are limited per domain value with `row_number`
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: t1.a, t1.b, t1.c
+          LeftSemi Join:  Filter: t1.a IS NOT DISTINCT FROM __dependent_sq_4.__domain_2
+            TableScan: t1
+            SubqueryAlias: __dependent_sq_4
+              Projection: __domain_2
+                Projection: t2.a, t2.b, t2.c, __domain_2
+                  Filter: __row_number_3 > UInt64(1) AND __row_number_3 <= UInt64(2)
+                    WindowAggr: windowExpr=[[row_number() PARTITION BY [__domain_2] ORDER BY [t2.b ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW AS __row_number_3]]
+                      Projection: t2.a, t2.b, t2.c, t2.a AS __domain_2
+                        Filter: t2.a IS NOT NULL
+                          TableScan: t2
+        ")
+    }
+
+    #[test]
+    fn nested_correlated_subqueries() -> Result<()> {
+        let innermost = LogicalPlanBuilder::from(test_table_scan_with_name("t3")?)
+            .filter(
+                col("t3.b")
+                    .eq(out_ref_col(DataType::UInt32, "t2.b"))
+                    .and(col("t3.c").gt(out_ref_col(DataType::UInt32, "t1.c"))),
+            )?
+            .build()?;
+        let subquery = LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+            .filter(
+                col("t2.a")
+                    .gt(out_ref_col(DataType::UInt32, "t1.a"))
+                    .and(exists(Arc::new(innermost))),
+            )?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .filter(exists(Arc::new(subquery)))?
+            .build()?;
+
+        // The innermost subquery references `t1.c`, which is replaced by the
+        // domain column `__domain_2` and decorrelated by the second pass
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: t1.a, t1.b, t1.c
+          LeftSemi Join:  Filter: t1.a IS NOT DISTINCT FROM __dependent_sq_3.__domain_1 AND t1.c IS NOT DISTINCT FROM __dependent_sq_3.__domain_2
+            TableScan: t1
+            SubqueryAlias: __dependent_sq_3
+              Projection: __domain_1, __domain_2
+                Projection: t2.a, t2.b, t2.c, __domain_1, __domain_2
+                  Filter: t2.a > __domain_1
+                    LeftSemi Join:  Filter: t2.b IS NOT DISTINCT FROM __dependent_sq_6.__domain_4 AND __domain_2 IS NOT DISTINCT FROM __dependent_sq_6.__domain_5
+                      Cross Join:
+                        TableScan: t2
+                        Aggregate: groupBy=[[t1.a AS __domain_1, t1.c AS __domain_2]], aggr=[[]]
+                          TableScan: t1
+                      SubqueryAlias: __dependent_sq_6
+                        Projection: __domain_4, __domain_5
+                          Filter: t3.b = __domain_4 AND t3.c > __domain_5
+                            Cross Join:
+                              TableScan: t3
+                              Aggregate: groupBy=[[t2.b AS __domain_4, __domain_2 AS __domain_5]], aggr=[[]]
+                                Cross Join:
+                                  TableScan: t2
+                                  Aggregate: groupBy=[[t1.a AS __domain_1, t1.c AS __domain_2]], aggr=[[]]
+                                    TableScan: t1
+        ")
+    }
+
+    #[test]
+    fn lateral_join_with_outer_reference_in_projection() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+            .filter(col("t2.a").eq(out_ref_col(DataType::UInt32, "t1.a")))?
+            .project(vec![
+                (col("t2.b") + out_ref_col(DataType::UInt32, "t1.b")).alias("s"),
+            ])?
+            .build()?;
+        let lateral = LogicalPlan::Subquery(Subquery {
+            outer_ref_columns: subquery.all_out_ref_exprs(),
+            subquery: Arc::new(subquery),
+            spans: Spans::new(),
+        });
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .cross_join(lateral)?
+            .build()?;
+
+        // The domain is not eliminated, as `t1.b` is not equal to an inner
+        // expression
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: t1.a, t1.b, t1.c, s
+          Inner Join:  Filter: t1.b IS NOT DISTINCT FROM __domain_1 AND t1.a IS NOT DISTINCT FROM __domain_2
+            TableScan: t1
+            Projection: s, __domain_1, __domain_2
+              Projection: t2.b + __domain_1 AS s, __domain_1, __domain_2
+                Filter: t2.a = __domain_2
+                  Cross Join:
+                    TableScan: t2
+                    Aggregate: groupBy=[[t1.b AS __domain_1, t1.a AS __domain_2]], aggr=[[]]
+                      TableScan: t1
+        ")
+    }
+
+    #[test]
+    fn disabled() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+            .filter(col("t2.a").lt(out_ref_col(DataType::UInt32, "t1.a")))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .filter(exists(Arc::new(subquery)))?
+            .build()?;
+        let optimized = optimize(plan.clone(), false)?;
+
+        assert_eq!(optimized, plan);
+        Ok(())
+    }
+}
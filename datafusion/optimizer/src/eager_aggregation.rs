@@ -0,0 +1,738 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`EagerAggregation`] pre-aggregates the inputs of inner joins below them
+
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use arrow::datatypes::DataType;
+use datafusion_common::alias::AliasGenerator;
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion_common::{Column, DFSchema, Dependency, NullEquality, Result};
+use datafusion_expr::expr::AggregateFunction;
+use datafusion_expr::logical_plan::TableScan;
+use datafusion_expr::{
+    Aggregate, AggregateUDF, Expr, ExprSchemable, Join, JoinType, LogicalPlan,
+    LogicalPlanBuilder, cast, lit, when,
+};
+
+/// Optimizer rule that pushes partial aggregates below inner joins, also
+/// known as eager aggregation.
+///
+/// An [`Aggregate`] over an inner [`Join`] whose aggregates only read one
+/// input of the join is rewritten to pre-aggregate that input on its join
+/// keys, and to combine the partial aggregates above the join. For example
+///
+/// ```text
+/// Aggregate: groupBy=[[d.region]], aggr=[[sum(f.amount)]]
+///   Inner Join: f.k = d.k
+///     TableScan: f
+///     TableScan: d
+/// ```
+///
+/// becomes
+///
+/// ```text
+/// Projection: d.region, sum(__eager_agg_1) AS sum(f.amount)
+///   Aggregate: groupBy=[[d.region]], aggr=[[sum(__eager_agg_1)]]
+///     Inner Join: f.k = d.k
+///       Aggregate: groupBy=[[f.k]], aggr=[[sum(f.amount) AS __eager_agg_1]]
+///         TableScan: f
+///       TableScan: d
+/// ```
+///
+/// so that the join builds and probes one row per key of `f` instead of one
+/// row per row of `f`.
+///
+/// The rewrite is done when
+///
+/// 1. all the aggregates are `sum`, `count`, `min`, `max` or `avg`, without
+///    `DISTINCT`, `FILTER` or `ORDER BY`,
+/// 2. unless they are all `min` or `max`, which ignore duplicates, the join
+///    keys of the other input are unique, as proven by its functional
+///    dependencies, so that each pre-aggregated row is joined at most once,
+/// 3. the statistics of the table the pre-aggregated input scans estimate
+///    that the pre-aggregation reduces its number of rows by the ratio
+///    `eager_aggregation_max_group_ratio`.
+#[derive(Default, Debug)]
+pub struct EagerAggregation {}
+
+impl EagerAggregation {
+    #[expect(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EagerAggregation {
+    fn name(&self) -> &str {
+        "eager_aggregation"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        None
+    }
+
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let options = config.options();
+        if !options.optimizer.enable_eager_aggregation {
+            return Ok(Transformed::no(plan));
+        }
+        let registry = config.function_registry();
+        let rewriter = EagerAggregator {
+            sum: registry.and_then(|registry| registry.udaf("sum").ok()),
+            count: registry.and_then(|registry| registry.udaf("count").ok()),
+            alias_generator: config.alias_generator(),
+            max_group_ratio: options.optimizer.eager_aggregation_max_group_ratio,
+        };
+
+        plan.transform_down_with_subqueries(|plan| {
+            let LogicalPlan::Aggregate(aggregate) = &plan else {
+                return Ok(Transformed::no(plan));
+            };
+            match rewriter.rewrite(aggregate)? {
+                // The pre-aggregated input is not rewritten again
+                Some(rewritten) => {
+                    Ok(Transformed::new(rewritten, true, TreeNodeRecursion::Jump))
+                }
+                None => Ok(Transformed::no(plan)),
+            }
+        })
+    }
+}
+
+/// How an aggregate is computed from partial aggregates
+enum Decomposition {
+    /// `combine(partial(x))`, e.g. `sum(count(x))` for `count(x)`
+    Combine {
+        partial: Arc<AggregateUDF>,
+        combine: Arc<AggregateUDF>,
+    },
+    /// `sum(sum(x)) / sum(count(x))` for `avg(x)`
+    Average {
+        sum: Arc<AggregateUDF>,
+        count: Arc<AggregateUDF>,
+    },
+}
+
+/// Rewrites aggregates over joins, see [`EagerAggregation`]
+struct EagerAggregator<'a> {
+    sum: Option<Arc<AggregateUDF>>,
+    count: Option<Arc<AggregateUDF>>,
+    alias_generator: &'a Arc<AliasGenerator>,
+    max_group_ratio: f64,
+}
+
+impl EagerAggregator<'_> {
+    /// Returns `aggregate` with its input pre-aggregated below the join,
+    /// or `None` if it can not or should not be
+    fn rewrite(&self, aggregate: &Aggregate) -> Result<Option<LogicalPlan>> {
+        let LogicalPlan::Join(join) = aggregate.input.as_ref() else {
+            return Ok(None);
+        };
+        if join.join_type != JoinType::Inner
+            || join.on.is_empty()
+            || aggregate
+                .group_expr
+                .iter()
+                .any(|expr| matches!(expr, Expr::GroupingSet(_)))
+        {
+            return Ok(None);
+        }
+
+        let mut decompositions = Vec::with_capacity(aggregate.aggr_expr.len());
+        for expr in &aggregate.aggr_expr {
+            let Some(decomposition) = self.decompose(expr, &join.schema)? else {
+                return Ok(None);
+            };
+            decompositions.push(decomposition);
+        }
+        let duplicate_insensitive = aggregate.aggr_expr.iter().all(|expr| {
+            matches!(aggregate_name(expr).as_deref(), Some("min") | Some("max"))
+        });
+
+        let mut aggregate_columns = vec![];
+        for expr in &aggregate.aggr_expr {
+            collect_columns(expr, None, &mut aggregate_columns)?;
+        }
+        for left in [true, false] {
+            let (input, other) = if left {
+                (&join.left, &join.right)
+            } else {
+                (&join.right, &join.left)
+            };
+            let keys = join
+                .on
+                .iter()
+                .map(|(l, r)| if left { (l, r) } else { (r, l) })
+                .collect::<Vec<_>>();
+            // An aggregated input is not aggregated again, in particular
+            // after this rule rewrote it
+            if matches!(input.as_ref(), LogicalPlan::Aggregate(_))
+                || !aggregate_columns
+                    .iter()
+                    .all(|column| input.schema().has_column(column))
+                || (!duplicate_insensitive
+                    && !is_unique(
+                        other.schema(),
+                        keys.iter().map(|(_, key)| *key),
+                        join.null_equality,
+                    ))
+            {
+                continue;
+            }
+
+            // The pre-aggregation keeps the columns of the input used above it
+            let schema = Some(input.schema().as_ref());
+            let mut group_columns = vec![];
+            for expr in &aggregate.group_expr {
+                collect_columns(expr, schema, &mut group_columns)?;
+            }
+            for (key, _) in &keys {
+                collect_columns(key, schema, &mut group_columns)?;
+            }
+            if let Some(filter) = &join.filter {
+                collect_columns(filter, schema, &mut group_columns)?;
+            }
+
+            let Some((num_rows, num_groups)) = estimate_groups(input, &group_columns)
+            else {
+                continue;
+            };
+            if num_groups > num_rows * self.max_group_ratio {
+                continue;
+            }
+
+            return self
+                .pre_aggregate(aggregate, join, left, group_columns, decompositions)
+                .map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Returns how `expr`, an aggregate of an input of schema `schema`, is
+    /// computed from partial aggregates, if it can be
+    fn decompose(&self, expr: &Expr, schema: &DFSchema) -> Result<Option<Decomposition>> {
+        let Expr::AggregateFunction(AggregateFunction { func, params }) =
+            strip_alias(expr)
+        else {
+            return Ok(None);
+        };
+        if params.distinct || params.filter.is_some() || !params.order_by.is_empty() {
+            return Ok(None);
+        }
+        let decomposition = match func.name().to_ascii_lowercase().as_str() {
+            "sum" | "min" | "max" => Some(Decomposition::Combine {
+                partial: Arc::clone(func),
+                combine: Arc::clone(func),
+            }),
+            "count" => self.sum.as_ref().map(|sum| Decomposition::Combine {
+                partial: Arc::clone(func),
+                combine: Arc::clone(sum),
+            }),
+            // Decimal averages are not computed by dividing sums
+            "avg" if expr.get_type(schema)? == DataType::Float64 => {
+                match (&self.sum, &self.count) {
+                    (Some(sum), Some(count)) => Some(Decomposition::Average {
+                        sum: Arc::clone(sum),
+                        count: Arc::clone(count),
+                    }),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        Ok(decomposition)
+    }
+
+    /// Rewrites `aggregate` to pre-aggregate the left input of `join` if
+    /// `left` is true, or its right input otherwise, on `group_columns`
+    fn pre_aggregate(
+        &self,
+        aggregate: &Aggregate,
+        join: &Join,
+        left: bool,
+        group_columns: Vec<Column>,
+        decompositions: Vec<Decomposition>,
+    ) -> Result<LogicalPlan> {
+        let mut partial_aggr_expr = vec![];
+        let mut final_aggr_expr = vec![];
+        let mut partial =
+            |func: Arc<AggregateUDF>, args: Vec<Expr>, combine: Arc<AggregateUDF>| {
+                let alias = self.alias_generator.next("__eager_agg");
+                partial_aggr_expr.push(aggregate_function(func, args).alias(&alias));
+                final_aggr_expr.push(aggregate_function(
+                    combine,
+                    vec![Expr::Column(Column::new_unqualified(alias))],
+                ));
+                final_aggr_expr.len() - 1
+            };
+        // The indexes of the final aggregates computing each aggregate
+        let mut final_indexes = Vec::with_capacity(decompositions.len());
+        for (expr, decomposition) in aggregate.aggr_expr.iter().zip(decompositions) {
+            let Expr::AggregateFunction(function) = strip_alias(expr) else {
+                unreachable!("decomposed expressions are aggregate functions");
+            };
+            let args = function.params.args.clone();
+            final_indexes.push(match decomposition {
+                Decomposition::Combine {
+                    partial: func,
+                    combine,
+                } => {
+                    vec![partial(func, args, combine)]
+                }
+                Decomposition::Average { sum, count } => {
+                    vec![
+                        partial(Arc::clone(&sum), args.clone(), Arc::clone(&sum)),
+                        partial(count, args, sum),
+                    ]
+                }
+            });
+        }
+
+        let (input, other) = if left {
+            (&join.left, &join.right)
+        } else {
+            (&join.right, &join.left)
+        };
+        let pre_aggregated = Arc::new(LogicalPlan::Aggregate(Aggregate::try_new(
+            Arc::clone(input),
+            group_columns.into_iter().map(Expr::Column).collect(),
+            partial_aggr_expr,
+        )?));
+        let (join_left, join_right) = if left {
+            (pre_aggregated, Arc::clone(other))
+        } else {
+            (Arc::clone(other), pre_aggregated)
+        };
+        let join = Join::try_new(
+            join_left,
+            join_right,
+            join.on.clone(),
+            join.filter.clone(),
+            join.join_type,
+            join.join_constraint,
+            join.null_equality,
+            join.null_aware,
+        )?;
+        let final_aggregate = LogicalPlan::Aggregate(Aggregate::try_new(
+            Arc::new(LogicalPlan::Join(join)),
+            aggregate.group_expr.clone(),
+            final_aggr_expr,
+        )?);
+
+        // Compute the aggregates from the final aggregates, with their
+        // original names and types
+        let final_schema = Arc::clone(final_aggregate.schema());
+        let num_groups = aggregate.group_expr.len();
+        let column = |idx: usize| {
+            Expr::Column(Column::from(final_schema.qualified_field(num_groups + idx)))
+        };
+        let mut exprs = (0..num_groups)
+            .map(|idx| Expr::Column(Column::from(final_schema.qualified_field(idx))))
+            .collect::<Vec<_>>();
+        for (expr, indexes) in aggregate.aggr_expr.iter().zip(final_indexes) {
+            let (qualifier, field) = aggregate.schema.qualified_field(exprs.len());
+            let mut result = match indexes.as_slice() {
+                [sum, count] => {
+                    cast(column(*sum), DataType::Float64)
+                        / cast(column(*count), DataType::Float64)
+                }
+                // The sum of no counts is null, while their count is 0
+                [idx]
+                    if num_groups == 0
+                        && aggregate_name(expr).as_deref() == Some("count") =>
+                {
+                    when(column(*idx).is_null(), lit(0_i64)).otherwise(column(*idx))?
+                }
+                [idx] => column(*idx),
+                _ => unreachable!("aggregates have one or two final aggregates"),
+            };
+            if result.get_type(&final_schema)? != *field.data_type() {
+                result = cast(result, field.data_type().clone());
+            }
+            exprs.push(result.alias_qualified(qualifier.cloned(), field.name()));
+        }
+        LogicalPlanBuilder::from(final_aggregate)
+            .project(exprs)?
+            .build()
+    }
+}
+
+fn aggregate_function(func: Arc<AggregateUDF>, args: Vec<Expr>) -> Expr {
+    Expr::AggregateFunction(AggregateFunction::new_udf(
+        func,
+        args,
+        false,
+        None,
+        vec![],
+        None,
+    ))
+}
+
+/// Returns the lowercase name of the aggregate function of `expr`, if any
+fn aggregate_name(expr: &Expr) -> Option<String> {
+    match strip_alias(expr) {
+        Expr::AggregateFunction(AggregateFunction { func, .. }) => {
+            Some(func.name().to_ascii_lowercase())
+        }
+        _ => None,
+    }
+}
+
+fn strip_alias(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Alias(alias) => strip_alias(&alias.expr),
+        _ => expr,
+    }
+}
+
+/// Adds the columns referenced by `expr` to `columns`, once each, restricted
+/// to the columns of `schema` if any
+fn collect_columns(
+    expr: &Expr,
+    schema: Option<&DFSchema>,
+    columns: &mut Vec<Column>,
+) -> Result<()> {
+    expr.apply(|expr| {
+        if let Expr::Column(column) = expr
+            && schema.is_none_or(|schema| schema.has_column(column))
+            && !columns.contains(column)
+        {
+            columns.push(column.clone());
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    Ok(())
+}
+
+/// Returns true if the rows of `schema` are unique on `keys`, i.e. each
+/// combination of values of the keys is joined with at most one row
+fn is_unique<'a>(
+    schema: &DFSchema,
+    keys: impl Iterator<Item = &'a Expr>,
+    null_equality: NullEquality,
+) -> bool {
+    let Some(key_indices) = keys
+        .map(|key| match key {
+            Expr::Column(column) => schema.maybe_index_of_column(column),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return false;
+    };
+    schema.functional_dependencies().iter().any(|dependency| {
+        dependency.mode == Dependency::Single
+            && dependency
+                .source_indices
+                .iter()
+                .all(|idx| key_indices.contains(idx))
+            // Unique keys may be null several times, null keys are only
+            // joined if nulls are equal
+            && (!dependency.nullable
+                || null_equality == NullEquality::NullEqualsNothing)
+    })
+}
+
+/// Returns the estimated number of rows of `plan` and number of distinct
+/// combinations of values of `columns` in it, from the statistics of the
+/// table it scans.
+///
+/// The filters above the scan are ignored, as they reduce both estimates.
+fn estimate_groups(plan: &LogicalPlan, columns: &[Column]) -> Option<(f64, f64)> {
+    let scan = scanned_table(plan)?;
+    let statistics = scan.source.statistics()?;
+    let num_rows = *statistics.num_rows.get_value()? as f64;
+    let mut num_groups = 1.0_f64;
+    for column in columns {
+        let (column_scan, idx) = source_column(plan, column)?;
+        if !std::ptr::eq(column_scan, scan) {
+            return None;
+        }
+        let distinct_count = statistics
+            .column_statistics
+            .get(idx)?
+            .distinct_count
+            .get_value()?;
+        num_groups *= *distinct_count as f64;
+    }
+    Some((num_rows, num_groups.min(num_rows)))
+}
+
+/// Returns the table scanned by `plan` if it only filters, projects or
+/// renames a scan
+fn scanned_table(plan: &LogicalPlan) -> Option<&TableScan> {
+    match plan {
+        LogicalPlan::TableScan(scan) => Some(scan),
+        LogicalPlan::Filter(filter) => scanned_table(&filter.input),
+        LogicalPlan::Projection(projection) => scanned_table(&projection.input),
+        LogicalPlan::SubqueryAlias(alias) => scanned_table(&alias.input),
+        _ => None,
+    }
+}
+
+/// Returns the scan `column` of `plan` is read from, and the index of the
+/// column in the schema of the scanned table
+fn source_column<'a>(
+    plan: &'a LogicalPlan,
+    column: &Column,
+) -> Option<(&'a TableScan, usize)> {
+    match plan {
+        LogicalPlan::TableScan(scan) => {
+            let idx = scan.projected_schema.maybe_index_of_column(column)?;
+            let idx = match &scan.projection {
+                Some(projection) => *projection.get(idx)?,
+                None => idx,
+            };
+            Some((scan, idx))
+        }
+        LogicalPlan::Filter(filter) => source_column(&filter.input, column),
+        LogicalPlan::Projection(projection) => {
+            let idx = projection.schema.maybe_index_of_column(column)?;
+            match strip_alias(projection.expr.get(idx)?) {
+                Expr::Column(column) => source_column(&projection.input, column),
+                _ => None,
+            }
+        }
+        LogicalPlan::SubqueryAlias(alias) => {
+            let idx = alias.schema.maybe_index_of_column(column)?;
+            let column = Column::from(alias.input.schema().qualified_field(idx));
+            source_column(&alias.input, &column)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::OptimizerContext;
+
+    use arrow::datatypes::{Field, Schema, SchemaRef};
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_common::stats::Precision;
+    use datafusion_common::{ColumnStatistics, Constraint, Constraints, Statistics};
+    use datafusion_expr::registry::{FunctionRegistry, MemoryFunctionRegistry};
+    use datafusion_expr::test::function_stub::{count, max, min, sum, sum_udaf};
+    use datafusion_expr::{TableSource, col};
+
+    /// An [`OptimizerConfig`] enabling eager aggregation, with the `sum`
+    /// function
+    struct TestConfig {
+        inner: OptimizerContext,
+        registry: MemoryFunctionRegistry,
+    }
+
+    impl TestConfig {
+        fn new() -> Result<Self> {
+            let mut options = ConfigOptions::default();
+            options.optimizer.enable_eager_aggregation = true;
+            let mut registry = MemoryFunctionRegistry::new();
+            registry.register_udaf(sum_udaf())?;
+            Ok(Self {
+                inner: OptimizerContext::new_with_config_options(Arc::new(options)),
+                registry,
+            })
+        }
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+            self.inner.query_execution_start_time()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            self.inner.alias_generator()
+        }
+
+        fn options(&self) -> Arc<ConfigOptions> {
+            self.inner.options()
+        }
+
+        fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
+            Some(&self.registry)
+        }
+    }
+
+    /// A table with constraints and statistics
+    struct StatisticsTableSource {
+        schema: SchemaRef,
+        constraints: Constraints,
+        statistics: Option<Statistics>,
+    }
+
+    impl TableSource for StatisticsTableSource {
+        fn schema(&self) -> SchemaRef {
+            Arc::clone(&self.schema)
+        }
+
+        fn constraints(&self) -> Option<&Constraints> {
+            Some(&self.constraints)
+        }
+
+        fn statistics(&self) -> Option<Statistics> {
+            self.statistics.clone()
+        }
+    }
+
+    /// Scans table `name` of `num_rows` rows with `Int64` columns `k` and
+    /// `v`, where `k` has `distinct_keys` distinct values and is the primary
+    /// key if `primary_key` is true
+    fn scan(
+        name: &str,
+        num_rows: Option<usize>,
+        distinct_keys: usize,
+        primary_key: bool,
+    ) -> Result<LogicalPlanBuilder> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int64, false),
+            Field::new("v", DataType::Int64, true),
+        ]));
+        let statistics = num_rows.map(|num_rows| Statistics {
+            num_rows: Precision::Inexact(num_rows),
+            total_byte_size: Precision::Absent,
+            column_statistics: vec![
+                ColumnStatistics::new_unknown()
+                    .with_distinct_count(Precision::Inexact(distinct_keys)),
+                ColumnStatistics::new_unknown(),
+            ],
+        });
+        let constraints = if primary_key {
+            Constraints::new_unverified(vec![Constraint::PrimaryKey(vec![0])])
+        } else {
+            Constraints::default()
+        };
+        let source = StatisticsTableSource {
+            schema,
+            constraints,
+            statistics,
+        };
+        LogicalPlanBuilder::scan(name, Arc::new(source), None)
+    }
+
+    /// `f JOIN d ON f.k = d.k` of a fact table with a thousand keys and a
+    /// dimension table of a thousand rows
+    fn fact_dimension_join(
+        fact_rows: Option<usize>,
+        dimension_primary_key: bool,
+    ) -> Result<LogicalPlanBuilder> {
+        let dimension = scan("d", Some(1000), 1000, dimension_primary_key)?.build()?;
+        scan("f", fact_rows, 1000, false)?.join(
+            dimension,
+            JoinType::Inner,
+            (vec!["f.k"], vec!["d.k"]),
+            None,
+        )
+    }
+
+    fn rewrite(plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        let rewritten =
+            EagerAggregation::new().rewrite(plan.clone(), &TestConfig::new()?)?;
+        if !rewritten.transformed {
+            return Ok(None);
+        }
+        assert!(
+            rewritten
+                .data
+                .schema()
+                .logically_equivalent_names_and_types(plan.schema())
+        );
+        Ok(Some(rewritten.data))
+    }
+
+    #[test]
+    fn pre_aggregate_below_join() -> Result<()> {
+        let plan = fact_dimension_join(Some(1_000_000), true)?
+            .aggregate(vec![col("d.v")], vec![sum(col("f.v")), count(col("f.v"))])?
+            .build()?;
+        insta::assert_snapshot!(rewrite(&plan)?.unwrap(), @r"
+        Projection: d.v, sum(__eager_agg_1) AS sum(f.v), sum(__eager_agg_2) AS COUNT(f.v)
+          Aggregate: groupBy=[[d.v]], aggr=[[sum(__eager_agg_1), sum(__eager_agg_2)]]
+            Inner Join: f.k = d.k
+              Aggregate: groupBy=[[f.k]], aggr=[[sum(f.v) AS __eager_agg_1, COUNT(f.v) AS __eager_agg_2]]
+                TableScan: f
+              TableScan: d
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn min_max_without_unique_keys() -> Result<()> {
+        let plan = fact_dimension_join(Some(1_000_000), false)?
+            .aggregate(vec![col("d.v")], vec![min(col("f.v")), max(col("f.v"))])?
+            .build()?;
+        insta::assert_snapshot!(rewrite(&plan)?.unwrap(), @r"
+        Projection: d.v, min(__eager_agg_1) AS min(f.v), max(__eager_agg_2) AS max(f.v)
+          Aggregate: groupBy=[[d.v]], aggr=[[min(__eager_agg_1), max(__eager_agg_2)]]
+            Inner Join: f.k = d.k
+              Aggregate: groupBy=[[f.k]], aggr=[[min(f.v) AS __eager_agg_1, max(f.v) AS __eager_agg_2]]
+                TableScan: f
+              TableScan: d
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn no_pre_aggregation_without_unique_keys() -> Result<()> {
+        let plan = fact_dimension_join(Some(1_000_000), false)?
+            .aggregate(vec![col("d.v")], vec![sum(col("f.v"))])?
+            .build()?;
+        assert!(rewrite(&plan)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn no_pre_aggregation_without_reduction() -> Result<()> {
+        // As many rows as keys
+        let plan = fact_dimension_join(Some(1000), true)?
+            .aggregate(vec![col("d.v")], vec![sum(col("f.v"))])?
+            .build()?;
+        assert!(rewrite(&plan)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn no_pre_aggregation_without_statistics() -> Result<()> {
+        let plan = fact_dimension_join(None, true)?
+            .aggregate(vec![col("d.v")], vec![sum(col("f.v"))])?
+            .build()?;
+        assert!(rewrite(&plan)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn no_pre_aggregation_of_both_inputs() -> Result<()> {
+        let plan = fact_dimension_join(Some(1_000_000), true)?
+            .aggregate(Vec::<Expr>::new(), vec![sum(col("f.v")), sum(col("d.v"))])?
+            .build()?;
+        assert!(rewrite(&plan)?.is_none());
+        Ok(())
+    }
+}
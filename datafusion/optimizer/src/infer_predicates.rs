@@ -0,0 +1,490 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`InferPredicates`] infers predicates on join keys from the predicates
+//! on the keys they are equal to
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::collation::Collation;
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion_common::{Column, DFSchema, ExprSchema, Result};
+use datafusion_expr::expr::{Between, InList};
+use datafusion_expr::expr_rewriter::replace_col;
+use datafusion_expr::utils::{conjunction, split_conjunction};
+use datafusion_expr::{BinaryExpr, Expr, Filter, JoinType, LogicalPlan, Operator};
+
+/// Infers predicates from the equalities of trees of inner joins and
+/// filters.
+///
+/// The columns that are equal to each other, as keys of inner joins or in
+/// filters, form equivalence classes. A predicate comparing a column to
+/// literals, i.e. a comparison, `BETWEEN`, an `IN` list or `IS NOT NULL`,
+/// holds for all the columns of its class. For example in
+///
+/// ```text
+/// Filter: a.x > 10
+///   Inner Join: b.x = c.x
+///     Inner Join: a.x = b.x
+///       TableScan: a
+///       TableScan: b
+///     TableScan: c
+/// ```
+///
+/// `b.x > 10` and `c.x > 10` are inferred. The inferred predicates are added
+/// in a filter above the tree, for [`PushDownFilter`] to push them to the
+/// inputs of the joins and then to the scans, where they can prune files.
+///
+/// Outer joins are not part of the trees: the trees above and below them
+/// are considered separately.
+///
+/// [`PushDownFilter`]: crate::push_down_filter::PushDownFilter
+#[derive(Default, Debug)]
+pub struct InferPredicates {}
+
+impl InferPredicates {
+    #[expect(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for InferPredicates {
+    fn name(&self) -> &str {
+        "infer_predicates"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        None
+    }
+
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        if !config.options().optimizer.enable_predicate_inference {
+            return Ok(Transformed::no(plan));
+        }
+        infer_predicates(plan)
+    }
+}
+
+/// Returns true if `plan` is part of a tree of inner joins and filters
+fn is_tree_node(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Filter(_) => true,
+        LogicalPlan::Join(join) => join.join_type == JoinType::Inner,
+        _ => false,
+    }
+}
+
+/// Adds the predicates inferred for each tree of inner joins and filters
+/// of `plan` above it
+fn infer_predicates(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    plan.transform_down(|plan| {
+        if !is_tree_node(&plan) {
+            return Ok(Transformed::no(plan));
+        }
+
+        let mut tree = JoinTree::default();
+        tree.collect(&plan);
+        let inferred = tree.infer(plan.schema())?;
+
+        let Transformed {
+            data: plan,
+            transformed,
+            ..
+        } = infer_in_inputs(plan)?;
+        let (plan, transformed) = match conjunction(inferred) {
+            Some(predicate) => (
+                LogicalPlan::Filter(Filter::try_new(predicate, Arc::new(plan))?),
+                true,
+            ),
+            None => (plan, transformed),
+        };
+        // The nodes of the tree were handled above
+        Ok(Transformed::new(plan, transformed, TreeNodeRecursion::Jump))
+    })
+}
+
+/// Infers predicates in the inputs of the tree of inner joins and filters
+/// `plan`
+fn infer_in_inputs(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    plan.map_children(|child| {
+        if is_tree_node(&child) {
+            infer_in_inputs(child)
+        } else {
+            infer_predicates(child)
+        }
+    })
+}
+
+/// The equalities and predicates of a tree of inner joins and filters. All
+/// of them hold for the output of the tree.
+#[derive(Default)]
+struct JoinTree {
+    /// Pairs of equal columns
+    equalities: Vec<(Column, Column)>,
+    /// Conjuncts of the filters and join filters
+    predicates: Vec<Expr>,
+}
+
+impl JoinTree {
+    fn collect(&mut self, plan: &LogicalPlan) {
+        match plan {
+            LogicalPlan::Filter(filter) => self.add_predicate(&filter.predicate),
+            LogicalPlan::Join(join) => {
+                for (left, right) in &join.on {
+                    if let (Some(left), Some(right)) =
+                        (left.try_as_col(), right.try_as_col())
+                    {
+                        self.equalities.push((left.clone(), right.clone()));
+                    }
+                }
+                if let Some(filter) = &join.filter {
+                    self.add_predicate(filter);
+                }
+            }
+            _ => return,
+        }
+        for input in plan.inputs() {
+            if is_tree_node(input) {
+                self.collect(input);
+            }
+        }
+    }
+
+    fn add_predicate(&mut self, predicate: &Expr) {
+        for conjunct in split_conjunction(predicate) {
+            if let Expr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::Eq,
+                right,
+            }) = conjunct
+                && let (Some(left), Some(right)) = (left.try_as_col(), right.try_as_col())
+                && left != right
+            {
+                self.equalities.push((left.clone(), right.clone()));
+            }
+            self.predicates.push(conjunct.clone());
+        }
+    }
+
+    /// Returns the equivalence classes of the columns of the equalities, with
+    /// the columns of each class in the order they first appear
+    fn equivalence_classes(&self) -> Vec<Vec<&Column>> {
+        let mut index: HashMap<&Column, usize> = HashMap::new();
+        let mut columns = vec![];
+        let mut parent: Vec<usize> = vec![];
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        for (left, right) in &self.equalities {
+            let [left, right] = [left, right].map(|column| {
+                *index.entry(column).or_insert_with(|| {
+                    columns.push(column);
+                    parent.push(parent.len());
+                    parent.len() - 1
+                })
+            });
+            let (left, right) = (find(&mut parent, left), find(&mut parent, right));
+            parent[right] = left;
+        }
+
+        let mut classes: Vec<Vec<&Column>> = vec![];
+        let mut class_index = HashMap::new();
+        for (i, column) in columns.into_iter().enumerate() {
+            let root = find(&mut parent, i);
+            let class = *class_index.entry(root).or_insert_with(|| {
+                classes.push(vec![]);
+                classes.len() - 1
+            });
+            classes[class].push(column);
+        }
+        classes
+    }
+
+    /// Returns the predicates inferred for the columns equal to the columns
+    /// of the predicates, if they are not already part of the tree
+    fn infer(&self, schema: &DFSchema) -> Result<Vec<Expr>> {
+        if self.equalities.is_empty() {
+            return Ok(vec![]);
+        }
+        let classes = self.equivalence_classes();
+        let class_of: HashMap<&Column, &[&Column]> = classes
+            .iter()
+            .flat_map(|class| class.iter().map(move |column| (*column, class.as_slice())))
+            .collect();
+
+        let existing = self.predicates.iter().collect::<HashSet<_>>();
+        let mut inferred = vec![];
+        for predicate in &self.predicates {
+            let Some(column) = column_predicate(predicate) else {
+                continue;
+            };
+            let Some(class) = class_of.get(column) else {
+                continue;
+            };
+            let Ok(field) = schema.field_from_column(column) else {
+                continue;
+            };
+            let Ok(collation) = Collation::from_field(field) else {
+                continue;
+            };
+            for &other in class.iter() {
+                // Predicates are only copied to columns of the same type and
+                // collation, to compare them to the same literals the same way
+                if other == column
+                    || !schema.field_from_column(other).is_ok_and(|f| {
+                        f.data_type() == field.data_type()
+                            && Collation::from_field(f).is_ok_and(|c| c == collation)
+                    })
+                {
+                    continue;
+                }
+                let predicate =
+                    replace_col(predicate.clone(), &HashMap::from([(column, other)]))?;
+                if !existing.contains(&predicate) && !inferred.contains(&predicate) {
+                    inferred.push(predicate);
+                }
+            }
+        }
+        Ok(inferred)
+    }
+}
+
+/// Returns the column of `predicate` if it compares a single column to
+/// literals. Such predicates are false or null if the column is null.
+fn column_predicate(predicate: &Expr) -> Option<&Column> {
+    match predicate {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op:
+                Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq,
+            right,
+        }) => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(column), Expr::Literal(..))
+            | (Expr::Literal(..), Expr::Column(column)) => Some(column),
+            _ => None,
+        },
+        Expr::Between(Between {
+            expr, low, high, ..
+        }) if matches!(
+            (low.as_ref(), high.as_ref()),
+            (Expr::Literal(..), Expr::Literal(..))
+        ) =>
+        {
+            expr.try_as_col()
+        }
+        Expr::InList(InList { expr, list, .. })
+            if list.iter().all(|e| matches!(e, Expr::Literal(..))) =>
+        {
+            expr.try_as_col()
+        }
+        Expr::IsNotNull(expr) => expr.try_as_col(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+    use crate::{OptimizerContext, assert_optimized_plan_eq_snapshot};
+
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_expr::logical_plan::table_scan;
+    use datafusion_expr::{LogicalPlanBuilder, col, lit};
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let mut options = ConfigOptions::default();
+            options.optimizer.enable_predicate_inference = true;
+            options.optimizer.max_passes = 1;
+            let optimizer_ctx =
+                OptimizerContext::new_with_config_options(Arc::new(options));
+            let rules: Vec<Arc<dyn crate::OptimizerRule + Send + Sync>> =
+                vec![Arc::new(InferPredicates::new())];
+            assert_optimized_plan_eq_snapshot!(
+                optimizer_ctx,
+                rules,
+                $plan,
+                @ $expected,
+            )
+        }};
+    }
+
+    /// `t1 JOIN t2 ON t1.a = t2.a JOIN t3 ON t2.a = t3.a`
+    fn three_way_join() -> Result<LogicalPlanBuilder> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+        let t3 = test_table_scan_with_name("t3")?;
+        LogicalPlanBuilder::from(t1)
+            .join(t2, JoinType::Inner, (vec!["t1.a"], vec!["t2.a"]), None)?
+            .join(t3, JoinType::Inner, (vec!["t2.a"], vec!["t3.a"]), None)
+    }
+
+    #[test]
+    fn infer_through_multi_way_join() -> Result<()> {
+        let plan = three_way_join()?
+            .filter(col("t1.a").gt(lit(10u32)))?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Filter: t2.a > UInt32(10) AND t3.a > UInt32(10)
+          Filter: t1.a > UInt32(10)
+            Inner Join: t2.a = t3.a
+              Inner Join: t1.a = t2.a
+                TableScan: t1
+                TableScan: t2
+              TableScan: t3
+        ")
+    }
+
+    #[test]
+    fn infer_in_list_and_is_not_null() -> Result<()> {
+        let plan = three_way_join()?
+            .filter(
+                col("t3.a")
+                    .in_list(vec![lit(1u32), lit(2u32)], false)
+                    .and(col("t1.a").is_not_null())
+                    .and(col("t2.a").is_not_null()),
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Filter: t2.a IN ([UInt32(1), UInt32(2)]) AND t1.a IN ([UInt32(1), UInt32(2)]) AND t3.a IS NOT NULL
+          Filter: t3.a IN ([UInt32(1), UInt32(2)]) AND t1.a IS NOT NULL AND t2.a IS NOT NULL
+            Inner Join: t2.a = t3.a
+              Inner Join: t1.a = t2.a
+                TableScan: t1
+                TableScan: t2
+              TableScan: t3
+        ")
+    }
+
+    #[test]
+    fn infer_from_predicate_below_join() -> Result<()> {
+        let t1 = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .filter(col("t1.b").between(lit(1u32), lit(5u32)))?
+            .build()?;
+        let t2 = test_table_scan_with_name("t2")?;
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(t2, JoinType::Inner, (vec!["t1.b"], vec!["t2.b"]), None)?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Filter: t2.b BETWEEN UInt32(1) AND UInt32(5)
+          Inner Join: t1.b = t2.b
+            Filter: t1.b BETWEEN UInt32(1) AND UInt32(5)
+              TableScan: t1
+            TableScan: t2
+        ")
+    }
+
+    #[test]
+    fn no_inference_through_outer_join() -> Result<()> {
+        let t1 = test_table_scan_with_name("t1")?;
+        let t2 = test_table_scan_with_name("t2")?;
+        let plan = LogicalPlanBuilder::from(t1)
+            .join(t2, JoinType::Left, (vec!["t1.a"], vec!["t2.a"]), None)?
+            .filter(col("t1.a").gt(lit(10u32)))?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Filter: t1.a > UInt32(10)
+          Left Join: t1.a = t2.a
+            TableScan: t1
+            TableScan: t2
+        ")
+    }
+
+    #[test]
+    fn no_inference_for_non_literal_predicates() -> Result<()> {
+        let plan = three_way_join()?
+            .filter(col("t1.a").gt(col("t1.b")))?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Filter: t1.a > t1.b
+          Inner Join: t2.a = t3.a
+            Inner Join: t1.a = t2.a
+              TableScan: t1
+              TableScan: t2
+            TableScan: t3
+        ")
+    }
+
+    #[test]
+    fn no_inference_between_collations() -> Result<()> {
+        let scan = |name: &str, collation: Collation| -> Result<LogicalPlan> {
+            let field =
+                collation.apply_to_field(Field::new("s", DataType::Utf8, true))?;
+            table_scan(Some(name), &Schema::new(vec![field]), None)?.build()
+        };
+        let plan = LogicalPlanBuilder::from(scan("t1", Collation::CaseInsensitive)?)
+            .join(
+                scan("t2", Collation::Binary)?,
+                JoinType::Inner,
+                (vec!["t1.s"], vec!["t2.s"]),
+                None,
+            )?
+            .join(
+                scan("t3", Collation::CaseInsensitive)?,
+                JoinType::Inner,
+                (vec!["t1.s"], vec!["t3.s"]),
+                None,
+            )?
+            .filter(col("t1.s").eq(lit("alice")))?
+            .build()?;
+
+        // `t1.s = 'alice'` also holds for `ALICE`, so it is only copied to
+        // the column with the same collation
+        assert_optimized_plan_equal!(plan, @r#"
+        Filter: t3.s = Utf8("alice")
+          Filter: t1.s = Utf8("alice")
+            Inner Join: t1.s = t3.s
+              Inner Join: t1.s = t2.s
+                TableScan: t1
+                TableScan: t2
+              TableScan: t3
+        "#)
+    }
+}
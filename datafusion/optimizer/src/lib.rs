@@ -41,8 +41,10 @@
 pub mod analyzer;
 pub mod common_subexpr_eliminate;
 pub mod decorrelate;
+pub mod decorrelate_dependent_join;
 pub mod decorrelate_lateral_join;
 pub mod decorrelate_predicate_subquery;
+pub mod eager_aggregation;
 pub mod eliminate_cross_join;
 pub mod eliminate_duplicated_expr;
 pub mod eliminate_filter;
@@ -59,6 +61,8 @@ pub mod eliminate_outer_join;
 pub mod extract_equijoin_predicate;
 pub mod extract_leaf_expressions;
 pub mod filter_null_join_keys;
+pub mod infer_predicates;
+pub mod materialized_view_rewrite;
 pub mod optimize_projections;
 pub mod optimize_unions;
 pub mod optimizer;
@@ -0,0 +1,602 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`MaterializedViewRewrite`] answers queries from materialized views
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::tree_node::{Transformed, TreeNodeRecursion};
+use datafusion_common::{Column, DFSchema, Result, TableReference};
+use datafusion_expr::expr::{AggregateFunction, ScalarFunction};
+use datafusion_expr::{
+    Aggregate, AggregateUDF, Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder,
+    ScalarUDF, TableSource, cast, lit,
+};
+
+/// A materialized view that [`MaterializedViewRewrite`] may use to answer
+/// queries.
+#[derive(Clone)]
+pub struct MaterializedViewCandidate {
+    /// The fully qualified name the view is registered under
+    name: TableReference,
+    /// The analyzed logical plan the view was computed from
+    definition: LogicalPlan,
+    /// The source scanned to read the stored result of `definition`
+    source: Arc<dyn TableSource>,
+}
+
+impl MaterializedViewCandidate {
+    /// Create a new candidate named `name` whose contents are the result of
+    /// `definition`, stored in `source`.
+    ///
+    /// `definition` must have been run through the same analyzer rules as
+    /// the queries it should be matched against, as plans are compared
+    /// structurally.
+    pub fn new(
+        name: TableReference,
+        definition: LogicalPlan,
+        source: Arc<dyn TableSource>,
+    ) -> Self {
+        Self {
+            name,
+            definition,
+            source,
+        }
+    }
+
+    /// The name the view is registered under
+    pub fn name(&self) -> &TableReference {
+        &self.name
+    }
+
+    /// The logical plan the view was computed from
+    pub fn definition(&self) -> &LogicalPlan {
+        &self.definition
+    }
+
+    fn scan(&self) -> Result<LogicalPlanBuilder> {
+        LogicalPlanBuilder::scan(self.name.clone(), Arc::clone(&self.source), None)
+    }
+}
+
+impl fmt::Debug for MaterializedViewCandidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MaterializedViewCandidate")
+            .field("name", &self.name)
+            .field("definition", &self.definition)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Optimizer rule that replaces the parts of a plan that compute the same
+/// result as a materialized view with a scan of that view.
+///
+/// The materialized views are provided by
+/// [`OptimizerConfig::materialized_views`]. A subtree is rewritten when
+///
+/// 1. it is identical to the definition of a view, or
+/// 2. it is an [`Aggregate`] over the same input as an aggregate view whose
+///    grouping expressions are a subset of the view's grouping expressions,
+///    and whose aggregates are all computed by the view. If the grouping
+///    expressions differ, the view's aggregates are rolled up to the coarser
+///    grouping, which is supported for `sum`, `min`, `max`, `count`,
+///    `bool_and`, `bool_or`, `bit_and`, `bit_or` and `bit_xor`. Counts are
+///    rolled up as `coalesce(sum(count), 0)`, which requires the `sum` and
+///    `coalesce` functions to be registered.
+///
+/// For example, given
+///
+/// ```sql
+/// CREATE MATERIALIZED VIEW sales_by_day AS
+///   SELECT region, day, sum(amount) AS total FROM sales GROUP BY region, day;
+/// ```
+///
+/// the query `SELECT region, sum(amount) FROM sales GROUP BY region` is
+/// answered by `SELECT region, sum(total) FROM sales_by_day GROUP BY region`.
+///
+/// This rule assumes the contents of the views are up to date with their
+/// inputs. It runs before any other optimizer rule, so the plans it sees
+/// have the same shape as the view definitions.
+#[derive(Default, Debug)]
+pub struct MaterializedViewRewrite {}
+
+impl MaterializedViewRewrite {
+    #[expect(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for MaterializedViewRewrite {
+    fn name(&self) -> &str {
+        "materialized_view_rewrite"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        None
+    }
+
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let views = config.materialized_views();
+        if views.is_empty()
+            || !config.options().optimizer.enable_materialized_view_rewrite
+        {
+            return Ok(Transformed::no(plan));
+        }
+        let functions = config
+            .function_registry()
+            .map(|registry| RollupFunctions {
+                sum: registry.udaf("sum").ok(),
+                coalesce: registry.udf("coalesce").ok(),
+            })
+            .unwrap_or_default();
+
+        plan.transform_down_with_subqueries(|plan| {
+            for view in views {
+                if let Some(rewritten) = rewrite_with_view(&plan, view, &functions)? {
+                    return Ok(Transformed::new(
+                        rewritten,
+                        true,
+                        TreeNodeRecursion::Jump,
+                    ));
+                }
+            }
+            Ok(Transformed::no(plan))
+        })
+    }
+}
+
+/// The registered functions used to roll up counts
+#[derive(Default)]
+struct RollupFunctions {
+    sum: Option<Arc<AggregateUDF>>,
+    coalesce: Option<Arc<ScalarUDF>>,
+}
+
+/// Returns `plan` computed from `view`, or `None` if `view` can not be used
+fn rewrite_with_view(
+    plan: &LogicalPlan,
+    view: &MaterializedViewCandidate,
+    functions: &RollupFunctions,
+) -> Result<Option<LogicalPlan>> {
+    if plan == view.definition() {
+        let scan = view.scan()?.build()?;
+        let columns = (0..scan.schema().fields().len()).collect::<Vec<_>>();
+        let exprs = columns_of(&scan, &columns);
+        return project_to_schema(scan, exprs, plan.schema()).map(Some);
+    }
+
+    let LogicalPlan::Aggregate(aggregate) = plan else {
+        return Ok(None);
+    };
+    let Some((view_aggregate, view_columns)) = view_aggregate(view.definition()) else {
+        return Ok(None);
+    };
+    if aggregate.input != view_aggregate.input
+        || has_grouping_sets(aggregate)
+        || has_grouping_sets(view_aggregate)
+    {
+        return Ok(None);
+    }
+
+    // The view column holding each grouping expression of the query
+    let mut group_columns = Vec::with_capacity(aggregate.group_expr.len());
+    for expr in &aggregate.group_expr {
+        let Some(column) = position(&view_aggregate.group_expr, expr)
+            .and_then(|idx| view_columns.get(&idx))
+        else {
+            return Ok(None);
+        };
+        group_columns.push(*column);
+    }
+    let num_view_groups = view_aggregate.group_expr.len();
+    // The view column holding each aggregate of the query
+    let mut aggregate_columns = Vec::with_capacity(aggregate.aggr_expr.len());
+    for expr in &aggregate.aggr_expr {
+        let Some(column) = position(&view_aggregate.aggr_expr, expr)
+            .and_then(|idx| view_columns.get(&(num_view_groups + idx)))
+        else {
+            return Ok(None);
+        };
+        aggregate_columns.push(*column);
+    }
+
+    let scan = view.scan()?;
+    if aggregate.group_expr.len() == num_view_groups {
+        // Same grouping: each row of the view is a row of the result
+        let scan = scan.build()?;
+        let columns = group_columns
+            .into_iter()
+            .chain(aggregate_columns)
+            .collect::<Vec<_>>();
+        let exprs = columns_of(&scan, &columns);
+        return project_to_schema(scan, exprs, &aggregate.schema).map(Some);
+    }
+
+    // Coarser grouping: roll up the aggregates of the view
+    let scan_schema = Arc::clone(scan.schema());
+    let column =
+        |idx: usize| Expr::Column(Column::from(scan_schema.qualified_field(idx)));
+    let mut rollups = Vec::with_capacity(aggregate.aggr_expr.len());
+    let mut finals = Vec::with_capacity(aggregate.aggr_expr.len());
+    for (expr, idx) in aggregate.aggr_expr.iter().zip(aggregate_columns) {
+        let Some((rollup, coalesce)) = rollup_function(expr, functions) else {
+            return Ok(None);
+        };
+        rollups.push(Expr::AggregateFunction(AggregateFunction::new_udf(
+            rollup,
+            vec![column(idx)],
+            false,
+            None,
+            vec![],
+            None,
+        )));
+        finals.push(coalesce);
+    }
+    let num_groups = group_columns.len();
+    let groups = group_columns.into_iter().map(column).collect::<Vec<_>>();
+    let rolled_up = scan.aggregate(groups, rollups)?.build()?;
+    let columns = (0..rolled_up.schema().fields().len()).collect::<Vec<_>>();
+    let mut exprs = columns_of(&rolled_up, &columns);
+    for (expr, coalesce) in exprs[num_groups..].iter_mut().zip(finals) {
+        // The sum of no counts is NULL rather than 0
+        if let Some(coalesce) = coalesce {
+            *expr = Expr::ScalarFunction(ScalarFunction::new_udf(
+                coalesce,
+                vec![expr.clone(), lit(0i64)],
+            ));
+        }
+    }
+    project_to_schema(rolled_up, exprs, &aggregate.schema).map(Some)
+}
+
+/// Returns the aggregate computed by a view definition, and a map from the
+/// index of each output column of the aggregate to the view column it is
+/// stored in
+fn view_aggregate(
+    definition: &LogicalPlan,
+) -> Option<(&Aggregate, HashMap<usize, usize>)> {
+    match definition {
+        LogicalPlan::Aggregate(aggregate) => {
+            let columns = (0..aggregate.schema.fields().len()).map(|idx| (idx, idx));
+            Some((aggregate, columns.collect()))
+        }
+        LogicalPlan::Projection(projection) => {
+            let LogicalPlan::Aggregate(aggregate) = projection.input.as_ref() else {
+                return None;
+            };
+            let mut columns = HashMap::new();
+            for (view_idx, expr) in projection.expr.iter().enumerate() {
+                if let Expr::Column(column) = strip_alias(expr)
+                    && let Some(idx) = aggregate.schema.maybe_index_of_column(column)
+                {
+                    columns.entry(idx).or_insert(view_idx);
+                }
+            }
+            Some((aggregate, columns))
+        }
+        _ => None,
+    }
+}
+
+/// Returns expressions referencing `columns` of `input`
+fn columns_of(input: &LogicalPlan, columns: &[usize]) -> Vec<Expr> {
+    columns
+        .iter()
+        .map(|idx| Expr::Column(Column::from(input.schema().qualified_field(*idx))))
+        .collect()
+}
+
+/// Projects `exprs` over `input` to the names and types of `schema`
+fn project_to_schema(
+    input: LogicalPlan,
+    exprs: Vec<Expr>,
+    schema: &DFSchema,
+) -> Result<LogicalPlan> {
+    let exprs = exprs
+        .into_iter()
+        .zip(schema.iter())
+        .map(|(mut expr, (qualifier, field))| {
+            if expr.get_type(input.schema())? != *field.data_type() {
+                expr = cast(expr, field.data_type().clone());
+            }
+            Ok(expr.alias_qualified(qualifier.cloned(), field.name()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    LogicalPlanBuilder::from(input).project(exprs)?.build()
+}
+
+/// Returns the function that combines partial results of `expr` into the
+/// result for a coarser grouping, if any, along with the function its result
+/// is passed to with a default of 0
+fn rollup_function(
+    expr: &Expr,
+    functions: &RollupFunctions,
+) -> Option<(Arc<AggregateUDF>, Option<Arc<ScalarUDF>>)> {
+    let Expr::AggregateFunction(AggregateFunction { func, params }) = strip_alias(expr)
+    else {
+        return None;
+    };
+    if params.distinct || params.filter.is_some() || !params.order_by.is_empty() {
+        return None;
+    }
+    match func.name().to_ascii_lowercase().as_str() {
+        "sum" | "min" | "max" | "bool_and" | "bool_or" | "bit_and" | "bit_or"
+        | "bit_xor" => Some((Arc::clone(func), None)),
+        "count" => Some((
+            Arc::clone(functions.sum.as_ref()?),
+            Some(Arc::clone(functions.coalesce.as_ref()?)),
+        )),
+        _ => None,
+    }
+}
+
+fn has_grouping_sets(aggregate: &Aggregate) -> bool {
+    aggregate
+        .group_expr
+        .iter()
+        .any(|expr| matches!(expr, Expr::GroupingSet(_)))
+}
+
+/// Returns the index of the expression in `exprs` equal to `expr`, ignoring
+/// aliases
+fn position(exprs: &[Expr], expr: &Expr) -> Option<usize> {
+    let expr = strip_alias(expr);
+    exprs.iter().position(|e| strip_alias(e) == expr)
+}
+
+fn strip_alias(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Alias(alias) => strip_alias(&alias.expr),
+        _ => expr,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::OptimizerContext;
+
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::alias::AliasGenerator;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_expr::logical_plan::builder::LogicalTableSource;
+    use datafusion_expr::logical_plan::table_scan;
+    use datafusion_expr::test::function_stub::{count, max, sum, sum_udaf};
+    use datafusion_expr::{ExprFunctionExt, Volatility, col, create_udf};
+
+    /// An [`OptimizerConfig`] providing materialized views
+    struct TestConfig {
+        inner: OptimizerContext,
+        views: Vec<MaterializedViewCandidate>,
+    }
+
+    impl TestConfig {
+        fn new(views: Vec<MaterializedViewCandidate>) -> Self {
+            let mut options = ConfigOptions::default();
+            options.optimizer.enable_materialized_view_rewrite = true;
+            Self {
+                inner: OptimizerContext::new_with_config_options(Arc::new(options)),
+                views,
+            }
+        }
+    }
+
+    impl OptimizerConfig for TestConfig {
+        fn query_execution_start_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+            self.inner.query_execution_start_time()
+        }
+
+        fn alias_generator(&self) -> &Arc<AliasGenerator> {
+            self.inner.alias_generator()
+        }
+
+        fn options(&self) -> Arc<ConfigOptions> {
+            self.inner.options()
+        }
+
+        fn materialized_views(&self) -> &[MaterializedViewCandidate] {
+            &self.views
+        }
+    }
+
+    fn scan(name: &str) -> Result<LogicalPlanBuilder> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::UInt32, false),
+            Field::new("c", DataType::Int64, true),
+        ]);
+        table_scan(Some(name), &schema, None)
+    }
+
+    fn candidate(definition: LogicalPlan) -> MaterializedViewCandidate {
+        let schema = Arc::clone(definition.schema().inner());
+        MaterializedViewCandidate::new(
+            TableReference::bare("mv"),
+            definition,
+            Arc::new(LogicalTableSource::new(schema)),
+        )
+    }
+
+    /// `SELECT a, b, sum(c) AS total, count(c) AS n, max(c) FROM t GROUP BY a, b`
+    fn aggregate_view() -> Result<MaterializedViewCandidate> {
+        let definition = scan("t")?
+            .aggregate(
+                vec![col("a"), col("b")],
+                vec![sum(col("c")), count(col("c")), max(col("c"))],
+            )?
+            .project(vec![
+                col("t.a"),
+                col("t.b"),
+                col("sum(t.c)").alias("total"),
+                col("COUNT(t.c)").alias("n"),
+                col("max(t.c)"),
+            ])?
+            .build()?;
+        Ok(candidate(definition))
+    }
+
+    fn rewrite(plan: &LogicalPlan, config: &TestConfig) -> Result<Option<LogicalPlan>> {
+        let rewritten = MaterializedViewRewrite::new().rewrite(plan.clone(), config)?;
+        if !rewritten.transformed {
+            return Ok(None);
+        }
+        assert!(
+            rewritten
+                .data
+                .schema()
+                .logically_equivalent_names_and_types(plan.schema())
+        );
+        Ok(Some(rewritten.data))
+    }
+
+    #[test]
+    fn exact_match() -> Result<()> {
+        let definition = scan("t")?
+            .filter(col("a").gt(lit(1u32)))?
+            .project(vec![col("a"), col("b")])?
+            .build()?;
+        let config = TestConfig::new(vec![candidate(definition.clone())]);
+
+        let plan = LogicalPlanBuilder::from(definition)
+            .limit(0, Some(10))?
+            .build()?;
+        insta::assert_snapshot!(rewrite(&plan, &config)?.unwrap(), @r"
+        Limit: skip=0, fetch=10
+          Projection: mv.a AS a, mv.b AS b
+            TableScan: mv
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn same_grouping() -> Result<()> {
+        let config = TestConfig::new(vec![aggregate_view()?]);
+        let plan = scan("t")?
+            .aggregate(vec![col("b"), col("a")], vec![max(col("c")), sum(col("c"))])?
+            .build()?;
+        insta::assert_snapshot!(rewrite(&plan, &config)?.unwrap(), @r"
+        Projection: mv.b AS b, mv.a AS a, mv.max(t.c) AS max(t.c), mv.total AS sum(t.c)
+          TableScan: mv
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn rollup() -> Result<()> {
+        let config = TestConfig::new(vec![aggregate_view()?]);
+        let plan = scan("t")?
+            .aggregate(vec![col("a")], vec![sum(col("c")), max(col("c"))])?
+            .sort(vec![col("a").sort(true, false)])?
+            .build()?;
+        insta::assert_snapshot!(rewrite(&plan, &config)?.unwrap(), @r"
+        Sort: t.a ASC NULLS LAST
+          Projection: mv.a AS a, sum(mv.total) AS sum(t.c), max(mv.max(t.c)) AS max(t.c)
+            Aggregate: groupBy=[[mv.a]], aggr=[[sum(mv.total), max(mv.max(t.c))]]
+              TableScan: mv
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn rollup_count() -> Result<()> {
+        let view = aggregate_view()?;
+        let plan = scan("t")?
+            .aggregate(Vec::<Expr>::new(), vec![count(col("c"))])?
+            .build()?;
+        // counts are rolled up by summing them
+        let functions = RollupFunctions {
+            sum: Some(sum_udaf()),
+            coalesce: Some(Arc::new(create_udf(
+                "coalesce",
+                vec![DataType::Int64, DataType::Int64],
+                DataType::Int64,
+                Volatility::Immutable,
+                Arc::new(|_| unimplemented!()),
+            ))),
+        };
+        let rewritten = rewrite_with_view(&plan, &view, &functions)?.unwrap();
+        insta::assert_snapshot!(rewritten, @r"
+        Projection: coalesce(sum(mv.n), Int64(0)) AS COUNT(t.c)
+          Aggregate: groupBy=[[]], aggr=[[sum(mv.n)]]
+            TableScan: mv
+        ");
+        let functions = RollupFunctions {
+            sum: Some(sum_udaf()),
+            coalesce: None,
+        };
+        assert!(rewrite_with_view(&plan, &view, &functions)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn no_match() -> Result<()> {
+        let config = TestConfig::new(vec![aggregate_view()?]);
+
+        // different input
+        let plan = scan("u")?
+            .aggregate(vec![col("a")], vec![sum(col("c"))])?
+            .build()?;
+        assert!(rewrite(&plan, &config)?.is_none());
+
+        // grouping expression not stored in the view
+        let plan = scan("t")?
+            .aggregate(vec![col("c")], vec![max(col("a"))])?
+            .build()?;
+        assert!(rewrite(&plan, &config)?.is_none());
+
+        // aggregate not computed by the view
+        let plan = scan("t")?
+            .aggregate(vec![col("a")], vec![max(col("b"))])?
+            .build()?;
+        assert!(rewrite(&plan, &config)?.is_none());
+
+        // DISTINCT aggregates can not be rolled up
+        let plan = scan("t")?
+            .aggregate(vec![col("a")], vec![count(col("c")).distinct().build()?])?
+            .build()?;
+        assert!(rewrite(&plan, &config)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_by_default() -> Result<()> {
+        let config = TestConfig {
+            inner: OptimizerContext::new(),
+            views: vec![aggregate_view()?],
+        };
+        let plan = scan("t")?
+            .aggregate(vec![col("a")], vec![sum(col("c"))])?
+            .build()?;
+        assert!(rewrite(&plan, &config)?.is_none());
+        Ok(())
+    }
+}
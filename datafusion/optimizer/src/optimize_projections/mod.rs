@@ -29,8 +29,8 @@ use datafusion_common::{
 };
 use datafusion_expr::expr::Alias;
 use datafusion_expr::{
-    Aggregate, Distinct, EmptyRelation, Expr, Projection, TableScanBuilder, Unnest,
-    Window, logical_plan::LogicalPlan,
+    Aggregate, Distinct, EmptyRelation, Expr, Projection, RowsPerMatch, TableScanBuilder,
+    Unnest, Window, logical_plan::LogicalPlan,
 };
 
 use crate::optimize_projections::required_indices::RequiredIndices;
@@ -307,7 +307,7 @@ fn optimize_projections(
                 })
                 .collect::<Result<_>>()?
         }
-        LogicalPlan::Limit(_) => {
+        LogicalPlan::Limit(_) | LogicalPlan::Sample(_) => {
             // Pass index requirements from the parent as well as column indices
             // that appear in this plan's expressions to its child. These operators
             // do not benefit from "small" inputs, so the projection_beneficial
@@ -406,6 +406,27 @@ fn optimize_projections(
                 "OptimizeProjection: should have handled in the match statement above"
             );
         }
+        LogicalPlan::MatchRecognize(match_recognize) => {
+            // With `ALL ROWS PER MATCH` the input columns are passed through,
+            // followed by the measures
+            let input_len = match_recognize.input.schema().fields().len();
+            let required_indices = match match_recognize.rows_per_match {
+                RowsPerMatch::All => RequiredIndices::new_from_indices(
+                    indices
+                        .indices()
+                        .iter()
+                        .copied()
+                        .filter(|idx| *idx < input_len)
+                        .collect(),
+                ),
+                RowsPerMatch::One => RequiredIndices::new(),
+            };
+            vec![
+                required_indices
+                    .with_projection_beneficial()
+                    .with_plan_exprs(&plan, match_recognize.input.schema())?,
+            ]
+        }
         LogicalPlan::Unnest(Unnest {
             input,
             dependency_indices,
@@ -754,7 +775,11 @@ fn split_join_requirements(
 ) -> (RequiredIndices, RequiredIndices) {
     match join_type {
         // In these cases requirements are split between left/right children:
-        JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => {
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::Right
+        | JoinType::Full
+        | JoinType::AsOf => {
             // Decrease right side indices by `left_len` so that they point to valid
             // positions within the right child:
             indices.split_off(left_len)
@@ -36,14 +36,16 @@ use datafusion_expr::dml::CopyTo;
 use datafusion_expr::logical_plan::LogicalPlan;
 use datafusion_expr::{
     Aggregate, Analyze, CreateMemoryTable, CreateView, DdlStatement, Distinct,
-    DistinctOn, DmlStatement, Explain, Expr, Extension, Filter, Join, Limit, Projection,
-    RecursiveQuery, Repartition, Sort, Statement, Subquery, SubqueryAlias, Union, Unnest,
-    Window,
+    DistinctOn, DmlStatement, Explain, Expr, Extension, Filter, Join, Limit,
+    MatchRecognize, Projection, RecursiveQuery, Repartition, Sample, Sort, Statement,
+    Subquery, SubqueryAlias, Union, Unnest, Window,
 };
 
 use crate::common_subexpr_eliminate::CommonSubexprEliminate;
+use crate::decorrelate_dependent_join::DecorrelateDependentJoin;
 use crate::decorrelate_lateral_join::DecorrelateLateralJoin;
 use crate::decorrelate_predicate_subquery::DecorrelatePredicateSubquery;
+use crate::eager_aggregation::EagerAggregation;
 use crate::eliminate_cross_join::EliminateCrossJoin;
 use crate::eliminate_duplicated_expr::EliminateDuplicatedExpr;
 use crate::eliminate_filter::EliminateFilter;
@@ -54,6 +56,10 @@ use crate::eliminate_outer_join::EliminateOuterJoin;
 use crate::extract_equijoin_predicate::ExtractEquijoinPredicate;
 use crate::extract_leaf_expressions::{ExtractLeafExpressions, PushDownLeafProjections};
 use crate::filter_null_join_keys::FilterNullJoinKeys;
+use crate::infer_predicates::InferPredicates;
+use crate::materialized_view_rewrite::{
+    MaterializedViewCandidate, MaterializedViewRewrite,
+};
 use crate::optimize_projections::OptimizeProjections;
 use crate::optimize_unions::OptimizeUnions;
 use crate::plan_signature::LogicalPlanSignature;
@@ -156,6 +162,12 @@ pub trait OptimizerConfig {
     fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
         None
     }
+
+    /// Return the materialized views that may be used to answer queries.
+    /// See [`MaterializedViewRewrite`].
+    fn materialized_views(&self) -> &[MaterializedViewCandidate] {
+        &[]
+    }
 }
 
 /// A standalone [`OptimizerConfig`] that can be used independently
@@ -288,6 +300,9 @@ impl Optimizer {
         //   (e.g. if the plan doesn't contain any of the nodes you are looking for
         //    return `Transformed::no`; only works if you control the traversal).
         let rules: Vec<Arc<dyn OptimizerRule + Sync + Send>> = vec![
+            // Must run first, so that plans still have the shape of the
+            // materialized view definitions they are matched against
+            Arc::new(MaterializedViewRewrite::new()),
             Arc::new(RewriteSetComparison::new()),
             Arc::new(OptimizeUnions::new()),
             Arc::new(UnionsToFilter::new()),
@@ -297,6 +312,8 @@ impl Optimizer {
             Arc::new(DecorrelatePredicateSubquery::new()),
             Arc::new(ScalarSubqueryToJoin::new()),
             Arc::new(DecorrelateLateralJoin::new()),
+            // Decorrelates the subqueries left by the rules above
+            Arc::new(DecorrelateDependentJoin::new()),
             Arc::new(ExtractEquijoinPredicate::new()),
             Arc::new(EliminateDuplicatedExpr::new()),
             Arc::new(EliminateFilter::new()),
@@ -307,7 +324,12 @@ impl Optimizer {
             Arc::new(EliminateOuterJoin::new()),
             // Filters can't be pushed down past Limits, we should do PushDownFilter after PushDownLimit
             Arc::new(PushDownLimit::new()),
+            // Predicates inferred from join keys are pushed down by PushDownFilter
+            Arc::new(InferPredicates::new()),
             Arc::new(PushDownFilter::new()),
+            // Runs after PushDownFilter so that the pre-aggregated inputs are
+            // filtered first
+            Arc::new(EagerAggregation::new()),
             Arc::new(SingleDistinctToGroupBy::new()),
             // The previous optimizations added expressions and projections,
             // that might benefit from the following rules
@@ -400,11 +422,15 @@ fn map_children_mut<F: FnMut(&mut LogicalPlan) -> Result<bool>>(
         | LogicalPlan::Aggregate(Aggregate { input, .. })
         | LogicalPlan::Sort(Sort { input, .. })
         | LogicalPlan::Limit(Limit { input, .. })
+        | LogicalPlan::Sample(Sample { input, .. })
         | LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. })
         | LogicalPlan::Analyze(Analyze { input, .. })
         | LogicalPlan::Dml(DmlStatement { input, .. })
         | LogicalPlan::Copy(CopyTo { input, .. })
-        | LogicalPlan::Unnest(Unnest { input, .. }) => f(Arc::make_mut(input))?,
+        | LogicalPlan::Unnest(Unnest { input, .. })
+        | LogicalPlan::MatchRecognize(MatchRecognize { input, .. }) => {
+            f(Arc::make_mut(input))?
+        }
         LogicalPlan::Subquery(Subquery { subquery, .. }) => f(Arc::make_mut(subquery))?,
         LogicalPlan::Join(Join { left, right, .. }) => {
             let l = f(Arc::make_mut(left))?;
@@ -476,9 +502,12 @@ fn map_children_mut<F: FnMut(&mut LogicalPlan) -> Result<bool>>(
         | LogicalPlan::Ddl(DdlStatement::CreateIndex(_))
         | LogicalPlan::Ddl(DdlStatement::DropTable(_))
         | LogicalPlan::Ddl(DdlStatement::DropView(_))
+        | LogicalPlan::Ddl(DdlStatement::RefreshMaterializedView(_))
+        | LogicalPlan::Ddl(DdlStatement::AnalyzeTable(_))
         | LogicalPlan::Ddl(DdlStatement::DropCatalogSchema(_))
         | LogicalPlan::Ddl(DdlStatement::CreateFunction(_))
         | LogicalPlan::Ddl(DdlStatement::DropFunction(_))
+        | LogicalPlan::Ddl(DdlStatement::AlterTable(_))
         | LogicalPlan::Statement(_) => false,
     })
 }
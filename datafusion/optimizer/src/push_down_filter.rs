@@ -169,7 +169,7 @@ pub struct PushDownFilter {}
 pub(crate) fn lr_is_preserved(join_type: JoinType) -> (bool, bool) {
     match join_type {
         JoinType::Inner => (true, true),
-        JoinType::Left => (true, false),
+        JoinType::Left | JoinType::AsOf => (true, false),
         JoinType::Right => (false, true),
         JoinType::Full => (false, false),
         // No columns from the right side of the join can be referenced in output
@@ -687,7 +687,11 @@ fn infer_join_predicates_from_on_filters(
     inferred_predicates: &mut InferredPredicates,
 ) -> Result<()> {
     match join_type {
-        JoinType::Full | JoinType::LeftAnti | JoinType::RightAnti => Ok(()),
+        // The as-of match condition is an inequality that selects a single
+        // row, so nothing can be inferred from it.
+        JoinType::Full | JoinType::LeftAnti | JoinType::RightAnti | JoinType::AsOf => {
+            Ok(())
+        }
         JoinType::Inner => infer_join_predicates_impl::<true, true>(
             join_col_keys,
             on_filters,
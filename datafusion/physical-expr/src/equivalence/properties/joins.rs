@@ -112,7 +112,7 @@ pub fn join_equivalence_properties(
 ///
 /// To do so, we increment column indices by the size of the left table when
 /// join schema consists of a combination of the left and right schemas. This
-/// is the case for `Inner`, `Left`, `Full` and `Right` joins. For other cases,
+/// is the case for `Inner`, `Left`, `Full`, `Right` and `AsOf` joins. For other cases,
 /// indices do not change.
 pub fn updated_right_ordering_equivalence_class(
     right_oeq_class: &mut OrderingEquivalenceClass,
@@ -121,7 +121,11 @@ pub fn updated_right_ordering_equivalence_class(
 ) -> Result<()> {
     if matches!(
         join_type,
-        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right
+        JoinType::Inner
+            | JoinType::Left
+            | JoinType::Full
+            | JoinType::Right
+            | JoinType::AsOf
     ) {
         right_oeq_class.add_offset(left_size as _)?;
     }
@@ -0,0 +1,254 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Collation key expression, see [`CollationKeyExpr`]
+
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::PhysicalExpr;
+use arrow::datatypes::{DataType, Field, FieldRef, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::collation::Collation;
+use datafusion_common::{Result, ScalarValue, plan_err};
+use datafusion_expr::ColumnarValue;
+
+/// Computes the [collation keys](Collation::append_key) of a string
+/// expression.
+///
+/// Comparing the keys with the binary comparison, sort and hash kernels
+/// compares the input values using the collation. This is used to compare,
+/// sort and join on strings with a non binary collation.
+#[derive(Debug, Eq)]
+pub struct CollationKeyExpr {
+    /// The input expression
+    arg: Arc<dyn PhysicalExpr>,
+    /// The collation of the input expression
+    collation: Collation,
+}
+
+// Manually derive PartialEq and Hash to work around https://github.com/rust-lang/rust/issues/78808
+impl PartialEq for CollationKeyExpr {
+    fn eq(&self, other: &Self) -> bool {
+        self.arg.eq(&other.arg) && self.collation == other.collation
+    }
+}
+
+impl Hash for CollationKeyExpr {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.arg.hash(state);
+        self.collation.hash(state);
+    }
+}
+
+impl CollationKeyExpr {
+    /// Create a new collation key expression
+    pub fn new(arg: Arc<dyn PhysicalExpr>, collation: Collation) -> Self {
+        Self { arg, collation }
+    }
+
+    /// Get the input expression
+    pub fn arg(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.arg
+    }
+
+    /// Get the collation of the input expression
+    pub fn collation(&self) -> Collation {
+        self.collation
+    }
+}
+
+impl std::fmt::Display for CollationKeyExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "collation_key({}, {})", self.arg, self.collation)
+    }
+}
+
+impl PhysicalExpr for CollationKeyExpr {
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        self.arg.data_type(input_schema)
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        self.arg.nullable(input_schema)
+    }
+
+    fn return_field(&self, input_schema: &Schema) -> Result<FieldRef> {
+        // The keys are compared in binary order, so they have no collation
+        let field = self.arg.return_field(input_schema)?;
+        Ok(Arc::new(Field::new(
+            format!("{self}"),
+            field.data_type().clone(),
+            field.is_nullable(),
+        )))
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        match self.arg.evaluate(batch)? {
+            ColumnarValue::Array(array) => {
+                Ok(ColumnarValue::Array(self.collation.keys(&array)?))
+            }
+            ColumnarValue::Scalar(scalar) => {
+                let keys = self.collation.keys(&scalar.to_array()?)?;
+                Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                    &keys, 0,
+                )?))
+            }
+        }
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn PhysicalExpr>> {
+        vec![&self.arg]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        Ok(Arc::new(CollationKeyExpr::new(
+            Arc::clone(&children[0]),
+            self.collation,
+        )))
+    }
+
+    fn fmt_sql(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.arg.fmt_sql(f)?;
+        write!(f, " COLLATE \"{}\"", self.collation)
+    }
+}
+
+/// Returns an expression that compares like `expr` under its collation.
+///
+/// If `expr` has a non binary collation, this returns a [`CollationKeyExpr`],
+/// otherwise `expr` itself.
+pub fn collation_key(
+    expr: Arc<dyn PhysicalExpr>,
+    input_schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let collation = Collation::from_field(&*expr.return_field(input_schema)?)?;
+    Ok(if collation.is_binary() {
+        expr
+    } else {
+        Arc::new(CollationKeyExpr::new(expr, collation))
+    })
+}
+
+/// Returns expressions that compare `left` and `right` under their collation.
+///
+/// If only one side has a non binary collation, the other side is compared
+/// using the same collation. It is an error to compare values with different
+/// non binary collations.
+pub fn collation_keys(
+    left: Arc<dyn PhysicalExpr>,
+    left_schema: &Schema,
+    right: Arc<dyn PhysicalExpr>,
+    right_schema: &Schema,
+) -> Result<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)> {
+    let left_collation = Collation::from_field(&*left.return_field(left_schema)?)?;
+    let right_collation = Collation::from_field(&*right.return_field(right_schema)?)?;
+    let collation = match (left_collation, right_collation) {
+        (Collation::Binary, collation) | (collation, Collation::Binary) => collation,
+        (left_collation, right_collation) if left_collation == right_collation => {
+            left_collation
+        }
+        (left_collation, right_collation) => {
+            return plan_err!(
+                "Cannot compare values with collations {left_collation} and {right_collation}"
+            );
+        }
+    };
+    if collation.is_binary() {
+        return Ok((left, right));
+    }
+    Ok((
+        Arc::new(CollationKeyExpr::new(left, collation)),
+        Arc::new(CollationKeyExpr::new(right, collation)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{col, lit};
+    use arrow::array::{AsArray, StringArray};
+    use datafusion_common::collation::CollationStrength;
+
+    fn collated_schema() -> Result<Schema> {
+        let field = Collation::CaseInsensitive.apply_to_field(Field::new(
+            "a",
+            DataType::Utf8,
+            true,
+        ))?;
+        Ok(Schema::new(vec![
+            field,
+            Field::new("b", DataType::Utf8, true),
+        ]))
+    }
+
+    #[test]
+    fn collation_key_of_collated_column() -> Result<()> {
+        let schema = Arc::new(collated_schema()?);
+        let a = collation_key(col("a", &schema)?, &schema)?;
+        assert_eq!(a.to_string(), "collation_key(a@0, nocase)");
+        assert!(a.return_field(&schema)?.metadata().is_empty());
+        let b = collation_key(col("b", &schema)?, &schema)?;
+        assert_eq!(b.to_string(), "b@1");
+
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(StringArray::from(vec![Some("AbC"), None])),
+                Arc::new(StringArray::from(vec![Some("AbC"), None])),
+            ],
+        )?;
+        let keys = a.evaluate(&batch)?.into_array(batch.num_rows())?;
+        assert_eq!(
+            keys.as_string::<i32>().iter().collect::<Vec<_>>(),
+            vec![Some("abc"), None]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn collation_keys_of_comparison() -> Result<()> {
+        let schema = collated_schema()?;
+        let (left, right) =
+            collation_keys(col("b", &schema)?, &schema, lit("X"), &schema)?;
+        assert_eq!(left.to_string(), "b@1");
+        assert_eq!(right.to_string(), "X");
+
+        let (left, right) =
+            collation_keys(col("a", &schema)?, &schema, col("b", &schema)?, &schema)?;
+        assert_eq!(left.to_string(), "collation_key(a@0, nocase)");
+        assert_eq!(right.to_string(), "collation_key(b@1, nocase)");
+
+        let other =
+            Schema::new(vec![
+                Collation::Unicode(CollationStrength::Primary)
+                    .apply_to_field(Field::new("c", DataType::Utf8, true))?,
+            ]);
+        let err = collation_keys(col("a", &schema)?, &schema, col("c", &other)?, &other)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "Cannot compare values with collations nocase and und-u-ks-level1"
+            ),
+            "{err}"
+        );
+        Ok(())
+    }
+}
@@ -21,6 +21,7 @@
 mod binary;
 mod case;
 mod cast;
+mod collation;
 mod column;
 mod dynamic_filters;
 mod in_list;
@@ -43,6 +44,7 @@ pub use crate::aggregate::stats::StatsType;
 pub use binary::{BinaryExpr, binary, similar_to};
 pub use case::{CaseExpr, case};
 pub use cast::{CastExpr, cast};
+pub use collation::{CollationKeyExpr, collation_key, collation_keys};
 pub use column::{Column, col, with_new_schema};
 pub use datafusion_expr::utils::format_state_name;
 pub use dynamic_filters::{
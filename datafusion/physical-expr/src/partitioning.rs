@@ -120,6 +120,17 @@ pub enum Partitioning {
     /// Allocate rows based on a hash of one of more expressions and the specified number of
     /// partitions
     Hash(Vec<Arc<dyn PhysicalExpr>>, usize),
+    /// Allocate rows to the buckets of one or more expressions, computed with
+    /// the [`BucketHash`] of bucketed tables, and the specified number of
+    /// buckets.
+    ///
+    /// Like [`Self::Hash`], this satisfies hash distribution requirements on
+    /// the expressions, but the partitions of the two schemes do not match:
+    /// partition `i` of a `Bucket` partitioning only covers the same rows as
+    /// partition `i` of another `Bucket` partitioning.
+    ///
+    /// [`BucketHash`]: datafusion_common::file_options::bucketing::BucketHash
+    Bucket(Vec<Arc<dyn PhysicalExpr>>, usize),
     /// Partition rows by source-declared ranges
     Range(RangePartitioning),
     /// Unknown partitioning scheme with a known number of partitions
@@ -138,6 +149,13 @@ impl Display for Partitioning {
                     .join(", ");
                 write!(f, "Hash([{phy_exprs_str}], {size})")
             }
+            Partitioning::Bucket(phy_exprs, size) => {
+                write!(
+                    f,
+                    "Bucket({}, {size})",
+                    format_physical_expr_list(phy_exprs)
+                )
+            }
             Partitioning::Range(range) => write!(f, "{range}"),
             Partitioning::UnknownPartitioning(size) => {
                 write!(f, "UnknownPartitioning({size})")
@@ -364,6 +382,22 @@ fn equivalent_exprs(
     physical_exprs_equal(&normalized_left, &normalized_right)
 }
 
+/// Projects the partitioning expressions `exprs`, replacing those that are
+/// not projected with unknown columns
+fn project_exprs(
+    exprs: &[Arc<dyn PhysicalExpr>],
+    mapping: &ProjectionMapping,
+    input_eq_properties: &EquivalenceProperties,
+) -> Vec<Arc<dyn PhysicalExpr>> {
+    input_eq_properties
+        .project_expressions(exprs, mapping)
+        .zip(exprs)
+        .map(|(proj_expr, expr)| {
+            proj_expr.unwrap_or_else(|| Arc::new(UnKnownColumn::new(&expr.to_string())))
+        })
+        .collect()
+}
+
 fn normalize_exprs(
     exprs: &[Arc<dyn PhysicalExpr>],
     eq_properties: &EquivalenceProperties,
@@ -401,7 +435,7 @@ impl Partitioning {
     pub fn partition_count(&self) -> usize {
         use Partitioning::*;
         match self {
-            RoundRobinBatch(n) | Hash(_, n) | UnknownPartitioning(n) => *n,
+            RoundRobinBatch(n) | Hash(_, n) | Bucket(_, n) | UnknownPartitioning(n) => *n,
             Range(range) => range.partition_count(),
         }
     }
@@ -426,6 +460,10 @@ impl Partitioning {
             (
                 Partitioning::Hash(left_exprs, left_count),
                 Partitioning::Hash(right_exprs, right_count),
+            )
+            | (
+                Partitioning::Bucket(left_exprs, left_count),
+                Partitioning::Bucket(right_exprs, right_count),
             ) => {
                 if left_count != right_count {
                     return false;
@@ -492,7 +530,8 @@ impl Partitioning {
                 // Here we do not check the partition count for hash partitioning and assumes the partition count
                 // and hash functions in the system are the same. In future if we plan to support storage partition-wise joins,
                 // then we need to have the partition count and hash functions validation.
-                Partitioning::Hash(partition_exprs, _) => {
+                Partitioning::Hash(partition_exprs, _)
+                | Partitioning::Bucket(partition_exprs, _) => {
                     // Empty hash partitioning is invalid
                     if partition_exprs.is_empty() || required_exprs.is_empty() {
                         return PartitioningSatisfaction::NotSatisfied;
@@ -541,18 +580,14 @@ impl Partitioning {
         input_eq_properties: &EquivalenceProperties,
     ) -> Self {
         match self {
-            Partitioning::Hash(exprs, part) => {
-                let normalized_exprs = input_eq_properties
-                    .project_expressions(exprs, mapping)
-                    .zip(exprs)
-                    .map(|(proj_expr, expr)| {
-                        proj_expr.unwrap_or_else(|| {
-                            Arc::new(UnKnownColumn::new(&expr.to_string()))
-                        })
-                    })
-                    .collect();
-                Partitioning::Hash(normalized_exprs, *part)
-            }
+            Partitioning::Hash(exprs, part) => Partitioning::Hash(
+                project_exprs(exprs, mapping, input_eq_properties),
+                *part,
+            ),
+            Partitioning::Bucket(exprs, part) => Partitioning::Bucket(
+                project_exprs(exprs, mapping, input_eq_properties),
+                *part,
+            ),
             Partitioning::Range(range) => {
                 if let Some(projected) = range.project(mapping, input_eq_properties) {
                     Partitioning::Range(projected)
@@ -575,10 +610,10 @@ impl PartialEq for Partitioning {
                 Partitioning::RoundRobinBatch(count2),
             ) if count1 == count2 => true,
             (Partitioning::Hash(exprs1, count1), Partitioning::Hash(exprs2, count2))
-                if physical_exprs_equal(exprs1, exprs2) && (count1 == count2) =>
-            {
-                true
-            }
+            | (
+                Partitioning::Bucket(exprs1, count1),
+                Partitioning::Bucket(exprs2, count2),
+            ) if physical_exprs_equal(exprs1, exprs2) && (count1 == count2) => true,
             (Partitioning::Range(left), Partitioning::Range(right)) => left == right,
             _ => false,
         }
@@ -1361,6 +1396,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_bucket_partitioning() -> Result<()> {
+        let fixture = PartitioningTestFixture::int64(&["a", "b"])?;
+        let bucket = Partitioning::Bucket(fixture.cols([0]), 4);
+
+        assert_eq!(bucket.to_string(), "Bucket([a@0], 4)");
+        assert_eq!(bucket.partition_count(), 4);
+        assert_eq!(
+            bucket.satisfaction(
+                &fixture.hash_distribution([0]),
+                &fixture.eq_properties,
+                false
+            ),
+            PartitioningSatisfaction::Exact
+        );
+        assert_eq!(
+            bucket.satisfaction(
+                &fixture.hash_distribution([0, 1]),
+                &fixture.eq_properties,
+                true
+            ),
+            PartitioningSatisfaction::Subset
+        );
+
+        // Buckets only match the partitions of the same buckets
+        assert!(bucket.compatible_with(
+            &Partitioning::Bucket(fixture.cols([0]), 4),
+            &fixture.eq_properties
+        ));
+        assert!(!bucket.compatible_with(
+            &Partitioning::Bucket(fixture.cols([0]), 2),
+            &fixture.eq_properties
+        ));
+        assert!(
+            !bucket.compatible_with(
+                &fixture.hash_partitioning([0], 4),
+                &fixture.eq_properties
+            )
+        );
+        assert_ne!(bucket, fixture.hash_partitioning([0], 4));
+
+        Ok(())
+    }
+
     #[test]
     fn test_round_robin_partitioning_compatible_with() {
         let eq_properties = EquivalenceProperties::new(Arc::new(Schema::empty()));
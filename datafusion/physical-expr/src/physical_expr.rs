@@ -17,7 +17,7 @@
 
 use std::sync::Arc;
 
-use crate::expressions::{self, Column};
+use crate::expressions::{self, Column, collation_key};
 use crate::{LexOrdering, PhysicalSortExpr, create_physical_expr};
 
 use arrow::compute::SortOptions;
@@ -193,15 +193,18 @@ pub fn create_lex_ordering(
 }
 
 /// Create a physical sort expression from a logical expression
+///
+/// Strings with a non binary collation are sorted by their
+/// [collation keys](crate::expressions::CollationKeyExpr).
 pub fn create_physical_sort_expr(
     e: &SortExpr,
     input_dfschema: &DFSchema,
     execution_props: &ExecutionProps,
 ) -> Result<PhysicalSortExpr> {
-    create_physical_expr(&e.expr, input_dfschema, execution_props).map(|expr| {
-        let options = SortOptions::new(!e.asc, e.nulls_first);
-        PhysicalSortExpr::new(expr, options)
-    })
+    let expr = create_physical_expr(&e.expr, input_dfschema, execution_props)?;
+    let expr = collation_key(expr, input_dfschema.as_arrow())?;
+    let options = SortOptions::new(!e.asc, e.nulls_first);
+    Ok(PhysicalSortExpr::new(expr, options))
 }
 
 /// Create vector of physical sort expression from a vector of logical expression
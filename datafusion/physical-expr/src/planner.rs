@@ -21,7 +21,10 @@ use crate::scalar_subquery::ScalarSubqueryExpr;
 use crate::{HigherOrderFunctionExpr, ScalarFunctionExpr};
 use crate::{
     PhysicalExpr,
-    expressions::{self, Column, Literal, binary, like, similar_to},
+    expressions::{
+        self, CollationKeyExpr, Column, Literal, binary, collation_key, collation_keys,
+        like, similar_to,
+    },
 };
 
 use arrow::datatypes::Schema;
@@ -214,7 +217,7 @@ pub fn create_physical_expr(
             //
             // There should be no coercion during physical
             // planning.
-            binary(lhs, *op, rhs, input_schema)
+            collated_binary(lhs, *op, rhs, input_schema)
         }
         Expr::Like(Like {
             negated,
@@ -363,14 +366,14 @@ pub fn create_physical_expr(
 
             // rewrite the between into the two binary operators
             let binary_expr = binary(
-                binary(
+                collated_binary(
                     Arc::clone(&value_expr),
                     Operator::GtEq,
                     low_expr,
                     input_schema,
                 )?,
                 Operator::And,
-                binary(
+                collated_binary(
                     Arc::clone(&value_expr),
                     Operator::LtEq,
                     high_expr,
@@ -397,8 +400,20 @@ pub fn create_physical_expr(
                 let value_expr =
                     create_physical_expr(expr, input_dfschema, execution_props)?;
 
-                let list_exprs =
+                let mut list_exprs =
                     create_physical_exprs(list, input_dfschema, execution_props)?;
+
+                // Strings with a non binary collation are compared by their
+                // collation keys
+                let value_expr = collation_key(value_expr, input_schema)?;
+                if let Some(key) = value_expr.downcast_ref::<CollationKeyExpr>() {
+                    list_exprs = list_exprs
+                        .into_iter()
+                        .map(|expr| {
+                            Arc::new(CollationKeyExpr::new(expr, key.collation())) as _
+                        })
+                        .collect();
+                }
                 expressions::in_list(value_expr, list_exprs, negated, input_schema)
             }
         },
@@ -571,6 +586,30 @@ pub fn create_physical_expr(
     }
 }
 
+/// Create a binary expression, comparing strings with a non binary collation
+/// by their collation keys
+fn collated_binary(
+    lhs: Arc<dyn PhysicalExpr>,
+    op: Operator,
+    rhs: Arc<dyn PhysicalExpr>,
+    input_schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let (lhs, rhs) = match op {
+        Operator::Eq
+        | Operator::NotEq
+        | Operator::Lt
+        | Operator::LtEq
+        | Operator::Gt
+        | Operator::GtEq
+        | Operator::IsDistinctFrom
+        | Operator::IsNotDistinctFrom => {
+            collation_keys(lhs, input_schema, rhs, input_schema)?
+        }
+        _ => (lhs, rhs),
+    };
+    binary(lhs, op, rhs, input_schema)
+}
+
 /// Create vector of Physical Expression from a vector of logical expression
 pub fn create_physical_exprs<'a, I>(
     exprs: I,
@@ -17,6 +17,7 @@
 
 //! Utilizing exact statistics from sources to avoid scanning data
 use datafusion_common::Result;
+use datafusion_common::collation::Collation;
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::scalar::ScalarValue;
 use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
@@ -59,6 +60,11 @@ impl PhysicalOptimizerRule for AggregateStatistics {
             let mut projections = vec![];
             for expr in partial_agg_exec.aggr_expr() {
                 let field = expr.field();
+                // Statistics are computed in binary order, which does not
+                // match the order of a non binary collation
+                if !Collation::from_field(&field)?.is_binary() {
+                    break;
+                }
                 let args = expr.expressions();
                 let statistics_args = StatisticsArgs {
                     statistics: &stats,
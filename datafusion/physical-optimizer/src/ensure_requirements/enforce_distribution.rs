@@ -176,7 +176,8 @@ pub fn adjust_input_keys_ordering(
                     | JoinType::LeftSemi
                     | JoinType::LeftAnti
                     | JoinType::Full
-                    | JoinType::LeftMark => vec![],
+                    | JoinType::LeftMark
+                    | JoinType::AsOf => vec![],
                 };
             }
             PartitionMode::Auto => {
@@ -529,21 +530,25 @@ fn reorder_current_join_keys(
     right_equivalence_properties: &EquivalenceProperties,
 ) -> (JoinKeyPairs, Option<Vec<usize>>) {
     match (left_partition, right_partition) {
-        (Some(Partitioning::Hash(left_exprs, _)), _) => {
-            match try_reorder(join_keys, left_exprs, left_equivalence_properties) {
-                (join_keys, None) => reorder_current_join_keys(
-                    join_keys,
-                    None,
-                    right_partition,
-                    left_equivalence_properties,
-                    right_equivalence_properties,
-                ),
-                result => result,
-            }
-        }
-        (_, Some(Partitioning::Hash(right_exprs, _))) => {
-            try_reorder(join_keys, right_exprs, right_equivalence_properties)
-        }
+        (
+            Some(Partitioning::Hash(left_exprs, _) | Partitioning::Bucket(left_exprs, _)),
+            _,
+        ) => match try_reorder(join_keys, left_exprs, left_equivalence_properties) {
+            (join_keys, None) => reorder_current_join_keys(
+                join_keys,
+                None,
+                right_partition,
+                left_equivalence_properties,
+                right_equivalence_properties,
+            ),
+            result => result,
+        },
+        (
+            _,
+            Some(
+                Partitioning::Hash(right_exprs, _) | Partitioning::Bucket(right_exprs, _),
+            ),
+        ) => try_reorder(join_keys, right_exprs, right_equivalence_properties),
         _ => (join_keys, None),
     }
 }
@@ -747,25 +752,34 @@ fn add_hash_on_top(
     };
 
     if needs_repartition {
-        // When there is an existing ordering, we preserve ordering during
-        // repartition. This will be rolled back in the future if any of the
-        // following conditions is true:
-        // - Preserving ordering is not helpful in terms of satisfying ordering
-        //   requirements.
-        // - Usage of order preserving variants is not desirable (per the flag
-        //   `config.optimizer.prefer_existing_sort`).
-        let partitioning = dist.create_partitioning(n_target);
-        let repartition =
-            RepartitionExec::try_new(Arc::clone(&input.plan), partitioning)?
-                .with_preserve_order();
-        let plan = Arc::new(repartition) as _;
-
-        return Ok(DistributionContext::new(plan, true, vec![input]));
+        return add_hash_repartition(input, dist, n_target);
     }
 
     Ok(input)
 }
 
+/// Adds a hash repartition operator that partitions `input` into `n_target`
+/// partitions for `dist`, whether or not `input` already satisfies it.
+fn add_hash_repartition(
+    input: DistributionContext,
+    dist: Distribution,
+    n_target: usize,
+) -> Result<DistributionContext> {
+    // When there is an existing ordering, we preserve ordering during
+    // repartition. This will be rolled back in the future if any of the
+    // following conditions is true:
+    // - Preserving ordering is not helpful in terms of satisfying ordering
+    //   requirements.
+    // - Usage of order preserving variants is not desirable (per the flag
+    //   `config.optimizer.prefer_existing_sort`).
+    let partitioning = dist.create_partitioning(n_target);
+    let repartition = RepartitionExec::try_new(Arc::clone(&input.plan), partitioning)?
+        .with_preserve_order();
+    let plan = Arc::new(repartition) as _;
+
+    Ok(DistributionContext::new(plan, true, vec![input]))
+}
+
 /// Adds a [`SortPreservingMergeExec`] or a [`CoalescePartitionsExec`] operator
 /// on top of the given plan node to satisfy a single partition requirement
 /// while preserving ordering constraints.
@@ -1158,6 +1172,41 @@ pub fn ensure_distribution(
         .is_some_and(|join| join.mode == PartitionMode::Partitioned)
         || plan.is::<SortMergeJoinExec>();
 
+    // Operators with several hash partitioned children match the partitions of
+    // their children by index. The bucket partitions of bucketed table scans
+    // only line up with the buckets of other scans, so they are kept when every
+    // hash partitioned child is bucketed on its keys into the same number of
+    // buckets. Otherwise bucketed children are repartitioned by hash like any
+    // other input.
+    let hash_requirements = plan
+        .required_input_distribution()
+        .into_iter()
+        .filter(|requirement| matches!(requirement, Distribution::HashPartitioned(_)))
+        .count();
+    let bucket_counts = izip!(children.iter(), plan.required_input_distribution())
+        .filter(|(_, requirement)| {
+            matches!(requirement, Distribution::HashPartitioned(_))
+        })
+        .map(|(child, requirement)| {
+            let partitioning = child.plan.output_partitioning();
+            (matches!(partitioning, Partitioning::Bucket(..))
+                && partitioning
+                    .satisfaction(
+                        &requirement,
+                        child.plan.equivalence_properties(),
+                        false,
+                    )
+                    .is_satisfied())
+            .then(|| partitioning.partition_count())
+        })
+        .collect::<Vec<_>>();
+    let keep_buckets = hash_requirements > 1
+        && bucket_counts
+            .first()
+            .copied()
+            .flatten()
+            .is_some_and(|count| bucket_counts.iter().all(|other| *other == Some(count)));
+
     let repartition_status_flags =
         get_repartition_requirement_status(&plan, batch_size, should_use_estimates)?;
     // This loop iterates over all the children to:
@@ -1244,7 +1293,20 @@ pub fn ensure_distribution(
                 Distribution::HashPartitioned(exprs) => {
                     // See https://github.com/apache/datafusion/issues/18341#issuecomment-3503238325 for background
                     // When inserting hash is necessary to satisfy hash requirement, insert hash repartition.
-                    if hash_necessary {
+                    if keep_buckets {
+                        // The buckets of all children already line up
+                    } else if hash_requirements > 1
+                        && matches!(
+                            child.plan.output_partitioning(),
+                            Partitioning::Bucket(..)
+                        )
+                    {
+                        child = add_hash_repartition(
+                            child,
+                            Distribution::HashPartitioned(exprs.to_vec()),
+                            target_partitions,
+                        )?;
+                    } else if hash_necessary {
                         child = add_hash_on_top(
                             child,
                             exprs.to_vec(),
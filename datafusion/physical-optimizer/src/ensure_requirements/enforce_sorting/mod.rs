@@ -111,13 +111,19 @@ fn update_sort_ctx_children_data(
             let required_orderings = child_plan.required_input_ordering();
             let flags = child_plan.maintains_input_order();
             // Add parent node to the tree if there is at least one child with
-            // a sort connection:
+            // a sort connection. Children whose ordering is required by the
+            // child plan itself do not count, as their sorts must be kept:
+            // `connected_to_sort` only returns the correct answer with bottom-up traversal
+            let connected_to_sort = child_node
+                .children
+                .iter()
+                .zip(required_orderings.iter())
+                .any(|(child, required_ordering)| {
+                    child.data && (required_ordering.is_none() || is_spm)
+                });
             izip!(flags, required_orderings).any(|(maintains, required_ordering)| {
                 let propagates_ordering =
                     (maintains && required_ordering.is_none()) || is_spm;
-                // `connected_to_sort` only returns the correct answer with bottom-up traversal
-                let connected_to_sort =
-                    child_node.children.iter().any(|child| child.data);
                 propagates_ordering && connected_to_sort
             })
         }
@@ -0,0 +1,739 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The [`JoinReorder`] rule reorders trees of inner joins using statistics,
+//! so that the joins producing the fewest rows are executed first.
+//!
+//! A tree of inner joins (hash, nested loop and cross joins) is flattened
+//! into a join graph: its relations, i.e. the inputs of the tree that are not
+//! inner joins themselves, and the predicates of its joins. The best order of
+//! the relations is then searched:
+//!
+//! - with dynamic programming over the connected subsets of relations, in
+//!   the spirit of DPhyp, when there are at most
+//!   `datafusion.optimizer.join_reorder_dp_threshold` relations;
+//! - greedily, joining first the two relations with the smallest result,
+//!   otherwise.
+//!
+//! The cost of an order is the sum of the estimated number of rows of its
+//! joins. Outer, semi and anti joins are never reordered: they are relations
+//! of the tree above them, and the trees below them are reordered separately.
+
+use std::sync::Arc;
+
+use crate::PhysicalOptimizerRule;
+use crate::join_selection::get_stats;
+use crate::optimizer::{ConfigOnlyContext, PhysicalOptimizerContext};
+
+use arrow::datatypes::{Schema, SchemaRef};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
+use datafusion_common::{
+    JoinSide, JoinType, NullEquality, Result, internal_datafusion_err, internal_err,
+};
+use datafusion_expr_common::operator::Operator;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column};
+use datafusion_physical_expr::{PhysicalExpr, PhysicalExprRef};
+use datafusion_physical_plan::joins::utils::{ColumnIndex, JoinFilter};
+use datafusion_physical_plan::joins::{
+    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode,
+};
+use datafusion_physical_plan::operator_statistics::StatisticsRegistry;
+use datafusion_physical_plan::projection::{ProjectionExec, ProjectionExpr};
+use datafusion_physical_plan::{ExecutionPlan, with_new_children_if_necessary};
+
+/// Selectivity of join filters that are not equalities between columns
+const FILTER_SELECTIVITY: f64 = 0.2;
+
+/// Maximum number of relations ordered with dynamic programming, whatever
+/// the configured threshold, as its memory grows exponentially with them
+const MAX_DP_RELATIONS: usize = 20;
+
+/// Maximum number of relations of a join graph, one per bit of a `u64`
+const MAX_RELATIONS: usize = 64;
+
+/// The [`JoinReorder`] rule reorders trees of inner joins based on the
+/// statistics of their inputs, when `datafusion.optimizer.enable_join_reorder`
+/// is set. See the [module documentation](self) for more details.
+#[derive(Default, Debug)]
+pub struct JoinReorder {}
+
+impl JoinReorder {
+    #[expect(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for JoinReorder {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.optimize_with_context(plan, &ConfigOnlyContext::new(config))
+    }
+
+    fn optimize_with_context(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        context: &dyn PhysicalOptimizerContext,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let config = context.config_options();
+        if !config.optimizer.enable_join_reorder {
+            return Ok(plan);
+        }
+        let mut default_registry = None;
+        let registry: Option<&StatisticsRegistry> =
+            if config.optimizer.use_statistics_registry {
+                Some(context.statistics_registry().unwrap_or_else(|| {
+                    default_registry
+                        .insert(StatisticsRegistry::default_with_builtin_providers())
+                }))
+            } else {
+                None
+            };
+        let reorderer = Reorderer {
+            dp_threshold: config
+                .optimizer
+                .join_reorder_dp_threshold
+                .min(MAX_DP_RELATIONS),
+            registry,
+        };
+        reorderer.reorder(plan).data()
+    }
+
+    fn name(&self) -> &str {
+        "join_reorder"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+struct Reorderer<'a> {
+    dp_threshold: usize,
+    registry: Option<&'a StatisticsRegistry>,
+}
+
+impl Reorderer<'_> {
+    /// Reorders the trees of inner joins of `plan`, from the top
+    fn reorder(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+    ) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
+        plan.transform_down(|plan| {
+            if InnerJoin::try_new(&plan).is_none() {
+                return Ok(Transformed::no(plan));
+            }
+
+            let mut graph = JoinGraph::default();
+            let (order, columns) = graph.add(&plan)?;
+            // The relations may contain other trees of inner joins
+            let mut transformed = false;
+            for relation in graph.relations.iter_mut() {
+                let reordered = self.reorder(Arc::clone(relation))?;
+                transformed |= reordered.transformed;
+                *relation = reordered.data;
+            }
+
+            let new_plan = match self.best_order(&graph, &order)? {
+                Some((model, best)) => {
+                    transformed = true;
+                    let (joined, new_columns, _) = graph.build(&best, &model)?;
+                    restore_columns(joined, &new_columns, &columns, &plan.schema())?
+                }
+                None if transformed => {
+                    with_relations(&plan, &mut graph.relations.into_iter())?
+                }
+                None => plan,
+            };
+            // The relations were already visited
+            Ok(Transformed::new(
+                new_plan,
+                transformed,
+                TreeNodeRecursion::Jump,
+            ))
+        })
+    }
+
+    /// Returns the best order of the relations of `graph` with the model
+    /// used to find it, or `None` if it is not cheaper than `original` or if
+    /// the statistics of some relations are unknown
+    fn best_order(
+        &self,
+        graph: &JoinGraph,
+        original: &JoinOrder,
+    ) -> Result<Option<(CardinalityModel, JoinOrder)>> {
+        let n = graph.relations.len();
+        if !(3..=MAX_RELATIONS).contains(&n) {
+            return Ok(None);
+        }
+        let Some(model) = CardinalityModel::try_new(graph, self.registry)? else {
+            return Ok(None);
+        };
+
+        let best = if n <= self.dp_threshold {
+            model.dynamic_programming()
+        } else {
+            model.greedy(
+                (0..n)
+                    .map(|relation| (1 << relation, JoinOrder::Relation(relation)))
+                    .collect(),
+            )
+        };
+        Ok((model.cost(&best).1 < model.cost(original).1).then_some((model, best)))
+    }
+}
+
+/// An inner join that may be reordered with the other joins of its tree
+struct InnerJoin<'a> {
+    left: &'a Arc<dyn ExecutionPlan>,
+    right: &'a Arc<dyn ExecutionPlan>,
+    /// Indices of the equal columns of `left` and `right`
+    on: Vec<(usize, usize)>,
+    filter: Option<&'a JoinFilter>,
+    mode: Option<PartitionMode>,
+}
+
+impl<'a> InnerJoin<'a> {
+    fn try_new(plan: &'a Arc<dyn ExecutionPlan>) -> Option<Self> {
+        if plan.fetch().is_some() {
+            return None;
+        }
+        let join = if let Some(join) = plan.downcast_ref::<HashJoinExec>() {
+            if join.join_type() != &JoinType::Inner
+                || join.projection.is_some()
+                || join.null_equality() != NullEquality::NullEqualsNothing
+                || join.null_aware
+            {
+                return None;
+            }
+            let on = join
+                .on()
+                .iter()
+                .map(|(left, right)| {
+                    let left = left.downcast_ref::<Column>()?;
+                    let right = right.downcast_ref::<Column>()?;
+                    Some((left.index(), right.index()))
+                })
+                .collect::<Option<_>>()?;
+            Self {
+                left: join.left(),
+                right: join.right(),
+                on,
+                filter: join.filter(),
+                mode: Some(*join.partition_mode()),
+            }
+        } else if let Some(join) = plan.downcast_ref::<NestedLoopJoinExec>() {
+            if join.join_type() != &JoinType::Inner || join.projection().is_some() {
+                return None;
+            }
+            Self {
+                left: join.left(),
+                right: join.right(),
+                on: vec![],
+                filter: join.filter(),
+                mode: None,
+            }
+        } else if let Some(join) = plan.downcast_ref::<CrossJoinExec>() {
+            Self {
+                left: join.left(),
+                right: join.right(),
+                on: vec![],
+                filter: None,
+                mode: None,
+            }
+        } else {
+            return None;
+        };
+        // Filters without columns could not be placed in the tree
+        join.filter
+            .is_none_or(|filter| !filter.column_indices().is_empty())
+            .then_some(join)
+    }
+}
+
+/// A column of the relation at the first index of a [`JoinGraph`]
+type RelationColumn = (usize, usize);
+
+/// A tree of inner joins flattened into its relations and the predicates
+/// joining them
+#[derive(Default)]
+struct JoinGraph {
+    relations: Vec<Arc<dyn ExecutionPlan>>,
+    /// Pairs of equal columns of different relations
+    equalities: Vec<(RelationColumn, RelationColumn)>,
+    filters: Vec<GraphFilter>,
+    /// The partition mode of the hash joins of the tree
+    mode: Option<PartitionMode>,
+}
+
+/// A join filter of a [`JoinGraph`]
+struct GraphFilter {
+    expression: Arc<dyn PhysicalExpr>,
+    /// The column of each field of `schema`
+    columns: Vec<RelationColumn>,
+    schema: SchemaRef,
+    /// The relations of `columns`
+    relations: u64,
+}
+
+/// The order in which the relations of a [`JoinGraph`] are joined
+#[derive(Debug, Clone)]
+enum JoinOrder {
+    Relation(usize),
+    Join(Box<JoinOrder>, Box<JoinOrder>),
+}
+
+impl JoinGraph {
+    /// Adds the relations and predicates of the tree of inner joins `plan`,
+    /// returning its order and the columns of its output
+    fn add(
+        &mut self,
+        plan: &Arc<dyn ExecutionPlan>,
+    ) -> Result<(JoinOrder, Vec<RelationColumn>)> {
+        let Some(join) = InnerJoin::try_new(plan) else {
+            let relation = self.relations.len();
+            self.relations.push(Arc::clone(plan));
+            let columns = (0..plan.schema().fields().len())
+                .map(|index| (relation, index))
+                .collect();
+            return Ok((JoinOrder::Relation(relation), columns));
+        };
+        self.mode = self.mode.or(join.mode);
+
+        let (left, left_columns) = self.add(join.left)?;
+        let (right, right_columns) = self.add(join.right)?;
+        for &(l, r) in &join.on {
+            self.equalities.push((left_columns[l], right_columns[r]));
+        }
+        if let Some(filter) = join.filter {
+            let mut columns = Vec::with_capacity(filter.column_indices().len());
+            for column in filter.column_indices() {
+                columns.push(match column.side {
+                    JoinSide::Left => left_columns[column.index],
+                    JoinSide::Right => right_columns[column.index],
+                    JoinSide::None => {
+                        return internal_err!("Unexpected column of an inner join");
+                    }
+                });
+            }
+            self.filters.push(GraphFilter {
+                expression: Arc::clone(filter.expression()),
+                relations: columns.iter().fold(0, |acc, (r, _)| acc | (1 << r)),
+                columns,
+                schema: Arc::clone(filter.schema()),
+            });
+        }
+
+        let mut columns = left_columns;
+        columns.extend(right_columns);
+        Ok((JoinOrder::Join(Box::new(left), Box::new(right)), columns))
+    }
+
+    /// Builds the plan joining the relations in `order`, returning it with
+    /// the columns of its output and its relations
+    fn build(
+        &self,
+        order: &JoinOrder,
+        model: &CardinalityModel,
+    ) -> Result<(Arc<dyn ExecutionPlan>, Vec<RelationColumn>, u64)> {
+        let (left, right) = match order {
+            JoinOrder::Relation(relation) => {
+                let plan = Arc::clone(&self.relations[*relation]);
+                let columns = (0..plan.schema().fields().len())
+                    .map(|index| (*relation, index))
+                    .collect();
+                return Ok((plan, columns, 1 << relation));
+            }
+            JoinOrder::Join(left, right) => {
+                (self.build(left, model)?, self.build(right, model)?)
+            }
+        };
+        // The smaller input is the build side
+        let (left, right) = if model.rows(left.2) > model.rows(right.2) {
+            (right, left)
+        } else {
+            (left, right)
+        };
+        let (left, left_columns, left_relations) = left;
+        let (right, right_columns, right_relations) = right;
+
+        let mut on: Vec<(PhysicalExprRef, PhysicalExprRef)> = vec![];
+        for &(a, b) in &self.equalities {
+            let (l, r) = if contains(left_relations, a.0)
+                && contains(right_relations, b.0)
+            {
+                (a, b)
+            } else if contains(left_relations, b.0) && contains(right_relations, a.0) {
+                (b, a)
+            } else {
+                continue;
+            };
+            on.push((
+                column(&left, &left_columns, l)?,
+                column(&right, &right_columns, r)?,
+            ));
+        }
+        let filter = self.join_filter(
+            (left_relations, &left_columns),
+            (right_relations, &right_columns),
+        )?;
+
+        let plan: Arc<dyn ExecutionPlan> = if !on.is_empty() {
+            Arc::new(HashJoinExec::try_new(
+                left,
+                right,
+                on,
+                filter,
+                &JoinType::Inner,
+                None,
+                self.mode.unwrap_or(PartitionMode::Auto),
+                NullEquality::NullEqualsNothing,
+                false,
+            )?)
+        } else if filter.is_some() {
+            Arc::new(NestedLoopJoinExec::try_new(
+                left,
+                right,
+                filter,
+                &JoinType::Inner,
+                None,
+            )?)
+        } else {
+            Arc::new(CrossJoinExec::new(left, right))
+        };
+        let mut columns = left_columns;
+        columns.extend(right_columns);
+        Ok((plan, columns, left_relations | right_relations))
+    }
+
+    /// Returns the filter of the join of `left` and `right`, combining the
+    /// filters that need both of them. Filters of a single relation are
+    /// evaluated by its first join.
+    fn join_filter(
+        &self,
+        (left_relations, left_columns): (u64, &[RelationColumn]),
+        (right_relations, right_columns): (u64, &[RelationColumn]),
+    ) -> Result<Option<JoinFilter>> {
+        let relations = left_relations | right_relations;
+        // Filters of a single join input were evaluated by its joins
+        let evaluated_by =
+            |side: u64, filter: u64| filter & !side == 0 && side.count_ones() > 1;
+        let mut expression: Option<Arc<dyn PhysicalExpr>> = None;
+        let mut column_indices = vec![];
+        let mut fields = vec![];
+        for filter in &self.filters {
+            if filter.relations & !relations != 0
+                || evaluated_by(left_relations, filter.relations)
+                || evaluated_by(right_relations, filter.relations)
+            {
+                continue;
+            }
+
+            let offset = column_indices.len();
+            for &c in &filter.columns {
+                column_indices.push(if contains(left_relations, c.0) {
+                    ColumnIndex {
+                        index: position(left_columns, c)?,
+                        side: JoinSide::Left,
+                    }
+                } else {
+                    ColumnIndex {
+                        index: position(right_columns, c)?,
+                        side: JoinSide::Right,
+                    }
+                });
+            }
+            fields.extend(filter.schema.fields().iter().cloned());
+            let filter_expression = Arc::clone(&filter.expression)
+                .transform(|expr| {
+                    if offset > 0
+                        && let Some(column) = expr.downcast_ref::<Column>()
+                    {
+                        let shifted: Arc<dyn PhysicalExpr> =
+                            Arc::new(Column::new(column.name(), column.index() + offset));
+                        return Ok(Transformed::yes(shifted));
+                    }
+                    Ok(Transformed::no(expr))
+                })
+                .data()?;
+            expression = Some(match expression {
+                Some(expression) => Arc::new(BinaryExpr::new(
+                    expression,
+                    Operator::And,
+                    filter_expression,
+                )),
+                None => filter_expression,
+            });
+        }
+        Ok(expression.map(|expression| {
+            JoinFilter::new(expression, column_indices, Arc::new(Schema::new(fields)))
+        }))
+    }
+}
+
+/// Estimates the number of rows of the joins of subsets of the relations of
+/// a [`JoinGraph`], represented as bitmaps
+struct CardinalityModel {
+    /// Number of rows of each relation
+    rows: Vec<f64>,
+    /// Relations and selectivity of each predicate
+    predicates: Vec<(u64, f64)>,
+    /// Pairs of relations joined by equalities
+    edges: Vec<u64>,
+}
+
+impl CardinalityModel {
+    /// Returns the model of `graph`, or `None` if the number of rows of some
+    /// relation is unknown
+    fn try_new(
+        graph: &JoinGraph,
+        registry: Option<&StatisticsRegistry>,
+    ) -> Result<Option<Self>> {
+        let mut rows = Vec::with_capacity(graph.relations.len());
+        let mut statistics = Vec::with_capacity(graph.relations.len());
+        for relation in &graph.relations {
+            let stats = get_stats(relation.as_ref(), registry)?;
+            let Some(&num_rows) = stats.num_rows.get_value() else {
+                return Ok(None);
+            };
+            rows.push(num_rows.max(1) as f64);
+            statistics.push(stats);
+        }
+
+        // Without statistics, columns are assumed to be unique
+        let distinct = |(relation, index): RelationColumn| {
+            statistics[relation]
+                .column_statistics
+                .get(index)
+                .and_then(|c| c.distinct_count.get_value())
+                .map_or(rows[relation], |&d| (d.max(1) as f64).min(rows[relation]))
+        };
+        let mut predicates = vec![];
+        let mut edges = vec![];
+        for &(a, b) in &graph.equalities {
+            let relations = (1 << a.0) | (1 << b.0);
+            predicates.push((relations, 1.0 / distinct(a).max(distinct(b))));
+            edges.push(relations);
+        }
+        for filter in &graph.filters {
+            predicates.push((filter.relations, FILTER_SELECTIVITY));
+        }
+        Ok(Some(Self {
+            rows,
+            predicates,
+            edges,
+        }))
+    }
+
+    /// Estimated number of rows of the join of `relations`
+    fn rows(&self, relations: u64) -> f64 {
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(relation, _)| contains(relations, *relation))
+            .map(|(_, rows)| rows)
+            .product::<f64>();
+        self.predicates
+            .iter()
+            .filter(|(predicate, _)| predicate & !relations == 0)
+            .fold(rows, |rows, (_, selectivity)| rows * selectivity)
+    }
+
+    /// Returns true if an equality joins `left` and `right`
+    fn connected(&self, left: u64, right: u64) -> bool {
+        self.edges
+            .iter()
+            .any(|&edge| edge & left != 0 && edge & right != 0)
+    }
+
+    /// Returns the relations and cost of `order`, the sum of the number of
+    /// rows of its joins
+    fn cost(&self, order: &JoinOrder) -> (u64, f64) {
+        match order {
+            JoinOrder::Relation(relation) => (1 << relation, 0.0),
+            JoinOrder::Join(left, right) => {
+                let (left, left_cost) = self.cost(left);
+                let (right, right_cost) = self.cost(right);
+                let relations = left | right;
+                (relations, left_cost + right_cost + self.rows(relations))
+            }
+        }
+    }
+
+    /// Finds the cheapest order joining connected subsets of relations, then
+    /// joins the disconnected subsets greedily
+    fn dynamic_programming(&self) -> JoinOrder {
+        let n = self.rows.len();
+        let all = (1u64 << n) - 1;
+        // The cost and left input of the cheapest join of each connected subset
+        let mut best: Vec<Option<(f64, u64)>> = vec![None; 1 << n];
+        for relation in 0..n {
+            best[1 << relation] = Some((0.0, 0));
+        }
+        // Subsets of a set are smaller than it, so they are visited first
+        for relations in 1..=all {
+            if relations.count_ones() < 2 {
+                continue;
+            }
+            let rows = self.rows(relations);
+            let mut left = (relations - 1) & relations;
+            while left > 0 {
+                let right = relations & !left;
+                if left < right
+                    && let (Some((left_cost, _)), Some((right_cost, _))) =
+                        (best[left as usize], best[right as usize])
+                    && self.connected(left, right)
+                {
+                    let cost = left_cost + right_cost + rows;
+                    if best[relations as usize].is_none_or(|(c, _)| cost < c) {
+                        best[relations as usize] = Some((cost, left));
+                    }
+                }
+                left = (left - 1) & relations;
+            }
+        }
+
+        fn order(best: &[Option<(f64, u64)>], relations: u64) -> JoinOrder {
+            match best[relations as usize] {
+                Some((_, left)) if left != 0 => JoinOrder::Join(
+                    Box::new(order(best, left)),
+                    Box::new(order(best, relations & !left)),
+                ),
+                _ => JoinOrder::Relation(relations.trailing_zeros() as usize),
+            }
+        }
+
+        // Each maximal connected subset has a plan, join them together
+        let mut components: Vec<u64> = (0..n).map(|relation| 1 << relation).collect();
+        for &edge in &self.edges {
+            let (joined, others): (Vec<u64>, Vec<u64>) = components
+                .into_iter()
+                .partition(|component| component & edge != 0);
+            components = others;
+            components.push(joined.into_iter().fold(0, |acc, c| acc | c));
+        }
+        let components = components
+            .into_iter()
+            .map(|component| (component, order(&best, component)))
+            .collect();
+        self.greedy(components)
+    }
+
+    /// Joins `orders` together, each time joining the two with the smallest
+    /// result, preferring those joined by an equality
+    fn greedy(&self, mut orders: Vec<(u64, JoinOrder)>) -> JoinOrder {
+        while orders.len() > 1 {
+            let mut best = (0, 1, (true, f64::INFINITY));
+            for i in 0..orders.len() {
+                for j in i + 1..orders.len() {
+                    let (left, right) = (orders[i].0, orders[j].0);
+                    let key = (!self.connected(left, right), self.rows(left | right));
+                    if key < best.2 {
+                        best = (i, j, key);
+                    }
+                }
+            }
+            let (i, j, _) = best;
+            let (right, right_order) = orders.swap_remove(j);
+            let (left, left_order) = orders.swap_remove(i);
+            orders.push((
+                left | right,
+                JoinOrder::Join(Box::new(left_order), Box::new(right_order)),
+            ));
+        }
+        orders
+            .pop()
+            .map(|(_, order)| order)
+            .unwrap_or(JoinOrder::Relation(0))
+    }
+}
+
+/// Returns true if `relations` contains `relation`
+fn contains(relations: u64, relation: usize) -> bool {
+    relations & (1 << relation) != 0
+}
+
+fn position(columns: &[RelationColumn], column: RelationColumn) -> Result<usize> {
+    columns
+        .iter()
+        .position(|c| *c == column)
+        .ok_or_else(|| internal_datafusion_err!("Column {column:?} not found in join"))
+}
+
+/// Returns the expression of `column` in the output of `plan`
+fn column(
+    plan: &Arc<dyn ExecutionPlan>,
+    columns: &[RelationColumn],
+    column: RelationColumn,
+) -> Result<PhysicalExprRef> {
+    let index = position(columns, column)?;
+    Ok(Arc::new(Column::new(
+        plan.schema().field(index).name(),
+        index,
+    )))
+}
+
+/// Projects the output of `plan`, whose columns are `columns`, to the
+/// `original` columns and `schema` of the reordered joins
+fn restore_columns(
+    plan: Arc<dyn ExecutionPlan>,
+    columns: &[RelationColumn],
+    original: &[RelationColumn],
+    schema: &SchemaRef,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if columns == original {
+        return Ok(plan);
+    }
+    let exprs = original
+        .iter()
+        .zip(schema.fields())
+        .map(|(&column, field)| {
+            let index = position(columns, column)?;
+            Ok(ProjectionExpr::new(
+                Arc::new(Column::new(field.name(), index)),
+                field.name(),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(ProjectionExec::try_new(exprs, plan)?))
+}
+
+/// Replaces the relations of the tree of inner joins `plan` with `relations`
+fn with_relations(
+    plan: &Arc<dyn ExecutionPlan>,
+    relations: &mut impl Iterator<Item = Arc<dyn ExecutionPlan>>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    match InnerJoin::try_new(plan) {
+        Some(join) => {
+            let children = vec![
+                with_relations(join.left, relations)?,
+                with_relations(join.right, relations)?,
+            ];
+            with_new_children_if_necessary(Arc::clone(plan), children)
+        }
+        None => relations
+            .next()
+            .ok_or_else(|| internal_datafusion_err!("Missing relation of join")),
+    }
+}
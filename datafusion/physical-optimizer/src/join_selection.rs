@@ -57,7 +57,7 @@ impl JoinSelection {
 }
 
 /// Get statistics for a plan node, using the registry if available.
-fn get_stats(
+pub(crate) fn get_stats(
     plan: &dyn ExecutionPlan,
     registry: Option<&StatisticsRegistry>,
 ) -> Result<Arc<Statistics>> {
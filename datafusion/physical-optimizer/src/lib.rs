@@ -25,6 +25,7 @@
 #![deny(clippy::clone_on_ref_ptr)]
 #![cfg_attr(test, allow(clippy::needless_pass_by_value))]
 
+pub mod adaptive_execution;
 pub mod aggregate_statistics;
 pub mod combine_partial_final_agg;
 pub mod ensure_coop;
@@ -34,6 +35,7 @@ pub mod ensure_requirements;
 // modules keep their public paths.
 pub use ensure_requirements::{enforce_distribution, enforce_sorting};
 pub mod filter_pushdown;
+pub mod join_reorder;
 pub mod join_selection;
 pub mod limit_pushdown;
 pub mod limit_pushdown_past_window;
@@ -20,11 +20,13 @@
 use std::fmt::Debug;
 use std::sync::Arc;
 
+use crate::adaptive_execution::AdaptiveExecution;
 use crate::aggregate_statistics::AggregateStatistics;
 use crate::combine_partial_final_agg::CombinePartialFinalAggregate;
 use crate::ensure_coop::EnsureCooperative;
 use crate::ensure_requirements::EnsureRequirements;
 use crate::filter_pushdown::FilterPushdown;
+use crate::join_reorder::JoinReorder;
 use crate::join_selection::JoinSelection;
 use crate::limit_pushdown::LimitPushdown;
 use crate::limited_distinct_aggregation::LimitedDistinctAggregation;
@@ -153,6 +155,10 @@ impl PhysicalOptimizer {
             // this information is not lost across different rules during optimization.
             Arc::new(OutputRequirements::new_add_mode()),
             Arc::new(AggregateStatistics::new()),
+            // The JoinReorder rule reorders trees of inner joins based on statistics
+            // when `enable_join_reorder` is set. It runs before JoinSelection, which
+            // then picks the build side and partition mode of the reordered joins.
+            Arc::new(JoinReorder::new()),
             // Statistics-based join selection will change the Auto mode to a real join implementation,
             // like collect left, or hash join, or future sort merge join, which will influence the
             // EnsureRequirements rule as it decides whether to add additional repartitioning and
@@ -238,6 +244,11 @@ impl PhysicalOptimizer {
             // Therefore, it should be run at the end of the optimization process since any changes to the plan may break the dynamic filter's references.
             // See `FilterPushdownPhase` for more details.
             Arc::new(FilterPushdown::new_post_optimization()),
+            // The AdaptiveExecution rule wraps plans with partitioned hash joins
+            // to re-plan them during execution, once the size of their build
+            // sides is known. It must run after the dynamic filters are pushed
+            // down, as the re-planned joins keep them.
+            Arc::new(AdaptiveExecution::new()),
             // The SanityCheckPlan rule checks whether the order and
             // distribution requirements of each node in the plan
             // is satisfied. It will also reject non-runnable query
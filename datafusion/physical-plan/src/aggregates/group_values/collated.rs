@@ -0,0 +1,257 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::mem::size_of;
+use std::sync::Arc;
+
+use crate::aggregates::group_values::GroupValues;
+use arrow::array::{Array, ArrayRef, AsArray, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion_common::Result;
+use datafusion_common::collation::Collation;
+use datafusion_expr::EmitTo;
+
+/// The collation and output type of each group column, `None` for columns
+/// compared in binary order
+type ColumnCollations = Vec<Option<(Collation, DataType)>>;
+
+/// A [`GroupValues`] for group keys with a non binary [`Collation`]
+///
+/// The values of collated columns are interned by their collation keys using
+/// the inner [`GroupValues`], so values that compare equal under the collation
+/// (for example `'a'` and `'A'` with a case-insensitive collation) belong to
+/// the same group. The first value seen for each group is emitted as the
+/// group value.
+pub struct GroupValuesCollated {
+    /// Interns the collation keys of the group values
+    inner: Box<dyn GroupValues>,
+
+    /// The collation of each group column
+    collations: ColumnCollations,
+
+    /// The first value seen for each group of each collated column
+    values: Vec<Vec<Option<String>>>,
+
+    /// The number of bytes used by the strings in `values`
+    values_size: usize,
+
+    /// Reused buffer for the keys of the input columns
+    keys: Vec<ArrayRef>,
+}
+
+impl GroupValuesCollated {
+    /// Returns the collations of the columns of `schema` that are not
+    /// compared in binary order, or `None` if there are no such columns
+    pub(super) fn collations(schema: &Schema) -> Result<Option<ColumnCollations>> {
+        let collations = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let collation = Collation::from_field(field)?;
+                let is_string = matches!(
+                    field.data_type(),
+                    DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+                );
+                Ok((!collation.is_binary() && is_string)
+                    .then(|| (collation, field.data_type().clone())))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(collations.iter().any(Option::is_some).then_some(collations))
+    }
+
+    /// Create a new [`GroupValuesCollated`] for the given collations, see
+    /// [`Self::collations`]
+    pub(super) fn new(inner: Box<dyn GroupValues>, collations: ColumnCollations) -> Self {
+        let values = vec![vec![]; collations.len()];
+        Self {
+            inner,
+            collations,
+            values,
+            values_size: 0,
+            keys: vec![],
+        }
+    }
+
+    /// Returns `schema` without the collation of its fields, which is used
+    /// to intern the collation keys
+    pub(super) fn key_schema(schema: &Schema) -> SchemaRef {
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                Arc::new(Field::new(
+                    field.name(),
+                    field.data_type().clone(),
+                    field.is_nullable(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new(fields))
+    }
+}
+
+/// Appends the values of the string array `array` to `values`
+fn append_values(
+    array: &ArrayRef,
+    rows: impl Iterator<Item = usize>,
+    values: &mut Vec<Option<String>>,
+) -> usize {
+    let mut size = 0;
+    for row in rows {
+        let value = (!array.is_null(row)).then(|| {
+            let value = match array.data_type() {
+                DataType::Utf8 => array.as_string::<i32>().value(row),
+                DataType::LargeUtf8 => array.as_string::<i64>().value(row),
+                _ => array.as_string_view().value(row),
+            };
+            size += value.len();
+            value.to_string()
+        });
+        values.push(value);
+    }
+    size
+}
+
+impl GroupValues for GroupValuesCollated {
+    fn intern(&mut self, cols: &[ArrayRef], groups: &mut Vec<usize>) -> Result<()> {
+        self.keys.clear();
+        for (col, collation) in cols.iter().zip(&self.collations) {
+            self.keys.push(match collation {
+                Some((collation, _)) => collation.keys(col)?,
+                None => Arc::clone(col),
+            });
+        }
+
+        let num_groups = self.inner.len();
+        self.inner.intern(&self.keys, groups)?;
+
+        // Find the first row of each new group
+        let mut new_rows = vec![None; self.inner.len() - num_groups];
+        for (row, group) in groups.iter().enumerate() {
+            if let Some(first_row) = group
+                .checked_sub(num_groups)
+                .and_then(|idx| new_rows.get_mut(idx))
+                && first_row.is_none()
+            {
+                *first_row = Some(row);
+            }
+        }
+        let new_rows = new_rows.into_iter().flatten().collect::<Vec<_>>();
+
+        for ((col, collation), values) in
+            cols.iter().zip(&self.collations).zip(&mut self.values)
+        {
+            if collation.is_some() {
+                self.values_size += append_values(col, new_rows.iter().copied(), values);
+            }
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        let values_size: usize = self
+            .values
+            .iter()
+            .map(|values| values.capacity() * size_of::<Option<String>>())
+            .sum();
+        self.inner.size() + values_size + self.values_size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn emit(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let mut output = self.inner.emit(emit_to)?;
+        for ((array, collation), values) in output
+            .iter_mut()
+            .zip(&self.collations)
+            .zip(&mut self.values)
+        {
+            let Some((_, data_type)) = collation else {
+                continue;
+            };
+            let emitted = emit_to.take_needed(values);
+            self.values_size -= emitted.iter().flatten().map(String::len).sum::<usize>();
+            let strings: ArrayRef = Arc::new(StringArray::from(emitted));
+            *array = cast(&strings, data_type)?;
+        }
+        Ok(output)
+    }
+
+    fn clear_shrink(&mut self, num_rows: usize) {
+        self.inner.clear_shrink(num_rows);
+        for values in &mut self.values {
+            values.clear();
+            values.shrink_to(num_rows);
+        }
+        self.values_size = 0;
+        self.keys.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregates::group_values::new_group_values;
+    use crate::aggregates::order::GroupOrdering;
+    use arrow::array::StringViewArray;
+
+    #[test]
+    fn groups_by_collation() -> Result<()> {
+        let field = Collation::CaseInsensitive.apply_to_field(Field::new(
+            "a",
+            DataType::Utf8View,
+            true,
+        ))?;
+        let schema = Arc::new(Schema::new(vec![field]));
+        let mut group_values = new_group_values(schema, &GroupOrdering::None)?;
+
+        let mut groups = vec![];
+        let input: ArrayRef = Arc::new(StringViewArray::from(vec![
+            Some("Foo"),
+            Some("bar"),
+            Some("FOO"),
+            None,
+        ]));
+        group_values.intern(&[input], &mut groups)?;
+        assert_eq!(groups, vec![0, 1, 0, 2]);
+
+        let input: ArrayRef =
+            Arc::new(StringViewArray::from(vec![Some("BAR"), Some("baz")]));
+        group_values.intern(&[input], &mut groups)?;
+        assert_eq!(groups, vec![1, 3]);
+        assert_eq!(group_values.len(), 4);
+
+        let output = group_values.emit(EmitTo::First(2))?;
+        assert_eq!(
+            output[0].as_string_view().iter().collect::<Vec<_>>(),
+            vec![Some("Foo"), Some("bar")]
+        );
+        let output = group_values.emit(EmitTo::All)?;
+        assert_eq!(
+            output[0].as_string_view().iter().collect::<Vec<_>>(),
+            vec![None, Some("baz")]
+        );
+        Ok(())
+    }
+}
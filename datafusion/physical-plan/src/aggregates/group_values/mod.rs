@@ -30,6 +30,8 @@ use datafusion_expr::EmitTo;
 
 pub mod multi_group_by;
 
+mod collated;
+pub use collated::GroupValuesCollated;
 mod row;
 pub use row::GroupValuesRows;
 mod single_group_by;
@@ -128,6 +130,9 @@ pub trait GroupValues: Send {
 ///
 ///   - Otherwise, the general implementation `GroupValuesRows` will be chosen.
 ///
+/// If any column has a non binary collation, the chosen implementation is
+/// wrapped in a [`GroupValuesCollated`] which interns the collation keys.
+///
 /// `GroupColumn`:  crate::aggregates::group_values::multi_group_by::GroupColumn
 /// `GroupValuesColumn`: crate::aggregates::group_values::multi_group_by::GroupValuesColumn
 /// `GroupValuesRows`: crate::aggregates::group_values::GroupValuesRows
@@ -135,6 +140,12 @@ pub fn new_group_values(
     schema: SchemaRef,
     group_ordering: &GroupOrdering,
 ) -> Result<Box<dyn GroupValues>> {
+    if let Some(collations) = GroupValuesCollated::collations(&schema)? {
+        let inner =
+            new_group_values(GroupValuesCollated::key_schema(&schema), group_ordering)?;
+        return Ok(Box::new(GroupValuesCollated::new(inner, collations)));
+    }
+
     if schema.fields.len() == 1 {
         let d = schema.fields[0].data_type();
 
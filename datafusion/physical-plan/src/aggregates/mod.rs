@@ -46,6 +46,7 @@ use arrow::array::{ArrayRef, UInt8Array, UInt16Array, UInt32Array, UInt64Array};
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use arrow_schema::FieldRef;
+use datafusion_common::collation::Collation;
 use datafusion_common::stats::Precision;
 use datafusion_common::{
     Constraint, Constraints, Result, ScalarValue, assert_eq_or_internal_err,
@@ -56,7 +57,9 @@ use datafusion_execution::memory_pool::MemoryLimit;
 use datafusion_expr::{Accumulator, Aggregate};
 use datafusion_physical_expr::aggregate::AggregateFunctionExpr;
 use datafusion_physical_expr::equivalence::ProjectionMapping;
-use datafusion_physical_expr::expressions::{Column, DynamicFilterPhysicalExpr, lit};
+use datafusion_physical_expr::expressions::{
+    Column, DynamicFilterPhysicalExpr, collation_key, lit,
+};
 use datafusion_physical_expr::{
     ConstExpr, EquivalenceProperties, physical_exprs_contains,
 };
@@ -417,6 +420,17 @@ impl PhysicalGroupBy {
         Aggregate::grouping_id_type(self.expr.len(), max_duplicate_ordinal(&self.groups))
     }
 
+    /// Returns true if any grouping expression has a non binary collation
+    pub fn has_collation(&self, input_schema: &Schema) -> Result<bool> {
+        for (expr, _) in &self.expr {
+            let field = expr.return_field(input_schema)?;
+            if !Collation::from_field(&field)?.is_binary() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     pub fn group_schema(&self, schema: &Schema) -> Result<SchemaRef> {
         Ok(Arc::new(Schema::new(self.group_fields(schema)?)))
     }
@@ -825,8 +839,14 @@ impl AggregateExec {
         // prefix requirements with this section. In this case, aggregation will
         // work more efficiently.
         // Copy the `PhysicalSortExpr`s to retain the sort options.
-        let (new_sort_exprs, indices) =
-            input_eq_properties.find_longest_permutation(&groupby_exprs)?;
+        //
+        // Values that are equal under a non binary collation are not
+        // adjacent in the input ordering, so such groups are not streamed.
+        let (new_sort_exprs, indices) = if group_by.has_collation(&input.schema())? {
+            (vec![], vec![])
+        } else {
+            input_eq_properties.find_longest_permutation(&groupby_exprs)?
+        };
 
         let mut new_requirements = new_sort_exprs
             .into_iter()
@@ -1700,7 +1720,18 @@ impl ExecutionPlan for AggregateExec {
                 vec![Distribution::UnspecifiedDistribution]
             }
             AggregateMode::FinalPartitioned | AggregateMode::SinglePartitioned => {
-                vec![Distribution::HashPartitioned(self.group_by.input_exprs())]
+                // Groups with a non binary collation are partitioned by their
+                // collation keys, so equal values end up in the same partition
+                let exprs = self
+                    .group_by
+                    .input_exprs()
+                    .into_iter()
+                    .map(|expr| {
+                        collation_key(Arc::clone(&expr), &self.input.schema())
+                            .unwrap_or(expr)
+                    })
+                    .collect();
+                vec![Distribution::HashPartitioned(exprs)]
             }
             AggregateMode::Final | AggregateMode::Single => {
                 vec![Distribution::SinglePartition]
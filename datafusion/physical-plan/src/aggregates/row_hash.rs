@@ -48,7 +48,7 @@ use datafusion_execution::memory_pool::proxy::VecAllocExt;
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_expr::{EmitTo, GroupsAccumulator};
 use datafusion_physical_expr::aggregate::AggregateFunctionExpr;
-use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::expressions::{Column, collation_key};
 use datafusion_physical_expr::{GroupsAccumulatorAdapter, PhysicalSortExpr};
 use datafusion_physical_expr_common::sort_expr::LexOrdering;
 
@@ -542,22 +542,25 @@ impl GroupedHashAggregateStream {
 
         let output_ordering = agg.cache.output_ordering();
 
-        let spill_sort_exprs =
-            group_schema
-                .fields
-                .into_iter()
-                .enumerate()
-                .map(|(idx, field)| {
-                    let output_expr = Column::new(field.name().as_str(), idx);
-
-                    // Try to use the sort options from the output ordering, if available.
-                    // This ensures that spilled state is sorted in the required order as well.
-                    let sort_options = output_ordering
-                        .and_then(|o| o.get_sort_options(&output_expr))
-                        .unwrap_or_default();
-
-                    PhysicalSortExpr::new(Arc::new(output_expr), sort_options)
-                });
+        let spill_sort_exprs = group_schema
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let output_expr = Column::new(field.name().as_str(), idx);
+
+                // Try to use the sort options from the output ordering, if available.
+                // This ensures that spilled state is sorted in the required order as well.
+                let sort_options = output_ordering
+                    .and_then(|o| o.get_sort_options(&output_expr))
+                    .unwrap_or_default();
+
+                // Groups with a non binary collation are merged by their
+                // collation keys, so the spilled state is sorted by them
+                let expr = collation_key(Arc::new(output_expr), &group_schema)?;
+                Ok(PhysicalSortExpr::new(expr, sort_options))
+            })
+            .collect::<Result<Vec<_>>>()?;
         let Some(spill_ordering) = LexOrdering::new(spill_sort_exprs) else {
             return internal_err!("Spill expression is empty");
         };
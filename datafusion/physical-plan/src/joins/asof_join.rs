@@ -0,0 +1,1146 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the as-of join plan, which joins every left row with the closest
+//! matching right row
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::Formatter;
+use std::sync::Arc;
+use std::task::Poll;
+
+use super::utils::{
+    BuildProbeJoinMetrics, StatefulStreamResult, build_join_schema, check_join_is_valid,
+    estimate_join_statistics, symmetric_join_output_partitioning,
+};
+use super::{JoinOn, JoinOnRef};
+use crate::execution_plan::{EmissionType, boundedness_from_children};
+use crate::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::spill::get_record_batch_memory_size;
+use crate::stream::EmptyRecordBatchStream;
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
+    PlanProperties, RecordBatchStream, SendableRecordBatchStream, Statistics,
+    check_if_same_properties, handle_state,
+};
+
+use arrow::array::{Array, ArrayRef, RecordBatch, RecordBatchOptions, new_null_array};
+use arrow::compute::{SortOptions, interleave};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::row::{Row, RowConverter, Rows, SortField};
+use datafusion_common::{
+    JoinType, NullEquality, Result, assert_eq_or_internal_err, internal_err, plan_err,
+};
+use datafusion_execution::TaskContext;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_expr::Operator;
+use datafusion_physical_expr::equivalence::join_equivalence_properties;
+use datafusion_physical_expr::{
+    LexOrdering, OrderingRequirements, PhysicalExprRef, PhysicalSortExpr,
+};
+use datafusion_physical_expr_common::physical_expr::fmt_sql;
+
+use futures::{Stream, StreamExt, ready};
+
+/// As-of Join Execution Plan
+///
+/// Joins each row of the left input with the single row of the right input
+/// that has equal `on` keys and is the closest one satisfying the match
+/// condition `left_match <operator> right_match`, where the operator is one of
+/// `<`, `<=`, `>` or `>=`. Left rows without such a right row are joined with
+/// NULLs, so the output contains exactly one row per left row. For example,
+/// with `trades.ts >= quotes.ts` every trade is joined with the latest quote at
+/// or before it:
+///
+/// ```text
+///     trades (left)        quotes (right)         output
+///   ┌─────┬────┐         ┌─────┬────┬─────┐     ┌─────┬────┬──────┐
+///   │ sym │ ts │         │ sym │ ts │ bid │     │ sym │ ts │ bid  │
+///   ├─────┼────┤         ├─────┼────┼─────┤     ├─────┼────┼──────┤
+///   │ A   │ 1  │         │ A   │ 2  │ 10  │     │ A   │ 1  │ NULL │
+///   │ A   │ 3  │         │ A   │ 3  │ 11  │     │ A   │ 3  │ 11   │
+///   │ A   │ 9  │         │ B   │ 1  │ 20  │     │ A   │ 9  │ 11   │
+///   │ B   │ 5  │         └─────┴────┴─────┘     │ B   │ 5  │ 20   │
+///   └─────┴────┘                                └─────┴────┴──────┘
+/// ```
+///
+/// # Algorithm
+///
+/// Both inputs are required to be sorted by their `on` keys and match
+/// expression, and to be hash partitioned by the `on` keys (or to be a single
+/// partition if there are no keys), which allows the optimizer to insert
+/// `SortExec` and `RepartitionExec` when needed. The join is a sort-merge:
+/// both inputs are streamed, and a cursor over the right input only moves
+/// forward as the left rows advance.
+///
+/// With `>` or `>=`, the cursor moves past the right rows of the same key that
+/// satisfy the match condition, and the last of them is the match. With `<` or
+/// `<=`, it moves past the right rows that do not satisfy it, and the first
+/// row it stops at is the match. Only the right batches between the match of
+/// the current left row and the cursor are buffered. Rows with NULL keys or a
+/// NULL match value never match.
+#[derive(Debug)]
+pub struct AsOfJoinExec {
+    /// Left (streamed) execution plan
+    pub left: Arc<dyn ExecutionPlan>,
+    /// Right (buffered) execution plan
+    pub right: Arc<dyn ExecutionPlan>,
+    /// Equi-join key pairs, as (left, right) expressions
+    pub on: JoinOn,
+    /// The (left, right) expressions compared by the match condition
+    pub match_on: (PhysicalExprRef, PhysicalExprRef),
+    /// Comparison operator of the match condition
+    pub operator: Operator,
+    /// The schema once the join is applied
+    schema: SchemaRef,
+    /// The required ordering of the left input
+    left_required_ordering: LexOrdering,
+    /// The required ordering of the right input
+    right_required_ordering: LexOrdering,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: Arc<PlanProperties>,
+}
+
+impl AsOfJoinExec {
+    /// Try to create a new [`AsOfJoinExec`]
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        match_on: (PhysicalExprRef, PhysicalExprRef),
+        operator: Operator,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        check_join_is_valid(&left_schema, &right_schema, &on)?;
+
+        if !matches!(
+            operator,
+            Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+        ) {
+            return plan_err!(
+                "As-of join match condition must use <, <=, > or >=, got {operator}"
+            );
+        }
+        for (left_expr, right_expr) in on.iter().chain([&match_on]) {
+            let left_type = left_expr.data_type(&left_schema)?;
+            let right_type = right_expr.data_type(&right_schema)?;
+            if left_type != right_type {
+                return plan_err!(
+                    "As-of join expressions {left_expr} and {right_expr} must have the same type, got {left_type} and {right_type}"
+                );
+            }
+        }
+
+        // Both sides are sorted by their keys, then by their match expression
+        let required_ordering = |exprs: Vec<PhysicalExprRef>| {
+            let sort_options = SortOptions::new(false, true);
+            let ordering = LexOrdering::new(
+                exprs
+                    .into_iter()
+                    .map(|expr| PhysicalSortExpr::new(expr, sort_options)),
+            );
+            let Some(ordering) = ordering else {
+                return internal_err!(
+                    "AsOfJoinExec requires valid sort expressions for its inputs"
+                );
+            };
+            Ok(ordering)
+        };
+        let left_required_ordering = required_ordering(
+            on.iter()
+                .map(|(l, _)| Arc::clone(l))
+                .chain([Arc::clone(&match_on.0)])
+                .collect(),
+        )?;
+        let right_required_ordering = required_ordering(
+            on.iter()
+                .map(|(_, r)| Arc::clone(r))
+                .chain([Arc::clone(&match_on.1)])
+                .collect(),
+        )?;
+
+        let (schema, _) = build_join_schema(&left_schema, &right_schema, &JoinType::AsOf);
+        let schema = Arc::new(schema);
+        let cache = Self::compute_properties(&left, &right, Arc::clone(&schema), &on)?;
+
+        Ok(Self {
+            left,
+            right,
+            on,
+            match_on,
+            operator,
+            schema,
+            left_required_ordering,
+            right_required_ordering,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache: Arc::new(cache),
+        })
+    }
+
+    /// Left (streamed) side of the join
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// Right (buffered) side of the join
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// Equi-join key pairs
+    pub fn on(&self) -> JoinOnRef<'_> {
+        &self.on
+    }
+
+    /// The (left, right) expressions compared by the match condition
+    pub fn match_on(&self) -> &(PhysicalExprRef, PhysicalExprRef) {
+        &self.match_on
+    }
+
+    /// Comparison operator of the match condition
+    pub fn operator(&self) -> Operator {
+        self.operator
+    }
+
+    /// This function creates the cache object that stores the plan properties
+    /// such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(
+        left: &Arc<dyn ExecutionPlan>,
+        right: &Arc<dyn ExecutionPlan>,
+        schema: SchemaRef,
+        on: JoinOnRef,
+    ) -> Result<PlanProperties> {
+        // Left rows are emitted in their input order, one output row each
+        let eq_properties = join_equivalence_properties(
+            left.equivalence_properties().clone(),
+            right.equivalence_properties().clone(),
+            &JoinType::AsOf,
+            schema,
+            &[true, false],
+            None,
+            on,
+        )?;
+
+        Ok(PlanProperties::new(
+            eq_properties,
+            symmetric_join_output_partitioning(left, right, &JoinType::AsOf)?,
+            EmissionType::Incremental,
+            boundedness_from_children([left, right]),
+        ))
+    }
+
+    fn with_new_children_and_same_properties(
+        &self,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Self {
+        let left = children.swap_remove(0);
+        let right = children.swap_remove(0);
+
+        Self {
+            left,
+            right,
+            on: self.on.clone(),
+            match_on: self.match_on.clone(),
+            operator: self.operator,
+            schema: Arc::clone(&self.schema),
+            left_required_ordering: self.left_required_ordering.clone(),
+            right_required_ordering: self.right_required_ordering.clone(),
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+impl DisplayAs for AsOfJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        let on = self
+            .on
+            .iter()
+            .map(|(l, r)| format!("({}, {})", fmt_sql(l.as_ref()), fmt_sql(r.as_ref())))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let match_condition = format!(
+            "{} {} {}",
+            fmt_sql(self.match_on.0.as_ref()),
+            self.operator,
+            fmt_sql(self.match_on.1.as_ref())
+        );
+
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "AsOfJoinExec: match_condition={match_condition}")?;
+                if !self.on.is_empty() {
+                    write!(f, ", on=[{on}]")?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "match_condition={match_condition}")?;
+                if !self.on.is_empty() {
+                    writeln!(f, "on={on}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for AsOfJoinExec {
+    fn name(&self) -> &'static str {
+        "AsOfJoinExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.left, &self.right]
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.on.is_empty() {
+            return vec![Distribution::SinglePartition, Distribution::SinglePartition];
+        }
+        let (left_expr, right_expr) = self
+            .on
+            .iter()
+            .map(|(l, r)| (Arc::clone(l), Arc::clone(r)))
+            .unzip();
+        vec![
+            Distribution::HashPartitioned(left_expr),
+            Distribution::HashPartitioned(right_expr),
+        ]
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<OrderingRequirements>> {
+        vec![
+            Some(OrderingRequirements::from(
+                self.left_required_ordering.clone(),
+            )),
+            Some(OrderingRequirements::from(
+                self.right_required_ordering.clone(),
+            )),
+        ]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        check_if_same_properties!(self, children);
+        match &children[..] {
+            [left, right] => Ok(Arc::new(AsOfJoinExec::try_new(
+                Arc::clone(left),
+                Arc::clone(right),
+                self.on.clone(),
+                self.match_on.clone(),
+                self.operator,
+            )?)),
+            _ => internal_err!(
+                "AsOfJoinExec should have 2 children, found {}",
+                children.len()
+            ),
+        }
+    }
+
+    fn reset_state(self: Arc<Self>) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(self.with_new_children_and_same_properties(vec![
+            Arc::clone(&self.left),
+            Arc::clone(&self.right),
+        ])))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        assert_eq_or_internal_err!(
+            self.left.output_partitioning().partition_count(),
+            self.right.output_partitioning().partition_count(),
+            "Invalid AsOfJoinExec, partition count mismatch between children,\
+                 consider using RepartitionExec"
+        );
+
+        let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
+        let reservation = MemoryConsumer::new(format!("AsOfJoinStream[{partition}]"))
+            .register(context.memory_pool());
+        let left = self.left.execute(partition, Arc::clone(&context))?;
+        let right = self.right.execute(partition, context)?;
+
+        let right_keys: Vec<_> = self.on.iter().map(|(_, r)| Arc::clone(r)).collect();
+        let right_schema = self.right.schema();
+        let key_converter = row_converter(&right_keys, &right_schema)?;
+        let match_converter =
+            row_converter(&[Arc::clone(&self.match_on.1)], &right_schema)?;
+
+        // The right columns of the output are nullable, see `build_join_schema`
+        let right_fields = &self.schema.fields()[self.left.schema().fields().len()..];
+        let null_columns = right_fields
+            .iter()
+            .map(|field| new_null_array(field.data_type(), 1))
+            .collect();
+        let null_row = RecordBatch::try_new_with_options(
+            Arc::new(Schema::new(right_fields.to_vec())),
+            null_columns,
+            &RecordBatchOptions::new().with_row_count(Some(1)),
+        )?;
+
+        Ok(Box::pin(AsOfJoinStream {
+            schema: Arc::clone(&self.schema),
+            left_keys: self.on.iter().map(|(l, _)| Arc::clone(l)).collect(),
+            left_match: Arc::clone(&self.match_on.0),
+            right_keys,
+            right_match: Arc::clone(&self.match_on.1),
+            operator: self.operator,
+            left,
+            right,
+            key_converter,
+            match_converter,
+            left_batch: None,
+            right_buffer: AsOfRightBuffer::default(),
+            null_row,
+            reservation,
+            join_metrics,
+            state: AsOfJoinStreamState::FetchLeft,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Arc<Statistics>> {
+        // Both sides are partitioned the same way
+        let left_stats = Arc::unwrap_or_clone(self.left.partition_statistics(partition)?);
+        let right_stats =
+            Arc::unwrap_or_clone(self.right.partition_statistics(partition)?);
+        let stats = estimate_join_statistics(
+            left_stats,
+            right_stats,
+            &self.on,
+            NullEquality::NullEqualsNothing,
+            &JoinType::AsOf,
+            &self.schema,
+        )?;
+        Ok(Arc::new(stats))
+    }
+}
+
+/// Creates a [`RowConverter`] for the given expressions, using the same sort
+/// options as the required ordering of the inputs.
+fn row_converter(exprs: &[PhysicalExprRef], schema: &SchemaRef) -> Result<RowConverter> {
+    let fields = exprs
+        .iter()
+        .map(|expr| {
+            Ok(SortField::new_with_options(
+                expr.data_type(schema)?,
+                SortOptions::new(false, true),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RowConverter::new(fields)?)
+}
+
+/// Evaluates `exprs` against `batch`
+fn evaluate_exprs(
+    exprs: &[PhysicalExprRef],
+    batch: &RecordBatch,
+) -> Result<Vec<ArrayRef>> {
+    exprs
+        .iter()
+        .map(|expr| expr.evaluate(batch)?.into_array(batch.num_rows()))
+        .collect()
+}
+
+/// A batch of the left input, with its join keys and match values converted
+/// into comparable rows, and the matches of the rows joined so far
+struct LeftBatch {
+    batch: RecordBatch,
+    /// The converted join keys, `None` if the join has no keys
+    key_rows: Option<Rows>,
+    /// The converted match values
+    match_rows: Rows,
+    /// Whether each row can have a match, that is its keys and match value
+    /// are not NULL
+    can_match: Vec<bool>,
+    /// The (batch, row) position of the right row matching each joined row,
+    /// see [`AsOfRightBuffer`]
+    matches: Vec<Option<(usize, usize)>>,
+}
+
+/// A batch of the right input, with its join keys and match values converted
+/// into comparable rows
+struct RightBatch {
+    batch: RecordBatch,
+    /// The converted join keys, `None` if the join has no keys
+    key_rows: Option<Rows>,
+    /// The converted match values
+    match_rows: Rows,
+    /// The match values, used to skip NULLs
+    match_values: ArrayRef,
+    /// The memory used by this batch
+    size: usize,
+}
+
+impl RightBatch {
+    /// Compares the join keys of `row` with the converted keys of a left row
+    fn cmp_key(&self, row: usize, key: Option<Row<'_>>) -> Ordering {
+        match (&self.key_rows, key) {
+            (Some(key_rows), Some(key)) => key_rows.row(row).cmp(&key),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+/// The position of the cursor of an [`AsOfRightBuffer`]
+enum RightPosition {
+    /// The (batch, row) position of the next right row
+    Row(usize, usize),
+    /// All the right rows were visited
+    Exhausted,
+    /// The next right batch must be fetched
+    Fetch,
+}
+
+/// The buffered right batches of an [`AsOfJoinStream`]. The rows are
+/// addressed by the position of their batch in the right input and their
+/// position in the batch.
+#[derive(Default)]
+struct AsOfRightBuffer {
+    /// The batches from the oldest one that may still be matched
+    batches: VecDeque<RightBatch>,
+    /// The number of batches that were removed from the front of `batches`
+    removed: usize,
+    /// The position of the next right row to visit
+    cursor: (usize, usize),
+    /// Whether the right input is exhausted
+    exhausted: bool,
+    /// With `>` and `>=`, the last visited right row that satisfies the match
+    /// condition
+    candidate: Option<(usize, usize)>,
+}
+
+impl AsOfRightBuffer {
+    fn batch(&self, index: usize) -> &RightBatch {
+        &self.batches[index - self.removed]
+    }
+
+    /// Moves the cursor past the end of its batch if needed
+    fn position(&mut self) -> RightPosition {
+        loop {
+            let (index, row) = self.cursor;
+            match self.batches.get(index - self.removed) {
+                Some(batch) if row < batch.batch.num_rows() => {
+                    return RightPosition::Row(index, row);
+                }
+                Some(_) => self.cursor = (index + 1, 0),
+                None if self.exhausted => return RightPosition::Exhausted,
+                None => return RightPosition::Fetch,
+            }
+        }
+    }
+
+    /// Moves the cursor forward for the left row with the converted `key` and
+    /// match `value`, and returns the position of its matching right row.
+    /// Returns `None` if the next right batch is needed to find it.
+    ///
+    /// The left rows must be visited in the order of their keys and values.
+    fn find_match(
+        &mut self,
+        key: Option<Row<'_>>,
+        value: Row<'_>,
+        operator: Operator,
+    ) -> Option<Option<(usize, usize)>> {
+        let latest = matches!(operator, Operator::Gt | Operator::GtEq);
+        let strict = matches!(operator, Operator::Gt | Operator::LtEq);
+        let next = loop {
+            let (index, row) = match self.position() {
+                RightPosition::Row(index, row) => (index, row),
+                RightPosition::Exhausted => break None,
+                RightPosition::Fetch => return None,
+            };
+            let batch = self.batch(index);
+            let ordering = batch.cmp_key(row, key);
+            // NULL match values sort first and never match
+            let is_null = batch.match_values.is_null(row);
+            // With `>` and `>=` the rows before the left row satisfy the match
+            // condition, with `<` and `<=` they do not
+            let is_before = ordering == Ordering::Equal && !is_null && {
+                let right_value = batch.match_rows.row(row);
+                if strict {
+                    right_value < value
+                } else {
+                    right_value <= value
+                }
+            };
+            match ordering {
+                Ordering::Greater => break None,
+                Ordering::Equal if !is_null && !is_before => break Some((index, row)),
+                Ordering::Equal if is_before && latest => {
+                    self.candidate = Some((index, row));
+                }
+                _ => {}
+            }
+            self.cursor = (index, row + 1);
+        };
+
+        if !latest {
+            return Some(next);
+        }
+        // The candidate of a previous key never matches again
+        if let Some((index, row)) = self.candidate
+            && self.batch(index).cmp_key(row, key) != Ordering::Equal
+        {
+            self.candidate = None;
+        }
+        Some(self.candidate)
+    }
+
+    /// Removes the batches that are before the cursor, the candidate and the
+    /// batch `keep_from`, returning the memory they used
+    fn remove_visited(&mut self, keep_from: Option<usize>) -> usize {
+        let first_needed = [self.candidate.map(|(index, _)| index), keep_from]
+            .into_iter()
+            .flatten()
+            .fold(self.cursor.0, usize::min);
+        let mut size = 0;
+        while self.removed < first_needed {
+            let Some(batch) = self.batches.pop_front() else {
+                break;
+            };
+            size += batch.size;
+            self.removed += 1;
+        }
+        size
+    }
+}
+
+/// Represents states of [`AsOfJoinStream`]
+enum AsOfJoinStreamState {
+    /// Fetching the next left batch
+    FetchLeft,
+    /// Fetching the next right batch, to join the current left batch
+    FetchRight,
+    /// Joining the rows of the current left batch
+    JoinLeft,
+}
+
+/// A stream that merges the sorted left and right inputs, emitting each left
+/// batch once all its rows are joined
+struct AsOfJoinStream {
+    /// Output schema
+    schema: SchemaRef,
+    /// Left join key expressions
+    left_keys: Vec<PhysicalExprRef>,
+    /// Left expression of the match condition
+    left_match: PhysicalExprRef,
+    /// Right join key expressions
+    right_keys: Vec<PhysicalExprRef>,
+    /// Right expression of the match condition
+    right_match: PhysicalExprRef,
+    /// Comparison operator of the match condition
+    operator: Operator,
+    /// Left (streamed) input
+    left: SendableRecordBatchStream,
+    /// Right (buffered) input
+    right: SendableRecordBatchStream,
+    /// Converts the join keys of both sides into comparable rows
+    key_converter: RowConverter,
+    /// Converts the match values of both sides into comparable rows
+    match_converter: RowConverter,
+    /// The left batch being joined
+    left_batch: Option<LeftBatch>,
+    /// The buffered right batches
+    right_buffer: AsOfRightBuffer,
+    /// A single row of NULLs, the right side of the unmatched left rows
+    null_row: RecordBatch,
+    /// Tracks the memory of the buffered right batches
+    reservation: MemoryReservation,
+    /// Join execution metrics
+    join_metrics: BuildProbeJoinMetrics,
+    /// State of the stream
+    state: AsOfJoinStreamState,
+}
+
+impl RecordBatchStream for AsOfJoinStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+impl Stream for AsOfJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.poll_next_impl(cx)
+    }
+}
+
+impl AsOfJoinStream {
+    fn poll_next_impl(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        loop {
+            return match self.state {
+                AsOfJoinStreamState::FetchLeft => {
+                    handle_state!(ready!(self.fetch_left_batch(cx)))
+                }
+                AsOfJoinStreamState::FetchRight => {
+                    handle_state!(ready!(self.fetch_right_batch(cx)))
+                }
+                AsOfJoinStreamState::JoinLeft => {
+                    let poll = handle_state!(self.join_left_batch());
+                    self.join_metrics.baseline.record_poll(poll)
+                }
+            };
+        }
+    }
+
+    /// Fetches the next left batch to join
+    fn fetch_left_batch(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Result<StatefulStreamResult<Option<RecordBatch>>>> {
+        let batch = match ready!(self.left.poll_next_unpin(cx)) {
+            Some(Ok(batch)) => batch,
+            Some(Err(e)) => return Poll::Ready(Err(e)),
+            None => {
+                // Release the resources of both inputs
+                self.left = Box::pin(EmptyRecordBatchStream::new(self.left.schema()));
+                self.right = Box::pin(EmptyRecordBatchStream::new(self.right.schema()));
+                self.right_buffer = AsOfRightBuffer::default();
+                self.reservation.free();
+                return Poll::Ready(Ok(StatefulStreamResult::Ready(None)));
+            }
+        };
+        self.join_metrics.input_batches.add(1);
+        self.join_metrics.input_rows.add(batch.num_rows());
+        if batch.num_rows() == 0 {
+            return Poll::Ready(Ok(StatefulStreamResult::Continue));
+        }
+
+        let keys = evaluate_exprs(&self.left_keys, &batch)?;
+        let match_values = self
+            .left_match
+            .evaluate(&batch)?
+            .into_array(batch.num_rows())?;
+        let can_match = (0..batch.num_rows())
+            .map(|row| {
+                !match_values.is_null(row) && keys.iter().all(|key| !key.is_null(row))
+            })
+            .collect();
+        let key_rows = (!keys.is_empty())
+            .then(|| self.key_converter.convert_columns(&keys))
+            .transpose()?;
+        let match_rows = self.match_converter.convert_columns(&[match_values])?;
+
+        self.left_batch = Some(LeftBatch {
+            matches: Vec::with_capacity(batch.num_rows()),
+            batch,
+            key_rows,
+            match_rows,
+            can_match,
+        });
+        self.state = AsOfJoinStreamState::JoinLeft;
+        Poll::Ready(Ok(StatefulStreamResult::Continue))
+    }
+
+    /// Fetches the next right batch into the buffer
+    fn fetch_right_batch(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Result<StatefulStreamResult<Option<RecordBatch>>>> {
+        match ready!(self.right.poll_next_unpin(cx)) {
+            Some(Ok(batch)) => {
+                let build_timer = self.join_metrics.build_time.timer();
+                self.join_metrics.build_input_batches.add(1);
+                self.join_metrics.build_input_rows.add(batch.num_rows());
+                if batch.num_rows() > 0 {
+                    let keys = evaluate_exprs(&self.right_keys, &batch)?;
+                    let key_rows = (!keys.is_empty())
+                        .then(|| self.key_converter.convert_columns(&keys))
+                        .transpose()?;
+                    let match_values = self
+                        .right_match
+                        .evaluate(&batch)?
+                        .into_array(batch.num_rows())?;
+                    let match_rows = self
+                        .match_converter
+                        .convert_columns(&[Arc::clone(&match_values)])?;
+                    let size = get_record_batch_memory_size(&batch)
+                        + key_rows.as_ref().map(Rows::size).unwrap_or_default()
+                        + match_rows.size()
+                        + match_values.get_array_memory_size();
+                    self.reservation.try_grow(size)?;
+                    self.join_metrics
+                        .build_mem_used
+                        .set_max(self.reservation.size());
+                    self.right_buffer.batches.push_back(RightBatch {
+                        batch,
+                        key_rows,
+                        match_rows,
+                        match_values,
+                        size,
+                    });
+                }
+                build_timer.done();
+            }
+            Some(Err(e)) => return Poll::Ready(Err(e)),
+            None => self.right_buffer.exhausted = true,
+        }
+        self.state = AsOfJoinStreamState::JoinLeft;
+        Poll::Ready(Ok(StatefulStreamResult::Continue))
+    }
+
+    /// Joins the rows of the current left batch, until the next right batch
+    /// is needed or all rows are joined
+    fn join_left_batch(&mut self) -> Result<StatefulStreamResult<Option<RecordBatch>>> {
+        let join_timer = self.join_metrics.join_time.timer();
+        let Some(left) = self.left_batch.as_mut() else {
+            return internal_err!("Expected a left batch to join in AsOfJoinStream");
+        };
+        while left.matches.len() < left.batch.num_rows() {
+            let row = left.matches.len();
+            let right_row = if left.can_match[row] {
+                let key = left.key_rows.as_ref().map(|rows| rows.row(row));
+                let value = left.match_rows.row(row);
+                match self.right_buffer.find_match(key, value, self.operator) {
+                    Some(right_row) => right_row,
+                    None => {
+                        // The matches are in order, so the batches before the
+                        // first one are not needed anymore
+                        let first_match = left.matches.iter().flatten().next();
+                        let removed = self
+                            .right_buffer
+                            .remove_visited(first_match.map(|(index, _)| *index));
+                        self.reservation.shrink(removed);
+                        self.state = AsOfJoinStreamState::FetchRight;
+                        return Ok(StatefulStreamResult::Continue);
+                    }
+                }
+            } else {
+                None
+            };
+            left.matches.push(right_row);
+        }
+
+        let Some(left) = self.left_batch.take() else {
+            return internal_err!("Expected a left batch to join in AsOfJoinStream");
+        };
+        let batch = self.build_output_batch(&left)?;
+        let removed = self.right_buffer.remove_visited(None);
+        self.reservation.shrink(removed);
+        join_timer.done();
+
+        self.state = AsOfJoinStreamState::FetchLeft;
+        Ok(StatefulStreamResult::Ready(Some(batch)))
+    }
+
+    /// Joins the rows of a left batch with their matching right rows
+    fn build_output_batch(&self, left: &LeftBatch) -> Result<RecordBatch> {
+        // The first batch is the row of NULLs of the unmatched rows
+        let right_batches = [&self.null_row]
+            .into_iter()
+            .chain(self.right_buffer.batches.iter().map(|batch| &batch.batch))
+            .collect::<Vec<_>>();
+        let indices = left
+            .matches
+            .iter()
+            .map(|right_row| match right_row {
+                Some((index, row)) => (index - self.right_buffer.removed + 1, *row),
+                None => (0, 0),
+            })
+            .collect::<Vec<_>>();
+        let right_columns = (0..self.null_row.num_columns())
+            .map(|i| {
+                let arrays = right_batches
+                    .iter()
+                    .map(|batch| batch.column(i).as_ref())
+                    .collect::<Vec<_>>();
+                Ok(interleave(&arrays, &indices)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_rows = left.batch.num_rows();
+        let columns = left
+            .batch
+            .columns()
+            .iter()
+            .cloned()
+            .chain(right_columns)
+            .collect();
+        Ok(RecordBatch::try_new_with_options(
+            Arc::clone(&self.schema),
+            columns,
+            &RecordBatchOptions::new().with_row_count(Some(num_rows)),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common;
+    use crate::test::{TestMemoryExec, build_table_i32, build_table_scan_i32};
+
+    use datafusion_common::test_util::batches_to_string;
+    use datafusion_physical_expr::expressions::col;
+    use insta::assert_snapshot;
+
+    fn quotes_batch() -> RecordBatch {
+        // Sorted by (sym, ts) as required by `AsOfJoinExec`
+        build_table_i32(
+            ("sym", &vec![1, 1, 1, 2]),
+            ("ts", &vec![2, 3, 6, 1]),
+            ("bid", &vec![10, 11, 12, 20]),
+        )
+    }
+
+    fn trades_batch() -> RecordBatch {
+        // Sorted by (t_sym, t_ts) as required by `AsOfJoinExec`
+        build_table_i32(
+            ("t_sym", &vec![1, 1, 1, 2, 3]),
+            ("t_ts", &vec![1, 3, 9, 5, 4]),
+            ("qty", &vec![100, 200, 400, 300, 500]),
+        )
+    }
+
+    /// Scans `batch` split into batches of one row
+    fn scan_rows(batch: RecordBatch) -> Result<Arc<dyn ExecutionPlan>> {
+        let batches = (0..batch.num_rows())
+            .map(|row| batch.slice(row, 1))
+            .collect::<Vec<_>>();
+        Ok(TestMemoryExec::try_new_exec(
+            &[batches],
+            batch.schema(),
+            None,
+        )?)
+    }
+
+    fn scan(batch: RecordBatch) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(TestMemoryExec::try_new_exec(
+            &[vec![batch.clone()]],
+            batch.schema(),
+            None,
+        )?)
+    }
+
+    async fn join_collect(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        with_keys: bool,
+        operator: Operator,
+    ) -> Result<Vec<RecordBatch>> {
+        let on = if with_keys {
+            vec![(col("t_sym", &left.schema())?, col("sym", &right.schema())?)]
+        } else {
+            vec![]
+        };
+        let match_on = (col("t_ts", &left.schema())?, col("ts", &right.schema())?);
+        let join = AsOfJoinExec::try_new(left, right, on, match_on, operator)?;
+
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        common::collect(stream).await
+    }
+
+    #[tokio::test]
+    async fn asof_join_with_keys() -> Result<()> {
+        let batches = join_collect(
+            scan(trades_batch())?,
+            scan(quotes_batch())?,
+            true,
+            Operator::GtEq,
+        )
+        .await?;
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +-------+------+-----+-----+----+-----+
+        | t_sym | t_ts | qty | sym | ts | bid |
+        +-------+------+-----+-----+----+-----+
+        | 1     | 1    | 100 |     |    |     |
+        | 1     | 3    | 200 | 1   | 3  | 11  |
+        | 1     | 9    | 400 | 1   | 6  | 12  |
+        | 2     | 5    | 300 | 2   | 1  | 20  |
+        | 3     | 4    | 500 |     |    |     |
+        +-------+------+-----+-----+----+-----+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_multiple_batches() -> Result<()> {
+        // Matches span right batches that are fetched while joining a left batch
+        let batches = join_collect(
+            scan(trades_batch())?,
+            scan_rows(quotes_batch())?,
+            true,
+            Operator::GtEq,
+        )
+        .await?;
+        let expected = batches_to_string(&batches);
+
+        let batches = join_collect(
+            scan_rows(trades_batch())?,
+            scan_rows(quotes_batch())?,
+            true,
+            Operator::GtEq,
+        )
+        .await?;
+        assert_eq!(batches.len(), 5);
+        assert_eq!(batches_to_string(&batches), expected);
+        assert_snapshot!(expected, @r"
+        +-------+------+-----+-----+----+-----+
+        | t_sym | t_ts | qty | sym | ts | bid |
+        +-------+------+-----+-----+----+-----+
+        | 1     | 1    | 100 |     |    |     |
+        | 1     | 3    | 200 | 1   | 3  | 11  |
+        | 1     | 9    | 400 | 1   | 6  | 12  |
+        | 2     | 5    | 300 | 2   | 1  | 20  |
+        | 3     | 4    | 500 |     |    |     |
+        +-------+------+-----+-----+----+-----+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_operators() -> Result<()> {
+        // Without keys, both sides only need to be sorted by their timestamps
+        let trades = || {
+            build_table_scan_i32(
+                ("t_sym", &vec![1, 1, 3, 2, 1]),
+                ("t_ts", &vec![1, 3, 4, 5, 9]),
+                ("qty", &vec![100, 200, 500, 300, 400]),
+            )
+        };
+        let quotes = || {
+            build_table_scan_i32(
+                ("sym", &vec![2, 1, 1, 1]),
+                ("ts", &vec![1, 2, 3, 6]),
+                ("bid", &vec![20, 10, 11, 12]),
+            )
+        };
+
+        let batches = join_collect(trades(), quotes(), false, Operator::Gt).await?;
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +-------+------+-----+-----+----+-----+
+        | t_sym | t_ts | qty | sym | ts | bid |
+        +-------+------+-----+-----+----+-----+
+        | 1     | 1    | 100 |     |    |     |
+        | 1     | 3    | 200 | 1   | 2  | 10  |
+        | 3     | 4    | 500 | 1   | 3  | 11  |
+        | 2     | 5    | 300 | 1   | 3  | 11  |
+        | 1     | 9    | 400 | 1   | 6  | 12  |
+        +-------+------+-----+-----+----+-----+
+        ");
+
+        let batches = join_collect(trades(), quotes(), false, Operator::LtEq).await?;
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +-------+------+-----+-----+----+-----+
+        | t_sym | t_ts | qty | sym | ts | bid |
+        +-------+------+-----+-----+----+-----+
+        | 1     | 1    | 100 | 2   | 1  | 20  |
+        | 1     | 3    | 200 | 1   | 3  | 11  |
+        | 3     | 4    | 500 | 1   | 6  | 12  |
+        | 2     | 5    | 300 | 1   | 6  | 12  |
+        | 1     | 9    | 400 |     |    |     |
+        +-------+------+-----+-----+----+-----+
+        ");
+
+        let batches = join_collect(trades(), quotes(), false, Operator::Lt).await?;
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +-------+------+-----+-----+----+-----+
+        | t_sym | t_ts | qty | sym | ts | bid |
+        +-------+------+-----+-----+----+-----+
+        | 1     | 1    | 100 | 1   | 2  | 10  |
+        | 1     | 3    | 200 | 1   | 6  | 12  |
+        | 3     | 4    | 500 | 1   | 6  | 12  |
+        | 2     | 5    | 300 | 1   | 6  | 12  |
+        | 1     | 9    | 400 |     |    |     |
+        +-------+------+-----+-----+----+-----+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_empty_right() -> Result<()> {
+        let empty =
+            build_table_scan_i32(("sym", &vec![]), ("ts", &vec![]), ("bid", &vec![]));
+        let batches =
+            join_collect(scan(trades_batch())?, empty, true, Operator::GtEq).await?;
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +-------+------+-----+-----+----+-----+
+        | t_sym | t_ts | qty | sym | ts | bid |
+        +-------+------+-----+-----+----+-----+
+        | 1     | 1    | 100 |     |    |     |
+        | 1     | 3    | 200 |     |    |     |
+        | 1     | 9    | 400 |     |    |     |
+        | 2     | 5    | 300 |     |    |     |
+        | 3     | 4    | 500 |     |    |     |
+        +-------+------+-----+-----+----+-----+
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn asof_join_rejects_equality_operator() -> Result<()> {
+        let (left, right) = (scan(trades_batch())?, scan(quotes_batch())?);
+        let match_on = (col("t_ts", &left.schema())?, col("ts", &right.schema())?);
+        let err = AsOfJoinExec::try_new(left, right, vec![], match_on, Operator::Eq)
+            .unwrap_err();
+        assert_snapshot!(err.strip_backtrace(), @"Error during planning: As-of join match condition must use <, <=, > or >=, got =");
+        Ok(())
+    }
+
+    #[test]
+    fn asof_join_requires_sorted_partitioned_inputs() -> Result<()> {
+        let (left, right) = (scan(trades_batch())?, scan(quotes_batch())?);
+        let on = vec![(col("t_sym", &left.schema())?, col("sym", &right.schema())?)];
+        let match_on = (col("t_ts", &left.schema())?, col("ts", &right.schema())?);
+        let join = AsOfJoinExec::try_new(left, right, on, match_on, Operator::GtEq)?;
+
+        let distribution = join.required_input_distribution();
+        assert!(matches!(
+            distribution.as_slice(),
+            [
+                Distribution::HashPartitioned(_),
+                Distribution::HashPartitioned(_)
+            ]
+        ));
+        assert_eq!(
+            join.left_required_ordering.to_string(),
+            "t_sym@0 ASC, t_ts@1 ASC"
+        );
+        assert_eq!(
+            join.right_required_ordering.to_string(),
+            "sym@0 ASC, ts@1 ASC"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Bloom filter of the build side join keys for dynamic filtering
+
+use std::{fmt::Display, hash::Hash, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, BooleanArray},
+    buffer::BooleanBuffer,
+    datatypes::{DataType, Schema},
+    record_batch::RecordBatch,
+};
+use datafusion_common::Result;
+use datafusion_common::hash_utils::with_hashes;
+use datafusion_expr::ColumnarValue;
+use datafusion_physical_expr_common::physical_expr::{
+    DynHash, PhysicalExpr, PhysicalExprRef,
+};
+
+use super::partitioned_hash_eval::{SeededRandomState, evaluate_columns};
+
+/// Salts deriving the 8 bits set by a hash in its block, as in the Parquet
+/// split block Bloom filter
+const SALT: [u32; 8] = [
+    0x47b6137b, 0x44974d91, 0x8824ad5b, 0xa2b7289d, 0x705495c7, 0x2df1424b, 0x9efc4947,
+    0x5c6bfb31,
+];
+
+/// A split block Bloom filter of join key hashes.
+///
+/// Each hash sets 8 bits of a single 256 bit block, so checking a hash reads a
+/// single cache line. This makes it smaller and faster to probe than the hash
+/// table of the join, at the cost of false positives.
+///
+/// See "Cache-, Hash- and Space-Efficient Bloom Filters" by Putze et al.
+#[derive(Debug, Clone)]
+pub struct JoinBloomFilter {
+    blocks: Vec<[u32; 8]>,
+}
+
+impl JoinBloomFilter {
+    /// Create an empty filter for `num_keys` distinct keys with a false
+    /// positive probability of `fpp`
+    pub fn new(num_keys: usize, fpp: f64) -> Self {
+        let fpp = fpp.clamp(f64::EPSILON, 0.5);
+        // The number of bits per key for a false positive probability of `fpp`
+        // with 8 bits set per key
+        let num_bits = -8.0 * num_keys as f64 / (1.0 - fpp.powf(1.0 / 8.0)).ln();
+        let num_blocks = ((num_bits / 256.0).ceil() as usize).max(1);
+        Self {
+            blocks: vec![[0; 8]; num_blocks],
+        }
+    }
+
+    /// Create a filter of the hashes of `values` with `random_state`
+    pub fn try_new_from_values(
+        values: &[ArrayRef],
+        random_state: &SeededRandomState,
+        num_keys: usize,
+        fpp: f64,
+    ) -> Result<Self> {
+        let mut filter = Self::new(num_keys, fpp);
+        with_hashes(values, random_state.random_state(), |hashes| {
+            hashes.iter().for_each(|hash| filter.insert_hash(*hash));
+            Ok(())
+        })?;
+        Ok(filter)
+    }
+
+    /// The block of `hash`, chosen by its upper 32 bits
+    fn block_index(&self, hash: u64) -> usize {
+        (((hash >> 32) * self.blocks.len() as u64) >> 32) as usize
+    }
+
+    /// The bits of its block set by `hash`, chosen by its lower 32 bits
+    fn mask(hash: u64) -> [u32; 8] {
+        let key = hash as u32;
+        SALT.map(|salt| 1 << (key.wrapping_mul(salt) >> 27))
+    }
+
+    /// Add `hash` to the filter
+    pub fn insert_hash(&mut self, hash: u64) {
+        let index = self.block_index(hash);
+        let block = &mut self.blocks[index];
+        for (word, mask) in block.iter_mut().zip(Self::mask(hash)) {
+            *word |= mask;
+        }
+    }
+
+    /// Returns false if `hash` was definitely not added to the filter
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        let block = &self.blocks[self.block_index(hash)];
+        block
+            .iter()
+            .zip(Self::mask(hash))
+            .all(|(word, mask)| word & mask != 0)
+    }
+
+    /// Returns which of `hashes` may have been added to the filter
+    pub fn contain_hashes(&self, hashes: &[u64]) -> BooleanArray {
+        let buffer =
+            BooleanBuffer::collect_bool(hashes.len(), |i| self.contains_hash(hashes[i]));
+        BooleanArray::new(buffer, None)
+    }
+
+    /// The size of the filter in bytes
+    pub fn size(&self) -> usize {
+        self.blocks.len() * size_of::<[u32; 8]>()
+    }
+}
+
+/// Physical expression that checks join keys in a [`JoinBloomFilter`] of the
+/// build side join keys.
+///
+/// Returns a [`BooleanArray`] that is false for the rows whose join keys
+/// (from `on_columns`) definitely have no match on the build side.
+pub struct BloomFilterExpr {
+    /// Columns in the ON clause used to compute the join key for lookups
+    on_columns: Vec<PhysicalExprRef>,
+    /// Random state for hashing (with seeds preserved for serialization)
+    random_state: SeededRandomState,
+    /// Filter to check against
+    filter: Arc<JoinBloomFilter>,
+    /// Description for display
+    description: String,
+}
+
+impl BloomFilterExpr {
+    /// Create a new BloomFilterExpr
+    ///
+    /// # Arguments
+    /// * `on_columns` - Columns in the ON clause used to compute the join key
+    /// * `random_state` - SeededRandomState the filter was built with
+    /// * `filter` - Bloom filter of the build side join keys
+    /// * `description` - Description for debugging
+    pub fn new(
+        on_columns: Vec<PhysicalExprRef>,
+        random_state: SeededRandomState,
+        filter: Arc<JoinBloomFilter>,
+        description: String,
+    ) -> Self {
+        Self {
+            on_columns,
+            random_state,
+            filter,
+            description,
+        }
+    }
+
+    /// Get the Bloom filter checked by this expression.
+    pub fn filter(&self) -> &Arc<JoinBloomFilter> {
+        &self.filter
+    }
+}
+
+impl std::fmt::Debug for BloomFilterExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cols = self
+            .on_columns
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let seed = self.random_state.seed();
+        write!(f, "{}({cols}, [{seed}])", self.description)
+    }
+}
+
+impl Hash for BloomFilterExpr {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.on_columns.dyn_hash(state);
+        self.description.hash(state);
+        self.random_state.seed().hash(state);
+        // Compared by pointer, as HashJoinExec creates one filter per
+        // partition per query execution (see HashTableLookupExpr)
+        Arc::as_ptr(&self.filter).hash(state);
+    }
+}
+
+impl PartialEq for BloomFilterExpr {
+    fn eq(&self, other: &Self) -> bool {
+        self.on_columns == other.on_columns
+            && self.description == other.description
+            && self.random_state.seed() == other.random_state.seed()
+            && Arc::ptr_eq(&self.filter, &other.filter)
+    }
+}
+
+impl Eq for BloomFilterExpr {}
+
+impl Display for BloomFilterExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+impl PhysicalExpr for BloomFilterExpr {
+    fn children(&self) -> Vec<&Arc<dyn PhysicalExpr>> {
+        self.on_columns.iter().collect()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        Ok(Arc::new(BloomFilterExpr::new(
+            children,
+            self.random_state.clone(),
+            Arc::clone(&self.filter),
+            self.description.clone(),
+        )))
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn nullable(&self, _input_schema: &Schema) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let join_keys = evaluate_columns(&self.on_columns, batch)?;
+        with_hashes(&join_keys, self.random_state.random_state(), |hashes| {
+            let array = self.filter.contain_hashes(hashes);
+            Ok(ColumnarValue::Array(Arc::new(array)))
+        })
+    }
+
+    #[cfg(feature = "proto")]
+    fn try_to_proto(
+        &self,
+        _ctx: &datafusion_physical_expr_common::physical_expr::proto_encode::PhysicalExprEncodeCtx<'_>,
+    ) -> Result<Option<datafusion_proto_models::protobuf::PhysicalExprNode>> {
+        use datafusion_proto_models::protobuf;
+        use datafusion_proto_models::protobuf::physical_expr_node::ExprType;
+
+        // Like HashTableLookupExpr, the filter is only created at runtime and
+        // is replaced with lit(true), which lets all rows through
+        let value = datafusion_proto_common::ScalarValue {
+            value: Some(datafusion_proto_common::scalar_value::Value::BoolValue(
+                true,
+            )),
+        };
+        Ok(Some(protobuf::PhysicalExprNode {
+            expr_id: None,
+            expr_type: Some(ExprType::Literal(value)),
+        }))
+    }
+
+    fn fmt_sql(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{AsArray, Int32Array};
+    use arrow::datatypes::Field;
+    use datafusion_physical_expr::expressions::Column;
+
+    use crate::joins::hash_join::exec::HASH_JOIN_SEED;
+
+    #[test]
+    fn test_bloom_filter_no_false_negatives() -> Result<()> {
+        let values: ArrayRef = Arc::new(Int32Array::from_iter_values(0..10_000));
+        let filter = JoinBloomFilter::try_new_from_values(
+            &[Arc::clone(&values)],
+            &HASH_JOIN_SEED,
+            10_000,
+            0.01,
+        )?;
+
+        with_hashes([&values], HASH_JOIN_SEED.random_state(), |hashes| {
+            assert!(hashes.iter().all(|hash| filter.contains_hash(*hash)));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_bloom_filter_false_positive_rate() -> Result<()> {
+        let values: ArrayRef = Arc::new(Int32Array::from_iter_values(0..10_000));
+        let filter = JoinBloomFilter::try_new_from_values(
+            &[values],
+            &HASH_JOIN_SEED,
+            10_000,
+            0.01,
+        )?;
+
+        let others: ArrayRef = Arc::new(Int32Array::from_iter_values(10_000..110_000));
+        let false_positives =
+            with_hashes([&others], HASH_JOIN_SEED.random_state(), |hashes| {
+                Ok(filter.contain_hashes(hashes).true_count())
+            })?;
+        assert!(false_positives < 2_000, "{false_positives} false positives");
+        Ok(())
+    }
+
+    #[test]
+    fn test_bloom_filter_expr() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let build: ArrayRef = Arc::new(Int32Array::from(vec![1, 3, 5]));
+        let filter =
+            JoinBloomFilter::try_new_from_values(&[build], &HASH_JOIN_SEED, 3, 0.001)?;
+        let expr = BloomFilterExpr::new(
+            vec![Arc::new(Column::new("a", 0))],
+            HASH_JOIN_SEED,
+            Arc::new(filter),
+            "bloom_filter".to_string(),
+        );
+
+        let probe = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5]))],
+        )?;
+        let result = expr.evaluate(&probe)?.into_array(probe.num_rows())?;
+        let result = result.as_boolean();
+        assert!(result.value(0) && result.value(2) && result.value(4));
+        Ok(())
+    }
+}
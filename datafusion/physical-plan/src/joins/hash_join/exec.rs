@@ -33,7 +33,11 @@ use crate::filter_pushdown::{
 };
 use crate::joins::Map;
 use crate::joins::array_map::ArrayMap;
-use crate::joins::hash_join::inlist_builder::build_struct_inlist_values;
+use crate::joins::hash_join::bloom_filter::JoinBloomFilter;
+use crate::joins::hash_join::grace::GraceHashJoin;
+use crate::joins::hash_join::inlist_builder::{
+    build_struct_inlist_values, distinct_values,
+};
 use crate::joins::hash_join::shared_bounds::{
     ColumnBounds, PartitionBounds, PushdownStrategy, SharedBuildAccumulator,
 };
@@ -46,12 +50,13 @@ use crate::joins::utils::{
     swap_join_projection, update_hash,
 };
 use crate::joins::{JoinOn, JoinOnRef, PartitionMode, SharedBitmapBuilder};
-use crate::metrics::{Count, MetricBuilder, MetricCategory};
+use crate::metrics::{Count, MetricBuilder, MetricCategory, SpillMetrics};
 use crate::projection::{
     EmbeddedProjection, JoinData, ProjectionExec, try_embed_projection,
     try_pushdown_through_join,
 };
 use crate::repartition::REPARTITION_RANDOM_STATE;
+use crate::spill::spill_manager::SpillManager;
 use crate::{
     DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
     PlanProperties, SendableRecordBatchStream, Statistics,
@@ -208,7 +213,7 @@ pub(super) struct JoinLeftData {
     /// If the partition has some rows this will be Some with the bounds for each join key column.
     pub(super) bounds: Option<PartitionBounds>,
     /// Membership testing strategy for filter pushdown
-    /// Contains either InList values for small build sides or hash table reference for large build sides
+    /// Contains either InList values for small build sides or a Bloom filter or hash table reference for large build sides
     pub(super) membership: PushdownStrategy,
     /// Shared atomic flag indicating if any probe partition saw data (for null-aware anti joins)
     /// This is shared across all probe partitions to provide global knowledge
@@ -449,6 +454,9 @@ impl HashJoinExecBuilder {
         }
 
         check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if join_type == JoinType::AsOf {
+            return plan_err!("HashJoinExec does not support as-of joins");
+        }
         let (join_schema, column_indices) =
             build_join_schema(&left_schema, &right_schema, &join_type);
 
@@ -708,6 +716,18 @@ impl From<&HashJoinExec> for HashJoinExecBuilder {
 ///                       └───────────────┘     └───────────────┘
 /// ```
 ///
+/// # Memory-limited Execution
+///
+/// In [`PartitionMode::Partitioned`] mode, when the build side of a partition
+/// does not fit in memory and disk spilling is available, the operator falls
+/// back to a "grace hash join": both inputs of the partition are split into
+/// buckets on disk by the hash of their join keys, and the buckets are then
+/// joined one by one, splitting them again if needed. See [`GraceHashJoin`]
+/// for details.
+///
+/// The fallback is not used when the join must preserve the order of the
+/// probe side, nor for null-aware anti joins.
+///
 /// # Clone / Shared State
 ///
 /// Note this structure includes a [`OnceAsync`] that is used to coordinate the
@@ -859,6 +879,20 @@ impl HashJoinExec {
         true
     }
 
+    /// Returns true if the build side can be spilled to disk when it does not
+    /// fit in memory, see [`GraceHashJoin`]
+    fn can_spill(&self, context: &TaskContext) -> bool {
+        // The build side is only split per partition, and spilled buckets do
+        // not preserve the order of the probe side. Null-aware anti joins need
+        // to know if any probe row has a NULL key, across all buckets.
+        let probe_order_maintained = Self::maintains_input_order(self.join_type)[1]
+            && self.right.output_ordering().is_some();
+        self.mode == PartitionMode::Partitioned
+            && !self.null_aware
+            && !probe_order_maintained
+            && context.runtime_env().disk_manager.tmp_files_enabled()
+    }
+
     /// left (build) side which gets hashed
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -1015,7 +1049,8 @@ impl HashJoinExec {
                 JoinType::Left
                 | JoinType::LeftAnti
                 | JoinType::LeftMark
-                | JoinType::Full => EmissionType::Both,
+                | JoinType::Full
+                | JoinType::AsOf => EmissionType::Both,
             }
         } else {
             right.pipeline_behavior()
@@ -1070,7 +1105,7 @@ impl HashJoinExec {
         let right = self.right();
         let new_join = self
             .builder()
-            .with_type(self.join_type.swap())
+            .with_type(self.join_type.try_swap()?)
             .with_new_children(vec![Arc::clone(right), Arc::clone(left)])?
             .with_on(
                 self.on()
@@ -1337,6 +1372,21 @@ impl ExecutionPlan for HashJoinExec {
             .flatten()
             .flatten();
 
+        // update column indices to reflect the projection
+        let column_indices_after_projection = match self.projection.as_ref() {
+            Some(projection) => projection
+                .iter()
+                .map(|i| self.column_indices[*i].clone())
+                .collect(),
+            None => self.column_indices.clone(),
+        };
+
+        let on_right = self
+            .on
+            .iter()
+            .map(|(_, right_expr)| Arc::clone(right_expr))
+            .collect::<Vec<_>>();
+
         let left_fut = match self.mode {
             PartitionMode::CollectLeft => self.left_fut.try_once(|| {
                 let left_stream = self.left.execute(0, Arc::clone(&context))?;
@@ -1358,6 +1408,54 @@ impl ExecutionPlan for HashJoinExec {
                     array_map_created_count,
                 ))
             })?,
+            PartitionMode::Partitioned if self.can_spill(&context) => {
+                let left_stream = self.left.execute(partition, Arc::clone(&context))?;
+                let right_stream = self.right.execute(partition, Arc::clone(&context))?;
+
+                let reservation =
+                    MemoryConsumer::new(format!("HashJoinInput[{partition}]"))
+                        .with_can_spill(true)
+                        .register(context.memory_pool());
+                let spill_metrics = SpillMetrics::new(&self.metrics, partition);
+                let spill_compression = context.session_config().spill_compression();
+                let build_spill_manager = SpillManager::new(
+                    context.runtime_env(),
+                    spill_metrics.clone(),
+                    self.left.schema(),
+                )
+                .with_compression_type(spill_compression);
+                let probe_spill_manager = SpillManager::new(
+                    context.runtime_env(),
+                    spill_metrics,
+                    self.right.schema(),
+                )
+                .with_compression_type(spill_compression);
+
+                return Ok(GraceHashJoin::new(
+                    partition,
+                    self.schema(),
+                    on_left,
+                    on_right,
+                    self.filter.clone(),
+                    self.join_type,
+                    self.random_state.random_state().clone(),
+                    column_indices_after_projection,
+                    self.null_equality,
+                    self.fetch,
+                    context,
+                    join_metrics,
+                    array_map_created_count,
+                    build_spill_manager,
+                    probe_spill_manager,
+                    left_stream,
+                    right_stream,
+                    reservation,
+                    self.right.output_ordering().is_some(),
+                    build_accumulator,
+                    enable_dynamic_filter_pushdown,
+                )
+                .into_stream());
+            }
             PartitionMode::Partitioned => {
                 let left_stream = self.left.execute(partition, Arc::clone(&context))?;
 
@@ -1392,21 +1490,6 @@ impl ExecutionPlan for HashJoinExec {
         // over the right that uses this information to issue new batches.
         let right_stream = self.right.execute(partition, context)?;
 
-        // update column indices to reflect the projection
-        let column_indices_after_projection = match self.projection.as_ref() {
-            Some(projection) => projection
-                .iter()
-                .map(|i| self.column_indices[*i].clone())
-                .collect(),
-            None => self.column_indices.clone(),
-        };
-
-        let on_right = self
-            .on
-            .iter()
-            .map(|(_, right_expr)| Arc::clone(right_expr))
-            .collect::<Vec<_>>();
-
         Ok(Box::pin(HashJoinStream::new(
             partition,
             self.schema(),
@@ -1716,7 +1799,7 @@ impl ExecutionPlan for HashJoinExec {
 fn lr_is_preserved(join_type: JoinType) -> (bool, bool) {
     match join_type {
         JoinType::Inner => (true, true),
-        JoinType::Left => (true, false),
+        JoinType::Left | JoinType::AsOf => (true, false),
         JoinType::Right => (false, true),
         JoinType::Full => (false, false),
         // Filters in semi/anti joins are either on the preserved side, or on join keys,
@@ -1810,7 +1893,7 @@ impl CollectLeftAccumulator {
 }
 
 /// State for collecting the build-side data during hash join
-struct BuildSideState {
+pub(super) struct BuildSideState {
     batches: Vec<RecordBatch>,
     num_rows: usize,
     metrics: BuildProbeJoinMetrics,
@@ -1824,7 +1907,7 @@ struct BuildSideState {
 
 impl BuildSideState {
     /// Create a new BuildSideState with optional accumulators for bounds computation
-    fn try_new(
+    pub(super) fn try_new(
         metrics: BuildProbeJoinMetrics,
         reservation: MemoryReservation,
         on_left: Vec<Arc<dyn PhysicalExpr>>,
@@ -1847,9 +1930,53 @@ impl BuildSideState {
                 .transpose()?,
         })
     }
+
+    /// Reserves memory for `batch`, returning its size. The batch must then be
+    /// added with [`Self::push`].
+    pub(super) fn try_reserve(&mut self, batch: &RecordBatch) -> Result<usize> {
+        let batch_size = self.memory_counter.count_batch(batch);
+        self.reservation.try_grow(batch_size)?;
+        Ok(batch_size)
+    }
+
+    /// Adds `batch`, whose memory was reserved by [`Self::try_reserve`], to
+    /// the build side
+    pub(super) fn push(&mut self, batch: RecordBatch, batch_size: usize) -> Result<()> {
+        // Update accumulators if computing bounds
+        if let Some(ref mut accumulators) = self.bounds_accumulators {
+            for accumulator in accumulators {
+                accumulator.update_batch(&batch)?;
+            }
+        }
+
+        // Update metrics
+        self.metrics.build_mem_used.add(batch_size);
+        self.metrics.build_input_batches.add(1);
+        self.metrics.build_input_rows.add(batch.num_rows());
+        // Update row count
+        self.num_rows += batch.num_rows();
+        // Push batch to output
+        self.batches.push(batch);
+        Ok(())
+    }
+
+    /// Returns the number of rows collected so far
+    pub(super) fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Returns the memory reservation of the build side
+    pub(super) fn reservation_mut(&mut self) -> &mut MemoryReservation {
+        &mut self.reservation
+    }
+
+    /// Returns the batches collected so far
+    pub(super) fn into_batches(self) -> Vec<RecordBatch> {
+        self.batches
+    }
 }
 
-fn should_collect_min_max_for_perfect_hash(
+pub(super) fn should_collect_min_max_for_perfect_hash(
     on_left: &[PhysicalExprRef],
     schema: &SchemaRef,
 ) -> Result<bool> {
@@ -1919,29 +2046,46 @@ async fn collect_left_input(
 
     let state = left_stream
         .try_fold(initial, |mut state, batch| async move {
-            // Update accumulators if computing bounds
-            if let Some(ref mut accumulators) = state.bounds_accumulators {
-                for accumulator in accumulators {
-                    accumulator.update_batch(&batch)?;
-                }
-            }
-
-            // Decide if we spill or not
-            let batch_size = state.memory_counter.count_batch(&batch);
             // Reserve memory for incoming batch
-            state.reservation.try_grow(batch_size)?;
-            // Update metrics
-            state.metrics.build_mem_used.add(batch_size);
-            state.metrics.build_input_batches.add(1);
-            state.metrics.build_input_rows.add(batch.num_rows());
-            // Update row count
-            state.num_rows += batch.num_rows();
-            // Push batch to output
-            state.batches.push(batch);
+            let batch_size = state.try_reserve(&batch)?;
+            state.push(batch, batch_size)?;
             Ok(state)
         })
         .await?;
 
+    finish_left_input(
+        state,
+        &schema,
+        &random_state,
+        &on_left,
+        with_visited_indices_bitmap,
+        probe_threads_count,
+        should_compute_dynamic_filters,
+        &config,
+        null_equality,
+        &array_map_created_count,
+    )
+}
+
+/// Builds the hash map for the build side batches collected in `state`.
+///
+/// See [`collect_left_input`] for details.
+#[expect(clippy::too_many_arguments)]
+pub(super) fn finish_left_input(
+    state: BuildSideState,
+    schema: &SchemaRef,
+    random_state: &RandomState,
+    on_left: &[PhysicalExprRef],
+    with_visited_indices_bitmap: bool,
+    probe_threads_count: usize,
+    should_compute_dynamic_filters: bool,
+    config: &ConfigOptions,
+    null_equality: NullEquality,
+    array_map_created_count: &Count,
+) -> Result<JoinLeftData> {
+    let should_collect_min_max_for_phj =
+        should_collect_min_max_for_perfect_hash(on_left, schema)?;
+
     // Extract fields from state
     let BuildSideState {
         batches,
@@ -1967,9 +2111,9 @@ async fn collect_left_input(
     let (join_hash_map, batch, left_values) =
         if let Some((array_map, batch, left_value)) = try_create_array_map(
             &bounds,
-            &schema,
+            schema,
             &batches,
-            &on_left,
+            on_left,
             &mut reservation,
             config.execution.perfect_hash_join_small_build_threshold,
             config.execution.perfect_hash_join_min_key_density,
@@ -2012,11 +2156,11 @@ async fn collect_left_input(
                 hashes_buffer.clear();
                 hashes_buffer.resize(batch.num_rows(), 0);
                 update_hash(
-                    &on_left,
+                    on_left,
                     batch,
                     &mut *hashmap,
                     offset,
-                    &random_state,
+                    random_state,
                     &mut hashes_buffer,
                     0,
                     true,
@@ -2025,9 +2169,9 @@ async fn collect_left_input(
             }
 
             // Merge all batches into a single batch, so we can directly index into the arrays
-            let batch = concat_batches(&schema, batches_iter.clone())?;
+            let batch = concat_batches(schema, batches_iter.clone())?;
 
-            let left_values = evaluate_expressions_to_arrays(&on_left, &batch)?;
+            let left_values = evaluate_expressions_to_arrays(on_left, &batch)?;
 
             (Map::HashMap(hashmap), batch, left_values)
         };
@@ -2051,13 +2195,14 @@ async fn collect_left_input(
         PushdownStrategy::Empty
     } else {
         // If the build side is small enough we can use IN list pushdown.
-        // If it's too big we fall back to pushing down a reference to the hash table.
+        // If it's too big we fall back to pushing down a Bloom filter of the join keys
+        // (if enabled) or a reference to the hash table.
         // See `PushdownStrategy` for more details.
         let estimated_size = left_values
             .iter()
             .map(|arr| arr.get_array_memory_size())
             .sum::<usize>();
-        if left_values.is_empty()
+        let in_list_values = if left_values.is_empty()
             || left_values[0].is_empty()
             || estimated_size > config.optimizer.hash_join_inlist_pushdown_max_size
             || map.num_of_distinct_key()
@@ -2065,11 +2210,44 @@ async fn collect_left_input(
                     .optimizer
                     .hash_join_inlist_pushdown_max_distinct_values
         {
-            PushdownStrategy::Map(Arc::clone(&map))
-        } else if let Some(in_list_values) = build_struct_inlist_values(&left_values)? {
-            PushdownStrategy::InList(in_list_values)
+            None
         } else {
-            PushdownStrategy::Map(Arc::clone(&map))
+            build_struct_inlist_values(&left_values)?
+        };
+        match in_list_values {
+            Some(in_list_values) => PushdownStrategy::InList(in_list_values),
+            None if should_compute_dynamic_filters
+                && !left_values.is_empty()
+                && config.optimizer.hash_join_bloom_filter_pushdown =>
+            {
+                let filter = JoinBloomFilter::try_new_from_values(
+                    &left_values,
+                    &HASH_JOIN_SEED,
+                    map.num_of_distinct_key(),
+                    config.optimizer.hash_join_bloom_filter_fpp,
+                )?;
+                reservation.try_grow(filter.size())?;
+                metrics.build_mem_used.add(filter.size());
+                // Parquet Bloom filters can only be probed with literal values, so
+                // the distinct keys of a single column are also pushed down as an IN
+                // list if there are few and small enough of them
+                let probe_values = if left_values.len() == 1
+                    && map.num_of_distinct_key()
+                        <= config.optimizer.hash_join_bloom_filter_max_probe_values
+                {
+                    let values = distinct_values(&left_values[0])?;
+                    (values.get_array_memory_size()
+                        <= config.optimizer.hash_join_inlist_pushdown_max_size)
+                        .then_some(values)
+                } else {
+                    None
+                };
+                PushdownStrategy::BloomFilter {
+                    filter: Arc::new(filter),
+                    probe_values,
+                }
+            }
+            None => PushdownStrategy::Map(Arc::clone(&map)),
         }
     };
 
@@ -2153,6 +2331,7 @@ mod tests {
         exec_err, internal_err,
     };
     use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
     use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::{BinaryExpr, Literal};
@@ -5472,8 +5651,12 @@ mod tests {
         ];
 
         for join_type in join_types {
+            // Disable spilling to disk, which would otherwise be used
             let runtime = RuntimeEnvBuilder::new()
                 .with_memory_limit(100, 1.0)
+                .with_disk_manager_builder(
+                    DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+                )
                 .build_arc()?;
             let session_config = SessionConfig::default().with_batch_size(50);
             let task_ctx = TaskContext::default()
@@ -5511,6 +5694,184 @@ mod tests {
         Ok(())
     }
 
+    /// Joins `left` and `right` on `b1 = b2` in partitioned mode, with the
+    /// given memory limit
+    async fn partitioned_join_with_memory_limit(
+        left: &RecordBatch,
+        right: &RecordBatch,
+        join_type: JoinType,
+        memory_limit: Option<usize>,
+    ) -> Result<(Vec<RecordBatch>, MetricsSet)> {
+        let mut runtime = RuntimeEnvBuilder::new();
+        if let Some(memory_limit) = memory_limit {
+            runtime = runtime.with_memory_limit(memory_limit, 1.0);
+        }
+        let task_ctx = Arc::new(
+            TaskContext::default()
+                .with_session_config(SessionConfig::default().with_batch_size(1024))
+                .with_runtime(runtime.build_arc()?),
+        );
+
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let join = HashJoinExec::try_new(
+            TestMemoryExec::try_new_exec(&[vec![left.clone()]], left.schema(), None)?,
+            TestMemoryExec::try_new_exec(&[vec![right.clone()]], right.schema(), None)?,
+            on,
+            None,
+            &join_type,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+            false,
+        )?;
+
+        let batches = common::collect(join.execute(0, task_ctx)?).await?;
+        Ok((batches, join.metrics().unwrap()))
+    }
+
+    #[cfg(not(feature = "force_hash_collisions"))]
+    #[tokio::test]
+    async fn partitioned_join_spill() -> Result<()> {
+        // The build side fits in memory, but not its hash table
+        let left = build_table_i32(
+            ("a1", &(0..4096).collect()),
+            ("b1", &(0..4096).map(|i| i % 3000).collect()),
+            ("c1", &(0..4096).collect()),
+        );
+        let right = build_table_i32(
+            ("a2", &(0..2048).collect()),
+            ("b2", &(0..2048).map(|i| i * 2).collect()),
+            ("c2", &(0..2048).collect()),
+        );
+
+        let join_types = vec![
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+            JoinType::LeftMark,
+            JoinType::RightMark,
+        ];
+
+        for join_type in join_types {
+            let (expected, metrics) =
+                partitioned_join_with_memory_limit(&left, &right, join_type, None)
+                    .await?;
+            assert_eq!(metrics.spill_count(), Some(0));
+
+            let (batches, metrics) = partitioned_join_with_memory_limit(
+                &left,
+                &right,
+                join_type,
+                Some(64 * 1024),
+            )
+            .await?;
+            assert!(metrics.spill_count().unwrap() > 0, "{join_type}");
+            assert!(
+                metrics.spilled_rows().unwrap() >= 4096 + 2048,
+                "{join_type}"
+            );
+            assert_eq!(
+                batches_to_sort_string(&batches),
+                batches_to_sort_string(&expected),
+                "{join_type}"
+            );
+            assert_join_metrics!(metrics, num_rows(&expected));
+        }
+
+        Ok(())
+    }
+
+    fn num_rows(batches: &[RecordBatch]) -> usize {
+        batches.iter().map(|b| b.num_rows()).sum()
+    }
+
+    #[cfg(not(feature = "force_hash_collisions"))]
+    #[tokio::test]
+    async fn partitioned_join_spill_recursively() -> Result<()> {
+        // The buckets of the build side do not fit in memory either, and are
+        // split again
+        let n = 65536;
+        let left = build_table_i32(
+            ("a1", &(0..n).collect()),
+            ("b1", &(0..n).map(|i| i % 50000).collect()),
+            ("c1", &(0..n).collect()),
+        );
+        let right = build_table_i32(
+            ("a2", &(0..n).collect()),
+            ("b2", &(0..n).map(|i| i * 3 % 70000).collect()),
+            ("c2", &(0..n).collect()),
+        );
+
+        for join_type in [JoinType::Inner, JoinType::Full] {
+            let (expected, _) =
+                partitioned_join_with_memory_limit(&left, &right, join_type, None)
+                    .await?;
+
+            let (batches, metrics) = partitioned_join_with_memory_limit(
+                &left,
+                &right,
+                join_type,
+                Some(64 * 1024),
+            )
+            .await?;
+            // More files than a single split of both inputs
+            assert!(metrics.spill_count().unwrap() > 32, "{join_type}");
+            assert_eq!(
+                batches_to_sort_string(&batches),
+                batches_to_sort_string(&expected),
+                "{join_type}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "force_hash_collisions"))]
+    #[tokio::test]
+    async fn partitioned_join_spill_with_fetch() -> Result<()> {
+        let left = build_table_i32(
+            ("a1", &(0..4096).collect()),
+            ("b1", &(0..4096).collect()),
+            ("c1", &(0..4096).collect()),
+        );
+        let right = build_table_i32(
+            ("a2", &(0..4096).collect()),
+            ("b2", &(0..4096).collect()),
+            ("c2", &(0..4096).collect()),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let join = HashJoinExecBuilder::new(
+            TestMemoryExec::try_new_exec(&[vec![left.clone()]], left.schema(), None)?,
+            TestMemoryExec::try_new_exec(&[vec![right.clone()]], right.schema(), None)?,
+            on,
+            JoinType::Inner,
+        )
+        .with_partition_mode(PartitionMode::Partitioned)
+        .with_fetch(Some(100))
+        .build()?;
+
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(64 * 1024, 1.0)
+            .build_arc()?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+        let batches = common::collect(join.execute(0, task_ctx)?).await?;
+
+        assert_eq!(num_rows(&batches), 100);
+        assert!(join.metrics().unwrap().spill_count().unwrap() > 0);
+        Ok(())
+    }
+
     fn build_table_struct(
         struct_name: &str,
         field_name_and_values: (&str, &Vec<Option<i32>>),
@@ -6410,6 +6771,68 @@ mod tests {
         Ok(())
     }
 
+    /// Builds the build side of `left`, joined on `b1`, pushing down a Bloom
+    /// filter
+    async fn collect_left_input_with_bloom_filter(
+        left: Arc<dyn ExecutionPlan>,
+        inlist_pushdown_max_size: usize,
+    ) -> Result<JoinLeftData> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let mut config = ConfigOptions::new();
+        config.optimizer.hash_join_bloom_filter_pushdown = true;
+        config.optimizer.hash_join_inlist_pushdown_max_size = inlist_pushdown_max_size;
+        let on_left = vec![Arc::new(Column::new_with_schema("b1", &left.schema())?) as _];
+        let reservation =
+            MemoryConsumer::new("HashJoinInput").register(task_ctx.memory_pool());
+
+        collect_left_input(
+            RandomState::with_seed(0),
+            left.execute(0, task_ctx)?,
+            on_left,
+            BuildProbeJoinMetrics::new(0, &ExecutionPlanMetricsSet::new()),
+            reservation,
+            false,
+            1,
+            true,
+            Arc::new(config),
+            NullEquality::NullEqualsNothing,
+            Count::new(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_probe_values_are_distinct() -> Result<()> {
+        // 10000 build rows with only 4 distinct keys are too large for an
+        // InList, but their distinct keys are not
+        let b1 = (0..10000).map(|i| i % 4).collect::<Vec<_>>();
+        let left = build_table(("a1", &b1), ("b1", &b1), ("c1", &b1));
+
+        let data = collect_left_input_with_bloom_filter(left, 1024).await?;
+
+        let PushdownStrategy::BloomFilter { probe_values, .. } = data.membership() else {
+            panic!("Expected a Bloom filter");
+        };
+        let probe_values = probe_values.as_ref().expect("Expected probe values");
+        assert_eq!(probe_values.len(), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_probe_values_max_size() -> Result<()> {
+        // The 500 distinct keys are few enough, but too large to push down
+        let b1 = (0..10000).map(|i| i % 500).collect::<Vec<_>>();
+        let left = build_table(("a1", &b1), ("b1", &b1), ("c1", &b1));
+
+        let data = collect_left_input_with_bloom_filter(left, 1024).await?;
+
+        let PushdownStrategy::BloomFilter { probe_values, .. } = data.membership() else {
+            panic!("Expected a Bloom filter");
+        };
+        assert!(probe_values.is_none());
+        Ok(())
+    }
+
     #[test]
     fn test_with_dynamic_filter_rejects_invalid_columns() -> Result<()> {
         let (_, _, on) = build_schema_and_on()?;
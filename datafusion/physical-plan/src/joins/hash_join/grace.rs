@@ -0,0 +1,561 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Grace hash join: spilling the build side of [`super::HashJoinExec`] to disk
+//!
+//! See [`GraceHashJoin`] for more details.
+
+use std::mem::size_of;
+use std::sync::Arc;
+
+use crate::joins::PartitionMode;
+use crate::joins::hash_join::exec::{
+    BuildSideState, JoinLeftData, finish_left_input,
+    should_collect_min_max_for_perfect_hash,
+};
+use crate::joins::hash_join::shared_bounds::SharedBuildAccumulator;
+use crate::joins::hash_join::stream::{
+    BuildSide, BuildSideInitialState, HashJoinStream, HashJoinStreamState,
+};
+use crate::joins::join_hash_map::JoinHashMapU32;
+use crate::joins::utils::{
+    BuildProbeJoinMetrics, ColumnIndex, JoinFilter, OnceFut, need_produce_result_in_final,
+};
+use crate::metrics::{Count, Gauge};
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::spill_manager::SpillManager;
+use crate::stream::{EmptyRecordBatchStream, RecordBatchStreamAdapter};
+use crate::{SendableRecordBatchStream, hash_utils::create_hashes};
+
+use arrow::array::UInt32Array;
+use arrow::compute::take_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::hash_utils::RandomState;
+use datafusion_common::utils::memory::estimate_memory_size;
+use datafusion_common::{DataFusionError, JoinType, NullEquality, Result};
+use datafusion_execution::TaskContext;
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_physical_expr::PhysicalExprRef;
+use datafusion_physical_expr_common::utils::evaluate_expressions_to_arrays;
+use futures::{StreamExt, TryStreamExt};
+
+/// Number of buckets the inputs are split into when the build side does not
+/// fit in memory
+const SPILL_FANOUT: usize = 16;
+
+/// Maximum number of times the inputs are split into buckets. Once reached,
+/// the join fails if the build side of a bucket does not fit in memory.
+const MAX_SPILL_DEPTH: usize = 4;
+
+/// Seed for assigning rows to buckets. It differs from the seeds of
+/// `RepartitionExec` and of the hash table, as rows of a partition (or of a
+/// bucket) would otherwise all end up in the same bucket.
+const SPILL_SEED: u64 = 5928374652384756911;
+
+/// Result of collecting the build side of a [`GraceHashJoin`]
+enum CollectedBuildSide {
+    /// The build side fits in memory
+    InMemory(JoinLeftData),
+    /// The build side was split into spilled buckets, indexed by the hash of
+    /// the join keys
+    Spilled(Vec<Option<RefCountedTempFile>>),
+}
+
+/// A pair of spilled buckets that remain to be joined
+struct SpilledBucket {
+    /// Build side rows of the bucket, `None` if there are none
+    build: Option<RefCountedTempFile>,
+    /// Probe side rows of the bucket, `None` if there are none
+    probe: Option<RefCountedTempFile>,
+    /// Number of times the inputs were split to produce this bucket
+    depth: usize,
+}
+
+/// Joins one partition of a [`super::HashJoinExec`] in
+/// [`PartitionMode::Partitioned`] mode, spilling to disk when the build side
+/// does not fit in memory.
+///
+/// The build side is first collected in memory as usual. If the memory
+/// reservation fails, the build side and then the probe side are hash
+/// partitioned on the join keys into [`SPILL_FANOUT`] buckets, which are
+/// written to disk. As rows with equal join keys are assigned to buckets with
+/// the same index, each pair of buckets is then joined on its own by a
+/// [`HashJoinStream`], one after the other.
+///
+/// A bucket whose build side still does not fit in memory is split again,
+/// using a different hash seed, up to [`MAX_SPILL_DEPTH`] times. This does not
+/// help if most rows have the same join key, in which case the join fails with
+/// the original resources error.
+///
+/// The joined buckets do not preserve the order of the probe side.
+pub(super) struct GraceHashJoin {
+    /// Output partition
+    partition: usize,
+    /// Output schema
+    schema: SchemaRef,
+    /// Join keys of the build side
+    on_left: Vec<PhysicalExprRef>,
+    /// Join keys of the probe side
+    on_right: Vec<PhysicalExprRef>,
+    /// Optional join filter
+    filter: Option<JoinFilter>,
+    /// Type of the join
+    join_type: JoinType,
+    /// Random state of the hash table
+    random_state: RandomState,
+    /// Columns of the output after the projection
+    column_indices: Vec<ColumnIndex>,
+    /// Defines the null equality for the join
+    null_equality: NullEquality,
+    /// Maximum number of rows in output batches
+    batch_size: usize,
+    /// Maximum number of rows to return
+    fetch: Option<usize>,
+    /// Session configuration
+    config: Arc<ConfigOptions>,
+    /// Task context, used to register the memory reservations of buckets
+    context: Arc<TaskContext>,
+    /// Metrics of the join
+    join_metrics: BuildProbeJoinMetrics,
+    /// Number of times an `ArrayMap` was created
+    array_map_created_count: Count,
+    /// Spills the buckets of the build side
+    build_spill_manager: SpillManager,
+    /// Spills the buckets of the probe side
+    probe_spill_manager: SpillManager,
+    /// Build side input, probe side input and memory reservation of the
+    /// partition, taken when the join starts
+    input: Option<(
+        SendableRecordBatchStream,
+        SendableRecordBatchStream,
+        MemoryReservation,
+    )>,
+    /// Whether the probe side input is ordered
+    right_side_ordered: bool,
+    /// Whether bounds for dynamic filter pushdown should be computed
+    should_compute_dynamic_filters: bool,
+    /// Accumulator for dynamic filter pushdown, taken once the build side has
+    /// been reported (or canceled if it was spilled)
+    build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+    /// Spilled buckets that remain to be joined, the next one is last
+    buckets: Vec<SpilledBucket>,
+}
+
+impl GraceHashJoin {
+    #[expect(clippy::too_many_arguments)]
+    pub(super) fn new(
+        partition: usize,
+        schema: SchemaRef,
+        on_left: Vec<PhysicalExprRef>,
+        on_right: Vec<PhysicalExprRef>,
+        filter: Option<JoinFilter>,
+        join_type: JoinType,
+        random_state: RandomState,
+        column_indices: Vec<ColumnIndex>,
+        null_equality: NullEquality,
+        fetch: Option<usize>,
+        context: Arc<TaskContext>,
+        join_metrics: BuildProbeJoinMetrics,
+        array_map_created_count: Count,
+        build_spill_manager: SpillManager,
+        probe_spill_manager: SpillManager,
+        left: SendableRecordBatchStream,
+        right: SendableRecordBatchStream,
+        reservation: MemoryReservation,
+        right_side_ordered: bool,
+        build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+        should_compute_dynamic_filters: bool,
+    ) -> Self {
+        Self {
+            partition,
+            schema,
+            on_left,
+            on_right,
+            filter,
+            join_type,
+            random_state,
+            column_indices,
+            null_equality,
+            batch_size: context.session_config().batch_size(),
+            fetch,
+            config: Arc::clone(context.session_config().options()),
+            context,
+            join_metrics,
+            array_map_created_count,
+            build_spill_manager,
+            probe_spill_manager,
+            input: Some((left, right, reservation)),
+            right_side_ordered,
+            should_compute_dynamic_filters,
+            build_accumulator,
+            buckets: vec![],
+        }
+    }
+
+    /// Returns a stream of the joined rows
+    pub(super) fn into_stream(self) -> SendableRecordBatchStream {
+        let schema = Arc::clone(&self.schema);
+        let stream = futures::stream::try_unfold(self, |mut join| async move {
+            let stream = join.next_stream().await?;
+            Ok::<_, DataFusionError>(stream.map(|stream| (stream, join)))
+        })
+        .try_flatten();
+        Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+    }
+
+    /// Returns a stream joining the inputs if the build side fits in memory,
+    /// or the next pair of spilled buckets, or `None` once all buckets have
+    /// been joined
+    async fn next_stream(&mut self) -> Result<Option<SendableRecordBatchStream>> {
+        loop {
+            // Earlier buckets may have produced enough rows already
+            let fetch = self.fetch.map(|fetch| {
+                fetch.saturating_sub(self.join_metrics.baseline.output_rows().value())
+            });
+            if fetch == Some(0) {
+                return Ok(None);
+            }
+
+            let (build, probe, reservation, depth) = match self.input.take() {
+                Some((build, probe, reservation)) => (build, probe, reservation, 0),
+                None => match self.buckets.pop() {
+                    Some(bucket) => (
+                        read_bucket(&self.build_spill_manager, bucket.build)?,
+                        read_bucket(&self.probe_spill_manager, bucket.probe)?,
+                        MemoryConsumer::new(format!("HashJoinInput[{}]", self.partition))
+                            .with_can_spill(true)
+                            .register(self.context.memory_pool()),
+                        bucket.depth,
+                    ),
+                    None => return Ok(None),
+                },
+            };
+
+            match self.collect_build_side(build, reservation, depth).await? {
+                CollectedBuildSide::InMemory(left_data) => {
+                    return Ok(Some(self.join_stream(left_data, probe, depth, fetch)));
+                }
+                CollectedBuildSide::Spilled(build_buckets) => {
+                    // A spilled partition does not contribute to the dynamic
+                    // filter, which then lets all its rows through
+                    if let Some(build_accumulator) = self.build_accumulator.take() {
+                        build_accumulator.report_canceled_partition(self.partition);
+                    }
+
+                    let probe_buckets = spill_buckets(
+                        vec![],
+                        Some(probe),
+                        &self.on_right,
+                        &self.probe_spill_manager,
+                        depth,
+                        (depth == 0).then_some((
+                            &self.join_metrics.input_batches,
+                            &self.join_metrics.input_rows,
+                        )),
+                    )
+                    .await?;
+
+                    // Push the buckets in reverse, so that they are joined in order
+                    for (build, probe) in
+                        build_buckets.into_iter().zip(probe_buckets).rev()
+                    {
+                        let skip = match (&build, &probe) {
+                            (None, None) => true,
+                            (None, Some(_)) => {
+                                self.join_type.empty_build_side_produces_empty_result()
+                            }
+                            (Some(_), None) => {
+                                !need_produce_result_in_final(self.join_type)
+                            }
+                            (Some(_), Some(_)) => false,
+                        };
+                        if !skip {
+                            self.buckets.push(SpilledBucket {
+                                build,
+                                probe,
+                                depth: depth + 1,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Collects the build side in memory, or splits it into spilled buckets
+    /// if it does not fit
+    async fn collect_build_side(
+        &mut self,
+        mut build: SendableRecordBatchStream,
+        reservation: MemoryReservation,
+        depth: usize,
+    ) -> Result<CollectedBuildSide> {
+        let schema = build.schema();
+        let should_compute_dynamic_filters =
+            depth == 0 && self.should_compute_dynamic_filters;
+        let should_collect_min_max_for_phj =
+            should_collect_min_max_for_perfect_hash(&self.on_left, &schema)?;
+        let metrics = self.metrics(depth);
+        let input_metrics = (depth == 0).then_some((
+            &self.join_metrics.build_input_batches,
+            &self.join_metrics.build_input_rows,
+        ));
+
+        let mut state = BuildSideState::try_new(
+            metrics.clone(),
+            reservation,
+            self.on_left.clone(),
+            &schema,
+            should_compute_dynamic_filters || should_collect_min_max_for_phj,
+        )?;
+
+        while let Some(batch) = build.next().await {
+            let batch = batch?;
+            match state.try_reserve(&batch) {
+                Ok(batch_size) => state.push(batch, batch_size)?,
+                Err(e) if can_spill(&e, depth) => {
+                    let mut batches = state.into_batches();
+                    batches.push(batch);
+                    let buckets = spill_buckets(
+                        batches,
+                        Some(build),
+                        &self.on_left,
+                        &self.build_spill_manager,
+                        depth,
+                        input_metrics,
+                    )
+                    .await?;
+                    return Ok(CollectedBuildSide::Spilled(buckets));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Make sure that the hash table fits in memory as well
+        let hash_table_size = estimate_memory_size::<(u32, u64)>(
+            state.num_rows(),
+            size_of::<JoinHashMapU32>(),
+        )?;
+        match state.reservation_mut().try_grow(hash_table_size) {
+            Ok(()) => state.reservation_mut().shrink(hash_table_size),
+            Err(e) if can_spill(&e, depth) => {
+                let buckets = spill_buckets(
+                    state.into_batches(),
+                    None,
+                    &self.on_left,
+                    &self.build_spill_manager,
+                    depth,
+                    input_metrics,
+                )
+                .await?;
+                return Ok(CollectedBuildSide::Spilled(buckets));
+            }
+            Err(e) => return Err(e),
+        }
+
+        let _timer = metrics.build_time.timer();
+        finish_left_input(
+            state,
+            &schema,
+            &self.random_state,
+            &self.on_left,
+            need_produce_result_in_final(self.join_type),
+            1,
+            should_compute_dynamic_filters,
+            &self.config,
+            self.null_equality,
+            &self.array_map_created_count,
+        )
+        .map(CollectedBuildSide::InMemory)
+    }
+
+    /// Returns a stream joining `probe` with the collected build side
+    fn join_stream(
+        &mut self,
+        left_data: JoinLeftData,
+        probe: SendableRecordBatchStream,
+        depth: usize,
+        fetch: Option<usize>,
+    ) -> SendableRecordBatchStream {
+        let (build_accumulator, right_side_ordered) = if depth == 0 {
+            (self.build_accumulator.take(), self.right_side_ordered)
+        } else {
+            (None, false)
+        };
+
+        Box::pin(HashJoinStream::new(
+            self.partition,
+            Arc::clone(&self.schema),
+            self.on_right.clone(),
+            self.filter.clone(),
+            self.join_type,
+            probe,
+            self.random_state.clone(),
+            self.metrics(depth),
+            self.column_indices.clone(),
+            self.null_equality,
+            HashJoinStreamState::WaitBuildSide,
+            BuildSide::Initial(BuildSideInitialState {
+                left_fut: OnceFut::new(async move { Ok(left_data) }),
+            }),
+            self.batch_size,
+            vec![],
+            right_side_ordered,
+            build_accumulator,
+            PartitionMode::Partitioned,
+            false,
+            fetch,
+        ))
+    }
+
+    /// Returns the metrics for joining the inputs split `depth` times.
+    ///
+    /// The input rows of spilled buckets were already counted while splitting
+    /// the inputs of the partition, so they are not counted again.
+    fn metrics(&self, depth: usize) -> BuildProbeJoinMetrics {
+        let mut metrics = self.join_metrics.clone();
+        if depth > 0 {
+            metrics.build_input_batches = Count::new();
+            metrics.build_input_rows = Count::new();
+            metrics.build_mem_used = Gauge::new();
+            metrics.input_batches = Count::new();
+            metrics.input_rows = Count::new();
+        }
+        metrics
+    }
+}
+
+impl Drop for GraceHashJoin {
+    fn drop(&mut self) {
+        // Do not leave the other partitions waiting for the build side of
+        // this one if the join is stopped before it was collected
+        if let Some(build_accumulator) = self.build_accumulator.take() {
+            build_accumulator.report_canceled_partition(self.partition);
+        }
+    }
+}
+
+/// Returns a stream reading a spilled bucket
+fn read_bucket(
+    spill_manager: &SpillManager,
+    file: Option<RefCountedTempFile>,
+) -> Result<SendableRecordBatchStream> {
+    match file {
+        Some(file) => spill_manager.read_spill_as_stream(file, None),
+        None => Ok(Box::pin(EmptyRecordBatchStream::new(Arc::clone(
+            spill_manager.schema(),
+        )))),
+    }
+}
+
+/// Returns true if the build side can be spilled after failing with `error`
+fn can_spill(error: &DataFusionError, depth: usize) -> bool {
+    depth < MAX_SPILL_DEPTH
+        && matches!(error.find_root(), DataFusionError::ResourcesExhausted(_))
+}
+
+/// Splits `batches` followed by the batches of `stream` into [`SPILL_FANOUT`]
+/// spill files by the hash of the join keys `on`, returning the spill file
+/// of each bucket.
+///
+/// If `input_metrics` is set, the batches and rows of `stream` are counted.
+async fn spill_buckets(
+    batches: Vec<RecordBatch>,
+    stream: Option<SendableRecordBatchStream>,
+    on: &[PhysicalExprRef],
+    spill_manager: &SpillManager,
+    depth: usize,
+    input_metrics: Option<(&Count, &Count)>,
+) -> Result<Vec<Option<RefCountedTempFile>>> {
+    let random_state = RandomState::with_seed(SPILL_SEED.wrapping_add(depth as u64));
+    let mut hashes_buffer = vec![];
+    let mut files = (0..SPILL_FANOUT).map(|_| None).collect::<Vec<_>>();
+
+    for batch in batches {
+        spill_batch(
+            &batch,
+            on,
+            &random_state,
+            &mut hashes_buffer,
+            &mut files,
+            spill_manager,
+        )?;
+    }
+
+    if let Some(mut stream) = stream {
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            if let Some((input_batches, input_rows)) = input_metrics {
+                input_batches.add(1);
+                input_rows.add(batch.num_rows());
+            }
+            spill_batch(
+                &batch,
+                on,
+                &random_state,
+                &mut hashes_buffer,
+                &mut files,
+                spill_manager,
+            )?;
+        }
+    }
+
+    files
+        .into_iter()
+        .map(|file| match file {
+            Some(mut file) => file.finish(),
+            None => Ok(None),
+        })
+        .collect()
+}
+
+/// Appends the rows of `batch` to the spill files of their buckets
+fn spill_batch(
+    batch: &RecordBatch,
+    on: &[PhysicalExprRef],
+    random_state: &RandomState,
+    hashes_buffer: &mut Vec<u64>,
+    files: &mut [Option<InProgressSpillFile>],
+    spill_manager: &SpillManager,
+) -> Result<()> {
+    let keys = evaluate_expressions_to_arrays(on, batch)?;
+    hashes_buffer.clear();
+    hashes_buffer.resize(batch.num_rows(), 0);
+    create_hashes(&keys, random_state, hashes_buffer)?;
+
+    let mut indices = vec![vec![]; files.len()];
+    for (row, hash) in hashes_buffer.iter().enumerate() {
+        indices[(*hash % files.len() as u64) as usize].push(row as u32);
+    }
+
+    for (file, indices) in files.iter_mut().zip(indices) {
+        if indices.is_empty() {
+            continue;
+        }
+        let bucket = take_record_batch(batch, &UInt32Array::from(indices))?;
+        if file.is_none() {
+            *file = Some(spill_manager.create_in_progress_file("HashJoin spill")?);
+        }
+        if let Some(file) = file {
+            file.append_batch(&bucket)?;
+        }
+    }
+    Ok(())
+}
@@ -19,10 +19,12 @@
 
 use std::sync::Arc;
 
-use arrow::array::{ArrayRef, StructArray};
+use arrow::array::{ArrayRef, StructArray, UInt32Array};
+use arrow::compute::take;
 use arrow::datatypes::{Field, FieldRef, Fields};
+use arrow::row::{RowConverter, SortField};
 use arrow_schema::DataType;
-use datafusion_common::Result;
+use datafusion_common::{HashSet, Result};
 
 pub(super) fn build_struct_fields(data_types: &[DataType]) -> Result<Fields> {
     data_types
@@ -77,6 +79,23 @@ pub(super) fn build_struct_inlist_values(
     Ok(Some(source_array))
 }
 
+/// Returns the distinct values of `array`, in the order of their first
+/// occurrence.
+///
+/// Unlike the values of [`build_struct_inlist_values`], which are
+/// deduplicated by `InListExpr`, this is used for values that are pushed down
+/// as they are, e.g. for scans to probe their Bloom filters with.
+pub(super) fn distinct_values(array: &ArrayRef) -> Result<ArrayRef> {
+    let converter = RowConverter::new(vec![SortField::new(array.data_type().clone())])?;
+    let rows = converter.convert_columns(std::slice::from_ref(array))?;
+    let mut seen = HashSet::with_capacity(rows.num_rows());
+    let indices = (0..rows.num_rows())
+        .filter(|&i| seen.insert(rows.row(i)))
+        .map(|i| i as u32)
+        .collect::<UInt32Array>();
+    Ok(take(array.as_ref(), &indices, None)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -155,4 +174,21 @@ mod tests {
         assert_eq!(result.len(), 3);
         assert_eq!(result.data_type(), dict_array.data_type());
     }
+
+    #[test]
+    fn test_distinct_values() {
+        let array = Arc::new(StringArray::from(vec![
+            Some("b"),
+            None,
+            Some("a"),
+            Some("b"),
+            None,
+        ])) as ArrayRef;
+
+        let result = distinct_values(&array).unwrap();
+
+        let expected =
+            Arc::new(StringArray::from(vec![Some("b"), None, Some("a")])) as ArrayRef;
+        assert!(expected.eq(&result));
+    }
 }
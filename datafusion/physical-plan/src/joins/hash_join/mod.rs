@@ -17,10 +17,13 @@
 
 //! [`HashJoinExec`] Partitioned Hash Join Operator
 
+pub use bloom_filter::{BloomFilterExpr, JoinBloomFilter};
 pub use exec::{HashJoinExec, HashJoinExecBuilder};
 pub use partitioned_hash_eval::{HashExpr, HashTableLookupExpr, SeededRandomState};
 
+mod bloom_filter;
 mod exec;
+mod grace;
 mod inlist_builder;
 mod partitioned_hash_eval;
 mod shared_bounds;
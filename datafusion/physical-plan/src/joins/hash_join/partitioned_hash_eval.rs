@@ -423,7 +423,7 @@ impl PhysicalExpr for HashTableLookupExpr {
     }
 }
 
-fn evaluate_columns(
+pub(super) fn evaluate_columns(
     columns: &[PhysicalExprRef],
     batch: &RecordBatch,
 ) -> Result<Vec<ArrayRef>> {
@@ -25,6 +25,7 @@ use crate::ExecutionPlan;
 use crate::ExecutionPlanProperties;
 use crate::joins::Map;
 use crate::joins::PartitionMode;
+use crate::joins::hash_join::bloom_filter::{BloomFilterExpr, JoinBloomFilter};
 use crate::joins::hash_join::exec::HASH_JOIN_SEED;
 use crate::joins::hash_join::inlist_builder::build_struct_fields;
 use crate::joins::hash_join::partitioned_hash_eval::{
@@ -39,7 +40,9 @@ use datafusion_functions::core::r#struct as struct_func;
 use datafusion_physical_expr::expressions::{
     BinaryExpr, CaseExpr, DynamicFilterPhysicalExpr, InListExpr, lit,
 };
-use datafusion_physical_expr::{PhysicalExpr, PhysicalExprRef, ScalarFunctionExpr};
+use datafusion_physical_expr::{
+    Partitioning, PhysicalExpr, PhysicalExprRef, ScalarFunctionExpr,
+};
 
 use parking_lot::Mutex;
 use tokio::sync::Notify;
@@ -82,7 +85,7 @@ impl PartitionBounds {
 /// Creates a membership predicate for filter pushdown.
 ///
 /// If `inlist_values` is provided (for small build sides), creates an InList expression.
-/// Otherwise, creates a HashTableLookup expression (for large build sides).
+/// Otherwise, creates a BloomFilter or HashTableLookup expression (for large build sides).
 ///
 /// Supports both single-column and multi-column joins using struct expressions.
 fn create_membership_predicate(
@@ -135,6 +138,35 @@ fn create_membership_predicate(
             hash_map,
             "hash_lookup".to_string(),
         )) as Arc<dyn PhysicalExpr>)),
+        // Use a Bloom filter of the join keys for large build sides, if enabled
+        PushdownStrategy::BloomFilter {
+            filter,
+            probe_values,
+        } => {
+            let bloom_filter = Arc::new(BloomFilterExpr::new(
+                on_right.to_vec(),
+                random_state.clone(),
+                filter,
+                "bloom_filter".to_string(),
+            )) as Arc<dyn PhysicalExpr>;
+            let Some(probe_values) = probe_values else {
+                return Ok(Some(bloom_filter));
+            };
+            // The IN list lets scans probe their own Bloom filters with the
+            // build side keys, and is only evaluated for the rows passing the
+            // Bloom filter of the join
+            let in_list = Arc::new(InListExpr::try_new_from_array(
+                Arc::clone(&on_right[0]),
+                probe_values,
+                false,
+                schema,
+            )?) as Arc<dyn PhysicalExpr>;
+            Ok(Some(Arc::new(BinaryExpr::new(
+                bloom_filter,
+                Operator::And,
+                in_list,
+            ))))
+        }
         // Empty partition - should not create a filter for this
         PushdownStrategy::Empty => Ok(None),
     }
@@ -253,6 +285,11 @@ pub(crate) struct SharedBuildAccumulator {
     /// Random state for partitioning (RepartitionExec's hash function with 0,0,0,0 seeds)
     /// Used for PartitionedHashLookupPhysicalExpr
     repartition_random_state: SeededRandomState,
+    /// Whether the build partitions were hash partitioned with
+    /// `repartition_random_state`, so probe rows can be routed to the filter of
+    /// their partition. Bucketed build sides are not, and their per-partition
+    /// filters are combined with OR instead.
+    route_by_hash: bool,
     /// Schema of the probe (right) side for evaluating filter expressions
     probe_schema: Arc<Schema>,
 }
@@ -264,6 +301,14 @@ pub(crate) enum PushdownStrategy {
     InList(ArrayRef),
     /// Use map lookup for large build sides
     Map(Arc<Map>),
+    /// Use a Bloom filter of the join keys for large build sides, when
+    /// `hash_join_bloom_filter_pushdown` is enabled, along with the InList
+    /// values of a single join key with at most
+    /// `hash_join_bloom_filter_max_probe_values` distinct values
+    BloomFilter {
+        filter: Arc<JoinBloomFilter>,
+        probe_values: Option<ArrayRef>,
+    },
     /// There was no data in this partition, do not build a dynamic filter for it
     Empty,
 }
@@ -403,6 +448,10 @@ impl SharedBuildAccumulator {
             dynamic_filter,
             on_right,
             repartition_random_state,
+            route_by_hash: !matches!(
+                left_child.output_partitioning(),
+                Partitioning::Bucket(..)
+            ),
             probe_schema: right_child.schema(),
         }
     }
@@ -650,7 +699,19 @@ impl SharedBuildAccumulator {
                     }
                 }
 
-                let filter_expr = if has_canceled_unknown {
+                let filter_expr = if !self.route_by_hash {
+                    if has_canceled_unknown {
+                        lit(true)
+                    } else {
+                        real_branches
+                            .into_iter()
+                            .map(|(_, then_expr)| then_expr)
+                            .reduce(|acc, expr| {
+                                Arc::new(BinaryExpr::new(acc, Operator::Or, expr)) as _
+                            })
+                            .unwrap_or_else(|| lit(false))
+                    }
+                } else if has_canceled_unknown {
                     let mut when_then_branches = empty_partition_ids
                         .into_iter()
                         .map(|partition_id| {
@@ -721,6 +782,7 @@ pub(super) fn make_partitioned_accumulator_for_test(
         dynamic_filter,
         on_right: vec![],
         repartition_random_state: SeededRandomState::with_seed(1),
+        route_by_hash: true,
         probe_schema,
     }
 }
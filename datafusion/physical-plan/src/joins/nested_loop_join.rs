@@ -62,8 +62,8 @@ use arrow_schema::DataType;
 use datafusion_common::cast::as_boolean_array;
 use datafusion_common::{
     JoinSide, NullEquality, Result, ScalarValue, Statistics, arrow_err,
-    assert_eq_or_internal_err, internal_datafusion_err, internal_err, project_schema,
-    unwrap_or_internal_err,
+    assert_eq_or_internal_err, internal_datafusion_err, internal_err, plan_err,
+    project_schema, unwrap_or_internal_err,
 };
 use datafusion_execution::TaskContext;
 use datafusion_execution::disk_manager::RefCountedTempFile;
@@ -282,6 +282,9 @@ impl NestedLoopJoinExecBuilder {
         let left_schema = left.schema();
         let right_schema = right.schema();
         check_join_is_valid(&left_schema, &right_schema, &[])?;
+        if join_type == JoinType::AsOf {
+            return plan_err!("NestedLoopJoinExec does not support as-of joins");
+        }
         let (join_schema, column_indices) =
             build_join_schema(&left_schema, &right_schema, &join_type);
         let join_schema = Arc::new(join_schema);
@@ -399,7 +402,8 @@ impl NestedLoopJoinExec {
                 JoinType::Left
                 | JoinType::LeftAnti
                 | JoinType::LeftMark
-                | JoinType::Full => EmissionType::Both,
+                | JoinType::Full
+                | JoinType::AsOf => EmissionType::Both,
             }
         } else {
             right.pipeline_behavior()
@@ -457,7 +461,7 @@ impl NestedLoopJoinExec {
             Arc::clone(right),
             Arc::clone(left),
             self.filter().map(JoinFilter::swap),
-            &self.join_type().swap(),
+            &self.join_type().try_swap()?,
             swap_join_projection(
                 left.schema().fields().len(),
                 right.schema().fields().len(),
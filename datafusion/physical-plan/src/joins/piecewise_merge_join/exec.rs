@@ -300,6 +300,9 @@ impl PiecewiseMergeJoinExec {
                 "Existence Joins are currently not supported for PiecewiseMergeJoin"
             );
         }
+        if join_type == JoinType::AsOf {
+            return not_impl_err!("As-of joins are not supported for PiecewiseMergeJoin");
+        }
 
         // Take the operator and enforce a sort order on the streamed + buffered side based on
         // the operator type.
@@ -412,7 +415,8 @@ impl PiecewiseMergeJoinExec {
             JoinType::Left
             | JoinType::LeftAnti
             | JoinType::LeftSemi
-            | JoinType::LeftMark => JoinSide::Left,
+            | JoinType::LeftMark
+            | JoinType::AsOf => JoinSide::Left,
         }
     }
 
@@ -149,6 +149,9 @@ impl SortMergeJoinExec {
         let right_schema = right.schema();
 
         check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if join_type == JoinType::AsOf {
+            return plan_err!("SortMergeJoinExec does not support as-of joins");
+        }
         if sort_options.len() != on.len() {
             return plan_err!(
                 "Expected number of sort options: {}, actual: {}",
@@ -219,7 +222,8 @@ impl SortMergeJoinExec {
             | JoinType::Full
             | JoinType::LeftAnti
             | JoinType::LeftSemi
-            | JoinType::LeftMark => JoinSide::Left,
+            | JoinType::LeftMark
+            | JoinType::AsOf => JoinSide::Left,
         }
     }
 
@@ -322,7 +326,7 @@ impl SortMergeJoinExec {
                 .map(|(l, r)| (Arc::clone(r), Arc::clone(l)))
                 .collect::<Vec<_>>(),
             self.filter().as_ref().map(JoinFilter::swap),
-            self.join_type().swap(),
+            self.join_type().try_swap()?,
             self.sort_options.clone(),
             self.null_equality,
         )?;
@@ -32,7 +32,7 @@ use arrow::array::{
 use arrow::compute::kernels::zip::zip;
 use arrow::compute::{self, filter_record_batch};
 use arrow::datatypes::SchemaRef;
-use datafusion_common::{JoinSide, JoinType, Result};
+use datafusion_common::{JoinSide, JoinType, Result, internal_err};
 
 use crate::joins::utils::JoinFilter;
 
@@ -248,13 +248,13 @@ pub fn get_corrected_filter_mask(
     batch_ids: &[usize],
     filter_mask: &BooleanArray,
     expected_size: usize,
-) -> Option<BooleanArray> {
+) -> Result<Option<BooleanArray>> {
     let row_indices_length = row_indices.len();
     let mut corrected_mask: BooleanBuilder =
         BooleanBuilder::with_capacity(row_indices_length);
     let mut seen_true = false;
 
-    match join_type {
+    Ok(match join_type {
         JoinType::Left | JoinType::Right | JoinType::Full => {
             // For each input row group: keep first filter-passing row,
             // discard (null) remaining matches, null-join if none passed.
@@ -291,7 +291,10 @@ pub fn get_corrected_filter_mask(
             unreachable!("Semi/anti/mark joins are handled by BitwiseSortMergeJoinStream")
         }
         JoinType::Inner => None,
-    }
+        JoinType::AsOf => {
+            return internal_err!("As-of joins are handled by AsOfJoinExec");
+        }
+    })
 }
 
 /// Applies corrected filter mask to record batch based on join type
@@ -384,5 +387,6 @@ pub fn filter_record_batch_by_join_type(
             "Semi/anti/mark joins are handled by SemiAntiMarkSortMergeJoinStream"
         ),
         JoinType::Inner => Ok(filter_record_batch(record_batch, corrected_mask)?),
+        JoinType::AsOf => internal_err!("As-of joins are handled by AsOfJoinExec"),
     }
 }
@@ -1715,7 +1715,7 @@ impl MaterializingSortMergeJoinStream {
             batch_ids,
             &out_mask,
             record_batch.num_rows(),
-        );
+        )?;
 
         let corrected_mask = if let Some(ref filtered_join_mask) = maybe_corrected_mask {
             filtered_join_mask
@@ -3173,7 +3173,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
             &[0usize],
             &BooleanArray::from(vec![true]),
             output.num_rows()
-        )
+        )?
         .unwrap(),
         BooleanArray::from(vec![true, false, false, false, false, false, false, false])
     );
@@ -3185,7 +3185,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
             &[0usize],
             &BooleanArray::from(vec![false]),
             output.num_rows()
-        )
+        )?
         .unwrap(),
         BooleanArray::from(vec![false, false, false, false, false, false, false, false])
     );
@@ -3197,7 +3197,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
             &[0usize; 2],
             &BooleanArray::from(vec![true, true]),
             output.num_rows()
-        )
+        )?
         .unwrap(),
         BooleanArray::from(vec![true, true, false, false, false, false, false, false])
     );
@@ -3209,7 +3209,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
             &[0usize; 3],
             &BooleanArray::from(vec![true, true, true]),
             output.num_rows()
-        )
+        )?
         .unwrap(),
         BooleanArray::from(vec![true, true, true, false, false, false, false, false])
     );
@@ -3221,7 +3221,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
             &[0usize; 3],
             &BooleanArray::from(vec![true, false, true]),
             output.num_rows()
-        )
+        )?
         .unwrap(),
         BooleanArray::from(vec![
             Some(true),
@@ -3242,7 +3242,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
             &[0usize; 3],
             &BooleanArray::from(vec![false, false, true]),
             output.num_rows()
-        )
+        )?
         .unwrap(),
         BooleanArray::from(vec![
             None,
@@ -3263,7 +3263,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
             &[0usize; 3],
             &BooleanArray::from(vec![false, true, true]),
             output.num_rows()
-        )
+        )?
         .unwrap(),
         BooleanArray::from(vec![
             None,
@@ -3284,7 +3284,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
             &[0usize; 3],
             &BooleanArray::from(vec![false, false, false]),
             output.num_rows()
-        )
+        )?
         .unwrap(),
         BooleanArray::from(vec![
             None,
@@ -3304,7 +3304,7 @@ async fn test_left_outer_join_filtered_mask() -> Result<()> {
         &joined_batches.filter_metadata.batch_ids,
         &out_mask,
         output.num_rows(),
-    )
+    )?
     .unwrap();
 
     assert_eq!(
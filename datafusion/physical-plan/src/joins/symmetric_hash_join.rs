@@ -231,6 +231,9 @@ impl SymmetricHashJoinExec {
 
         // Check if the join is valid with the given on constraints:
         check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if *join_type == JoinType::AsOf {
+            return plan_err!("SymmetricHashJoinExec does not support as-of joins");
+        }
 
         // Build the join schema from the left and right schemas:
         let (schema, column_indices) =
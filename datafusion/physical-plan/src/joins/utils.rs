@@ -68,7 +68,7 @@ use datafusion_common::stats::Precision;
 use datafusion_common::utils::normalize_float_zero;
 use datafusion_common::{
     DataFusionError, JoinSide, JoinType, NullEquality, Result, SharedResult,
-    not_impl_err, plan_err,
+    internal_err, not_impl_err, plan_err,
 };
 use datafusion_expr::Operator;
 use datafusion_expr::interval_arithmetic::Interval;
@@ -137,13 +137,18 @@ pub fn adjust_right_output_partitioning(
     right_partitioning: &Partitioning,
     left_columns_len: usize,
 ) -> Result<Partitioning> {
+    let offset_exprs = |exprs: &[Arc<dyn PhysicalExpr>]| {
+        exprs
+            .iter()
+            .map(|expr| add_offset_to_expr(Arc::clone(expr), left_columns_len as _))
+            .collect::<Result<Vec<_>>>()
+    };
     let result = match right_partitioning {
         Partitioning::Hash(exprs, size) => {
-            let new_exprs = exprs
-                .iter()
-                .map(|expr| add_offset_to_expr(Arc::clone(expr), left_columns_len as _))
-                .collect::<Result<_>>()?;
-            Partitioning::Hash(new_exprs, *size)
+            Partitioning::Hash(offset_exprs(exprs)?, *size)
+        }
+        Partitioning::Bucket(exprs, size) => {
+            Partitioning::Bucket(offset_exprs(exprs)?, *size)
         }
         Partitioning::Range(_) => {
             // Range partitioning optimizer propagation is tracked in
@@ -239,6 +244,7 @@ fn output_join_field(old_field: &Field, join_type: &JoinType, is_left: bool) ->
     let force_nullable = match join_type {
         JoinType::Inner => false,
         JoinType::Left => !is_left, // right input is padded with nulls
+        JoinType::AsOf => !is_left, // right input is padded with nulls
         JoinType::Right => is_left, // left input is padded with nulls
         JoinType::Full => true,     // both inputs can be padded with nulls
         JoinType::LeftSemi => false, // doesn't introduce nulls
@@ -297,7 +303,11 @@ pub fn build_join_schema(
     };
 
     let (fields, column_indices): (SchemaBuilder, Vec<ColumnIndex>) = match join_type {
-        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::Full
+        | JoinType::Right
+        | JoinType::AsOf => {
             // left then right
             left_fields().chain(right_fields()).unzip()
         }
@@ -659,6 +669,25 @@ fn estimate_join_cardinality(
                 column_statistics,
             })
         }
+        // Every left row is emitted exactly once, joined with at most one right row
+        JoinType::AsOf => {
+            let num_rows = *left_stats.num_rows.get_value()?;
+            let column_statistics = left_stats
+                .column_statistics
+                .into_iter()
+                .chain(
+                    right_stats
+                        .column_statistics
+                        .into_iter()
+                        .map(|stats| stats.to_inexact()),
+                )
+                .collect();
+            Some(PartialJoinStatistics {
+                num_rows,
+                total_byte_size: Precision::Absent,
+                column_statistics,
+            })
+        }
     }
 }
 
@@ -1432,6 +1461,9 @@ pub(crate) fn adjust_indices_by_join_type(
             Ok((left_indices, right_indices))
             // unmatched left row will be produced in the end of loop, and it has been set in the left visited bitmap
         }
+        JoinType::AsOf => {
+            internal_err!("As-of joins are only supported by AsOfJoinExec")
+        }
         JoinType::Right => {
             // combine the matched and unmatched right result together
             append_right_indices(
@@ -1887,9 +1919,11 @@ pub(crate) fn symmetric_join_output_partitioning(
     let left_partitioning = left.output_partitioning();
     let right_partitioning = right.output_partitioning();
     let result = match join_type {
-        JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-            left_partitioning.clone()
-        }
+        JoinType::Left
+        | JoinType::LeftSemi
+        | JoinType::LeftAnti
+        | JoinType::LeftMark
+        | JoinType::AsOf => left_partitioning.clone(),
         JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
             right_partitioning.clone()
         }
@@ -1921,7 +1955,8 @@ pub(crate) fn asymmetric_join_output_partitioning(
         | JoinType::LeftSemi
         | JoinType::LeftAnti
         | JoinType::Full
-        | JoinType::LeftMark => Partitioning::UnknownPartitioning(
+        | JoinType::LeftMark
+        | JoinType::AsOf => Partitioning::UnknownPartitioning(
             right.output_partitioning().partition_count(),
         ),
     };
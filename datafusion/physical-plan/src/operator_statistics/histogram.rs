@@ -0,0 +1,1043 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Histograms of the values of columns, see [`ColumnHistogram`].
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::compute::{SortOptions, concat_batches, sort};
+use arrow::datatypes::DataType;
+use datafusion_common::stats::Precision;
+use datafusion_common::{Result, ScalarValue, Statistics};
+use datafusion_execution::TaskContext;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::expressions::{
+    BinaryExpr, Column, InListExpr, IsNotNullExpr, IsNullExpr, Literal, NotExpr,
+};
+use futures::StreamExt;
+
+use super::ExtendedStatistics;
+use crate::{ExecutionPlan, execute_stream};
+
+/// Options controlling the size of the histograms built by
+/// [`ColumnHistogram::try_from_array`] and [`sample_histograms`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistogramOptions {
+    /// Maximum number of buckets of a histogram
+    pub num_buckets: usize,
+    /// Maximum number of most common values kept outside of the buckets
+    pub num_most_common_values: usize,
+    /// Maximum number of rows read by [`sample_histograms`]
+    pub sample_size: usize,
+}
+
+impl Default for HistogramOptions {
+    fn default() -> Self {
+        Self {
+            num_buckets: 100,
+            num_most_common_values: 100,
+            sample_size: 30_000,
+        }
+    }
+}
+
+/// A bucket of a [`ColumnHistogram`]: the non-null values in `[lower, upper]`
+/// that are not most common values.
+///
+/// Values are assumed to be uniformly distributed within a bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramBucket {
+    /// Smallest value of the bucket
+    pub lower: ScalarValue,
+    /// Largest value of the bucket
+    pub upper: ScalarValue,
+    /// Number of rows with a value in the bucket
+    pub count: f64,
+    /// Number of distinct values in the bucket
+    pub distinct_count: f64,
+}
+
+impl HistogramBucket {
+    /// Returns true if `value` is within the bounds of the bucket
+    fn contains(&self, value: &ScalarValue) -> bool {
+        matches!(
+            self.lower.partial_cmp(value),
+            Some(Ordering::Less | Ordering::Equal)
+        ) && matches!(
+            self.upper.partial_cmp(value),
+            Some(Ordering::Greater | Ordering::Equal)
+        )
+    }
+
+    /// Estimated number of rows of the bucket equal to `value`
+    fn rows_eq(&self, value: &ScalarValue) -> f64 {
+        if self.contains(value) {
+            self.count / self.distinct_count.max(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Estimated number of rows of the bucket less than `value`
+    fn rows_lt(&self, value: &ScalarValue) -> f64 {
+        match (self.lower.partial_cmp(value), self.upper.partial_cmp(value)) {
+            (Some(Ordering::Greater | Ordering::Equal), _) => 0.0,
+            (_, Some(Ordering::Less)) => self.count,
+            (Some(Ordering::Less), Some(_)) => {
+                let fraction =
+                    interpolate(&self.lower, &self.upper, value).unwrap_or(0.5);
+                // The distinct values are spread over the range of the bucket:
+                // the smallest one is below any value greater than `lower`
+                // and the largest one is not below `upper`
+                let distinct = self.distinct_count.max(1.0);
+                let below = if distinct >= 2.0 {
+                    (1.0 + fraction * (distinct - 2.0)) / distinct
+                } else {
+                    fraction
+                };
+                self.count * below
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Fraction of the values of the bucket within `[lower, upper]`, a range
+    /// within the bounds of the bucket
+    fn overlap(&self, lower: &ScalarValue, upper: &ScalarValue) -> f64 {
+        let distinct = self.distinct_count.max(1.0);
+        if self.lower == self.upper {
+            return 1.0;
+        }
+        match (
+            interpolate(&self.lower, &self.upper, lower),
+            interpolate(&self.lower, &self.upper, upper),
+        ) {
+            (Some(from), Some(to)) => (to - from).clamp(1.0 / distinct, 1.0),
+            _ if *lower == self.lower && *upper == self.upper => 1.0,
+            _ => 0.5,
+        }
+    }
+
+    /// Extends the bucket with the values of `other`, the next bucket of the
+    /// histogram
+    fn absorb(&mut self, other: &Self) {
+        if other.upper.partial_cmp(&self.upper) == Some(Ordering::Greater) {
+            self.upper = other.upper.clone();
+        }
+        self.count += other.count;
+        self.distinct_count += other.distinct_count;
+    }
+
+    /// Estimated number of rows of the equi-join of the values of two buckets
+    fn join_rows(&self, other: &Self) -> f64 {
+        let lower = match self.lower.partial_cmp(&other.lower) {
+            Some(Ordering::Less) => &other.lower,
+            Some(_) => &self.lower,
+            None => return 0.0,
+        };
+        let upper = match self.upper.partial_cmp(&other.upper) {
+            Some(Ordering::Greater) => &other.upper,
+            Some(_) => &self.upper,
+            None => return 0.0,
+        };
+        if !matches!(
+            lower.partial_cmp(upper),
+            Some(Ordering::Less | Ordering::Equal)
+        ) {
+            return 0.0;
+        }
+
+        let left = self.overlap(lower, upper);
+        let right = other.overlap(lower, upper);
+        let left_distinct = (self.distinct_count * left).max(1.0);
+        let right_distinct = (other.distinct_count * right).max(1.0);
+        self.count * left * other.count * right / left_distinct.max(right_distinct)
+    }
+}
+
+/// Equi-depth histogram and most common values of a column.
+///
+/// The most common values are counted individually, and the remaining
+/// non-null values are split into [`HistogramBucket`]s holding about the same
+/// number of rows. Unlike the minimum, maximum and number of distinct values
+/// of [`ColumnStatistics`], this describes skewed distributions, allowing
+/// accurate estimates of the selectivity of predicates such as
+/// `status = 500` when most rows have the same value.
+///
+/// Counts are floating point numbers as they are usually estimates, e.g.
+/// after scaling the histogram of a sample to the size of its population.
+///
+/// [`ColumnStatistics`]: datafusion_common::ColumnStatistics
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColumnHistogram {
+    /// Buckets of the values that are not most common values, ordered by
+    /// their lower bound. Buckets may overlap.
+    buckets: Vec<HistogramBucket>,
+    /// The most common values, with their number of rows
+    most_common_values: Vec<(ScalarValue, f64)>,
+    /// Number of null rows
+    null_count: f64,
+}
+
+impl ColumnHistogram {
+    /// Create a new histogram from its buckets, most common values and number
+    /// of nulls
+    pub fn new(
+        mut buckets: Vec<HistogramBucket>,
+        most_common_values: Vec<(ScalarValue, f64)>,
+        null_count: f64,
+    ) -> Self {
+        buckets.sort_by(|a, b| a.lower.partial_cmp(&b.lower).unwrap_or(Ordering::Equal));
+        Self {
+            buckets,
+            most_common_values,
+            null_count,
+        }
+    }
+
+    /// Build the histogram of the values of `array`.
+    ///
+    /// Values more frequent than average are kept as most common values, or
+    /// all the values if there are no more than
+    /// [`HistogramOptions::num_most_common_values`] distinct values.
+    pub fn try_from_array(array: &ArrayRef, options: &HistogramOptions) -> Result<Self> {
+        let null_count = array.null_count();
+        let sorted = sort(
+            array,
+            Some(SortOptions {
+                descending: false,
+                nulls_first: false,
+            }),
+        )?;
+
+        // Count the rows of each distinct value
+        let mut runs: Vec<(ScalarValue, usize)> = vec![];
+        for index in 0..sorted.len() - null_count {
+            let value = ScalarValue::try_from_array(&sorted, index)?;
+            match runs.last_mut() {
+                Some((last, count)) if *last == value => *count += 1,
+                _ => runs.push((value, 1)),
+            }
+        }
+
+        let num_values = sorted.len() - null_count;
+        let average = num_values as f64 / runs.len().max(1) as f64;
+        let keep_all = runs.len() <= options.num_most_common_values;
+        let mut by_count = (0..runs.len()).collect::<Vec<_>>();
+        by_count.sort_by(|&a, &b| runs[b].1.cmp(&runs[a].1));
+        let mut is_common = vec![false; runs.len()];
+        for &index in by_count.iter().take(options.num_most_common_values) {
+            let count = runs[index].1;
+            is_common[index] = keep_all || (count > 1 && count as f64 > average);
+        }
+
+        let mut most_common_values = vec![];
+        let mut rest = vec![];
+        for (run, is_common) in runs.into_iter().zip(is_common) {
+            if is_common {
+                most_common_values.push((run.0, run.1 as f64));
+            } else {
+                rest.push(run);
+            }
+        }
+
+        // Split the other values into buckets of about the same number of rows
+        let rest_count = rest.iter().map(|(_, count)| count).sum::<usize>();
+        let depth = (rest_count as f64 / options.num_buckets.max(1) as f64).max(1.0);
+        let mut buckets = vec![];
+        let mut current: Option<HistogramBucket> = None;
+        for (value, count) in rest {
+            let bucket = current.get_or_insert_with(|| HistogramBucket {
+                lower: value.clone(),
+                upper: value.clone(),
+                count: 0.0,
+                distinct_count: 0.0,
+            });
+            bucket.upper = value;
+            bucket.count += count as f64;
+            bucket.distinct_count += 1.0;
+            if bucket.count >= depth {
+                buckets.extend(current.take());
+            }
+        }
+        buckets.extend(current);
+
+        Ok(Self {
+            buckets,
+            most_common_values,
+            null_count: null_count as f64,
+        })
+    }
+
+    /// The buckets of the values that are not most common values
+    pub fn buckets(&self) -> &[HistogramBucket] {
+        &self.buckets
+    }
+
+    /// The most common values, with their number of rows
+    pub fn most_common_values(&self) -> &[(ScalarValue, f64)] {
+        &self.most_common_values
+    }
+
+    /// Number of null rows
+    pub fn null_count(&self) -> f64 {
+        self.null_count
+    }
+
+    /// Number of non-null rows
+    pub fn num_values(&self) -> f64 {
+        self.buckets.iter().map(|b| b.count).sum::<f64>()
+            + self.most_common_values.iter().map(|(_, c)| c).sum::<f64>()
+    }
+
+    /// Number of rows, including nulls
+    pub fn num_rows(&self) -> f64 {
+        self.num_values() + self.null_count
+    }
+
+    /// Number of distinct non-null values
+    pub fn distinct_count(&self) -> f64 {
+        self.most_common_values.len() as f64
+            + self.buckets.iter().map(|b| b.distinct_count).sum::<f64>()
+    }
+
+    /// Type of the values of the histogram, if it has any
+    pub fn data_type(&self) -> Option<DataType> {
+        self.most_common_values
+            .first()
+            .map(|(value, _)| value.data_type())
+            .or_else(|| self.buckets.first().map(|b| b.lower.data_type()))
+    }
+
+    /// Returns the histogram of a random subset of `fraction` of the rows.
+    ///
+    /// A `fraction` greater than one scales a histogram built from a sample
+    /// to the size of the population, keeping its number of distinct values.
+    pub fn scale(&self, fraction: f64) -> Self {
+        let fraction = fraction.max(0.0);
+        let distinct = |count: f64, distinct: f64| {
+            if fraction >= 1.0 || distinct <= 0.0 {
+                distinct
+            } else {
+                // See `ndv_after_selectivity`
+                distinct * (1.0 - (1.0 - fraction).powf(count / distinct))
+            }
+        };
+        Self {
+            buckets: self
+                .buckets
+                .iter()
+                .map(|b| HistogramBucket {
+                    lower: b.lower.clone(),
+                    upper: b.upper.clone(),
+                    count: b.count * fraction,
+                    distinct_count: distinct(b.count, b.distinct_count),
+                })
+                .collect(),
+            most_common_values: self
+                .most_common_values
+                .iter()
+                .map(|(value, count)| (value.clone(), count * fraction))
+                .collect(),
+            null_count: self.null_count * fraction,
+        }
+    }
+
+    /// Returns the histogram of the distinct values, each counted once
+    pub fn distinct(&self) -> Self {
+        Self {
+            buckets: self
+                .buckets
+                .iter()
+                .map(|b| HistogramBucket {
+                    count: b.distinct_count,
+                    ..b.clone()
+                })
+                .collect(),
+            most_common_values: self
+                .most_common_values
+                .iter()
+                .map(|(value, _)| (value.clone(), 1.0))
+                .collect(),
+            null_count: self.null_count.min(1.0),
+        }
+    }
+
+    /// Returns the histogram of the rows of both `self` and `other`, e.g. the
+    /// histograms of the files of a table
+    pub fn merge(&self, other: &Self) -> Self {
+        let mut most_common_values = self.most_common_values.clone();
+        for (value, count) in &other.most_common_values {
+            match most_common_values.iter_mut().find(|(v, _)| v == value) {
+                Some((_, total)) => *total += count,
+                None => most_common_values.push((value.clone(), *count)),
+            }
+        }
+        let buckets = self.buckets.iter().chain(&other.buckets).cloned().collect();
+        Self::new(
+            buckets,
+            most_common_values,
+            self.null_count + other.null_count,
+        )
+    }
+
+    /// Merges adjacent buckets into at most `max_buckets` buckets holding
+    /// about the same number of rows.
+    ///
+    /// A bucket holding more rows than the others is kept whole, as its
+    /// values cannot be split without knowing how they are distributed.
+    pub fn compact(mut self, max_buckets: usize) -> Self {
+        if self.buckets.len() <= max_buckets || max_buckets == 0 {
+            return self;
+        }
+        let depth =
+            self.buckets.iter().map(|b| b.count).sum::<f64>() / max_buckets as f64;
+        let mut buckets: Vec<HistogramBucket> = Vec::with_capacity(max_buckets);
+        let mut current: Option<HistogramBucket> = None;
+        for bucket in std::mem::take(&mut self.buckets) {
+            let merged = match current.take() {
+                Some(mut merged) => {
+                    merged.absorb(&bucket);
+                    merged
+                }
+                None => bucket,
+            };
+            // The last bucket takes the remaining rows
+            if merged.count >= depth && buckets.len() + 1 < max_buckets {
+                buckets.push(merged);
+            } else {
+                current = Some(merged);
+            }
+        }
+        buckets.extend(current);
+        for bucket in &mut buckets {
+            // Overlapping buckets may share values
+            bucket.distinct_count = bucket.distinct_count.min(bucket.count);
+        }
+        self.buckets = buckets;
+        self
+    }
+
+    /// Number of rows of the most common value `value`, if it is one
+    fn most_common_count(&self, value: &ScalarValue) -> Option<f64> {
+        self.most_common_values
+            .iter()
+            .find(|(v, _)| v == value)
+            .map(|(_, count)| *count)
+    }
+
+    /// Estimated number of rows in the buckets equal to `value`
+    fn bucket_rows_eq(&self, value: &ScalarValue) -> f64 {
+        self.buckets.iter().map(|b| b.rows_eq(value)).sum()
+    }
+
+    /// Estimated number of rows equal to `value`
+    pub fn rows_eq(&self, value: &ScalarValue) -> f64 {
+        self.most_common_count(value).unwrap_or(0.0) + self.bucket_rows_eq(value)
+    }
+
+    /// Estimated number of rows less than `value`
+    pub fn rows_lt(&self, value: &ScalarValue) -> f64 {
+        let common = self
+            .most_common_values
+            .iter()
+            .filter(|(v, _)| v.partial_cmp(value) == Some(Ordering::Less))
+            .map(|(_, count)| count)
+            .sum::<f64>();
+        common + self.buckets.iter().map(|b| b.rows_lt(value)).sum::<f64>()
+    }
+
+    /// Estimated fraction of the rows for which `column <op> value` is true,
+    /// or `None` if `op` is not a comparison or `value` cannot be compared
+    /// with the values of the histogram.
+    pub fn selectivity(&self, op: Operator, value: &ScalarValue) -> Option<f64> {
+        let num_rows = self.num_rows();
+        if num_rows <= 0.0 {
+            return None;
+        }
+        if !matches!(
+            op,
+            Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq
+        ) {
+            return None;
+        }
+        // Comparisons with NULL are never true
+        if value.is_null() {
+            return Some(0.0);
+        }
+        let value = match self.data_type() {
+            Some(data_type) if data_type != value.data_type() => {
+                value.cast_to(&data_type).ok()?
+            }
+            _ => value.clone(),
+        };
+
+        let num_values = self.num_values();
+        let rows = match op {
+            Operator::Eq => self.rows_eq(&value),
+            Operator::NotEq => num_values - self.rows_eq(&value),
+            Operator::Lt => self.rows_lt(&value),
+            Operator::LtEq => self.rows_lt(&value) + self.rows_eq(&value),
+            Operator::Gt => num_values - self.rows_lt(&value) - self.rows_eq(&value),
+            _ => num_values - self.rows_lt(&value),
+        };
+        Some((rows / num_rows).clamp(0.0, 1.0))
+    }
+
+    /// Estimated fraction of the rows that are null
+    pub fn null_fraction(&self) -> Option<f64> {
+        let num_rows = self.num_rows();
+        (num_rows > 0.0).then(|| self.null_count / num_rows)
+    }
+
+    /// Estimated number of rows of the equi-join of the columns with the
+    /// histograms `self` and `other`
+    pub fn join_rows(&self, other: &Self) -> f64 {
+        let mut rows = 0.0;
+        for (value, count) in &self.most_common_values {
+            rows += count * other.rows_eq(value);
+        }
+        for (value, count) in &other.most_common_values {
+            if self.most_common_count(value).is_none() {
+                rows += count * self.bucket_rows_eq(value);
+            }
+        }
+        for left in &self.buckets {
+            for right in &other.buckets {
+                rows += left.join_rows(right);
+            }
+        }
+        rows
+    }
+
+    /// Estimated fraction of the rows of the cross join of the columns with
+    /// the histograms `self` and `other` kept by their equi-join
+    pub fn join_selectivity(&self, other: &Self) -> Option<f64> {
+        let cross_join_rows = self.num_rows() * other.num_rows();
+        (cross_join_rows > 0.0)
+            .then(|| (self.join_rows(other) / cross_join_rows).clamp(0.0, 1.0))
+    }
+}
+
+/// Position of `value` in the range `[lower, upper]`, from 0.0 to 1.0, if
+/// the values are numeric or temporal
+fn interpolate(
+    lower: &ScalarValue,
+    upper: &ScalarValue,
+    value: &ScalarValue,
+) -> Option<f64> {
+    let (lower, upper, value) = (to_f64(lower)?, to_f64(upper)?, to_f64(value)?);
+    if upper <= lower {
+        return None;
+    }
+    Some(((value - lower) / (upper - lower)).clamp(0.0, 1.0))
+}
+
+fn to_f64(value: &ScalarValue) -> Option<f64> {
+    let data_type = value.data_type();
+    let value = if data_type.is_temporal() {
+        value.cast_to(&DataType::Int64).ok()?
+    } else if data_type.is_numeric() {
+        value.clone()
+    } else {
+        return None;
+    };
+    match value.cast_to(&DataType::Float64).ok()? {
+        ScalarValue::Float64(value) => value,
+        _ => None,
+    }
+}
+
+/// Returns true if [`sample_histograms`] builds histograms of columns of
+/// type `data_type`
+fn supports_histogram(data_type: &DataType) -> bool {
+    data_type.is_numeric()
+        || data_type.is_temporal()
+        || matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Utf8View
+                | DataType::Binary
+                | DataType::LargeBinary
+                | DataType::BinaryView
+        )
+}
+
+/// [`ExtendedStatistics`] extension holding the [`ColumnHistogram`] of each
+/// column of the output of a plan, if known.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColumnHistograms {
+    columns: Vec<Option<Arc<ColumnHistogram>>>,
+}
+
+impl ColumnHistograms {
+    /// Create new histograms, one per column of the schema
+    pub fn new(columns: Vec<Option<Arc<ColumnHistogram>>>) -> Self {
+        Self { columns }
+    }
+
+    /// The histogram of the column at `index`, if known
+    pub fn column(&self, index: usize) -> Option<&Arc<ColumnHistogram>> {
+        self.columns.get(index)?.as_ref()
+    }
+
+    /// The histogram of each column
+    pub fn columns(&self) -> &[Option<Arc<ColumnHistogram>>] {
+        &self.columns
+    }
+
+    /// Number of columns
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Returns true if there are no columns
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Returns true if the histogram of any column is known
+    pub fn has_histograms(&self) -> bool {
+        self.columns.iter().any(Option::is_some)
+    }
+
+    /// Returns the histograms of the columns at `indices`
+    pub fn project(&self, indices: &[usize]) -> Self {
+        Self::new(
+            indices
+                .iter()
+                .map(|&index| self.column(index).cloned())
+                .collect(),
+        )
+    }
+
+    /// Returns the histograms of a random subset of `fraction` of the rows,
+    /// see [`ColumnHistogram::scale`]
+    pub fn scale(&self, fraction: f64) -> Self {
+        Self::new(
+            self.columns
+                .iter()
+                .map(|h| h.as_ref().map(|h| Arc::new(h.scale(fraction))))
+                .collect(),
+        )
+    }
+
+    /// Returns the histograms of the columns of `self` followed by the
+    /// columns of `other`
+    pub fn concat(&self, other: &Self) -> Self {
+        Self::new(self.columns.iter().chain(&other.columns).cloned().collect())
+    }
+
+    /// Returns `base` extended with these histograms, which also provide the
+    /// number of distinct values of the columns where `base` lacks it
+    pub fn into_statistics(self, base: &Statistics) -> ExtendedStatistics {
+        let mut base = base.clone();
+        for (column, histogram) in base.column_statistics.iter_mut().zip(&self.columns) {
+            if let (Precision::Absent, Some(histogram)) =
+                (&column.distinct_count, histogram)
+            {
+                column.distinct_count =
+                    Precision::Inexact(histogram.distinct_count().round() as usize);
+            }
+        }
+        let mut stats = ExtendedStatistics::new(base);
+        stats.set_extension(self);
+        stats
+    }
+}
+
+/// Estimate the fraction of the rows for which `predicate` is true from the
+/// histograms of the columns it references.
+///
+/// Supports comparisons of columns with literals, `IN` lists, `IS [NOT] NULL`
+/// and their combinations with `AND`, `OR` and `NOT`, assuming independence
+/// between columns. Returns `None` if any part of `predicate` is not
+/// supported or references a column without histogram.
+pub fn estimate_selectivity(
+    predicate: &Arc<dyn PhysicalExpr>,
+    histograms: &ColumnHistograms,
+) -> Option<f64> {
+    let column_histogram = |expr: &Arc<dyn PhysicalExpr>| {
+        histograms.column(expr.downcast_ref::<Column>()?.index())
+    };
+
+    if let Some(binary) = predicate.downcast_ref::<BinaryExpr>() {
+        return match binary.op() {
+            Operator::And => Some(
+                estimate_selectivity(binary.left(), histograms)?
+                    * estimate_selectivity(binary.right(), histograms)?,
+            ),
+            Operator::Or => {
+                let left = estimate_selectivity(binary.left(), histograms)?;
+                let right = estimate_selectivity(binary.right(), histograms)?;
+                Some(left + right - left * right)
+            }
+            op => {
+                if let (Some(histogram), Some(literal)) = (
+                    column_histogram(binary.left()),
+                    binary.right().downcast_ref::<Literal>(),
+                ) {
+                    histogram.selectivity(*op, literal.value())
+                } else if let (Some(literal), Some(histogram)) = (
+                    binary.left().downcast_ref::<Literal>(),
+                    column_histogram(binary.right()),
+                ) {
+                    histogram.selectivity(op.swap()?, literal.value())
+                } else {
+                    None
+                }
+            }
+        };
+    }
+    if let Some(not) = predicate.downcast_ref::<NotExpr>() {
+        return estimate_selectivity(not.arg(), histograms).map(|s| 1.0 - s);
+    }
+    if let Some(in_list) = predicate.downcast_ref::<InListExpr>() {
+        let histogram = column_histogram(in_list.expr())?;
+        let mut selectivity = 0.0;
+        for item in in_list.list() {
+            let value = item.downcast_ref::<Literal>()?.value();
+            selectivity += histogram.selectivity(Operator::Eq, value)?;
+        }
+        let selectivity = selectivity.min(1.0);
+        return if in_list.negated() {
+            let not_null = 1.0 - histogram.null_fraction()?;
+            Some((not_null - selectivity).max(0.0))
+        } else {
+            Some(selectivity)
+        };
+    }
+    if let Some(is_null) = predicate.downcast_ref::<IsNullExpr>() {
+        return column_histogram(is_null.arg())?.null_fraction();
+    }
+    if let Some(is_not_null) = predicate.downcast_ref::<IsNotNullExpr>() {
+        return column_histogram(is_not_null.arg())?
+            .null_fraction()
+            .map(|s| 1.0 - s);
+    }
+    if let Some(literal) = predicate.downcast_ref::<Literal>() {
+        return match literal.value() {
+            ScalarValue::Boolean(Some(true)) => Some(1.0),
+            ScalarValue::Boolean(_) => Some(0.0),
+            _ => None,
+        };
+    }
+    None
+}
+
+/// Build the histograms of the columns of the output of `plan` from its
+/// first [`HistogramOptions::sample_size`] rows.
+///
+/// The rows are read in the order `plan` produces them, so for histograms
+/// representative of all the rows `plan` should sample them itself, e.g. a
+/// `DataSourceExec` returned by `try_pushdown_sample`. If `plan` knows its
+/// number of rows, the histograms are scaled up to it.
+pub async fn sample_histograms(
+    plan: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+    options: &HistogramOptions,
+) -> Result<ColumnHistograms> {
+    let schema = plan.schema();
+    let num_rows = plan.partition_statistics(None)?.num_rows;
+    let mut stream = execute_stream(plan, context)?;
+    let mut batches = vec![];
+    let mut sampled = 0;
+    while sampled < options.sample_size {
+        let Some(batch) = stream.next().await.transpose()? else {
+            break;
+        };
+        let batch = batch.slice(0, batch.num_rows().min(options.sample_size - sampled));
+        sampled += batch.num_rows();
+        batches.push(batch);
+    }
+    let batch = concat_batches(&schema, &batches)?;
+
+    let fraction = match num_rows.get_value() {
+        Some(&num_rows) if sampled > 0 && num_rows > sampled => {
+            num_rows as f64 / sampled as f64
+        }
+        _ => 1.0,
+    };
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|array| {
+            if !supports_histogram(array.data_type()) {
+                return Ok(None);
+            }
+            let histogram = ColumnHistogram::try_from_array(array, options)?;
+            Ok(Some(Arc::new(histogram.scale(fraction))))
+        })
+        .collect::<Result<_>>()?;
+    Ok(ColumnHistograms::new(columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use datafusion_physical_expr::expressions::{in_list, lit};
+
+    fn skewed() -> ArrayRef {
+        // 900 rows of 200, 100 rows of 0..100 and 10 nulls
+        let values = std::iter::repeat_n(Some(200), 900)
+            .chain((0..100).map(Some))
+            .chain(std::iter::repeat_n(None, 10));
+        Arc::new(Int32Array::from_iter(values))
+    }
+
+    fn options() -> HistogramOptions {
+        HistogramOptions {
+            num_buckets: 10,
+            num_most_common_values: 5,
+            ..Default::default()
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_histogram_from_array() -> Result<()> {
+        let histogram = ColumnHistogram::try_from_array(&skewed(), &options())?;
+        assert_eq!(
+            histogram.most_common_values(),
+            &[(ScalarValue::Int32(Some(200)), 900.0)]
+        );
+        assert_eq!(histogram.buckets().len(), 10);
+        assert_eq!(histogram.buckets()[0].lower, ScalarValue::Int32(Some(0)));
+        assert_eq!(histogram.buckets()[0].upper, ScalarValue::Int32(Some(9)));
+        assert_eq!(histogram.null_count(), 10.0);
+        assert_eq!(histogram.num_rows(), 1010.0);
+        assert_eq!(histogram.distinct_count(), 101.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_histogram_keeps_all_values_if_few() -> Result<()> {
+        let array: ArrayRef = Arc::new(StringArray::from(vec!["a", "b", "a", "c"]));
+        let histogram = ColumnHistogram::try_from_array(&array, &options())?;
+        assert!(histogram.buckets().is_empty());
+        assert_eq!(histogram.most_common_values().len(), 3);
+        assert_close(
+            histogram
+                .selectivity(Operator::Eq, &ScalarValue::from("a"))
+                .unwrap(),
+            0.5,
+        );
+        assert_close(
+            histogram
+                .selectivity(Operator::Eq, &ScalarValue::from("d"))
+                .unwrap(),
+            0.0,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_selectivity() -> Result<()> {
+        let histogram = ColumnHistogram::try_from_array(&skewed(), &options())?;
+        let selectivity = |op, value: i32| {
+            histogram
+                .selectivity(op, &ScalarValue::from(value))
+                .unwrap()
+        };
+
+        // The most common value
+        assert_close(selectivity(Operator::Eq, 200), 900.0 / 1010.0);
+        assert_close(selectivity(Operator::NotEq, 200), 100.0 / 1010.0);
+        // A value of a bucket
+        assert_close(selectivity(Operator::Eq, 5), 1.0 / 1010.0);
+        // Ranges
+        assert_close(selectivity(Operator::Lt, 50), 50.0 / 1010.0);
+        assert_close(selectivity(Operator::LtEq, 99), 100.0 / 1010.0);
+        assert_close(selectivity(Operator::Gt, 99), 900.0 / 1010.0);
+        assert_close(selectivity(Operator::GtEq, 0), 1000.0 / 1010.0);
+        assert_close(selectivity(Operator::Lt, 0), 0.0);
+        // Literals of another type are cast
+        assert_close(
+            histogram
+                .selectivity(Operator::Eq, &ScalarValue::Int64(Some(200)))
+                .unwrap(),
+            900.0 / 1010.0,
+        );
+        assert_eq!(
+            histogram.selectivity(Operator::Eq, &ScalarValue::Int32(None)),
+            Some(0.0)
+        );
+        assert_eq!(
+            histogram.selectivity(Operator::Plus, &ScalarValue::from(1)),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimate_selectivity() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let histograms = ColumnHistograms::new(vec![
+            Some(Arc::new(ColumnHistogram::try_from_array(
+                &skewed(),
+                &options(),
+            )?)),
+            None,
+        ]);
+        let a = Arc::new(Column::new("a", 0)) as Arc<dyn PhysicalExpr>;
+        let b = Arc::new(Column::new("b", 1)) as Arc<dyn PhysicalExpr>;
+        let binary = |left, op, right| -> Arc<dyn PhysicalExpr> {
+            Arc::new(BinaryExpr::new(left, op, right))
+        };
+
+        let eq = binary(Arc::clone(&a), Operator::Eq, lit(200));
+        assert_close(
+            estimate_selectivity(&eq, &histograms).unwrap(),
+            900.0 / 1010.0,
+        );
+        // The literal may be on the left
+        let swapped = binary(lit(50), Operator::Gt, Arc::clone(&a));
+        assert_close(
+            estimate_selectivity(&swapped, &histograms).unwrap(),
+            50.0 / 1010.0,
+        );
+        // The columns of disjunctions are assumed independent
+        let or = binary(Arc::clone(&eq), Operator::Or, swapped);
+        let (left, right) = (900.0 / 1010.0, 50.0 / 1010.0);
+        assert_close(
+            estimate_selectivity(&or, &histograms).unwrap(),
+            left + right - left * right,
+        );
+        let not = Arc::new(NotExpr::new(Arc::clone(&eq))) as Arc<dyn PhysicalExpr>;
+        assert_close(
+            estimate_selectivity(&not, &histograms).unwrap(),
+            110.0 / 1010.0,
+        );
+        let list = in_list(Arc::clone(&a), vec![lit(1), lit(200)], &false, &schema)?;
+        assert_close(
+            estimate_selectivity(&list, &histograms).unwrap(),
+            901.0 / 1010.0,
+        );
+        let is_null = Arc::new(IsNullExpr::new(Arc::clone(&a))) as Arc<dyn PhysicalExpr>;
+        assert_close(
+            estimate_selectivity(&is_null, &histograms).unwrap(),
+            10.0 / 1010.0,
+        );
+
+        // Columns without histogram are not estimated
+        let other = binary(b, Operator::Eq, lit(1));
+        assert_eq!(estimate_selectivity(&other, &histograms), None);
+        let and = binary(eq, Operator::And, other);
+        assert_eq!(estimate_selectivity(&and, &histograms), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_selectivity() -> Result<()> {
+        let left = ColumnHistogram::try_from_array(&skewed(), &options())?;
+        // One row of each value of 0..1000
+        let array: ArrayRef = Arc::new(Int32Array::from_iter_values(0..1000));
+        let right = ColumnHistogram::try_from_array(&array, &options())?;
+        assert!(right.most_common_values().is_empty());
+
+        // Each row of `left` but the nulls matches one row of `right`
+        assert!((left.join_rows(&right) - 1000.0).abs() < 20.0);
+        assert!((right.join_rows(&left) - 1000.0).abs() < 20.0);
+        assert_close(
+            left.join_selectivity(&right).unwrap(),
+            left.join_rows(&right) / 1_010_000.0,
+        );
+        // The most common value of `left` matches 900 rows of itself
+        assert!(left.join_rows(&left) > 810_000.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scale_and_merge() -> Result<()> {
+        let histogram = ColumnHistogram::try_from_array(&skewed(), &options())?;
+        let half = histogram.scale(0.5);
+        assert_close(half.num_rows(), 505.0);
+        assert!(half.distinct_count() < histogram.distinct_count());
+
+        let merged = histogram.merge(&histogram);
+        assert_close(merged.num_rows(), 2020.0);
+        assert_eq!(merged.most_common_values().len(), 1);
+        assert_eq!(merged.buckets().len(), 20);
+        let compacted = merged.compact(10);
+        assert_eq!(compacted.buckets().len(), 10);
+        assert_close(compacted.num_rows(), 2020.0);
+
+        let distinct = histogram.distinct();
+        assert_close(distinct.num_values(), 101.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_equi_depth() {
+        let bucket = |lower: i32, upper: i32, count: f64| HistogramBucket {
+            lower: ScalarValue::Int32(Some(lower)),
+            upper: ScalarValue::Int32(Some(upper)),
+            count,
+            distinct_count: count,
+        };
+        let histogram = ColumnHistogram::new(
+            vec![
+                bucket(0, 9, 10.0),
+                bucket(10, 19, 10.0),
+                bucket(20, 29, 10.0),
+                bucket(30, 39, 10.0),
+                bucket(40, 79, 40.0),
+                bucket(80, 89, 10.0),
+                bucket(90, 99, 10.0),
+            ],
+            vec![],
+            0.0,
+        );
+
+        // Adjacent buckets are merged until they hold a quarter of the rows,
+        // and the deep bucket is kept whole
+        let compacted = histogram.clone().compact(4);
+        assert_eq!(
+            compacted.buckets(),
+            &[
+                bucket(0, 29, 30.0),
+                bucket(30, 79, 50.0),
+                bucket(80, 99, 20.0)
+            ]
+        );
+        assert_close(compacted.num_rows(), histogram.num_rows());
+
+        let compacted = histogram.compact(1);
+        assert_eq!(compacted.buckets(), &[bucket(0, 99, 100.0)]);
+    }
+}
@@ -50,6 +50,16 @@
 //! 7. [`UnionStatisticsProvider`] - sums input row counts
 //! 8. [`DefaultStatisticsProvider`] - fallback to `partition_statistics(None)`
 //!
+//! # Histograms
+//!
+//! [`ColumnHistograms`] is a built-in extension holding an equi-depth
+//! [`ColumnHistogram`] and the most common values of columns. Providers of
+//! leaf nodes attach it, e.g. from histograms built by [`sample_histograms`],
+//! and the built-in providers propagate it through filters, projections,
+//! joins and aggregates. Where available, histograms replace the uniform
+//! distributions otherwise assumed to estimate the selectivity of filters
+//! (see [`estimate_selectivity`]) and equi-joins.
+//!
 //! # Relationship to [#20184](https://github.com/apache/datafusion/issues/20184)
 //!
 //! This module performs its own bottom-up tree walk in [`StatisticsRegistry::compute`],
@@ -86,6 +96,14 @@
 //! let stats = registry.compute(plan.as_ref())?;
 //! ```
 
+mod histogram;
+
+pub use histogram::{
+    ColumnHistogram, ColumnHistograms, HistogramBucket, HistogramOptions,
+    estimate_selectivity, sample_histograms,
+};
+
+use std::any::Any;
 use std::fmt::{self, Debug};
 use std::sync::Arc;
 
@@ -237,7 +255,7 @@ pub enum StatisticsResult {
 ///     }
 /// }
 /// ```
-pub trait StatisticsProvider: Debug + Send + Sync {
+pub trait StatisticsProvider: Any + Debug + Send + Sync {
     /// Compute statistics for an [`ExecutionPlan`] node.
     ///
     /// # Arguments
@@ -345,6 +363,14 @@ impl StatisticsRegistry {
         &self.providers
     }
 
+    /// Returns the first provider of type `T` of the chain, e.g. to feed
+    /// it data it computes statistics from.
+    pub fn provider<T: StatisticsProvider>(&self) -> Option<&T> {
+        self.providers
+            .iter()
+            .find_map(|provider| (provider.as_ref() as &dyn Any).downcast_ref::<T>())
+    }
+
     /// Compute extended statistics for a plan through the provider chain.
     ///
     /// Performs a bottom-up tree walk: child statistics are computed recursively
@@ -510,6 +536,21 @@ fn computed_with_row_count(
     Ok(StatisticsResult::Computed(ExtendedStatistics::new(base)))
 }
 
+/// Attaches the [`ColumnHistograms`] of the output of an operator, if any, to
+/// the statistics computed for it.
+fn with_histograms(
+    result: StatisticsResult,
+    histograms: Option<ColumnHistograms>,
+) -> StatisticsResult {
+    match (result, histograms) {
+        (StatisticsResult::Computed(mut stats), Some(histograms)) => {
+            stats.set_extension(histograms);
+            StatisticsResult::Computed(stats)
+        }
+        (result, _) => result,
+    }
+}
+
 /// Statistics provider for [`FilterExec`](crate::filter::FilterExec) that uses
 /// pre-computed enhanced child statistics from the registry walk.
 ///
@@ -519,6 +560,10 @@ fn computed_with_row_count(
 /// estimation logic as `FilterExec::statistics_helper`, then additionally
 /// adjusts each column's `distinct_count` using [`ndv_after_selectivity`] based
 /// on the computed selectivity ratio.
+///
+/// If the input has [`ColumnHistograms`] for the columns of the predicate,
+/// the selectivity is estimated from them with [`estimate_selectivity`]
+/// instead, and the histograms are scaled by it for the output.
 #[derive(Debug, Default)]
 pub struct FilterStatisticsProvider;
 
@@ -539,6 +584,7 @@ impl StatisticsProvider for FilterStatisticsProvider {
 
         let input_stats = (*child_stats[0].base).clone();
         let input_rows = input_stats.num_rows;
+        let histograms = child_stats[0].get_extension::<ColumnHistograms>();
         let mut stats = FilterExec::statistics_helper(
             &filter.input().schema(),
             input_stats,
@@ -547,6 +593,16 @@ impl StatisticsProvider for FilterStatisticsProvider {
             // TODO: pass filter.expression_analyzer_registry() once #21122 lands
         )?;
 
+        // Histograms account for skew, unlike the uniform distributions
+        // assumed by statistics_helper
+        if let Some(&rows) = input_rows.get_value()
+            && let Some(selectivity) =
+                histograms.and_then(|h| estimate_selectivity(filter.predicate(), h))
+        {
+            let filtered_rows = (rows as f64 * selectivity).round() as usize;
+            rescale_byte_size(&mut stats, Precision::Inexact(filtered_rows));
+        }
+
         // Adjust distinct_count for each column using the selectivity ratio
         // via the probabilistic survival model from
         // ndv_after_selectivity to account for rows removed by the filter.
@@ -564,17 +620,34 @@ impl StatisticsProvider for FilterStatisticsProvider {
             }
         }
 
+        let histograms = histograms.and_then(|histograms| {
+            let (Some(&orig_rows), Some(&filtered_rows)) =
+                (input_rows.get_value(), stats.num_rows.get_value())
+            else {
+                return None;
+            };
+            let selectivity = filtered_rows as f64 / orig_rows.max(1) as f64;
+            let histograms = histograms.scale(selectivity);
+            Some(match filter.projection().as_deref() {
+                Some(projection) => histograms.project(projection),
+                None => histograms,
+            })
+        });
+
         let stats = stats.project(filter.projection().as_ref());
-        Ok(StatisticsResult::Computed(ExtendedStatistics::new(stats)))
+        Ok(with_histograms(
+            StatisticsResult::Computed(ExtendedStatistics::new(stats)),
+            histograms,
+        ))
     }
 }
 
 /// Statistics provider for [`ProjectionExec`](crate::projection::ProjectionExec)
 /// that uses pre-computed enhanced child statistics from the registry walk.
 ///
-/// Maps enhanced child column statistics to output columns based on the
-/// projection expressions, preserving NDV and other statistics through
-/// column references.
+/// Maps enhanced child column statistics and [`ColumnHistograms`] to output
+/// columns based on the projection expressions, preserving NDV and other
+/// statistics through column references.
 #[derive(Debug, Default)]
 pub struct ProjectionStatisticsProvider;
 
@@ -585,6 +658,7 @@ impl StatisticsProvider for ProjectionStatisticsProvider {
         child_stats: &[ExtendedStatistics],
     ) -> Result<StatisticsResult> {
         use crate::projection::ProjectionExec;
+        use datafusion_physical_expr::expressions::Column;
 
         let Some(proj) = plan.downcast_ref::<ProjectionExec>() else {
             return Ok(StatisticsResult::Delegate);
@@ -600,7 +674,24 @@ impl StatisticsProvider for ProjectionStatisticsProvider {
         let stats = proj
             .projection_expr()
             .project_statistics(input_stats, &output_schema)?;
-        Ok(StatisticsResult::Computed(ExtendedStatistics::new(stats)))
+        let histograms =
+            child_stats[0]
+                .get_extension::<ColumnHistograms>()
+                .map(|histograms| {
+                    ColumnHistograms::new(
+                        proj.expr()
+                            .iter()
+                            .map(|e| {
+                                let col = e.expr.downcast_ref::<Column>()?;
+                                histograms.column(col.index()).cloned()
+                            })
+                            .collect(),
+                    )
+                });
+        Ok(with_histograms(
+            StatisticsResult::Computed(ExtendedStatistics::new(stats)),
+            histograms,
+        ))
     }
 }
 
@@ -645,7 +736,8 @@ impl StatisticsProvider for PassthroughStatisticsProvider {
 /// that estimates output cardinality from the NDV of GROUP BY columns.
 ///
 /// For each GROUP BY column, looks up `distinct_count` from the enhanced
-/// child statistics. The estimated output rows is the product of all
+/// child statistics, or from the column's [`ColumnHistogram`] if unknown.
+/// The estimated output rows is the product of all
 /// column NDVs, capped at the input row count. This assumes independence
 /// between columns, so correlated columns (e.g., `city` and `state`) will
 /// produce overestimates.
@@ -653,9 +745,13 @@ impl StatisticsProvider for PassthroughStatisticsProvider {
 /// For GROUPING SETS / CUBE / ROLLUP, delegates to the built-in
 /// `partition_statistics`, which handles per-set NDV estimation correctly.
 ///
+/// When grouping by a single column with a histogram, the output has the
+/// histogram of its distinct values, including for `Partial` aggregates.
+///
 /// Delegates when:
 /// - The plan is not an `AggregateExec`
 /// - The aggregate is `Partial` (per-partition, not bounded by global NDV)
+///   and there is no histogram to propagate
 /// - GROUP BY is empty (scalar aggregate)
 /// - Any GROUP BY expression is not a simple column reference
 /// - Any GROUP BY column lacks NDV information
@@ -677,10 +773,35 @@ impl StatisticsProvider for AggregateStatisticsProvider {
             return Ok(StatisticsResult::Delegate);
         };
 
+        let input_histograms = child_stats
+            .first()
+            .and_then(|stats| stats.get_extension::<ColumnHistograms>());
+
+        // Grouping by a single column outputs each of its values once
+        let histograms = match agg.group_expr().expr() {
+            [(expr, _)] if agg.group_expr().groups().len() == 1 => expr
+                .downcast_ref::<Column>()
+                .and_then(|col| input_histograms?.column(col.index()))
+                .map(|histogram| {
+                    let mut columns = vec![None; agg.schema().fields().len()];
+                    columns[0] = Some(Arc::new(histogram.distinct()));
+                    ColumnHistograms::new(columns)
+                }),
+            _ => None,
+        };
+
         // Partial aggregates produce per-partition groups, not bounded by
         // global NDV; delegate to the built-in estimate for those.
         if matches!(agg.mode(), AggregateMode::Partial) {
-            return Ok(StatisticsResult::Delegate);
+            return Ok(match histograms {
+                Some(histograms) => with_histograms(
+                    StatisticsResult::Computed(ExtendedStatistics::new_arc(
+                        plan.partition_statistics(None)?,
+                    )),
+                    Some(histograms),
+                ),
+                None => StatisticsResult::Delegate,
+            });
         }
 
         if child_stats.is_empty() || agg.group_expr().expr().is_empty() {
@@ -695,10 +816,14 @@ impl StatisticsProvider for AggregateStatisticsProvider {
             let Some(col) = expr.downcast_ref::<Column>() else {
                 return Ok(StatisticsResult::Delegate);
             };
-            let Some(&ndv) = input_stats
+            let Some(ndv) = input_stats
                 .column_statistics
                 .get(col.index())
-                .and_then(|s| s.distinct_count.get_value())
+                .and_then(|s| s.distinct_count.get_value().copied())
+                .or_else(|| {
+                    let histogram = input_histograms?.column(col.index())?;
+                    Some(histogram.distinct_count().round() as usize)
+                })
             else {
                 return Ok(StatisticsResult::Delegate);
             };
@@ -729,7 +854,10 @@ impl StatisticsProvider for AggregateStatisticsProvider {
 
         let num_rows = Precision::Inexact(estimate);
 
-        computed_with_row_count(plan, num_rows)
+        Ok(with_histograms(
+            computed_with_row_count(plan, num_rows)?,
+            histograms,
+        ))
     }
 }
 
@@ -741,6 +869,10 @@ impl StatisticsProvider for AggregateStatisticsProvider {
 /// falling back to the Cartesian product when any key lacks NDV on both sides.
 /// For cross joins, uses the exact Cartesian product.
 ///
+/// Join keys with a [`ColumnHistogram`] on both sides are estimated from the
+/// histograms instead, which accounts for skewed keys. The histograms of the
+/// inputs are scaled to the estimated output and propagated.
+///
 /// The base inner-join estimate is then adjusted for the join type:
 /// - Semi joins: capped at the preserved-side row count
 /// - Anti joins: preserved-side minus matched rows (clamped to 0)
@@ -768,12 +900,13 @@ impl StatisticsProvider for JoinStatisticsProvider {
             return Ok(StatisticsResult::Delegate);
         }
 
-        let left = &child_stats[0].base;
-        let right = &child_stats[1].base;
+        let left = &child_stats[0];
+        let right = &child_stats[1];
 
-        let (Some(&left_rows), Some(&right_rows)) =
-            (left.num_rows.get_value(), right.num_rows.get_value())
-        else {
+        let (Some(&left_rows), Some(&right_rows)) = (
+            left.base.num_rows.get_value(),
+            right.base.num_rows.get_value(),
+        ) else {
             return Ok(StatisticsResult::Delegate);
         };
 
@@ -781,26 +914,39 @@ impl StatisticsProvider for JoinStatisticsProvider {
 
         /// Estimate equi-join output using NDV of join key columns:
         ///   left_rows * right_rows / product(max(left_ndv_i, right_ndv_i))
+        /// Keys with histograms on both sides use their join selectivity instead.
         /// Falls back to Cartesian product if any key lacks NDV on both sides.
         fn equi_join_estimate(
             on: JoinOnRef,
-            left: &Statistics,
-            right: &Statistics,
+            left: &ExtendedStatistics,
+            right: &ExtendedStatistics,
             left_rows: usize,
             right_rows: usize,
         ) -> usize {
             if on.is_empty() {
                 return left_rows.saturating_mul(right_rows);
             }
+            let left_histograms = left.get_extension::<ColumnHistograms>();
+            let right_histograms = right.get_extension::<ColumnHistograms>();
             let mut ndv_divisor: usize = 1;
+            let mut histogram_selectivity: Option<f64> = None;
             for (left_key, right_key) in on {
-                let left_ndv = left_key
-                    .downcast_ref::<Column>()
-                    .and_then(|c| left.column_statistics.get(c.index()))
+                let left_col = left_key.downcast_ref::<Column>();
+                let right_col = right_key.downcast_ref::<Column>();
+                if let (Some(l), Some(r)) = (left_col, right_col)
+                    && let Some(selectivity) = left_histograms
+                        .and_then(|h| h.column(l.index()))
+                        .zip(right_histograms.and_then(|h| h.column(r.index())))
+                        .and_then(|(l, r)| l.join_selectivity(r))
+                {
+                    *histogram_selectivity.get_or_insert(1.0) *= selectivity;
+                    continue;
+                }
+                let left_ndv = left_col
+                    .and_then(|c| left.base.column_statistics.get(c.index()))
                     .and_then(|s| s.distinct_count.get_value().copied());
-                let right_ndv = right_key
-                    .downcast_ref::<Column>()
-                    .and_then(|c| right.column_statistics.get(c.index()))
+                let right_ndv = right_col
+                    .and_then(|c| right.base.column_statistics.get(c.index()))
                     .and_then(|s| s.distinct_count.get_value().copied());
                 match (left_ndv, right_ndv) {
                     (Some(l), Some(r)) if l > 0 && r > 0 => {
@@ -810,29 +956,44 @@ impl StatisticsProvider for JoinStatisticsProvider {
                 }
             }
             let max_rows = left_rows.saturating_mul(right_rows);
-            max_rows.checked_div(ndv_divisor).unwrap_or(max_rows)
+            let estimate = max_rows.checked_div(ndv_divisor).unwrap_or(max_rows);
+            match histogram_selectivity {
+                Some(selectivity) => (estimate as f64 * selectivity).round() as usize,
+                None => estimate,
+            }
         }
 
-        let (inner_estimate, is_exact_cartesian, join_type) = if let Some(hash_join) =
-            plan.downcast_ref::<HashJoinExec>()
-        {
-            let est =
-                equi_join_estimate(hash_join.on(), left, right, left_rows, right_rows);
-            (est, false, *hash_join.join_type())
-        } else if let Some(smj) = plan.downcast_ref::<SortMergeJoinExec>() {
-            let est = equi_join_estimate(smj.on(), left, right, left_rows, right_rows);
-            (est, false, smj.join_type())
-        } else if plan.downcast_ref::<CrossJoinExec>().is_some() {
-            let both_exact = left.num_rows.is_exact().unwrap_or(false)
-                && right.num_rows.is_exact().unwrap_or(false);
-            (
-                left_rows.saturating_mul(right_rows),
-                both_exact,
-                JoinType::Inner,
-            )
-        } else {
-            return Ok(StatisticsResult::Delegate);
-        };
+        let (inner_estimate, is_exact_cartesian, join_type, projection) =
+            if let Some(hash_join) = plan.downcast_ref::<HashJoinExec>() {
+                let est = equi_join_estimate(
+                    hash_join.on(),
+                    left,
+                    right,
+                    left_rows,
+                    right_rows,
+                );
+                (
+                    est,
+                    false,
+                    *hash_join.join_type(),
+                    hash_join.projection.as_deref(),
+                )
+            } else if let Some(smj) = plan.downcast_ref::<SortMergeJoinExec>() {
+                let est =
+                    equi_join_estimate(smj.on(), left, right, left_rows, right_rows);
+                (est, false, smj.join_type(), None)
+            } else if plan.downcast_ref::<CrossJoinExec>().is_some() {
+                let both_exact = left.base.num_rows.is_exact().unwrap_or(false)
+                    && right.base.num_rows.is_exact().unwrap_or(false);
+                (
+                    left_rows.saturating_mul(right_rows),
+                    both_exact,
+                    JoinType::Inner,
+                    None,
+                )
+            } else {
+                return Ok(StatisticsResult::Delegate);
+            };
 
         // Apply join-type-aware cardinality bounds
         let estimated = match join_type {
@@ -852,7 +1013,7 @@ impl StatisticsProvider for JoinStatisticsProvider {
             JoinType::RightAnti => {
                 right_rows.saturating_sub(inner_estimate.min(right_rows))
             }
-            JoinType::LeftMark => left_rows,
+            JoinType::LeftMark | JoinType::AsOf => left_rows,
             JoinType::RightMark => right_rows,
         };
 
@@ -864,7 +1025,43 @@ impl StatisticsProvider for JoinStatisticsProvider {
             Precision::Inexact(estimated)
         };
 
-        computed_with_row_count(plan, num_rows)
+        // Each input row appears about `estimated / input_rows` times
+        let scaled = |stats: &ExtendedStatistics, rows: usize| {
+            let fraction = estimated as f64 / rows.max(1) as f64;
+            match stats.get_extension::<ColumnHistograms>() {
+                Some(histograms) => histograms.scale(fraction),
+                None => {
+                    ColumnHistograms::new(vec![None; stats.base.column_statistics.len()])
+                }
+            }
+        };
+        let histograms = if left.has_extension::<ColumnHistograms>()
+            || right.has_extension::<ColumnHistograms>()
+        {
+            match join_type {
+                JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => {
+                    Some(scaled(left, left_rows).concat(&scaled(right, right_rows)))
+                }
+                JoinType::LeftSemi | JoinType::LeftAnti => Some(scaled(left, left_rows)),
+                JoinType::RightSemi | JoinType::RightAnti => {
+                    Some(scaled(right, right_rows))
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+        let histograms = histograms
+            .map(|histograms| match projection {
+                Some(projection) => histograms.project(projection),
+                None => histograms,
+            })
+            .filter(|histograms| histograms.len() == plan.schema().fields().len());
+
+        Ok(with_histograms(
+            computed_with_row_count(plan, num_rows)?,
+            histograms,
+        ))
     }
 }
 
@@ -2294,4 +2491,108 @@ mod tests {
         assert_eq!(stats_b.base.num_rows, Precision::Inexact(50));
         Ok(())
     }
+
+    /// Registry with the built-in providers and a provider attaching to
+    /// sources the histogram of column `a`: 901 rows of 7 and one row of
+    /// each other value of 0..100
+    fn registry_with_skewed_histogram() -> Result<StatisticsRegistry> {
+        use arrow::array::{ArrayRef, Int32Array};
+
+        let values = std::iter::repeat_n(7, 900).chain(0..100);
+        let array: ArrayRef = Arc::new(Int32Array::from_iter_values(values));
+        let histogram = Arc::new(ColumnHistogram::try_from_array(
+            &array,
+            &HistogramOptions::default(),
+        )?);
+        let mut registry = StatisticsRegistry::default_with_builtin_providers();
+        registry.register(Arc::new(ClosureStatisticsProvider::new(move |plan, _| {
+            if plan.downcast_ref::<MockSourceExec>().is_none() {
+                return Ok(StatisticsResult::Delegate);
+            }
+            let histograms =
+                ColumnHistograms::new(vec![Some(Arc::clone(&histogram)), None]);
+            let base = plan.partition_statistics(None)?;
+            Ok(StatisticsResult::Computed(
+                histograms.into_statistics(&base),
+            ))
+        })));
+        Ok(registry)
+    }
+
+    #[test]
+    fn test_filter_provider_uses_histogram() -> Result<()> {
+        let registry = registry_with_skewed_histogram()?;
+        let schema = make_schema();
+        let filter_rows = |op, value: i32| -> Result<Precision<usize>> {
+            let predicate = Arc::new(BinaryExpr::new(col("a", &schema)?, op, lit(value)));
+            let filter: Arc<dyn ExecutionPlan> =
+                Arc::new(FilterExec::try_new(predicate, make_source(1000))?);
+            Ok(registry.compute(filter.as_ref())?.base.num_rows)
+        };
+
+        // Uniform distributions would estimate 1000 / 100 rows
+        assert_eq!(filter_rows(Operator::Eq, 7)?, Precision::Inexact(901));
+        assert_eq!(filter_rows(Operator::NotEq, 7)?, Precision::Inexact(99));
+        assert_eq!(filter_rows(Operator::Lt, 50)?, Precision::Inexact(950));
+        Ok(())
+    }
+
+    #[test]
+    fn test_histograms_propagate_through_filter_and_projection() -> Result<()> {
+        let registry = registry_with_skewed_histogram()?;
+        let schema = make_schema();
+        let predicate =
+            Arc::new(BinaryExpr::new(col("a", &schema)?, Operator::GtEq, lit(50)));
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, make_source(1000))?);
+        let proj: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![
+                (col("b", &schema)?, "b".to_string()),
+                (col("a", &schema)?, "a".to_string()),
+            ],
+            filter,
+        )?);
+
+        let stats = registry.compute(proj.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(50));
+        let histograms = stats.get_extension::<ColumnHistograms>().unwrap();
+        assert!(histograms.column(0).is_none());
+        let histogram = histograms.column(1).unwrap();
+        assert!((histogram.num_rows() - 50.0).abs() < 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate_provider_uses_histogram() -> Result<()> {
+        let registry = registry_with_skewed_histogram()?;
+        let group_by = PhysicalGroupBy::new_single(vec![(
+            Arc::new(Column::new("a", 0)),
+            "a".to_string(),
+        )]);
+        let agg = make_aggregate(make_source(1000), group_by)?;
+
+        let stats = registry.compute(agg.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(100));
+        // Each group has a single row
+        let histograms = stats.get_extension::<ColumnHistograms>().unwrap();
+        assert_eq!(histograms.column(0).unwrap().num_rows(), 100.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_provider_uses_histogram() -> Result<()> {
+        let registry = registry_with_skewed_histogram()?;
+        let join = make_hash_join(make_source(1000), make_source(1000))?;
+
+        // The 901 rows of 7 on each side match each other, while the number
+        // of distinct values alone gives 1000 * 1000 / 100 = 10000 rows
+        let stats = registry.compute(join.as_ref())?;
+        let rows = *stats.base.num_rows.get_value().unwrap();
+        assert!((810_000..=812_000).contains(&rows), "got {rows}");
+        let histograms = stats.get_extension::<ColumnHistograms>().unwrap();
+        assert_eq!(histograms.len(), 4);
+        assert!(histograms.column(0).is_some());
+        assert!(histograms.column(2).is_some());
+        Ok(())
+    }
 }
@@ -1490,6 +1490,7 @@ impl ExecutionPlan for RepartitionExec {
             }
             Partitioning::RoundRobinBatch(_)
             | Partitioning::Hash(_, _)
+            | Partitioning::Bucket(_, _)
             | Partitioning::UnknownPartitioning(_) => {}
         }
 
@@ -1522,6 +1523,11 @@ impl ExecutionPlan for RepartitionExec {
                     "Changing RepartitionExec partition counts with range partitioning is not implemented"
                 );
             }
+            Bucket(_, _) => {
+                return not_impl_err!(
+                    "Changing RepartitionExec partition counts with bucket partitioning is not implemented"
+                );
+            }
         };
         Ok(Some(Arc::new(Self {
             input: Arc::clone(&self.input),
@@ -0,0 +1,521 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the TABLESAMPLE plan, see [`SampleExec`]
+
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+
+use crate::execution_plan::CardinalityEffect;
+use crate::joins::SeededRandomState;
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, RecordOutput,
+};
+use crate::stream::RecordBatchStreamAdapter;
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
+    Partitioning, PlanProperties, SendableRecordBatchStream, Statistics,
+    check_if_same_properties,
+};
+
+use arrow::array::BooleanArray;
+use arrow::compute::{filter_record_batch, interleave_record_batch};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::stats::Precision;
+use datafusion_common::{Result, assert_eq_or_internal_err};
+use datafusion_execution::TaskContext;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_expr::SampleMethod;
+use futures::{StreamExt, TryStreamExt, stream};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Returns a random sample of the rows of its input, see [`SampleMethod`].
+///
+/// [`SampleMethod::Bernoulli`] and [`SampleMethod::System`] sample each input
+/// partition independently. [`SampleMethod::Rows`] requires a single input
+/// partition and returns the sampled rows in their input order.
+#[derive(Debug, Clone)]
+pub struct SampleExec {
+    /// Input execution plan
+    input: Arc<dyn ExecutionPlan>,
+    /// How rows are sampled
+    method: SampleMethod,
+    /// Seed for the random number generator, or `None` to use a random seed
+    /// each time the plan is executed
+    seed: Option<u64>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    cache: Arc<PlanProperties>,
+}
+
+impl SampleExec {
+    /// Create a new [`SampleExec`]
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        method: SampleMethod,
+        seed: Option<u64>,
+    ) -> Self {
+        let cache = Self::compute_properties(&input, &method);
+        Self {
+            input,
+            method,
+            seed,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache: Arc::new(cache),
+        }
+    }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// How rows are sampled
+    pub fn method(&self) -> SampleMethod {
+        self.method
+    }
+
+    /// Seed for the random number generator
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(
+        input: &Arc<dyn ExecutionPlan>,
+        method: &SampleMethod,
+    ) -> PlanProperties {
+        let partitioning = match method {
+            SampleMethod::Rows(_) => Partitioning::UnknownPartitioning(1),
+            SampleMethod::Bernoulli(_) | SampleMethod::System(_) => {
+                input.output_partitioning().clone()
+            }
+        };
+        PlanProperties::new(
+            input.equivalence_properties().clone(),
+            partitioning,
+            input.pipeline_behavior(),
+            input.boundedness(),
+        )
+    }
+
+    fn with_new_children_and_same_properties(
+        &self,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Self {
+        Self {
+            input: children.swap_remove(0),
+            metrics: ExecutionPlanMetricsSet::new(),
+            ..Self::clone(self)
+        }
+    }
+}
+
+impl DisplayAs for SampleExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "SampleExec: method={}", self.method)?;
+                if let Some(seed) = self.seed {
+                    write!(f, ", seed={seed}")?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "method={}", self.method)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for SampleExec {
+    fn name(&self) -> &'static str {
+        "SampleExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        match self.method {
+            SampleMethod::Rows(_) => vec![Distribution::SinglePartition],
+            SampleMethod::Bernoulli(_) | SampleMethod::System(_) => {
+                vec![Distribution::UnspecifiedDistribution]
+            }
+        }
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        check_if_same_properties!(self, children);
+        Ok(Arc::new(SampleExec::new(
+            children.swap_remove(0),
+            self.method,
+            self.seed,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let seed = self
+            .seed
+            .unwrap_or_else(rand::random)
+            .wrapping_add(partition as u64);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let schema = self.schema();
+
+        match self.method {
+            SampleMethod::Bernoulli(fraction) => {
+                let input = self.input.execute(partition, context)?;
+                let stream = input.map(move |batch| {
+                    let batch = batch?;
+                    let mask = (0..batch.num_rows())
+                        .map(|_| Some(rng.random_bool(fraction)))
+                        .collect::<BooleanArray>();
+                    let batch = filter_record_batch(&batch, &mask)?;
+                    Ok(batch.record_output(&baseline_metrics))
+                });
+                Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+            }
+            SampleMethod::System(fraction) => {
+                let input = self.input.execute(partition, context)?;
+                let stream = input
+                    .try_filter(move |_| {
+                        futures::future::ready(rng.random_bool(fraction))
+                    })
+                    .map_ok(move |batch| batch.record_output(&baseline_metrics));
+                Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+            }
+            SampleMethod::Rows(n) => {
+                assert_eq_or_internal_err!(
+                    partition,
+                    0,
+                    "SampleExec invalid partition {partition}"
+                );
+                assert_eq_or_internal_err!(
+                    self.input.output_partitioning().partition_count(),
+                    1,
+                    "SampleExec requires a single input partition"
+                );
+                let input = self.input.execute(0, Arc::clone(&context))?;
+                let reservation =
+                    MemoryConsumer::new("SampleExec").register(context.memory_pool());
+                let reservoir = Reservoir::new(n, rng, reservation);
+                let stream = stream::once(async move {
+                    let batch = reservoir.sample(input).await?;
+                    Ok(batch.record_output(&baseline_metrics))
+                })
+                .try_filter(|batch| futures::future::ready(batch.num_rows() > 0));
+                Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Arc<Statistics>> {
+        let input_stats =
+            Arc::unwrap_or_clone(self.input.partition_statistics(partition)?);
+        let num_rows = input_stats.num_rows;
+        let total_byte_size = input_stats.total_byte_size;
+        let mut stats = input_stats.to_inexact();
+        match self.method {
+            SampleMethod::Bernoulli(fraction) | SampleMethod::System(fraction) => {
+                stats.num_rows = num_rows.with_estimated_selectivity(fraction);
+                stats.total_byte_size =
+                    total_byte_size.with_estimated_selectivity(fraction);
+            }
+            SampleMethod::Rows(n) => {
+                stats.num_rows = match num_rows {
+                    Precision::Absent => Precision::Inexact(n),
+                    num_rows => num_rows.min(&Precision::Exact(n)),
+                };
+            }
+        }
+        Ok(Arc::new(stats))
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        CardinalityEffect::LowerEqual
+    }
+}
+
+/// Returns whether a block of rows identified by `key` is part of a sample of
+/// `fraction` of all blocks.
+///
+/// The result only depends on `fraction`, `seed` and `key`, so that data
+/// sources can skip the same files or row groups each time they are scanned
+/// with the same seed. The hasher is seeded with `seed` and has no per-process
+/// randomness, so the sample is also stable across processes.
+pub fn sample_block(fraction: f64, seed: u64, key: impl Hash) -> bool {
+    let hash = SeededRandomState::with_seed(seed)
+        .random_state()
+        .hash_one(key);
+    // Use the top 53 bits of the hash as a uniformly distributed value in [0, 1)
+    let value = (hash >> 11) as f64 / (1u64 << 53) as f64;
+    value < fraction
+}
+
+/// Number of input batches that may be retained by a [`Reservoir`] before the
+/// sampled rows are copied into a single batch
+const RESERVOIR_COMPACT_BATCHES: usize = 32;
+
+/// Uniformly samples a fixed number of rows from a stream of unknown length
+/// using reservoir sampling.
+///
+/// Sampled rows reference the input batch they came from. Once too many input
+/// batches are retained, the sampled rows are copied into a single batch so
+/// that memory use is bounded by the size of the sample.
+struct Reservoir {
+    /// Number of rows to sample
+    size: usize,
+    rng: StdRng,
+    /// Input batches that contain sampled rows
+    batches: Vec<RecordBatch>,
+    /// `(position in the input, batch index, row index)` of each sampled row
+    rows: Vec<(usize, usize, usize)>,
+    /// Number of input rows seen so far
+    seen: usize,
+    reservation: MemoryReservation,
+}
+
+impl Reservoir {
+    fn new(size: usize, rng: StdRng, reservation: MemoryReservation) -> Self {
+        Self {
+            size,
+            rng,
+            batches: vec![],
+            rows: Vec::with_capacity(size),
+            seen: 0,
+            reservation,
+        }
+    }
+
+    /// Consume `input` and return the sampled rows in input order
+    async fn sample(
+        mut self,
+        mut input: SendableRecordBatchStream,
+    ) -> Result<RecordBatch> {
+        let schema = input.schema();
+        while let Some(batch) = input.next().await {
+            self.insert(batch?)?;
+        }
+        let batch = self.compact()?;
+        Ok(batch.unwrap_or_else(|| RecordBatch::new_empty(schema)))
+    }
+
+    fn insert(&mut self, batch: RecordBatch) -> Result<()> {
+        let batch_idx = self.batches.len();
+        let mut retained = false;
+        for row_idx in 0..batch.num_rows() {
+            let position = self.seen + row_idx;
+            if self.rows.len() < self.size {
+                self.rows.push((position, batch_idx, row_idx));
+                retained = true;
+            } else {
+                let slot = self.rng.random_range(0..=position);
+                if slot < self.size {
+                    self.rows[slot] = (position, batch_idx, row_idx);
+                    retained = true;
+                }
+            }
+        }
+        self.seen += batch.num_rows();
+
+        if retained {
+            self.reservation.try_grow(batch.get_array_memory_size())?;
+            self.batches.push(batch);
+            if self.batches.len() >= RESERVOIR_COMPACT_BATCHES
+                && let Some(batch) = self.compact()?
+            {
+                self.reservation.try_grow(batch.get_array_memory_size())?;
+                self.rows = (0..batch.num_rows())
+                    .map(|row_idx| (self.rows[row_idx].0, 0, row_idx))
+                    .collect();
+                self.batches = vec![batch];
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the sampled rows, in input order, into a single batch and release
+    /// the retained input batches
+    fn compact(&mut self) -> Result<Option<RecordBatch>> {
+        if self.batches.is_empty() {
+            return Ok(None);
+        }
+        self.rows.sort_unstable_by_key(|(position, _, _)| *position);
+        let batches = self.batches.iter().collect::<Vec<_>>();
+        let indices = self
+            .rows
+            .iter()
+            .map(|(_, batch_idx, row_idx)| (*batch_idx, *row_idx))
+            .collect::<Vec<_>>();
+        let batch = interleave_record_batch(&batches, &indices)?;
+        self.batches.clear();
+        self.reservation.free();
+        Ok(Some(batch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::collect;
+    use crate::test::TestMemoryExec;
+
+    use arrow::array::{AsArray, Int32Array};
+    use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+
+    fn int_batches(num_batches: i32, batch_size: i32) -> Vec<RecordBatch> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        (0..num_batches)
+            .map(|b| {
+                let values = Int32Array::from_iter_values(
+                    (b * batch_size)..((b + 1) * batch_size),
+                );
+                RecordBatch::try_new(Arc::clone(&schema), vec![Arc::new(values)]).unwrap()
+            })
+            .collect()
+    }
+
+    async fn run_sample(
+        partitions: Vec<Vec<RecordBatch>>,
+        method: SampleMethod,
+        seed: Option<u64>,
+    ) -> Result<Vec<i32>> {
+        let schema = partitions[0][0].schema();
+        let input = TestMemoryExec::try_new_exec(&partitions, schema, None)?;
+        let sample: Arc<dyn ExecutionPlan> =
+            Arc::new(SampleExec::new(input, method, seed));
+        let task_ctx = Arc::new(TaskContext::default());
+        let mut values = vec![];
+        for partition in 0..sample.output_partitioning().partition_count() {
+            let stream = sample.execute(partition, Arc::clone(&task_ctx))?;
+            for batch in collect(stream).await? {
+                values.extend(batch.column(0).as_primitive::<Int32Type>().values());
+            }
+        }
+        Ok(values)
+    }
+
+    #[tokio::test]
+    async fn bernoulli_sample() -> Result<()> {
+        let batches = int_batches(10, 100);
+        let values = run_sample(
+            vec![batches.clone()],
+            SampleMethod::Bernoulli(0.5),
+            Some(42),
+        )
+        .await?;
+        assert!(values.len() > 400 && values.len() < 600, "{}", values.len());
+        assert!(values.is_sorted());
+
+        // The same seed returns the same sample
+        let again = run_sample(
+            vec![batches.clone()],
+            SampleMethod::Bernoulli(0.5),
+            Some(42),
+        )
+        .await?;
+        assert_eq!(values, again);
+
+        let none =
+            run_sample(vec![batches.clone()], SampleMethod::Bernoulli(0.0), None).await?;
+        assert!(none.is_empty());
+        let all = run_sample(vec![batches], SampleMethod::Bernoulli(1.0), None).await?;
+        assert_eq!(all.len(), 1000);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn system_sample_keeps_whole_batches() -> Result<()> {
+        let values = run_sample(
+            vec![int_batches(100, 10)],
+            SampleMethod::System(0.3),
+            Some(7),
+        )
+        .await?;
+        assert_eq!(values.len() % 10, 0);
+        assert!(values.chunks(10).all(|chunk| chunk[0] % 10 == 0));
+        assert!(values.len() > 100 && values.len() < 500, "{}", values.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rows_sample() -> Result<()> {
+        // Sample from many batches so that the reservoir is compacted
+        let batches = int_batches(100, 10);
+        let values =
+            run_sample(vec![batches.clone()], SampleMethod::Rows(25), Some(1)).await?;
+        assert_eq!(values.len(), 25);
+        assert!(values.is_sorted());
+        assert!(values.windows(2).all(|w| w[0] != w[1]));
+        let again = run_sample(vec![batches], SampleMethod::Rows(25), Some(1)).await?;
+        assert_eq!(values, again);
+
+        // Fewer input rows than the sample size
+        let values =
+            run_sample(vec![int_batches(2, 5)], SampleMethod::Rows(25), None).await?;
+        assert_eq!(values, (0..10).collect::<Vec<_>>());
+
+        let values =
+            run_sample(vec![int_batches(2, 5)], SampleMethod::Rows(0), None).await?;
+        assert!(values.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn sample_block_is_deterministic() {
+        let sampled = (0..1000)
+            .filter(|i| sample_block(0.25, 3, format!("file_{i}")))
+            .collect::<Vec<_>>();
+        assert!(sampled.len() > 150 && sampled.len() < 350);
+        let again = (0..1000)
+            .filter(|i| sample_block(0.25, 3, format!("file_{i}")))
+            .collect::<Vec<_>>();
+        assert_eq!(sampled, again);
+        assert!(!sample_block(0.0, 3, "file"));
+        assert!(sample_block(1.0, 3, "file"));
+    }
+}
@@ -26,8 +26,10 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use super::input_buffer::{EvaluationColumns, SpillableInputBuffer, SpilledPartitions};
 use super::utils::create_schema;
 use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::spill::get_record_batch_memory_size;
 use crate::stream::EmptyRecordBatchStream;
 use crate::windows::{
     calc_requirements, get_ordered_partition_by_indices, get_partition_by_sort_exprs,
@@ -52,7 +54,7 @@ use datafusion_common::utils::{
     evaluate_partition_ranges, get_at_indices, get_row_at_idx,
 };
 use datafusion_common::{
-    HashMap, Result, arrow_datafusion_err, exec_datafusion_err, exec_err,
+    HashMap, HashSet, Result, arrow_datafusion_err, exec_datafusion_err, exec_err,
 };
 use datafusion_execution::TaskContext;
 use datafusion_expr::ColumnarValue;
@@ -165,11 +167,14 @@ impl BoundedWindowAggExec {
     }
 
     /// Initializes the appropriate [`PartitionSearcher`] implementation from
-    /// the state.
-    fn get_search_algo(&self) -> Result<Box<dyn PartitionSearcher>> {
+    /// the state. `input_schema` is the schema of the batches the searcher
+    /// buffers for evaluation.
+    fn get_search_algo(
+        &self,
+        input_schema: SchemaRef,
+    ) -> Result<Box<dyn PartitionSearcher>> {
         let partition_by_sort_keys = self.partition_by_sort_keys()?;
         let ordered_partition_by_indices = self.ordered_partition_by_indices.clone();
-        let input_schema = self.input().schema();
         Ok(match &self.input_order_mode {
             InputOrderMode::Sorted => {
                 // In Sorted mode, all partition by columns should be ordered.
@@ -361,14 +366,33 @@ impl ExecutionPlan for BoundedWindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
-        let search_mode = self.get_search_algo()?;
+        let input = self.input.execute(partition, Arc::clone(&context))?;
+        let evaluation_columns =
+            EvaluationColumns::new(&self.input.schema(), &self.window_expr);
+        let search_mode =
+            self.get_search_algo(Arc::clone(evaluation_columns.schema()))?;
+        let input_rows = SpillableInputBuffer::new(
+            "BoundedWindowAggStream",
+            partition,
+            input.schema(),
+            &context,
+            &self.metrics,
+        );
+        let spilled_partitions = SpilledPartitions::new(
+            partition,
+            Arc::clone(evaluation_columns.schema()),
+            &context,
+            &self.metrics,
+        );
         let stream = Box::pin(BoundedWindowAggStream::new(
             Arc::clone(&self.schema),
             self.window_expr.clone(),
             input,
             BaselineMetrics::new(&self.metrics, partition),
             search_mode,
+            evaluation_columns,
+            input_rows,
+            spilled_partitions,
         )?);
         Ok(stream)
     }
@@ -427,13 +451,35 @@ trait PartitionSearcher: Send {
     /// cannot receive any more values.
     fn mark_partition_end(&self, partition_buffers: &mut PartitionBatches);
 
+    /// Whether the buffers of partitions that wait for more rows can be
+    /// spilled, i.e. the order of `partition_buffers` is not significant
+    /// except for its last partition.
+    fn supports_partition_spilling(&self) -> bool {
+        false
+    }
+
+    /// Returns whether the partition `partition_key`, which is not in
+    /// `partition_buffers`, cannot receive any more values.
+    fn is_partition_ended(
+        &self,
+        _partition_key: &PartitionKey,
+        _partition_buffers: &PartitionBatches,
+    ) -> bool {
+        false
+    }
+
     /// Updates `input_buffer` and `partition_buffers` with the new `record_batch`.
+    /// The rows of partitions in `spilled_partitions` are read back before
+    /// new rows are added to them, and `partition_buffers_size` is updated
+    /// with the memory used by the changed partition buffers.
     fn update_partition_batch(
         &mut self,
         input_buffer: &mut RecordBatch,
         record_batch: RecordBatch,
         window_expr: &[Arc<dyn WindowExpr>],
         partition_buffers: &mut PartitionBatches,
+        spilled_partitions: &mut SpilledPartitions,
+        partition_buffers_size: &mut usize,
     ) -> Result<()> {
         if record_batch.num_rows() == 0 {
             return Ok(());
@@ -443,7 +489,11 @@ trait PartitionSearcher: Send {
         for (partition_row, partition_batch) in partition_batches {
             if let Some(partition_batch_state) = partition_buffers.get_mut(&partition_row)
             {
-                partition_batch_state.extend(&partition_batch)?
+                let size =
+                    get_record_batch_memory_size(&partition_batch_state.record_batch);
+                partition_batch_state.extend(&partition_batch)?;
+                *partition_buffers_size = *partition_buffers_size - size
+                    + get_record_batch_memory_size(&partition_batch_state.record_batch);
             } else {
                 let options = RecordBatchOptions::new()
                     .with_row_count(Some(partition_batch.num_rows()));
@@ -456,6 +506,15 @@ trait PartitionSearcher: Send {
                     partition_batch.columns().to_vec(),
                     &options,
                 )?;
+                // The window states of a spilled partition still refer to its
+                // buffered rows, so these precede the new rows.
+                let partition_batch = match spilled_partitions.take(&partition_row)? {
+                    Some(spilled) => {
+                        concat_batches(self.input_schema(), [&spilled, &partition_batch])?
+                    }
+                    None => partition_batch,
+                };
+                *partition_buffers_size += get_record_batch_memory_size(&partition_batch);
                 let partition_batch_state =
                     PartitionBatchState::new_with_batch(partition_batch);
                 partition_buffers.insert(partition_row, partition_batch_state);
@@ -664,6 +723,29 @@ impl PartitionSearcher for LinearSearch {
         self.ordered_partition_by_indices.is_empty()
     }
 
+    fn supports_partition_spilling(&self) -> bool {
+        // Results are matched with their rows by partition key, and only the
+        // last partition is used by `mark_partition_end`
+        true
+    }
+
+    fn is_partition_ended(
+        &self,
+        partition_key: &PartitionKey,
+        partition_buffers: &PartitionBatches,
+    ) -> bool {
+        if self.ordered_partition_by_indices.is_empty() {
+            return false;
+        }
+        // Same as `mark_partition_end`, a partition is done once the sorted
+        // PARTITION BY columns of the most recent partition differ from its own
+        partition_buffers.last().is_some_and(|(last_row, _)| {
+            self.ordered_partition_by_indices
+                .iter()
+                .any(|idx| last_row[*idx] != partition_key[*idx])
+        })
+    }
+
     fn input_schema(&self) -> &SchemaRef {
         &self.input_schema
     }
@@ -921,11 +1003,19 @@ fn evaluate_partition_by_column_values(
 }
 
 /// Stream for the bounded window aggregation plan.
+///
+/// Only the input columns referenced by the window expressions (see
+/// [`EvaluationColumns`]) take part in the computation. The full input rows
+/// wait for their results in a [`SpillableInputBuffer`], which spills them to
+/// disk under memory pressure, e.g. when the results of a large partition are
+/// held back by its window frames. If that is not enough, the buffers of
+/// partitions whose results are all emitted are moved to [`SpilledPartitions`]
+/// until they receive more rows.
 pub struct BoundedWindowAggStream {
     schema: SchemaRef,
     input: SendableRecordBatchStream,
-    /// The record batch executor receives as input (i.e. the columns needed
-    /// while calculating aggregation results).
+    /// The record batch executor receives as input, projected to the columns
+    /// needed while calculating aggregation results.
     input_buffer: RecordBatch,
     /// We separate `input_buffer` based on partitions (as
     /// determined by PARTITION BY columns) and store them per partition
@@ -946,6 +1036,16 @@ pub struct BoundedWindowAggStream {
     /// Search mode for partition columns. This determines the algorithm with
     /// which we group each partition.
     search_mode: Box<dyn PartitionSearcher>,
+    evaluation_columns: EvaluationColumns,
+    /// Full input rows, waiting for their window results
+    input_rows: SpillableInputBuffer,
+    /// Window results that are ready to be emitted with the next rows of
+    /// `input_rows`
+    output: Option<Vec<ArrayRef>>,
+    /// Buffers of partitions that wait for more rows on disk
+    spilled_partitions: SpilledPartitions,
+    /// Memory used by `partition_buffers`
+    partition_buffers_size: usize,
 }
 
 impl BoundedWindowAggStream {
@@ -983,12 +1083,16 @@ impl Stream for BoundedWindowAggStream {
 
 impl BoundedWindowAggStream {
     /// Create a new BoundedWindowAggStream
+    #[expect(clippy::too_many_arguments)]
     fn new(
         schema: SchemaRef,
         window_expr: Vec<Arc<dyn WindowExpr>>,
         input: SendableRecordBatchStream,
         baseline_metrics: BaselineMetrics,
         search_mode: Box<dyn PartitionSearcher>,
+        evaluation_columns: EvaluationColumns,
+        input_rows: SpillableInputBuffer,
+        spilled_partitions: SpilledPartitions,
     ) -> Result<Self> {
         let state = window_expr.iter().map(|_| IndexMap::new()).collect();
         let empty_batch = RecordBatch::new_empty(Arc::clone(&schema));
@@ -1002,10 +1106,17 @@ impl BoundedWindowAggStream {
             window_expr,
             baseline_metrics,
             search_mode,
+            evaluation_columns,
+            input_rows,
+            output: None,
+            spilled_partitions,
+            partition_buffers_size: 0,
         })
     }
 
-    fn compute_aggregates(&mut self) -> Result<Option<RecordBatch>> {
+    /// Calculates the window expression results that can be emitted, and
+    /// prunes the state accordingly.
+    fn compute_aggregates(&mut self) -> Result<Option<Vec<ArrayRef>>> {
         // calculate window cols
         for (cur_window_expr, state) in
             self.window_expr.iter().zip(&mut self.window_agg_states)
@@ -1013,29 +1124,90 @@ impl BoundedWindowAggStream {
             cur_window_expr.evaluate_stateful(&self.partition_buffers, state)?;
         }
 
-        let schema = Arc::clone(&self.schema);
         let window_expr_out = self.search_mode.calculate_out_columns(
             &self.input_buffer,
             &self.window_agg_states,
             &mut self.partition_buffers,
             &self.window_expr,
         )?;
-        if let Some(window_expr_out) = window_expr_out {
+        if let Some(window_expr_out) = &window_expr_out {
             let n_out = window_expr_out[0].len();
-            // right append new columns to corresponding section in the original input buffer.
-            let columns_to_show = self
-                .input_buffer
-                .columns()
-                .iter()
-                .map(|elem| elem.slice(0, n_out))
-                .chain(window_expr_out)
-                .collect::<Vec<_>>();
-            let n_generated = columns_to_show[0].len();
-            self.prune_state(n_generated)?;
-            Ok(Some(RecordBatch::try_new(schema, columns_to_show)?))
-        } else {
-            Ok(None)
+            self.prune_state(n_out)?;
         }
+        Ok(window_expr_out)
+    }
+
+    /// Updates the memory reservation for the buffers used in window
+    /// expression evaluation, spilling partition buffers if the memory pool
+    /// cannot otherwise satisfy it.
+    fn update_state_reservation(&mut self) -> Result<()> {
+        let result = self.input_rows.try_resize_state(self.state_size());
+        if result.is_err() && self.spill_idle_partitions()? {
+            return self.input_rows.try_resize_state(self.state_size());
+        }
+        result
+    }
+
+    /// Returns the memory used by the buffers used in window expression
+    /// evaluation.
+    fn state_size(&self) -> usize {
+        get_record_batch_memory_size(&self.input_buffer) + self.partition_buffers_size
+    }
+
+    /// Moves the buffers of the partitions whose rows all have their window
+    /// results emitted to disk, except for the most recent partition. Returns
+    /// whether any partition buffer was spilled.
+    fn spill_idle_partitions(&mut self) -> Result<bool> {
+        if !self.search_mode.supports_partition_spilling()
+            || !self.spilled_partitions.can_spill()
+        {
+            return Ok(false);
+        }
+        // Spilled partitions that cannot receive any more rows are not needed
+        let ended = self
+            .spilled_partitions
+            .keys()
+            .filter(|key| {
+                self.search_mode
+                    .is_partition_ended(key, &self.partition_buffers)
+            })
+            .cloned()
+            .collect::<HashSet<_>>();
+        if !ended.is_empty() {
+            for key in &ended {
+                self.spilled_partitions.remove(key);
+            }
+            for window_agg_state in self.window_agg_states.iter_mut() {
+                window_agg_state.retain(|key, _| !ended.contains(key));
+            }
+        }
+
+        let last = self.partition_buffers.len().saturating_sub(1);
+        let window_agg_states = &self.window_agg_states;
+        let mut idle = vec![];
+        let mut idx = 0;
+        self.partition_buffers.retain(|key, partition_batch_state| {
+            let is_idle = idx < last
+                && !partition_batch_state.is_end
+                && partition_batch_state.record_batch.num_rows() > 0
+                && window_agg_states.iter().all(|window_agg_state| {
+                    window_agg_state.get(key).is_some_and(
+                        |WindowState { state, .. }| {
+                            state.n_row_result_missing == 0 && state.out_col.is_empty()
+                        },
+                    )
+                });
+            idx += 1;
+            if is_idle {
+                idle.push((key.clone(), partition_batch_state.record_batch.clone()));
+            }
+            !is_idle
+        });
+        for (key, batch) in idle.iter() {
+            self.partition_buffers_size -= get_record_batch_memory_size(batch);
+            self.spilled_partitions.spill(key.clone(), batch)?;
+        }
+        Ok(!idle.is_empty())
     }
 
     #[inline]
@@ -1043,44 +1215,63 @@ impl BoundedWindowAggStream {
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<RecordBatch>>> {
-        if self.finished {
-            return Poll::Ready(None);
-        }
-
         let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
-        match ready!(self.input.poll_next_unpin(cx)) {
-            Some(Ok(batch)) => {
-                // Start the timer for compute time within this operator. It will be
-                // stopped when dropped.
-                let _timer = elapsed_compute.timer();
-
-                self.search_mode.update_partition_batch(
-                    &mut self.input_buffer,
-                    batch,
-                    &self.window_expr,
-                    &mut self.partition_buffers,
-                )?;
-                if let Some(batch) = self.compute_aggregates()? {
-                    return Poll::Ready(Some(Ok(batch)));
-                }
-                self.poll_next_inner(cx)
+        loop {
+            if let Some(window_expr_out) = &self.output {
+                let n_out = window_expr_out[0].len();
+                let rows = ready!(self.input_rows.poll_take(cx, n_out))?;
+                // right append new columns to corresponding section in the original input rows.
+                let columns_to_show = rows
+                    .columns()
+                    .iter()
+                    .cloned()
+                    .chain(self.output.take().into_iter().flatten())
+                    .collect::<Vec<_>>();
+                return Poll::Ready(Some(
+                    RecordBatch::try_new(Arc::clone(&self.schema), columns_to_show)
+                        .map_err(Into::into),
+                ));
+            }
+            if self.finished {
+                return Poll::Ready(None);
             }
-            Some(Err(e)) => Poll::Ready(Some(Err(e))),
-            None => {
-                let _timer = elapsed_compute.timer();
-
-                self.finished = true;
-                // Release the input pipeline's resources before computing the
-                // final aggregates.
-                let input_schema = self.input.schema();
-                self.input = Box::pin(EmptyRecordBatchStream::new(input_schema));
-                for (_, partition_batch_state) in self.partition_buffers.iter_mut() {
-                    partition_batch_state.is_end = true;
+
+            match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    // Start the timer for compute time within this operator. It will be
+                    // stopped when dropped.
+                    let _timer = elapsed_compute.timer();
+
+                    let evaluation_batch = self.evaluation_columns.project(&batch)?;
+                    self.input_rows.push(batch)?;
+                    self.search_mode.update_partition_batch(
+                        &mut self.input_buffer,
+                        evaluation_batch,
+                        &self.window_expr,
+                        &mut self.partition_buffers,
+                        &mut self.spilled_partitions,
+                        &mut self.partition_buffers_size,
+                    )?;
+                    self.output = self.compute_aggregates()?;
+                    self.update_state_reservation()?;
                 }
-                if let Some(batch) = self.compute_aggregates()? {
-                    return Poll::Ready(Some(Ok(batch)));
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => {
+                    let _timer = elapsed_compute.timer();
+
+                    self.finished = true;
+                    // Release the input pipeline's resources before computing the
+                    // final aggregates.
+                    let input_schema = self.input.schema();
+                    self.input = Box::pin(EmptyRecordBatchStream::new(input_schema));
+                    for (_, partition_batch_state) in self.partition_buffers.iter_mut() {
+                        partition_batch_state.is_end = true;
+                    }
+                    // The results of spilled partitions are all emitted
+                    self.spilled_partitions.clear();
+                    self.output = self.compute_aggregates()?;
+                    self.update_state_reservation()?;
                 }
-                Poll::Ready(None)
             }
         }
     }
@@ -1091,8 +1282,15 @@ impl BoundedWindowAggStream {
         // Remove partitions which we know already ended (is_end flag is true).
         // Since the retain method preserves insertion order, we still have
         // ordering in between partitions after removal.
-        self.partition_buffers
-            .retain(|_, partition_batch_state| !partition_batch_state.is_end);
+        let mut removed_size = 0;
+        self.partition_buffers.retain(|_, partition_batch_state| {
+            if partition_batch_state.is_end {
+                removed_size +=
+                    get_record_batch_memory_size(&partition_batch_state.record_batch);
+            }
+            !partition_batch_state.is_end
+        });
+        self.partition_buffers_size -= removed_size;
 
         // The data in `self.partition_batches` is used by all window expressions.
         // Therefore, when removing from `self.partition_batches`, we need to remove
@@ -1123,7 +1321,10 @@ impl BoundedWindowAggStream {
 
         // Retract no longer needed parts during window calculations from partition batch:
         for (partition_row, n_prune) in n_prune_each_partition.iter() {
-            let pb_state = &mut self.partition_buffers[partition_row];
+            // Spilled partitions are pruned once their rows are read back
+            let Some(pb_state) = self.partition_buffers.get_mut(partition_row) else {
+                continue;
+            };
 
             let batch = &pb_state.record_batch;
             pb_state.record_batch = batch.slice(*n_prune, batch.num_rows() - n_prune);
@@ -1174,8 +1375,11 @@ impl BoundedWindowAggStream {
                 },
             ) in partition_window_agg_states
             {
-                let partition_batch = &mut self.partition_buffers[partition_key];
-                let n_to_del = partition_batch.n_out_row;
+                // The results of spilled partitions are all emitted
+                let n_to_del = self
+                    .partition_buffers
+                    .get(partition_key)
+                    .map_or(0, |partition_batch| partition_batch.n_out_row);
                 let n_to_keep = out_col.len() - n_to_del;
                 *out_col = out_col.slice(n_to_del, n_to_keep);
             }
@@ -1269,24 +1473,27 @@ mod tests {
     use crate::{ExecutionPlan, displayable, execute_stream};
 
     use arrow::array::{
-        RecordBatch,
+        ArrayRef, Int64Array, RecordBatch, StringArray,
         builder::{Int64Builder, UInt64Builder},
     };
-    use arrow::compute::SortOptions;
+    use arrow::compute::{SortOptions, concat_batches};
     use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
     use datafusion_common::test_util::batches_to_string;
     use datafusion_common::{Result, ScalarValue, exec_datafusion_err};
     use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::memory_pool::FairSpillPool;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_execution::{
         RecordBatchStream, SendableRecordBatchStream, TaskContext,
     };
     use datafusion_expr::{
-        WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunctionDefinition,
+        Operator, WindowFrame, WindowFrameBound, WindowFrameUnits,
+        WindowFunctionDefinition,
     };
     use datafusion_functions_aggregate::count::count_udaf;
     use datafusion_functions_window::nth_value::last_value_udwf;
     use datafusion_functions_window::nth_value::nth_value_udwf;
-    use datafusion_physical_expr::expressions::{Column, Literal, col};
+    use datafusion_physical_expr::expressions::{Column, Literal, binary, col, lit};
     use datafusion_physical_expr::window::StandardWindowExpr;
     use datafusion_physical_expr::{LexOrdering, PhysicalExpr};
 
@@ -1860,4 +2067,156 @@ mod tests {
         ));
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_bounded_window_agg_spill() -> Result<()> {
+        const NUM_ROWS: usize = 32 * 1024;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("v", DataType::Int64, false),
+            Field::new("payload", DataType::Utf8, false),
+        ]));
+        let batches = (0..NUM_ROWS / 1024)
+            .map(|batch_idx| {
+                let values = (0..1024).map(|row| (batch_idx * 1024 + row) as i64);
+                let payload = (0..1024).map(|row| format!("{row:0>256}"));
+                Ok(RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![
+                        Arc::new(Int64Array::from_iter_values(values)),
+                        Arc::new(StringArray::from_iter_values(payload)),
+                    ],
+                )?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let input = TestMemoryExec::try_new_exec(
+            std::slice::from_ref(&batches),
+            Arc::clone(&schema),
+            None,
+        )?;
+        // Each row waits for the 4096 rows following it before it is emitted
+        let window_expr = create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            "count(v)".to_string(),
+            &[col("v", &schema)?],
+            &[],
+            &[],
+            Arc::new(WindowFrame::new_bounds(
+                WindowFrameUnits::Rows,
+                WindowFrameBound::CurrentRow,
+                WindowFrameBound::Following(ScalarValue::UInt64(Some(4096))),
+            )),
+            Arc::clone(&schema),
+            false,
+            false,
+            None,
+        )?;
+        let window = Arc::new(BoundedWindowAggExec::try_new(
+            vec![window_expr],
+            input,
+            InputOrderMode::Sorted,
+            false,
+        )?);
+
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(Arc::new(FairSpillPool::new(512 * 1024)))
+            .build_arc()?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+        let result = collect(window.execute(0, task_ctx)?).await?;
+
+        let metrics = window.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert!(metrics.spilled_rows().unwrap() > 0);
+
+        let input = concat_batches(&schema, &batches)?;
+        let result = concat_batches(&window.schema(), &result)?;
+        let expected_counts: ArrayRef = Arc::new(Int64Array::from_iter_values(
+            (0..NUM_ROWS).map(|row| (NUM_ROWS - row).min(4097) as i64),
+        ));
+        assert_eq!(result.num_rows(), NUM_ROWS);
+        assert_eq!(result.columns()[..2], input.columns()[..]);
+        assert_eq!(result.column(2), &expected_counts);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bounded_window_agg_spill_partitions() -> Result<()> {
+        const NUM_ROWS: usize = 48 * 1024;
+        const NUM_PARTITIONS: usize = 12;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("v", DataType::Int64, false),
+            Field::new("payload", DataType::Utf8, false),
+        ]));
+        let batches = (0..NUM_ROWS / 1024)
+            .map(|batch_idx| {
+                let values = (0..1024).map(|row| (batch_idx * 1024 + row) as i64);
+                let payload = (0..1024).map(|row| format!("{row:0>256}"));
+                Ok(RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![
+                        Arc::new(Int64Array::from_iter_values(values)),
+                        Arc::new(StringArray::from_iter_values(payload)),
+                    ],
+                )?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let input = TestMemoryExec::try_new_exec(
+            std::slice::from_ref(&batches),
+            Arc::clone(&schema),
+            None,
+        )?;
+        // Each input batch belongs to a single partition of
+        // `(v / 1024) % 12`, and each partition keeps its last 256 rows
+        let partition_by = binary(
+            binary(col("v", &schema)?, Operator::Divide, lit(1024i64), &schema)?,
+            Operator::Modulo,
+            lit(NUM_PARTITIONS as i64),
+            &schema,
+        )?;
+        let window_expr = create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            "count(payload)".to_string(),
+            &[col("payload", &schema)?],
+            &[partition_by],
+            &[PhysicalSortExpr::new_default(col("v", &schema)?)],
+            Arc::new(WindowFrame::new_bounds(
+                WindowFrameUnits::Rows,
+                WindowFrameBound::Preceding(ScalarValue::UInt64(Some(255))),
+                WindowFrameBound::CurrentRow,
+            )),
+            Arc::clone(&schema),
+            false,
+            false,
+            None,
+        )?;
+        let window = Arc::new(BoundedWindowAggExec::try_new(
+            vec![window_expr],
+            input,
+            InputOrderMode::Linear,
+            false,
+        )?);
+
+        // The buffers of all partitions do not fit into the memory pool, so
+        // the idle ones have to be spilled
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(Arc::new(FairSpillPool::new(1024 * 1024)))
+            .build_arc()?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+        let result = collect(window.execute(0, task_ctx)?).await?;
+
+        let metrics = window.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+
+        let input = concat_batches(&schema, &batches)?;
+        let result = concat_batches(&window.schema(), &result)?;
+        let expected_counts: ArrayRef =
+            Arc::new(Int64Array::from_iter_values((0..NUM_ROWS).map(|row| {
+                let batch_idx = row / 1024;
+                let partition_row = batch_idx / NUM_PARTITIONS * 1024 + row % 1024;
+                (partition_row + 1).min(256) as i64
+            })));
+        assert_eq!(result.num_rows(), NUM_ROWS);
+        assert_eq!(result.columns()[..2], input.columns()[..]);
+        assert_eq!(result.column(2), &expected_counts);
+        Ok(())
+    }
 }
@@ -0,0 +1,491 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Spill-backed buffering of the input rows of window operators.
+//!
+//! Window operators need two things from their input: the values window
+//! functions are evaluated over (arguments, PARTITION BY and ORDER BY
+//! columns, see [`EvaluationColumns`]), and the complete input rows, which are
+//! only needed again when the window results are appended to them. Both can
+//! be moved to disk under memory pressure:
+//!
+//! * [`SpillableInputBuffer`] holds rows in their input order and reads them
+//!   back, in order, when they are needed. It buffers the complete input rows
+//!   until their window results are emitted, and the evaluation columns of
+//!   whole partitions until they are evaluated.
+//! * [`SpilledPartitions`] holds the evaluation columns of window partitions
+//!   that wait for more input rows, and reads them back once these arrive.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::BufReader;
+use std::mem;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::metrics::{ExecutionPlanMetricsSet, SpillMetrics};
+use crate::spill::get_record_batch_memory_size;
+use crate::spill::spill_manager::SpillManager;
+use crate::spill::spill_pool::{self, SpillPoolWriter};
+use crate::windows::WindowUDFExpr;
+use crate::{SendableRecordBatchStream, WindowExpr};
+
+use arrow::array::{ArrayRef, NullArray, RecordBatch, RecordBatchOptions};
+use arrow::compute::concat_batches;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::ipc::reader::StreamReader;
+use datafusion_common::{Result, internal_err};
+use datafusion_execution::TaskContext;
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_physical_expr::utils::collect_columns;
+use datafusion_physical_expr::window::{
+    AggregateWindowExpr, PartitionKey, PlainAggregateWindowExpr,
+    SlidingAggregateWindowExpr, StandardWindowExpr,
+};
+use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
+
+use futures::{StreamExt, ready};
+
+/// The input columns window expressions are evaluated over.
+///
+/// Columns that no window expression references are replaced by
+/// [`NullArray`]s (and typed as [`DataType::Null`] in [`Self::schema`]), so
+/// that batches kept for window evaluation do not retain them. Column indices
+/// are unchanged, hence window expressions evaluate against projected batches
+/// exactly as they would against the input.
+#[derive(Debug, Clone)]
+pub(crate) struct EvaluationColumns {
+    /// Schema of the projected batches
+    schema: SchemaRef,
+    /// Whether the input column at each index is retained
+    retained: Vec<bool>,
+}
+
+impl EvaluationColumns {
+    /// Determines the columns `window_expr` reference. All columns are
+    /// retained if any of the expressions is of a type whose inputs cannot be
+    /// fully inspected.
+    pub(crate) fn new(
+        input_schema: &SchemaRef,
+        window_expr: &[Arc<dyn WindowExpr>],
+    ) -> Self {
+        let num_fields = input_schema.fields().len();
+        let Some(referenced) = referenced_columns(window_expr) else {
+            return Self {
+                schema: Arc::clone(input_schema),
+                retained: vec![true; num_fields],
+            };
+        };
+        let retained = (0..num_fields)
+            .map(|idx| referenced.contains(&idx))
+            .collect::<Vec<_>>();
+        let fields = input_schema
+            .fields()
+            .iter()
+            .zip(&retained)
+            .map(|(field, retained)| {
+                if *retained {
+                    Arc::clone(field)
+                } else {
+                    Arc::new(Field::new(field.name(), DataType::Null, true))
+                }
+            })
+            .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        ));
+        Self { schema, retained }
+    }
+
+    /// Schema of the batches returned by [`Self::project`]
+    pub(crate) fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Replaces the columns of `batch` that are not referenced by any
+    /// window expression with [`NullArray`]s.
+    pub(crate) fn project(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(&self.retained)
+            .map(|(column, retained)| {
+                if *retained {
+                    Arc::clone(column)
+                } else {
+                    Arc::new(NullArray::new(num_rows)) as ArrayRef
+                }
+            })
+            .collect();
+        // Use the evaluation schema rather than `batch.schema()`, which may
+        // differ in nullability, see https://github.com/apache/datafusion/issues/9320
+        Ok(RecordBatch::try_new_with_options(
+            Arc::clone(&self.schema),
+            columns,
+            &RecordBatchOptions::new().with_row_count(Some(num_rows)),
+        )?)
+    }
+}
+
+/// Returns the indices of all input columns referenced by `window_expr`, or
+/// `None` if some expression is not one of the known window expression types.
+fn referenced_columns(window_expr: &[Arc<dyn WindowExpr>]) -> Option<HashSet<usize>> {
+    let mut exprs: Vec<Arc<dyn PhysicalExpr>> = vec![];
+    for expr in window_expr {
+        let all_expressions = expr.all_expressions();
+        exprs.extend(all_expressions.args);
+        exprs.extend(all_expressions.partition_by_exprs);
+        exprs.extend(all_expressions.order_by_exprs);
+
+        let any = expr.as_any();
+        if let Some(standard) = any.downcast_ref::<StandardWindowExpr>() {
+            if let Some(udwf) = standard
+                .get_standard_func_expr()
+                .as_any()
+                .downcast_ref::<WindowUDFExpr>()
+            {
+                exprs.extend(udwf.args().iter().cloned());
+            }
+        } else if let Some(aggregate) = any.downcast_ref::<PlainAggregateWindowExpr>() {
+            exprs.extend(aggregate.filter_expr().cloned());
+            exprs.extend(
+                aggregate
+                    .get_aggregate_expr()
+                    .order_bys()
+                    .iter()
+                    .map(|sort_expr| Arc::clone(&sort_expr.expr)),
+            );
+        } else if let Some(aggregate) = any.downcast_ref::<SlidingAggregateWindowExpr>() {
+            exprs.extend(aggregate.filter_expr().cloned());
+            exprs.extend(
+                aggregate
+                    .get_aggregate_expr()
+                    .order_bys()
+                    .iter()
+                    .map(|sort_expr| Arc::clone(&sort_expr.expr)),
+            );
+        } else {
+            return None;
+        }
+    }
+    Some(
+        exprs
+            .iter()
+            .flat_map(collect_columns)
+            .map(|column| column.index())
+            .collect(),
+    )
+}
+
+/// Creates a [`SpillManager`] for batches of `schema`, or returns `None` if the
+/// disk manager of `context` cannot create temporary files.
+fn spill_manager(
+    partition: usize,
+    schema: SchemaRef,
+    context: &TaskContext,
+    metrics: &ExecutionPlanMetricsSet,
+) -> Option<SpillManager> {
+    let runtime = context.runtime_env();
+    runtime.disk_manager.tmp_files_enabled().then(|| {
+        SpillManager::new(runtime, SpillMetrics::new(metrics, partition), schema)
+            .with_compression_type(context.session_config().spill_compression())
+    })
+}
+
+/// A FIFO buffer of input rows that are waiting to be read back, either for
+/// their window results or for evaluation.
+///
+/// Rows are kept in memory while the memory pool allows it. Once a
+/// reservation fails, buffered rows are written to a spill pool (see
+/// [`spill_pool::channel`]) and read back, in their original order, by
+/// [`Self::poll_take`]. Rows pushed while earlier rows are spilled are kept
+/// in memory again if possible, so the buffer consists of
+///
+/// ```text
+/// [ head (in memory) ] [ spilled rows ] [ tail (in memory) ]
+/// ```
+///
+/// The buffer also owns the reservation for the operator's unspillable
+/// evaluation state, so that growing that state can make room by spilling
+/// buffered rows, see [`Self::try_resize_state`].
+pub(crate) struct SpillableInputBuffer {
+    schema: SchemaRef,
+    /// Batches preceding the spilled rows, along with their reserved size
+    head: VecDeque<(RecordBatch, usize)>,
+    /// Number of rows in the spill pool that have not been read back yet
+    spilled_rows: usize,
+    /// Batches following the spilled rows, along with their reserved size.
+    /// Always empty when there are no spilled rows.
+    tail: VecDeque<(RecordBatch, usize)>,
+    /// Slices collected by an in-progress [`Self::poll_take`]
+    taken: Vec<RecordBatch>,
+    /// Total number of rows in `taken`
+    taken_rows: usize,
+    /// Writer and reader of the spill pool, `None` if spilling is disabled
+    spill: Option<(SpillPoolWriter, SendableRecordBatchStream)>,
+    /// Reservation for the buffered rows
+    reservation: MemoryReservation,
+    /// Reservation for the unspillable state of the operator
+    state_reservation: MemoryReservation,
+}
+
+impl SpillableInputBuffer {
+    /// Creates a new buffer for rows of `schema`. Memory is reserved through
+    /// consumers named `{name}[{partition}]` (buffered rows) and
+    /// `{name}State[{partition}]` (evaluation state). Spilling is enabled if
+    /// the disk manager of `context` can create temporary files.
+    pub(crate) fn new(
+        name: &str,
+        partition: usize,
+        schema: SchemaRef,
+        context: &TaskContext,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> Self {
+        let spill_manager =
+            spill_manager(partition, Arc::clone(&schema), context, metrics);
+        let reservation = MemoryConsumer::new(format!("{name}[{partition}]"))
+            .with_can_spill(spill_manager.is_some())
+            .register(context.memory_pool());
+        let state_reservation = MemoryConsumer::new(format!("{name}State[{partition}]"))
+            .register(context.memory_pool());
+        let spill = spill_manager.map(|spill_manager| {
+            let max_file_size = context
+                .session_config()
+                .options()
+                .execution
+                .max_spill_file_size_bytes;
+            spill_pool::channel(max_file_size, Arc::new(spill_manager))
+        });
+        Self {
+            schema,
+            head: VecDeque::new(),
+            spilled_rows: 0,
+            tail: VecDeque::new(),
+            taken: vec![],
+            taken_rows: 0,
+            spill,
+            reservation,
+            state_reservation,
+        }
+    }
+
+    /// Appends `batch` to the end of the buffer, spilling buffered rows if
+    /// the memory pool cannot accommodate it.
+    pub(crate) fn push(&mut self, batch: RecordBatch) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let size = get_record_batch_memory_size(&batch);
+        if self.spill.is_none() {
+            self.reservation.try_grow(size)?;
+        } else if self.reservation.try_grow(size).is_err() {
+            self.spill()?;
+            return self.spill_batch(&batch);
+        }
+        if self.spilled_rows == 0 {
+            self.head.push_back((batch, size));
+        } else {
+            self.tail.push_back((batch, size));
+        }
+        Ok(())
+    }
+
+    /// Moves all buffered rows that can be spilled without breaking their
+    /// order to disk. This is a no-op if spilling is disabled.
+    pub(crate) fn spill(&mut self) -> Result<()> {
+        if self.spill.is_none() {
+            return Ok(());
+        }
+        // Rows in `head` can only be spilled if nothing precedes them in the
+        // spill pool, as they would otherwise be read back out of order.
+        let batches = if self.spilled_rows == 0 {
+            mem::take(&mut self.head)
+        } else {
+            VecDeque::new()
+        };
+        for (batch, size) in batches.into_iter().chain(mem::take(&mut self.tail)) {
+            self.spill_batch(&batch)?;
+            self.reservation.shrink(size);
+        }
+        Ok(())
+    }
+
+    fn spill_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        let Some((writer, _)) = &self.spill else {
+            return internal_err!("Spilling is disabled for this window input buffer");
+        };
+        writer.push_batch(batch)?;
+        self.spilled_rows += batch.num_rows();
+        Ok(())
+    }
+
+    /// Resizes the reservation for the unspillable state of the operator to
+    /// `size`, spilling buffered rows first if the memory pool cannot
+    /// otherwise satisfy it.
+    pub(crate) fn try_resize_state(&mut self, size: usize) -> Result<()> {
+        if self.state_reservation.try_resize(size).is_err() {
+            self.spill()?;
+            self.state_reservation.try_resize(size)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the first `n` rows from the buffer and returns them as a single
+    /// batch, reading them back from disk if they were spilled.
+    pub(crate) fn poll_take(
+        &mut self,
+        cx: &mut Context<'_>,
+        n: usize,
+    ) -> Poll<Result<RecordBatch>> {
+        while self.taken_rows < n {
+            let needed = n - self.taken_rows;
+            if let Some((batch, size)) = self.head.pop_front() {
+                let num_rows = batch.num_rows();
+                if num_rows > needed {
+                    self.taken.push(batch.slice(0, needed));
+                    self.head
+                        .push_front((batch.slice(needed, num_rows - needed), size));
+                    self.taken_rows += needed;
+                } else {
+                    self.reservation.shrink(size);
+                    self.taken.push(batch);
+                    self.taken_rows += num_rows;
+                }
+            } else if self.spilled_rows > 0 {
+                let Some((_, reader)) = &mut self.spill else {
+                    return Poll::Ready(internal_err!(
+                        "Window input buffer has spilled rows but no spill reader"
+                    ));
+                };
+                let batch = match ready!(reader.poll_next_unpin(cx)) {
+                    Some(Ok(batch)) => batch,
+                    Some(Err(e)) => return Poll::Ready(Err(e)),
+                    None => {
+                        return Poll::Ready(internal_err!(
+                            "Spill pool of window input buffer ended with {} rows outstanding",
+                            self.spilled_rows
+                        ));
+                    }
+                };
+                // These rows are about to be emitted, so they are read back
+                // even if the memory pool is exhausted.
+                let size = get_record_batch_memory_size(&batch);
+                self.reservation.grow(size);
+                self.spilled_rows = self.spilled_rows.saturating_sub(batch.num_rows());
+                self.head.push_back((batch, size));
+                if self.spilled_rows == 0 {
+                    self.head.append(&mut self.tail);
+                }
+            } else {
+                return Poll::Ready(internal_err!(
+                    "Window input buffer has {needed} fewer rows than requested"
+                ));
+            }
+        }
+        self.taken_rows = 0;
+        let mut taken = mem::take(&mut self.taken);
+        let batch = if taken.len() == 1 {
+            taken.swap_remove(0)
+        } else {
+            concat_batches(&self.schema, &taken)?
+        };
+        Poll::Ready(Ok(batch))
+    }
+}
+
+/// The buffered rows of window partitions that were moved to disk.
+///
+/// A partition whose rows all have their window results emitted only keeps
+/// rows because the window frames of future rows may include them. Such a
+/// partition can be spilled with [`Self::spill`] until it receives more rows,
+/// at which point [`Self::take`] reads its rows back.
+pub(crate) struct SpilledPartitions {
+    /// `None` if spilling is disabled
+    spill_manager: Option<SpillManager>,
+    /// The spill file of each spilled partition
+    files: HashMap<PartitionKey, RefCountedTempFile>,
+}
+
+impl SpilledPartitions {
+    /// Creates an empty set of spilled partitions, whose rows have the given
+    /// `schema`. Spilling is enabled if the disk manager of `context` can
+    /// create temporary files.
+    pub(crate) fn new(
+        partition: usize,
+        schema: SchemaRef,
+        context: &TaskContext,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> Self {
+        Self {
+            spill_manager: spill_manager(partition, schema, context, metrics),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Returns whether partitions can be spilled
+    pub(crate) fn can_spill(&self) -> bool {
+        self.spill_manager.is_some()
+    }
+
+    /// Returns the keys of the spilled partitions
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &PartitionKey> {
+        self.files.keys()
+    }
+
+    /// Moves the rows of the partition `key` to disk
+    pub(crate) fn spill(&mut self, key: PartitionKey, batch: &RecordBatch) -> Result<()> {
+        let Some(spill_manager) = &self.spill_manager else {
+            return internal_err!("Spilling is disabled for these window partitions");
+        };
+        let Some(file) = spill_manager.spill_record_batch_and_finish(
+            std::slice::from_ref(batch),
+            "WindowPartition",
+        )?
+        else {
+            return internal_err!("Window partition was not written to its spill file");
+        };
+        self.files.insert(key, file);
+        Ok(())
+    }
+
+    /// Reads the rows of the partition `key` back, or returns `None` if the
+    /// partition is not spilled.
+    pub(crate) fn take(&mut self, key: &PartitionKey) -> Result<Option<RecordBatch>> {
+        let (Some(spill_manager), Some(file)) =
+            (&self.spill_manager, self.files.remove(key))
+        else {
+            return Ok(None);
+        };
+        let reader =
+            StreamReader::try_new(BufReader::new(File::open(file.path())?), None)?;
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(concat_batches(spill_manager.schema(), &batches)?))
+    }
+
+    /// Discards the spilled partition `key`
+    pub(crate) fn remove(&mut self, key: &PartitionKey) {
+        self.files.remove(key);
+    }
+
+    /// Discards all spilled partitions
+    pub(crate) fn clear(&mut self) {
+        self.files.clear();
+    }
+}
@@ -0,0 +1,854 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Row pattern recognition (SQL `MATCH_RECOGNIZE`), see [`MatchRecognizeExec`]
+
+mod pattern;
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use self::pattern::{MatchedRow, Matcher, Program};
+use crate::execution_plan::EmissionType;
+use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::windows::{
+    calc_requirements, get_ordered_partition_by_indices, get_partition_by_sort_exprs,
+};
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
+    PhysicalExpr, PlanProperties, RecordBatchStream, SendableRecordBatchStream,
+    check_if_same_properties,
+};
+
+use arrow::array::{
+    ArrayRef, AsArray, RecordBatch, StringArray, UInt32Array, UInt64Array,
+};
+use arrow::compute::{concat_batches, take, take_record_batch};
+use arrow::datatypes::SchemaRef;
+use datafusion_common::utils::evaluate_partition_ranges;
+use datafusion_common::{
+    Result, ScalarValue, exec_err, internal_datafusion_err, plan_err,
+};
+use datafusion_execution::TaskContext;
+use datafusion_expr::{AfterMatchSkip, RowPattern, RowsPerMatch};
+use datafusion_physical_expr::EquivalenceProperties;
+use datafusion_physical_expr::aggregate::AggregateFunctionExpr;
+use datafusion_physical_expr_common::sort_expr::{
+    OrderingRequirements, PhysicalSortExpr,
+};
+
+use futures::{Stream, StreamExt, ready};
+
+/// Finds the sequences of rows of each partition of its input that match a
+/// [`RowPattern`] (SQL `MATCH_RECOGNIZE`), see
+/// [`datafusion_expr::MatchRecognize`] for the semantics.
+///
+/// The input must be sorted by the partition keys and the order keys, so
+/// each partition is processed as a stream of rows: the rows are mapped to
+/// the pattern variables whose `DEFINE` condition holds, and a [`Matcher`]
+/// advances over them until the preferred match starting at a row is known.
+/// Only the rows from the start of the current match attempt on are
+/// buffered, so unbounded sorted inputs are supported.
+#[derive(Debug, Clone)]
+pub struct MatchRecognizeExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The expressions that divide the input into partitions
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    /// The order of the rows within each partition
+    order_by: Vec<PhysicalSortExpr>,
+    /// The values computed for each match, or each row of a match
+    measures: Vec<MatchRecognizeMeasure>,
+    rows_per_match: RowsPerMatch,
+    after_match_skip: AfterMatchSkip,
+    pattern: RowPattern,
+    /// The conditions of the pattern variables
+    defines: Vec<(String, Arc<dyn PhysicalExpr>)>,
+    /// The compiled pattern
+    program: Arc<Program>,
+    /// Schema after the pattern is matched
+    schema: SchemaRef,
+    /// Partition by indices that defines preset for existing ordering
+    // see `get_ordered_partition_by_indices` for more details.
+    ordered_partition_by_indices: Vec<usize>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: Arc<PlanProperties>,
+}
+
+impl MatchRecognizeExec {
+    /// Create a new execution plan for row pattern recognition, `schema` is
+    /// the partition by columns (for [`RowsPerMatch::One`]) or the input
+    /// columns (for [`RowsPerMatch::All`]) followed by the measures
+    #[expect(clippy::too_many_arguments)]
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        partition_by: Vec<Arc<dyn PhysicalExpr>>,
+        order_by: Vec<PhysicalSortExpr>,
+        measures: Vec<MatchRecognizeMeasure>,
+        rows_per_match: RowsPerMatch,
+        after_match_skip: AfterMatchSkip,
+        pattern: RowPattern,
+        defines: Vec<(String, Arc<dyn PhysicalExpr>)>,
+        schema: SchemaRef,
+    ) -> Result<Self> {
+        let program = Program::try_new(&pattern)?;
+        let variables = defines
+            .iter()
+            .map(|(variable, _)| variable)
+            .chain(measures.iter().filter_map(|m| m.variable.as_ref()));
+        for variable in variables {
+            if program.variable_index(variable).is_none() {
+                return plan_err!(
+                    "Pattern variable {variable} is not defined in PATTERN {pattern}"
+                );
+            }
+        }
+        let num_input_columns = match rows_per_match {
+            RowsPerMatch::One => partition_by.len(),
+            RowsPerMatch::All => input.schema().fields().len(),
+        };
+        if schema.fields().len() != num_input_columns + measures.len() {
+            return plan_err!(
+                "MatchRecognizeExec schema has wrong number of fields. Expected {} got {}",
+                num_input_columns + measures.len(),
+                schema.fields().len()
+            );
+        }
+
+        let ordered_partition_by_indices =
+            get_ordered_partition_by_indices(&partition_by, &input)?;
+        let cache = Self::compute_properties(&schema, &input);
+        Ok(Self {
+            input,
+            partition_by,
+            order_by,
+            measures,
+            rows_per_match,
+            after_match_skip,
+            pattern,
+            defines,
+            program: Arc::new(program),
+            schema,
+            ordered_partition_by_indices,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache: Arc::new(cache),
+        })
+    }
+
+    /// Input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The expressions that divide the input into partitions
+    pub fn partition_by(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.partition_by
+    }
+
+    /// The order of the rows within each partition
+    pub fn order_by(&self) -> &[PhysicalSortExpr] {
+        &self.order_by
+    }
+
+    /// The values computed for each match, or each row of a match
+    pub fn measures(&self) -> &[MatchRecognizeMeasure] {
+        &self.measures
+    }
+
+    /// Whether one row is produced per match or per matched row
+    pub fn rows_per_match(&self) -> RowsPerMatch {
+        self.rows_per_match
+    }
+
+    /// Where the search for the next match resumes after a match
+    pub fn after_match_skip(&self) -> &AfterMatchSkip {
+        &self.after_match_skip
+    }
+
+    /// The pattern to match
+    pub fn pattern(&self) -> &RowPattern {
+        &self.pattern
+    }
+
+    /// The conditions of the pattern variables
+    pub fn defines(&self) -> &[(String, Arc<dyn PhysicalExpr>)] {
+        &self.defines
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(
+        schema: &SchemaRef,
+        input: &Arc<dyn ExecutionPlan>,
+    ) -> PlanProperties {
+        PlanProperties::new(
+            EquivalenceProperties::new(Arc::clone(schema)),
+            input.output_partitioning().clone(),
+            EmissionType::Incremental,
+            input.boundedness(),
+        )
+    }
+
+    fn with_new_children_and_same_properties(
+        &self,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Self {
+        Self {
+            input: children.swap_remove(0),
+            metrics: ExecutionPlanMetricsSet::new(),
+            ..Self::clone(self)
+        }
+    }
+}
+
+impl DisplayAs for MatchRecognizeExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let partition_by = self
+            .partition_by
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let measures = self
+            .measures
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let order_by = self
+                    .order_by
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let defines = self
+                    .defines
+                    .iter()
+                    .map(|(variable, condition)| format!("{variable} AS {condition}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "MatchRecognizeExec: partition_by=[{partition_by}], order_by=[{order_by}], measures=[{measures}], {}, AFTER MATCH SKIP {}, pattern=[{}], define=[{defines}]",
+                    self.rows_per_match, self.after_match_skip, self.pattern
+                )
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "partition_by={partition_by}")?;
+                writeln!(f, "measures={measures}")?;
+                writeln!(f, "pattern={}", self.pattern)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for MatchRecognizeExec {
+    fn name(&self) -> &'static str {
+        "MatchRecognizeExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<OrderingRequirements>> {
+        if self.ordered_partition_by_indices.len() < self.partition_by.len() {
+            vec![calc_requirements(&self.partition_by, &self.order_by)]
+        } else {
+            let partition_bys = self
+                .ordered_partition_by_indices
+                .iter()
+                .map(|idx| &self.partition_by[*idx]);
+            vec![calc_requirements(partition_bys, &self.order_by)]
+        }
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.partition_by.is_empty() {
+            vec![Distribution::SinglePartition]
+        } else {
+            vec![Distribution::HashPartitioned(self.partition_by.clone())]
+        }
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        check_if_same_properties!(self, children);
+        Ok(Arc::new(MatchRecognizeExec::try_new(
+            children.swap_remove(0),
+            self.partition_by.clone(),
+            self.order_by.clone(),
+            self.measures.clone(),
+            self.rows_per_match,
+            self.after_match_skip.clone(),
+            self.pattern.clone(),
+            self.defines.clone(),
+            Arc::clone(&self.schema),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context)?;
+        let partition_by_sort_keys = get_partition_by_sort_exprs(
+            &self.input,
+            &self.partition_by,
+            &self.ordered_partition_by_indices,
+        )?;
+        let stream = MatchRecognizeStream::try_new(
+            self,
+            input,
+            partition_by_sort_keys,
+            BaselineMetrics::new(&self.metrics, partition),
+        )?;
+        Ok(Box::pin(stream))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+/// A measure of [`MatchRecognizeExec`], see [`datafusion_expr::RowPatternMeasure`]
+#[derive(Debug, Clone)]
+pub struct MatchRecognizeMeasure {
+    /// The name of the output column
+    pub name: String,
+    /// Restricts the rows of the match to the rows mapped to this pattern
+    /// variable, or all rows of the match if `None`
+    pub variable: Option<String>,
+    /// The function computed over the rows of the match
+    pub function: MeasureFunction,
+}
+
+impl fmt::Display for MatchRecognizeMeasure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let variable = match &self.variable {
+            Some(variable) => format!("{variable}: "),
+            None => String::new(),
+        };
+        match &self.function {
+            MeasureFunction::First(expr) => write!(f, "FIRST({variable}{expr})")?,
+            MeasureFunction::Last(expr) => write!(f, "LAST({variable}{expr})")?,
+            MeasureFunction::Aggregate(expr) => match &self.variable {
+                Some(variable) => write!(f, "{} FILTER (WHERE {variable})", expr.name())?,
+                None => write!(f, "{}", expr.name())?,
+            },
+            MeasureFunction::Classifier => write!(f, "CLASSIFIER()")?,
+            MeasureFunction::MatchNumber => write!(f, "MATCH_NUMBER()")?,
+        }
+        write!(f, " AS {}", self.name)
+    }
+}
+
+/// A function computed over the rows of a match, see
+/// [`datafusion_expr::RowPatternFunction`]
+#[derive(Debug, Clone)]
+pub enum MeasureFunction {
+    /// The value of the expression for the first row
+    First(Arc<dyn PhysicalExpr>),
+    /// The value of the expression for the last row
+    Last(Arc<dyn PhysicalExpr>),
+    /// An aggregate function over the rows
+    Aggregate(Arc<AggregateFunctionExpr>),
+    /// The name of the pattern variable of the last row
+    Classifier,
+    /// The sequential number of the match within its partition
+    MatchNumber,
+}
+
+/// A match of the pattern in the current partition
+struct FoundMatch {
+    /// The partition row index of the first row
+    start: usize,
+    rows: Vec<MatchedRow>,
+    number: u64,
+}
+
+/// The buffered rows and matching state of the current partition
+struct PartitionState {
+    /// The values of the partition keys, `None` before the first row
+    key: Option<Vec<ScalarValue>>,
+    /// The buffered rows, starting at partition row index `offset`
+    buffer: RecordBatch,
+    /// For each buffered row, the bits of the variables it can be mapped to
+    masks: Vec<u64>,
+    offset: usize,
+    /// The partition row index of the current match attempt
+    next_start: usize,
+    /// The current match attempt and the number of rows it consumed
+    matcher: Option<(Matcher, usize)>,
+    /// The number of matches found in the partition
+    num_matches: u64,
+}
+
+impl PartitionState {
+    fn new(input_schema: &SchemaRef) -> Self {
+        Self {
+            key: None,
+            buffer: RecordBatch::new_empty(Arc::clone(input_schema)),
+            masks: vec![],
+            offset: 0,
+            next_start: 0,
+            matcher: None,
+            num_matches: 0,
+        }
+    }
+}
+
+/// Stream for [`MatchRecognizeExec`]
+struct MatchRecognizeStream {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    partition_by_sort_keys: Vec<PhysicalSortExpr>,
+    ordered_partition_by_indices: Vec<usize>,
+    rows_per_match: RowsPerMatch,
+    program: Arc<Program>,
+    /// The conditions of the pattern variables, by variable index
+    defines: Vec<Option<Arc<dyn PhysicalExpr>>>,
+    /// The measures with the variable index they are restricted to
+    measures: Vec<(Option<usize>, MeasureFunction)>,
+    /// [`AfterMatchSkip`] with the variable index for `TO FIRST` or `TO LAST`
+    after_match_skip: (AfterMatchSkip, Option<usize>),
+    partition: PartitionState,
+    finished: bool,
+    baseline_metrics: BaselineMetrics,
+}
+
+impl MatchRecognizeStream {
+    fn try_new(
+        exec: &MatchRecognizeExec,
+        input: SendableRecordBatchStream,
+        partition_by_sort_keys: Vec<PhysicalSortExpr>,
+        baseline_metrics: BaselineMetrics,
+    ) -> Result<Self> {
+        let program = Arc::clone(&exec.program);
+        let variable_index = |name: &str| {
+            program.variable_index(name).ok_or_else(|| {
+                internal_datafusion_err!("Unknown pattern variable {name}")
+            })
+        };
+        let mut defines = vec![None; program.variables().len()];
+        for (variable, condition) in &exec.defines {
+            defines[variable_index(variable)?] = Some(Arc::clone(condition));
+        }
+        let measures = exec
+            .measures
+            .iter()
+            .map(|measure| {
+                let variable = measure
+                    .variable
+                    .as_deref()
+                    .map(variable_index)
+                    .transpose()?;
+                Ok((variable, measure.function.clone()))
+            })
+            .collect::<Result<_>>()?;
+        let skip_variable = match &exec.after_match_skip {
+            AfterMatchSkip::ToFirst(variable) | AfterMatchSkip::ToLast(variable) => {
+                Some(variable_index(variable)?)
+            }
+            AfterMatchSkip::PastLastRow | AfterMatchSkip::ToNextRow => None,
+        };
+        let partition = PartitionState::new(&input.schema());
+        Ok(Self {
+            schema: Arc::clone(&exec.schema),
+            input,
+            partition_by: exec.partition_by.clone(),
+            partition_by_sort_keys,
+            ordered_partition_by_indices: exec.ordered_partition_by_indices.clone(),
+            rows_per_match: exec.rows_per_match,
+            program,
+            defines,
+            measures,
+            after_match_skip: (exec.after_match_skip.clone(), skip_variable),
+            partition,
+            finished: false,
+            baseline_metrics,
+        })
+    }
+
+    /// Processes an input batch, returning the output for the matches that
+    /// are completed by it
+    fn process_batch(&mut self, batch: &RecordBatch) -> Result<Option<RecordBatch>> {
+        let sort_columns = self
+            .ordered_partition_by_indices
+            .iter()
+            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(batch))
+            .collect::<Result<Vec<_>>>()?;
+        let ranges = evaluate_partition_ranges(batch.num_rows(), &sort_columns)?;
+
+        let mut output = vec![];
+        for range in ranges {
+            let key = sort_columns
+                .iter()
+                .map(|column| ScalarValue::try_from_array(&column.values, range.start))
+                .collect::<Result<Vec<_>>>()?;
+            if self.partition.key.as_ref().is_some_and(|k| *k != key) {
+                output.extend(self.process_partition(true)?);
+                self.partition = PartitionState::new(&self.input.schema());
+            }
+            self.partition.key = Some(key);
+            self.append(&batch.slice(range.start, range.end - range.start))?;
+            output.extend(self.process_partition(false)?);
+        }
+        self.concat_output(&output)
+    }
+
+    /// Completes the last partition at the end of the input
+    fn process_end(&mut self) -> Result<Option<RecordBatch>> {
+        let output = self.process_partition(true)?;
+        self.partition = PartitionState::new(&self.input.schema());
+        self.concat_output(output.as_slice())
+    }
+
+    fn concat_output(&self, output: &[RecordBatch]) -> Result<Option<RecordBatch>> {
+        if output.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(concat_batches(&self.schema, output)?))
+    }
+
+    /// Appends rows of the current partition to the buffer
+    fn append(&mut self, rows: &RecordBatch) -> Result<()> {
+        let mut masks = vec![0u64; rows.num_rows()];
+        for (variable, condition) in self.defines.iter().enumerate() {
+            let bit = 1 << variable;
+            let Some(condition) = condition else {
+                // Variables without a condition match every row
+                masks.iter_mut().for_each(|mask| *mask |= bit);
+                continue;
+            };
+            let values = condition.evaluate(rows)?.into_array(rows.num_rows())?;
+            let Some(values) = values.as_boolean_opt() else {
+                return exec_err!(
+                    "DEFINE condition {condition} must be a boolean, got {}",
+                    values.data_type()
+                );
+            };
+            for (mask, value) in masks.iter_mut().zip(values) {
+                if value == Some(true) {
+                    *mask |= bit;
+                }
+            }
+        }
+
+        let partition = &mut self.partition;
+        partition.buffer = concat_batches(&rows.schema(), [&partition.buffer, rows])?;
+        partition.masks.extend(masks);
+        Ok(())
+    }
+
+    /// Finds the matches in the buffered rows of the current partition,
+    /// which has no more rows if `complete`, and returns their output
+    fn process_partition(&mut self, complete: bool) -> Result<Option<RecordBatch>> {
+        let program = Arc::clone(&self.program);
+        let (skip, skip_variable) = &self.after_match_skip;
+        let partition = &mut self.partition;
+        let end = partition.offset + partition.masks.len();
+        let mut found = vec![];
+        loop {
+            let (matcher, consumed) = match &mut partition.matcher {
+                Some(matcher) => matcher,
+                None if partition.next_start < end => partition
+                    .matcher
+                    .insert((Matcher::new(&program, partition.next_start == 0), 0)),
+                None => break,
+            };
+            while !matcher.is_done() && partition.next_start + *consumed < end {
+                let row = partition.next_start + *consumed - partition.offset;
+                matcher.advance(&program, partition.masks[row]);
+                *consumed += 1;
+            }
+            if !matcher.is_done() {
+                if !complete {
+                    // Wait for more rows of the partition
+                    break;
+                }
+                matcher.finish(&program);
+            }
+
+            let (matcher, _) = partition.matcher.take().unwrap();
+            let start = partition.next_start;
+            let Some(rows) = matcher.into_match() else {
+                partition.next_start += 1;
+                continue;
+            };
+            partition.next_start =
+                after_match_skip_row(skip, *skip_variable, start, &rows)?;
+            partition.num_matches += 1;
+            found.push(FoundMatch {
+                start,
+                rows,
+                number: partition.num_matches,
+            });
+        }
+
+        let output = self.build_output(&found)?;
+
+        // Release the rows before the current match attempt
+        let partition = &mut self.partition;
+        let first = (partition.next_start - partition.offset).min(partition.masks.len());
+        if first > 0 {
+            partition.buffer = partition
+                .buffer
+                .slice(first, partition.buffer.num_rows() - first);
+            partition.masks.drain(..first);
+            partition.offset += first;
+        }
+        Ok(output)
+    }
+
+    /// Computes the output rows of the matches from the buffered rows
+    fn build_output(&self, found: &[FoundMatch]) -> Result<Option<RecordBatch>> {
+        if found.is_empty() {
+            return Ok(None);
+        }
+        let partition = &self.partition;
+        let buffer = &partition.buffer;
+        let num_rows = buffer.num_rows();
+
+        // The arguments of the measures, evaluated on the buffered rows
+        let measure_args = self
+            .measures
+            .iter()
+            .map(|(_, function)| match function {
+                MeasureFunction::First(expr) | MeasureFunction::Last(expr) => {
+                    Ok(vec![expr.evaluate(buffer)?.into_array(num_rows)?])
+                }
+                MeasureFunction::Aggregate(expr) => expr
+                    .expressions()
+                    .iter()
+                    .map(|arg| arg.evaluate(buffer)?.into_array(num_rows))
+                    .collect(),
+                MeasureFunction::Classifier | MeasureFunction::MatchNumber => Ok(vec![]),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The buffer row of each output row, and the values of the measures
+        let mut indices = vec![];
+        let mut measure_values = vec![MeasureValues::default(); self.measures.len()];
+        for found_match in found {
+            let base = found_match.start - partition.offset;
+            let mut first = vec![None; self.measures.len()];
+            let mut last = vec![None; self.measures.len()];
+            let mut accumulators = self
+                .measures
+                .iter()
+                .map(|(_, function)| match function {
+                    MeasureFunction::Aggregate(expr) => {
+                        expr.create_accumulator().map(Some)
+                    }
+                    _ => Ok(None),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let last_row = found_match.rows.len() - 1;
+            for (i, row) in found_match.rows.iter().enumerate() {
+                let index = (base + i) as u32;
+                for (m, (variable, _)) in self.measures.iter().enumerate() {
+                    if variable.is_some_and(|variable| variable != row.variable) {
+                        continue;
+                    }
+                    first[m].get_or_insert(index);
+                    last[m] = Some(index);
+                    if let Some(accumulator) = &mut accumulators[m] {
+                        let args = measure_args[m]
+                            .iter()
+                            .map(|arg| arg.slice(base + i, 1))
+                            .collect::<Vec<_>>();
+                        accumulator.update_batch(&args)?;
+                    }
+                }
+
+                // Running semantics for every row or final semantics for the
+                // last row
+                let emit = match self.rows_per_match {
+                    RowsPerMatch::One => i == last_row,
+                    RowsPerMatch::All => !row.excluded,
+                };
+                if !emit {
+                    continue;
+                }
+                indices.push(match self.rows_per_match {
+                    RowsPerMatch::One => base as u32,
+                    RowsPerMatch::All => index,
+                });
+                for (m, (_, function)) in self.measures.iter().enumerate() {
+                    let values = &mut measure_values[m];
+                    match function {
+                        MeasureFunction::First(_) => values.indices.push(first[m]),
+                        MeasureFunction::Last(_) => values.indices.push(last[m]),
+                        MeasureFunction::Aggregate(_) => values
+                            .scalars
+                            .push(accumulators[m].as_mut().unwrap().evaluate()?),
+                        MeasureFunction::Classifier => values
+                            .classifiers
+                            .push(&self.program.variables()[row.variable]),
+                        MeasureFunction::MatchNumber => {
+                            values.numbers.push(found_match.number)
+                        }
+                    }
+                }
+            }
+        }
+
+        let indices = UInt32Array::from(indices);
+        let mut columns = match self.rows_per_match {
+            RowsPerMatch::One => self
+                .partition_by
+                .iter()
+                .map(|expr| {
+                    let values = expr.evaluate(buffer)?.into_array(num_rows)?;
+                    Ok(take(&values, &indices, None)?)
+                })
+                .collect::<Result<Vec<_>>>()?,
+            RowsPerMatch::All => take_record_batch(buffer, &indices)?.columns().to_vec(),
+        };
+        for ((values, args), (_, function)) in measure_values
+            .into_iter()
+            .zip(measure_args)
+            .zip(&self.measures)
+        {
+            let column: ArrayRef = match function {
+                MeasureFunction::First(_) | MeasureFunction::Last(_) => {
+                    take(&args[0], &UInt32Array::from(values.indices), None)?
+                }
+                MeasureFunction::Aggregate(_) => {
+                    ScalarValue::iter_to_array(values.scalars)?
+                }
+                MeasureFunction::Classifier => {
+                    Arc::new(StringArray::from(values.classifiers))
+                }
+                MeasureFunction::MatchNumber => {
+                    Arc::new(UInt64Array::from(values.numbers))
+                }
+            };
+            columns.push(column);
+        }
+        Ok(Some(RecordBatch::try_new(
+            Arc::clone(&self.schema),
+            columns,
+        )?))
+    }
+}
+
+/// Returns the partition row index of the next match attempt after a
+/// match starting at `start`
+fn after_match_skip_row(
+    skip: &AfterMatchSkip,
+    variable: Option<usize>,
+    start: usize,
+    rows: &[MatchedRow],
+) -> Result<usize> {
+    let row = match skip {
+        AfterMatchSkip::PastLastRow => return Ok(start + rows.len()),
+        AfterMatchSkip::ToNextRow => return Ok(start + 1),
+        AfterMatchSkip::ToFirst(_) => {
+            rows.iter().position(|row| Some(row.variable) == variable)
+        }
+        AfterMatchSkip::ToLast(_) => {
+            rows.iter().rposition(|row| Some(row.variable) == variable)
+        }
+    };
+    match row {
+        Some(0) => exec_err!(
+            "AFTER MATCH SKIP {skip} would resume at the first row of the match"
+        ),
+        Some(row) => Ok(start + row),
+        None => exec_err!(
+            "AFTER MATCH SKIP {skip} failed: no row of the match is mapped to the pattern variable"
+        ),
+    }
+}
+
+/// The values of a measure for the output rows
+#[derive(Default, Clone)]
+struct MeasureValues<'a> {
+    indices: Vec<Option<u32>>,
+    scalars: Vec<ScalarValue>,
+    classifiers: Vec<&'a str>,
+    numbers: Vec<u64>,
+}
+
+impl Stream for MatchRecognizeStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_inner(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl MatchRecognizeStream {
+    #[inline]
+    fn poll_next_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+        loop {
+            let result = match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    let _timer = elapsed_compute.timer();
+                    self.process_batch(&batch)
+                }
+                Some(Err(e)) => Err(e),
+                None => {
+                    self.finished = true;
+                    let _timer = elapsed_compute.timer();
+                    self.process_end()
+                }
+            };
+            match result {
+                Ok(Some(batch)) => return Poll::Ready(Some(Ok(batch))),
+                Ok(None) if self.finished => return Poll::Ready(None),
+                Ok(None) => continue,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+}
+
+impl RecordBatchStream for MatchRecognizeStream {
+    /// Get the schema
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
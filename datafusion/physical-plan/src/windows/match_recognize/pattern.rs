@@ -0,0 +1,458 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Matching of [`RowPattern`]s with a Pike VM: a [`Program`] is simulated
+//! as a nondeterministic automaton by a [`Matcher`], which advances all
+//! alive threads one row at a time. Threads are kept in priority order so
+//! the first match found by the highest priority thread is the preferred
+//! match (the first alternative and the longest repetition).
+
+use std::sync::Arc;
+
+use datafusion_common::{Result, not_impl_err};
+use datafusion_expr::RowPattern;
+use itertools::Itertools;
+
+/// The maximum number of pattern variables, which are represented as a bit
+/// mask for each row
+pub(super) const MAX_VARIABLES: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Inst {
+    /// Consumes a row mapped to the variable
+    Row { variable: usize, excluded: bool },
+    /// Continues at both targets, preferring the first
+    Split(usize, usize),
+    /// Continues at the target
+    Jump(usize),
+    /// Continues if no row was consumed in the partition yet
+    PartitionStart,
+    /// Continues if all rows of the partition were consumed
+    PartitionEnd,
+    /// The pattern matched
+    Match,
+}
+
+/// A [`RowPattern`] compiled to instructions of a Pike VM
+#[derive(Debug)]
+pub(super) struct Program {
+    insts: Vec<Inst>,
+    /// The names of the pattern variables, indexed by their bit in the masks
+    variables: Vec<String>,
+}
+
+impl Program {
+    pub(super) fn try_new(pattern: &RowPattern) -> Result<Self> {
+        let variables = pattern
+            .variables()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if variables.len() > MAX_VARIABLES {
+            return not_impl_err!(
+                "MATCH_RECOGNIZE supports at most {MAX_VARIABLES} pattern variables"
+            );
+        }
+        let mut program = Self {
+            insts: vec![],
+            variables,
+        };
+        program.compile(pattern, false);
+        program.insts.push(Inst::Match);
+        Ok(program)
+    }
+
+    /// Returns the names of the pattern variables, in the order of their
+    /// bits in the masks passed to [`Matcher::advance`]
+    pub(super) fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// Returns the index of the variable
+    pub(super) fn variable_index(&self, name: &str) -> Option<usize> {
+        self.variables.iter().position(|variable| variable == name)
+    }
+
+    fn push(&mut self, inst: Inst) -> usize {
+        self.insts.push(inst);
+        self.insts.len() - 1
+    }
+
+    fn compile(&mut self, pattern: &RowPattern, excluded: bool) {
+        match pattern {
+            RowPattern::Variable(name) => {
+                let variable = self.variable_index(name).unwrap();
+                self.push(Inst::Row { variable, excluded });
+            }
+            RowPattern::PartitionStart => {
+                self.push(Inst::PartitionStart);
+            }
+            RowPattern::PartitionEnd => {
+                self.push(Inst::PartitionEnd);
+            }
+            RowPattern::Exclude(pattern) => self.compile(pattern, true),
+            RowPattern::Concat(patterns) => {
+                for pattern in patterns {
+                    self.compile(pattern, excluded);
+                }
+            }
+            RowPattern::Alternation(patterns) => {
+                self.compile_alternation(patterns, excluded)
+            }
+            RowPattern::Permute(patterns) => {
+                // All orders, in lexicographic order of the listed patterns
+                let orders = (0..patterns.len())
+                    .permutations(patterns.len())
+                    .map(|order| {
+                        RowPattern::Concat(
+                            order.into_iter().map(|i| patterns[i].clone()).collect(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                self.compile_alternation(&orders, excluded)
+            }
+            RowPattern::Repetition { pattern, min, max } => {
+                for _ in 0..*min {
+                    self.compile(pattern, excluded);
+                }
+                match max {
+                    None => {
+                        let split = self.push(Inst::Split(0, 0));
+                        self.compile(pattern, excluded);
+                        self.push(Inst::Jump(split));
+                        self.insts[split] = Inst::Split(split + 1, self.insts.len());
+                    }
+                    Some(max) => {
+                        let splits = (*min..*max)
+                            .map(|_| {
+                                let split = self.push(Inst::Split(0, 0));
+                                self.compile(pattern, excluded);
+                                split
+                            })
+                            .collect::<Vec<_>>();
+                        let end = self.insts.len();
+                        for split in splits {
+                            self.insts[split] = Inst::Split(split + 1, end);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn compile_alternation(&mut self, patterns: &[RowPattern], excluded: bool) {
+        let mut jumps = vec![];
+        for (i, pattern) in patterns.iter().enumerate() {
+            if i + 1 == patterns.len() {
+                self.compile(pattern, excluded);
+            } else {
+                let split = self.push(Inst::Split(0, 0));
+                self.compile(pattern, excluded);
+                jumps.push(self.push(Inst::Jump(0)));
+                self.insts[split] = Inst::Split(split + 1, self.insts.len());
+            }
+        }
+        let end = self.insts.len();
+        for jump in jumps {
+            self.insts[jump] = Inst::Jump(end);
+        }
+    }
+}
+
+/// The rows consumed by a thread, as a list linked from the last row
+#[derive(Debug)]
+struct PathNode {
+    variable: usize,
+    excluded: bool,
+    prev: Option<Arc<PathNode>>,
+}
+
+// Drop long paths iteratively rather than recursively
+impl Drop for PathNode {
+    fn drop(&mut self) {
+        let mut prev = self.prev.take();
+        while let Some(node) = prev {
+            prev = match Arc::try_unwrap(node) {
+                Ok(mut node) => node.prev.take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Thread {
+    pc: usize,
+    path: Option<Arc<PathNode>>,
+}
+
+/// A row of a match: the index of its pattern variable and whether it is
+/// excluded from the output of `ALL ROWS PER MATCH`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct MatchedRow {
+    pub(super) variable: usize,
+    pub(super) excluded: bool,
+}
+
+/// Finds the preferred match of a [`Program`] starting at a row
+#[derive(Debug)]
+pub(super) struct Matcher {
+    /// The alive threads in priority order, each waiting at a
+    /// [`Inst::Row`] or [`Inst::PartitionEnd`] instruction
+    threads: Vec<Thread>,
+    /// The path of the preferred match found so far
+    matched: Option<Option<Arc<PathNode>>>,
+    /// Whether the first row has not been consumed yet
+    at_partition_start: bool,
+    /// Reused by each step to visit every instruction at most once
+    visited: Vec<bool>,
+}
+
+impl Matcher {
+    /// Starts matching at a row, `at_partition_start` is true for the first
+    /// row of the partition
+    pub(super) fn new(program: &Program, at_partition_start: bool) -> Self {
+        let mut matcher = Self {
+            threads: vec![],
+            matched: None,
+            at_partition_start,
+            visited: vec![false; program.insts.len()],
+        };
+        let mut threads = vec![];
+        matcher.add_thread(program, &mut threads, 0, None);
+        matcher.threads = threads;
+        matcher
+    }
+
+    /// Returns true if no thread is alive, i.e. [`Self::into_match`] returns
+    /// the preferred match
+    pub(super) fn is_done(&self) -> bool {
+        self.threads.is_empty()
+    }
+
+    /// Consumes the next row, `mask` has the bits of the variables that
+    /// the row can be mapped to set
+    pub(super) fn advance(&mut self, program: &Program, mask: u64) {
+        self.visited.fill(false);
+        self.at_partition_start = false;
+        let mut next = vec![];
+        for thread in std::mem::take(&mut self.threads) {
+            if let Inst::Row { variable, excluded } = program.insts[thread.pc]
+                && mask & (1 << variable) != 0
+            {
+                let path = Arc::new(PathNode {
+                    variable,
+                    excluded,
+                    prev: thread.path,
+                });
+                if self.add_thread(program, &mut next, thread.pc + 1, Some(path)) {
+                    // Lower priority threads can only find less preferred matches
+                    break;
+                }
+            }
+        }
+        self.threads = next;
+    }
+
+    /// Ends the partition after the consumed rows
+    pub(super) fn finish(&mut self, program: &Program) {
+        self.visited.fill(false);
+        let mut next = vec![];
+        for thread in std::mem::take(&mut self.threads) {
+            if program.insts[thread.pc] == Inst::PartitionEnd
+                && self.add_thread(program, &mut next, thread.pc + 1, thread.path)
+            {
+                break;
+            }
+        }
+        // Threads waiting for more rows or another partition end can not match
+        self.threads.clear();
+    }
+
+    /// Returns the rows of the preferred match, or `None` if the pattern did
+    /// not match or only matched no rows
+    pub(super) fn into_match(self) -> Option<Vec<MatchedRow>> {
+        let mut rows = vec![];
+        let mut node = self.matched??;
+        loop {
+            rows.push(MatchedRow {
+                variable: node.variable,
+                excluded: node.excluded,
+            });
+            match &node.prev {
+                Some(prev) => node = Arc::clone(prev),
+                None => break,
+            }
+        }
+        rows.reverse();
+        Some(rows)
+    }
+
+    /// Adds the threads reachable from `pc` without consuming a row to
+    /// `threads` in priority order, returning true if the pattern matched
+    fn add_thread(
+        &mut self,
+        program: &Program,
+        threads: &mut Vec<Thread>,
+        pc: usize,
+        path: Option<Arc<PathNode>>,
+    ) -> bool {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if std::mem::replace(&mut self.visited[pc], true) {
+                continue;
+            }
+            match program.insts[pc] {
+                Inst::Jump(target) => stack.push(target),
+                Inst::Split(preferred, other) => {
+                    stack.push(other);
+                    stack.push(preferred);
+                }
+                Inst::PartitionStart => {
+                    if self.at_partition_start {
+                        stack.push(pc + 1);
+                    }
+                }
+                Inst::Row { .. } | Inst::PartitionEnd => threads.push(Thread {
+                    pc,
+                    path: path.clone(),
+                }),
+                Inst::Match => {
+                    self.matched = Some(path);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str) -> RowPattern {
+        RowPattern::Variable(name.to_string())
+    }
+
+    fn repeat(pattern: RowPattern, min: u32, max: Option<u32>) -> RowPattern {
+        RowPattern::Repetition {
+            pattern: Box::new(pattern),
+            min,
+            max,
+        }
+    }
+
+    /// Matches the pattern against rows given as the variables each row can
+    /// be mapped to, returning the variables of the matched rows
+    fn find_match(pattern: &RowPattern, rows: &[&str]) -> Option<String> {
+        let program = Program::try_new(pattern).unwrap();
+        let mut matcher = Matcher::new(&program, true);
+        for row in rows {
+            if matcher.is_done() {
+                break;
+            }
+            let mask = row
+                .chars()
+                .filter_map(|c| program.variable_index(&c.to_string()))
+                .fold(0, |mask, variable| mask | (1 << variable));
+            matcher.advance(&program, mask);
+        }
+        matcher.finish(&program);
+        matcher.into_match().map(|rows| {
+            rows.iter()
+                .map(|row| {
+                    let name = &program.variables()[row.variable];
+                    if row.excluded {
+                        name.to_lowercase()
+                    } else {
+                        name.clone()
+                    }
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn greedy_repetition() {
+        let pattern =
+            RowPattern::Concat(vec![var("A"), repeat(var("B"), 1, None), var("C")]);
+        assert_eq!(
+            find_match(&pattern, &["A", "B", "BC", "BC", "C"]),
+            Some("ABBBC".to_string())
+        );
+        // Backtracks to the last row that can be mapped to C
+        assert_eq!(
+            find_match(&pattern, &["A", "BC", "BC", "B"]),
+            Some("ABC".to_string())
+        );
+        assert_eq!(find_match(&pattern, &["A", "C"]), None);
+
+        let pattern = repeat(var("A"), 2, Some(3));
+        assert_eq!(find_match(&pattern, &["A"; 5]), Some("AAA".to_string()));
+        assert_eq!(find_match(&pattern, &["A", "B"]), None);
+    }
+
+    #[test]
+    fn alternation_prefers_first() {
+        let pattern = RowPattern::Alternation(vec![
+            RowPattern::Concat(vec![var("A"), var("B")]),
+            RowPattern::Concat(vec![var("A"), var("B"), var("C")]),
+        ]);
+        assert_eq!(
+            find_match(&pattern, &["AB", "AB", "C"]),
+            Some("AB".to_string())
+        );
+        assert_eq!(
+            find_match(&pattern, &["A", "C"]),
+            None,
+            "neither alternative matches"
+        );
+
+        let pattern = RowPattern::Permute(vec![var("A"), var("B")]);
+        assert_eq!(find_match(&pattern, &["AB", "AB"]), Some("AB".to_string()));
+        assert_eq!(find_match(&pattern, &["B", "A"]), Some("BA".to_string()));
+    }
+
+    #[test]
+    fn anchors_and_exclusion() {
+        let pattern = RowPattern::Concat(vec![
+            RowPattern::PartitionStart,
+            var("A"),
+            RowPattern::Exclude(Box::new(repeat(var("B"), 0, None))),
+            RowPattern::PartitionEnd,
+        ]);
+        assert_eq!(
+            find_match(&pattern, &["A", "B", "B"]),
+            Some("Abb".to_string())
+        );
+        assert_eq!(find_match(&pattern, &["A", "B", "A"]), None);
+
+        let program = Program::try_new(&pattern).unwrap();
+        assert!(Matcher::new(&program, false).is_done());
+    }
+
+    #[test]
+    fn empty_match() {
+        let pattern = repeat(var("A"), 0, None);
+        assert_eq!(find_match(&pattern, &["B"]), None);
+        assert_eq!(find_match(&pattern, &["A", "B"]), Some("A".to_string()));
+        // Empty loop iterations terminate
+        let pattern = repeat(repeat(var("A"), 0, None), 0, None);
+        assert_eq!(find_match(&pattern, &["A", "A"]), Some("AA".to_string()));
+    }
+}
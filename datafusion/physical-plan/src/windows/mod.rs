@@ -18,6 +18,8 @@
 //! Physical expressions for window functions
 
 mod bounded_window_agg_exec;
+mod input_buffer;
+mod match_recognize;
 mod utils;
 mod window_agg_exec;
 
@@ -56,6 +58,7 @@ pub use bounded_window_agg_exec::BoundedWindowAggExec;
 pub use datafusion_physical_expr::window::{
     PlainAggregateWindowExpr, StandardWindowExpr, WindowExpr,
 };
+pub use match_recognize::{MatchRecognizeExec, MatchRecognizeMeasure, MeasureFunction};
 pub use window_agg_exec::WindowAggExec;
 
 /// Build field from window function and add it into schema
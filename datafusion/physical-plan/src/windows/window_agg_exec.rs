@@ -17,10 +17,13 @@
 
 //! Stream and channel implementations for window function expressions.
 
+use std::cmp::min;
+use std::collections::VecDeque;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use super::input_buffer::{EvaluationColumns, SpillableInputBuffer};
 use super::utils::create_schema;
 use crate::execution_plan::{CardinalityEffect, EmissionType};
 use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
@@ -35,14 +38,14 @@ use crate::{
     SendableRecordBatchStream, Statistics, WindowExpr, check_if_same_properties,
 };
 
-use arrow::array::ArrayRef;
-use arrow::compute::{concat, concat_batches};
+use arrow::array::{Array, ArrayRef};
+use arrow::compute::concat;
 use arrow::datatypes::SchemaRef;
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::stats::Precision;
-use datafusion_common::utils::{evaluate_partition_ranges, transpose};
-use datafusion_common::{Result, assert_eq_or_internal_err};
+use datafusion_common::utils::{evaluate_partition_ranges, get_row_at_idx, transpose};
+use datafusion_common::{Result, ScalarValue, assert_eq_or_internal_err};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr_common::sort_expr::{
     OrderingRequirements, PhysicalSortExpr,
@@ -264,7 +267,23 @@ impl ExecutionPlan for WindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, Arc::clone(&context))?;
+        let input_rows = SpillableInputBuffer::new(
+            "WindowAggStream",
+            partition,
+            input.schema(),
+            &context,
+            &self.metrics,
+        );
+        let partitions = SpillableInputBuffer::new(
+            "WindowAggStreamPartitions",
+            partition,
+            Arc::clone(
+                EvaluationColumns::new(&input.schema(), &self.window_expr).schema(),
+            ),
+            &context,
+            &self.metrics,
+        );
         let stream = Box::pin(WindowAggStream::new(
             Arc::clone(&self.schema),
             self.window_expr.clone(),
@@ -272,6 +291,9 @@ impl ExecutionPlan for WindowAggExec {
             BaselineMetrics::new(&self.metrics, partition),
             self.partition_by_sort_keys()?,
             self.ordered_partition_by_indices.clone(),
+            input_rows,
+            partitions,
+            context.session_config().batch_size(),
         )?);
         Ok(stream)
     }
@@ -316,26 +338,50 @@ fn compute_window_aggregates(
 }
 
 /// stream for window aggregation plan
+///
+/// Input rows are buffered in a [`SpillableInputBuffer`], and the columns
+/// referenced by the window expressions (see [`EvaluationColumns`]) in a
+/// second one, both of which spill to disk under memory pressure. Once the
+/// input is exhausted, whole partitions are read back and evaluated, at least
+/// `batch_size` rows at a time, and their window results are emitted in
+/// `batch_size` chunks alongside the input rows read back from the first
+/// buffer. Only the partitions being evaluated are held in memory.
 pub struct WindowAggStream {
     schema: SchemaRef,
     input: SendableRecordBatchStream,
-    batches: Vec<RecordBatch>,
+    /// Input rows projected to the columns window expressions reference,
+    /// waiting for evaluation
+    partitions: SpillableInputBuffer,
+    /// Number of rows of each partition in `partitions`
+    partition_lengths: VecDeque<usize>,
+    /// PARTITION BY values of the last partition in `partitions`
+    last_partition_key: Option<Vec<ScalarValue>>,
     finished: bool,
     window_expr: Vec<Arc<dyn WindowExpr>>,
     partition_by_sort_keys: Vec<PhysicalSortExpr>,
     baseline_metrics: BaselineMetrics,
     ordered_partition_by_indices: Vec<usize>,
+    evaluation_columns: EvaluationColumns,
+    /// Full input rows, waiting for their window results
+    input_rows: SpillableInputBuffer,
+    /// Computed window results, along with the number of rows emitted so far
+    output: Option<(Vec<ArrayRef>, usize)>,
+    batch_size: usize,
 }
 
 impl WindowAggStream {
     /// Create a new WindowAggStream
-    pub fn new(
+    #[expect(clippy::too_many_arguments)]
+    pub(crate) fn new(
         schema: SchemaRef,
         window_expr: Vec<Arc<dyn WindowExpr>>,
         input: SendableRecordBatchStream,
         baseline_metrics: BaselineMetrics,
         partition_by_sort_keys: Vec<PhysicalSortExpr>,
         ordered_partition_by_indices: Vec<usize>,
+        input_rows: SpillableInputBuffer,
+        partitions: SpillableInputBuffer,
+        batch_size: usize,
     ) -> Result<Self> {
         // In WindowAggExec all partition by columns should be ordered.
         assert_eq_or_internal_err!(
@@ -343,43 +389,99 @@ impl WindowAggStream {
             ordered_partition_by_indices.len(),
             "All partition by columns should have an ordering"
         );
+        let evaluation_columns = EvaluationColumns::new(&input.schema(), &window_expr);
         Ok(Self {
             schema,
             input,
-            batches: vec![],
+            partitions,
+            partition_lengths: VecDeque::new(),
+            last_partition_key: None,
             finished: false,
             window_expr,
             baseline_metrics,
             partition_by_sort_keys,
             ordered_partition_by_indices,
+            evaluation_columns,
+            input_rows,
+            output: None,
+            batch_size,
         })
     }
 
-    fn compute_aggregates(&self) -> Result<Option<RecordBatch>> {
-        // record compute time on drop
-        let _timer = self.baseline_metrics.elapsed_compute().timer();
-
-        let batch = concat_batches(&self.input.schema(), &self.batches)?;
+    /// Buffers an input batch: the full rows in `input_rows`, and the
+    /// columns needed for evaluation in `partitions`.
+    fn buffer_batch(&mut self, batch: RecordBatch) -> Result<()> {
         if batch.num_rows() == 0 {
-            return Ok(None);
+            return Ok(());
         }
+        let evaluation_batch = self.evaluation_columns.project(&batch)?;
+        self.input_rows.push(batch)?;
 
-        let partition_by_sort_keys = self
+        let sort_columns = self
             .ordered_partition_by_indices
             .iter()
-            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(&batch))
+            .map(|idx| {
+                self.partition_by_sort_keys[*idx]
+                    .evaluate_to_sort_column(&evaluation_batch)
+            })
             .collect::<Result<Vec<_>>>()?;
+        let partition_by_columns = sort_columns
+            .iter()
+            .map(|sort_column| Arc::clone(&sort_column.values))
+            .collect::<Vec<_>>();
         let partition_points =
-            evaluate_partition_ranges(batch.num_rows(), &partition_by_sort_keys)?;
+            evaluate_partition_ranges(evaluation_batch.num_rows(), &sort_columns)?;
+        for partition_point in partition_points {
+            let key = get_row_at_idx(&partition_by_columns, partition_point.start)?;
+            let length = partition_point.end - partition_point.start;
+            // The input is sorted on the PARTITION BY columns, so a partition
+            // can only continue the last one
+            match self.partition_lengths.back_mut() {
+                Some(last) if self.last_partition_key.as_ref() == Some(&key) => {
+                    *last += length
+                }
+                _ => self.partition_lengths.push_back(length),
+            }
+            self.last_partition_key = Some(key);
+        }
+        self.partitions.push(evaluation_batch)
+    }
+
+    /// Returns the number of leading partitions in `partitions` that make up
+    /// at least `batch_size` rows, along with their total number of rows.
+    fn next_partitions(&self) -> (usize, usize) {
+        let mut n_partitions = 0;
+        let mut n_rows = 0;
+        for length in &self.partition_lengths {
+            if n_rows >= self.batch_size {
+                break;
+            }
+            n_partitions += 1;
+            n_rows += length;
+        }
+        (n_partitions, n_rows)
+    }
+
+    /// Computes the window aggregate columns for `batch`, which consists of
+    /// the whole partitions with the given `partition_lengths`.
+    fn compute_aggregates(
+        &mut self,
+        batch: &RecordBatch,
+        partition_lengths: impl IntoIterator<Item = usize>,
+    ) -> Result<Vec<ArrayRef>> {
+        // record compute time on drop
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+        let _timer = elapsed_compute.timer();
 
         let mut partition_results = vec![];
+        let mut offset = 0;
         // Calculate window cols
-        for partition_point in partition_points {
-            let length = partition_point.end - partition_point.start;
+        for length in partition_lengths {
             partition_results.push(compute_window_aggregates(
                 &self.window_expr,
-                &batch.slice(partition_point.start, length),
-            )?)
+                &batch.slice(offset, length),
+            )?);
+            offset += length;
         }
         let columns = transpose(partition_results)
             .iter()
@@ -388,16 +490,19 @@ impl WindowAggStream {
             .into_iter()
             .collect::<Result<Vec<ArrayRef>, ArrowError>>()?;
 
-        // combine with the original cols
-        // note the setup of window aggregates is that they newly calculated window
-        // expression results are always appended to the columns
-        let mut batch_columns = batch.columns().to_vec();
-        // calculate window cols
-        batch_columns.extend_from_slice(&columns);
-        Ok(Some(RecordBatch::try_new(
-            Arc::clone(&self.schema),
-            batch_columns,
-        )?))
+        let state_size = columns.iter().map(|c| c.get_array_memory_size()).sum();
+        self.try_resize_state(state_size)?;
+        Ok(columns)
+    }
+
+    /// Resizes the reservation for the computed window results, spilling the
+    /// rows waiting for evaluation if spilling the input rows is not enough.
+    fn try_resize_state(&mut self, size: usize) -> Result<()> {
+        if self.input_rows.try_resize_state(size).is_err() {
+            self.partitions.spill()?;
+            self.input_rows.try_resize_state(size)?;
+        }
+        Ok(())
     }
 }
 
@@ -419,32 +524,60 @@ impl WindowAggStream {
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<RecordBatch>>> {
-        if self.finished {
-            return Poll::Ready(None);
-        }
-
         loop {
-            return Poll::Ready(Some(match ready!(self.input.poll_next_unpin(cx)) {
-                Some(Ok(batch)) => {
-                    self.batches.push(batch);
-                    continue;
+            if let Some((columns, offset)) = &mut self.output {
+                let num_rows = columns[0].len();
+                let length = min(self.batch_size, num_rows - *offset);
+                let rows = ready!(self.input_rows.poll_take(cx, length))?;
+                // combine with the original cols
+                // note the setup of window aggregates is that they newly calculated window
+                // expression results are always appended to the columns
+                let batch_columns = rows
+                    .columns()
+                    .iter()
+                    .cloned()
+                    .chain(columns.iter().map(|column| column.slice(*offset, length)))
+                    .collect();
+                *offset += length;
+                if *offset == num_rows {
+                    self.output = None;
+                    self.try_resize_state(0)?;
+                }
+                // Empty record batches should not be emitted.
+                // They need to be treated as  [`Option<RecordBatch>`]es and handled separately
+                debug_assert!(length > 0);
+                return Poll::Ready(Some(
+                    RecordBatch::try_new(Arc::clone(&self.schema), batch_columns)
+                        .map_err(Into::into),
+                ));
+            }
+            if self.finished {
+                let (n_partitions, n_rows) = self.next_partitions();
+                if n_partitions == 0 {
+                    return Poll::Ready(None);
                 }
-                Some(Err(e)) => Err(e),
+                let batch = ready!(self.partitions.poll_take(cx, n_rows))?;
+                let partition_lengths = self
+                    .partition_lengths
+                    .drain(..n_partitions)
+                    .collect::<Vec<_>>();
+                let columns = self.compute_aggregates(&batch, partition_lengths)?;
+                self.output = Some((columns, 0));
+                continue;
+            }
+
+            match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => self.buffer_batch(batch)?,
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
                 None => {
                     // Release the input pipeline's resources before computing
                     // the final aggregates.
                     let input_schema = self.input.schema();
                     self.input = Box::pin(EmptyRecordBatchStream::new(input_schema));
-                    let Some(result) = self.compute_aggregates()? else {
-                        return Poll::Ready(None);
-                    };
                     self.finished = true;
-                    // Empty record batches should not be emitted.
-                    // They need to be treated as  [`Option<RecordBatch>`]es and handled separately
-                    debug_assert!(result.num_rows() > 0);
-                    Ok(result)
+                    self.last_partition_key = None;
                 }
-            }));
+            }
         }
     }
 }
@@ -459,14 +592,20 @@ impl RecordBatchStream for WindowAggStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::collect;
     use crate::test::TestMemoryExec;
     use crate::windows::create_window_expr;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::compute::concat_batches;
     use arrow::datatypes::{DataType, Field, Schema};
-    use datafusion_common::ScalarValue;
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::memory_pool::FairSpillPool;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_expr::{
         WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunctionDefinition,
     };
     use datafusion_functions_aggregate::count::count_udaf;
+    use datafusion_physical_expr::LexOrdering;
 
     #[test]
     fn test_window_agg_cardinality_effect() -> Result<()> {
@@ -498,4 +637,133 @@ mod tests {
         ));
         Ok(())
     }
+
+    /// Returns `num_batches` batches of 1024 rows with an Int64 column `v`
+    /// and a wide Utf8 column `payload` that no window expression references.
+    fn wide_batches(schema: &SchemaRef, num_batches: usize) -> Result<Vec<RecordBatch>> {
+        (0..num_batches)
+            .map(|batch_idx| {
+                let values = (0..1024).map(|row| (batch_idx * 1024 + row) as i64);
+                let payload = (0..1024).map(|row| format!("{row:0>256}"));
+                Ok(RecordBatch::try_new(
+                    Arc::clone(schema),
+                    vec![
+                        Arc::new(Int64Array::from_iter_values(values)),
+                        Arc::new(StringArray::from_iter_values(payload)),
+                    ],
+                )?)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_window_agg_spill() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("v", DataType::Int64, false),
+            Field::new("payload", DataType::Utf8, false),
+        ]));
+        let batches = wide_batches(&schema, 32)?;
+        let input: Arc<dyn ExecutionPlan> = TestMemoryExec::try_new_exec(
+            std::slice::from_ref(&batches),
+            Arc::clone(&schema),
+            None,
+        )?;
+        // COUNT(v) OVER () puts all rows into a single window partition
+        let window_expr = create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            "count(v)".to_string(),
+            &[crate::expressions::col("v", &schema)?],
+            &[],
+            &[],
+            Arc::new(WindowFrame::new(None)),
+            Arc::clone(&schema),
+            false,
+            false,
+            None,
+        )?;
+        let window = Arc::new(WindowAggExec::try_new(vec![window_expr], input, true)?);
+
+        // The payload alone takes up several MBs
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(Arc::new(FairSpillPool::new(512 * 1024)))
+            .build_arc()?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+        let result = collect(window.execute(0, task_ctx)?).await?;
+
+        let metrics = window.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert!(metrics.spilled_rows().unwrap() > 0);
+
+        let input = concat_batches(&schema, &batches)?;
+        let result = concat_batches(&window.schema(), &result)?;
+        let expected_counts: ArrayRef =
+            Arc::new(Int64Array::from(vec![32 * 1024; 32 * 1024]));
+        assert_eq!(result.num_rows(), 32 * 1024);
+        assert_eq!(result.columns()[..2], input.columns()[..]);
+        assert_eq!(result.column(2), &expected_counts);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_window_agg_spill_partitions() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("v", DataType::Int64, false),
+            Field::new("payload", DataType::Utf8, false),
+        ]));
+        let batches = wide_batches(&schema, 32)?;
+        let input = TestMemoryExec::try_new(
+            std::slice::from_ref(&batches),
+            Arc::clone(&schema),
+            None,
+        )?;
+        let partition_by = crate::expressions::binary(
+            crate::expressions::col("v", &schema)?,
+            datafusion_expr::Operator::Divide,
+            crate::expressions::lit(1024i64),
+            &schema,
+        )?;
+        let ordering =
+            LexOrdering::new([PhysicalSortExpr::new_default(Arc::clone(&partition_by))])
+                .unwrap();
+        let input = Arc::new(input.try_with_sort_information(vec![ordering])?);
+        let input: Arc<dyn ExecutionPlan> =
+            Arc::new(TestMemoryExec::update_cache(&input));
+        // COUNT(payload) OVER (PARTITION BY v / 1024) has 32 partitions of
+        // 1024 rows, and evaluates the wide payload column
+        let window_expr = create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            "count(payload)".to_string(),
+            &[crate::expressions::col("payload", &schema)?],
+            &[partition_by],
+            &[],
+            Arc::new(WindowFrame::new(None)),
+            Arc::clone(&schema),
+            false,
+            false,
+            None,
+        )?;
+        let window = Arc::new(WindowAggExec::try_new(vec![window_expr], input, true)?);
+
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(Arc::new(FairSpillPool::new(1024 * 1024)))
+            .build_arc()?;
+        let task_ctx = Arc::new(
+            TaskContext::default()
+                .with_session_config(SessionConfig::new().with_batch_size(1024))
+                .with_runtime(runtime),
+        );
+        let result = collect(window.execute(0, task_ctx)?).await?;
+
+        // Both the input rows and the evaluated columns are spilled
+        let metrics = window.metrics().unwrap();
+        assert!(metrics.spilled_rows().unwrap() > 32 * 1024);
+
+        let input = concat_batches(&schema, &batches)?;
+        let result = concat_batches(&window.schema(), &result)?;
+        let expected_counts: ArrayRef = Arc::new(Int64Array::from(vec![1024; 32 * 1024]));
+        assert_eq!(result.num_rows(), 32 * 1024);
+        assert_eq!(result.columns()[..2], input.columns()[..]);
+        assert_eq!(result.column(2), &expected_counts);
+        Ok(())
+    }
 }
@@ -1040,6 +1040,10 @@ impl TryFrom<&protobuf::CsvOptions> for CsvOptions {
                 .ignore_trailing_whitespace
                 .first()
                 .map(|h| *h != 0),
+            sniff_dialect: proto_opts.sniff_dialect,
+            sniff_sample_size: proto_opts.sniff_sample_size.map(|h| h as usize),
+            delimiter_sequence: proto_opts.delimiter_sequence.clone(),
+            terminator_sequence: proto_opts.terminator_sequence.clone(),
         })
     }
 }
@@ -1738,6 +1738,18 @@ impl serde::Serialize for CsvOptions {
         if !self.ignore_trailing_whitespace.is_empty() {
             len += 1;
         }
+        if self.sniff_dialect {
+            len += 1;
+        }
+        if self.sniff_sample_size.is_some() {
+            len += 1;
+        }
+        if self.delimiter_sequence.is_some() {
+            len += 1;
+        }
+        if self.terminator_sequence.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion_common.CsvOptions", len)?;
         if !self.has_header.is_empty() {
             #[allow(clippy::needless_borrow)]
@@ -1833,6 +1845,20 @@ impl serde::Serialize for CsvOptions {
             #[allow(clippy::needless_borrows_for_generic_args)]
             struct_ser.serialize_field("ignoreTrailingWhitespace", pbjson::private::base64::encode(&self.ignore_trailing_whitespace).as_str())?;
         }
+        if self.sniff_dialect {
+            struct_ser.serialize_field("sniffDialect", &self.sniff_dialect)?;
+        }
+        if let Some(v) = self.sniff_sample_size.as_ref() {
+            #[allow(clippy::needless_borrow)]
+            #[allow(clippy::needless_borrows_for_generic_args)]
+            struct_ser.serialize_field("sniffSampleSize", ToString::to_string(&v).as_str())?;
+        }
+        if let Some(v) = self.delimiter_sequence.as_ref() {
+            struct_ser.serialize_field("delimiterSequence", v)?;
+        }
+        if let Some(v) = self.terminator_sequence.as_ref() {
+            struct_ser.serialize_field("terminatorSequence", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -1881,6 +1907,14 @@ impl<'de> serde::Deserialize<'de> for CsvOptions {
             "ignoreLeadingWhitespace",
             "ignore_trailing_whitespace",
             "ignoreTrailingWhitespace",
+            "sniff_dialect",
+            "sniffDialect",
+            "sniff_sample_size",
+            "sniffSampleSize",
+            "delimiter_sequence",
+            "delimiterSequence",
+            "terminator_sequence",
+            "terminatorSequence",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -1907,6 +1941,10 @@ impl<'de> serde::Deserialize<'de> for CsvOptions {
             QuoteStyle,
             IgnoreLeadingWhitespace,
             IgnoreTrailingWhitespace,
+            SniffDialect,
+            SniffSampleSize,
+            DelimiterSequence,
+            TerminatorSequence,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -1950,6 +1988,10 @@ impl<'de> serde::Deserialize<'de> for CsvOptions {
                             "quoteStyle" | "quote_style" => Ok(GeneratedField::QuoteStyle),
                             "ignoreLeadingWhitespace" | "ignore_leading_whitespace" => Ok(GeneratedField::IgnoreLeadingWhitespace),
                             "ignoreTrailingWhitespace" | "ignore_trailing_whitespace" => Ok(GeneratedField::IgnoreTrailingWhitespace),
+                            "sniffDialect" | "sniff_dialect" => Ok(GeneratedField::SniffDialect),
+                            "sniffSampleSize" | "sniff_sample_size" => Ok(GeneratedField::SniffSampleSize),
+                            "delimiterSequence" | "delimiter_sequence" => Ok(GeneratedField::DelimiterSequence),
+                            "terminatorSequence" | "terminator_sequence" => Ok(GeneratedField::TerminatorSequence),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -1991,6 +2033,10 @@ impl<'de> serde::Deserialize<'de> for CsvOptions {
                 let mut quote_style__ = None;
                 let mut ignore_leading_whitespace__ = None;
                 let mut ignore_trailing_whitespace__ = None;
+                let mut sniff_dialect__ = None;
+                let mut sniff_sample_size__ = None;
+                let mut delimiter_sequence__ = None;
+                let mut terminator_sequence__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::HasHeader => {
@@ -2151,6 +2197,32 @@ impl<'de> serde::Deserialize<'de> for CsvOptions {
                                 Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
                             ;
                         }
+                        GeneratedField::SniffDialect => {
+                            if sniff_dialect__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("sniffDialect"));
+                            }
+                            sniff_dialect__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::SniffSampleSize => {
+                            if sniff_sample_size__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("sniffSampleSize"));
+                            }
+                            sniff_sample_size__ = 
+                                map_.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                        GeneratedField::DelimiterSequence => {
+                            if delimiter_sequence__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("delimiterSequence"));
+                            }
+                            delimiter_sequence__ = map_.next_value()?;
+                        }
+                        GeneratedField::TerminatorSequence => {
+                            if terminator_sequence__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("terminatorSequence"));
+                            }
+                            terminator_sequence__ = map_.next_value()?;
+                        }
                     }
                 }
                 Ok(CsvOptions {
@@ -2176,6 +2248,10 @@ impl<'de> serde::Deserialize<'de> for CsvOptions {
                     quote_style: quote_style__.unwrap_or_default(),
                     ignore_leading_whitespace: ignore_leading_whitespace__.unwrap_or_default(),
                     ignore_trailing_whitespace: ignore_trailing_whitespace__.unwrap_or_default(),
+                    sniff_dialect: sniff_dialect__.unwrap_or_default(),
+                    sniff_sample_size: sniff_sample_size__,
+                    delimiter_sequence: delimiter_sequence__,
+                    terminator_sequence: terminator_sequence__,
                 })
             }
         }
@@ -4922,6 +4998,7 @@ impl serde::Serialize for JoinType {
             Self::Rightanti => "RIGHTANTI",
             Self::Leftmark => "LEFTMARK",
             Self::Rightmark => "RIGHTMARK",
+            Self::Asof => "ASOF",
         };
         serializer.serialize_str(variant)
     }
@@ -4943,6 +5020,7 @@ impl<'de> serde::Deserialize<'de> for JoinType {
             "RIGHTANTI",
             "LEFTMARK",
             "RIGHTMARK",
+            "ASOF",
         ];
 
         struct GeneratedVisitor;
@@ -4993,6 +5071,7 @@ impl<'de> serde::Deserialize<'de> for JoinType {
                     "RIGHTANTI" => Ok(JoinType::Rightanti),
                     "LEFTMARK" => Ok(JoinType::Leftmark),
                     "RIGHTMARK" => Ok(JoinType::Rightmark),
+                    "ASOF" => Ok(JoinType::Asof),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
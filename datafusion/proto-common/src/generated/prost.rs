@@ -698,6 +698,18 @@ pub struct CsvOptions {
     /// Whether to ignore trailing whitespace in string values
     #[prost(bytes = "vec", tag = "22")]
     pub ignore_trailing_whitespace: ::prost::alloc::vec::Vec<u8>,
+    /// Whether to detect the dialect from a sample of the data
+    #[prost(bool, tag = "23")]
+    pub sniff_dialect: bool,
+    /// Optional number of bytes sampled to detect the dialect
+    #[prost(uint64, optional, tag = "24")]
+    pub sniff_sample_size: ::core::option::Option<u64>,
+    /// Optional multi-byte delimiter, replacing `delimiter`
+    #[prost(string, optional, tag = "25")]
+    pub delimiter_sequence: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional multi-byte terminator, replacing `terminator`
+    #[prost(string, optional, tag = "26")]
+    pub terminator_sequence: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Options controlling CSV format
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
@@ -1051,6 +1063,7 @@ pub enum JoinType {
     Rightanti = 7,
     Leftmark = 8,
     Rightmark = 9,
+    Asof = 10,
 }
 impl JoinType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -1069,6 +1082,7 @@ impl JoinType {
             Self::Rightanti => "RIGHTANTI",
             Self::Leftmark => "LEFTMARK",
             Self::Rightmark => "RIGHTMARK",
+            Self::Asof => "ASOF",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -1084,6 +1098,7 @@ impl JoinType {
             "RIGHTANTI" => Some(Self::Rightanti),
             "LEFTMARK" => Some(Self::Leftmark),
             "RIGHTMARK" => Some(Self::Rightmark),
+            "ASOF" => Some(Self::Asof),
             _ => None,
         }
     }
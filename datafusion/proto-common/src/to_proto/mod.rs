@@ -1056,6 +1056,10 @@ impl TryFrom<&CsvOptions> for protobuf::CsvOptions {
             ignore_trailing_whitespace: opts
                 .ignore_trailing_whitespace
                 .map_or_else(Vec::new, |h| vec![h as u8]),
+            sniff_dialect: opts.sniff_dialect,
+            sniff_sample_size: opts.sniff_sample_size.map(|h| h as u64),
+            delimiter_sequence: opts.delimiter_sequence.clone(),
+            terminator_sequence: opts.terminator_sequence.clone(),
         })
     }
 }
@@ -2080,6 +2080,136 @@ impl<'de> serde::Deserialize<'de> for BinaryExprNode {
         deserializer.deserialize_struct("datafusion.BinaryExprNode", FIELDS, GeneratedVisitor)
     }
 }
+impl serde::Serialize for BucketSpec {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.columns.is_empty() {
+            len += 1;
+        }
+        if self.num_buckets != 0 {
+            len += 1;
+        }
+        if !self.hash.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("datafusion.BucketSpec", len)?;
+        if !self.columns.is_empty() {
+            struct_ser.serialize_field("columns", &self.columns)?;
+        }
+        if self.num_buckets != 0 {
+            #[allow(clippy::needless_borrow)]
+            #[allow(clippy::needless_borrows_for_generic_args)]
+            struct_ser.serialize_field("numBuckets", ToString::to_string(&self.num_buckets).as_str())?;
+        }
+        if !self.hash.is_empty() {
+            struct_ser.serialize_field("hash", &self.hash)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BucketSpec {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "columns",
+            "num_buckets",
+            "numBuckets",
+            "hash",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Columns,
+            NumBuckets,
+            Hash,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl serde::de::Visitor<'_> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "columns" => Ok(GeneratedField::Columns),
+                            "numBuckets" | "num_buckets" => Ok(GeneratedField::NumBuckets),
+                            "hash" => Ok(GeneratedField::Hash),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BucketSpec;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct datafusion.BucketSpec")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BucketSpec, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut columns__ = None;
+                let mut num_buckets__ = None;
+                let mut hash__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Columns => {
+                            if columns__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("columns"));
+                            }
+                            columns__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::NumBuckets => {
+                            if num_buckets__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("numBuckets"));
+                            }
+                            num_buckets__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Hash => {
+                            if hash__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("hash"));
+                            }
+                            hash__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(BucketSpec {
+                    columns: columns__.unwrap_or_default(),
+                    num_buckets: num_buckets__.unwrap_or_default(),
+                    hash: hash__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("datafusion.BucketSpec", FIELDS, GeneratedVisitor)
+    }
+}
 impl serde::Serialize for BufferExecNode {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -3673,6 +3803,9 @@ impl serde::Serialize for CreateExternalTableNode {
         if !self.column_defaults.is_empty() {
             len += 1;
         }
+        if self.bucket_spec.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.CreateExternalTableNode", len)?;
         if let Some(v) = self.name.as_ref() {
             struct_ser.serialize_field("name", v)?;
@@ -3716,6 +3849,9 @@ impl serde::Serialize for CreateExternalTableNode {
         if !self.column_defaults.is_empty() {
             struct_ser.serialize_field("columnDefaults", &self.column_defaults)?;
         }
+        if let Some(v) = self.bucket_spec.as_ref() {
+            struct_ser.serialize_field("bucketSpec", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -3746,6 +3882,8 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
             "constraints",
             "column_defaults",
             "columnDefaults",
+            "bucket_spec",
+            "bucketSpec",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -3764,6 +3902,7 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
             Options,
             Constraints,
             ColumnDefaults,
+            BucketSpec,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -3799,6 +3938,7 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
                             "options" => Ok(GeneratedField::Options),
                             "constraints" => Ok(GeneratedField::Constraints),
                             "columnDefaults" | "column_defaults" => Ok(GeneratedField::ColumnDefaults),
+                            "bucketSpec" | "bucket_spec" => Ok(GeneratedField::BucketSpec),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -3832,6 +3972,7 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
                 let mut options__ = None;
                 let mut constraints__ = None;
                 let mut column_defaults__ = None;
+                let mut bucket_spec__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Name => {
@@ -3922,6 +4063,12 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
                                 map_.next_value::<std::collections::HashMap<_, _>>()?
                             );
                         }
+                        GeneratedField::BucketSpec => {
+                            if bucket_spec__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("bucketSpec"));
+                            }
+                            bucket_spec__ = map_.next_value()?;
+                        }
                     }
                 }
                 Ok(CreateExternalTableNode {
@@ -3939,6 +4086,7 @@ impl<'de> serde::Deserialize<'de> for CreateExternalTableNode {
                     options: options__.unwrap_or_default(),
                     constraints: constraints__,
                     column_defaults: column_defaults__.unwrap_or_default(),
+                    bucket_spec: bucket_spec__,
                 })
             }
         }
@@ -5627,6 +5775,7 @@ impl serde::Serialize for dml_node::Type {
             Self::InsertOverwrite => "INSERT_OVERWRITE",
             Self::InsertReplace => "INSERT_REPLACE",
             Self::Truncate => "TRUNCATE",
+            Self::Merge => "MERGE",
         };
         serializer.serialize_str(variant)
     }
@@ -5645,6 +5794,7 @@ impl<'de> serde::Deserialize<'de> for dml_node::Type {
             "INSERT_OVERWRITE",
             "INSERT_REPLACE",
             "TRUNCATE",
+            "MERGE",
         ];
 
         struct GeneratedVisitor;
@@ -5692,6 +5842,7 @@ impl<'de> serde::Deserialize<'de> for dml_node::Type {
                     "INSERT_OVERWRITE" => Ok(dml_node::Type::InsertOverwrite),
                     "INSERT_REPLACE" => Ok(dml_node::Type::InsertReplace),
                     "TRUNCATE" => Ok(dml_node::Type::Truncate),
+                    "MERGE" => Ok(dml_node::Type::Merge),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
@@ -6945,6 +7096,9 @@ impl serde::Serialize for FileScanExecConf {
         if self.partitioned_by_file_group.is_some() {
             len += 1;
         }
+        if !self.bucket_columns.is_empty() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.FileScanExecConf", len)?;
         if !self.file_groups.is_empty() {
             struct_ser.serialize_field("fileGroups", &self.file_groups)?;
@@ -6984,6 +7138,9 @@ impl serde::Serialize for FileScanExecConf {
         if let Some(v) = self.partitioned_by_file_group.as_ref() {
             struct_ser.serialize_field("partitionedByFileGroup", v)?;
         }
+        if !self.bucket_columns.is_empty() {
+            struct_ser.serialize_field("bucketColumns", &self.bucket_columns)?;
+        }
         struct_ser.end()
     }
 }
@@ -7013,6 +7170,8 @@ impl<'de> serde::Deserialize<'de> for FileScanExecConf {
             "projectionExprs",
             "partitioned_by_file_group",
             "partitionedByFileGroup",
+            "bucket_columns",
+            "bucketColumns",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -7029,6 +7188,7 @@ impl<'de> serde::Deserialize<'de> for FileScanExecConf {
             BatchSize,
             ProjectionExprs,
             PartitionedByFileGroup,
+            BucketColumns,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -7062,6 +7222,7 @@ impl<'de> serde::Deserialize<'de> for FileScanExecConf {
                             "batchSize" | "batch_size" => Ok(GeneratedField::BatchSize),
                             "projectionExprs" | "projection_exprs" => Ok(GeneratedField::ProjectionExprs),
                             "partitionedByFileGroup" | "partitioned_by_file_group" => Ok(GeneratedField::PartitionedByFileGroup),
+                            "bucketColumns" | "bucket_columns" => Ok(GeneratedField::BucketColumns),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -7093,6 +7254,7 @@ impl<'de> serde::Deserialize<'de> for FileScanExecConf {
                 let mut batch_size__ = None;
                 let mut projection_exprs__ = None;
                 let mut partitioned_by_file_group__ = None;
+                let mut bucket_columns__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::FileGroups => {
@@ -7172,6 +7334,12 @@ impl<'de> serde::Deserialize<'de> for FileScanExecConf {
                             }
                             partitioned_by_file_group__ = map_.next_value()?;
                         }
+                        GeneratedField::BucketColumns => {
+                            if bucket_columns__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("bucketColumns"));
+                            }
+                            bucket_columns__ = Some(map_.next_value()?);
+                        }
                     }
                 }
                 Ok(FileScanExecConf {
@@ -7187,6 +7355,7 @@ impl<'de> serde::Deserialize<'de> for FileScanExecConf {
                     batch_size: batch_size__,
                     projection_exprs: projection_exprs__,
                     partitioned_by_file_group: partitioned_by_file_group__,
+                    bucket_columns: bucket_columns__.unwrap_or_default(),
                 })
             }
         }
@@ -7228,6 +7397,9 @@ impl serde::Serialize for FileSinkConfig {
         if self.file_output_mode != 0 {
             len += 1;
         }
+        if self.bucket_spec.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("datafusion.FileSinkConfig", len)?;
         if !self.object_store_url.is_empty() {
             struct_ser.serialize_field("objectStoreUrl", &self.object_store_url)?;
@@ -7260,6 +7432,9 @@ impl serde::Serialize for FileSinkConfig {
                 .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.file_output_mode)))?;
             struct_ser.serialize_field("fileOutputMode", &v)?;
         }
+        if let Some(v) = self.bucket_spec.as_ref() {
+            struct_ser.serialize_field("bucketSpec", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -7288,6 +7463,8 @@ impl<'de> serde::Deserialize<'de> for FileSinkConfig {
             "fileExtension",
             "file_output_mode",
             "fileOutputMode",
+            "bucket_spec",
+            "bucketSpec",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -7301,6 +7478,7 @@ impl<'de> serde::Deserialize<'de> for FileSinkConfig {
             InsertOp,
             FileExtension,
             FileOutputMode,
+            BucketSpec,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -7331,6 +7509,7 @@ impl<'de> serde::Deserialize<'de> for FileSinkConfig {
                             "insertOp" | "insert_op" => Ok(GeneratedField::InsertOp),
                             "fileExtension" | "file_extension" => Ok(GeneratedField::FileExtension),
                             "fileOutputMode" | "file_output_mode" => Ok(GeneratedField::FileOutputMode),
+                            "bucketSpec" | "bucket_spec" => Ok(GeneratedField::BucketSpec),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -7359,6 +7538,7 @@ impl<'de> serde::Deserialize<'de> for FileSinkConfig {
                 let mut insert_op__ = None;
                 let mut file_extension__ = None;
                 let mut file_output_mode__ = None;
+                let mut bucket_spec__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::ObjectStoreUrl => {
@@ -7415,6 +7595,12 @@ impl<'de> serde::Deserialize<'de> for FileSinkConfig {
                             }
                             file_output_mode__ = Some(map_.next_value::<FileOutputMode>()? as i32);
                         }
+                        GeneratedField::BucketSpec => {
+                            if bucket_spec__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("bucketSpec"));
+                            }
+                            bucket_spec__ = map_.next_value()?;
+                        }
                     }
                 }
                 Ok(FileSinkConfig {
@@ -7427,6 +7613,7 @@ impl<'de> serde::Deserialize<'de> for FileSinkConfig {
                     insert_op: insert_op__.unwrap_or_default(),
                     file_extension: file_extension__.unwrap_or_default(),
                     file_output_mode: file_output_mode__.unwrap_or_default(),
+                    bucket_spec: bucket_spec__,
                 })
             }
         }
@@ -15883,6 +16070,9 @@ impl serde::Serialize for Partitioning {
                 partitioning::PartitionMethod::Range(v) => {
                     struct_ser.serialize_field("range", v)?;
                 }
+                partitioning::PartitionMethod::Bucket(v) => {
+                    struct_ser.serialize_field("bucket", v)?;
+                }
             }
         }
         struct_ser.end()
@@ -15900,6 +16090,7 @@ impl<'de> serde::Deserialize<'de> for Partitioning {
             "hash",
             "unknown",
             "range",
+            "bucket",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -15908,6 +16099,7 @@ impl<'de> serde::Deserialize<'de> for Partitioning {
             Hash,
             Unknown,
             Range,
+            Bucket,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -15933,6 +16125,7 @@ impl<'de> serde::Deserialize<'de> for Partitioning {
                             "hash" => Ok(GeneratedField::Hash),
                             "unknown" => Ok(GeneratedField::Unknown),
                             "range" => Ok(GeneratedField::Range),
+                            "bucket" => Ok(GeneratedField::Bucket),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -15979,6 +16172,13 @@ impl<'de> serde::Deserialize<'de> for Partitioning {
                                 return Err(serde::de::Error::duplicate_field("range"));
                             }
                             partition_method__ = map_.next_value::<::std::option::Option<_>>()?.map(partitioning::PartitionMethod::Range)
+;
+                        }
+                        GeneratedField::Bucket => {
+                            if partition_method__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("bucket"));
+                            }
+                            partition_method__ = map_.next_value::<::std::option::Option<_>>()?.map(partitioning::PartitionMethod::Bucket)
 ;
                         }
                     }
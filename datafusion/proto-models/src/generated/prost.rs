@@ -275,6 +275,18 @@ pub struct CreateExternalTableNode {
         ::prost::alloc::string::String,
         LogicalExprNode,
     >,
+    #[prost(message, optional, tag = "16")]
+    pub bucket_spec: ::core::option::Option<BucketSpec>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BucketSpec {
+    #[prost(string, repeated, tag = "1")]
+    pub columns: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(uint64, tag = "2")]
+    pub num_buckets: u64,
+    /// The hash computing the bucket of a row, e.g. "murmur3"
+    #[prost(string, tag = "3")]
+    pub hash: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PrepareNode {
@@ -476,6 +488,7 @@ pub mod dml_node {
         InsertOverwrite = 4,
         InsertReplace = 5,
         Truncate = 6,
+        Merge = 7,
     }
     impl Type {
         /// String value of the enum field names used in the ProtoBuf definition.
@@ -491,6 +504,7 @@ pub mod dml_node {
                 Self::InsertOverwrite => "INSERT_OVERWRITE",
                 Self::InsertReplace => "INSERT_REPLACE",
                 Self::Truncate => "TRUNCATE",
+                Self::Merge => "MERGE",
             }
         }
         /// Creates an enum from field names used in the ProtoBuf definition.
@@ -503,6 +517,7 @@ pub mod dml_node {
                 "INSERT_OVERWRITE" => Some(Self::InsertOverwrite),
                 "INSERT_REPLACE" => Some(Self::InsertReplace),
                 "TRUNCATE" => Some(Self::Truncate),
+                "MERGE" => Some(Self::Merge),
                 _ => None,
             }
         }
@@ -1255,6 +1270,8 @@ pub struct FileSinkConfig {
     /// Determines how the output path is interpreted.
     #[prost(enumeration = "FileOutputMode", tag = "12")]
     pub file_output_mode: i32,
+    #[prost(message, optional, tag = "13")]
+    pub bucket_spec: ::core::option::Option<BucketSpec>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JsonSink {
@@ -1695,6 +1712,8 @@ pub struct FileScanExecConf {
     pub projection_exprs: ::core::option::Option<ProjectionExprs>,
     #[prost(bool, optional, tag = "14")]
     pub partitioned_by_file_group: ::core::option::Option<bool>,
+    #[prost(string, repeated, tag = "15")]
+    pub bucket_columns: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ParquetScanExecNode {
@@ -2089,7 +2108,7 @@ pub struct RepartitionExecNode {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Partitioning {
-    #[prost(oneof = "partitioning::PartitionMethod", tags = "1, 2, 3, 4")]
+    #[prost(oneof = "partitioning::PartitionMethod", tags = "1, 2, 3, 4, 5")]
     pub partition_method: ::core::option::Option<partitioning::PartitionMethod>,
 }
 /// Nested message and enum types in `Partitioning`.
@@ -2104,6 +2123,8 @@ pub mod partitioning {
         Unknown(u64),
         #[prost(message, tag = "4")]
         Range(super::PhysicalRangePartitioning),
+        #[prost(message, tag = "5")]
+        Bucket(super::PhysicalHashRepartition),
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -73,6 +73,10 @@ impl FromProto<&CsvFormatFactory> for CsvOptionsProto {
                 ignore_trailing_whitespace: options
                     .ignore_trailing_whitespace
                     .map_or(vec![], |v| vec![v as u8]),
+                sniff_dialect: options.sniff_dialect,
+                sniff_sample_size: options.sniff_sample_size.map(|v| v as u64),
+                delimiter_sequence: options.delimiter_sequence.clone(),
+                terminator_sequence: options.terminator_sequence.clone(),
             }
         } else {
             CsvOptionsProto::default()
@@ -181,6 +185,10 @@ impl FromProto<&CsvOptionsProto> for CsvOptions {
             } else {
                 Some(proto.ignore_trailing_whitespace[0] != 0)
             },
+            sniff_dialect: proto.sniff_dialect,
+            sniff_sample_size: proto.sniff_sample_size.map(|v| v as usize),
+            delimiter_sequence: proto.delimiter_sequence.clone(),
+            terminator_sequence: proto.terminator_sequence.clone(),
         }
     }
 }
@@ -19,6 +19,7 @@ use std::sync::Arc;
 
 use arrow::datatypes::{DataType, Field};
 use datafusion_common::datatype::DataTypeExt;
+use datafusion_common::file_options::bucketing::BucketSpec;
 use datafusion_common::{
     NullEquality, RecursionUnnestOption, Result, ScalarValue, TableReference,
     UnnestOptions, exec_datafusion_err, internal_err, plan_datafusion_err,
@@ -75,6 +76,17 @@ impl FromProto<&protobuf::UnnestOptions> for UnnestOptions {
     }
 }
 
+impl TryFromProto<&protobuf::BucketSpec> for BucketSpec {
+    type Error = Error;
+
+    fn try_from_proto(spec: &protobuf::BucketSpec) -> Result<Self, Self::Error> {
+        Ok(
+            BucketSpec::try_new(spec.columns.clone(), spec.num_buckets as usize)?
+                .with_hash(spec.hash.parse()?),
+        )
+    }
+}
+
 impl FromProto<protobuf::WindowFrameUnits> for WindowFrameUnits {
     fn from_proto(units: protobuf::WindowFrameUnits) -> Self {
         match units {
@@ -217,6 +229,7 @@ impl FromProto<protobuf::JoinType> for JoinType {
             protobuf::JoinType::Rightanti => JoinType::RightAnti,
             protobuf::JoinType::Leftmark => JoinType::LeftMark,
             protobuf::JoinType::Rightmark => JoinType::RightMark,
+            protobuf::JoinType::Asof => JoinType::AsOf,
         }
     }
 }
@@ -251,6 +264,7 @@ impl FromProto<protobuf::dml_node::Type> for WriteOp {
             protobuf::dml_node::Type::InsertReplace => WriteOp::Insert(InsertOp::Replace),
             protobuf::dml_node::Type::Ctas => WriteOp::Ctas,
             protobuf::dml_node::Type::Truncate => WriteOp::Truncate,
+            protobuf::dml_node::Type::Merge => WriteOp::Merge,
         }
     }
 }
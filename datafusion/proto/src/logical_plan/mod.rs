@@ -37,6 +37,7 @@ use crate::protobuf::{ToProtoError, proto_error};
 use arrow::datatypes::{DataType, Field, Schema, SchemaBuilder, SchemaRef};
 use datafusion_catalog::cte_worktable::CteWorkTable;
 use datafusion_catalog::empty::EmptyTable;
+use datafusion_common::file_options::bucketing::BucketSpec;
 use datafusion_common::file_options::file_type::FileType;
 use datafusion_common::format::{
     ExplainAnalyzeCategories, ExplainFormat, MetricCategory, MetricType,
@@ -607,7 +608,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                         FileFormatType::Avro(..) => {
                             #[cfg(feature = "avro")]
                             {
-                                Arc::new(AvroFormat)
+                                Arc::new(AvroFormat::default())
                             }
                             #[cfg(not(feature = "avro"))]
                             {
@@ -655,9 +656,10 @@ impl AsLogicalPlan for LogicalPlanNode {
                         .with_listing_options(options)
                         .with_schema(Arc::new(schema));
 
-                let provider = ListingTable::try_new(config)?.with_cache(
-                    ctx.runtime_env().cache_manager.get_file_statistic_cache(),
-                );
+                let cache_manager = &ctx.runtime_env().cache_manager;
+                let provider = ListingTable::try_new(config)?
+                    .with_cache(cache_manager.get_file_statistic_cache())
+                    .with_list_files_cache(cache_manager.get_list_files_cache());
 
                 let table_name =
                     from_table_reference(scan.table_name.as_ref(), "ListingTableScan")?;
@@ -789,6 +791,12 @@ impl AsLogicalPlan for LogicalPlanNode {
                     column_defaults.insert(col_name.clone(), expr);
                 }
 
+                let bucket_spec = create_extern_table
+                    .bucket_spec
+                    .as_ref()
+                    .map(BucketSpec::try_from_proto)
+                    .transpose()?;
+
                 Ok(LogicalPlan::Ddl(DdlStatement::CreateExternalTable(
                     Box::new(
                         CreateExternalTable::builder(
@@ -803,6 +811,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                         .with_partition_cols(
                             create_extern_table.table_partition_cols.clone(),
                         )
+                        .with_bucket_spec(bucket_spec)
                         .with_order_exprs(order_exprs)
                         .with_if_not_exists(create_extern_table.if_not_exists)
                         .with_or_replace(create_extern_table.or_replace)
@@ -834,6 +843,8 @@ impl AsLogicalPlan for LogicalPlanNode {
                     input: Arc::new(plan),
                     or_replace: create_view.or_replace,
                     definition,
+                    materialized: false,
+                    location: None,
                 })))
             }
             LogicalPlanType::CreateCatalogSchema(create_catalog_schema) => {
@@ -1790,6 +1801,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                     file_type,
                     schema: df_schema,
                     table_partition_cols,
+                    bucket_spec,
                     if_not_exists,
                     or_replace,
                     definition,
@@ -1834,16 +1846,27 @@ impl AsLogicalPlan for LogicalPlanNode {
                             options: options.clone(),
                             constraints: Some(constraints.clone().into()),
                             column_defaults: converted_column_defaults,
+                            bucket_spec: bucket_spec
+                                .as_ref()
+                                .map(protobuf::BucketSpec::from_proto),
                         },
                     )),
                 })
             }
+            LogicalPlan::Ddl(DdlStatement::CreateView(CreateView {
+                materialized: true,
+                ..
+            })) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for materialized views",
+            )),
             LogicalPlan::Ddl(DdlStatement::CreateView(CreateView {
                 name,
                 input,
                 or_replace,
                 definition,
                 temporary,
+                materialized: false,
+                location: _,
             })) => Ok(LogicalPlanNode {
                 logical_plan_type: Some(LogicalPlanType::CreateView(Box::new(
                     protobuf::CreateViewNode {
@@ -2073,6 +2096,17 @@ impl AsLogicalPlan for LogicalPlanNode {
             LogicalPlan::Ddl(DdlStatement::DropFunction(_)) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for DropFunction",
             )),
+            LogicalPlan::Ddl(DdlStatement::AlterTable(_)) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for AlterTable",
+            )),
+            LogicalPlan::Ddl(DdlStatement::RefreshMaterializedView(_)) => {
+                Err(proto_error(
+                    "LogicalPlan serde is not yet implemented for RefreshMaterializedView",
+                ))
+            }
+            LogicalPlan::Ddl(DdlStatement::AnalyzeTable(_)) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for AnalyzeTable",
+            )),
             LogicalPlan::Statement(_) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for Statement",
             )),
@@ -2128,6 +2162,12 @@ impl AsLogicalPlan for LogicalPlanNode {
             LogicalPlan::DescribeTable(_) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for DescribeTable",
             )),
+            LogicalPlan::MatchRecognize(_) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for MatchRecognize",
+            )),
+            LogicalPlan::Sample(_) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for Sample",
+            )),
             LogicalPlan::RecursiveQuery(recursive) => {
                 let static_term = LogicalPlanNode::try_from_logical_plan(
                     recursive.static_term.as_ref(),
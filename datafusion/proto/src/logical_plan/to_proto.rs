@@ -21,6 +21,7 @@
 
 use std::collections::HashMap;
 
+use datafusion_common::file_options::bucketing::BucketSpec;
 use datafusion_common::{NullEquality, TableReference, UnnestOptions};
 use datafusion_expr::WriteOp;
 use datafusion_expr::dml::InsertOp;
@@ -70,6 +71,16 @@ impl FromProto<&UnnestOptions> for protobuf::UnnestOptions {
     }
 }
 
+impl FromProto<&BucketSpec> for protobuf::BucketSpec {
+    fn from_proto(spec: &BucketSpec) -> Self {
+        Self {
+            columns: spec.columns.clone(),
+            num_buckets: spec.num_buckets as u64,
+            hash: spec.hash.to_string(),
+        }
+    }
+}
+
 impl FromProto<&StringifiedPlan> for protobuf::StringifiedPlan {
     fn from_proto(stringified_plan: &StringifiedPlan) -> Self {
         Self {
@@ -731,6 +742,7 @@ impl FromProto<JoinType> for protobuf::JoinType {
             JoinType::RightAnti => protobuf::JoinType::Rightanti,
             JoinType::LeftMark => protobuf::JoinType::Leftmark,
             JoinType::RightMark => protobuf::JoinType::Rightmark,
+            JoinType::AsOf => protobuf::JoinType::Asof,
         }
     }
 }
@@ -765,6 +777,7 @@ impl FromProto<&WriteOp> for protobuf::dml_node::Type {
             WriteOp::Update => protobuf::dml_node::Type::Update,
             WriteOp::Ctas => protobuf::dml_node::Type::Ctas,
             WriteOp::Truncate => protobuf::dml_node::Type::Truncate,
+            WriteOp::Merge => protobuf::dml_node::Type::Merge,
         }
     }
 }
@@ -24,6 +24,7 @@ use arrow::compute::SortOptions;
 use arrow::datatypes::{Field, Schema};
 use arrow::ipc::reader::StreamReader;
 use chrono::{TimeZone, Utc};
+use datafusion_common::file_options::bucketing::BucketSpec;
 use datafusion_common::{
     DataFusionError, Result, ScalarValue, internal_datafusion_err, not_impl_err,
 };
@@ -485,6 +486,20 @@ pub fn parse_protobuf_partitioning(
                     proto_converter,
                 )?))
             }
+            Some(protobuf::partitioning::PartitionMethod::Bucket(
+                bucket_partitioning,
+            )) => {
+                let exprs = parse_physical_exprs(
+                    &bucket_partitioning.hash_expr,
+                    ctx,
+                    input_schema,
+                    proto_converter,
+                )?;
+                Ok(Some(Partitioning::Bucket(
+                    exprs,
+                    bucket_partitioning.partition_count as usize,
+                )))
+            }
             Some(protobuf::partitioning::PartitionMethod::Unknown(partition_count)) => {
                 Ok(Some(Partitioning::UnknownPartitioning(
                     *partition_count as usize,
@@ -646,6 +661,7 @@ pub fn parse_protobuf_file_scan_config(
         .with_output_ordering(output_ordering)
         .with_batch_size(proto.batch_size.map(|s| s as usize))
         .with_partitioned_by_file_group(proto.partitioned_by_file_group.unwrap_or(false))
+        .with_bucket_columns(proto.bucket_columns.clone())
         .build();
     Ok(config)
 }
@@ -804,6 +820,11 @@ impl TryFromProto<&protobuf::FileSinkConfig> for FileSinkConfig {
             keep_partition_by_columns: conf.keep_partition_by_columns,
             file_extension: conf.file_extension.clone(),
             file_output_mode,
+            bucket_spec: conf
+                .bucket_spec
+                .as_ref()
+                .map(BucketSpec::try_from_proto)
+                .transpose()?,
         })
     }
 }
@@ -46,7 +46,7 @@ use super::{
     DefaultPhysicalProtoConverter, PhysicalExtensionCodec,
     PhysicalProtoConverterExtension, encode_human_display_alias,
 };
-use crate::convert::TryFromProto;
+use crate::convert::{FromProto, TryFromProto};
 use crate::protobuf::{
     self, PhysicalSortExprNode, PhysicalSortExprNodeCollection,
     physical_aggregate_expr_node, physical_window_expr_node,
@@ -409,6 +409,14 @@ pub fn serialize_partitioning(
                 )),
             }
         }
+        Partitioning::Bucket(exprs, partition_count) => protobuf::Partitioning {
+            partition_method: Some(protobuf::partitioning::PartitionMethod::Bucket(
+                protobuf::PhysicalHashRepartition {
+                    hash_expr: serialize_physical_exprs(exprs, codec, proto_converter)?,
+                    partition_count: *partition_count as u64,
+                },
+            )),
+        },
         Partitioning::Range(range) => protobuf::Partitioning {
             partition_method: Some(protobuf::partitioning::PartitionMethod::Range(
                 serialize_range_partitioning(range, codec, proto_converter)?,
@@ -592,6 +600,7 @@ pub fn serialize_file_scan_config(
         batch_size: conf.batch_size.map(|s| s as u64),
         projection_exprs,
         partitioned_by_file_group: Some(conf.partitioned_by_file_group),
+        bucket_columns: conf.bucket_columns.clone(),
     })
 }
 
@@ -701,6 +710,10 @@ impl TryFromProto<&FileSinkConfig> for protobuf::FileSinkConfig {
             insert_op: conf.insert_op as i32,
             file_extension: conf.file_extension.to_string(),
             file_output_mode: file_output_mode.into(),
+            bucket_spec: conf
+                .bucket_spec
+                .as_ref()
+                .map(protobuf::BucketSpec::from_proto),
         })
     }
 }
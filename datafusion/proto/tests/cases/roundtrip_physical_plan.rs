@@ -1589,6 +1589,7 @@ fn roundtrip_json_sink() -> Result<()> {
         keep_partition_by_columns: true,
         file_extension: "json".into(),
         file_output_mode: FileOutputMode::SingleFile,
+        bucket_spec: None,
     };
     let data_sink = Arc::new(JsonSink::new(
         file_sink_config,
@@ -1628,6 +1629,7 @@ fn roundtrip_csv_sink() -> Result<()> {
         keep_partition_by_columns: true,
         file_extension: "csv".into(),
         file_output_mode: FileOutputMode::Directory,
+        bucket_spec: None,
     };
     let data_sink = Arc::new(CsvSink::new(
         file_sink_config,
@@ -1681,6 +1683,7 @@ fn roundtrip_parquet_sink() -> Result<()> {
         keep_partition_by_columns: true,
         file_extension: "parquet".into(),
         file_output_mode: FileOutputMode::Automatic,
+        bucket_spec: None,
     };
     let data_sink = Arc::new(ParquetSink::new(
         file_sink_config,
@@ -1816,6 +1819,20 @@ fn roundtrip_range_partitioning() -> Result<()> {
     roundtrip_test(Arc::new(repartition))
 }
 
+#[test]
+fn roundtrip_bucket_partitioning() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+    let input = Arc::new(EmptyExec::new(Arc::clone(&schema)));
+    // RepartitionExec is used only to carry the partitioning through proto.
+    // Only scans of bucketed tables produce bucket partitioning.
+    let repartition = RepartitionExec::try_new(
+        input,
+        Partitioning::Bucket(vec![col("a", &schema)?], 4),
+    )?;
+
+    roundtrip_test(Arc::new(repartition))
+}
+
 #[test]
 fn roundtrip_interleave() -> Result<()> {
     let field_a = Field::new("col", DataType::Int64, false);
@@ -4094,6 +4111,7 @@ fn roundtrip_parquet_exec_partitioned_by_file_group() -> Result<()> {
                 1024,
             )])])
             .with_partitioned_by_file_group(true)
+            .with_bucket_columns(vec!["col".to_string()])
             .build();
 
     assert!(scan_config.partitioned_by_file_group);
@@ -4124,6 +4142,7 @@ fn roundtrip_parquet_exec_partitioned_by_file_group() -> Result<()> {
         .expect("Expected FileScanConfig");
 
     assert!(file_scan_config.partitioned_by_file_group);
+    assert_eq!(file_scan_config.bucket_columns, vec!["col".to_string()]);
 
     Ok(())
 }
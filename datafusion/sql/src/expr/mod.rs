@@ -22,8 +22,8 @@ use datafusion_expr::planner::{
 use sqlparser::ast::{
     AccessExpr, BinaryOperator, CastFormat, CastKind, CeilFloorKind,
     DataType as SQLDataType, DateTimeField, DictionaryField, Expr as SQLExpr,
-    ExprWithAlias as SQLExprWithAlias, JsonPath, MapEntry, StructField, Subscript,
-    TrimWhereField, TypedString, Value, ValueWithSpan,
+    ExprWithAlias as SQLExprWithAlias, JsonPath, MapEntry, ObjectName, StructField,
+    Subscript, TrimWhereField, TypedString, Value, ValueWithSpan,
 };
 
 use datafusion_common::{
@@ -39,7 +39,9 @@ use datafusion_expr::{
     Operator, TryCast, lit, when,
 };
 
-use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use crate::planner::{
+    ContextProvider, PlannerContext, SqlToRel, object_name_to_collation,
+};
 use datafusion_functions_nested::expr_fn::{
     array_has, array_max, array_min, array_position, cardinality,
 };
@@ -653,10 +655,35 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             SQLExpr::JsonAccess { value, path } => {
                 self.parse_json_access(schema, planner_context, value, &path)
             }
+            SQLExpr::Collate { expr, collation } => {
+                self.sql_collate_to_expr(*expr, &collation, schema, planner_context)
+            }
             _ => not_impl_err!("Unsupported ast node in sqltorel: {sql:?}"),
         }
     }
 
+    /// Plans `<expr> COLLATE <collation>` as a call to the `collate` function
+    fn sql_collate_to_expr(
+        &self,
+        expr: SQLExpr,
+        collation: &ObjectName,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        let collation = object_name_to_collation(collation)?;
+        let expr = self.sql_expr_to_logical_expr(expr, schema, planner_context)?;
+        let fun = self
+            .context_provider
+            .get_function_meta("collate")
+            .ok_or_else(|| {
+                internal_datafusion_err!("Unable to find expected 'collate' function")
+            })?;
+        Ok(Expr::ScalarFunction(ScalarFunction::new_udf(
+            fun,
+            vec![expr, lit(collation.name())],
+        )))
+    }
+
     fn parse_json_access(
         &self,
         schema: &DFSchema,
@@ -22,13 +22,14 @@
 
 use datafusion_common::DataFusionError;
 use datafusion_common::config::SqlParserOptions;
+use datafusion_common::file_options::bucketing::BucketSpec;
 use datafusion_common::format::{ExplainFormat, ExplainStatementOptions};
 use datafusion_common::{Diagnostic, Span, sql_err};
 use sqlparser::ast::{ExprWithAlias, Ident, OrderByOptions};
 use sqlparser::tokenizer::TokenWithSpan;
 use sqlparser::{
     ast::{
-        ColumnDef, ColumnOptionDef, ObjectName, OrderByExpr, Query,
+        ColumnDef, ColumnOptionDef, ObjectName, ObjectType, OrderByExpr, Query,
         Statement as SQLStatement, TableConstraint, Value,
     },
     dialect::{Dialect, GenericDialect, keywords::Keyword},
@@ -229,6 +230,7 @@ pub(crate) type LexOrdering = Vec<OrderByExpr>;
 /// <TABLE_NAME>[ (<column_definition>) ]
 /// STORED AS <file_type>
 /// [ PARTITIONED BY (<column_definition list> | <column list>) ]
+/// [ CLUSTERED BY (<column list>) INTO <literal> BUCKETS ]
 /// [ WITH ORDER (<ordered column list>)
 /// [ OPTIONS (<key_value_list>) ]
 /// LOCATION <literal>
@@ -253,6 +255,8 @@ pub struct CreateExternalTable {
     pub location: String,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// Hash bucketing of the files
+    pub bucket_spec: Option<BucketSpec>,
     /// Ordered expressions
     pub order_exprs: Vec<LexOrdering>,
     /// Option to not error if table already exists
@@ -289,6 +293,9 @@ impl fmt::Display for CreateExternalTable {
             }
             write!(f, ") ")?;
         }
+        if let Some(bucket_spec) = &self.bucket_spec {
+            write!(f, "{bucket_spec} ")?;
+        }
         write!(f, "LOCATION {}", self.location)
     }
 }
@@ -308,6 +315,41 @@ impl fmt::Display for ResetStatement {
     }
 }
 
+/// DataFusion extension for `REFRESH MATERIALIZED VIEW`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshMaterializedViewStatement {
+    /// The materialized view to refresh
+    pub name: ObjectName,
+}
+
+impl fmt::Display for RefreshMaterializedViewStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "REFRESH MATERIALIZED VIEW {}", self.name)
+    }
+}
+
+/// DataFusion extension for
+/// `ANALYZE TABLE <name> COMPUTE STATISTICS [FOR COLUMNS <col>, ...]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzeTableStatement {
+    /// The table to compute statistics for
+    pub table_name: ObjectName,
+    /// The columns to compute statistics for, all columns if empty
+    pub columns: Vec<Ident>,
+}
+
+impl fmt::Display for AnalyzeTableStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ANALYZE TABLE {} COMPUTE STATISTICS", self.table_name)?;
+        if !self.columns.is_empty() {
+            let columns: Vec<String> =
+                self.columns.iter().map(|c| c.to_string()).collect();
+            write!(f, " FOR COLUMNS {}", columns.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 /// DataFusion SQL Statement.
 ///
 /// This can either be a [`Statement`] from [`sqlparser`] from a
@@ -327,6 +369,10 @@ pub enum Statement {
     Explain(ExplainStatement),
     /// Extension: `RESET`
     Reset(ResetStatement),
+    /// Extension: `REFRESH MATERIALIZED VIEW`
+    RefreshMaterializedView(RefreshMaterializedViewStatement),
+    /// Extension: `ANALYZE TABLE`
+    AnalyzeTable(AnalyzeTableStatement),
 }
 
 impl fmt::Display for Statement {
@@ -337,6 +383,8 @@ impl fmt::Display for Statement {
             Statement::CopyTo(stmt) => write!(f, "{stmt}"),
             Statement::Explain(stmt) => write!(f, "{stmt}"),
             Statement::Reset(stmt) => write!(f, "{stmt}"),
+            Statement::RefreshMaterializedView(stmt) => write!(f, "{stmt}"),
+            Statement::AnalyzeTable(stmt) => write!(f, "{stmt}"),
         }
     }
 }
@@ -632,6 +680,19 @@ impl<'a> DFParser<'a> {
                         self.parser.next_token(); // RESET
                         self.parse_reset()
                     }
+                    Keyword::DROP if self.peek_nth_keyword(1, Keyword::MATERIALIZED) => {
+                        self.parser.next_token(); // DROP
+                        self.parser.next_token(); // MATERIALIZED
+                        self.parse_drop_materialized_view()
+                    }
+                    Keyword::ANALYZE => {
+                        self.parser.next_token(); // ANALYZE
+                        self.parse_analyze_table()
+                    }
+                    _ if w.value.eq_ignore_ascii_case("REFRESH") => {
+                        self.parser.next_token(); // REFRESH
+                        self.parse_refresh_materialized_view()
+                    }
                     _ => {
                         // use sqlparser-rs parser
                         self.parse_and_handle_statement()
@@ -904,6 +965,68 @@ impl<'a> DFParser<'a> {
         Ok(Statement::Reset(ResetStatement::Variable(variable)))
     }
 
+    /// Returns true if the `n`th next token is the keyword `keyword`
+    fn peek_nth_keyword(&self, n: usize, keyword: Keyword) -> bool {
+        matches!(
+            self.parser.peek_nth_token(n).token,
+            Token::Word(w) if w.keyword == keyword
+        )
+    }
+
+    /// Parse a SQL `REFRESH MATERIALIZED VIEW <name>`
+    pub fn parse_refresh_materialized_view(
+        &mut self,
+    ) -> Result<Statement, DataFusionError> {
+        self.parser
+            .expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])?;
+        let name = self.parser.parse_object_name(true)?;
+        Ok(Statement::RefreshMaterializedView(
+            RefreshMaterializedViewStatement { name },
+        ))
+    }
+
+    /// Parse a SQL
+    /// `ANALYZE TABLE <name> COMPUTE STATISTICS [FOR COLUMNS <col>, ...]`
+    pub fn parse_analyze_table(&mut self) -> Result<Statement, DataFusionError> {
+        self.parser.expect_keyword(Keyword::TABLE)?;
+        let table_name = self.parser.parse_object_name(true)?;
+        self.parser
+            .expect_keywords(&[Keyword::COMPUTE, Keyword::STATISTICS])?;
+        let columns = if self
+            .parser
+            .parse_keywords(&[Keyword::FOR, Keyword::COLUMNS])
+        {
+            self.parser
+                .parse_comma_separated(|parser| parser.parse_identifier())?
+        } else {
+            vec![]
+        };
+        Ok(Statement::AnalyzeTable(AnalyzeTableStatement {
+            table_name,
+            columns,
+        }))
+    }
+
+    /// Parse a SQL `DROP MATERIALIZED VIEW [IF EXISTS] <name>`
+    ///
+    /// Materialized views are dropped like regular views, so this is
+    /// returned as a `DROP VIEW` statement.
+    pub fn parse_drop_materialized_view(&mut self) -> Result<Statement, DataFusionError> {
+        self.parser.expect_keyword(Keyword::VIEW)?;
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self.parser.parse_object_name(true)?;
+        Ok(Statement::Statement(Box::new(SQLStatement::Drop {
+            object_type: ObjectType::View,
+            if_exists,
+            names: vec![name],
+            cascade: false,
+            restrict: false,
+            purge: false,
+            temporary: false,
+            table: None,
+        })))
+    }
+
     pub fn parse_explain_format(&mut self) -> Result<Option<String>, DataFusionError> {
         if !self.parser.parse_keyword(Keyword::FORMAT) {
             return Ok(None);
@@ -1112,6 +1235,7 @@ impl<'a> DFParser<'a> {
             file_type: Option<String>,
             location: Option<String>,
             table_partition_cols: Option<Vec<String>>,
+            bucket_spec: Option<BucketSpec>,
             order_exprs: Vec<LexOrdering>,
             options: Option<Vec<(String, Value)>>,
         }
@@ -1125,6 +1249,7 @@ impl<'a> DFParser<'a> {
                 Keyword::DELIMITER,
                 Keyword::COMPRESSION,
                 Keyword::PARTITIONED,
+                Keyword::CLUSTERED,
                 Keyword::OPTIONS,
             ]) {
                 match keyword {
@@ -1187,6 +1312,23 @@ impl<'a> DFParser<'a> {
                             }
                         }
                     }
+                    Keyword::CLUSTERED => {
+                        self.parser.expect_keyword(Keyword::BY)?;
+                        ensure_not_set(&builder.bucket_spec, "CLUSTERED BY")?;
+                        let columns = self.parse_partitions()?;
+                        self.parser.expect_keyword(Keyword::INTO)?;
+                        let num_buckets = self.parser.parse_literal_uint()?;
+                        self.parser.expect_keyword(Keyword::BUCKETS)?;
+                        let Some(bucket_spec) = usize::try_from(num_buckets)
+                            .ok()
+                            .and_then(|n| BucketSpec::try_new(columns, n).ok())
+                        else {
+                            return parser_err!(
+                                "CLUSTERED BY requires at least one column and one bucket"
+                            )?;
+                        };
+                        builder.bucket_spec = Some(bucket_spec);
+                    }
                     Keyword::OPTIONS => {
                         ensure_not_set(&builder.options, "OPTIONS")?;
                         builder.options = Some(self.parse_value_options()?);
@@ -1223,6 +1365,7 @@ impl<'a> DFParser<'a> {
             file_type: builder.file_type.unwrap(),
             location: builder.location.unwrap(),
             table_partition_cols: builder.table_partition_cols.unwrap_or(vec![]),
+            bucket_spec: builder.bucket_spec,
             order_exprs: builder.order_exprs,
             if_not_exists,
             or_replace,
@@ -1334,6 +1477,7 @@ mod tests {
             file_type: "CSV".to_string(),
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1352,6 +1496,7 @@ mod tests {
             file_type: "CSV".to_string(),
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1371,6 +1516,7 @@ mod tests {
             file_type: "CSV".to_string(),
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1390,6 +1536,7 @@ mod tests {
             file_type: "CSV".to_string(),
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1412,6 +1559,32 @@ mod tests {
             file_type: "CSV".to_string(),
             location: "foo.csv".into(),
             table_partition_cols: vec!["p1".to_string(), "p2".to_string()],
+            bucket_spec: None,
+            order_exprs: vec![],
+            if_not_exists: false,
+            or_replace: false,
+            temporary: false,
+            unbounded: false,
+            options: vec![],
+            constraints: vec![],
+        });
+        expect_parse_ok(sql, expected)?;
+
+        // positive case: clustered by
+        let sql = "CREATE EXTERNAL TABLE t(c1 int, c2 int) STORED AS PARQUET CLUSTERED BY (c1, c2) INTO 8 BUCKETS LOCATION 'foo'";
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: name.clone(),
+            columns: vec![
+                make_column_def("c1", DataType::Int(display)),
+                make_column_def("c2", DataType::Int(display)),
+            ],
+            file_type: "PARQUET".to_string(),
+            location: "foo".into(),
+            table_partition_cols: vec![],
+            bucket_spec: Some(BucketSpec::try_new(
+                vec!["c1".to_string(), "c2".to_string()],
+                8,
+            )?),
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1441,6 +1614,7 @@ mod tests {
                 file_type: "CSV".to_string(),
                 location: "foo.csv".into(),
                 table_partition_cols: vec![],
+                bucket_spec: None,
                 order_exprs: vec![],
                 if_not_exists: false,
                 or_replace: false,
@@ -1463,6 +1637,7 @@ mod tests {
             file_type: "PARQUET".to_string(),
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1481,6 +1656,7 @@ mod tests {
             file_type: "PARQUET".to_string(),
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1499,6 +1675,7 @@ mod tests {
             file_type: "AVRO".to_string(),
             location: "foo.avro".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1517,6 +1694,7 @@ mod tests {
             file_type: "PARQUET".to_string(),
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: true,
             or_replace: false,
@@ -1536,6 +1714,7 @@ mod tests {
             file_type: "PARQUET".to_string(),
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: true,
@@ -1557,6 +1736,7 @@ mod tests {
             file_type: "CSV".to_string(),
             location: "foo.csv".into(),
             table_partition_cols: vec!["p1".to_string()],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1590,6 +1770,7 @@ mod tests {
             file_type: "X".to_string(),
             location: "blahblah".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1608,6 +1789,7 @@ mod tests {
             file_type: "X".to_string(),
             location: "blahblah".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![],
             if_not_exists: false,
             or_replace: false,
@@ -1651,6 +1833,7 @@ mod tests {
                 file_type: "CSV".to_string(),
                 location: "foo.csv".into(),
                 table_partition_cols: vec![],
+                bucket_spec: None,
                 order_exprs: vec![vec![OrderByExpr {
                     expr: Identifier(Ident {
                         value: "c1".to_owned(),
@@ -1682,6 +1865,7 @@ mod tests {
             file_type: "CSV".to_string(),
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![vec![
                 OrderByExpr {
                     expr: Identifier(Ident {
@@ -1729,6 +1913,7 @@ mod tests {
             file_type: "CSV".to_string(),
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            bucket_spec: None,
             order_exprs: vec![vec![OrderByExpr {
                 expr: Expr::BinaryOp {
                     left: Box::new(Identifier(Ident {
@@ -1779,6 +1964,7 @@ mod tests {
             file_type: "PARQUET".to_string(),
             location: "foo.parquet".into(),
             table_partition_cols: vec!["c1".into()],
+            bucket_spec: None,
             order_exprs: vec![vec![OrderByExpr {
                 expr: Expr::BinaryOp {
                     left: Box::new(Identifier(Ident {
@@ -1847,6 +2033,7 @@ mod tests {
             file_type: "PARQUET".to_string(),
             location: "foo.parquet".into(),
             table_partition_cols: vec!["c1".into()],
+            bucket_spec: None,
             order_exprs: vec![vec![OrderByExpr {
                 expr: Expr::BinaryOp {
                     left: Box::new(Identifier(Ident {
@@ -2173,6 +2360,7 @@ mod tests {
                         file_type: "CSV".to_string(),
                         location: "foo.csv".into(),
                         table_partition_cols: vec![],
+                        bucket_spec: None,
                         order_exprs: vec![],
                         if_not_exists: false,
                         or_replace: false,
@@ -2190,6 +2378,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn refresh_materialized_view() -> Result<(), DataFusionError> {
+        let expected =
+            Statement::RefreshMaterializedView(RefreshMaterializedViewStatement {
+                name: ObjectName::from(vec![Ident::from("s"), Ident::from("mv")]),
+            });
+        expect_parse_ok("REFRESH MATERIALIZED VIEW s.mv", expected)?;
+        verified_stmt("REFRESH MATERIALIZED VIEW mv");
+
+        expect_parse_error("REFRESH VIEW mv", "Expected: MATERIALIZED, found: VIEW");
+        Ok(())
+    }
+
+    #[test]
+    fn analyze_table() -> Result<(), DataFusionError> {
+        let expected = Statement::AnalyzeTable(AnalyzeTableStatement {
+            table_name: ObjectName::from(vec![Ident::from("t")]),
+            columns: vec![],
+        });
+        expect_parse_ok("ANALYZE TABLE t COMPUTE STATISTICS", expected)?;
+
+        let expected = Statement::AnalyzeTable(AnalyzeTableStatement {
+            table_name: ObjectName::from(vec![Ident::from("s"), Ident::from("t")]),
+            columns: vec![Ident::from("a"), Ident::with_quote('"', "B")],
+        });
+        expect_parse_ok(
+            "ANALYZE TABLE s.t COMPUTE STATISTICS FOR COLUMNS a, \"B\"",
+            expected,
+        )?;
+        verified_stmt("ANALYZE TABLE t COMPUTE STATISTICS FOR COLUMNS a, b");
+
+        expect_parse_error("ANALYZE TABLE t", "Expected: COMPUTE, found: EOF");
+        Ok(())
+    }
+
+    #[test]
+    fn drop_materialized_view() {
+        let statements =
+            DFParser::parse_sql("DROP MATERIALIZED VIEW IF EXISTS mv").unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].to_string(), "DROP VIEW IF EXISTS mv");
+    }
+
     #[test]
     fn test_custom_tokens() {
         // Span mock.
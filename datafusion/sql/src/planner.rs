@@ -24,6 +24,7 @@ use std::vec;
 use crate::utils::make_decimal_type;
 use arrow::datatypes::*;
 use datafusion_common::TableReference;
+use datafusion_common::collation::Collation;
 use datafusion_common::config::SqlParserOptions;
 use datafusion_common::datatype::{DataTypeExt, FieldExt};
 use datafusion_common::error::add_possible_columns_to_diag;
@@ -489,13 +490,18 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 .options
                 .iter()
                 .any(|x| x.option == ColumnOption::NotNull);
-            fields.push(
-                data_type
-                    .as_ref()
-                    .clone()
-                    .with_name(self.ident_normalizer.normalize(column.name))
-                    .with_nullable(!not_nullable),
-            );
+            let mut field = data_type
+                .as_ref()
+                .clone()
+                .with_name(self.ident_normalizer.normalize(column.name))
+                .with_nullable(!not_nullable);
+            if let Some(collation) = column.options.iter().find_map(|x| match &x.option {
+                ColumnOption::Collation(collation) => Some(collation),
+                _ => None,
+            }) {
+                field = object_name_to_collation(collation)?.apply_to_field(field)?;
+            }
+            fields.push(field);
         }
 
         Ok(Schema::new(fields))
@@ -1042,3 +1048,15 @@ pub fn object_name_to_qualifier(
         .collect::<Result<Vec<_>>>()
         .map(|parts| parts.join(" AND "))
 }
+
+/// Parse the collation name of a `COLLATE` clause, which must be a single
+/// identifier such as `nocase` or `"en-u-ks-level2"`
+pub(crate) fn object_name_to_collation(name: &ObjectName) -> Result<Collation> {
+    match name.0.as_slice() {
+        [part] => match part.as_ident() {
+            Some(ident) => Collation::try_new(&ident.value),
+            None => plan_err!("Unsupported collation '{name}'"),
+        },
+        _ => plan_err!("Unsupported collation '{name}'"),
+    }
+}
@@ -16,10 +16,14 @@
 // under the License.
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
-use datafusion_common::{Column, Result, not_impl_err, plan_datafusion_err};
-use datafusion_expr::{JoinType, LogicalPlan, LogicalPlanBuilder};
+use datafusion_common::{Column, Result, not_impl_err, plan_datafusion_err, plan_err};
+use datafusion_expr::utils::split_conjunction_owned;
+use datafusion_expr::{
+    BinaryExpr, Expr, JoinType, LogicalPlan, LogicalPlanBuilder, Operator,
+};
 use sqlparser::ast::{
-    Join, JoinConstraint, JoinOperator, ObjectName, TableFactor, TableWithJoins,
+    Expr as SQLExpr, Join, JoinConstraint, JoinOperator, ObjectName, TableFactor,
+    TableWithJoins,
 };
 use std::collections::HashSet;
 
@@ -98,6 +102,16 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             JoinOperator::CrossJoin(JoinConstraint::None) => {
                 self.parse_cross_join(left, right)
             }
+            JoinOperator::AsOf {
+                match_condition,
+                constraint,
+            } => self.parse_asof_join(
+                left,
+                right,
+                match_condition,
+                constraint,
+                planner_context,
+            ),
             other => not_impl_err!("Unsupported JOIN operator {other:?}"),
         }
     }
@@ -110,6 +124,48 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         LogicalPlanBuilder::from(left).cross_join(right)?.build()
     }
 
+    /// Plans `left ASOF JOIN right MATCH_CONDITION (..) [ON ..]`, where the
+    /// optional ON clause may only contain equality conditions.
+    fn parse_asof_join(
+        &self,
+        left: LogicalPlan,
+        right: LogicalPlan,
+        match_condition: SQLExpr,
+        constraint: JoinConstraint,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let join_schema = left.schema().join(right.schema())?;
+        let match_condition =
+            self.sql_to_expr(match_condition, &join_schema, planner_context)?;
+
+        let (left_keys, right_keys): (Vec<Expr>, Vec<Expr>) = match constraint {
+            JoinConstraint::On(sql_expr) => {
+                let on = self.sql_to_expr(sql_expr, &join_schema, planner_context)?;
+                split_conjunction_owned(on)
+                    .into_iter()
+                    .map(|expr| match expr {
+                        Expr::BinaryExpr(BinaryExpr {
+                            left,
+                            op: Operator::Eq,
+                            right,
+                        }) => Ok((*left, *right)),
+                        other => plan_err!(
+                            "ASOF JOIN only supports equality conditions in the ON clause, got {other}"
+                        ),
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .unzip()
+            }
+            JoinConstraint::None => (vec![], vec![]),
+            other => return not_impl_err!("Unsupported ASOF JOIN constraint {other:?}"),
+        };
+
+        LogicalPlanBuilder::from(left)
+            .join_asof(right, (left_keys, right_keys), match_condition)?
+            .build()
+    }
+
     fn parse_join(
         &self,
         left: LogicalPlan,
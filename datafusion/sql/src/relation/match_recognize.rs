@@ -0,0 +1,559 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::BTreeSet;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion_common::{
+    Column, DataFusionError, Result, not_impl_datafusion_err, not_impl_err,
+    plan_datafusion_err, plan_err,
+};
+use datafusion_expr::expr::WindowFunction;
+use datafusion_expr::{
+    AfterMatchSkip, Expr, ExprFunctionExt, LogicalPlan, LogicalPlanBuilder,
+    MatchRecognize, RowPattern, RowPatternDefinition, RowPatternFunction,
+    RowPatternMeasure, RowsPerMatch, WindowFunctionDefinition, lit,
+};
+use sqlparser::ast::{
+    self, EmptyMatchesMode, Expr as SQLExpr, FunctionArg, FunctionArgExpr,
+    FunctionArguments, Ident, MatchRecognizePattern, MatchRecognizeSymbol, Measure,
+    OrderByExpr, RepetitionQuantifier, SymbolDefinition, Value, VisitMut, VisitorMut,
+    visit_expressions_mut,
+};
+
+/// The SQL arguments of `TableFactor::MatchRecognize`
+pub(super) struct MatchRecognizeClauses {
+    pub(super) partition_by: Vec<SQLExpr>,
+    pub(super) order_by: Vec<OrderByExpr>,
+    pub(super) measures: Vec<Measure>,
+    pub(super) rows_per_match: Option<ast::RowsPerMatch>,
+    pub(super) after_match_skip: Option<ast::AfterMatchSkip>,
+    pub(super) pattern: MatchRecognizePattern,
+    pub(super) symbols: Vec<SymbolDefinition>,
+}
+
+impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plan `<input> MATCH_RECOGNIZE (...)` as a [`MatchRecognize`] node
+    /// followed by a projection of its output columns.
+    ///
+    /// `PREV` and `NEXT` in `DEFINE` are planned as `lag` and `lead` window
+    /// functions of the input. Each `MEASURES` expression is split into the
+    /// [`RowPatternFunction`]s it uses, which are computed by the
+    /// [`MatchRecognize`] node, and the expression combining them, which is
+    /// computed by the projection.
+    pub(super) fn plan_match_recognize(
+        &self,
+        input: LogicalPlan,
+        clauses: MatchRecognizeClauses,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let MatchRecognizeClauses {
+            partition_by,
+            order_by,
+            measures,
+            rows_per_match,
+            after_match_skip,
+            pattern,
+            symbols,
+        } = clauses;
+
+        let pattern = row_pattern(pattern)?;
+        let variables = pattern
+            .variables()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>();
+        let rows_per_match = match rows_per_match {
+            None | Some(ast::RowsPerMatch::OneRow) => RowsPerMatch::One,
+            Some(ast::RowsPerMatch::AllRows(None | Some(EmptyMatchesMode::Omit))) => {
+                RowsPerMatch::All
+            }
+            Some(ast::RowsPerMatch::AllRows(Some(mode))) => {
+                return not_impl_err!("MATCH_RECOGNIZE with {mode} is not supported");
+            }
+        };
+        let after_match_skip = match after_match_skip {
+            None | Some(ast::AfterMatchSkip::PastLastRow) => AfterMatchSkip::PastLastRow,
+            Some(ast::AfterMatchSkip::ToNextRow) => AfterMatchSkip::ToNextRow,
+            Some(ast::AfterMatchSkip::ToFirst(ident)) => {
+                AfterMatchSkip::ToFirst(ident.value)
+            }
+            Some(ast::AfterMatchSkip::ToLast(ident)) => {
+                AfterMatchSkip::ToLast(ident.value)
+            }
+        };
+
+        let input_schema = Arc::clone(input.schema());
+        let partition_by = partition_by
+            .into_iter()
+            .map(|expr| self.sql_to_expr(expr, &input_schema, planner_context))
+            .collect::<Result<Vec<_>>>()?;
+        let order_by = self.order_by_to_sort_expr(
+            order_by,
+            &input_schema,
+            planner_context,
+            true,
+            None,
+        )?;
+
+        // Replace PREV and NEXT in DEFINE by columns computed by window functions
+        let mut navigations = NavigationRewriter::default();
+        let mut conditions = vec![];
+        for SymbolDefinition {
+            symbol,
+            mut definition,
+        } in symbols
+        {
+            navigations.condition = conditions.len();
+            control_flow_to_result(definition.visit(&mut navigations))?;
+            check_own_variable(&symbol.value, &mut definition, &variables)?;
+            conditions.push((symbol.value, definition));
+        }
+        let mut window_exprs = vec![];
+        let mut navigation_names = vec![];
+        for (i, (navigation, mut arg, offset)) in
+            navigations.navigations.into_iter().enumerate()
+        {
+            let (symbol, _) = &conditions[navigations.symbols[i]];
+            check_own_variable(symbol, &mut arg, &variables)?;
+            let arg = self.sql_to_expr(arg, &input_schema, planner_context)?;
+            let udwf = self
+                .context_provider
+                .get_window_meta(navigation.window_function())
+                .ok_or_else(|| {
+                    plan_datafusion_err!(
+                        "{navigation} requires the {} window function",
+                        navigation.window_function()
+                    )
+                })?;
+            let window_expr = Expr::from(WindowFunction::new(
+                WindowFunctionDefinition::WindowUDF(udwf),
+                vec![arg, lit(offset)],
+            ))
+            .partition_by(partition_by.clone())
+            .order_by(order_by.clone())
+            .build()?;
+            navigation_names.push(window_expr.schema_name().to_string());
+            if !window_exprs.contains(&window_expr) {
+                window_exprs.push(window_expr);
+            }
+        }
+        let input = if window_exprs.is_empty() {
+            input
+        } else {
+            // Name the window function columns like their placeholders
+            let exprs = input_schema
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .chain(navigation_names.into_iter().enumerate().map(|(i, name)| {
+                    Expr::Column(Column::from_name(name)).alias(navigation_column(i))
+                }))
+                .collect::<Vec<_>>();
+            let window = LogicalPlanBuilder::window_plan(input, window_exprs)?;
+            LogicalPlanBuilder::from(window).project(exprs)?.build()?
+        };
+        let schema = Arc::clone(input.schema());
+        let defines = conditions
+            .into_iter()
+            .map(|(symbol, condition)| {
+                let condition = self.sql_to_expr(condition, &schema, planner_context)?;
+                Ok(RowPatternDefinition::new(symbol, condition))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Replace the row pattern functions in MEASURES by columns computed by
+        // the MatchRecognize node
+        let mut functions = MeasureRewriter {
+            context_provider: self.context_provider,
+            functions: vec![],
+        };
+        let mut measure_exprs = vec![];
+        for Measure { mut expr, alias } in measures {
+            control_flow_to_result(expr.visit(&mut functions))?;
+            measure_exprs.push((expr, self.ident_normalizer.normalize(alias)));
+        }
+        let row_pattern_measures = functions
+            .functions
+            .into_iter()
+            .enumerate()
+            .map(|(i, function)| {
+                let (variable, function) = match function {
+                    SqlRowPatternFunction::First(mut expr) => {
+                        let variable = strip_variable(&mut expr, &variables)?;
+                        let expr = self.sql_to_expr(expr, &schema, planner_context)?;
+                        (variable, RowPatternFunction::First(expr))
+                    }
+                    SqlRowPatternFunction::Last(mut expr) => {
+                        let variable = strip_variable(&mut expr, &variables)?;
+                        let expr = self.sql_to_expr(expr, &schema, planner_context)?;
+                        (variable, RowPatternFunction::Last(expr))
+                    }
+                    SqlRowPatternFunction::Aggregate(mut expr) => {
+                        let variable = strip_variable(&mut expr, &variables)?;
+                        let expr = self.sql_to_expr(expr, &schema, planner_context)?;
+                        (variable, RowPatternFunction::Aggregate(expr.unalias()))
+                    }
+                    SqlRowPatternFunction::Classifier => {
+                        (None, RowPatternFunction::Classifier)
+                    }
+                    SqlRowPatternFunction::MatchNumber => {
+                        (None, RowPatternFunction::MatchNumber)
+                    }
+                };
+                Ok(RowPatternMeasure::new(
+                    measure_column(i),
+                    variable,
+                    function,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_input_columns = match rows_per_match {
+            RowsPerMatch::One => partition_by.len(),
+            // The columns computed for PREV and NEXT are not part of the output
+            RowsPerMatch::All => input_schema.fields().len(),
+        };
+        let match_recognize = MatchRecognize::try_new(
+            Arc::new(input),
+            partition_by,
+            order_by,
+            row_pattern_measures,
+            rows_per_match,
+            after_match_skip,
+            pattern,
+            defines,
+        )?;
+        let schema = Arc::clone(&match_recognize.schema);
+        let mut exprs = (0..num_input_columns)
+            .map(|i| Expr::Column(Column::from(schema.qualified_field(i))))
+            .collect::<Vec<_>>();
+        for (expr, name) in measure_exprs {
+            exprs.push(
+                self.sql_to_expr(expr, &schema, planner_context)?
+                    .alias(name),
+            );
+        }
+        LogicalPlanBuilder::from(LogicalPlan::MatchRecognize(match_recognize))
+            .project(exprs)?
+            .build()
+    }
+}
+
+fn navigation_column(i: usize) -> String {
+    format!("__match_recognize_navigation_{i}")
+}
+
+fn measure_column(i: usize) -> String {
+    format!("__match_recognize_measure_{i}")
+}
+
+fn placeholder(name: String) -> SQLExpr {
+    SQLExpr::Identifier(Ident::with_quote('"', name))
+}
+
+fn control_flow_to_result(flow: ControlFlow<DataFusionError>) -> Result<()> {
+    match flow {
+        ControlFlow::Continue(()) => Ok(()),
+        ControlFlow::Break(e) => Err(e),
+    }
+}
+
+/// Converts the pattern of `MATCH_RECOGNIZE`
+fn row_pattern(pattern: MatchRecognizePattern) -> Result<RowPattern> {
+    fn symbol(symbol: MatchRecognizeSymbol) -> RowPattern {
+        match symbol {
+            MatchRecognizeSymbol::Named(ident) => RowPattern::Variable(ident.value),
+            MatchRecognizeSymbol::Start => RowPattern::PartitionStart,
+            MatchRecognizeSymbol::End => RowPattern::PartitionEnd,
+        }
+    }
+
+    let patterns = |patterns: Vec<MatchRecognizePattern>| {
+        patterns.into_iter().map(row_pattern).collect::<Result<_>>()
+    };
+    Ok(match pattern {
+        MatchRecognizePattern::Symbol(s) => symbol(s),
+        MatchRecognizePattern::Exclude(s) => RowPattern::Exclude(Box::new(symbol(s))),
+        MatchRecognizePattern::Permute(symbols) => {
+            RowPattern::Permute(symbols.into_iter().map(symbol).collect())
+        }
+        MatchRecognizePattern::Concat(p) => RowPattern::Concat(patterns(p)?),
+        MatchRecognizePattern::Group(pattern) => row_pattern(*pattern)?,
+        MatchRecognizePattern::Alternation(p) => RowPattern::Alternation(patterns(p)?),
+        MatchRecognizePattern::Repetition(pattern, quantifier) => {
+            let (min, max) = match quantifier {
+                RepetitionQuantifier::ZeroOrMore => (0, None),
+                RepetitionQuantifier::OneOrMore => (1, None),
+                RepetitionQuantifier::AtMostOne => (0, Some(1)),
+                RepetitionQuantifier::Exactly(n) => (n, Some(n)),
+                RepetitionQuantifier::AtLeast(n) => (n, None),
+                RepetitionQuantifier::AtMost(m) => (0, Some(m)),
+                RepetitionQuantifier::Range(n, m) => {
+                    if n > m {
+                        return plan_err!(
+                            "Invalid pattern quantifier {{{n},{m}}}: the minimum is greater than the maximum"
+                        );
+                    }
+                    (n, Some(m))
+                }
+            };
+            RowPattern::Repetition {
+                pattern: Box::new(row_pattern(*pattern)?),
+                min,
+                max,
+            }
+        }
+    })
+}
+
+/// Removes the pattern variable qualifier from the column references
+/// `<variable>.<column>` in `expr`, returning the variable. All qualified
+/// references must use the same variable.
+fn strip_variable(
+    expr: &mut SQLExpr,
+    variables: &BTreeSet<String>,
+) -> Result<Option<String>> {
+    let mut variable: Option<String> = None;
+    control_flow_to_result(visit_expressions_mut(expr, |expr| {
+        if let SQLExpr::CompoundIdentifier(idents) = expr
+            && let [qualifier, column] = idents.as_slice()
+            && variables.contains(&qualifier.value)
+        {
+            match &variable {
+                Some(variable) if *variable != qualifier.value => {
+                    return ControlFlow::Break(not_impl_datafusion_err!(
+                        "References to different pattern variables {variable} and {qualifier} in one row pattern function are not supported"
+                    ));
+                }
+                _ => variable = Some(qualifier.value.clone()),
+            }
+            *expr = SQLExpr::Identifier(column.clone());
+        }
+        ControlFlow::Continue(())
+    }))?;
+    Ok(variable)
+}
+
+/// Removes the pattern variable qualifier from the column references of
+/// the condition of `symbol`, which can only reference its own variable
+fn check_own_variable(
+    symbol: &str,
+    expr: &mut SQLExpr,
+    variables: &BTreeSet<String>,
+) -> Result<()> {
+    match strip_variable(expr, variables)? {
+        Some(variable) if variable != symbol => not_impl_err!(
+            "References to other pattern variables in DEFINE are not supported: {variable} in the condition of {symbol}"
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the lowercase name of an unqualified function call without `OVER`
+/// clause, and its arguments if they are all unnamed expressions
+fn function_call(expr: &SQLExpr) -> Option<(String, Option<Vec<&SQLExpr>>)> {
+    let SQLExpr::Function(function) = expr else {
+        return None;
+    };
+    if function.over.is_some() {
+        return None;
+    }
+    let [name] = function.name.0.as_slice() else {
+        return None;
+    };
+    let name = name.as_ident()?.value.to_lowercase();
+    let args = match &function.args {
+        FunctionArguments::None => Some(vec![]),
+        FunctionArguments::List(list) => list
+            .args
+            .iter()
+            .map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Some(expr),
+                _ => None,
+            })
+            .collect(),
+        FunctionArguments::Subquery(_) => None,
+    };
+    Some((name, args))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Navigation {
+    Prev,
+    Next,
+}
+
+impl Navigation {
+    fn window_function(&self) -> &'static str {
+        match self {
+            Self::Prev => "lag",
+            Self::Next => "lead",
+        }
+    }
+}
+
+impl std::fmt::Display for Navigation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Prev => write!(f, "PREV"),
+            Self::Next => write!(f, "NEXT"),
+        }
+    }
+}
+
+/// Replaces `PREV(<expr> [, <offset>])` and `NEXT(<expr> [, <offset>])` in
+/// the conditions of `DEFINE` by placeholder columns
+#[derive(Default)]
+struct NavigationRewriter {
+    navigations: Vec<(Navigation, SQLExpr, i64)>,
+    /// The index of the condition of each navigation
+    symbols: Vec<usize>,
+    /// The index of the condition being visited
+    condition: usize,
+}
+
+impl NavigationRewriter {
+    fn navigation(&self, expr: &SQLExpr) -> Result<Option<(Navigation, SQLExpr, i64)>> {
+        let Some((name, args)) = function_call(expr) else {
+            return Ok(None);
+        };
+        let navigation = match name.as_str() {
+            "prev" => Navigation::Prev,
+            "next" => Navigation::Next,
+            "first" | "last" | "classifier" | "match_number" => {
+                return not_impl_err!(
+                    "{} is not supported in DEFINE",
+                    name.to_uppercase()
+                );
+            }
+            _ => return Ok(None),
+        };
+        let (arg, offset) = match args.as_deref() {
+            Some([arg]) => (arg, 1),
+            Some([arg, SQLExpr::Value(value)]) => match &value.value {
+                Value::Number(n, _) => match n.parse::<i64>() {
+                    Ok(offset) if offset >= 0 => (arg, offset),
+                    _ => {
+                        return plan_err!(
+                            "The offset of {navigation} must be a non-negative integer, got {n}"
+                        );
+                    }
+                },
+                _ => {
+                    return plan_err!(
+                        "The offset of {navigation} must be a non-negative integer, got {value}"
+                    );
+                }
+            },
+            _ => return plan_err!("{navigation} expects an expression and an offset"),
+        };
+        if function_call(arg).is_some_and(|(name, _)| name == "prev" || name == "next") {
+            return not_impl_err!("Nested {navigation} is not supported");
+        }
+        Ok(Some((navigation, (*arg).clone(), offset)))
+    }
+}
+
+impl VisitorMut for NavigationRewriter {
+    type Break = DataFusionError;
+
+    fn pre_visit_expr(&mut self, expr: &mut SQLExpr) -> ControlFlow<Self::Break> {
+        match self.navigation(expr) {
+            Ok(Some(navigation)) => {
+                *expr = placeholder(navigation_column(self.navigations.len()));
+                self.navigations.push(navigation);
+                self.symbols.push(self.condition);
+                ControlFlow::Continue(())
+            }
+            Ok(None) => ControlFlow::Continue(()),
+            Err(e) => ControlFlow::Break(e),
+        }
+    }
+}
+
+/// A row pattern function in `MEASURES`, before planning its argument
+enum SqlRowPatternFunction {
+    First(SQLExpr),
+    Last(SQLExpr),
+    Aggregate(SQLExpr),
+    Classifier,
+    MatchNumber,
+}
+
+/// Replaces the row pattern functions in `MEASURES` by placeholder columns.
+/// Column references outside of functions refer to the last row, like
+/// `LAST(<column>)`.
+struct MeasureRewriter<'a, S: ContextProvider> {
+    context_provider: &'a S,
+    functions: Vec<SqlRowPatternFunction>,
+}
+
+impl<S: ContextProvider> MeasureRewriter<'_, S> {
+    fn function(&self, expr: &SQLExpr) -> Result<Option<SqlRowPatternFunction>> {
+        if matches!(
+            expr,
+            SQLExpr::Identifier(_) | SQLExpr::CompoundIdentifier(_)
+        ) {
+            return Ok(Some(SqlRowPatternFunction::Last(expr.clone())));
+        }
+        let Some((name, args)) = function_call(expr) else {
+            return Ok(None);
+        };
+        let function = match (name.as_str(), args.as_deref()) {
+            ("first", Some([arg])) => SqlRowPatternFunction::First((*arg).clone()),
+            ("last", Some([arg])) => SqlRowPatternFunction::Last((*arg).clone()),
+            ("classifier", Some([])) => SqlRowPatternFunction::Classifier,
+            ("match_number", Some([])) => SqlRowPatternFunction::MatchNumber,
+            ("first" | "last" | "classifier" | "match_number", _) => {
+                return not_impl_err!(
+                    "{} with these arguments is not supported in MEASURES",
+                    name.to_uppercase()
+                );
+            }
+            ("prev" | "next", _) => {
+                return not_impl_err!(
+                    "{} is not supported in MEASURES",
+                    name.to_uppercase()
+                );
+            }
+            _ if self.context_provider.get_aggregate_meta(&name).is_some() => {
+                SqlRowPatternFunction::Aggregate(expr.clone())
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(function))
+    }
+}
+
+impl<S: ContextProvider> VisitorMut for MeasureRewriter<'_, S> {
+    type Break = DataFusionError;
+
+    fn pre_visit_expr(&mut self, expr: &mut SQLExpr) -> ControlFlow<Self::Break> {
+        match self.function(expr) {
+            Ok(Some(function)) => {
+                *expr = placeholder(measure_column(self.functions.len()));
+                self.functions.push(function);
+                ControlFlow::Continue(())
+            }
+            Ok(None) => ControlFlow::Continue(()),
+            Err(e) => ControlFlow::Break(e),
+        }
+    }
+}
@@ -32,6 +32,11 @@ use datafusion_expr::{Subquery, SubqueryAlias};
 use sqlparser::ast::{FunctionArg, FunctionArgExpr, Spanned, TableFactor};
 
 mod join;
+mod match_recognize;
+mod pivot;
+mod sample;
+
+use match_recognize::MatchRecognizeClauses;
 
 struct SqlToRelRelationContext<'a, 'b, S: ContextProvider> {
     planner: &'a SqlToRel<'b, S>,
@@ -150,9 +155,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         let relation_span = relation.span();
         let (plan, alias) = match relation {
             TableFactor::Table {
-                name, alias, args, ..
+                name,
+                alias,
+                args,
+                sample,
+                ..
             } => {
-                if let Some(func_args) = args {
+                let (plan, alias) = if let Some(func_args) = args {
                     let tbl_func_name =
                         name.0.first().unwrap().as_ident().unwrap().to_string();
                     let args = func_args
@@ -210,6 +219,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                         }?,
                         alias,
                     )
+                };
+                match sample {
+                    Some(sample) => (
+                        self.plan_table_sample(plan, sample, planner_context)?,
+                        alias,
+                    ),
+                    None => (plan, alias),
                 }
             }
             TableFactor::Derived {
@@ -294,6 +310,71 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                         .build()?;
                 (plan, alias)
             }
+            TableFactor::Pivot {
+                table,
+                aggregate_functions,
+                value_column,
+                value_source,
+                default_on_null,
+                alias,
+            } => {
+                let input = self.create_relation(*table, planner_context)?;
+                let plan = self.plan_pivot(
+                    input,
+                    aggregate_functions,
+                    value_column,
+                    value_source,
+                    default_on_null,
+                    planner_context,
+                )?;
+                (plan, alias)
+            }
+            TableFactor::Unpivot {
+                table,
+                value,
+                name,
+                columns,
+                null_inclusion,
+                alias,
+            } => {
+                let input = self.create_relation(*table, planner_context)?;
+                let plan = self.plan_unpivot(
+                    input,
+                    value,
+                    name,
+                    columns,
+                    null_inclusion.as_ref(),
+                    planner_context,
+                )?;
+                (plan, alias)
+            }
+            TableFactor::MatchRecognize {
+                table,
+                partition_by,
+                order_by,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                pattern,
+                symbols,
+                alias,
+            } => {
+                let input = self.create_relation(*table, planner_context)?;
+                let plan = self.plan_match_recognize(
+                    input,
+                    MatchRecognizeClauses {
+                        partition_by,
+                        order_by,
+                        measures,
+                        rows_per_match,
+                        after_match_skip,
+                        pattern,
+                        symbols,
+                    },
+                    planner_context,
+                )?;
+                (plan, alias)
+            }
             // @todo Support TableFactory::TableFunction?
             _ => {
                 return not_impl_err!(
@@ -0,0 +1,148 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion_common::{DFSchema, Result, not_impl_err};
+use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder, when};
+use sqlparser::ast::{
+    Expr as SQLExpr, ExprWithAlias, Ident, NullInclusion, PivotValueSource,
+};
+
+impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plan `<input> PIVOT (<aggregates> FOR <column> IN (<values>))` as an
+    /// aggregate of one filtered aggregate per value, see
+    /// [`LogicalPlanBuilder::pivot`].
+    ///
+    /// Dynamic values (`IN (ANY)` or `IN (<subquery>)`) must have been resolved
+    /// to a list of values before planning, see
+    /// [`crate::resolve::dynamic_pivot_queries`].
+    pub(super) fn plan_pivot(
+        &self,
+        input: LogicalPlan,
+        aggregate_functions: Vec<ExprWithAlias>,
+        value_column: Vec<SQLExpr>,
+        value_source: PivotValueSource,
+        default_on_null: Option<SQLExpr>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let Ok([value_column]) = <[SQLExpr; 1]>::try_from(value_column) else {
+            return not_impl_err!("PIVOT with multiple value columns is not supported");
+        };
+        let PivotValueSource::List(values) = value_source else {
+            return not_impl_err!(
+                "PIVOT ... IN ({value_source}) must be resolved to a list of values before planning"
+            );
+        };
+
+        let schema = input.schema().as_ref().clone();
+        let aggr_exprs = aggregate_functions
+            .into_iter()
+            .map(|aggr| self.sql_to_aliased_expr(aggr, &schema, planner_context))
+            .collect::<Result<Vec<_>>>()?;
+        let pivot_column = self.sql_to_expr(value_column, &schema, planner_context)?;
+        let pivot_values = values
+            .into_iter()
+            .map(|value| {
+                self.sql_to_aliased_expr(value, &DFSchema::empty(), planner_context)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let num_pivot_exprs = aggr_exprs.len() * pivot_values.len();
+
+        let plan = LogicalPlanBuilder::from(input)
+            .pivot(aggr_exprs, pivot_column, pivot_values)?
+            .build()?;
+        let Some(default_on_null) = default_on_null else {
+            return Ok(plan);
+        };
+
+        // Replace the NULL results of the pivot columns, which follow the
+        // grouping columns
+        let default_on_null =
+            self.sql_to_expr(default_on_null, &DFSchema::empty(), planner_context)?;
+        let num_group_exprs = plan.schema().fields().len() - num_pivot_exprs;
+        let exprs = plan
+            .schema()
+            .columns()
+            .into_iter()
+            .enumerate()
+            .map(|(i, column)| {
+                if i < num_group_exprs {
+                    return Ok(Expr::Column(column));
+                }
+                let name = column.name.clone();
+                let column = Expr::Column(column);
+                Ok(when(column.clone().is_null(), default_on_null.clone())
+                    .otherwise(column)?
+                    .alias(name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        LogicalPlanBuilder::from(plan).project(exprs)?.build()
+    }
+
+    /// Plan `<input> UNPIVOT (<value> FOR <name> IN (<columns>))` as a union
+    /// of one projection per column, see [`LogicalPlanBuilder::unpivot`].
+    pub(super) fn plan_unpivot(
+        &self,
+        input: LogicalPlan,
+        value: SQLExpr,
+        name: Ident,
+        columns: Vec<ExprWithAlias>,
+        null_inclusion: Option<&NullInclusion>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let SQLExpr::Identifier(value) = value else {
+            return not_impl_err!("UNPIVOT with multiple value columns is not supported");
+        };
+        let schema = input.schema().as_ref().clone();
+        let columns = columns
+            .into_iter()
+            .map(|column| {
+                if matches!(column.expr, SQLExpr::Tuple(_)) {
+                    return not_impl_err!(
+                        "UNPIVOT with multiple value columns is not supported"
+                    );
+                }
+                self.sql_to_aliased_expr(column, &schema, planner_context)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Rows with a NULL value are excluded by default
+        let include_nulls = matches!(null_inclusion, Some(&NullInclusion::IncludeNulls));
+        LogicalPlanBuilder::from(input)
+            .unpivot(
+                self.ident_normalizer.normalize(value),
+                self.ident_normalizer.normalize(name),
+                columns,
+                include_nulls,
+            )?
+            .build()
+    }
+
+    fn sql_to_aliased_expr(
+        &self,
+        ExprWithAlias { expr, alias }: ExprWithAlias,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        // Drop the aliases added by the planner, e.g. `count(*)`
+        let expr = self.sql_to_expr(expr, schema, planner_context)?.unalias();
+        Ok(match alias {
+            Some(alias) => expr.alias(self.ident_normalizer.normalize(alias)),
+            None => expr,
+        })
+    }
+}
@@ -0,0 +1,127 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use arrow::datatypes::DataType;
+use datafusion_common::{DFSchema, Result, not_impl_err, plan_err};
+use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder, SampleMethod};
+use sqlparser::ast::{
+    Expr as SQLExpr, TableSampleKind, TableSampleMethod, TableSampleUnit,
+};
+
+impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plan `<input> TABLESAMPLE <method> (<quantity>) [REPEATABLE (<seed>)]`
+    /// as a [`LogicalPlan::Sample`].
+    ///
+    /// `BERNOULLI` and `ROW` sample individual rows, `SYSTEM` and `BLOCK`
+    /// sample blocks of rows. A quantity without a unit is a percentage, and
+    /// `<n> ROWS` returns a sample of `n` rows.
+    pub(super) fn plan_table_sample(
+        &self,
+        input: LogicalPlan,
+        sample: TableSampleKind,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let (TableSampleKind::BeforeTableAlias(sample)
+        | TableSampleKind::AfterTableAlias(sample)) = sample;
+
+        if sample.bucket.is_some() {
+            return not_impl_err!("TABLESAMPLE with BUCKET is not supported");
+        }
+        if sample.offset.is_some() {
+            return not_impl_err!("TABLESAMPLE with OFFSET is not supported");
+        }
+        let Some(quantity) = sample.quantity else {
+            return plan_err!("TABLESAMPLE requires a sample size");
+        };
+
+        let seed = sample
+            .seed
+            .map(|seed| match seed.value.to_string().parse::<u64>() {
+                Ok(seed) => Ok(seed),
+                Err(_) => plan_err!(
+                    "TABLESAMPLE seed must be a non-negative integer, got {}",
+                    seed.value
+                ),
+            })
+            .transpose()?;
+
+        let value = self.table_sample_quantity(quantity.value, planner_context)?;
+        let block_sampling = matches!(
+            sample.name,
+            Some(TableSampleMethod::System | TableSampleMethod::Block)
+        );
+        let method = match quantity.unit {
+            Some(TableSampleUnit::Rows) => {
+                if block_sampling {
+                    return plan_err!(
+                        "TABLESAMPLE {} does not support a number of rows",
+                        sample.name.unwrap()
+                    );
+                }
+                if value < 0.0 || value.fract() != 0.0 {
+                    return plan_err!(
+                        "TABLESAMPLE number of rows must be a non-negative integer, got {value}"
+                    );
+                }
+                SampleMethod::Rows(value as usize)
+            }
+            Some(TableSampleUnit::Percent) | None => {
+                if !(0.0..=100.0).contains(&value) {
+                    return plan_err!(
+                        "TABLESAMPLE percentage must be between 0 and 100, got {value}"
+                    );
+                }
+                if block_sampling {
+                    SampleMethod::System(value / 100.0)
+                } else {
+                    SampleMethod::Bernoulli(value / 100.0)
+                }
+            }
+        };
+
+        LogicalPlanBuilder::from(input)
+            .sample(method, seed)?
+            .build()
+    }
+
+    /// Evaluate the sample size of a `TABLESAMPLE` clause, which must be a
+    /// numeric literal
+    fn table_sample_quantity(
+        &self,
+        value: SQLExpr,
+        planner_context: &mut PlannerContext,
+    ) -> Result<f64> {
+        let expr = self.sql_to_expr(value, &DFSchema::empty(), planner_context)?;
+        let (negated, expr) = match expr {
+            Expr::Negative(expr) => (true, *expr),
+            expr => (false, expr),
+        };
+        let value = match &expr {
+            Expr::Literal(value, _) if value.data_type().is_numeric() => {
+                value.cast_to(&DataType::Float64)?
+            }
+            _ => {
+                return plan_err!(
+                    "TABLESAMPLE sample size must be a numeric literal, got {expr}"
+                );
+            }
+        };
+        let value: f64 = value.try_into()?;
+        Ok(if negated { -value } else { value })
+    }
+}
@@ -18,12 +18,15 @@
 use std::collections::BTreeSet;
 use std::ops::ControlFlow;
 
-use datafusion_common::{DataFusionError, Result};
+use datafusion_common::{DataFusionError, Result, internal_datafusion_err, not_impl_err};
 
 use crate::TableReference;
 use crate::parser::{CopyToSource, CopyToStatement, Statement as DFStatement};
 use crate::planner::object_name_to_table_reference;
+use sqlparser::ast::helpers::attached_token::AttachedToken;
 use sqlparser::ast::*;
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::Parser;
 
 // following constants are used in `resolve_table_references`
 // and should be same as `datafusion/catalog/src/information_schema.rs`
@@ -185,7 +188,10 @@ fn visit_statement(statement: &DFStatement, visitor: &mut RelationVisitor) -> Re
         DFStatement::Explain(explain) => {
             visit_statement(&explain.statement, visitor)?;
         }
-        DFStatement::Reset(_) => {}
+        DFStatement::AnalyzeTable(analyze) => {
+            control_flow_to_result(visitor.insert_relation(&analyze.table_name))?;
+        }
+        DFStatement::Reset(_) | DFStatement::RefreshMaterializedView(_) => {}
     }
     Ok(())
 }
@@ -243,6 +249,211 @@ pub fn resolve_table_references(
     ))
 }
 
+// Collects the queries returning the values of dynamic pivots, i.e.
+// `PIVOT ... IN (ANY [ORDER BY ...])` and `PIVOT ... IN (<subquery>)`.
+struct DynamicPivotCollector<'a> {
+    dialect: &'a dyn Dialect,
+    /// CTEs of the enclosing queries, which may be referenced by a pivot input
+    ctes_in_scope: Vec<Cte>,
+    queries: Vec<Query>,
+}
+
+impl DynamicPivotCollector<'_> {
+    fn values_query(&self, table_factor: &TableFactor) -> Result<Option<Query>> {
+        let TableFactor::Pivot {
+            table,
+            value_column,
+            value_source,
+            ..
+        } = table_factor
+        else {
+            return Ok(None);
+        };
+        let mut query = match value_source {
+            PivotValueSource::List(_) => return Ok(None),
+            PivotValueSource::Any(order_by) => {
+                let [value_column] = value_column.as_slice() else {
+                    return not_impl_err!(
+                        "PIVOT with multiple value columns is not supported"
+                    );
+                };
+                let order_by = if order_by.is_empty() {
+                    value_column.to_string()
+                } else {
+                    order_by
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                let sql = format!(
+                    "SELECT DISTINCT {value_column} FROM {table} ORDER BY {order_by}"
+                );
+                *Parser::new(self.dialect)
+                    .try_with_sql(&sql)?
+                    .parse_query()?
+            }
+            PivotValueSource::Subquery(query) => query.as_ref().clone(),
+        };
+
+        if !self.ctes_in_scope.is_empty() {
+            let mut cte_tables = self.ctes_in_scope.clone();
+            let mut recursive = false;
+            if let Some(with) = query.with.take() {
+                recursive = with.recursive;
+                cte_tables.extend(with.cte_tables);
+            }
+            query.with = Some(With {
+                with_token: AttachedToken::empty(),
+                recursive,
+                cte_tables,
+            });
+        }
+        Ok(Some(query))
+    }
+}
+
+impl Visitor for DynamicPivotCollector<'_> {
+    type Break = DataFusionError;
+
+    fn pre_visit_query(&mut self, q: &Query) -> ControlFlow<Self::Break> {
+        if let Some(with) = &q.with {
+            self.ctes_in_scope.extend(with.cte_tables.iter().cloned());
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, q: &Query) -> ControlFlow<Self::Break> {
+        if let Some(with) = &q.with {
+            let len = self.ctes_in_scope.len() - with.cte_tables.len();
+            self.ctes_in_scope.truncate(len);
+        }
+        ControlFlow::Continue(())
+    }
+
+    // Pivots are visited bottom up, so that nested dynamic pivots are
+    // collected (and replaced) in the same order by both visitors
+    fn post_visit_table_factor(
+        &mut self,
+        table_factor: &TableFactor,
+    ) -> ControlFlow<Self::Break> {
+        match self.values_query(table_factor) {
+            Ok(Some(query)) => self.queries.push(query),
+            Ok(None) => {}
+            Err(e) => return ControlFlow::Break(e),
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+// Replaces the value source of dynamic pivots with their resolved values.
+struct DynamicPivotResolver {
+    values: std::vec::IntoIter<Vec<ExprWithAlias>>,
+}
+
+impl VisitorMut for DynamicPivotResolver {
+    type Break = DataFusionError;
+
+    fn post_visit_table_factor(
+        &mut self,
+        table_factor: &mut TableFactor,
+    ) -> ControlFlow<Self::Break> {
+        if let TableFactor::Pivot { value_source, .. } = table_factor
+            && !matches!(value_source, PivotValueSource::List(_))
+        {
+            let Some(values) = self.values.next() else {
+                return ControlFlow::Break(internal_datafusion_err!(
+                    "No values provided for PIVOT ... IN ({value_source})"
+                ));
+            };
+            *value_source = PivotValueSource::List(values);
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Returns the queries computing the values of the dynamic pivots in the SQL
+/// statement, that is `PIVOT ... IN (ANY [ORDER BY ...])` and
+/// `PIVOT ... IN (<subquery>)`.
+///
+/// The output columns of a pivot depend on its values, so these queries must
+/// be run, and their results passed to [`resolve_dynamic_pivots`], before the
+/// statement can be planned. `dialect` is used to parse the generated queries.
+///
+/// An `EXPLAIN` statement, other than `EXPLAIN ANALYZE`, does not run queries,
+/// so no query is returned for its dynamic pivots, which can then not be
+/// planned.
+///
+/// ## Example
+///
+/// ```
+/// # use datafusion_sql::parser::DFParser;
+/// # use datafusion_sql::resolve::dynamic_pivot_queries;
+/// # use datafusion_sql::sqlparser::dialect::GenericDialect;
+/// let query = "SELECT * FROM sales PIVOT (sum(amount) FOR quarter IN (ANY))";
+/// let statement = DFParser::parse_sql(query).unwrap().pop_back().unwrap();
+/// let queries = dynamic_pivot_queries(&statement, &GenericDialect {}).unwrap();
+/// assert_eq!(
+///     queries[0].to_string(),
+///     "SELECT DISTINCT quarter FROM sales ORDER BY quarter"
+/// );
+/// ```
+pub fn dynamic_pivot_queries(
+    statement: &crate::parser::Statement,
+    dialect: &dyn Dialect,
+) -> Result<Vec<Query>> {
+    let mut visitor = DynamicPivotCollector {
+        dialect,
+        ctes_in_scope: vec![],
+        queries: vec![],
+    };
+    match statement {
+        DFStatement::Statement(s) => control_flow_to_result(s.visit(&mut visitor))?,
+        DFStatement::CopyTo(CopyToStatement {
+            source: CopyToSource::Query(query),
+            ..
+        }) => control_flow_to_result(query.visit(&mut visitor))?,
+        DFStatement::Explain(explain) if explain.options.analyze => {
+            return dynamic_pivot_queries(&explain.statement, dialect);
+        }
+        DFStatement::Explain(_)
+        | DFStatement::CopyTo(_)
+        | DFStatement::CreateExternalTable(_)
+        | DFStatement::Reset(_)
+        | DFStatement::RefreshMaterializedView(_)
+        | DFStatement::AnalyzeTable(_) => {}
+    }
+    Ok(visitor.queries)
+}
+
+/// Replaces the values of the dynamic pivots in the SQL statement with
+/// `values`, which holds the values of each query returned by
+/// [`dynamic_pivot_queries`], in the same order.
+pub fn resolve_dynamic_pivots(
+    statement: &mut crate::parser::Statement,
+    values: Vec<Vec<ExprWithAlias>>,
+) -> Result<()> {
+    let mut visitor = DynamicPivotResolver {
+        values: values.into_iter(),
+    };
+    match statement {
+        DFStatement::Statement(s) => control_flow_to_result(s.visit(&mut visitor)),
+        DFStatement::CopyTo(CopyToStatement {
+            source: CopyToSource::Query(query),
+            ..
+        }) => control_flow_to_result(query.visit(&mut visitor)),
+        DFStatement::Explain(explain) if explain.options.analyze => {
+            resolve_dynamic_pivots(&mut explain.statement, visitor.values.collect())
+        }
+        DFStatement::Explain(_)
+        | DFStatement::CopyTo(_)
+        | DFStatement::CreateExternalTable(_)
+        | DFStatement::Reset(_)
+        | DFStatement::RefreshMaterializedView(_)
+        | DFStatement::AnalyzeTable(_) => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
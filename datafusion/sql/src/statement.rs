@@ -21,45 +21,54 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::parser::{
-    CopyToSource, CopyToStatement, CreateExternalTable, DFParser, ExplainStatement,
-    LexOrdering, ResetStatement, Statement as DFStatement,
+    AnalyzeTableStatement, CopyToSource, CopyToStatement, CreateExternalTable, DFParser,
+    ExplainStatement, LexOrdering, RefreshMaterializedViewStatement, ResetStatement,
+    Statement as DFStatement,
 };
 use crate::planner::{
     ContextProvider, PlannerContext, SqlToRel, object_name_to_qualifier,
 };
 use crate::utils::normalize_ident;
 
-use arrow::datatypes::{Field, FieldRef, Fields};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::cast::as_int64_array;
+use datafusion_common::collation::Collation;
 use datafusion_common::error::_plan_err;
 use datafusion_common::format::ExplainStatementOptions;
+use datafusion_common::metadata::FieldMetadata;
 use datafusion_common::parsers::CompressionTypeVariant;
 use datafusion_common::{
-    Column, Constraint, Constraints, DFSchema, DFSchemaRef, DataFusionError, Result,
-    ScalarValue, SchemaError, SchemaReference, TableReference, ToDFSchema, exec_err,
-    internal_err, not_impl_err, plan_datafusion_err, plan_err, schema_err,
+    Column, Constraint, Constraints, DFSchema, DFSchemaRef, DataFusionError, JoinType,
+    Result, ScalarValue, SchemaError, SchemaReference, TableReference, ToDFSchema,
+    exec_err, internal_err, not_impl_err, plan_datafusion_err, plan_err, schema_err,
     unqualified_field_not_found,
 };
-use datafusion_expr::dml::{CopyTo, InsertOp};
+use datafusion_expr::dml::{CopyTo, InsertOp, MERGE_ACTION_COLUMN, MergeAction};
+use datafusion_expr::expr::WindowFunction;
 use datafusion_expr::expr_rewriter::normalize_col_with_schemas_and_ambiguity_check;
 use datafusion_expr::logical_plan::DdlStatement;
 use datafusion_expr::logical_plan::builder::project;
 use datafusion_expr::utils::expr_to_columns;
 use datafusion_expr::{
-    Analyze, CreateCatalog, CreateCatalogSchema,
+    AlterTable as PlanAlterTable, AlterTableOperation, Analyze, AnalyzeTable,
+    ColumnarValue, CreateCatalog, CreateCatalogSchema,
     CreateExternalTable as PlanCreateExternalTable, CreateFunction, CreateFunctionBody,
     CreateIndex as PlanCreateIndex, CreateMemoryTable, CreateView, Deallocate,
     DescribeTable, DmlStatement, DropCatalogSchema, DropFunction, DropTable, DropView,
-    EmptyRelation, Execute, Explain, ExplainFormat, Expr, ExprSchemable, Filter,
-    LogicalPlan, LogicalPlanBuilder, OperateFunctionArg, PlanType, Prepare,
-    ResetVariable, SetVariable, SortExpr, Statement as PlanStatement, ToStringifiedPlan,
-    TransactionAccessMode, TransactionConclusion, TransactionEnd,
-    TransactionIsolationLevel, TransactionStart, Volatility, WriteOp, cast, col,
+    EmptyRelation, Execute, Explain, ExplainFormat, Expr, ExprFunctionExt, ExprSchemable,
+    Filter, LogicalPlan, LogicalPlanBuilder, OperateFunctionArg, PlanType, Prepare,
+    RefreshMaterializedView, ResetVariable, ScalarUDF, SetVariable, SortExpr,
+    Statement as PlanStatement, ToStringifiedPlan, TransactionAccessMode,
+    TransactionConclusion, TransactionEnd, TransactionIsolationLevel, TransactionStart,
+    Volatility, WindowFunctionDefinition, WriteOp, cast, col, create_udf, expr::Case,
+    lit,
 };
 use sqlparser::ast::{
     self, BeginTransactionKind, CheckConstraint, ForeignKeyConstraint, IndexColumn,
-    IndexType, NullsDistinctOption, OrderByExpr, OrderByOptions, PrimaryKeyConstraint,
-    Set, ShowStatementIn, ShowStatementOptions, SqliteOnConflict, TableObject,
-    UniqueConstraint, Update, UpdateTableFromKind, ValueWithSpan,
+    IndexType, Merge, MergeAction as SQLMergeAction, MergeClause, MergeClauseKind,
+    MergeInsertKind, NullsDistinctOption, OrderByExpr, OrderByOptions,
+    PrimaryKeyConstraint, Set, ShowStatementIn, ShowStatementOptions, SqliteOnConflict,
+    TableObject, UniqueConstraint, Update, UpdateTableFromKind, ValueWithSpan,
 };
 use sqlparser::ast::{
     Assignment, AssignmentTarget, ColumnDef, CreateIndex, CreateTable,
@@ -221,6 +230,55 @@ fn calc_inline_constraints_from_columns(columns: &[ColumnDef]) -> Vec<TableConst
     constraints
 }
 
+/// Extracts the storage location from the `WITH (...)` options of a
+/// `CREATE MATERIALIZED VIEW` statement
+fn materialized_view_location(options: &[ast::SqlOption]) -> Result<Option<String>> {
+    let mut location = None;
+    for option in options {
+        let ast::SqlOption::KeyValue { key, value } = option else {
+            return not_impl_err!("Unsupported materialized view option: {option}");
+        };
+        if !key.value.eq_ignore_ascii_case("location") {
+            return plan_err!("Unsupported materialized view option: {key}");
+        }
+        let SQLExpr::Value(v) = value else {
+            return plan_err!("Unsupported value for option {key}: {value}");
+        };
+        let Some(path) = crate::utils::value_to_string(&v.value) else {
+            return plan_err!("Unsupported value for option {key}: {value}");
+        };
+        location = Some(path);
+    }
+    Ok(location)
+}
+
+/// Returns the function that checks the number of rows of the join of a
+/// `MERGE` for each target row, returning its second argument (the action of
+/// the row) unless a target row matched more than one source row
+fn merge_cardinality_check() -> ScalarUDF {
+    create_udf(
+        "merge_cardinality_check",
+        vec![DataType::Int64, DataType::Utf8],
+        DataType::Utf8,
+        Volatility::Immutable,
+        Arc::new(|args: &[ColumnarValue]| {
+            let [matches, action] = args else {
+                return internal_err!("merge_cardinality_check expects two arguments");
+            };
+            let matches = matches.to_array(1)?;
+            if as_int64_array(&matches)?
+                .iter()
+                .any(|count| count.is_some_and(|count| count > 1))
+            {
+                return exec_err!(
+                    "MERGE failed: a target row matched more than one source row"
+                );
+            }
+            Ok(action.clone())
+        }),
+    )
+}
+
 impl<S: ContextProvider> SqlToRel<'_, S> {
     /// Generate a logical plan from an DataFusion SQL statement
     pub fn statement_to_plan(&self, statement: DFStatement) -> Result<LogicalPlan> {
@@ -232,6 +290,10 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 self.explain_to_plan(options, *statement)
             }
             DFStatement::Reset(statement) => self.reset_statement_to_plan(statement),
+            DFStatement::RefreshMaterializedView(statement) => {
+                self.refresh_materialized_view_to_plan(statement)
+            }
+            DFStatement::AnalyzeTable(statement) => self.analyze_table_to_plan(statement),
         }
     }
 
@@ -562,13 +624,21 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                                 .iter()
                                 .zip(input_fields)
                                 .map(|(field, input_field)| {
-                                    cast(
+                                    let expr = cast(
                                         col(input_field.name()),
                                         field.data_type().clone(),
-                                    )
-                                    .alias(field.name())
+                                    );
+                                    // Keep the collation of the column
+                                    if Collation::from_field(field)?.is_binary() {
+                                        Ok(expr.alias(field.name()))
+                                    } else {
+                                        Ok(expr.alias_with_metadata(
+                                            field.name(),
+                                            Some(FieldMetadata::from(field.as_ref())),
+                                        ))
+                                    }
                                 })
-                                .collect::<Vec<_>>();
+                                .collect::<Result<Vec<_>>>()?;
 
                             LogicalPlanBuilder::from(plan.clone())
                                 .project(project_exprs)?
@@ -625,7 +695,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 name,
                 columns,
                 query,
-                options: CreateTableOptions::None,
+                options,
                 cluster_by,
                 comment,
                 with_no_schema_binding,
@@ -638,9 +708,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 name_before_not_exists,
                 copy_grants,
             }) => {
-                if materialized {
-                    return not_impl_err!("Materialized views not supported")?;
-                }
+                let location = match &options {
+                    CreateTableOptions::None => None,
+                    CreateTableOptions::With(options) if materialized => {
+                        materialized_view_location(options)?
+                    }
+                    _ => return not_impl_err!("View options not supported: {options:?}"),
+                };
                 if !cluster_by.is_empty() {
                     return not_impl_err!("Cluster by not supported")?;
                 }
@@ -668,7 +742,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     name,
                     columns,
                     query,
-                    options: CreateTableOptions::None,
+                    options,
                     cluster_by,
                     comment,
                     with_no_schema_binding,
@@ -688,6 +762,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     query,
                     or_replace,
                     temporary,
+                    materialized,
                     ..
                 }) = stmt
                 else {
@@ -716,6 +791,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     or_replace,
                     definition: Some(sql),
                     temporary,
+                    materialized,
+                    location,
                 })))
             }
             Statement::ShowCreate { obj_type, obj_name } => match obj_type {
@@ -1217,9 +1294,30 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 self.delete_to_plan(&table_name, selection, limit)
             }
 
+            Statement::Merge(Merge {
+                merge_token: _,
+                optimizer_hints,
+                into,
+                table,
+                source,
+                on,
+                clauses,
+                output,
+            }) => {
+                if !optimizer_hints.is_empty() {
+                    plan_err!("Optimizer hints not supported")?;
+                }
+                if output.is_some() {
+                    plan_err!("Merge OUTPUT clause not supported")?;
+                }
+                // optional keyword doesn't change behavior
+                let _ = into;
+                self.merge_to_plan(table, source, *on, clauses)
+            }
+
             Statement::StartTransaction {
                 modes,
-                begin: false,
+                begin: _,
                 modifier,
                 transaction,
                 statements,
@@ -1600,12 +1698,121 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     },
                 )))
             }
+            Statement::AlterTable(ast::AlterTable {
+                name,
+                if_exists,
+                only,
+                operations,
+                location,
+                on_cluster,
+                table_type,
+                end_token: _,
+            }) => {
+                if only {
+                    return not_impl_err!("ALTER TABLE ONLY is not supported");
+                }
+                if location.is_some() {
+                    return not_impl_err!("ALTER TABLE SET LOCATION is not supported");
+                }
+                if on_cluster.is_some() {
+                    return not_impl_err!("ALTER TABLE ON CLUSTER is not supported");
+                }
+                if let Some(table_type) = table_type {
+                    return not_impl_err!("ALTER {table_type:?} TABLE is not supported");
+                }
+                let name = self.object_name_to_table_reference(name)?;
+                let operations = operations
+                    .into_iter()
+                    .map(|operation| {
+                        self.alter_table_operation_to_plan(operation, planner_context)
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                Ok(LogicalPlan::Ddl(DdlStatement::AlterTable(PlanAlterTable {
+                    name,
+                    if_exists,
+                    operations,
+                    schema: DFSchemaRef::new(DFSchema::empty()),
+                })))
+            }
             stmt => {
                 not_impl_err!("Unsupported SQL statement: {stmt}")
             }
         }
     }
 
+    /// Convert a single `ALTER TABLE` operation. `DROP COLUMN a, b` expands
+    /// to one operation per column.
+    fn alter_table_operation_to_plan(
+        &self,
+        operation: ast::AlterTableOperation,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Vec<AlterTableOperation>> {
+        match operation {
+            ast::AlterTableOperation::AddColumn {
+                column_keyword: _,
+                if_not_exists,
+                column_def,
+                column_position,
+            } => {
+                if column_position.is_some() {
+                    return not_impl_err!(
+                        "ALTER TABLE ADD COLUMN with FIRST/AFTER is not supported"
+                    );
+                }
+                let columns = vec![column_def];
+                let default = self
+                    .build_column_defaults(&columns, planner_context)?
+                    .pop()
+                    .map(|(_, expr)| expr);
+                let schema = self.build_schema(columns)?;
+                Ok(vec![AlterTableOperation::AddColumn {
+                    field: Arc::clone(&schema.fields()[0]),
+                    default,
+                    if_not_exists,
+                }])
+            }
+            ast::AlterTableOperation::DropColumn {
+                has_column_keyword: _,
+                column_names,
+                if_exists,
+                drop_behavior,
+            } => {
+                if drop_behavior.is_some() {
+                    return not_impl_err!(
+                        "ALTER TABLE DROP COLUMN with CASCADE/RESTRICT is not supported"
+                    );
+                }
+                Ok(column_names
+                    .into_iter()
+                    .map(|name| AlterTableOperation::DropColumn {
+                        name: self.ident_normalizer.normalize(name),
+                        if_exists,
+                    })
+                    .collect())
+            }
+            ast::AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => Ok(vec![AlterTableOperation::RenameColumn {
+                old_name: self.ident_normalizer.normalize(old_column_name),
+                new_name: self.ident_normalizer.normalize(new_column_name),
+            }]),
+            ast::AlterTableOperation::RenameTable { table_name } => {
+                let (ast::RenameTableNameKind::As(new_name)
+                | ast::RenameTableNameKind::To(new_name)) = table_name;
+                Ok(vec![AlterTableOperation::RenameTable {
+                    new_name: self.object_name_to_table_reference(new_name)?,
+                }])
+            }
+            operation => {
+                not_impl_err!("Unsupported ALTER TABLE operation: {operation}")
+            }
+        }
+    }
+
     fn get_delete_target(&self, from: FromTable) -> Result<ObjectName> {
         let mut from = match from {
             FromTable::WithFromKeyword(v) => v,
@@ -1807,6 +2014,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             file_type,
             location,
             table_partition_cols,
+            bucket_spec,
             if_not_exists,
             temporary,
             order_exprs,
@@ -1858,6 +2066,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             Box::new(
                 PlanCreateExternalTable::builder(name, location, file_type, df_schema)
                     .with_partition_cols(table_partition_cols)
+                    .with_bucket_spec(bucket_spec)
                     .with_if_not_exists(if_not_exists)
                     .with_or_replace(or_replace)
                     .with_temporary(temporary)
@@ -2220,6 +2429,48 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }
     }
 
+    fn refresh_materialized_view_to_plan(
+        &self,
+        statement: RefreshMaterializedViewStatement,
+    ) -> Result<LogicalPlan> {
+        Ok(LogicalPlan::Ddl(DdlStatement::RefreshMaterializedView(
+            RefreshMaterializedView {
+                name: self.object_name_to_table_reference(statement.name)?,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            },
+        )))
+    }
+
+    fn analyze_table_to_plan(
+        &self,
+        statement: AnalyzeTableStatement,
+    ) -> Result<LogicalPlan> {
+        let AnalyzeTableStatement {
+            table_name,
+            columns,
+        } = statement;
+        let name = self.object_name_to_table_reference(table_name)?;
+        let table_schema = self
+            .context_provider
+            .get_table_source(name.clone())?
+            .schema();
+        let columns = columns
+            .into_iter()
+            .map(|ident| {
+                let column = self.ident_normalizer.normalize(ident);
+                if table_schema.column_with_name(&column).is_none() {
+                    return plan_err!("Column '{column}' not found in table '{name}'");
+                }
+                Ok(column)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(LogicalPlan::Ddl(DdlStatement::AnalyzeTable(AnalyzeTable {
+            name,
+            columns,
+            schema: DFSchemaRef::new(DFSchema::empty()),
+        })))
+    }
+
     fn reset_statement_to_plan(&self, statement: ResetStatement) -> Result<LogicalPlan> {
         match statement {
             ResetStatement::Variable(variable) => {
@@ -2407,6 +2658,326 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         Ok(plan)
     }
 
+    /// Plan `MERGE INTO <table> USING <source> ON <on> <clauses>` as a join of
+    /// the target table with the source, projected to the new values of the
+    /// target columns and the [`MergeAction`] of each row, see
+    /// [`WriteOp::Merge`].
+    ///
+    /// The `WHEN` clauses are evaluated in order, the first one that applies
+    /// to a row determines its action.
+    fn merge_to_plan(
+        &self,
+        table: TableFactor,
+        source: TableFactor,
+        on: SQLExpr,
+        clauses: Vec<MergeClause>,
+    ) -> Result<LogicalPlan> {
+        if clauses.is_empty() {
+            return plan_err!("MERGE requires at least one WHEN clause");
+        }
+        let table_name = match &table {
+            TableFactor::Table { name, .. } => name.clone(),
+            _ => plan_err!("Cannot merge into non-table relation!")?,
+        };
+
+        // Do a table lookup to verify the table exists
+        let table_name = self.object_name_to_table_reference(table_name)?;
+        let table_source = self.context_provider.get_table_source(table_name.clone())?;
+        let table_schema = table_source.schema();
+
+        // Mark the rows of both sides, to tell the rows of the join that only
+        // come from one side apart. The rows of the target are numbered, to
+        // find the target rows that match more than one source row.
+        const TARGET_ROW: &str = "__merge_target_row";
+        const SOURCE_ROW: &str = "__merge_source_row";
+        let mut planner_context = PlannerContext::new();
+        let mut plan_with_marker = |relation, marker: Expr, name: &str| {
+            let plan = self.plan_from_tables(
+                vec![TableWithJoins {
+                    relation,
+                    joins: vec![],
+                }],
+                &mut planner_context,
+            )?;
+            let mut exprs: Vec<Expr> = plan
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .collect();
+            let plan = if let Expr::WindowFunction(_) = &marker {
+                exprs.push(
+                    Expr::Column(Column::from_name(marker.schema_name().to_string()))
+                        .alias(name),
+                );
+                LogicalPlanBuilder::window_plan(plan, vec![marker])?
+            } else {
+                exprs.push(marker.alias(name));
+                plan
+            };
+            project(plan, exprs)
+        };
+        let row_number = self
+            .context_provider
+            .get_window_meta("row_number")
+            .ok_or_else(|| {
+                plan_datafusion_err!("MERGE requires the row_number window function")
+            })?;
+        let row_number = Expr::from(WindowFunction::new(
+            WindowFunctionDefinition::WindowUDF(row_number),
+            vec![],
+        ));
+        let target = plan_with_marker(table, row_number, TARGET_ROW)?;
+        let source = plan_with_marker(source, lit(true), SOURCE_ROW)?;
+        let join_schema = target.schema().join(source.schema())?;
+        let target_columns: Vec<Expr> = target
+            .schema()
+            .columns()
+            .into_iter()
+            .take(table_schema.fields().len())
+            .map(Expr::Column)
+            .collect();
+
+        // The condition, action and new values (by column index) of each clause
+        let mut planned_clauses = Vec::with_capacity(clauses.len());
+        for MergeClause {
+            clause_kind,
+            predicate,
+            action,
+            ..
+        } in clauses
+        {
+            let (condition, schema) = match clause_kind {
+                MergeClauseKind::Matched => (
+                    col(TARGET_ROW)
+                        .is_not_null()
+                        .and(col(SOURCE_ROW).is_not_null()),
+                    &join_schema,
+                ),
+                MergeClauseKind::NotMatched | MergeClauseKind::NotMatchedByTarget => {
+                    (col(TARGET_ROW).is_null(), source.schema().as_ref())
+                }
+                MergeClauseKind::NotMatchedBySource => {
+                    (col(SOURCE_ROW).is_null(), target.schema().as_ref())
+                }
+            };
+            let condition = match predicate {
+                Some(predicate) => condition.and(self.sql_to_expr(
+                    predicate,
+                    schema,
+                    &mut planner_context,
+                )?),
+                None => condition,
+            };
+
+            let is_insert_clause = matches!(
+                clause_kind,
+                MergeClauseKind::NotMatched | MergeClauseKind::NotMatchedByTarget
+            );
+            let (action, values) = match action {
+                SQLMergeAction::Update(update) if !is_insert_clause => {
+                    if update.update_predicate.is_some()
+                        || update.delete_predicate.is_some()
+                    {
+                        return not_impl_err!(
+                            "MERGE UPDATE with WHERE or DELETE WHERE is not supported"
+                        );
+                    }
+                    let mut values = HashMap::with_capacity(update.assignments.len());
+                    for assign in update.assignments {
+                        let AssignmentTarget::ColumnName(cols) = assign.target else {
+                            return plan_err!("Tuples are not supported");
+                        };
+                        let col_name = cols
+                            .0
+                            .last()
+                            .and_then(|part| part.as_ident())
+                            .ok_or_else(|| plan_datafusion_err!("Empty column id"))?;
+                        let col_name = self.ident_normalizer.normalize(col_name.clone());
+                        let index = table_schema.index_of(&col_name)?;
+                        let value =
+                            self.sql_to_expr(assign.value, schema, &mut planner_context)?;
+                        values.insert(index, value);
+                    }
+                    (MergeAction::Update, values)
+                }
+                SQLMergeAction::Delete { .. } if !is_insert_clause => {
+                    (MergeAction::Delete, HashMap::new())
+                }
+                SQLMergeAction::Insert(insert) if is_insert_clause => {
+                    if insert.insert_predicate.is_some() {
+                        return not_impl_err!("MERGE INSERT with WHERE is not supported");
+                    }
+                    let MergeInsertKind::Values(values) = insert.kind else {
+                        return not_impl_err!("MERGE INSERT ROW is not supported");
+                    };
+                    let Ok([row]) = <[_; 1]>::try_from(values.rows) else {
+                        return plan_err!("MERGE INSERT expects a single row of values");
+                    };
+                    let indices = if insert.columns.is_empty() {
+                        (0..table_schema.fields().len()).collect()
+                    } else {
+                        insert
+                            .columns
+                            .into_iter()
+                            .map(|name| {
+                                let [part] = name.0.as_slice() else {
+                                    return not_impl_err!(
+                                        "Multi-part column names in MERGE INSERT not supported: {name}"
+                                    );
+                                };
+                                let ident = part.as_ident().ok_or_else(|| {
+                                    plan_datafusion_err!(
+                                        "Expected identifier in MERGE INSERT, got {part}"
+                                    )
+                                })?;
+                                Ok(table_schema
+                                    .index_of(&self.ident_normalizer.normalize(ident.clone()))?)
+                            })
+                            .collect::<Result<Vec<_>>>()?
+                    };
+                    if indices.len() != row.content.len() {
+                        plan_err!("Column count doesn't match insert query!")?;
+                    }
+                    let mut values = indices
+                        .into_iter()
+                        .zip(row.content)
+                        .map(|(index, value)| {
+                            Ok((
+                                index,
+                                self.sql_to_expr(value, schema, &mut planner_context)?,
+                            ))
+                        })
+                        .collect::<Result<HashMap<_, _>>>()?;
+                    // Fill in the default value of the columns that are not specified
+                    for (index, field) in table_schema.fields().iter().enumerate() {
+                        values.entry(index).or_insert_with(|| {
+                            table_source
+                                .get_column_default(field.name())
+                                .cloned()
+                                .unwrap_or(Expr::Literal(ScalarValue::Null, None))
+                        });
+                    }
+                    (MergeAction::Insert, values)
+                }
+                action => {
+                    return plan_err!(
+                        "Invalid MERGE action for WHEN {clause_kind}: {action}"
+                    );
+                }
+            };
+            planned_clauses.push((condition, action, values));
+        }
+
+        // A full join is only needed for the source rows that are inserted,
+        // rows of the target are always kept
+        let join_type = if planned_clauses
+            .iter()
+            .any(|(_, action, _)| *action == MergeAction::Insert)
+        {
+            JoinType::Full
+        } else {
+            JoinType::Left
+        };
+        let on = self.sql_to_expr(on, &join_schema, &mut planner_context)?;
+        let mut plan = LogicalPlanBuilder::from(target)
+            .join_on(source, join_type, Some(on))?
+            .build()?;
+
+        // Drop the source rows that are not inserted, unless an insert clause
+        // applies to all of them
+        let target_row_missing = col(TARGET_ROW).is_null();
+        if join_type == JoinType::Full
+            && !planned_clauses
+                .iter()
+                .any(|(condition, ..)| *condition == target_row_missing)
+        {
+            let predicate = planned_clauses
+                .iter()
+                .filter(|(_, action, _)| *action == MergeAction::Insert)
+                .fold(
+                    col(TARGET_ROW).is_not_null(),
+                    |predicate, (condition, ..)| predicate.or(condition.clone()),
+                );
+            plan = LogicalPlanBuilder::from(plan).filter(predicate)?.build()?;
+        }
+
+        // Count the rows of the join of each target row, which is an error if
+        // it matched more than one source row. The source rows that do not
+        // match any target row are not counted.
+        let count = self
+            .context_provider
+            .get_aggregate_meta("count")
+            .ok_or_else(|| {
+                plan_datafusion_err!("MERGE requires the count aggregate function")
+            })?;
+        let matches = Expr::from(WindowFunction::new(
+            WindowFunctionDefinition::AggregateUDF(count),
+            vec![col(TARGET_ROW)],
+        ))
+        .partition_by(vec![col(TARGET_ROW)])
+        .build()?;
+        let matches_column =
+            Expr::Column(Column::from_name(matches.schema_name().to_string()));
+        let plan = LogicalPlanBuilder::window_plan(plan, vec![matches])?;
+
+        // Build the new value of each column, keeping the current value if
+        // no clause modifies it
+        let mut exprs = target_columns
+            .into_iter()
+            .zip(table_schema.fields())
+            .enumerate()
+            .map(|(index, (current, field))| {
+                let mut when_then_expr = Vec::with_capacity(planned_clauses.len());
+                for (condition, _, values) in &planned_clauses {
+                    let value = match values.get(&index) {
+                        Some(value) => {
+                            let mut value = value.clone();
+                            // Update placeholder's datatype to the type of the target column
+                            if let Expr::Placeholder(placeholder) = &mut value {
+                                placeholder.field = placeholder
+                                    .field
+                                    .take()
+                                    .or_else(|| Some(Arc::clone(field)));
+                            }
+                            // Cast to target column type, if necessary
+                            value.cast_to(field.data_type(), plan.schema())?
+                        }
+                        None => current.clone(),
+                    };
+                    when_then_expr.push((Box::new(condition.clone()), Box::new(value)));
+                }
+                let expr = if when_then_expr.iter().all(|(_, value)| **value == current) {
+                    current
+                } else {
+                    Expr::Case(Case::new(None, when_then_expr, Some(Box::new(current))))
+                };
+                Ok(expr.alias(field.name()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let when_then_expr = planned_clauses
+            .into_iter()
+            .map(|(condition, action, _)| {
+                (Box::new(condition), Box::new(lit(action.name())))
+            })
+            .collect();
+        let action = Expr::Case(Case::new(None, when_then_expr, None));
+        exprs.push(
+            merge_cardinality_check()
+                .call(vec![matches_column, action])
+                .alias(MERGE_ACTION_COLUMN),
+        );
+        let source = project(plan, exprs)?;
+
+        let plan = LogicalPlan::Dml(DmlStatement::new(
+            table_name,
+            table_source,
+            WriteOp::Merge,
+            Arc::new(source),
+        ));
+        Ok(plan)
+    }
+
     fn insert_to_plan(
         &self,
         table_name: ObjectName,
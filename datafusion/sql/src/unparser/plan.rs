@@ -135,7 +135,11 @@ impl Unparser<'_> {
             | LogicalPlan::Copy(_)
             | LogicalPlan::DescribeTable(_)
             | LogicalPlan::RecursiveQuery(_)
-            | LogicalPlan::Unnest(_) => not_impl_err!("Unsupported plan: {plan:?}"),
+            | LogicalPlan::Unnest(_)
+            | LogicalPlan::MatchRecognize(_)
+            | LogicalPlan::Sample(_) => {
+                not_impl_err!("Unsupported plan: {plan:?}")
+            }
         }
     }
 
@@ -1193,6 +1197,12 @@ impl Unparser<'_> {
                     &mut right_relation,
                 )?;
 
+                if join.join_type == JoinType::AsOf && !table_scan_filters.is_empty() {
+                    return not_impl_err!(
+                        "Unsupported as-of join with table scan filters: {join:?}"
+                    );
+                }
+
                 let (join_filters, where_filters) = Self::split_join_on_and_where_filters(
                     join.join_type,
                     &join.filter,
@@ -1203,10 +1213,14 @@ impl Unparser<'_> {
                     select.selection(Some(filter_expr));
                 }
 
+                // The match condition of an as-of join is not part of its
+                // ON clause, it is unparsed as `MATCH_CONDITION (..)` instead
                 let join_constraint = self.join_constraint_to_sql(
                     join.join_constraint,
                     &join.on,
-                    join_filters.as_ref(),
+                    join_filters
+                        .as_ref()
+                        .filter(|_| join.join_type != JoinType::AsOf),
                 )?;
 
                 let right_projection: Option<Vec<ast::SelectItem>> = if !already_projected
@@ -1282,15 +1296,28 @@ impl Unparser<'_> {
                     JoinType::Inner
                     | JoinType::Left
                     | JoinType::Right
-                    | JoinType::Full => {
+                    | JoinType::Full
+                    | JoinType::AsOf => {
                         let Ok(Some(relation)) = right_relation.build() else {
                             return internal_err!("Failed to build right relation");
                         };
+                        let join_operator = if join.join_type == JoinType::AsOf {
+                            let Some(match_condition) = &join_filters else {
+                                return internal_err!(
+                                    "As-of join is missing its match condition"
+                                );
+                            };
+                            ast::JoinOperator::AsOf {
+                                match_condition: self.expr_to_sql(match_condition)?,
+                                constraint: join_constraint,
+                            }
+                        } else {
+                            self.join_operator_to_sql(join.join_type, join_constraint)?
+                        };
                         let ast_join = ast::Join {
                             relation,
                             global: false,
-                            join_operator: self
-                                .join_operator_to_sql(join.join_type, join_constraint)?,
+                            join_operator,
                         };
                         let mut from = select.pop_from().unwrap();
                         from.push_join(ast_join);
@@ -2128,6 +2155,11 @@ impl Unparser<'_> {
             JoinType::LeftMark | JoinType::RightMark => {
                 unimplemented!("Unparsing of Mark join type")
             }
+            JoinType::AsOf => {
+                return internal_err!(
+                    "As-of joins must be unparsed together with their match condition"
+                );
+            }
         })
     }
 
@@ -564,6 +564,16 @@ fn plan_start_transaction() {
     );
 }
 
+#[test]
+fn plan_begin_transaction() {
+    let sql = "begin";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @"TransactionStart: ReadWrite Serializable"
+    );
+}
+
 #[test]
 fn plan_start_transaction_isolation() {
     let sql = "start transaction isolation level read committed";
@@ -71,8 +71,12 @@ pub fn to_substrait_rel(
             not_impl_err!("Unsupported plan type: {plan:?}")?
         }
         LogicalPlan::Unnest(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
+        LogicalPlan::Sample(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
         LogicalPlan::RecursiveQuery(plan) => {
             not_impl_err!("Unsupported plan type: {plan:?}")?
         }
+        LogicalPlan::MatchRecognize(plan) => {
+            not_impl_err!("Unsupported plan type: {plan:?}")?
+        }
     }
 }